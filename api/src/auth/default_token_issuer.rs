@@ -1,13 +1,37 @@
+use crate::auth::jwks::{Jwk, JwkSet};
+use crate::errors::{ApiError, ApplicationError, ApplicationErrorType};
 use db::models::{AccessToken, Scopes, TokenIssuer};
 
 use chrono::Duration;
-use jwt::{decode, encode, errors, Header, TokenData, Validation};
+use itertools::Itertools;
+use jwt::{decode, decode_header, encode, errors, Algorithm, Header, TokenData, Validation};
+use serde::Serialize;
+use std::str::FromStr;
 use uuid::Uuid;
 
+/// Private key used to sign new tokens, identified in the token header by its `kid`
+#[derive(Clone)]
+pub struct TokenSigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub private_key: Vec<u8>,
+}
+
+/// Public key accepted when verifying tokens. Retired signing keys stay in this list
+/// until the tokens they signed have expired.
+#[derive(Clone)]
+pub struct TokenVerificationKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub public_key: Vec<u8>,
+}
+
 #[derive(Clone)]
 pub struct DefaultTokenIssuer {
     pub token_secret: String,
     pub token_issuer: String,
+    pub signing_key: Option<TokenSigningKey>,
+    pub verification_keys: Vec<TokenVerificationKey>,
 }
 
 impl DefaultTokenIssuer {
@@ -15,22 +39,92 @@ impl DefaultTokenIssuer {
         DefaultTokenIssuer {
             token_secret,
             token_issuer,
+            signing_key: None,
+            verification_keys: Vec::new(),
         }
     }
+
+    pub fn with_signing_keys(
+        mut self,
+        signing_key: TokenSigningKey,
+        verification_keys: Vec<TokenVerificationKey>,
+    ) -> Result<Self, ApiError> {
+        if !verification_keys.iter().any(|k| k.kid == signing_key.kid) {
+            return Err(ApplicationError::new_with_type(
+                ApplicationErrorType::ServerConfigError,
+                format!("No verification key configured for signing key '{}'", signing_key.kid),
+            )
+            .into());
+        }
+        self.signing_key = Some(signing_key);
+        self.verification_keys = verification_keys;
+        Ok(self)
+    }
+
+    pub fn decode_with_validation(
+        &self,
+        access_token: &str,
+        mut validation: Validation,
+    ) -> Result<TokenData<AccessToken>, errors::Error> {
+        let header = decode_header(access_token)?;
+        match header.kid {
+            Some(kid) => {
+                let verification_key = self
+                    .verification_keys
+                    .iter()
+                    .find(|k| k.kid == kid)
+                    .ok_or_else(|| errors::Error::from(errors::ErrorKind::InvalidToken))?;
+                validation.algorithms = vec![verification_key.algorithm];
+                decode::<AccessToken>(access_token, &verification_key.public_key, &validation)
+            }
+            // Tokens signed with the shared secret carry no key id, they are only accepted
+            // while no signing keys are configured
+            None => {
+                if self.signing_key.is_some() {
+                    return Err(errors::ErrorKind::InvalidToken.into());
+                }
+                validation.algorithms = vec![Algorithm::HS256];
+                decode::<AccessToken>(access_token, self.token_secret.as_bytes(), &validation)
+            }
+        }
+    }
+
+    /// Signs `claims` with the signing key when one is configured so they can be verified against
+    /// the published `jwks`, otherwise with the shared secret
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, errors::Error> {
+        match self.signing_key {
+            Some(ref signing_key) => {
+                let mut header = Header::new(signing_key.algorithm);
+                header.kid = Some(signing_key.kid.clone());
+                encode(&header, claims, &signing_key.private_key)
+            }
+            None => encode(&Header::default(), claims, self.token_secret.as_bytes()),
+        }
+    }
+
+    pub fn jwks(&self) -> Result<JwkSet, ApiError> {
+        Ok(JwkSet {
+            keys: self
+                .verification_keys
+                .iter()
+                .map(|k| Jwk::from_verification_key(k))
+                .collect::<Result<Vec<Jwk>, ApiError>>()?,
+        })
+    }
 }
 
 impl TokenIssuer for DefaultTokenIssuer {
     fn encode(&self, claims: &AccessToken) -> Result<String, errors::Error> {
-        encode(&Header::default(), claims, self.token_secret.as_bytes())
+        self.sign(claims)
     }
     fn decode(&self, access_token: &str) -> Result<TokenData<AccessToken>, jwt::errors::Error> {
-        decode::<AccessToken>(access_token, self.token_secret.as_bytes(), &Validation::default())
+        self.decode_with_validation(access_token, Validation::default())
     }
 
     fn issue(&self, user_id: Uuid, expires: Duration) -> Result<String, errors::Error> {
         let access_token_claims = AccessToken::new(user_id, self.token_issuer.to_string(), expires.num_minutes());
 
-        encode(&Header::default(), &access_token_claims, self.token_secret.as_bytes())
+        self.encode(&access_token_claims)
     }
 
     fn issue_with_limited_scopes(
@@ -41,6 +135,71 @@ impl TokenIssuer for DefaultTokenIssuer {
     ) -> Result<String, errors::Error> {
         let access_token_claims =
             AccessToken::new_limited_scope(user_id, self.token_issuer.to_string(), expires.num_minutes(), scopes);
-        encode(&Header::default(), &access_token_claims, self.token_secret.as_bytes())
+        self.encode(&access_token_claims)
     }
+
+    fn issue_refresh_token(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        token_id: Uuid,
+        expires: Duration,
+    ) -> Result<String, errors::Error> {
+        let access_token_claims = AccessToken::new_refresh_token(
+            user_id,
+            self.token_issuer.to_string(),
+            expires.num_minutes(),
+            session_id,
+            token_id,
+        );
+        self.encode(&access_token_claims)
+    }
+}
+
+impl FromStr for TokenSigningKey {
+    type Err = ApiError;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let (kid, algorithm, private_key) = parse_key(val)?;
+        Ok(TokenSigningKey {
+            kid,
+            algorithm,
+            private_key,
+        })
+    }
+}
+
+impl FromStr for TokenVerificationKey {
+    type Err = ApiError;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let (kid, algorithm, public_key) = parse_key(val)?;
+        Ok(TokenVerificationKey {
+            kid,
+            algorithm,
+            public_key,
+        })
+    }
+}
+
+fn parse_key(val: &str) -> Result<(String, Algorithm, Vec<u8>), ApiError> {
+    let format_error = || {
+        ApplicationError::new_with_type(
+            ApplicationErrorType::ServerConfigError,
+            "Token key was not in the correct format: '<kid>:<RS256|ES256>:<base64 DER key>'".to_string(),
+        )
+    };
+    let split = val.splitn(3, ':').collect_vec();
+    if split.len() < 3 || split[0].is_empty() {
+        return Err(format_error().into());
+    }
+
+    let algorithm = match split[1] {
+        "RS256" => Algorithm::RS256,
+        "ES256" => Algorithm::ES256,
+        _ => return Err(format_error().into()),
+    };
+    let key = base64::decode(split[2].trim()).map_err(|_| format_error())?;
+
+    Ok((split[0].to_string(), algorithm, key))
 }
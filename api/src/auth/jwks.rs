@@ -0,0 +1,190 @@
+use crate::auth::default_token_issuer::TokenVerificationKey;
+use crate::errors::{ApiError, ApplicationError};
+use jwt::Algorithm;
+
+/// JSON Web Key as described in RFC 7517, published so other services can verify tokens
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: String,
+    pub alg: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl Jwk {
+    pub fn from_verification_key(key: &TokenVerificationKey) -> Result<Jwk, ApiError> {
+        let mut jwk = Jwk {
+            kty: "".to_string(),
+            kid: key.kid.clone(),
+            alg: format!("{:?}", key.algorithm),
+            key_use: "sig".to_string(),
+            n: None,
+            e: None,
+            crv: None,
+            x: None,
+            y: None,
+        };
+
+        match key.algorithm {
+            Algorithm::RS256 => {
+                let (modulus, exponent) = parse_rsa_public_key(&key.public_key).ok_or_else(|| {
+                    ApplicationError::new(format!("Verification key '{}' is not a DER encoded RSA key", key.kid))
+                })?;
+                jwk.kty = "RSA".to_string();
+                jwk.n = Some(encode_base64_url(modulus));
+                jwk.e = Some(encode_base64_url(exponent));
+            }
+            Algorithm::ES256 => {
+                // Uncompressed P-256 point: 0x04 followed by the 32 byte x and y coordinates
+                if key.public_key.len() != 65 || key.public_key[0] != 0x04 {
+                    return Err(ApplicationError::new(format!(
+                        "Verification key '{}' is not an uncompressed P-256 point",
+                        key.kid
+                    ))
+                    .into());
+                }
+                jwk.kty = "EC".to_string();
+                jwk.crv = Some("P-256".to_string());
+                jwk.x = Some(encode_base64_url(&key.public_key[1..33]));
+                jwk.y = Some(encode_base64_url(&key.public_key[33..65]));
+            }
+            _ => {
+                return Err(ApplicationError::new(format!(
+                    "Verification key '{}' uses an algorithm that cannot be published",
+                    key.kid
+                ))
+                .into());
+            }
+        }
+
+        Ok(jwk)
+    }
+
+    /// Key for verifying tokens signed by another issuer, such as an OpenID Connect provider
+    pub fn to_verification_key(&self) -> Result<TokenVerificationKey, ApiError> {
+        let invalid_key = || ApplicationError::new(format!("JSON web key '{}' is not a supported key", self.kid));
+        let decode = |value: &Option<String>| {
+            value
+                .as_ref()
+                .and_then(|v| base64::decode_config(v, base64::URL_SAFE_NO_PAD).ok())
+                .ok_or_else(invalid_key)
+        };
+
+        let (algorithm, public_key) = match (self.kty.as_str(), self.alg.as_str()) {
+            ("RSA", "RS256") => (
+                Algorithm::RS256,
+                encode_rsa_public_key(&decode(&self.n)?, &decode(&self.e)?),
+            ),
+            ("EC", "ES256") if self.crv.as_ref().map(|c| c.as_str()) == Some("P-256") => {
+                let mut point = vec![0x04];
+                point.extend(decode(&self.x)?);
+                point.extend(decode(&self.y)?);
+                (Algorithm::ES256, point)
+            }
+            _ => return Err(invalid_key().into()),
+        };
+
+        Ok(TokenVerificationKey {
+            kid: self.kid.clone(),
+            algorithm,
+            public_key,
+        })
+    }
+}
+
+fn encode_base64_url(value: &[u8]) -> String {
+    base64::encode_config(value, base64::URL_SAFE_NO_PAD)
+}
+
+/// Reads the modulus and exponent out of a PKCS#1 `RSAPublicKey` structure
+fn parse_rsa_public_key(der: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (sequence, _) = read_der_element(der, 0x30)?;
+    let (modulus, remaining) = read_der_element(sequence, 0x02)?;
+    let (exponent, _) = read_der_element(remaining, 0x02)?;
+    Some((trim_leading_zeros(modulus), trim_leading_zeros(exponent)))
+}
+
+/// Writes a PKCS#1 `RSAPublicKey` structure, the reverse of `parse_rsa_public_key`
+fn encode_rsa_public_key(modulus: &[u8], exponent: &[u8]) -> Vec<u8> {
+    let mut sequence = encode_der_integer(modulus);
+    sequence.extend(encode_der_integer(exponent));
+    encode_der_element(0x30, &sequence)
+}
+
+fn encode_der_integer(value: &[u8]) -> Vec<u8> {
+    let value = trim_leading_zeros(value);
+    // Integers are signed so a leading zero keeps values with the high bit set positive
+    let mut contents = Vec::with_capacity(value.len() + 1);
+    if value.is_empty() || value[0] & 0x80 != 0 {
+        contents.push(0);
+    }
+    contents.extend_from_slice(value);
+    encode_der_element(0x02, &contents)
+}
+
+fn encode_der_element(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut element = vec![tag];
+    if contents.len() < 0x80 {
+        element.push(contents.len() as u8);
+    } else {
+        let length_bytes: Vec<u8> = (contents.len() as u32)
+            .to_be_bytes()
+            .iter()
+            .skip_while(|b| **b == 0)
+            .cloned()
+            .collect();
+        element.push(0x80 | length_bytes.len() as u8);
+        element.extend(length_bytes);
+    }
+    element.extend_from_slice(contents);
+    element
+}
+
+/// Returns the contents of the DER element at the start of `input` and the bytes following it
+fn read_der_element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    if input.len() < 2 || input[0] != tag {
+        return None;
+    }
+
+    let (length, header_length) = if input[1] < 0x80 {
+        (input[1] as usize, 2)
+    } else {
+        let length_bytes = (input[1] & 0x7f) as usize;
+        if length_bytes == 0 || length_bytes > 4 || input.len() < 2 + length_bytes {
+            return None;
+        }
+        let length = input[2..2 + length_bytes]
+            .iter()
+            .fold(0usize, |length, b| (length << 8) | *b as usize);
+        (length, 2 + length_bytes)
+    };
+
+    if input.len() < header_length + length {
+        return None;
+    }
+    Some((
+        &input[header_length..header_length + length],
+        &input[header_length + length..],
+    ))
+}
+
+fn trim_leading_zeros(value: &[u8]) -> &[u8] {
+    let first_non_zero = value.iter().position(|b| *b != 0).unwrap_or(value.len());
+    &value[first_non_zero..]
+}
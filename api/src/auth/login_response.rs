@@ -0,0 +1,71 @@
+use crate::auth::TokenResponse;
+use crate::errors::ApiError;
+use crate::models::RequestInfo;
+use actix_web::Error;
+use actix_web::HttpRequest;
+use actix_web::HttpResponse;
+use actix_web::Responder;
+use chrono::Duration;
+use db::models::{Scopes, TokenIssuer, User};
+use diesel::PgConnection;
+use futures::future::{err, ok, Ready};
+use serde_json;
+
+/// Minutes a user has to answer the second factor challenge after a successful password check
+const MFA_CHALLENGE_EXPIRY_MINUTES: i64 = 5;
+
+#[derive(Serialize, Deserialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Token(TokenResponse),
+    MfaChallenge(MfaChallengeResponse),
+}
+
+impl Responder for LoginResponse {
+    type Future = Ready<Result<HttpResponse, Error>>;
+    type Error = Error;
+
+    fn respond_to(self, _req: &HttpRequest) -> Self::Future {
+        match serde_json::to_string(&self) {
+            Ok(body) => ok(HttpResponse::Ok().content_type("application/json").body(body)),
+            Err(e) => err(e.into()),
+        }
+    }
+}
+
+impl LoginResponse {
+    /// Users with multi-factor authentication enabled receive a short lived challenge token
+    /// which is exchanged for the access and refresh tokens once the second factor is verified
+    pub fn create_from_user(
+        token_issuer: &dyn TokenIssuer,
+        expires: Duration,
+        user: &User,
+        request_info: &RequestInfo,
+        conn: &PgConnection,
+    ) -> Result<Self, ApiError> {
+        if user.mfa_enabled() {
+            return Ok(LoginResponse::MfaChallenge(MfaChallengeResponse {
+                mfa_required: true,
+                mfa_token: token_issuer.issue_with_limited_scopes(
+                    user.id,
+                    vec![Scopes::MfaChallenge],
+                    Duration::minutes(MFA_CHALLENGE_EXPIRY_MINUTES),
+                )?,
+            }));
+        }
+
+        Ok(LoginResponse::Token(TokenResponse::create_from_user(
+            token_issuer,
+            expires,
+            user,
+            request_info,
+            conn,
+        )?))
+    }
+}
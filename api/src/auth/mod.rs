@@ -1,5 +1,8 @@
+pub use self::login_response::{LoginResponse, MfaChallengeResponse};
 pub use self::token_response::TokenResponse;
 
 pub mod default_token_issuer;
+pub mod jwks;
+pub mod login_response;
 pub mod token_response;
 pub mod user;
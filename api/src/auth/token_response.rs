@@ -1,13 +1,14 @@
 use crate::errors::ApiError;
+use crate::models::RequestInfo;
 use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use actix_web::Responder;
 use chrono::Duration;
-use db::models::{Scopes, TokenIssuer, User};
+use db::models::{TokenIssuer, User, UserSession};
+use diesel::PgConnection;
 use futures::future::{err, ok, Ready};
 use serde_json;
-use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 pub struct TokenResponse {
@@ -35,21 +36,31 @@ impl TokenResponse {
         }
     }
 
-    pub fn create_from_user(token_issuer: &dyn TokenIssuer, expires: Duration, user: &User) -> Result<Self, ApiError> {
-        Ok(TokenResponse {
-            access_token: token_issuer.issue(user.id, expires)?,
-            refresh_token: token_issuer.issue_with_limited_scopes(user.id, vec![Scopes::TokenRefresh], expires * 60)?,
-        })
+    /// Starts a new session for the user, the refresh token can only be used once
+    pub fn create_from_user(
+        token_issuer: &dyn TokenIssuer,
+        expires: Duration,
+        user: &User,
+        request_info: &RequestInfo,
+        conn: &PgConnection,
+    ) -> Result<Self, ApiError> {
+        let session = UserSession::create(user.id, request_info.user_agent.clone()).commit(conn)?;
+        TokenResponse::create_from_session(token_issuer, expires, &session)
     }
 
-    pub fn create_from_refresh_token(
+    pub fn create_from_session(
         token_issuer: &dyn TokenIssuer,
         expires: Duration,
-        user_id: Uuid,
+        session: &UserSession,
     ) -> Result<Self, ApiError> {
         Ok(TokenResponse {
-            access_token: token_issuer.issue(user_id, expires)?,
-            refresh_token: token_issuer.issue_with_limited_scopes(user_id, vec![Scopes::TokenRefresh], expires * 60)?,
+            access_token: token_issuer.issue(session.user_id, expires)?,
+            refresh_token: token_issuer.issue_refresh_token(
+                session.user_id,
+                session.id,
+                session.current_token_id,
+                expires * 60,
+            )?,
         })
     }
 }
@@ -2,7 +2,7 @@ use crate::errors::*;
 use crate::extractors::OptionalUser;
 use actix_web::{HttpRequest, Result};
 use db::models::User as DbUser;
-use db::models::{scopes, Event, EventUser, Order, Organization, Roles, Scopes};
+use db::models::{scopes, Event, EventUser, Order, Organization, OrganizationApiKey, Roles, Scopes};
 use db::prelude::errors::EnumParseError;
 use db::prelude::Optional;
 use diesel::PgConnection;
@@ -23,6 +23,8 @@ pub struct User {
     pub method: String,
     pub global_scopes_only: bool,
     pub is_public_user: bool,
+    /// Set when the request authenticated with an organization API key rather than a user token
+    pub api_key: Option<OrganizationApiKey>,
 }
 
 impl User {
@@ -40,6 +42,7 @@ impl User {
             method: request.method().to_string(),
             global_scopes_only: false,
             is_public_user,
+            api_key: None,
         };
         if let Some(scopes) = limited_scopes {
             result.global_scopes = scopes;
@@ -51,6 +54,22 @@ impl User {
         Ok(result)
     }
 
+    /// Requests made with an API key are attributed to the user who created it, but only the
+    /// key's own scopes apply so none of that user's roles are carried over
+    pub fn new_for_api_key(api_key: OrganizationApiKey, mut user: DbUser, request: &HttpRequest) -> User {
+        user.role = vec![];
+        User {
+            user,
+            global_scopes: vec![],
+            ip_address: request.connection_info().remote().map(|i| i.to_string()),
+            uri: request.uri().to_string(),
+            method: request.method().to_string(),
+            global_scopes_only: true,
+            is_public_user: false,
+            api_key: Some(api_key),
+        }
+    }
+
     pub fn id(&self) -> Uuid {
         self.user.id
     }
@@ -67,6 +86,20 @@ impl User {
         connection: Option<&PgConnection>,
         log_on_failure: bool,
     ) -> Result<bool, ApiError> {
+        if let Some(ref api_key) = self.api_key {
+            let allowed = organization
+                .map(|organization| api_key.allows(scope, organization.id, event_id))
+                .unwrap_or(false);
+            if !allowed && log_on_failure {
+                let mut logging_data = HashMap::new();
+                logging_data.insert("accessed_scope", json!(scope.to_string()));
+                logging_data.insert("organization_api_key_id", json!(api_key.id));
+                logging_data.insert("organization_id", json!(organization.map(|o| o.id)));
+                self.log_unauthorized_access_attempt(logging_data);
+            }
+            return Ok(allowed);
+        }
+
         if self.global_scopes_only {
             if self.global_scopes.contains(&scope.to_string()) {
                 return Ok(true);
@@ -97,7 +130,8 @@ impl User {
                 {
                     let event_user = EventUser::find_by_event_id_user_id(event_id, self.id(), connection).optional()?;
                     if let Some(event_user) = event_user {
-                        let scopes = scopes::get_scopes(vec![event_user.role], additional_scopes);
+                        let mut scopes = scopes::get_scopes(vec![event_user.role], additional_scopes);
+                        organization.apply_mfa_policy(&self.user, &mut scopes);
 
                         if scopes.contains(&scope) {
                             return Ok(true);
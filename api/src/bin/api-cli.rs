@@ -24,7 +24,7 @@ pub fn main() {
     let environment = Config::parse_environment().unwrap_or_else(|_| panic!("Environment is invalid."));
     jlog!(Info, &format!("Environment loaded {:?}", environment));
 
-    let config = Config::new(environment);
+    let config = Config::new(environment).unwrap_or_else(|err| panic!("Configuration is invalid: {}", err));
     let service_locator = ServiceLocator::new(&config).expect("Expected service locator to load");
     let database = Database::from_config(&config);
 
@@ -65,6 +65,12 @@ pub fn main() {
       (@arg ADDITIONAL: -a --additional +takes_value "Additional Scopes comma separated")
       (@arg REVOKED: -r --revoked +takes_value "Revoked Scopes comma separated")
       (@arg clear: -c --clear "Clear all scopes"))
+     (@subcommand rotate_wallet_keys =>
+      (name: "rotate-wallet-keys")
+      (about: "Re-encrypts wallet secret keys with the newest WALLET_ENCRYPTION_KEYS version"))
+     (@subcommand rotate_mfa_keys =>
+      (name: "rotate-mfa-keys")
+      (about: "Re-encrypts users' MFA secrets with the newest MFA_ENCRYPTION_KEYS version"))
      (@subcommand version =>
       (name: "version")
       (about: "Get the current version")))
@@ -86,6 +92,8 @@ pub fn main() {
             update_customer_io_webhooks(args.value_of("site_id"), args.value_of("api_key"), database)
         }
         ("additional_scopes", Some(args)) => additional_scopes(database, args),
+        ("rotate-wallet-keys", Some(_)) => rotate_wallet_keys(config, database),
+        ("rotate-mfa-keys", Some(_)) => rotate_mfa_keys(config, database),
         _ => {
             eprintln!("Invalid subcommand '{}'", matches.subcommand().0);
         }
@@ -142,6 +150,26 @@ fn additional_scopes(database: Database, args: &ArgMatches) {
     }
 }
 
+fn rotate_wallet_keys(config: Config, database: Database) {
+    info!("Re-encrypting wallet secret keys");
+    let connection = database.get_connection().expect("Expected connection to establish");
+    let connection = connection.get();
+
+    let updated = Wallet::reencrypt_secret_keys(&config.wallet_encryption_keys, connection)
+        .expect("Expected to re-encrypt wallet secret keys");
+    println!("Re-encrypted {} wallets", updated);
+}
+
+fn rotate_mfa_keys(config: Config, database: Database) {
+    info!("Re-encrypting MFA secrets");
+    let connection = database.get_connection().expect("Expected connection to establish");
+    let connection = connection.get();
+
+    let updated = User::reencrypt_mfa_secrets(&config.mfa_encryption_keys, connection)
+        .expect("Expected to re-encrypt MFA secrets");
+    println!("Re-encrypted {} MFA secrets", updated);
+}
+
 fn generate_genre_slugs(database: Database) {
     info!("Generating genre and city genre slugs");
     let connection = database.get_connection().expect("Expected connection to establish");
@@ -365,45 +393,53 @@ fn sync_spotify_genres(config: Config, database: Database) {
 fn sync_purchase_metadata(database: Database, service_locator: ServiceLocator) {
     info!("Syncing purchase metadata");
     let connection = database.get_connection().expect("Expected connection to establish");
-    let mut i = 0;
-    let mut exit = false;
-
-    loop {
-        if exit {
-            break;
-        }
 
-        let payments =
-            Payment::find_all_with_orders_paginated_by_provider(PaymentProviders::Stripe, i, 50, connection.get())
-                .expect("Expected to find all payments with orders");
-        i += 1;
+    // Metadata is sent to the processor that took the original payment
+    for provider in vec![PaymentProviders::Stripe, PaymentProviders::Braintree] {
+        let mut i = 0;
+        let mut exit = false;
 
-        if payments.len() == 0 {
-            break;
-        }
+        loop {
+            if exit {
+                break;
+            }
 
-        let mut rt = tokio::runtime::Runtime::new().expect("Failed to start async Runtime");
+            let payments = Payment::find_all_with_orders_paginated_by_provider(provider, i, 50, connection.get())
+                .expect("Expected to find all payments with orders");
+            i += 1;
 
-        for (payment, order) in payments {
-            let organizations = order.organizations(connection.get()).unwrap();
+            if payments.len() == 0 {
+                break;
+            }
 
-            let stripe = service_locator
-                .create_payment_processor(PaymentProviders::Stripe, &organizations[0])
-                .expect("Expected Stripe processor");
+            let mut rt = tokio::runtime::Runtime::new().expect("Failed to start async Runtime");
 
-            if let Some(external_reference) = payment.external_reference {
-                let purchase_metadata = order
-                    .purchase_metadata(connection.get())
-                    .expect("Expected purchase metadata for order");
-                let result = rt.block_on(stripe.update_metadata(&external_reference, purchase_metadata));
+            for (payment, order) in payments {
+                let organizations = order.organizations(connection.get()).unwrap();
 
-                match result {
-                    // Sleep to avoid hammering Stripe API
-                    Ok(_) => thread::sleep(time::Duration::from_secs(1)),
-                    Err(error) => {
-                        error!("Error: {}", error);
-                        exit = true;
-                        break;
+                let payment_processor =
+                    match service_locator.create_payment_processor(payment.provider, &organizations[0]) {
+                        Ok(payment_processor) => payment_processor,
+                        Err(error) => {
+                            error!("Error: {}", error);
+                            continue;
+                        }
+                    };
+
+                if let Some(external_reference) = payment.external_reference {
+                    let purchase_metadata = order
+                        .purchase_metadata(connection.get())
+                        .expect("Expected purchase metadata for order");
+                    let result = rt.block_on(payment_processor.update_metadata(&external_reference, purchase_metadata));
+
+                    match result {
+                        // Sleep to avoid hammering the payment processor API
+                        Ok(_) => thread::sleep(time::Duration::from_secs(1)),
+                        Err(error) => {
+                            error!("Error: {}", error);
+                            exit = true;
+                            break;
+                        }
                     }
                 }
             }
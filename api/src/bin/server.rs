@@ -23,7 +23,7 @@ async fn main() {
     let environment = Config::parse_environment().unwrap_or_else(|_| panic!("Environment is invalid."));
     jlog!(Info, &format!("Environment loaded: {:?}", environment));
 
-    let config = Config::new(environment);
+    let config = Config::new(environment).unwrap_or_else(|err| panic!("Configuration is invalid: {}", err));
 
     let matches = App::new("Big Neon API Server")
         .author("Big Neon")
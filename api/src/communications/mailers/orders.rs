@@ -25,6 +25,7 @@ pub fn confirmation_email(
     let mut total_fees = 0;
     let mut total_initial_fees = 0;
     let mut total_refunded_fees = 0;
+    let mut total_tax = 0;
 
     for oi in &display_order.items {
         match oi.item_type {
@@ -56,8 +57,27 @@ pub fn confirmation_email(
                     ));
                 }
             }
+            OrderItemTypes::Products => {
+                item_breakdown.push_str(&generate_item_row(
+                    &oi.description,
+                    oi.quantity,
+                    oi.unit_price_in_cents,
+                    false,
+                ));
+                if oi.refunded_quantity > 0 {
+                    item_breakdown.push_str(&generate_item_row(
+                        "Refunded",
+                        oi.refunded_quantity,
+                        oi.unit_price_in_cents,
+                        true,
+                    ));
+                }
+            }
             // Do nothing, included above with ticket for display
             OrderItemTypes::Discount => (),
+            OrderItemTypes::Tax => {
+                total_tax += (oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents;
+            }
             _ => {
                 //Accumulate fees
                 total_initial_fees += oi.quantity * oi.unit_price_in_cents;
@@ -81,6 +101,12 @@ pub fn confirmation_email(
             format!("{:.*}", 2, total_refunded_fees as f64 / 100.0)
         ));
     }
+    if total_tax > 0 {
+        total_breakdown.push_str(&format!(
+            "<tr><th>Tax Total</th><td>{}</td></tr>",
+            format!("{:.*}", 2, total_tax as f64 / 100.0)
+        ));
+    }
     total_breakdown.push_str(&format!(
         "<tr><th>Order Total</th><td>{}</td></tr>",
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0)
@@ -107,6 +133,7 @@ pub fn confirmation_email(
         format!("{:.*}", 2, total_refunded_fees as f64 / 100.0),
     );
     template_data.insert("total_fees".to_string(), format!("{:.*}", 2, total_fees as f64 / 100.0));
+    template_data.insert("total_tax".to_string(), format!("{:.*}", 2, total_tax as f64 / 100.0));
     template_data.insert(
         "total_price".to_string(),
         format!("{:.*}", 2, display_order.total_in_cents as f64 / 100.0),
@@ -39,3 +39,49 @@ pub fn ticket_counts(
 
     Ok(())
 }
+
+pub fn report_export_ready(
+    config: &Config,
+    user: &User,
+    report_export: &ReportExport,
+    download_token: &str,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let email = match user.email.as_ref() {
+        Some(email) => email,
+        None => return Ok(()),
+    };
+    let download_link = format!(
+        "{}/report_exports/{}/download?token={}",
+        config.api_base_url, report_export.id, download_token
+    );
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email.to_string());
+    let title = format!("{} Report export ready", SITE_NAME);
+    let template_id = config.email_templates.report_export_ready.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("report".to_string(), report_export.report.clone());
+    template_data.insert("download_link".to_string(), download_link);
+    if let Some(expires_at) = report_export.expires_at {
+        template_data.insert(
+            "expires_at".to_string(),
+            expires_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+        );
+    }
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["report_export", "reports"]),
+        None,
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
@@ -14,7 +14,7 @@ pub fn send_tickets(
     from_user: &User,
     conn: &PgConnection,
 ) -> Result<(), ApiError> {
-    let receive_tickets_link = transfer.receive_url(&config.front_end_url, conn)?;
+    let receive_tickets_link = transfer.receive_url(&config.front_end_url, &config.wallet_encryption_keys, conn)?;
     let source = CommAddress::from(config.communication_default_source_email.clone());
     let destinations = CommAddress::from(email);
     let title = "{sender_name} has sent you some tickets".to_string();
@@ -60,7 +60,7 @@ pub fn transfer_drip_reminder(
     config: &Config,
     conn: &PgConnection,
 ) -> Result<(), ApiError> {
-    let receive_tickets_link = transfer.receive_url(&config.front_end_url, conn)?;
+    let receive_tickets_link = transfer.receive_url(&config.front_end_url, &config.wallet_encryption_keys, conn)?;
     let source = CommAddress::from(config.communication_default_source_email.clone());
     let destinations = CommAddress::from(email.clone());
     let title = format!("{}: Ticket transfer reminder", SITE_NAME);
@@ -209,3 +209,48 @@ pub fn transfer_cancelled(
 
     Ok(())
 }
+
+pub fn waitlist_offer(
+    config: &Config,
+    email: String,
+    user: &User,
+    waitlist_entry: &WaitlistEntry,
+    ticket_type: &TicketType,
+    event: &Event,
+    redemption_code: &str,
+    purchase_link: &str,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{}: Tickets available for {}", SITE_NAME, event.name);
+    let template_id = config.email_templates.waitlist_offer.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("ticket_type_name".to_string(), ticket_type.name.clone());
+    template_data.insert("quantity".to_string(), waitlist_entry.quantity.to_string());
+    template_data.insert("redemption_code".to_string(), redemption_code.to_string());
+    template_data.insert("purchase_link".to_string(), purchase_link.to_string());
+    if let Some(offer_expires_at) = waitlist_entry.offer_expires_at {
+        template_data.insert(
+            "offer_expires_at".to_string(),
+            offer_expires_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+        );
+    }
+    insert_event_template_data(&mut template_data, event, conn)?;
+
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["waitlist", "waitlist_offer"]),
+        None,
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
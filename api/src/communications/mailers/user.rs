@@ -62,6 +62,29 @@ pub fn password_reset_email(config: &Config, user: &User) -> Communication {
     )
 }
 
+pub fn account_locked_email(config: &Config, user: &User, unlock_token: &str) -> Communication {
+    let unlock_link = format!("{}/unlock-account?token={}", config.front_end_url.clone(), unlock_token);
+    let email: &str = user.email.as_ref().expect("Email is not set");
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email.to_string());
+    let title = format!("{} Account locked", SITE_NAME);
+    let template_id = config.email_templates.account_locked.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("unlock_link".to_string(), unlock_link);
+    Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["account_locked", "account"]),
+        None,
+    )
+}
+
 pub fn invite_user_email(config: &Config, user: &User, conn: &PgConnection) -> Result<(), ApiError> {
     let invite_link = format!(
         "{}/password-reset?token={}&invite=true",
@@ -37,7 +37,7 @@ pub fn transfer_drip_reminder(
     conn: &PgConnection,
     deep_linker: &dyn DeepLinker,
 ) -> Result<(), ApiError> {
-    let receive_tickets_link = transfer.receive_url(&config.front_end_url, conn)?;
+    let receive_tickets_link = transfer.receive_url(&config.front_end_url, &config.wallet_encryption_keys, conn)?;
     let link = deep_linker.create_deep_link_with_fallback(&receive_tickets_link);
     let source = CommAddress::from(config.communication_default_source_phone.clone());
     let destinations = CommAddress::from(phone);
@@ -70,7 +70,7 @@ pub fn send_tickets(
     conn: &PgConnection,
     deep_linker: &dyn DeepLinker,
 ) -> Result<(), ApiError> {
-    let receive_tickets_link = transfer.receive_url(&config.front_end_url, conn)?;
+    let receive_tickets_link = transfer.receive_url(&config.front_end_url, &config.wallet_encryption_keys, conn)?;
     let link = deep_linker.create_deep_link_with_fallback(&receive_tickets_link);
 
     let source = CommAddress::from(config.communication_default_source_phone.clone());
@@ -95,3 +95,40 @@ pub fn send_tickets(
 
     Ok(())
 }
+
+pub fn waitlist_offer(
+    config: &Config,
+    phone: String,
+    waitlist_entry: &WaitlistEntry,
+    event: &Event,
+    purchase_link: &str,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let source = CommAddress::from(config.communication_default_source_phone.clone());
+    let destinations = CommAddress::from(phone);
+    let mut body = format!(
+        "{} tickets to {} are available for you from the waitlist. Purchase them here: {}",
+        waitlist_entry.quantity, event.name, purchase_link
+    );
+    if let Some(offer_expires_at) = waitlist_entry.offer_expires_at {
+        body = format!(
+            "{} This offer expires {}.",
+            body,
+            offer_expires_at.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+    Communication::new(
+        CommunicationType::Sms,
+        body,
+        None,
+        Some(source),
+        destinations,
+        None,
+        None,
+        Some(vec!["waitlist"]),
+        None,
+    )
+    .queue(conn)?;
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
-use crate::auth::default_token_issuer::DefaultTokenIssuer;
-use crate::errors::{ApiError, ApplicationError};
+use crate::auth::default_token_issuer::{DefaultTokenIssuer, TokenSigningKey, TokenVerificationKey};
+use crate::errors::{ApiError, ApplicationError, ApplicationErrorType};
 use crate::SITE_NAME;
 use chrono::Duration;
-use db::models::{EmailProvider, Environment};
+use db::models::{EmailProvider, Environment, APPLE_SITE, GOOGLE_SITE};
+use db::utils::encryption::KeyRing;
 use db::utils::errors::EnumParseError;
 use dotenv::dotenv;
 use itertools::Itertools;
@@ -37,6 +38,7 @@ pub struct Config {
     pub facebook_app_secret: Option<String>,
     pub globee_api_key: String,
     pub globee_base_url: String,
+    pub braintree_base_url: String,
     pub validate_ipns: bool,
     pub api_base_url: String,
     pub google_recaptcha_secret_key: Option<String>,
@@ -44,7 +46,11 @@ pub struct Config {
     pub block_external_comms: bool,
     pub primary_currency: String,
     pub stripe_secret_key: String,
+    pub stripe_webhook_secret: Option<String>,
+    pub stripe_dispute_nullify_tickets: bool,
     pub token_issuer: Box<DefaultTokenIssuer>,
+    pub scanner_snapshot_secret: String,
+    pub scanner_snapshot_max_age: Duration,
     pub tari_client: Box<dyn TariClient + Send + Sync>,
     pub communication_default_source_email: String,
     pub communication_default_source_phone: String,
@@ -65,12 +71,17 @@ pub struct Config {
     pub twilio_account_id: String,
     pub twilio_api_key: String,
     pub api_keys_encryption_key: String,
+    pub wallet_encryption_keys: KeyRing,
+    pub mfa_encryption_keys: KeyRing,
     pub jwt_expiry_time: Duration,
     pub branch_io_base_url: String,
     pub branch_io_branch_key: String,
     pub branch_io_timeout: u64,
     pub max_instances_per_ticket_type: i64,
+    pub report_export_inline_row_limit: i64,
     pub connection_pool: ConnectionPoolConfig,
+    pub login_throttling: LoginThrottlingConfig,
+    pub oidc_providers: Vec<OidcProviderConfig>,
     pub ssr_trigger_header: String,
     pub ssr_trigger_value: String,
     pub customer_io: CustomerIoSettings,
@@ -91,6 +102,27 @@ pub struct ConnectionPoolConfig {
     pub max: u32,
 }
 
+#[derive(Clone)]
+pub struct LoginThrottlingConfig {
+    pub max_failed_attempts: i64,
+    pub max_failed_attempts_per_ip: i64,
+    pub lockout_period: Duration,
+    /// Proxies in front of the API which append the client's address to `X-Forwarded-For`
+    pub trusted_proxy_count: usize,
+}
+
+/// OpenID Connect provider whose ID tokens are accepted for login
+#[derive(Clone, Debug)]
+pub struct OidcProviderConfig {
+    pub name: String,
+    /// Stored as the external login site
+    pub site: String,
+    pub issuers: Vec<String>,
+    pub jwks_uri: String,
+    /// Accepted audiences, apps and websites are registered with separate client ids
+    pub client_ids: Vec<String>,
+}
+
 #[derive(Clone)]
 pub struct CubeJs {
     pub secret: String,
@@ -104,12 +136,15 @@ pub struct SharetribeConfig {
 
 #[derive(Clone)]
 pub struct EmailTemplates {
+    pub account_locked: EmailTemplate,
     pub custom_broadcast: EmailTemplate,
     pub org_invite: EmailTemplate,
     pub password_reset: EmailTemplate,
+    pub report_export_ready: EmailTemplate,
     pub ticket_count_report: EmailTemplate,
     pub resend_download_link: EmailTemplate,
     pub user_registered_magic_link: EmailTemplate,
+    pub waitlist_offer: EmailTemplate,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -177,28 +212,42 @@ const CLIENT_CACHE_PERIOD: &str = "CLIENT_CACHE_PERIOD";
 const READONLY_DATABASE_URL: &str = "READONLY_DATABASE_URL";
 const DOMAIN: &str = "DOMAIN";
 const EMAIL_ONLY_REGISTRATION_ALLOWED: &str = "EMAIL_ONLY_REGISTRATION_ALLOWED";
+const EMAIL_TEMPLATES_ACCOUNT_LOCKED: &str = "EMAIL_TEMPLATES_ACCOUNT_LOCKED";
 const EMAIL_TEMPLATES_CUSTOM_BROADCAST: &str = "EMAIL_TEMPLATES_CUSTOM_BROADCAST";
 const EMAIL_TEMPLATES_ORG_INVITE: &str = "EMAIL_TEMPLATES_ORG_INVITE";
 const EMAIL_TEMPLATES_PASSWORD_RESET: &str = "EMAIL_TEMPLATES_PASSWORD_RESET";
+const EMAIL_TEMPLATES_REPORT_EXPORT_READY: &str = "EMAIL_TEMPLATES_REPORT_EXPORT_READY";
 const EMAIL_TEMPLATES_TICKET_COUNT_REPORT: &str = "EMAIL_TEMPLATES_TICKET_COUNT_REPORT";
 const EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK: &str = "EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK";
 const EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK: &str = "EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK";
+const EMAIL_TEMPLATES_WAITLIST_OFFER: &str = "EMAIL_TEMPLATES_WAITLIST_OFFER";
 const ENVIRONMENT: &str = "ENVIRONMENT";
 const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
 const GLOBEE_API_KEY: &str = "GLOBEE_API_KEY";
 const GLOBEE_BASE_URL: &str = "GLOBEE_BASE_URL";
+const BRAINTREE_BASE_URL: &str = "BRAINTREE_BASE_URL";
 const VALIDATE_IPNS: &str = "VALIDATE_IPNS";
 const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
+const STRIPE_WEBHOOK_SECRET: &str = "STRIPE_WEBHOOK_SECRET";
+const STRIPE_DISPUTE_NULLIFY_TICKETS: &str = "STRIPE_DISPUTE_NULLIFY_TICKETS";
 const STATIC_FILE_PATH: &str = "STATIC_FILE_PATH";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
 const TEST_READONLY_DATABASE_URL: &str = "TEST_READONLY_DATABASE_URL";
 const TOKEN_SECRET: &str = "TOKEN_SECRET";
 const TOKEN_ISSUER: &str = "TOKEN_ISSUER";
+// Verifies offline scans were made against an issued scanner snapshot, defaults to TOKEN_SECRET
+const SCANNER_SNAPSHOT_SECRET: &str = "SCANNER_SNAPSHOT_SECRET";
+// Offline scans made against older snapshots are rejected
+const SCANNER_SNAPSHOT_MAX_AGE_HOURS: &str = "SCANNER_SNAPSHOT_MAX_AGE_HOURS";
+// Asymmetric signing, each key in the format '<kid>:<RS256|ES256>:<base64 DER key>'
+const TOKEN_SIGNING_KEY: &str = "TOKEN_SIGNING_KEY";
+// Comma separated, must include the public half of the signing key and any retired keys still being accepted
+const TOKEN_VERIFICATION_KEYS: &str = "TOKEN_VERIFICATION_KEYS";
 const HTTP_KEEP_ALIVE: &str = "HTTP_KEEP_ALIVE";
 // Blocks all external communications from occurring
 const BLOCK_EXTERNAL_COMMS: &str = "BLOCK_EXTERNAL_COMMS";
@@ -233,6 +282,8 @@ const TWILIO_API_KEY: &str = "TWILIO_API_KEY";
 const TWILIO_ACCOUNT_ID: &str = "TWILIO_ACCOUNT_ID";
 
 const API_KEYS_ENCRYPTION_KEY: &str = "API_KEYS_ENCRYPTION_KEY";
+const WALLET_ENCRYPTION_KEYS: &str = "WALLET_ENCRYPTION_KEYS";
+const MFA_ENCRYPTION_KEYS: &str = "MFA_ENCRYPTION_KEYS";
 
 const JWT_EXPIRY_TIME: &str = "JWT_EXPIRY_TIME";
 const BRANCH_IO_BASE_URL: &str = "BRANCH_IO_BASE_URL";
@@ -240,9 +291,21 @@ const BRANCH_IO_BRANCH_KEY: &str = "BRANCH_IO_BRANCH_KEY";
 const BRANCH_IO_TIMEOUT: &str = "BRANCH_IO_TIMEOUT";
 
 const MAX_INSTANCES_PER_TICKET_TYPE: &str = "MAX_INSTANCES_PER_TICKET_TYPE";
+// Report exports with more rows than this are built in the background and emailed as a download link
+const REPORT_EXPORT_INLINE_ROW_LIMIT: &str = "REPORT_EXPORT_INLINE_ROW_LIMIT";
 const CONNECTION_POOL_MIN: &str = "CONNECTION_POOL_MIN";
 const CONNECTION_POOL_MAX: &str = "CONNECTION_POOL_MAX";
 
+// Failed logins before an account is locked, and per IP address within the lockout period
+const LOGIN_MAX_FAILED_ATTEMPTS: &str = "LOGIN_MAX_FAILED_ATTEMPTS";
+const LOGIN_MAX_FAILED_ATTEMPTS_PER_IP: &str = "LOGIN_MAX_FAILED_ATTEMPTS_PER_IP";
+const LOGIN_LOCKOUT_MINUTES: &str = "LOGIN_LOCKOUT_MINUTES";
+const TRUSTED_PROXY_COUNT: &str = "TRUSTED_PROXY_COUNT";
+
+// Comma separated client ids, a provider is only enabled once its client ids are set
+const OIDC_GOOGLE_CLIENT_IDS: &str = "OIDC_GOOGLE_CLIENT_IDS";
+const OIDC_APPLE_CLIENT_IDS: &str = "OIDC_APPLE_CLIENT_IDS";
+
 const SSR_TRIGGER_HEADER: &str = "SSR_TRIGGER_HEADER";
 const SSR_TRIGGER_VALUE: &str = "SSR_TRIGGER_VALUE";
 
@@ -264,7 +327,7 @@ impl Config {
         Ok(Environment::Development)
     }
 
-    pub fn new(environment: Environment) -> Self {
+    pub fn new(environment: Environment) -> Result<Self, ApiError> {
         dotenv().ok();
 
         let app_name = env::var(&APP_NAME).unwrap_or_else(|_| SITE_NAME.to_string());
@@ -336,11 +399,34 @@ impl Config {
 
         let primary_currency = env::var(&PRIMARY_CURRENCY).unwrap_or_else(|_| "usd".to_string());
         let stripe_secret_key = env::var(&STRIPE_SECRET_KEY).unwrap_or_else(|_| "<stripe not enabled>".to_string());
-
-        let token_issuer = Box::new(DefaultTokenIssuer::new(
-            get_env_var(TOKEN_SECRET),
-            get_env_var(TOKEN_ISSUER),
-        ));
+        let stripe_webhook_secret = env::var(&STRIPE_WEBHOOK_SECRET).ok();
+        let stripe_dispute_nullify_tickets = env::var(&STRIPE_DISPUTE_NULLIFY_TICKETS)
+            .unwrap_or("false".to_string())
+            .parse()
+            .expect(&format!(
+                "{} is not a valid boolean value",
+                STRIPE_DISPUTE_NULLIFY_TICKETS
+            ));
+
+        let mut token_issuer = DefaultTokenIssuer::new(get_env_var(TOKEN_SECRET), get_env_var(TOKEN_ISSUER));
+        if let Ok(signing_key) = env::var(&TOKEN_SIGNING_KEY) {
+            let signing_key: TokenSigningKey = signing_key.parse()?;
+            let verification_keys = get_env_var(TOKEN_VERIFICATION_KEYS)
+                .split(',')
+                .map(|k| k.trim().parse())
+                .collect::<Result<Vec<TokenVerificationKey>, ApiError>>()?;
+            token_issuer = token_issuer.with_signing_keys(signing_key, verification_keys)?;
+        }
+        let token_issuer = Box::new(token_issuer);
+        let scanner_snapshot_secret = env::var(&SCANNER_SNAPSHOT_SECRET).unwrap_or_else(|_| get_env_var(TOKEN_SECRET));
+        let scanner_snapshot_max_age = Duration::hours(
+            env::var(SCANNER_SNAPSHOT_MAX_AGE_HOURS)
+                .map(|s| {
+                    s.parse()
+                        .expect("Not a valid integer for SCANNER_SNAPSHOT_MAX_AGE_HOURS")
+                })
+                .unwrap_or(24),
+        );
 
         let facebook_app_id = env::var(&FACEBOOK_APP_ID).ok();
 
@@ -366,6 +452,10 @@ impl Config {
             Environment::Production => "https://globee.com/payment-api/v1/".to_string(),
             _ => "https://test.globee.com/payment-api/v1/".to_string(),
         });
+        let braintree_base_url = env::var(&BRAINTREE_BASE_URL).unwrap_or_else(|_| match environment {
+            Environment::Production => "https://payments.braintree-api.com/graphql".to_string(),
+            _ => "https://payments.sandbox.braintree-api.com/graphql".to_string(),
+        });
 
         let branch_io_base_url = env::var(&BRANCH_IO_BASE_URL).unwrap_or("https://api2.branch.io/v1".to_string());
         let branch_io_branch_key = get_env_var(BRANCH_IO_BRANCH_KEY);
@@ -390,12 +480,15 @@ impl Config {
 
         let email_only_registration_allowed = get_env_var(EMAIL_ONLY_REGISTRATION_ALLOWED).parse().unwrap();
         let email_templates = EmailTemplates {
+            account_locked: get_env_var(EMAIL_TEMPLATES_ACCOUNT_LOCKED).parse().unwrap(),
             custom_broadcast: get_env_var(EMAIL_TEMPLATES_CUSTOM_BROADCAST).parse().unwrap(),
             org_invite: get_env_var(EMAIL_TEMPLATES_ORG_INVITE).parse().unwrap(),
             password_reset: get_env_var(EMAIL_TEMPLATES_PASSWORD_RESET).parse().unwrap(),
+            report_export_ready: get_env_var(EMAIL_TEMPLATES_REPORT_EXPORT_READY).parse().unwrap(),
             ticket_count_report: get_env_var(EMAIL_TEMPLATES_TICKET_COUNT_REPORT).parse().unwrap(),
             resend_download_link: get_env_var(EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK).parse().unwrap(),
             user_registered_magic_link: get_env_var(EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK).parse().unwrap(),
+            waitlist_offer: get_env_var(EMAIL_TEMPLATES_WAITLIST_OFFER).parse().unwrap(),
         };
 
         let customer_io_base_url = get_env_var(CUSTOMER_IO_BASE_URL);
@@ -443,6 +536,24 @@ impl Config {
         let twilio_account_id = get_env_var(TWILIO_ACCOUNT_ID);
 
         let api_keys_encryption_key = get_env_var(API_KEYS_ENCRYPTION_KEY);
+        let wallet_encryption_keys = KeyRing::parse(&env::var(&WALLET_ENCRYPTION_KEYS).unwrap_or("".to_string()))
+            .ok()
+            .filter(|key_ring| key_ring.current_version().is_some())
+            .ok_or_else(|| {
+                ApplicationError::new_with_type(
+                    ApplicationErrorType::ServerConfigError,
+                    format!("{} must be comma separated version:key pairs", WALLET_ENCRYPTION_KEYS),
+                )
+            })?;
+        let mfa_encryption_keys = KeyRing::parse(&env::var(&MFA_ENCRYPTION_KEYS).unwrap_or("".to_string()))
+            .ok()
+            .filter(|key_ring| key_ring.current_version().is_some())
+            .ok_or_else(|| {
+                ApplicationError::new_with_type(
+                    ApplicationErrorType::ServerConfigError,
+                    format!("{} must be comma separated version:key pairs", MFA_ENCRYPTION_KEYS),
+                )
+            })?;
 
         let block_external_comms = match env::var(&BLOCK_EXTERNAL_COMMS)
             .unwrap_or_else(|_| "0".to_string())
@@ -463,6 +574,12 @@ impl Config {
                     .expect("Not a valid integer for max instances per ticket type")
             })
             .unwrap_or(10000);
+        let report_export_inline_row_limit = env::var(&REPORT_EXPORT_INLINE_ROW_LIMIT)
+            .map(|s| {
+                s.parse()
+                    .expect("Not a valid integer for REPORT_EXPORT_INLINE_ROW_LIMIT")
+            })
+            .unwrap_or(5000);
         let connection_pool = ConnectionPoolConfig {
             min: env::var(CONNECTION_POOL_MIN)
                 .map(|s| s.parse().expect("Not a valid integer for CONNECTION_POOL_MIN"))
@@ -472,6 +589,49 @@ impl Config {
                 .unwrap_or(20),
         };
 
+        let login_throttling = LoginThrottlingConfig {
+            max_failed_attempts: env::var(LOGIN_MAX_FAILED_ATTEMPTS)
+                .map(|s| s.parse().expect("Not a valid integer for LOGIN_MAX_FAILED_ATTEMPTS"))
+                .unwrap_or(10),
+            max_failed_attempts_per_ip: env::var(LOGIN_MAX_FAILED_ATTEMPTS_PER_IP)
+                .map(|s| {
+                    s.parse()
+                        .expect("Not a valid integer for LOGIN_MAX_FAILED_ATTEMPTS_PER_IP")
+                })
+                .unwrap_or(50),
+            lockout_period: Duration::minutes(
+                env::var(LOGIN_LOCKOUT_MINUTES)
+                    .map(|s| s.parse().expect("Not a valid integer for LOGIN_LOCKOUT_MINUTES"))
+                    .unwrap_or(30),
+            ),
+            trusted_proxy_count: env::var(TRUSTED_PROXY_COUNT)
+                .map(|s| s.parse().expect("Not a valid integer for TRUSTED_PROXY_COUNT"))
+                .unwrap_or(1),
+        };
+
+        let mut oidc_providers = Vec::new();
+        if let Ok(client_ids) = env::var(OIDC_GOOGLE_CLIENT_IDS) {
+            oidc_providers.push(OidcProviderConfig {
+                name: "google".to_string(),
+                site: GOOGLE_SITE.to_string(),
+                issuers: vec![
+                    "https://accounts.google.com".to_string(),
+                    "accounts.google.com".to_string(),
+                ],
+                jwks_uri: "https://www.googleapis.com/oauth2/v3/certs".to_string(),
+                client_ids: client_ids.split(',').map(|s| s.trim().to_string()).collect(),
+            });
+        }
+        if let Ok(client_ids) = env::var(OIDC_APPLE_CLIENT_IDS) {
+            oidc_providers.push(OidcProviderConfig {
+                name: "apple".to_string(),
+                site: APPLE_SITE.to_string(),
+                issuers: vec!["https://appleid.apple.com".to_string()],
+                jwks_uri: "https://appleid.apple.com/auth/keys".to_string(),
+                client_ids: client_ids.split(',').map(|s| s.trim().to_string()).collect(),
+            });
+        }
+
         let ssr_trigger_header = env::var(&SSR_TRIGGER_HEADER).unwrap_or("x-ssr".to_string());
         let ssr_trigger_value = env::var(&SSR_TRIGGER_VALUE).unwrap_or("facebook".to_string());
 
@@ -491,7 +651,7 @@ impl Config {
             _ => panic!("Invalid value for PRODUCT_CONTEXT"),
         };
 
-        Config {
+        Ok(Config {
             actix: Actix {
                 workers,
                 backlog,
@@ -519,6 +679,7 @@ impl Config {
             facebook_app_secret,
             globee_api_key,
             globee_base_url,
+            braintree_base_url,
             branch_io_base_url,
             validate_ipns,
             api_base_url,
@@ -527,7 +688,11 @@ impl Config {
             block_external_comms,
             primary_currency,
             stripe_secret_key,
+            stripe_webhook_secret,
+            stripe_dispute_nullify_tickets,
             token_issuer,
+            scanner_snapshot_secret,
+            scanner_snapshot_max_age,
             front_end_url,
             tari_client,
             communication_default_source_email,
@@ -549,15 +714,20 @@ impl Config {
             twilio_api_key,
             twilio_account_id,
             api_keys_encryption_key,
+            wallet_encryption_keys,
+            mfa_encryption_keys,
             jwt_expiry_time,
             branch_io_branch_key,
             branch_io_timeout,
             max_instances_per_ticket_type,
+            report_export_inline_row_limit,
             connection_pool,
+            login_throttling,
+            oidc_providers,
             ssr_trigger_header,
             ssr_trigger_value,
             sharetribe,
             product_context,
-        }
+        })
     }
 }
@@ -0,0 +1,123 @@
+use crate::auth::user::User as AuthUser;
+use crate::controllers::stages;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use diesel::PgConnection;
+
+#[derive(Deserialize)]
+pub struct CreateAccessZoneRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(AccessZone::find_for_event(&event, connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateAccessZoneRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    let access_zone =
+        AccessZone::create_for_event(event.id, json.name.clone(), json.description.clone()).commit(connection)?;
+    Ok(HttpResponse::Created().json(&access_zone))
+}
+
+pub async fn venue_index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue = Venue::find(path.id, connection)?;
+    stages::check_access(&venue, &user, connection)?;
+
+    Ok(HttpResponse::Ok().json(AccessZone::find_by_venue_id(venue.id, connection)?))
+}
+
+pub async fn create_for_venue(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateAccessZoneRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue = Venue::find(path.id, connection)?;
+    stages::check_access(&venue, &user, connection)?;
+
+    let access_zone =
+        AccessZone::create_for_venue(venue.id, json.name.clone(), json.description.clone()).commit(connection)?;
+    Ok(HttpResponse::Created().json(&access_zone))
+}
+
+pub async fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<AccessZoneEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let access_zone = AccessZone::find(path.id, connection)?;
+    check_access(&access_zone, &user, connection)?;
+
+    let access_zone = access_zone.update(json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&access_zone))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let access_zone = AccessZone::find(path.id, connection)?;
+    check_access(&access_zone, &user, connection)?;
+
+    access_zone.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn check_access(access_zone: &AccessZone, user: &AuthUser, connection: &PgConnection) -> Result<(), ApiError> {
+    match (access_zone.event_id, access_zone.venue_id) {
+        (Some(event_id), _) => {
+            let event = Event::find(event_id, connection)?;
+            user.requires_scope_for_organization_event(
+                Scopes::EventWrite,
+                &event.organization(connection)?,
+                &event,
+                connection,
+            )?;
+        }
+        (None, Some(venue_id)) => stages::check_access(&Venue::find(venue_id, connection)?, user, connection)?,
+        (None, None) => user.requires_scope(Scopes::VenueWrite)?,
+    }
+    Ok(())
+}
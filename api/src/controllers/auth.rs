@@ -1,16 +1,20 @@
-use crate::auth::TokenResponse;
+use crate::auth::{LoginResponse, TokenResponse};
+use crate::communications::mailers;
+use crate::config::Config;
 use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
-use crate::jwt::{decode, Validation};
+use crate::helpers::login_throttling::{client_ip_address, LoginThrottle, LoginThrottleStatus};
 use crate::models::*;
 use crate::server::{AppState, GetAppState};
 use crate::utils::google_recaptcha;
 use actix_web::{web::Data, HttpRequest, HttpResponse};
+use cache::RedisCacheConnection;
 use db::prelude::*;
+use db::utils::encryption::KeyRing;
 use diesel::PgConnection;
-use log::Level::Info;
+use log::Level::{Info, Warn};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -28,6 +32,17 @@ pub struct RefreshRequest {
     refresh_token: String,
 }
 
+#[derive(Deserialize)]
+pub struct UnlockRequest {
+    unlock_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct MfaTokenRequest {
+    mfa_token: String,
+    code: String,
+}
+
 impl LoginRequest {
     pub fn new(email: &str, password: &str) -> Self {
         LoginRequest {
@@ -46,12 +61,29 @@ impl RefreshRequest {
     }
 }
 
+impl UnlockRequest {
+    pub fn new(unlock_token: &str) -> Self {
+        UnlockRequest {
+            unlock_token: String::from(unlock_token),
+        }
+    }
+}
+
+impl MfaTokenRequest {
+    pub fn new(mfa_token: &str, code: &str) -> Self {
+        MfaTokenRequest {
+            mfa_token: String::from(mfa_token),
+            code: String::from(code),
+        }
+    }
+}
+
 pub async fn token(
     (http_request, connection, login_request, request_info): (HttpRequest, Connection, Json<LoginRequest>, RequestInfo),
-) -> Result<TokenResponse, ApiError> {
+) -> Result<LoginResponse, ApiError> {
     let state = http_request.state();
-    let connection_info = http_request.connection_info();
-    let remote_ip = connection_info.remote();
+    let ip_address = client_ip_address(&http_request, state.config.login_throttling.trusted_proxy_count);
+    let ip_address = ip_address.as_ref().map(|ip_address| ip_address.as_str());
     let mut login_log_data = HashMap::new();
     login_log_data.insert("email", login_request.email.clone().into());
 
@@ -61,7 +93,7 @@ pub async fn token(
                 let captcha_response = google_recaptcha::verify_response(
                     google_recaptcha_secret_key,
                     captcha_response.to_owned(),
-                    remote_ip,
+                    ip_address,
                 )
                 .await?;
                 if !captcha_response.success {
@@ -74,46 +106,145 @@ pub async fn token(
         }
     }
 
+    // Throttling is only applied when the Redis cache is configured
+    let mut login_throttle = state
+        .database
+        .cache_database
+        .inner
+        .clone()
+        .map(|cache| LoginThrottle::new(cache, state.config.login_throttling.clone()));
+    if let Some(ref mut login_throttle) = login_throttle {
+        match login_throttle.status(&login_request.email, ip_address) {
+            Ok(LoginThrottleStatus::Allowed) => (),
+            Ok(LoginThrottleStatus::BackingOff { retry_after_seconds }) => {
+                return application::too_many_requests(&format!(
+                    "Too many failed login attempts, try again in {} seconds",
+                    retry_after_seconds
+                ));
+            }
+            Ok(LoginThrottleStatus::Locked) => {
+                return application::too_many_requests(
+                    "Account is temporarily locked due to too many failed login attempts",
+                );
+            }
+            Err(err) => error!("auth#token: {:?}", err),
+        }
+    }
+
     // Generic messaging to prevent exposing user is member of system
     let login_failure_messaging = "Email or password incorrect";
 
-    let user = match User::find_by_email(&login_request.email, false, connection.get()).optional() {
-        Ok(u) => match u {
-            Some(usr) => usr,
-            None => return application::unauthorized_with_message(login_failure_messaging, None, Some(login_log_data)),
-        },
-        Err(_e) => {
+    let user = User::find_by_email(&login_request.email, false, connection.get())
+        .optional()
+        .unwrap_or(None);
+    let user = match user {
+        Some(user) if user.check_password(&login_request.password) => user,
+        user => {
+            record_failed_login(
+                &state.config,
+                login_throttle.as_mut(),
+                &login_request.email,
+                user.as_ref(),
+                ip_address,
+                &request_info,
+                &connection,
+            )?;
             return application::unauthorized_with_message(login_failure_messaging, None, Some(login_log_data));
         }
     };
 
-    if !user.check_password(&login_request.password) {
-        return application::unauthorized_with_message(login_failure_messaging, None, Some(login_log_data));
+    if let Some(ref mut login_throttle) = login_throttle {
+        if let Err(err) = login_throttle.record_success(&login_request.email) {
+            error!("auth#token: {:?}", err);
+        }
     }
 
     user.login_domain_event(json!(request_info), connection.get())?;
     jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone()});
-    let response = TokenResponse::create_from_user(&*state.config.token_issuer, state.config.jwt_expiry_time, &user)?;
+    let response = LoginResponse::create_from_user(
+        &*state.config.token_issuer,
+        state.config.jwt_expiry_time,
+        &user,
+        &request_info,
+        connection.get(),
+    )?;
+    Ok(response)
+}
+
+pub async fn token_mfa(
+    (state, connection, mfa_request, request_info): (Data<AppState>, Connection, Json<MfaTokenRequest>, RequestInfo),
+) -> Result<TokenResponse, ApiError> {
+    let token = state.config.token_issuer.decode(&mfa_request.mfa_token)?;
+    let conn = connection.get();
+    match token.claims.scopes {
+        Some(ref scopes) if scopes.contains(&Scopes::MfaChallenge.to_string()) => (),
+        _ => {
+            return application::unauthorized_with_message(
+                "Token can not be used to complete a multi-factor login",
+                None,
+                None,
+            );
+        }
+    }
+
+    let user = User::find(token.claims.get_id()?, conn)?;
+    if UserMfaChallenge::exhausted(user.id, &mfa_request.mfa_token, conn)? {
+        return application::unauthorized_with_message(
+            "Too many incorrect authentication codes, please log in again",
+            None,
+            None,
+        );
+    }
+
+    if !user.verify_mfa_code(&mfa_request.code, &state.config.mfa_encryption_keys, conn)? {
+        UserMfaChallenge::record_failure(user.id, &mfa_request.mfa_token, conn)?;
+        // Incorrect codes also count towards locking the account
+        if let Some(ref email) = user.email {
+            let mut login_throttle = state
+                .database
+                .cache_database
+                .inner
+                .clone()
+                .map(|cache| LoginThrottle::new(cache, state.config.login_throttling.clone()));
+            record_failed_login(
+                &state.config,
+                login_throttle.as_mut(),
+                email,
+                Some(&user),
+                None,
+                &request_info,
+                &connection,
+            )?;
+        }
+        // Keep the failed attempt as the request's transaction is rolled back on error
+        connection.commit_transaction()?;
+        connection.begin_transaction()?;
+        return application::unauthorized_with_message("Authentication code incorrect", None, None);
+    }
+
+    UserMfaChallenge::destroy_for_user(user.id, conn)?;
+    jlog!(Info, "User completed multi-factor login", {"id": user.id, "email": user.email.clone()});
+    let response = TokenResponse::create_from_user(
+        &*state.config.token_issuer,
+        state.config.jwt_expiry_time,
+        &user,
+        &request_info,
+        conn,
+    )?;
     Ok(response)
 }
 
 pub async fn token_refresh(
-    (state, connection, refresh_request): (Data<AppState>, Connection, Json<RefreshRequest>),
+    (state, connection, refresh_request, request_info): (Data<AppState>, Connection, Json<RefreshRequest>, RequestInfo),
 ) -> Result<HttpResponse, ApiError> {
-    let mut validation = Validation::default();
-    validation.validate_exp = false;
-    let token = decode::<AccessToken>(
-        &refresh_request.refresh_token,
-        state.config.token_issuer.token_secret.as_bytes(),
-        &validation,
-    )?;
+    let token = state.config.token_issuer.decode(&refresh_request.refresh_token)?;
     let conn = connection.get();
     let user_id = token.claims.get_id()?;
     let user;
     if let Some(ref scopes) = token.claims.scopes {
         // Promote temp user
         if scopes.contains(&Scopes::TemporaryUserPromote.to_string()) {
-            user = promote_temp_to_user(user_id, conn)?;
+            user = promote_temp_to_user(user_id, &state.config.wallet_encryption_keys, conn)?;
         } else if !scopes.contains(&Scopes::TokenRefresh.to_string()) {
             return application::unauthorized_with_message(
                 "Token does not have the scope needed to refresh",
@@ -122,23 +253,119 @@ pub async fn token_refresh(
             );
         } else {
             user = User::find(user_id, conn)?;
+            if user.deleted_at.is_some() {
+                return application::unauthorized_with_message("Token no longer valid", None, None);
+            }
+
+            if let (Some(session_id), Some(token_id)) = (token.claims.sid, token.claims.jti) {
+                let session = match UserSession::find(session_id, conn).optional()? {
+                    Some(session) if session.user_id == user.id => session,
+                    _ => return application::unauthorized_with_message("Token no longer valid", None, None),
+                };
+                let response = match session.rotate(token_id, conn)? {
+                    Some(session) => TokenResponse::create_from_session(
+                        &*state.config.token_issuer,
+                        state.config.jwt_expiry_time,
+                        &session,
+                    )?,
+                    None => {
+                        if session.is_active() {
+                            jlog!(Warn, "Refresh token reused, session revoked", {"user_id": user.id, "session_id": session.id});
+                        }
+                        // Keep the revocation as the request's transaction is rolled back on error
+                        connection.commit_transaction()?;
+                        connection.begin_transaction()?;
+                        return application::unauthorized_with_message("Token no longer valid", None, None);
+                    }
+                };
+                return Ok(HttpResponse::Ok().json(response));
+            }
+
             let password_modified_timestamp = user.password_modified_at.timestamp() as u64;
             // If the user changes their password invalidate all refresh tokens
             if password_modified_timestamp > token.claims.issued {
                 return application::unauthorized_with_message("Token no longer valid", None, None);
             }
+
+            // Revoking sessions also invalidates refresh tokens issued outside of a session
+            if let Some(revoked_at) = UserSession::last_revoked_at_for_user(user.id, conn)? {
+                if revoked_at.timestamp() as u64 >= token.claims.issued {
+                    return application::unauthorized_with_message("Token no longer valid", None, None);
+                }
+            }
         }
     } else {
         return application::unauthorized_with_message("Token can not be used to refresh", None, None);
     }
 
-    let response =
-        TokenResponse::create_from_refresh_token(&*state.config.token_issuer, state.config.jwt_expiry_time, user.id)?;
+    // Tokens issued without a session, such as magic links, start a new session
+    let response = TokenResponse::create_from_user(
+        &*state.config.token_issuer,
+        state.config.jwt_expiry_time,
+        &user,
+        &request_info,
+        conn,
+    )?;
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-fn promote_temp_to_user(user_id: Uuid, conn: &PgConnection) -> Result<User, ApiError> {
+pub async fn unlock((state, unlock_request): (Data<AppState>, Json<UnlockRequest>)) -> Result<HttpResponse, ApiError> {
+    let unlocked = match state.database.cache_database.inner.clone() {
+        Some(cache) => LoginThrottle::new(cache, state.config.login_throttling.clone())
+            .unlock(&unlock_request.unlock_token)
+            .map_err(|err| ApplicationError::new(err.to_string()))?,
+        None => None,
+    };
+
+    match unlocked {
+        Some(_) => Ok(HttpResponse::Ok().json(json!({}))),
+        None => application::unprocessable("Unlock link is invalid or has expired"),
+    }
+}
+
+pub async fn jwks(state: Data<AppState>) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(state.config.token_issuer.jwks()?))
+}
+
+/// Failed attempts are committed straight away as the request's transaction is rolled back
+/// when responding with the unauthorized error
+fn record_failed_login(
+    config: &Config,
+    login_throttle: Option<&mut LoginThrottle<RedisCacheConnection>>,
+    email: &str,
+    user: Option<&User>,
+    ip_address: Option<&str>,
+    request_info: &RequestInfo,
+    connection: &Connection,
+) -> Result<(), ApiError> {
+    let conn = connection.get();
+    User::login_failed_domain_event(
+        user.map(|user| user.id),
+        json!({ "email": email, "ip_address": ip_address, "user_agent": request_info.user_agent }),
+        conn,
+    )?;
+
+    if let Some(login_throttle) = login_throttle {
+        match login_throttle.record_failure(email, ip_address) {
+            Ok(failed_login) => {
+                if let (Some(unlock_token), Some(user)) = (failed_login.unlock_token, user) {
+                    jlog!(Info, "User account locked after failed login attempts", {"id": user.id, "failed_attempts": failed_login.failed_attempts});
+                    if user.email.is_some() {
+                        mailers::user::account_locked_email(config, user, &unlock_token).queue(conn)?;
+                    }
+                }
+            }
+            Err(err) => error!("auth#record_failed_login: {:?}", err),
+        }
+    }
+
+    connection.commit_transaction()?;
+    connection.begin_transaction()?;
+    Ok(())
+}
+
+fn promote_temp_to_user(user_id: Uuid, key_ring: &KeyRing, conn: &PgConnection) -> Result<User, ApiError> {
     let temp_user = TemporaryUser::find(user_id, &conn)?;
     let user = temp_user.users(&conn)?.into_iter().next();
     if let Some(user) = user {
@@ -151,7 +378,7 @@ fn promote_temp_to_user(user_id: Uuid, conn: &PgConnection) -> Result<User, ApiE
         temp_user.phone.clone(),
         &Uuid::new_v4().to_string(),
     )
-    .commit(None, &conn)?;
+    .commit(None, key_ring, &conn)?;
     temp_user.associate_user(user.id, conn)?;
     Ok(user)
 }
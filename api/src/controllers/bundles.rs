@@ -0,0 +1,135 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use diesel::PgConnection;
+use serde_with::rust::double_option;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateBundleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub ticket_types: Vec<UpdateBundleTicketType>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct UpdateBundleRequest {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+    pub ticket_types: Option<Vec<UpdateBundleTicketType>>,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeRead, &organization, connection)?;
+
+    let mut bundles = Vec::new();
+    for bundle in Bundle::find_for_organization(organization.id, connection)? {
+        bundles.push(bundle.for_display(connection)?);
+    }
+    Ok(HttpResponse::Ok().json(&bundles))
+}
+
+pub async fn show((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(path.id, connection)?;
+    if bundle.deleted_at.is_some() {
+        return application::not_found();
+    }
+
+    Ok(HttpResponse::Ok().json(&bundle.for_display(connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateBundleRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection)?;
+    let ticket_type_ids: Vec<Uuid> = json.ticket_types.iter().map(|t| t.ticket_type_id).collect();
+    requires_scope_for_events(&ticket_type_ids, &organization, &user, connection)?;
+
+    let json = json.into_inner();
+    let bundle = Bundle::create(organization.id, json.name, json.description, json.price_in_cents)
+        .commit(Some(user.id()), connection)?;
+    bundle.update_ticket_types(&json.ticket_types, Some(user.id()), connection)?;
+
+    application::created(json!(bundle.for_display(connection)?))
+}
+
+/// Updates the bundle, the price is reallocated across its ticket types when either changes
+pub async fn update(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<UpdateBundleRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(path.id, connection)?;
+    requires_scope_for_bundle(&bundle, &user, connection)?;
+    if let Some(ref ticket_types) = json.ticket_types {
+        let ticket_type_ids: Vec<Uuid> = ticket_types.iter().map(|t| t.ticket_type_id).collect();
+        requires_scope_for_events(&ticket_type_ids, &bundle.organization(connection)?, &user, connection)?;
+    }
+
+    let json = json.into_inner();
+    let bundle = bundle.update(
+        BundleEditableAttributes {
+            name: json.name,
+            description: json.description,
+            price_in_cents: json.price_in_cents,
+        },
+        Some(user.id()),
+        connection,
+    )?;
+    if let Some(ref ticket_types) = json.ticket_types {
+        bundle.update_ticket_types(ticket_types, Some(user.id()), connection)?;
+    }
+
+    Ok(HttpResponse::Ok().json(&bundle.for_display(connection)?))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(path.id, connection)?;
+    requires_scope_for_bundle(&bundle, &user, connection)?;
+
+    bundle.delete(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn requires_scope_for_bundle(bundle: &Bundle, user: &AuthUser, connection: &PgConnection) -> Result<(), ApiError> {
+    let organization = bundle.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection)?;
+    let ticket_type_ids: Vec<Uuid> = bundle
+        .ticket_types(connection)?
+        .iter()
+        .map(|t| t.ticket_type_id)
+        .collect();
+    requires_scope_for_events(&ticket_type_ids, &organization, user, connection)
+}
+
+/// A bundle spans events so event limited roles need access to every event it covers
+fn requires_scope_for_events(
+    ticket_type_ids: &[Uuid],
+    organization: &Organization,
+    user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    for ticket_type_id in ticket_type_ids {
+        let event = TicketType::find(*ticket_type_id, connection)?.event(connection)?;
+        user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, organization, &event, connection)?;
+    }
+
+    Ok(())
+}
@@ -36,6 +36,24 @@ pub struct CartItem {
     pub quantity: u32,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub redemption_code: Option<String>,
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CartBundle {
+    pub bundle_id: Uuid,
+    pub quantity: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CartProduct {
+    pub product_id: Uuid,
+    #[serde(default)]
+    pub product_variant_id: Option<Uuid>,
+    pub quantity: u32,
+    #[serde(default)]
+    pub event_id: Option<Uuid>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -43,6 +61,10 @@ pub struct UpdateCartRequest {
     pub items: Vec<CartItem>,
     pub box_office_pricing: Option<bool>,
     pub tracking_data: Option<Value>,
+    #[serde(default)]
+    pub bundles: Vec<CartBundle>,
+    #[serde(default)]
+    pub products: Vec<CartProduct>,
 }
 
 pub async fn update_cart(
@@ -73,6 +95,7 @@ pub async fn update_cart(
             quantity: i.quantity,
             ticket_type_id: i.ticket_type_id,
             redemption_code: i.redemption_code.clone(),
+            seat_ids: i.seat_ids.clone(),
         })
         .collect();
 
@@ -83,6 +106,12 @@ pub async fn update_cart(
     }
 
     cart.update_quantities(user.id(), &order_items, box_office_pricing, false, connection)?;
+    if !json.bundles.is_empty() {
+        cart.update_bundle_quantities(user.id(), &order_bundles(&json.bundles), connection)?;
+    }
+    if !json.products.is_empty() {
+        cart.update_product_quantities(user.id(), &order_products(&json.products), connection)?;
+    }
 
     cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
     cart.set_tracking_data(json.tracking_data.clone(), Some(user.id()), connection)?;
@@ -90,6 +119,28 @@ pub async fn update_cart(
     Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
 }
 
+fn order_bundles(bundles: &[CartBundle]) -> Vec<UpdateOrderBundle> {
+    bundles
+        .iter()
+        .map(|b| UpdateOrderBundle {
+            bundle_id: b.bundle_id,
+            quantity: b.quantity,
+        })
+        .collect()
+}
+
+fn order_products(products: &[CartProduct]) -> Vec<UpdateOrderProduct> {
+    products
+        .iter()
+        .map(|p| UpdateOrderProduct {
+            product_id: p.product_id,
+            product_variant_id: p.product_variant_id,
+            quantity: p.quantity,
+            event_id: p.event_id,
+        })
+        .collect()
+}
+
 pub async fn duplicate(
     (connection, path, user): (Connection, Path<PathParameters>, User),
 ) -> Result<HttpResponse, ApiError> {
@@ -144,6 +195,7 @@ pub async fn replace_cart(
             quantity: i.quantity,
             ticket_type_id: i.ticket_type_id,
             redemption_code: i.redemption_code.clone(),
+            seat_ids: i.seat_ids.clone(),
         })
         .collect();
 
@@ -154,6 +206,12 @@ pub async fn replace_cart(
     }
 
     cart.update_quantities(user.id(), &order_items, box_office_pricing, true, connection)?;
+    if !json.bundles.is_empty() {
+        cart.update_bundle_quantities(user.id(), &order_bundles(&json.bundles), connection)?;
+    }
+    if !json.products.is_empty() {
+        cart.update_product_quantities(user.id(), &order_products(&json.products), connection)?;
+    }
 
     cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
     cart.set_tracking_data(json.tracking_data.clone(), Some(user.id()), connection)?;
@@ -247,6 +305,12 @@ pub async fn checkout(
         return application::unprocessable("Could not complete this checkout because it contains invalid order items");
     }
 
+    if order.currencies(connection.get())?.len() > 1 {
+        return application::unprocessable(
+            "Could not complete this checkout because it contains items in more than one currency",
+        );
+    }
+
     order.set_tracking_data(req.tracking_data.clone(), Some(user.id()), connection.get())?;
 
     let order_items = order.items(connection.get())?;
@@ -301,6 +365,7 @@ pub async fn checkout(
                 phone.clone(),
                 note.clone(),
                 &user,
+                &state.config,
                 &request_info,
             )?
         }
@@ -323,7 +388,6 @@ pub async fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
                 provider.clone(),
                 true,
                 false,
@@ -340,7 +404,6 @@ pub async fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
                 *provider,
                 false,
                 false,
@@ -362,7 +425,6 @@ pub async fn checkout(
                 &mut order,
                 Some(&token),
                 &user,
-                &state.config.primary_currency,
                 *provider,
                 false,
                 *save_payment_method,
@@ -413,6 +475,7 @@ fn checkout_external(
     phone: Option<String>,
     note: Option<String>,
     user: &User,
+    config: &Config,
     request_info: &RequestInfo,
 ) -> Result<HttpResponse, ApiError> {
     let conn = conn.get();
@@ -449,7 +512,15 @@ fn checkout_external(
     }
     let guest = match guest {
         Some(g) => g,
-        None => DbUser::create_stub(first_name, last_name, email, phone, Some(user.id()), conn)?,
+        None => DbUser::create_stub(
+            first_name,
+            last_name,
+            email,
+            phone,
+            Some(user.id()),
+            &config.wallet_encryption_keys,
+            conn,
+        )?,
     };
 
     if let Some(note) = note {
@@ -474,7 +545,6 @@ async fn checkout_payment_processor(
     order: &mut Order,
     token: Option<&str>,
     auth_user: &User,
-    currency: &str,
     provider: PaymentProviders,
     use_stored_payment: bool,
     save_payment_method: bool,
@@ -502,16 +572,27 @@ async fn checkout_payment_processor(
     };
 
     let event = events.remove(0);
+    let currency = order.set_currency(connection)?;
 
-    let client = service_locator.create_payment_processor(provider, &event.organization(connection)?)?;
+    let organization = event.organization(connection)?;
+    let client = service_locator.create_payment_processor(provider, &organization)?;
     match client.behavior() {
         PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
             return redirect_to_payment_page(&*behavior, &auth_user.user, order, conn.get(), config).await;
         }
         PaymentProcessorBehavior::AuthThenComplete(behavior) => {
+            let payment_method_organization_id = if behavior.repeat_tokens_per_organization() {
+                Some(organization.id)
+            } else {
+                None
+            };
             let token = if use_stored_payment {
                 info!("CART: Using stored payment");
-                match auth_user.user.payment_method(provider, connection).optional()? {
+                match auth_user
+                    .user
+                    .payment_method(provider, payment_method_organization_id, connection)
+                    .optional()?
+                {
                     Some(payment_method) => payment_method.provider,
                     None => {
                         return application::unprocessable(
@@ -530,7 +611,11 @@ async fn checkout_payment_processor(
 
                 if save_payment_method {
                     info!("CART: User has requested to save the payment method");
-                    match auth_user.user.payment_method(provider, connection).optional()? {
+                    match auth_user
+                        .user
+                        .payment_method(provider, payment_method_organization_id, connection)
+                        .optional()?
+                    {
                         Some(payment_method) => {
                             let behavior = match client.behavior() {
                                     PaymentProcessorBehavior::AuthThenComplete(b) => b,
@@ -542,11 +627,12 @@ async fn checkout_payment_processor(
                                 .update_repeat_token(&payment_method.provider, token, SITE_NAME)
                                 .await?;
                             let payment_method_parameters = PaymentMethodEditableAttributes {
+                                provider: Some(client_response.token.clone()),
                                 provider_data: Some(client_response.to_json()?),
                             };
                             payment_method.update(&payment_method_parameters, auth_user.id(), connection)?;
 
-                            payment_method.provider
+                            client_response.token
                         }
                         None => {
                             let behavior = match client.behavior() {
@@ -562,6 +648,7 @@ async fn checkout_payment_processor(
                                 set_default,
                                 repeat_token.token.clone(),
                                 repeat_token.to_json()?,
+                                payment_method_organization_id,
                             )
                             .commit(auth_user.id(), connection)?;
                             repeat_token.token
@@ -575,7 +662,7 @@ async fn checkout_payment_processor(
             return auth_then_complete(
                 &*behavior,
                 token,
-                currency,
+                &currency,
                 order,
                 auth_user,
                 conn,
@@ -600,10 +687,12 @@ async fn auth_then_complete(
     let connection = conn.get();
     info!("CART: Auth'ing to payment provider");
     let amount = order.calculate_total(connection)?;
+    let application_fee = order.calculate_company_fee_total(connection)?;
     let auth_result = client
         .auth(
             &token,
             amount,
+            application_fee,
             currency,
             SITE_NAME,
             order.purchase_metadata(connection)?,
@@ -616,7 +705,11 @@ async fn auth_then_complete(
         amount,
         client.payment_provider(),
         auth_result.id.clone(),
-        PaymentStatus::Authorized,
+        if auth_result.requires_action() {
+            PaymentStatus::RequiresAction
+        } else {
+            PaymentStatus::Authorized
+        },
         auth_result.to_json()?,
         connection,
     ) {
@@ -627,6 +720,15 @@ async fn auth_then_complete(
         }
     };
 
+    if let Some(client_secret) = auth_result.client_secret {
+        info!("CART: Payment requires additional authentication");
+        payment.mark_requires_action(Some(auth_user.id()), connection)?;
+        let order = Order::find(order.id, connection)?;
+        let mut response = serde_json::to_value(order.for_display(None, auth_user.id(), connection)?)?;
+        response["client_secret"] = json!(client_secret);
+        return Ok(HttpResponse::Accepted().json(response));
+    }
+
     conn.commit_transaction()?;
     conn.begin_transaction()?;
 
@@ -647,6 +749,100 @@ async fn auth_then_complete(
     }
 }
 
+/// Completes a cart once the customer has finished authenticating its payment with their card issuer
+pub async fn confirm(
+    (connection, path, user, state): (Connection, Path<PathParameters>, User, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    let conn = connection.get();
+    let order = Order::find(path.id, conn)?;
+    if order.user_id != user.id() {
+        return application::forbidden("This cart does not belong to you");
+    } else if order.status != OrderStatus::PendingAuthentication {
+        return application::unprocessable("Could not confirm this cart because it is not awaiting authentication");
+    }
+
+    let payment = match order
+        .payments(conn)?
+        .into_iter()
+        .find(|p| p.status == PaymentStatus::RequiresAction)
+    {
+        Some(payment) => payment,
+        None => return application::unprocessable("Could not find a payment awaiting authentication"),
+    };
+
+    if !complete_authenticated_payment(&connection, &payment, Some(user.id()), &state.service_locator).await? {
+        return application::unprocessable("Payment still requires additional authentication");
+    }
+
+    let order = Order::find(order.id, conn)?;
+    Ok(HttpResponse::Ok().json(json!(order.for_display(None, user.id(), conn)?)))
+}
+
+/// Captures a payment that required additional authentication, completing its order. Returns `false`
+/// if the customer has not finished authenticating. Failed authentication returns the order to the cart.
+pub async fn complete_authenticated_payment(
+    conn: &Connection,
+    payment: &Payment,
+    current_user_id: Option<Uuid>,
+    service_locator: &ServiceLocator,
+) -> Result<bool, ApiError> {
+    let connection = conn.get();
+    let mut order = Order::find(payment.order_id, connection)?;
+    // Confirmation calls and webhooks can arrive together, lock the order so only one captures the payment
+    order.lock_version(connection)?;
+    let payment = Payment::find(payment.id, connection)?;
+    if payment.status != PaymentStatus::RequiresAction {
+        return Ok(payment.status == PaymentStatus::Completed);
+    }
+    let auth_token = match payment.external_reference {
+        Some(ref external_reference) => external_reference.clone(),
+        None => return Err(ApplicationError::new("Payment has no external reference".to_string()).into()),
+    };
+
+    let mut organizations = order.organizations(connection)?;
+    if organizations.len() != 1 {
+        return Err(ApplicationError::new(
+            "Orders containing more than one organization are not supported".to_string(),
+        )
+        .into());
+    }
+    let payment_processor = service_locator.create_payment_processor(payment.provider, &organizations.remove(0))?;
+    let behavior = match payment_processor.behavior() {
+        PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+        _ => {
+            return Err(ApplicationError::new_with_type(
+                ApplicationErrorType::Unprocessable,
+                "Payment provider does not support additional authentication".to_string(),
+            )
+            .into())
+        }
+    };
+
+    let auth_result = match behavior.find_authed_charge(&auth_token).await {
+        Ok(auth_result) => auth_result,
+        Err(e) => {
+            payment.mark_authentication_failed(json!({ "error": e.to_string() }), current_user_id, connection)?;
+            // Keep the cancelled payment when the error is returned
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+            return Err(e.into());
+        }
+    };
+    if auth_result.requires_action() {
+        return Ok(false);
+    }
+
+    if order.is_expired() {
+        order.try_refresh_expired_cart(current_user_id, connection)?;
+    }
+
+    info!("CART: Completing authenticated charge with payment provider");
+    let charge_result = behavior.complete_authed_charge(&auth_token).await?;
+    payment.mark_complete(charge_result.to_json()?, current_user_id, connection)?;
+
+    Ok(true)
+}
+
 async fn redirect_to_payment_page(
     client: &dyn RedirectToPaymentPageBehavior,
     user: &DbUser,
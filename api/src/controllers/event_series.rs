@@ -0,0 +1,171 @@
+use crate::auth::user::User as AuthUser;
+use crate::controllers::ticket_types;
+use crate::database::Connection;
+use crate::domain_events::executors::UpdateGenresPayload;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::{EventSeriesTicketTypePathParameters, PathParameters};
+use crate::server::AppState;
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use db::models::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateEventSeriesRequest {
+    pub template_event_id: Uuid,
+    pub name: String,
+    /// RRULE subset, e.g. `FREQ=WEEKLY;BYDAY=TH;COUNT=12`
+    pub recurrence_rule: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DisplayEventSeries {
+    #[serde(flatten)]
+    pub event_series: EventSeries,
+    pub events: Vec<Event>,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReadEvents, &organization, connection)?;
+
+    let event_series = EventSeries::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&event_series))
+}
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    user.requires_scope_for_organization(
+        Scopes::OrgReadEvents,
+        &event_series.organization(connection)?,
+        connection,
+    )?;
+
+    Ok(HttpResponse::Ok().json(&for_display(event_series, connection)?))
+}
+
+/// Turns the template event into a recurring series, creating an occurrence for every upcoming
+/// date in the recurrence rule
+pub async fn create(
+    (connection, json, user, state): (Connection, Json<CreateEventSeriesRequest>, AuthUser, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let template = Event::find(json.template_event_id, connection)?;
+    let organization = template.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::EventClone, &organization, &template, connection)?;
+
+    let json = json.into_inner();
+    let event_series = EventSeries::create(organization.id, template.id, json.name, json.recurrence_rule)
+        .commit(Some(user.id()), connection)?;
+    let occurrences = event_series.generate_occurrences(Some(user.id()), connection)?;
+    occurrences_created(&occurrences, &user, &state, connection)?;
+
+    Ok(HttpResponse::Created().json(&for_display(event_series, connection)?))
+}
+
+/// Updates the series, occurrences for dates added to the recurrence rule are created
+pub async fn update(
+    (connection, path, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventSeriesEditableAttributes>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    requires_scope_for_series(Scopes::EventWrite, &event_series, &user, connection)?;
+
+    let event_series = event_series.update(Some(user.id()), json.into_inner(), connection)?;
+    let occurrences = event_series.generate_occurrences(Some(user.id()), connection)?;
+    occurrences_created(&occurrences, &user, &state, connection)?;
+
+    Ok(HttpResponse::Ok().json(&for_display(event_series, connection)?))
+}
+
+/// Applies the changes to the template and every upcoming occurrence of the series
+pub async fn update_events(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    requires_scope_for_series(Scopes::EventWrite, &event_series, &user, connection)?;
+
+    let events = event_series.update_events(Some(user.id()), json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&events))
+}
+
+/// Applies the changes to a ticket type of the template and its copies on every upcoming occurrence
+pub async fn update_ticket_type(
+    (connection, path, json, user): (
+        Connection,
+        Path<EventSeriesTicketTypePathParameters>,
+        Json<TicketTypeEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(path.id, connection)?;
+    requires_scope_for_series(Scopes::TicketTypeWrite, &event_series, &user, connection)?;
+
+    let ticket_types =
+        event_series.update_ticket_types(path.ticket_type_id, Some(user.id()), json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&ticket_types))
+}
+
+fn for_display(event_series: EventSeries, connection: &PgConnection) -> Result<DisplayEventSeries, ApiError> {
+    let events = event_series.events(connection)?;
+    Ok(DisplayEventSeries { event_series, events })
+}
+
+/// Changes to the series are made through its template so event limited roles need access to it
+fn requires_scope_for_series(
+    scope: Scopes,
+    event_series: &EventSeries,
+    user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    let template = event_series.template_event(connection)?;
+    user.requires_scope_for_organization_event(scope, &event_series.organization(connection)?, &template, connection)
+}
+
+fn occurrences_created(
+    occurrences: &[Event],
+    user: &AuthUser,
+    state: &Data<AppState>,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    for event in occurrences {
+        // Clone tickets on blockchain (TODO: should be moved to background job as part of ticket type create)
+        let ticket_types = event.ticket_types(false, None, connection)?;
+        ticket_types::create_ticket_type_blockchain_assets(event, &ticket_types, state, connection)?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::UpdateGenres,
+            None,
+            json!(UpdateGenresPayload { user_id: user.id() }),
+            Some(Tables::Events),
+            Some(event.id),
+        )
+        .commit(connection)?;
+    }
+
+    Ok(())
+}
@@ -23,6 +23,8 @@ use chrono::prelude::*;
 use chrono::Duration;
 use db::dev::times;
 use db::prelude::*;
+use db::utils::hash::hmac_sha256;
+use db::utils::rand::random_alpha_string;
 use diesel::PgConnection;
 use serde::Serialize;
 use serde_json::Value;
@@ -637,7 +639,14 @@ pub async fn ticket_holder_count(
 #[derive(Deserialize, Serialize, Debug)]
 pub struct TicketRedeemRequest {
     pub redeem_key: String,
+    /// Required when redeeming with a rotating code as those are only unique per ticket
+    #[serde(default)]
+    pub ticket_id: Option<Uuid>,
+    /// Treated as a scan for events using rotating codes when omitted
     pub check_in_source: Option<CheckInSource>,
+    /// Gate the ticket is scanned at
+    #[serde(default)]
+    pub access_zone_id: Option<Uuid>,
 }
 
 pub async fn redeem_ticket(
@@ -654,15 +663,21 @@ pub async fn redeem_ticket(
     let db_event = Event::find(parameters.id, connection)?;
     let organization = db_event.organization(connection)?;
     auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &db_event, connection)?;
-    let ticket =
-        TicketInstance::find_by_event_id_redeem_key(parameters.id, redeem_parameters.redeem_key.clone(), connection)?;
+    let check_in_source = check_in_source(&db_event, &organization, &redeem_parameters, &auth_user, connection)?;
+    let ticket = find_ticket_to_scan(&db_event, &redeem_parameters, connection)?;
     let redeemable = TicketInstance::show_redeemable_ticket(ticket.id, connection)?;
+    if let Some(access_zone_id) = redeem_parameters.access_zone_id {
+        if !AccessZone::find(access_zone_id, connection)?.is_for_event(&db_event) {
+            return application::unprocessable("Access zone is not available for this event");
+        }
+    }
 
-    let result = TicketInstance::redeem_ticket(
+    let result = TicketInstance::redeem_ticket_for_access_zone(
         ticket.id,
         redeem_parameters.redeem_key.clone(),
         auth_user.id(),
-        redeem_parameters.check_in_source.unwrap_or(CheckInSource::GuestList),
+        check_in_source,
+        redeem_parameters.access_zone_id,
         connection,
     )?;
 
@@ -673,7 +688,7 @@ pub async fn redeem_ticket(
             match asset.blockchain_asset_id {
                 Some(a) => {
                     let wallet = Wallet::find(ticket.wallet_id, connection)?;
-                    state.config.tari_client.modify_asset_redeem_token(&wallet.secret_key, &wallet.public_key,
+                    state.config.tari_client.modify_asset_redeem_token(&wallet.secret_key(&state.config.wallet_encryption_keys)?, &wallet.public_key,
                                                                        &a,
                                                                        vec![ticket.token_id as u64],
                     )?;
@@ -696,6 +711,10 @@ pub async fn redeem_ticket(
                 None => Ok(HttpResponse::BadRequest().json(json!({ "error": "Could not redeem because the asset has not been assigned on the blockchain.".to_string()}))),
             }
         }
+        RedeemResults::TicketReentrySuccess => {
+            // Already redeemed on chain when the ticket first entered
+            Ok(HttpResponse::Ok().json(redeemable))
+        }
         RedeemResults::TicketTransferInProcess => {
             Ok(HttpResponse::BadRequest()
                 .json(json!({"error": "Ticket has pending transfer in progress.".to_string()})))
@@ -705,12 +724,250 @@ pub async fn redeem_ticket(
         "redeemed_by": redeemable.redeemed_by,
         "redeemed_at": redeemable.redeemed_at
         }))),
-        RedeemResults::TicketInvalid => {
+        RedeemResults::TicketReentryNotAllowed => Ok(HttpResponse::Conflict().json(json!({
+        "error": "Ticket is not allowed to re-enter.".to_string(),
+        "redeemed_by": redeemable.redeemed_by,
+        "redeemed_at": redeemable.redeemed_at
+        }))),
+        RedeemResults::TicketNotValidForAccessZone => {
+            Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket is not valid for this gate.".to_string()})))
+        }
+        RedeemResults::TicketInvalid | RedeemResults::TicketCheckedOut | RedeemResults::TicketNotCheckedIn => {
             Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket is invalid.".to_string()})))
         }
     }
 }
 
+/// The static redeem key of events using rotating codes is only accepted for guest list check-ins, which
+/// require box office access rather than trusting the source sent by the client
+fn check_in_source(
+    event: &Event,
+    organization: &Organization,
+    redeem_parameters: &TicketRedeemRequest,
+    user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<CheckInSource, ApiError> {
+    match redeem_parameters.check_in_source {
+        None if event.rotating_redeem_codes => Ok(CheckInSource::Scanned),
+        None => Ok(CheckInSource::GuestList),
+        Some(CheckInSource::GuestList) if event.rotating_redeem_codes => {
+            user.requires_scope_for_organization_event(Scopes::BoxOfficeTicketRead, organization, event, connection)?;
+            Ok(CheckInSource::GuestList)
+        }
+        Some(check_in_source) => Ok(check_in_source),
+    }
+}
+
+/// Rotating codes are looked up by the ticket id sent along with them, static keys are unique per
+/// event
+fn find_ticket_to_scan(
+    event: &Event,
+    redeem_parameters: &TicketRedeemRequest,
+    connection: &PgConnection,
+) -> Result<TicketInstance, ApiError> {
+    match redeem_parameters.ticket_id {
+        Some(ticket_id) => {
+            let ticket = TicketInstance::find(ticket_id, connection)?;
+            if ticket.event(connection)?.id != event.id {
+                return Err(NotFoundError {}.into());
+            }
+            Ok(ticket)
+        }
+        None => Ok(TicketInstance::find_by_event_id_redeem_key(
+            event.id,
+            redeem_parameters.redeem_key.clone(),
+            connection,
+        )?),
+    }
+}
+
+pub async fn check_out_ticket(
+    (connection, parameters, redeem_parameters, auth_user): (
+        Connection,
+        Path<PathParameters>,
+        Json<TicketRedeemRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let db_event = Event::find(parameters.id, connection)?;
+    let organization = db_event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &db_event, connection)?;
+    let ticket = find_ticket_to_scan(&db_event, &redeem_parameters, connection)?;
+
+    let result = TicketInstance::check_out_ticket(
+        ticket.id,
+        redeem_parameters.redeem_key.clone(),
+        auth_user.id(),
+        connection,
+    )?;
+
+    match result {
+        RedeemResults::TicketCheckedOut => {
+            Ok(HttpResponse::Ok().json(TicketScan::find_for_ticket_instance(ticket.id, connection)?))
+        }
+        RedeemResults::TicketNotCheckedIn => {
+            Ok(HttpResponse::Conflict().json(json!({"error": "Ticket is not checked in.".to_string()})))
+        }
+        _ => Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket is invalid.".to_string()}))),
+    }
+}
+
+pub async fn occupancy(
+    (connection, parameters, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::ScanReportRead, &organization, &event, connection)?;
+
+    Ok(HttpResponse::Ok().json(TicketScan::occupancy_for_event(event.id, connection)?))
+}
+
+/// Claims of the signed snapshot returned to door scanners
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ScannerSnapshot {
+    pub event_id: Uuid,
+    pub generated_at: NaiveDateTime,
+    pub salt: String,
+    /// Returned with offline scans to show they were made against this snapshot
+    pub upload_token: String,
+    /// Offline scans are no longer accepted for the snapshot after this time
+    pub exp: i64,
+    pub tickets: Vec<ScannerSnapshotTicket>,
+    /// Access zones each ticket type opens, keyed by ticket type id
+    pub ticket_type_access_zones: HashMap<Uuid, Vec<Uuid>>,
+}
+
+impl ScannerSnapshot {
+    pub fn upload_token(secret: &str, event_id: Uuid, generated_at: NaiveDateTime, salt: &str) -> String {
+        hmac_sha256::sign(secret, &format!("{}.{}.{}", event_id, generated_at.timestamp(), salt))
+    }
+}
+
+/// Snapshot of the redeemable tickets for an event allowing door staff to keep scanning when the
+/// device loses connectivity. The snapshot is signed like an access token so devices can verify it
+/// against the published keys. Scans made offline are uploaded through `redeem_offline`.
+pub async fn scanner_snapshot(
+    (connection, parameters, auth_user, state): (Connection, Path<PathParameters>, AuthUser, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    let generated_at = Utc::now().naive_utc();
+    let salt = random_alpha_string(16);
+    let upload_token =
+        ScannerSnapshot::upload_token(&state.config.scanner_snapshot_secret, event.id, generated_at, &salt);
+    let tickets = TicketInstance::find_for_scanner_snapshot(event.id, &salt, connection)?;
+    let mut ticket_type_access_zones = HashMap::new();
+    for ticket_type in event.ticket_types(false, None, connection)? {
+        let access_zone_ids: Vec<Uuid> = ticket_type
+            .access_zones(connection)?
+            .into_iter()
+            .map(|z| z.id)
+            .collect();
+        ticket_type_access_zones.insert(ticket_type.id, access_zone_ids);
+    }
+
+    let snapshot = state.config.token_issuer.sign(&ScannerSnapshot {
+        event_id: event.id,
+        generated_at,
+        salt,
+        upload_token,
+        exp: (generated_at + state.config.scanner_snapshot_max_age).timestamp(),
+        tickets,
+        ticket_type_access_zones,
+    })?;
+    Ok(HttpResponse::Ok().json(json!({ "snapshot": snapshot })))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OfflineRedeemRequest {
+    pub generated_at: NaiveDateTime,
+    pub salt: String,
+    pub upload_token: String,
+    pub scans: Vec<OfflineScan>,
+}
+
+pub async fn redeem_offline(
+    (connection, parameters, redeem_parameters, auth_user, state, cache_database): (
+        Connection,
+        Path<PathParameters>,
+        Json<OfflineRedeemRequest>,
+        AuthUser,
+        Data<AppState>,
+        CacheDatabase,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    // Scans must have been made against a recent snapshot issued for this event
+    let expected_upload_token = ScannerSnapshot::upload_token(
+        &state.config.scanner_snapshot_secret,
+        event.id,
+        redeem_parameters.generated_at,
+        &redeem_parameters.salt,
+    );
+    if expected_upload_token != redeem_parameters.upload_token {
+        return application::unprocessable("Scanner snapshot is invalid");
+    }
+    if redeem_parameters.generated_at + state.config.scanner_snapshot_max_age < Utc::now().naive_utc() {
+        return application::unprocessable("Scanner snapshot has expired");
+    }
+    let access_zone_ids: Vec<Uuid> = AccessZone::find_for_event(&event, connection)?
+        .into_iter()
+        .map(|z| z.id)
+        .collect();
+    if redeem_parameters
+        .scans
+        .iter()
+        .filter_map(|s| s.access_zone_id)
+        .any(|id| !access_zone_ids.contains(&id))
+    {
+        return application::unprocessable("Access zone is not available for this event");
+    }
+
+    let results = TicketInstance::redeem_offline_scans(event.id, &redeem_parameters.scans, auth_user.id(), connection)?;
+
+    for result in results
+        .iter()
+        .filter(|r| r.result == RedeemResults::TicketRedeemSuccess)
+    {
+        //Redeem ticket on chain
+        let ticket = TicketInstance::find(result.ticket_id, connection)?;
+        let asset = Asset::find(ticket.asset_id, connection)?;
+        if let Some(blockchain_asset_id) = asset.blockchain_asset_id {
+            let wallet = Wallet::find(ticket.wallet_id, connection)?;
+            state.config.tari_client.modify_asset_redeem_token(
+                &wallet.secret_key(&state.config.wallet_encryption_keys)?,
+                &wallet.public_key,
+                &blockchain_asset_id,
+                vec![ticket.token_id as u64],
+            )?;
+        }
+
+        cache_database.inner.clone().and_then(|conn| {
+            caching::publish(
+                conn,
+                RedisPubSubChannel::TicketRedemptions,
+                messages::TicketRedemption {
+                    ticket_id: ticket.id,
+                    event_id: event.id,
+                    redeemer_id: auth_user.id(),
+                },
+            )
+            .ok()
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 pub async fn show_from_organizations(
     (connection, path, paging, user): (Connection, Path<PathParameters>, Query<PagingParameters>, AuthUser),
 ) -> Result<WebPayload<EventSummaryResult>, ApiError> {
@@ -1,10 +1,10 @@
 use crate::auth::user::User as AuthUser;
-use crate::auth::TokenResponse;
+use crate::auth::{LoginResponse, TokenResponse};
 use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
-use crate::models::FacebookWebLoginToken;
+use crate::models::{FacebookWebLoginToken, RequestInfo};
 use crate::server::AppState;
 use actix_web::{web::Data, HttpResponse};
 use db::prelude::*;
@@ -30,7 +30,13 @@ struct FacebookGraphResponse {
 
 // TODO: Not covered by tests
 pub async fn web_login(
-    (state, connection, auth_token, auth_user): (Data<AppState>, Connection, Json<FacebookWebLoginToken>, OptionalUser),
+    (state, connection, auth_token, auth_user, request_info): (
+        Data<AppState>,
+        Connection,
+        Json<FacebookWebLoginToken>,
+        OptionalUser,
+        RequestInfo,
+    ),
 ) -> Result<HttpResponse, ApiError> {
     let url = format!("{}/me?fields=id,email,first_name,last_name", FACEBOOK_GRAPH_URL);
     let connection = connection.get();
@@ -66,6 +72,8 @@ pub async fn web_login(
             &*state.config.token_issuer,
             state.config.jwt_expiry_time,
             &auth_user.user,
+            &request_info,
+            connection,
         )?;
         return Ok(HttpResponse::Ok().json(response));
     }
@@ -109,6 +117,7 @@ pub async fn web_login(
                                 auth_token.access_token.clone(),
                                 vec![],
                                 None,
+                                &state.config.wallet_encryption_keys,
                                 connection,
                             )?,
                             _ => return Err(e.into()),
@@ -125,12 +134,19 @@ pub async fn web_login(
                     auth_token.access_token.clone(),
                     vec![],
                     None,
+                    &state.config.wallet_encryption_keys,
                     connection,
                 )?
             }
         }
     };
-    let response = TokenResponse::create_from_user(&*state.config.token_issuer, state.config.jwt_expiry_time, &user)?;
+    let response = LoginResponse::create_from_user(
+        &*state.config.token_issuer,
+        state.config.jwt_expiry_time,
+        &user,
+        &request_info,
+        connection,
+    )?;
     Ok(HttpResponse::Ok().json(response))
 }
 
@@ -0,0 +1,134 @@
+use crate::auth::{LoginResponse, TokenResponse};
+use crate::config::OidcProviderConfig;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{OidcWebLoginToken, RequestInfo, StringPathParameters};
+use crate::server::AppState;
+use crate::utils::oidc::{self, IdTokenClaims};
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use db::prelude::*;
+use db::utils::encryption::KeyRing;
+use diesel::PgConnection;
+
+/// Logs in with an ID token from a configured OpenID Connect provider such as Google or Apple
+pub async fn web_login(
+    (state, connection, path, login_token, auth_user, request_info): (
+        Data<AppState>,
+        Connection,
+        Path<StringPathParameters>,
+        Json<OidcWebLoginToken>,
+        OptionalUser,
+        RequestInfo,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let provider = match state.config.oidc_providers.iter().find(|p| p.name == path.id) {
+        Some(provider) => provider,
+        None => return application::not_found(),
+    };
+    let claims = oidc::verify_id_token(provider, &login_token.id_token).await?;
+    let connection = connection.get();
+
+    if login_token.link_to_user_id {
+        let auth_user = match auth_user.into_inner() {
+            Some(auth_user) => auth_user,
+            None => {
+                return application::unauthorized_with_message(
+                    &format!("User must be logged in to link {}", provider.name),
+                    None,
+                    None,
+                )
+            }
+        };
+        auth_user.user.add_or_replace_external_login(
+            Some(auth_user.id()),
+            claims.sub.clone(),
+            provider.site.clone(),
+            String::new(),
+            vec![],
+            connection,
+        )?;
+        let response = TokenResponse::create_from_user(
+            &*state.config.token_issuer,
+            state.config.jwt_expiry_time,
+            &auth_user.user,
+            &request_info,
+            connection,
+        )?;
+        return Ok(HttpResponse::Ok().json(response));
+    }
+
+    let user = match ExternalLogin::find_user(&claims.sub, &provider.site, connection)? {
+        Some(external_login) => User::find(external_login.user_id, connection)?,
+        None => find_or_create_user(
+            provider,
+            &claims,
+            &login_token,
+            &state.config.wallet_encryption_keys,
+            connection,
+        )?,
+    };
+    if user.deleted_at.is_some() {
+        return application::forbidden("This account has been deleted");
+    }
+
+    let response = LoginResponse::create_from_user(
+        &*state.config.token_issuer,
+        state.config.jwt_expiry_time,
+        &user,
+        &request_info,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Existing accounts are only linked when the provider has verified the email address
+fn find_or_create_user(
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+    login_token: &OidcWebLoginToken,
+    key_ring: &KeyRing,
+    connection: &PgConnection,
+) -> Result<User, ApiError> {
+    let email = claims.verified_email();
+    if let Some(email) = email {
+        if let Some(user) = User::find_by_email(email, true, connection).optional()? {
+            if user.deleted_at.is_none() {
+                user.add_or_replace_external_login(
+                    None,
+                    claims.sub.clone(),
+                    provider.site.clone(),
+                    String::new(),
+                    vec![],
+                    connection,
+                )?;
+            }
+            return Ok(user);
+        }
+    }
+
+    Ok(User::create_from_external_login(
+        claims.sub.clone(),
+        claims
+            .given_name
+            .clone()
+            .or_else(|| login_token.first_name.clone())
+            .unwrap_or_default(),
+        claims
+            .family_name
+            .clone()
+            .or_else(|| login_token.last_name.clone())
+            .unwrap_or_default(),
+        email.map(|e| e.to_string()),
+        provider.site.clone(),
+        String::new(),
+        vec![],
+        None,
+        key_ring,
+        connection,
+    )?)
+}
@@ -1,7 +1,13 @@
 use crate::database::Connection;
 use crate::errors::ApiError;
 use crate::extractors::Json;
-use actix_web::HttpResponse;
+use crate::helpers::application;
+use crate::payments::stripe::verify_webhook_signature;
+use crate::payments::PaymentProcessorError;
+use crate::server::AppState;
+use ::stripe::WebhookEvent;
+use actix_web::{web::Data, HttpRequest, HttpResponse};
+use chrono::prelude::*;
 use db::prelude::*;
 use db::utils::dates::IntoDateBuilder;
 use globee::GlobeeIpnRequest;
@@ -33,3 +39,38 @@ pub async fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Resul
 
     Ok(HttpResponse::Ok().finish())
 }
+
+/// Stripe webhook endpoint. Verified events are queued for processing so failures can be retried.
+pub async fn stripe(
+    (state, request, payload, conn): (Data<AppState>, HttpRequest, String, Connection),
+) -> Result<HttpResponse, ApiError> {
+    let webhook_secret = match state.config.stripe_webhook_secret {
+        Some(ref webhook_secret) => webhook_secret,
+        None => return application::unprocessable("Stripe webhooks are not enabled"),
+    };
+    let signature_header = request
+        .headers()
+        .get("Stripe-Signature")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    if !verify_webhook_signature(&payload, signature_header, webhook_secret, Utc::now().timestamp()) {
+        return application::bad_request("Invalid Stripe signature");
+    }
+
+    let event = WebhookEvent::from_str(&payload).map_err(PaymentProcessorError::from)?;
+    jlog!(Debug, "Stripe webhook received", { "event_id": &event.id, "type": &event.event_type });
+
+    let mut action = DomainAction::create(
+        None,
+        DomainActionTypes::ProcessStripeWebhook,
+        None,
+        json!(event),
+        None,
+        None,
+    );
+    action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+    action.max_attempt_count = 5;
+    action.commit(conn.get())?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,15 +1,18 @@
+pub mod access_zones;
 pub mod admin;
 pub mod analytics;
 pub mod announcements;
 pub mod artists;
 pub mod auth;
 pub mod broadcasts;
+pub mod bundles;
 pub mod cart;
 pub mod codes;
 pub mod collection_items;
 pub mod collections;
 pub mod comps;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod events;
 pub mod external;
 pub mod genres;
@@ -18,16 +21,21 @@ pub mod ipns;
 pub mod listings;
 pub mod notes;
 pub mod orders;
+pub mod organization_api_keys;
 pub mod organization_invites;
 pub mod organization_venues;
+pub mod organization_webhooks;
 pub mod organizations;
 pub mod password_resets;
 pub mod payment_methods;
 pub mod payments;
+pub mod products;
 pub mod rarities;
 pub mod redemption_codes;
 pub mod regions;
+pub mod report_exports;
 pub mod reports;
+pub mod seat_maps;
 pub mod send_download_link;
 pub mod settlement_adjustments;
 pub mod settlements;
@@ -35,10 +43,13 @@ pub mod sitemap_gen;
 pub mod slugs;
 pub mod stages;
 pub mod status;
+pub mod tax_rates;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
 pub mod user_invites;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
+pub mod webhook_deliveries;
 pub mod websockets;
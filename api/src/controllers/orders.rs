@@ -256,7 +256,7 @@ pub async fn refund(
                     };
                     let user_wallet = Wallet::find(wallet_id, connection)?;
                     state.config.tari_client.transfer_tokens(
-                        &user_wallet.secret_key,
+                        &user_wallet.secret_key(&state.config.wallet_encryption_keys)?,
                         &user_wallet.public_key,
                         &a,
                         token_ids.clone(),
@@ -375,7 +375,7 @@ pub async fn refund(
                         };
                         let user_wallet = Wallet::find(wallet_id, connection)?;
                         state.config.tari_client.transfer_tokens(
-                            &organization_wallet.secret_key,
+                            &organization_wallet.secret_key(&state.config.wallet_encryption_keys)?,
                             &organization_wallet.public_key,
                             &a,
                             token_ids.clone(),
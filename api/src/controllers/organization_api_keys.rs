@@ -0,0 +1,106 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::Json;
+use crate::helpers::application;
+use crate::models::{OrganizationApiKeyPathParameters, PathParameters};
+use actix_web::{web::Path, HttpResponse};
+use chrono::NaiveDateTime;
+use db::prelude::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewOrganizationApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub event_ids: Vec<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// Returned when an API key is created, the only time the key itself is shown
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedOrganizationApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: OrganizationApiKey,
+    pub key: String,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(OrganizationApiKey::find_for_organization(organization.id, connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewOrganizationApiKeyRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    if user.api_key.is_some() {
+        return application::unauthorized_with_message("API keys can not be used to create API keys", None, None);
+    }
+
+    let json = json.into_inner();
+    let mut scopes = Vec::new();
+    for scope in &json.scopes {
+        match scope.parse::<Scopes>() {
+            Ok(scope) => scopes.push(scope),
+            Err(_) => return application::unprocessable(&format!("Unknown scope {}", scope)),
+        }
+    }
+    // A key can never do more than the user creating it
+    for scope in &scopes {
+        if !user.has_scope_for_organization(*scope, &organization, connection)? {
+            return application::unauthorized_with_message(
+                &format!("User does not have the {} scope for this organization", scope),
+                Some(user),
+                None,
+            );
+        }
+    }
+
+    let (new_api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        user.id(),
+        json.name,
+        scopes,
+        json.event_ids,
+        json.expires_at,
+    );
+    let api_key = new_api_key.commit(connection)?;
+
+    Ok(HttpResponse::Created().json(CreatedOrganizationApiKeyResponse { api_key, key }))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<OrganizationApiKeyPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let api_key = find_api_key_for_organization(&path, &user, connection)?;
+    Ok(HttpResponse::Ok().json(api_key.revoke(Some(&user.user), connection)?))
+}
+
+fn find_api_key_for_organization(
+    path: &OrganizationApiKeyPathParameters,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<OrganizationApiKey, ApiError> {
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let api_key = OrganizationApiKey::find(path.api_key_id, connection)?;
+    if api_key.organization_id != organization.id {
+        return Err(NotFoundError {}.into());
+    }
+    Ok(api_key)
+}
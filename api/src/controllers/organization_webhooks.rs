@@ -0,0 +1,135 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::domain_events::webhook_publisher::WebhookPublisher;
+use crate::errors::*;
+use crate::extractors::Json;
+use crate::models::{OrganizationWebhookPathParameters, PathParameters};
+use actix_web::{web::Path, HttpResponse};
+use db::prelude::*;
+use diesel::PgConnection;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewOrganizationWebhookRequest {
+    pub webhook_url: String,
+    pub event_types: Vec<DomainEventTypes>,
+}
+
+/// Historic events are never imported for organization webhooks, they only receive events raised
+/// after they are created
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UpdateOrganizationWebhookRequest {
+    pub webhook_url: Option<String>,
+    pub event_types: Option<Vec<DomainEventTypes>>,
+}
+
+/// Returned when a webhook is created, the only time the signing secret is shown
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedOrganizationWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: DomainEventPublisher,
+    pub webhook_secret: String,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(DomainEventPublisher::find_by_organization_id(
+        organization.id,
+        connection,
+    )?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewOrganizationWebhookRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let json = json.into_inner();
+    let webhook =
+        DomainEventPublisher::create(Some(organization.id), json.event_types, json.webhook_url).commit(connection)?;
+
+    Ok(HttpResponse::Created().json(CreatedOrganizationWebhookResponse {
+        webhook_secret: webhook.webhook_secret.clone(),
+        webhook,
+    }))
+}
+
+pub async fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrganizationWebhookPathParameters>,
+        Json<UpdateOrganizationWebhookRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook_for_organization(&path, &user, connection)?;
+    let json = json.into_inner();
+    let attributes = DomainEventPublisherEditableAttributes {
+        webhook_url: json.webhook_url,
+        import_historic_events: None,
+        event_types: json.event_types,
+    };
+    Ok(HttpResponse::Ok().json(webhook.update(&attributes, connection)?))
+}
+
+pub async fn pause(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook_for_organization(&path, &user, connection)?;
+    Ok(HttpResponse::Ok().json(webhook.pause(connection)?))
+}
+
+pub async fn resume(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook_for_organization(&path, &user, connection)?;
+    Ok(HttpResponse::Ok().json(webhook.resume(connection)?))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook_for_organization(&path, &user, connection)?;
+    webhook.delete(connection)?;
+    Ok(HttpResponse::Ok().json({}))
+}
+
+/// Queues a signed test delivery to the webhook URL, sent even while the webhook is paused
+pub async fn test(
+    (connection, path, user): (Connection, Path<OrganizationWebhookPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook_for_organization(&path, &user, connection)?;
+    let webhook_delivery =
+        WebhookDelivery::create(webhook.id, None, json!(WebhookPublisher::create_test_payload(&webhook)))
+            .commit(connection)?;
+    webhook_delivery.queue(connection)?;
+    Ok(HttpResponse::Ok().json(webhook_delivery))
+}
+
+fn find_webhook_for_organization(
+    path: &OrganizationWebhookPathParameters,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<DomainEventPublisher, ApiError> {
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let webhook = DomainEventPublisher::find(path.webhook_id, connection)?;
+    if webhook.organization_id != Some(organization.id) || webhook.deleted_at.is_some() {
+        return Err(NotFoundError {}.into());
+    }
+    Ok(webhook)
+}
@@ -28,6 +28,7 @@ pub struct FeeScheduleWithRanges {
     pub name: String,
     pub version: i16,
     pub created_at: NaiveDateTime,
+    pub currency: String,
     pub ranges: Vec<FeeScheduleRange>,
 }
 
@@ -62,6 +63,16 @@ pub struct NewOrganizationRequest {
     pub cc_fee_percent: Option<f32>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub braintree_public_key: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub braintree_private_key: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub braintree_merchant_account_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub stripe_connect_account_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -155,6 +166,16 @@ pub async fn create(
             None => state.config.max_instances_per_ticket_type,
         }),
         settlement_type: new_organization.settlement_type,
+        braintree_public_key: new_organization.braintree_public_key.clone(),
+        braintree_private_key: new_organization.braintree_private_key.clone(),
+        braintree_merchant_account_id: new_organization.braintree_merchant_account_id.clone(),
+        stripe_connect_account_id: new_organization.stripe_connect_account_id.clone(),
+        currency: Some(
+            new_organization
+                .currency
+                .clone()
+                .unwrap_or(state.config.primary_currency.to_uppercase()),
+        ),
     };
 
     let mut organization = new_organization_with_fee_schedule.commit(
@@ -166,7 +187,12 @@ pub async fn create(
 
     organization.decrypt(&state.config.api_keys_encryption_key)?;
 
-    Wallet::create_for_organization(organization.id, "Default".to_string(), connection)?;
+    Wallet::create_for_organization(
+        organization.id,
+        "Default".to_string(),
+        &state.config.wallet_encryption_keys,
+        connection,
+    )?;
 
     Ok(HttpResponse::Created().json(&organization))
 }
@@ -186,8 +212,12 @@ pub async fn update(
 
     if organization_update.settlement_type.is_some() {
         user.requires_scope_for_organization(Scopes::OrgModifySettlementType, &organization, conn)?;
-    } else if organization_update.max_instances_per_ticket_type.is_some() {
+    } else if organization_update.max_instances_per_ticket_type.is_some()
+        || organization_update.stripe_connect_account_id.is_some()
+    {
         user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, conn)?;
+    } else if organization_update.require_mfa_for_financial_scopes.is_some() {
+        user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, conn)?;
     } else {
         user.requires_scope_for_organization(Scopes::OrgWrite, &organization, conn)?;
     }
@@ -333,6 +363,7 @@ pub async fn show_fee_schedule(
         name: fee_schedule.name,
         version: fee_schedule.version,
         created_at: fee_schedule.created_at,
+        currency: fee_schedule.currency,
         ranges: fee_schedule_ranges,
     }))
 }
@@ -352,12 +383,15 @@ pub async fn add_fee_schedule(
     let fee_schedule_ranges = fee_schedule.ranges(connection)?;
 
     Organization::find(parameters.id, connection)?.add_fee_schedule(&fee_schedule, connection)?;
+    // Reload to pick up the organization's currency
+    let fee_schedule = FeeSchedule::find(fee_schedule.id, connection)?;
 
     Ok(HttpResponse::Created().json(FeeScheduleWithRanges {
         id: fee_schedule.id,
         name: fee_schedule.name,
         version: fee_schedule.version,
         created_at: fee_schedule.created_at,
+        currency: fee_schedule.currency,
         ranges: fee_schedule_ranges,
     }))
 }
@@ -1,9 +1,10 @@
-use crate::auth::TokenResponse;
+use crate::auth::LoginResponse;
 use crate::communications::mailers;
 use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
+use crate::models::RequestInfo;
 use crate::server::AppState;
 use actix_web::{web::Data, HttpResponse};
 use db::models::concerns::users::password_resetable::*;
@@ -46,17 +47,24 @@ pub async fn create(
 }
 
 pub async fn update(
-    (state, connection, parameters): (Data<AppState>, Connection, Json<UpdatePasswordResetParameters>),
+    (state, connection, parameters, request_info): (
+        Data<AppState>,
+        Connection,
+        Json<UpdatePasswordResetParameters>,
+        RequestInfo,
+    ),
 ) -> Result<HttpResponse, ApiError> {
     let user =
         User::consume_password_reset_token(&parameters.password_reset_token, &parameters.password, connection.get())
             .optional()?;
 
     match user {
-        Some(user) => Ok(HttpResponse::Ok().json(&TokenResponse::create_from_user(
+        Some(user) => Ok(HttpResponse::Ok().json(&LoginResponse::create_from_user(
             &*state.config.token_issuer,
             state.config.jwt_expiry_time,
             &user,
+            &request_info,
+            connection.get(),
         )?)),
         None => application::unprocessable("Password has already been reset."),
     }
@@ -0,0 +1,173 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use diesel::PgConnection;
+use serde_with::rust::double_option;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateProductRequest {
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    #[serde(default)]
+    pub quantity: i64,
+    #[serde(default)]
+    pub requires_pickup: bool,
+    #[serde(default)]
+    pub variants: Vec<UpdateProductVariant>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct UpdateProductRequest {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+    pub quantity: Option<i64>,
+    pub requires_pickup: Option<bool>,
+    pub variants: Option<Vec<UpdateProductVariant>>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RedeemProductRequest {
+    pub order_item_id: Uuid,
+    pub quantity: u32,
+}
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeRead, &organization, connection)?;
+
+    let mut products = Vec::new();
+    for product in Product::find_for_organization(organization.id, None, connection)? {
+        products.push(product.for_display(connection)?);
+    }
+    Ok(HttpResponse::Ok().json(&products))
+}
+
+/// Products that can be added to the cart alongside tickets for the event
+pub async fn index_for_event((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+
+    let mut products = Vec::new();
+    for product in Product::find_for_organization(event.organization_id, Some(event.id), connection)? {
+        products.push(product.for_display(connection)?);
+    }
+    Ok(HttpResponse::Ok().json(&products))
+}
+
+pub async fn show((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    if product.deleted_at.is_some() {
+        return application::not_found();
+    }
+
+    Ok(HttpResponse::Ok().json(&product.for_display(connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateProductRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection)?;
+    if let Some(event_id) = json.event_id {
+        let event = Event::find(event_id, connection)?;
+        user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, &organization, &event, connection)?;
+    }
+
+    let json = json.into_inner();
+    let product = Product::create(
+        organization.id,
+        json.event_id,
+        json.name,
+        json.description,
+        json.price_in_cents,
+        json.quantity,
+        json.requires_pickup,
+    )
+    .commit(Some(user.id()), connection)?;
+    if !json.variants.is_empty() {
+        product.update_variants(&json.variants, Some(user.id()), connection)?;
+    }
+
+    application::created(json!(product.for_display(connection)?))
+}
+
+pub async fn update(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<UpdateProductRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    requires_scope_for_product(&product, &user, connection)?;
+
+    let json = json.into_inner();
+    let product = product.update(
+        ProductEditableAttributes {
+            name: json.name,
+            description: json.description,
+            price_in_cents: json.price_in_cents,
+            quantity: json.quantity,
+            requires_pickup: json.requires_pickup,
+        },
+        Some(user.id()),
+        connection,
+    )?;
+    if let Some(ref variants) = json.variants {
+        product.update_variants(variants, Some(user.id()), connection)?;
+    }
+
+    Ok(HttpResponse::Ok().json(&product.for_display(connection)?))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let product = Product::find(path.id, connection)?;
+    requires_scope_for_product(&product, &user, connection)?;
+
+    product.delete(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Marks product units of a paid order as picked up at the door
+pub async fn redeem(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<RedeemProductRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    let order_item = OrderItem::find(json.order_item_id, connection)?;
+    if order_item.item_type != OrderItemTypes::Products || order_item.event_id != Some(event.id) {
+        return application::not_found();
+    }
+
+    let order_item = order_item.redeem_product(json.quantity, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&order_item))
+}
+
+fn requires_scope_for_product(product: &Product, user: &AuthUser, connection: &PgConnection) -> Result<(), ApiError> {
+    let organization = product.organization(connection)?;
+    match product.event_id {
+        Some(event_id) => {
+            let event = Event::find(event_id, connection)?;
+            user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, &organization, &event, connection)
+        }
+        None => user.requires_scope_for_organization(Scopes::TicketTypeWrite, &organization, connection),
+    }
+}
@@ -0,0 +1,27 @@
+use crate::database::Connection;
+use crate::errors::*;
+use crate::models::PathParameters;
+use crate::utils::report_exports::ReportExportDownloadStream;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+
+#[derive(Deserialize)]
+pub struct ReportExportDownloadParameters {
+    pub token: String,
+}
+
+/// Downloads a completed export using the token from the emailed link, so no login is needed
+pub async fn download(
+    (connection, path, query): (Connection, Path<PathParameters>, Query<ReportExportDownloadParameters>),
+) -> Result<HttpResponse, ApiError> {
+    let report_export = ReportExport::find_for_download(path.id, &query.token, connection.get())?;
+    let file_name = report_export.file_name();
+
+    Ok(HttpResponse::Ok()
+        .content_type(report_export.format.content_type())
+        .header("Content-Disposition", format!("attachment; filename=\"{}\"", file_name))
+        .streaming(ReportExportDownloadStream::new(report_export, connection)))
+}
@@ -3,13 +3,16 @@ use crate::database::Connection;
 use crate::errors::*;
 use crate::helpers::application;
 use crate::models::{PathParameters, WebPayload};
+use crate::server::AppState;
+use crate::utils::report_exports::{self, ReportExportParameters, ReportExportStream, ReportPages};
 use actix_web::{
     http::StatusCode,
-    web::{Path, Query},
+    web::{Data, Path, Query},
     HttpResponse,
 };
 use chrono::prelude::*;
 use db::models::*;
+use diesel::PgConnection;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::str;
@@ -25,6 +28,21 @@ pub struct ReportQueryParameters {
     query: Option<String>,
     page: Option<u32>,
     limit: Option<u32>,
+    /// `csv` or `xlsx` to export every row of the report instead of a JSON page
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub format: Option<String>,
+}
+
+impl From<&ReportQueryParameters> for ReportExportParameters {
+    fn from(s: &ReportQueryParameters) -> ReportExportParameters {
+        ReportExportParameters {
+            report: s.report.trim().to_string(),
+            event_id: s.event_id,
+            start_utc: s.start_utc,
+            end_utc: s.end_utc,
+            query: s.query.clone(),
+        }
+    }
 }
 
 impl From<ReportQueryParameters> for Paging {
@@ -56,8 +74,18 @@ impl From<ReportQueryParameters> for Paging {
 }
 
 pub async fn get_report(
-    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+    (state, connection, query, path, user): (
+        Data<AppState>,
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
 ) -> Result<HttpResponse, ApiError> {
+    if query.format.is_some() {
+        return export_report((state, connection, query, path, user));
+    }
+
     match query.report.trim() {
         "box_office_sales_summary" => box_office_sales_summary((connection, query, path, user)),
         "transaction_details" => Ok(transaction_detail_report((connection, query, path, user))?.into_http_response()?),
@@ -69,17 +97,146 @@ pub async fn get_report(
         "reconciliation_summary" => reconciliation_summary_report((connection, query, path, user)),
         "reconciliation_details" => reconciliation_detail_report((connection, query, path, user)),
         "promo_code" => promo_code_report((connection, query, path, user)),
+        "waitlist" => waitlist_report((connection, query, path, user)),
+        "event_series" => event_series_report((connection, query, path, user)),
+        "product_sales" => product_sales_report((connection, query, path, user)),
         _ => application::not_found(),
     }
 }
 
+/// Exports every row of the report as CSV or XLSX, streamed as the rows are loaded. Exports with more
+/// rows than the configured limit are built in the background and a download link is emailed to the user.
+pub fn export_report(
+    (state, connection, query, path, user): (
+        Data<AppState>,
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let stream_connection = connection.clone();
+    let connection = connection.get();
+    let format: ReportExportFormat = match query.format.as_ref().map(|f| f.parse()) {
+        Some(Ok(format)) => format,
+        _ => return application::unprocessable("Report format must be csv or xlsx"),
+    };
+    let organization = Organization::find(path.id, connection)?;
+    let parameters = ReportExportParameters::from(&*query);
+    requires_report_access(
+        &parameters.report,
+        parameters.event_id,
+        &organization,
+        &user,
+        connection,
+    )?;
+
+    if report_exports::estimated_row_count(organization.id, &parameters, connection)?
+        > state.config.report_export_inline_row_limit
+    {
+        if user.user.email.is_none() {
+            return application::unprocessable("An email address is required to export this many rows");
+        }
+        let report_export = ReportExport::create(
+            organization.id,
+            user.id(),
+            parameters.report.clone(),
+            format,
+            json!(parameters),
+        )
+        .commit(connection)?;
+        return Ok(HttpResponse::Accepted().json(report_export));
+    }
+
+    let file_name = format!(
+        "{}_{}.{}",
+        parameters.report,
+        Utc::now().format("%Y%m%d"),
+        format.extension()
+    );
+    let pages = ReportPages::new(organization.id, parameters);
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .header("Content-Disposition", format!("attachment; filename=\"{}\"", file_name))
+        .streaming(ReportExportStream::new(format, pages, stream_connection)))
+}
+
+/// The scopes required to read each report, shared by the JSON reports and their exports
+enum ReportAccess {
+    /// Organization wide reports, the event is ignored
+    Organization(Scopes),
+    /// The first scope is checked for the organization, the second for the event when one is given
+    OrganizationOrEvent(Scopes, Scopes),
+    /// Per event reports, the event is required
+    Event(Scopes),
+}
+
+fn report_access(report: &str) -> Option<ReportAccess> {
+    match report {
+        "box_office_sales_summary" => Some(ReportAccess::Organization(Scopes::OrgReports)),
+        "weekly_settlement" | "reconciliation_summary" | "reconciliation_details" => {
+            Some(ReportAccess::Organization(Scopes::OrgFinancialReports))
+        }
+        "transaction_details" | "promo_code" | "product_sales" => Some(ReportAccess::OrganizationOrEvent(
+            Scopes::OrgReports,
+            Scopes::EventFinancialReports,
+        )),
+        "ticket_count" | "waitlist" | "event_series" => Some(ReportAccess::OrganizationOrEvent(
+            Scopes::DashboardRead,
+            Scopes::DashboardRead,
+        )),
+        "event_summary" | "audit_report" => Some(ReportAccess::Event(Scopes::EventFinancialReports)),
+        "scan_count" => Some(ReportAccess::Event(Scopes::ScanReportRead)),
+        _ => None,
+    }
+}
+
+fn requires_report_access(
+    report: &str,
+    event_id: Option<Uuid>,
+    organization: &Organization,
+    user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    let access = match report_access(report) {
+        Some(access) => access,
+        None => return Err(NotFoundError {}.into()),
+    };
+    let event = match (&access, event_id) {
+        (ReportAccess::Organization(_), _) | (_, None) => None,
+        (_, Some(event_id)) => {
+            let event = Event::find(event_id, connection)?;
+            if event.organization_id != organization.id {
+                return Err(NotFoundError {}.into());
+            }
+            Some(event)
+        }
+    };
+
+    match (access, event) {
+        (ReportAccess::Organization(scope), _) | (ReportAccess::OrganizationOrEvent(scope, _), None) => {
+            user.requires_scope_for_organization(scope, organization, connection)
+        }
+        (ReportAccess::OrganizationOrEvent(_, scope), Some(event)) | (ReportAccess::Event(scope), Some(event)) => {
+            user.requires_scope_for_organization_event(scope, organization, &event, connection)
+        }
+        (ReportAccess::Event(_), None) => Err(ApplicationError::bad_request("event_id parameter is required").into()),
+    }
+}
+
 pub fn box_office_sales_summary(
     (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
 ) -> Result<HttpResponse, ApiError> {
     let connection = connection.get();
 
     let organization = Organization::find(path.id, connection)?;
-    user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+    requires_report_access(
+        "box_office_sales_summary",
+        query.event_id,
+        &organization,
+        &user,
+        connection,
+    )?;
 
     let result = Report::box_office_sales_summary_report(path.id, query.start_utc, query.end_utc, connection)?;
     Ok(HttpResponse::Ok().json(result))
@@ -91,12 +248,7 @@ pub fn transaction_detail_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if let Some(event_id) = query.event_id {
-        let event = Event::find(event_id, connection)?;
-        user.requires_scope_for_organization_event(Scopes::EventFinancialReports, &organization, &event, connection)?;
-    } else {
-        user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
-    }
+    requires_report_access("transaction_details", query.event_id, &organization, &user, connection)?;
 
     let result = Report::transaction_detail_report(
         query.query.clone(),
@@ -117,12 +269,7 @@ pub fn event_summary_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if let Some(event_id) = query.event_id {
-        let event = Event::find(event_id, connection)?;
-        user.requires_scope_for_organization_event(Scopes::EventFinancialReports, &organization, &event, connection)?;
-    } else {
-        return application::bad_request("event_id parameter is required");
-    }
+    requires_report_access("event_summary", query.event_id, &organization, &user, connection)?;
 
     let result = Report::summary_event_report(
         //We catch the is_none() above so I'll use unwrap here
@@ -140,12 +287,7 @@ pub fn audit_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if let Some(event_id) = query.event_id {
-        let event = Event::find(event_id, connection)?;
-        user.requires_scope_for_organization_event(Scopes::EventFinancialReports, &organization, &event, connection)?;
-    } else {
-        return application::bad_request("event_id parameter is required");
-    }
+    requires_report_access("audit_report", query.event_id, &organization, &user, connection)?;
 
     let all_sales_result = Report::summary_event_report(
         //We catch the is_none() above so I'll use unwrap here
@@ -182,7 +324,7 @@ pub fn weekly_settlement_report(
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
 
-    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+    requires_report_access("weekly_settlement", query.event_id, &organization, &user, connection)?;
 
     let result = Report::organization_summary_report(path.id, query.start_utc, query.end_utc, connection)?;
     Ok(HttpResponse::Ok().json(result))
@@ -193,9 +335,8 @@ pub fn scan_counts(
 ) -> Result<HttpResponse, ApiError> {
     let connection = connection.get();
     if let Some(event_id) = query.event_id {
-        let event = Event::find(event_id, connection)?;
-        let organization = event.organization(connection)?;
-        user.requires_scope_for_organization_event(Scopes::ScanReportRead, &organization, &event, connection)?;
+        let organization = Event::find(event_id, connection)?.organization(connection)?;
+        requires_report_access("scan_count", query.event_id, &organization, &user, connection)?;
 
         let result = Report::scan_count_report(
             query.event_id.unwrap(),
@@ -215,12 +356,7 @@ pub fn ticket_counts(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if let Some(event_id) = query.event_id {
-        let event = Event::find(event_id, connection)?;
-        user.requires_scope_for_organization_event(Scopes::DashboardRead, &organization, &event, connection)?;
-    } else {
-        user.requires_scope_for_organization(Scopes::DashboardRead, &organization, connection)?;
-    }
+    requires_report_access("ticket_count", query.event_id, &organization, &user, connection)?;
 
     let result = Report::ticket_count_report(query.event_id, Some(path.id), connection)?;
     Ok(HttpResponse::Ok().json(result))
@@ -232,17 +368,48 @@ pub fn promo_code_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if let Some(event_id) = query.event_id {
-        let event = Event::find(event_id, connection)?;
-        user.requires_scope_for_organization_event(Scopes::EventFinancialReports, &organization, &event, connection)?;
-    } else {
-        user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
-    }
+    requires_report_access("promo_code", query.event_id, &organization, &user, connection)?;
 
     let result = Report::promo_code_report(query.event_id, Some(path.id), connection)?;
     Ok(HttpResponse::Ok().json(result))
 }
 
+pub fn waitlist_report(
+    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    //Check if they have org admin permissions
+    let organization = Organization::find(path.id, connection)?;
+    requires_report_access("waitlist", query.event_id, &organization, &user, connection)?;
+
+    let result = Report::waitlist_report(query.event_id, Some(path.id), connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn event_series_report(
+    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    //Check if they have org admin permissions
+    let organization = Organization::find(path.id, connection)?;
+    requires_report_access("event_series", query.event_id, &organization, &user, connection)?;
+
+    let result = Report::event_series_report(query.event_id, Some(path.id), connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn product_sales_report(
+    (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    //Check if they have org admin permissions
+    let organization = Organization::find(path.id, connection)?;
+    requires_report_access("product_sales", query.event_id, &organization, &user, connection)?;
+
+    let result = Report::product_sales_report(query.event_id, Some(path.id), connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
 pub fn reconciliation_summary_report(
     (connection, query, path, user): (Connection, Query<ReportQueryParameters>, Path<PathParameters>, AuthUser),
 ) -> Result<HttpResponse, ApiError> {
@@ -250,7 +417,13 @@ pub fn reconciliation_summary_report(
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
 
-    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+    requires_report_access(
+        "reconciliation_summary",
+        query.event_id,
+        &organization,
+        &user,
+        connection,
+    )?;
 
     let result = Report::reconciliation_summary_report(path.id, query.start_utc, query.end_utc, connection)?;
     Ok(HttpResponse::Ok().json(result))
@@ -263,7 +436,13 @@ pub fn reconciliation_detail_report(
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
 
-    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+    requires_report_access(
+        "reconciliation_details",
+        query.event_id,
+        &organization,
+        &user,
+        connection,
+    )?;
 
     let result = Report::reconciliation_detail_report(path.id, query.start_utc, query.end_utc, connection)?;
     Ok(HttpResponse::Ok().json(result))
@@ -0,0 +1,118 @@
+use crate::auth::user::User as AuthUser;
+use crate::controllers::stages::check_access;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateSeatMap {
+    pub name: String,
+    pub stage_id: Option<Uuid>,
+    pub sections: Vec<CreateSeatMapSection>,
+}
+
+/// Sections, rows and seats are listed from best to worst
+#[derive(Deserialize)]
+pub struct CreateSeatMapSection {
+    pub name: String,
+    pub rows: Vec<CreateSeatMapRow>,
+}
+
+/// Seats are listed in the order they sit in the row
+#[derive(Deserialize)]
+pub struct CreateSeatMapRow {
+    pub label: String,
+    pub seats: Vec<CreateSeat>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateSeat {
+    pub seat_number: String,
+    pub price_tier: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateEventSeating {
+    pub seat_map_id: Uuid,
+    /// Ticket type each price tier of the seat map is sold as
+    pub price_tiers: HashMap<String, Uuid>,
+}
+
+pub async fn index((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let seat_maps = SeatMap::find_by_venue_id(path.id, connection.get())?;
+    Ok(HttpResponse::Ok().json(&seat_maps))
+}
+
+pub async fn show((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let seat_map = SeatMap::find(path.id, connection)?;
+    Ok(HttpResponse::Ok().json(&seat_map.for_display(connection)?))
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<CreateSeatMap>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let venue = Venue::find(path.id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    let json = json.into_inner();
+    let seat_map = SeatMap::create(venue.id, json.stage_id, json.name).commit(connection)?;
+    for (section_rank, section) in json.sections.into_iter().enumerate() {
+        let seat_map_section =
+            SeatMapSection::create(seat_map.id, section.name, section_rank as i32).commit(connection)?;
+        let mut seats = Vec::new();
+        for (row_rank, row) in section.rows.into_iter().enumerate() {
+            for (seat_rank, seat) in row.seats.into_iter().enumerate() {
+                seats.push(Seat::create(
+                    seat_map_section.id,
+                    row.label.clone(),
+                    row_rank as i32,
+                    seat.seat_number,
+                    seat_rank as i32,
+                    seat.price_tier,
+                ));
+            }
+        }
+        Seat::create_multiple(&seats, connection)?;
+    }
+
+    Ok(HttpResponse::Created().json(&seat_map.for_display(connection)?))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let seat_map = SeatMap::find(path.id, connection)?;
+    let venue = Venue::find(seat_map.venue_id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    seat_map.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Seats of the event with their availability, for choosing seats when buying tickets
+pub async fn event_seats((connection, path): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Seat::find_for_event(event.id, connection)?))
+}
+
+pub async fn update_event_seating(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<UpdateEventSeating>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::EventWrite, &organization, &event, connection)?;
+
+    let seat_map = SeatMap::find(json.seat_map_id, connection)?;
+    let tiers = seat_map.assign_to_event(&event, &json.price_tiers, connection)?;
+    Ok(HttpResponse::Ok().json(&tiers))
+}
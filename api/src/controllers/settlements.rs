@@ -17,6 +17,8 @@ pub struct NewSettlementRequest {
     pub start_time: NaiveDateTime,
     pub end_time: NaiveDateTime,
     pub comment: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 pub async fn index(
@@ -44,7 +46,7 @@ pub async fn create(
     let connection = connection.get();
     let organization = Organization::find(path.id, connection)?;
     user.requires_scope_for_organization(Scopes::SettlementWrite, &organization, connection)?;
-    let new_settlement = Settlement::create(
+    let mut settlement = Settlement::create(
         organization.id,
         new_settlement.start_time,
         new_settlement.end_time,
@@ -52,7 +54,8 @@ pub async fn create(
         new_settlement.comment.clone(),
         organization.settlement_type == SettlementTypes::PostEvent,
     );
-    let settlement = new_settlement.commit(Some(user.user), connection)?;
+    settlement.currency = new_settlement.currency.clone();
+    let settlement = settlement.commit(Some(user.user), connection)?;
     Ok(HttpResponse::Created().json(&settlement))
 }
 
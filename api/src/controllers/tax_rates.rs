@@ -0,0 +1,82 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+use uuid::Uuid;
+
+pub async fn index(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgRead, &organization, connection)?;
+    Ok(HttpResponse::Ok().json(TaxRate::find_for_organization(organization.id, connection)?))
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct NewTaxRateRequest {
+    pub venue_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f32,
+    #[serde(default)]
+    pub inclusive: bool,
+    #[serde(default)]
+    pub applies_to_fees: bool,
+}
+
+pub async fn create(
+    (connection, path, json, user): (Connection, Path<PathParameters>, Json<NewTaxRateRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    if let Some(venue_id) = json.venue_id {
+        let venue = Venue::find(venue_id, connection)?;
+        if !venue.organizations(connection)?.iter().any(|o| o.id == organization.id) {
+            return application::unprocessable("Venue is not linked to this organization");
+        }
+    }
+
+    let tax_rate = TaxRate::create(
+        organization.id,
+        json.venue_id,
+        json.name.clone(),
+        json.rate_percent,
+        json.inclusive,
+        json.applies_to_fees,
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&tax_rate))
+}
+
+pub async fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<TaxRateEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let tax_rate = TaxRate::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &tax_rate.organization(connection)?, connection)?;
+
+    let tax_rate = tax_rate.update(json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&tax_rate))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let tax_rate = TaxRate::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &tax_rate.organization(connection)?, connection)?;
+
+    tax_rate.destroy(connection)?;
+    Ok(HttpResponse::Ok().json({}))
+}
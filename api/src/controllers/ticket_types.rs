@@ -69,6 +69,12 @@ pub struct CreateTicketTypeRequest {
     pub rarity_id: Option<Uuid>,
     #[serde(default)]
     pub promo_image_url: Option<String>,
+    #[serde(default)]
+    pub reentry_policy: Option<ReentryPolicy>,
+    #[serde(default)]
+    pub reentry_limit: Option<i32>,
+    #[serde(default)]
+    pub access_zone_ids: Option<Vec<Uuid>>,
 }
 
 impl Default for CreateTicketTypeRequest {
@@ -95,6 +101,9 @@ impl Default for CreateTicketTypeRequest {
             contents: vec![],
             rarity_id: None,
             promo_image_url: None,
+            reentry_policy: None,
+            reentry_limit: None,
+            access_zone_ids: None,
         }
     }
 }
@@ -158,6 +167,12 @@ pub struct UpdateTicketTypeRequest {
     #[serde(default)]
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default)]
+    pub reentry_policy: Option<ReentryPolicy>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub reentry_limit: Option<Option<i32>>,
+    #[serde(default)]
+    pub access_zone_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -324,7 +339,7 @@ pub async fn update(
             //Issue more tickets on chain
             match asset.blockchain_asset_id {
                 Some(a) => state.config.tari_client.modify_asset_increase_supply(
-                    &org_wallet.secret_key,
+                    &org_wallet.secret_key(&state.config.wallet_encryption_keys)?,
                     &org_wallet.public_key,
                     &a,
                     requested_capacity as u64,
@@ -364,8 +379,13 @@ pub async fn update(
         additional_fee_in_cents: data.additional_fee_in_cents,
         app_sales_enabled: data.app_sales_enabled,
         rank: data.rank,
+        reentry_policy: data.reentry_policy,
+        reentry_limit: data.reentry_limit,
     };
     let updated_ticket_type = ticket_type.update(update_parameters, Some(user.id()), connection)?;
+    if let Some(ref access_zone_ids) = data.access_zone_ids {
+        updated_ticket_type.update_access_zones(access_zone_ids.clone(), connection)?;
+    }
 
     if let Some(ref data_ticket_pricing) = data.ticket_pricing {
         //Retrieve the current list of pricing associated with this ticket_type and remove unwanted pricing
@@ -462,7 +482,7 @@ fn nullify_tickets(
         match asset.blockchain_asset_id {
             Some(a) => {
                 state.config.tari_client.modify_asset_nullify_tokens(
-                    &org_wallet.secret_key,
+                    &org_wallet.secret_key(&state.config.wallet_encryption_keys)?,
                     &org_wallet.public_key,
                     &a,
                     tari_ids,
@@ -496,7 +516,7 @@ pub(crate) fn create_ticket_type_blockchain_assets(
     // Only create the blockchain assets after all of the ticket types have succeeded
     for data in ticket_types {
         let tari_asset_id = state.config.tari_client.create_asset(
-            &org_wallet.secret_key,
+            &org_wallet.secret_key(&state.config.wallet_encryption_keys)?,
             &org_wallet.public_key,
             TariNewAsset {
                 name: format!("{}.{}", event.id, data.name),
@@ -571,6 +591,15 @@ fn create_ticket_types(
             Some(user.id()),
             connection,
         )?;
+        let ticket_type = match ticket_type_data.reentry_policy {
+            Some(reentry_policy) => {
+                ticket_type.update_reentry_policy(reentry_policy, ticket_type_data.reentry_limit, connection)?
+            }
+            None => ticket_type,
+        };
+        if let Some(ref access_zone_ids) = ticket_type_data.access_zone_ids {
+            ticket_type.update_access_zones(access_zone_ids.clone(), connection)?;
+        }
         //Add each ticket pricing entry for newly created ticket type
         for current_pricing_entry in &ticket_type_data.ticket_pricing {
             let _pricing_result = ticket_type.add_ticket_pricing(
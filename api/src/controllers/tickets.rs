@@ -13,6 +13,7 @@ use actix_web::{
 use chrono::prelude::*;
 use db::models::User as DbUser;
 use db::prelude::*;
+use db::utils::encryption::KeyRing;
 use diesel::pg::PgConnection;
 use itertools::Itertools;
 use regex::Regex;
@@ -152,6 +153,7 @@ pub async fn send_via_email_or_phone(
             &send_tickets_request.email_or_phone,
             TransferMessageType::Email,
             user.id,
+            &state.config.wallet_encryption_keys,
             connection,
         )?;
 
@@ -169,6 +171,7 @@ pub async fn send_via_email_or_phone(
                 &tickets.collect_vec(),
                 connection,
                 &*state.config.tari_client,
+                &state.config.wallet_encryption_keys,
                 &sender_wallet,
                 &receiver_wallet,
             )?;
@@ -234,7 +237,12 @@ pub struct SendTicketsRequest {
 }
 
 pub async fn transfer_authorization(
-    (connection, transfer_tickets_request, auth_user): (Connection, Json<TransferTicketRequest>, User),
+    (state, connection, transfer_tickets_request, auth_user): (
+        Data<AppState>,
+        Connection,
+        Json<TransferTicketRequest>,
+        User,
+    ),
 ) -> Result<HttpResponse, ApiError> {
     auth_user.requires_scope(Scopes::TicketTransfer)?;
     let connection = connection.get();
@@ -247,7 +255,7 @@ pub async fn transfer_authorization(
         false,
         connection,
     )?
-    .into_authorization(connection)?;
+    .into_authorization(&state.config.wallet_encryption_keys, connection)?;
 
     Ok(HttpResponse::Ok().json(&transfer_authorization))
 }
@@ -278,6 +286,7 @@ pub async fn receive_transfer(
         &tickets,
         connection,
         &*state.config.tari_client,
+        &state.config.wallet_encryption_keys,
         &sender_wallet,
         &receiver_wallet,
     )?;
@@ -289,6 +298,7 @@ pub(crate) fn transfer_tickets_on_blockchain(
     tickets: &[TicketInstance],
     connection: &PgConnection,
     tari_client: &dyn TariClient,
+    key_ring: &KeyRing,
     sender_wallet: &Wallet,
     receiver_wallet: &Wallet,
 ) -> Result<(), ApiError> {
@@ -306,7 +316,7 @@ pub(crate) fn transfer_tickets_on_blockchain(
         let asset = Asset::find(*asset_id, connection)?;
         match asset.blockchain_asset_id {
             Some(a) => tari_client.transfer_tokens(
-                &sender_wallet.secret_key,
+                &sender_wallet.secret_key(key_ring)?,
                 &sender_wallet.public_key,
                 &a,
                 token_ids.clone(),
@@ -147,6 +147,7 @@ pub async fn cancel(
                 &tickets.collect_vec(),
                 connection,
                 &*state.config.tari_client,
+                &state.config.wallet_encryption_keys,
                 &destination_user_wallet,
                 &source_user_wallet,
             )?;
@@ -37,7 +37,7 @@ pub async fn create(
         None,
     );
 
-    let user = new_user.commit(Some(auth_user.id()), connection)?;
+    let user = new_user.commit(Some(auth_user.id()), &state.config.wallet_encryption_keys, connection)?;
     let user = user.create_password_reset_token(connection)?;
 
     mailers::user::invite_user_email(&state.config, &user, connection)?;
@@ -21,6 +21,7 @@ use actix_web::{
 };
 use chrono::Duration;
 use db::prelude::*;
+use db::utils::totp;
 use diesel::PgConnection;
 use futures::future::{err, ok, Ready};
 use serde_json::Value;
@@ -63,6 +64,22 @@ pub struct ActivityParameters {
     past_or_upcoming: Option<String>,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MfaEnrollmentResponse {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MfaActivationResponse {
+    pub recovery_codes: Vec<String>,
+}
+
 #[derive(Deserialize, Clone)]
 pub struct InputPushNotificationTokens {
     pub token_source: String,
@@ -177,6 +194,64 @@ pub async fn update_current_user(
     Ok(current_user)
 }
 
+pub async fn begin_mfa_enrollment(
+    (state, connection, auth_user): (Data<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let user = auth_user
+        .user
+        .begin_mfa_enrollment(&state.config.mfa_encryption_keys, connection)?;
+    let secret = user.mfa_secret(&state.config.mfa_encryption_keys)?.unwrap_or_default();
+    let account_name = user.email.clone().unwrap_or_else(|| user.id.to_string());
+
+    Ok(HttpResponse::Ok().json(&MfaEnrollmentResponse {
+        provisioning_uri: totp::provisioning_uri(&secret, &state.config.app_name, &account_name),
+        secret,
+    }))
+}
+
+pub async fn activate_mfa(
+    (state, connection, json, auth_user): (Data<AppState>, Connection, Json<MfaCodeRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let (_, recovery_codes) = auth_user
+        .user
+        .activate_mfa(&json.code, &state.config.mfa_encryption_keys, connection)?;
+
+    Ok(HttpResponse::Ok().json(&MfaActivationResponse { recovery_codes }))
+}
+
+pub async fn disable_mfa(
+    (state, connection, json, auth_user): (Data<AppState>, Connection, Json<MfaCodeRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    auth_user
+        .user
+        .disable_mfa(&json.code, &state.config.mfa_encryption_keys, connection)?;
+
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+pub async fn sessions((connection, auth_user): (Connection, AuthUser)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let sessions = UserSession::find_active_for_user(auth_user.id(), connection)?;
+
+    Ok(HttpResponse::Ok().json(&sessions))
+}
+
+pub async fn revoke_session(
+    (connection, parameters, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let session = UserSession::find(parameters.id, connection)?;
+    if session.user_id != auth_user.id() {
+        return application::unauthorized(Some(auth_user), None);
+    }
+    session.revoke(connection)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
 pub async fn show(
     (connection, parameters, auth_user): (Connection, Path<PathParameters>, AuthUser),
 ) -> Result<HttpResponse, ApiError> {
@@ -287,7 +362,7 @@ pub async fn register(
     }
 
     let new_user: NewUser = parameters.into_inner().into();
-    match new_user.commit(None, connection.get()) {
+    match new_user.commit(None, &state.config.wallet_encryption_keys, connection.get()) {
         Ok(_) => (),
         Err(e) => match e.error_code {
             ErrorCode::DuplicateKeyError => {
@@ -316,7 +391,7 @@ pub async fn register_with_email_only(
 
     let new_user: NewUser = parameters.into_inner().into();
     let conn = connection.get();
-    let user = match new_user.commit(None, conn) {
+    let user = match new_user.commit(None, &state.config.wallet_encryption_keys, conn) {
         Ok(user) => user,
         Err(e) => match e.error_code {
             ErrorCode::DuplicateKeyError => {
@@ -365,7 +440,7 @@ pub async fn register_and_login(
     let email = parameters.email.clone();
     let password = parameters.password.clone();
     let new_user: NewUser = parameters.into_inner().into();
-    match new_user.commit(None, connection.get()) {
+    match new_user.commit(None, &state.config.wallet_encryption_keys, connection.get()) {
         Ok(_) => (),
         Err(e) => match e.error_code {
             ErrorCode::DuplicateKeyError => {
@@ -448,6 +523,19 @@ pub async fn delete(
     Ok(HttpResponse::Ok().finish())
 }
 
+pub async fn revoke_sessions_for_user(
+    (conn, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let conn = conn.get();
+    if user.id() != path.id {
+        user.requires_scope(Scopes::UserDelete)?
+    }
+
+    let target_user = User::find(path.id, conn)?;
+    UserSession::revoke_all_for_user(target_user.id, conn)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
 pub async fn create_marketplace_account(
     (user, state, conn): (AuthUser, Data<AppState>, Connection),
 ) -> Result<HttpResponse, ApiError> {
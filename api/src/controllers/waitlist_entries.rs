@@ -0,0 +1,53 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::EventTicketPathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::*;
+
+#[derive(Deserialize)]
+pub struct CreateWaitlistEntryRequest {
+    pub quantity: u32,
+}
+
+/// Joins the waitlist of a sold out ticket type, freed tickets are offered in the order people joined
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<CreateWaitlistEntryRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id(), json.quantity).commit(connection)?;
+    Ok(HttpResponse::Created().json(&waitlist_entry))
+}
+
+pub async fn show(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    match WaitlistEntry::find_active(path.ticket_type_id, user.id(), connection)? {
+        Some(waitlist_entry) => Ok(HttpResponse::Ok().json(&waitlist_entry)),
+        None => application::not_found(),
+    }
+}
+
+/// Leaves the waitlist, tickets held for an open offer go to the next person in line
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    match WaitlistEntry::find_active(path.ticket_type_id, user.id(), connection)? {
+        Some(waitlist_entry) => Ok(HttpResponse::Ok().json(&waitlist_entry.cancel(Some(user.id()), connection)?)),
+        None => application::not_found(),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::models::{PathParameters, WebPayload, WebhookDeliveryPathParameters};
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+use diesel::PgConnection;
+
+pub async fn index(
+    (connection, path, query, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<WebPayload<WebhookDelivery>, ApiError> {
+    let connection = connection.get();
+    let domain_event_publisher = DomainEventPublisher::find(path.id, connection)?;
+    requires_webhook_access(&domain_event_publisher, &user, connection)?;
+    let status = match query.get_tag_as_str("status") {
+        Some(status) => Some(status.parse::<WebhookDeliveryStatus>()?),
+        None => None,
+    };
+
+    let payload = WebhookDelivery::find_by_domain_event_publisher_id(
+        domain_event_publisher.id,
+        status,
+        query.page(),
+        query.limit(),
+        connection,
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+pub async fn replay(
+    (connection, path, user): (Connection, Path<WebhookDeliveryPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let domain_event_publisher = DomainEventPublisher::find(path.id, connection)?;
+    requires_webhook_access(&domain_event_publisher, &user, connection)?;
+    let webhook_delivery = WebhookDelivery::find(path.webhook_delivery_id, connection)?;
+    if webhook_delivery.domain_event_publisher_id != domain_event_publisher.id {
+        return Err(NotFoundError {}.into());
+    }
+    Ok(HttpResponse::Ok().json(webhook_delivery.replay(connection)?))
+}
+
+/// Organization webhooks are managed by the organization, system webhooks by admins
+fn requires_webhook_access(
+    domain_event_publisher: &DomainEventPublisher,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<(), ApiError> {
+    match domain_event_publisher.organization_id {
+        Some(organization_id) => {
+            let organization = Organization::find(organization_id, connection)?;
+            user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)
+        }
+        None => user.requires_scope(Scopes::OrgAdmin),
+    }
+}
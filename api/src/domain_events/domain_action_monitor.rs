@@ -7,12 +7,14 @@ use crate::utils::ServiceLocator;
 use db::prelude::*;
 use log::Level::*;
 use logging::*;
+use std::collections::HashMap;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
 use std::thread::JoinHandle;
 use std::time::Duration;
 use std::{cmp, thread};
 use tokio::time::timeout;
+use uuid::Uuid;
 
 pub struct DomainActionMonitor {
     config: Config,
@@ -80,18 +82,30 @@ impl DomainActionMonitor {
 
         let domain_events = DomainEvent::find_after_seq(last_seq_pub, 500, connection)?;
 
+        // Owning organizations are only looked up for events an organization has subscribed to
+        let mut owning_organization_ids: HashMap<Uuid, Option<Uuid>> = HashMap::new();
         if domain_events.len() > 0 {
             for publisher in domain_event_publishers.iter_mut() {
                 match &mut publisher.acquire_lock(60, connection) {
                     Ok(_) => {
                         for event in &domain_events {
                             conn.begin_transaction()?;
-                            if publisher.last_domain_event_seq.unwrap_or(-1) < event.seq
-                                && publisher.event_types.contains(&event.event_type)
-                                && (publisher.organization_id.is_none()
-                                    || publisher.organization_id == event.organization_id)
-                            {
-                                jlog!(Info, "bigneon::domain_events", "Publishing event", {"publisher_id": publisher.id, "event_type": &event.event_type, "organization_id": event.organization_id, "event": &event});
+                            let mut publish = publisher.last_domain_event_seq.unwrap_or(-1) < event.seq
+                                && !publisher.is_paused()
+                                && publisher.event_types.contains(&event.event_type);
+                            if publish && publisher.organization_id.is_some() {
+                                let organization_id = match owning_organization_ids.get(&event.id) {
+                                    Some(organization_id) => *organization_id,
+                                    None => {
+                                        let organization_id = event.owning_organization_id(connection)?;
+                                        owning_organization_ids.insert(event.id, organization_id);
+                                        organization_id
+                                    }
+                                };
+                                publish = publisher.organization_id == organization_id;
+                            }
+                            if publish {
+                                jlog!(Info, "bigneon::domain_events", "Publishing event", {"publisher_id": publisher.id, "event_type": &event.event_type, "organization_id": publisher.organization_id, "event": &event});
                                 webhook_publisher.publish(&publisher, &event, connection)?;
                             }
                             publisher.update_last_domain_event_seq(event.seq, connection)?;
@@ -120,6 +134,7 @@ impl DomainActionMonitor {
             config.front_end_url.clone(),
             config.token_issuer.as_ref().clone(),
             service_locator.create_deep_linker()?,
+            config.wallet_encryption_keys.clone(),
         );
         loop {
             if rx.try_recv().is_ok() {
@@ -1,34 +1,107 @@
+use crate::config::Config;
 use crate::database::Connection;
 use crate::domain_events::executor_future::ExecutorFuture;
 use crate::domain_events::routing::DomainActionExecutor;
 use crate::errors::*;
+use crate::payments::PaymentProcessorError;
 use db::prelude::*;
-use futures::future;
-use log::Level::Error;
+use log::Level::{Error, Info, Trace};
+use stripe::StripeClient;
 
-pub struct FinalizeSettlementsExecutor {}
+pub struct FinalizeSettlementsExecutor {
+    config: Config,
+}
 
 impl DomainActionExecutor for FinalizeSettlementsExecutor {
     fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
-        match self.perform_job(&conn) {
-            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
-            Err(e) => {
-                jlog!(Error, "Finalize settlements action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
-                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
-            }
-        }
+        let future = FinalizeSettlementsExecutor::perform_job(action.clone(), conn.clone(), self.config.clone());
+        ExecutorFuture::new(action, conn, Box::pin(future))
     }
 }
 
 impl FinalizeSettlementsExecutor {
-    pub fn new() -> FinalizeSettlementsExecutor {
-        FinalizeSettlementsExecutor {}
+    pub fn new(config: Config) -> FinalizeSettlementsExecutor {
+        FinalizeSettlementsExecutor { config }
+    }
+
+    pub async fn perform_job(action: DomainAction, conn: Connection, config: Config) -> Result<(), ApiError> {
+        let result = FinalizeSettlementsExecutor::finalize(&conn, &config).await;
+        if let Err(ref e) = result {
+            jlog!(Error, "Finalize settlements action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+        }
+        result
     }
 
-    pub fn perform_job(&self, conn: &Connection) -> Result<(), ApiError> {
-        let conn = conn.get();
-        Settlement::finalize_settlements(conn)?;
-        Settlement::create_next_finalize_settlements_domain_action(conn)?;
+    async fn finalize(conn: &Connection, config: &Config) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let settlements = Settlement::finalize_settlements(connection)?;
+        Settlement::create_next_finalize_settlements_domain_action(connection)?;
+
+        for settlement in settlements {
+            // A failed transfer leaves the settlement finalized without a transfer id so it can be paid manually
+            if let Err(e) = FinalizeSettlementsExecutor::transfer(&settlement, conn, config).await {
+                jlog!(Error, "bigneon::domain_actions", "Settlement transfer failed", {
+                    "settlement_id": settlement.id,
+                    "organization_id": settlement.organization_id,
+                    "error": e.to_string()
+                });
+                settlement.set_stripe_transfer_error(e.to_string(), connection)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transfers the Stripe-collected amount held on the platform to the organization's connected account
+    async fn transfer(settlement: &Settlement, conn: &Connection, config: &Config) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let organization = Organization::find(settlement.organization_id, connection)?;
+        let connected_account_id = match organization.stripe_connect_account_id {
+            Some(ref connected_account_id) => connected_account_id,
+            None => return Ok(()),
+        };
+
+        let amount = settlement.stripe_transfer_amount_in_cents(connection)?;
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        if config.environment == Environment::Test {
+            return Ok(());
+        }
+
+        if config.block_external_comms {
+            jlog!(Trace, "bigneon::domain_actions", "Blocked settlement transfer", { "settlement_id": settlement.id });
+            return Ok(());
+        }
+
+        let transfer = StripeClient::new(config.stripe_secret_key.clone())
+            .create_transfer(
+                connected_account_id,
+                &format!("settlement-transfer-{}", settlement.id),
+                amount,
+                &settlement.currency,
+                &format!(
+                    "Settlement {} - {}",
+                    settlement.start_time.date(),
+                    settlement.end_time.date()
+                ),
+                &settlement.id.to_string(),
+                vec![
+                    ("settlement_id".to_string(), settlement.id.to_string()),
+                    ("organization_id".to_string(), organization.id.to_string()),
+                ],
+            )
+            .await
+            .map_err(PaymentProcessorError::from)?;
+        settlement.set_stripe_transfer_id(transfer.id.clone(), connection)?;
+
+        jlog!(Info, "bigneon::domain_actions", "Settlement transferred", {
+            "settlement_id": settlement.id,
+            "organization_id": organization.id,
+            "stripe_transfer_id": transfer.id,
+            "amount": amount
+        });
 
         Ok(())
     }
@@ -1,27 +1,37 @@
 pub use self::broadcast_push_notification::*;
 pub use self::finalize_settlements::*;
 pub use self::process_payment_ipn::*;
+pub use self::process_report_export::*;
 pub use self::process_settlement_report::*;
+pub use self::process_stripe_webhook::*;
 pub use self::process_transfer_drip_event::*;
+pub use self::process_waitlist::*;
+pub use self::purge_expired_report_exports::*;
 pub use self::regenerate_drip_actions::*;
 pub use self::release_hold_inventory::*;
 pub use self::retarget_abandoned_orders::*;
 pub use self::send_automatic_report_emails::*;
 pub use self::send_communication::*;
 pub use self::send_order_complete::*;
+pub use self::send_webhook::*;
 pub use self::submit_sitemap_to_search_engines::*;
 pub use self::update_genres::*;
 
 mod broadcast_push_notification;
 mod finalize_settlements;
 mod process_payment_ipn;
+mod process_report_export;
 mod process_settlement_report;
+mod process_stripe_webhook;
 mod process_transfer_drip_event;
+mod process_waitlist;
+mod purge_expired_report_exports;
 mod regenerate_drip_actions;
 mod release_hold_inventory;
 mod retarget_abandoned_orders;
 mod send_automatic_report_emails;
 mod send_communication;
 mod send_order_complete;
+mod send_webhook;
 mod submit_sitemap_to_search_engines;
 mod update_genres;
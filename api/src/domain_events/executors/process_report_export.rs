@@ -0,0 +1,123 @@
+use crate::communications::mailers;
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::utils::report_exports::{self, ReportExportParameters, ReportPages};
+use db::prelude::*;
+use diesel::PgConnection;
+use futures::future;
+use log::Level::{Error, Warn};
+use std::io::{self, Write};
+
+/// The export is stored a chunk at a time as it is written
+const CONTENT_CHUNK_SIZE: usize = 1024 * 1024;
+
+pub struct ProcessReportExportExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessReportExportExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process report export action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessReportExportExecutor {
+    pub fn new(config: Config) -> ProcessReportExportExecutor {
+        ProcessReportExportExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .ok_or_else(|| ApplicationError::new("No report export id attached to domain action".to_string()))?;
+        let report_export = ReportExport::find(id, conn)?;
+        if report_export.status != ReportExportStatus::Pending {
+            return Ok(());
+        }
+
+        // Report errors will not go away on a retry so the export is failed instead
+        let parameters: ReportExportParameters = serde_json::from_value(report_export.parameters.clone())?;
+        let row_count = match ProcessReportExportExecutor::build(&report_export, parameters, conn) {
+            Ok(row_count) => row_count,
+            Err(e) => {
+                jlog!(Warn, "bigneon::domain_actions", "Report export failed", {
+                    "report_export_id": report_export.id,
+                    "error": e.to_string()
+                });
+                report_export.mark_failed(e.to_string(), conn)?;
+                return Ok(());
+            }
+        };
+
+        let (report_export, download_token) = report_export.mark_completed(row_count, conn)?;
+        let user = User::find(report_export.user_id, conn)?;
+        mailers::reports::report_export_ready(&self.config, &user, &report_export, &download_token, conn)?;
+
+        Ok(())
+    }
+
+    fn build(
+        report_export: &ReportExport,
+        parameters: ReportExportParameters,
+        conn: &PgConnection,
+    ) -> Result<i64, ApiError> {
+        let mut pages = ReportPages::new(report_export.organization_id, parameters);
+        let mut content = ReportExportContent {
+            report_export,
+            conn,
+            buffer: Vec::new(),
+            started: false,
+        };
+        report_exports::write_export(report_export.format, &mut pages, conn, &mut content)?;
+        Ok(pages.row_count())
+    }
+}
+
+struct ReportExportContent<'a> {
+    report_export: &'a ReportExport,
+    conn: &'a PgConnection,
+    buffer: Vec<u8>,
+    started: bool,
+}
+
+impl<'a> ReportExportContent<'a> {
+    /// The first chunk replaces anything left by an earlier attempt
+    fn store(&mut self) -> Result<(), DatabaseError> {
+        if self.started {
+            self.report_export.append_content(&self.buffer, self.conn)?;
+        } else {
+            self.report_export.set_content(&self.buffer, self.conn)?;
+            self.started = true;
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<'a> Write for ReportExportContent<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CONTENT_CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.started && self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.store()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+    }
+}
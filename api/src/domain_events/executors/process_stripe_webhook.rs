@@ -0,0 +1,157 @@
+use crate::config::Config;
+use crate::controllers::cart::complete_authenticated_payment;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::payments::PaymentProcessorError;
+use crate::utils::ServiceLocator;
+use db::prelude::*;
+use db::utils::errors::Optional;
+use log::Level::{Debug, Error, Warn};
+use stripe::{Dispute, WebhookEvent};
+
+pub struct ProcessStripeWebhookExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessStripeWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        let future = ProcessStripeWebhookExecutor::perform_job(action.clone(), conn.clone(), self.config.clone());
+        ExecutorFuture::new(action, conn, Box::pin(future))
+    }
+}
+
+impl ProcessStripeWebhookExecutor {
+    pub fn new(config: Config) -> ProcessStripeWebhookExecutor {
+        ProcessStripeWebhookExecutor { config }
+    }
+
+    pub async fn perform_job(action: DomainAction, conn: Connection, config: Config) -> Result<(), ApiError> {
+        let event: WebhookEvent = serde_json::from_value(action.payload.clone())?;
+        let result = ProcessStripeWebhookExecutor::process(&event, &conn, &config).await;
+        if let Err(ref e) = result {
+            jlog!(Error, "Stripe webhook processing failed", {
+                "action_id": action.id,
+                "event_id": &event.id,
+                "type": &event.event_type,
+                "error": e.to_string()
+            });
+        }
+        result
+    }
+
+    async fn process(event: &WebhookEvent, conn: &Connection, config: &Config) -> Result<(), ApiError> {
+        match event.event_type.as_str() {
+            "payment_intent.amount_capturable_updated"
+            | "payment_intent.payment_failed"
+            | "payment_intent.canceled" => {
+                ProcessStripeWebhookExecutor::process_payment_intent(event, conn, config).await
+            }
+            "charge.refunded" => ProcessStripeWebhookExecutor::process_refund(event, conn),
+            "charge.dispute.created"
+            | "charge.dispute.updated"
+            | "charge.dispute.funds_withdrawn"
+            | "charge.dispute.funds_reinstated"
+            | "charge.dispute.closed" => ProcessStripeWebhookExecutor::process_dispute(event, conn, config),
+            _ => Ok(()),
+        }
+    }
+
+    /// Payment intents that required additional authentication are completed (or returned to the cart)
+    /// here when the customer does not return to confirm them
+    async fn process_payment_intent(event: &WebhookEvent, conn: &Connection, config: &Config) -> Result<(), ApiError> {
+        let payment = match ProcessStripeWebhookExecutor::find_payment(&[event.object_id()], conn)? {
+            Some(payment) => payment,
+            // Payment intents not created by checkout
+            None => return Ok(()),
+        };
+        payment.record_ipn(json!(event), None, conn.get())?;
+
+        if payment.status == PaymentStatus::RequiresAction {
+            let service_locator = ServiceLocator::new(config)?;
+            match complete_authenticated_payment(conn, &payment, None, &service_locator).await {
+                Ok(_) => (),
+                // Failed authentication has been recorded against the payment
+                Err(e) => {
+                    jlog!(Debug, "Stripe payment intent was not completed", {
+                        "event_id": &event.id,
+                        "payment_id": payment.id,
+                        "error": e.to_string()
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Refunds issued from the Stripe dashboard are recorded against the payment for reconciliation,
+    /// the order itself is only refunded through the refund endpoints
+    fn process_refund(event: &WebhookEvent, conn: &Connection) -> Result<(), ApiError> {
+        let references = [event.object_field("payment_intent"), event.object_id()];
+        if let Some(payment) = ProcessStripeWebhookExecutor::find_payment(&references, conn)? {
+            payment.record_ipn(json!(event), None, conn.get())?;
+        }
+
+        Ok(())
+    }
+
+    fn process_dispute(event: &WebhookEvent, conn: &Connection, config: &Config) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let dispute: Dispute = event.object().map_err(PaymentProcessorError::from)?;
+        let references = [
+            dispute.payment_intent.as_ref().map(|p| p.as_str()),
+            Some(dispute.charge.as_str()),
+        ];
+        let payment = match ProcessStripeWebhookExecutor::find_payment(&references, conn)? {
+            Some(payment) => payment,
+            None => {
+                jlog!(Warn, "bigneon::domain_actions", "Stripe dispute received for unknown payment", {
+                    "event_id": &event.id,
+                    "dispute_id": &dispute.id,
+                    "charge": &dispute.charge
+                });
+                return Ok(());
+            }
+        };
+        let mut order = Order::find(payment.order_id, connection)?;
+
+        match event.event_type.as_str() {
+            "charge.dispute.created" => {
+                let settlement_adjustment = order.mark_disputed(
+                    &dispute.id,
+                    dispute.amount,
+                    config.stripe_dispute_nullify_tickets,
+                    json!(dispute),
+                    connection,
+                )?;
+                if settlement_adjustment.is_none() {
+                    jlog!(Warn, "bigneon::domain_actions", "No pending settlement for Stripe dispute chargeback", {
+                        "dispute_id": &dispute.id,
+                        "order_id": order.id
+                    });
+                }
+            }
+            "charge.dispute.closed" => {
+                order.close_dispute(&dispute.id, dispute.is_won(), json!(dispute), connection)?;
+            }
+            _ => order.update_dispute(&dispute.id, json!(dispute), connection)?,
+        }
+
+        Ok(())
+    }
+
+    /// Card payments are referenced by their payment intent, or by their charge if made before payment intents
+    fn find_payment(references: &[Option<&str>], conn: &Connection) -> Result<Option<Payment>, ApiError> {
+        for reference in references.iter().filter_map(|r| *r) {
+            if let Some(payment) =
+                Payment::find_by_external_reference(PaymentProviders::Stripe, reference, conn.get()).optional()?
+            {
+                return Ok(Some(payment));
+            }
+        }
+
+        Ok(None)
+    }
+}
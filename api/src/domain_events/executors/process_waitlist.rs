@@ -0,0 +1,81 @@
+use crate::communications::{mailers, smsers};
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use diesel::PgConnection;
+use futures::future;
+use log::Level::Error;
+
+pub struct ProcessWaitlistExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ProcessWaitlistExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process waitlist action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessWaitlistExecutor {
+    pub fn new(config: Config) -> ProcessWaitlistExecutor {
+        ProcessWaitlistExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let ticket_type_id = action
+            .main_table_id
+            .ok_or_else(|| ApplicationError::new("No ticket type id attached to domain action".to_string()))?;
+
+        for waitlist_entry in WaitlistEntry::process(ticket_type_id, conn)? {
+            self.send_offer(&waitlist_entry, conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn send_offer(&self, waitlist_entry: &WaitlistEntry, conn: &PgConnection) -> Result<(), ApiError> {
+        let hold = match waitlist_entry.hold(conn)? {
+            Some(hold) => hold,
+            None => return Ok(()),
+        };
+        let redemption_code = hold.redemption_code.clone().unwrap_or_default();
+        let user = User::find(waitlist_entry.user_id, conn)?;
+        let ticket_type = TicketType::find(waitlist_entry.ticket_type_id, conn)?;
+        let event = ticket_type.event(conn)?;
+        let purchase_link = format!(
+            "{}/tickets/{}?code={}",
+            self.config.front_end_url,
+            event.slug(conn)?,
+            redemption_code
+        );
+
+        if let Some(email) = user.email.clone() {
+            mailers::tickets::waitlist_offer(
+                &self.config,
+                email,
+                &user,
+                waitlist_entry,
+                &ticket_type,
+                &event,
+                &redemption_code,
+                &purchase_link,
+                conn,
+            )?;
+        }
+        if let Some(phone) = user.phone.clone() {
+            smsers::tickets::waitlist_offer(&self.config, phone, waitlist_entry, &event, &purchase_link, conn)?;
+        }
+
+        Ok(())
+    }
+}
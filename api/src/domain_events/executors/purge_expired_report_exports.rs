@@ -0,0 +1,37 @@
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::{Error, Info};
+
+pub struct PurgeExpiredReportExportsExecutor {}
+
+impl DomainActionExecutor for PurgeExpiredReportExportsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Purge expired report exports action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl PurgeExpiredReportExportsExecutor {
+    pub fn new() -> PurgeExpiredReportExportsExecutor {
+        PurgeExpiredReportExportsExecutor {}
+    }
+
+    pub fn perform_job(&self, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let purged = ReportExport::purge_expired(conn)?;
+        jlog!(Info, "bigneon::domain_actions", "Purged expired report exports", { "count": purged });
+
+        ReportExport::create_next_purge_expired_domain_action(conn)?;
+
+        Ok(())
+    }
+}
@@ -76,7 +76,7 @@ impl SendOrderCompleteExecutor {
                     };
                     let org_wallet = Wallet::find(wallet_id, conn)?;
                     tari_client.transfer_tokens(
-                        &org_wallet.secret_key,
+                        &org_wallet.secret_key(&self.config.wallet_encryption_keys)?,
                         &org_wallet.public_key,
                         &a,
                         token_ids.clone(),
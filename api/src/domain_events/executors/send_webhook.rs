@@ -0,0 +1,65 @@
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::utils::webhook;
+use db::prelude::*;
+use log::Level::{Trace, Warn};
+
+pub struct SendWebhookExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        let future = SendWebhookExecutor::perform_job(action.clone(), conn.clone(), self.config.clone());
+        ExecutorFuture::new(action, conn, Box::pin(future))
+    }
+}
+
+impl SendWebhookExecutor {
+    pub fn new(config: Config) -> SendWebhookExecutor {
+        SendWebhookExecutor { config }
+    }
+
+    pub async fn perform_job(action: DomainAction, conn: Connection, config: Config) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let webhook_delivery_id = action
+            .main_table_id
+            .ok_or_else(|| ApplicationError::new("No webhook delivery id attached to domain action".to_string()))?;
+        let webhook_delivery = WebhookDelivery::find(webhook_delivery_id, conn)?;
+        if webhook_delivery.status != WebhookDeliveryStatus::Pending {
+            return Ok(());
+        }
+
+        if config.environment == Environment::Test {
+            return Ok(());
+        }
+
+        if config.block_external_comms {
+            jlog!(Trace, "bigneon::domain_actions", "Blocked webhook delivery", { "webhook_delivery_id": webhook_delivery.id });
+            return Ok(());
+        }
+
+        // Failed attempts are recorded against the delivery which schedules its own retry
+        match webhook::deliver_webhook(&webhook_delivery, conn, &config) {
+            Ok(response) => {
+                if response.is_success() {
+                    webhook_delivery.mark_delivered(response.status as i32, response.body, conn)?;
+                } else {
+                    webhook_delivery.mark_failed(Some(response.status as i32), response.body, conn)?;
+                }
+            }
+            Err(err) => {
+                jlog!(Warn, "bigneon::domain_actions", "Webhook delivery failed", {
+                    "webhook_delivery_id": webhook_delivery.id,
+                    "error": err.to_string()
+                });
+                webhook_delivery.mark_failed(None, Some(err.to_string()), conn)?;
+            }
+        }
+
+        Ok(())
+    }
+}
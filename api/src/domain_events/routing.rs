@@ -48,16 +48,21 @@ impl DomainActionRouter {
             match action_type {
                 Communication => Box::new(SendCommunicationExecutor::new(conf)),
                 BroadcastPushNotification => Box::new(BroadcastPushNotificationExecutor::new(&conf)),
-                FinalizeSettlements => Box::new(FinalizeSettlementsExecutor::new()),
+                FinalizeSettlements => Box::new(FinalizeSettlementsExecutor::new(conf)),
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
+                ProcessReportExport => Box::new(ProcessReportExportExecutor::new(conf)),
                 RegenerateDripActions => Box::new(RegenerateDripActionsExecutor::new(conf)),
                 ReleaseHoldInventory => Box::new(ReleaseHoldInventoryExecutor::new()),
                 SendPurchaseCompletedCommunication => Box::new(SendOrderCompleteExecutor::new(conf)),
                 UpdateGenres => Box::new(UpdateGenresExecutor::new()),
                 ProcessSettlementReport => Box::new(ProcessSettlementReportExecutor::new(conf)),
+                ProcessStripeWebhook => Box::new(ProcessStripeWebhookExecutor::new(conf)),
                 ProcessTransferDrip => Box::new(ProcessTransferDripEventExecutor::new(conf)),
+                ProcessWaitlist => Box::new(ProcessWaitlistExecutor::new(conf)),
+                PurgeExpiredReportExports => Box::new(PurgeExpiredReportExportsExecutor::new()),
                 RetargetAbandonedOrders => Box::new(RetargetAbandonedOrdersExecutor::new()),
                 SendAutomaticReportEmails => Box::new(SendAutomaticReportEmailsExecutor::new(conf)),
+                SendWebhook => Box::new(SendWebhookExecutor::new(conf)),
                 SubmitSitemapToSearchEngines => Box::new(SubmitSitemapToSearchEnginesExecutor::new(
                     conf.api_base_url.clone(),
                     conf.block_external_comms,
@@ -79,12 +84,24 @@ impl DomainActionRouter {
         self.add_executor(PaymentProviderIPN, find_executor(PaymentProviderIPN))
             .expect("Configuration error");
 
+        self.add_executor(ProcessReportExport, find_executor(ProcessReportExport))
+            .expect("Configuration error");
+
         self.add_executor(ProcessSettlementReport, find_executor(ProcessSettlementReport))
             .expect("Configuration error");
 
+        self.add_executor(ProcessStripeWebhook, find_executor(ProcessStripeWebhook))
+            .expect("Configuration error");
+
         self.add_executor(ProcessTransferDrip, find_executor(ProcessTransferDrip))
             .expect("Configuration error");
 
+        self.add_executor(ProcessWaitlist, find_executor(ProcessWaitlist))
+            .expect("Configuration error");
+
+        self.add_executor(PurgeExpiredReportExports, find_executor(PurgeExpiredReportExports))
+            .expect("Configuration error");
+
         self.add_executor(RegenerateDripActions, find_executor(RegenerateDripActions))
             .expect("Configuration error");
 
@@ -100,6 +117,9 @@ impl DomainActionRouter {
         self.add_executor(SendAutomaticReportEmails, find_executor(SendAutomaticReportEmails))
             .expect("Configuration error");
 
+        self.add_executor(SendWebhook, find_executor(SendWebhook))
+            .expect("Configuration error");
+
         self.add_executor(
             SendPurchaseCompletedCommunication,
             find_executor(SendPurchaseCompletedCommunication),
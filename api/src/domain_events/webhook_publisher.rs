@@ -2,8 +2,10 @@ use crate::auth::default_token_issuer::DefaultTokenIssuer;
 use crate::domain_events::errors::DomainActionError;
 use crate::errors::ApiError;
 use crate::utils::deep_linker::DeepLinker;
+use chrono::prelude::*;
 use chrono::Duration;
 use db::prelude::*;
+use db::utils::encryption::KeyRing;
 use diesel::PgConnection;
 use log::Level::Error;
 use serde_json::Value;
@@ -13,14 +15,21 @@ pub struct WebhookPublisher {
     pub front_end_url: String,
     pub token_issuer: DefaultTokenIssuer,
     pub deep_linker: Box<dyn DeepLinker>,
+    pub wallet_encryption_keys: KeyRing,
 }
 
 impl WebhookPublisher {
-    pub fn new(front_end_url: String, token_issuer: DefaultTokenIssuer, deep_linker: Box<dyn DeepLinker>) -> Self {
+    pub fn new(
+        front_end_url: String,
+        token_issuer: DefaultTokenIssuer,
+        deep_linker: Box<dyn DeepLinker>,
+        wallet_encryption_keys: KeyRing,
+    ) -> Self {
         WebhookPublisher {
             front_end_url,
             token_issuer,
             deep_linker,
+            wallet_encryption_keys,
         }
     }
     pub fn publish(
@@ -30,20 +39,9 @@ impl WebhookPublisher {
         conn: &PgConnection,
     ) -> Result<(), DomainActionError> {
         for webhook_payload in self.create_webhook_payloads(&domain_event, conn)? {
-            let mut comms = Communication::new(
-                CommunicationType::Webhook,
-                "Domain Event Webhook".to_string(),
-                Some(json!(webhook_payload).to_string()),
-                None,
-                CommAddress::from(domain_event_publisher.webhook_url.clone()),
-                None,
-                None,
-                Some(vec!["webhooks"]),
-                None,
-            );
-            comms.main_table = Some(Tables::DomainEventPublishers);
-            comms.main_table_id = Some(domain_event_publisher.id);
-            comms.queue(conn)?;
+            WebhookDelivery::create(domain_event_publisher.id, Some(domain_event.id), json!(webhook_payload))
+                .commit(conn)?
+                .queue(conn)?;
         }
         Ok(())
     }
@@ -219,6 +217,137 @@ impl WebhookPublisher {
                     );
                 }
             }
+            DomainEventTypes::EventCreated
+            | DomainEventTypes::EventUpdated
+            | DomainEventTypes::EventPublished
+            | DomainEventTypes::EventUnpublished
+            | DomainEventTypes::EventCancelled
+            | DomainEventTypes::EventDeleted => {
+                let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+                let event = Event::find_including_deleted(main_id, conn)?;
+                Event::event_payload_data(&event, &self.front_end_url, &mut data, conn)?;
+                data.insert(
+                    "webhook_event_type".to_string(),
+                    json!(webhook_event_type(domain_event.event_type)),
+                );
+                data.insert("show_status".to_string(), json!(event.status));
+                data.insert(
+                    "show_cancelled_at".to_string(),
+                    json!(event.cancelled_at.map(|c| c.timestamp())),
+                );
+                data.insert("timestamp".to_string(), json!(domain_event.created_at.timestamp()));
+                result.push(data);
+            }
+            DomainEventTypes::TicketTypeCreated
+            | DomainEventTypes::TicketTypeUpdated
+            | DomainEventTypes::TicketTypeSalesStarted
+            | DomainEventTypes::TicketTypeSoldOut => {
+                let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+                let ticket_type = TicketType::find(main_id, conn)?;
+                let event = ticket_type.event(conn)?;
+                Event::event_payload_data(&event, &self.front_end_url, &mut data, conn)?;
+                data.insert(
+                    "webhook_event_type".to_string(),
+                    json!(webhook_event_type(domain_event.event_type)),
+                );
+                data.insert("ticket_type_id".to_string(), json!(ticket_type.id));
+                data.insert("ticket_type_name".to_string(), json!(ticket_type.name));
+                data.insert("ticket_type_status".to_string(), json!(ticket_type.status));
+                data.insert(
+                    "ticket_type_start_date".to_string(),
+                    json!(ticket_type.start_date.map(|d| d.timestamp())),
+                );
+                data.insert(
+                    "ticket_type_end_date".to_string(),
+                    json!(ticket_type.end_date.map(|d| d.timestamp())),
+                );
+                data.insert(
+                    "ticket_type_limit_per_person".to_string(),
+                    json!(ticket_type.limit_per_person),
+                );
+                data.insert("timestamp".to_string(), json!(domain_event.created_at.timestamp()));
+                result.push(data);
+            }
+            DomainEventTypes::HoldCreated
+            | DomainEventTypes::HoldDeleted
+            | DomainEventTypes::HoldQuantityChanged
+            | DomainEventTypes::HoldAutomaticallyReleased => {
+                let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+                let hold = Hold::find(main_id, conn)?;
+                let event = hold.event(conn)?;
+                let (quantity, available) = hold.quantity(conn)?;
+                Event::event_payload_data(&event, &self.front_end_url, &mut data, conn)?;
+                data.insert(
+                    "webhook_event_type".to_string(),
+                    json!(webhook_event_type(domain_event.event_type)),
+                );
+                data.insert("hold_id".to_string(), json!(hold.id));
+                data.insert("hold_name".to_string(), json!(hold.name));
+                data.insert("hold_type".to_string(), json!(hold.hold_type));
+                data.insert("parent_hold_id".to_string(), json!(hold.parent_hold_id));
+                data.insert("ticket_type_id".to_string(), json!(hold.ticket_type_id));
+                data.insert("redemption_code".to_string(), json!(hold.redemption_code));
+                data.insert("discount_in_cents".to_string(), json!(hold.discount_in_cents));
+                data.insert("max_per_user".to_string(), json!(hold.max_per_user));
+                data.insert("end_at".to_string(), json!(hold.end_at.map(|e| e.timestamp())));
+                data.insert("quantity".to_string(), json!(quantity));
+                data.insert("available".to_string(), json!(available));
+                if domain_event.event_type == DomainEventTypes::HoldQuantityChanged {
+                    if let Some(ref event_data) = domain_event.event_data {
+                        data.insert("old_quantity".to_string(), event_data["old_quantity"].clone());
+                        data.insert("new_quantity".to_string(), event_data["new_quantity"].clone());
+                    }
+                }
+                data.insert("timestamp".to_string(), json!(domain_event.created_at.timestamp()));
+                result.push(data);
+            }
+            DomainEventTypes::CodeCreated | DomainEventTypes::CodeUpdated | DomainEventTypes::CodeDeleted => {
+                let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+                let code = Code::find_including_deleted(main_id, conn)?;
+                let event = code.event(conn)?;
+                Event::event_payload_data(&event, &self.front_end_url, &mut data, conn)?;
+                data.insert(
+                    "webhook_event_type".to_string(),
+                    json!(webhook_event_type(domain_event.event_type)),
+                );
+                data.insert("code_id".to_string(), json!(code.id));
+                data.insert("code_name".to_string(), json!(code.name));
+                data.insert("code_type".to_string(), json!(code.code_type));
+                data.insert("redemption_code".to_string(), json!(code.redemption_code));
+                data.insert("max_uses".to_string(), json!(code.max_uses));
+                data.insert("discount_in_cents".to_string(), json!(code.discount_in_cents));
+                data.insert("discount_as_percentage".to_string(), json!(code.discount_as_percentage));
+                data.insert("max_tickets_per_user".to_string(), json!(code.max_tickets_per_user));
+                data.insert("start_date".to_string(), json!(code.start_date.timestamp()));
+                data.insert("end_date".to_string(), json!(code.end_date.timestamp()));
+                data.insert("timestamp".to_string(), json!(domain_event.created_at.timestamp()));
+                result.push(data);
+            }
+            DomainEventTypes::TicketInstanceRedeemed => {
+                let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+                let ticket = TicketInstance::find(main_id, conn)?;
+                let ticket_type = ticket.ticket_type(conn)?;
+                let event = ticket.event(conn)?;
+                let wallet = Wallet::find(ticket.wallet_id, conn)?;
+                Event::event_payload_data(&event, &self.front_end_url, &mut data, conn)?;
+                data.insert("webhook_event_type".to_string(), json!("ticket_redeemed"));
+                data.insert("ticket_id".to_string(), json!(ticket.id));
+                data.insert(
+                    "ticket_number".to_string(),
+                    json!(TicketInstance::parse_ticket_number(ticket.id)),
+                );
+                data.insert("ticket_type_id".to_string(), json!(ticket_type.id));
+                data.insert("ticket_type_name".to_string(), json!(ticket_type.name));
+                data.insert("user_id".to_string(), json!(wallet.user_id));
+                data.insert("redeemed_by_user_id".to_string(), json!(ticket.redeemed_by_user_id));
+                data.insert(
+                    "redeemed_at".to_string(),
+                    json!(ticket.redeemed_at.map(|r| r.timestamp())),
+                );
+                data.insert("check_in_source".to_string(), json!(ticket.check_in_source));
+                data.insert("timestamp".to_string(), json!(domain_event.created_at.timestamp()));
+                result.push(data);
+            }
             _ => {
                 return Err(DatabaseError::new(
                     ErrorCode::BusinessProcessError,
@@ -231,6 +360,19 @@ impl WebhookPublisher {
         Ok(result)
     }
 
+    /// Payload sent when an organization tests a webhook subscription
+    pub fn create_test_payload(domain_event_publisher: &DomainEventPublisher) -> HashMap<String, serde_json::Value> {
+        let mut data: HashMap<String, serde_json::Value> = HashMap::new();
+        data.insert("webhook_event_type".to_string(), json!("test"));
+        data.insert(
+            "organization_id".to_string(),
+            json!(domain_event_publisher.organization_id),
+        );
+        data.insert("event_types".to_string(), json!(domain_event_publisher.event_types));
+        data.insert("timestamp".to_string(), json!(Utc::now().timestamp()));
+        data
+    }
+
     fn order_payload_data(
         &self,
         conn: &PgConnection,
@@ -264,6 +406,8 @@ impl WebhookPublisher {
         let mut refunded_fees_total = 0;
         let mut discount_total = 0;
         let mut refunded_discount_total = 0;
+        let mut tax_total = 0;
+        let mut refunded_tax_total = 0;
         let mut j_items = Vec::<R>::new();
         for item in order.items(conn)? {
             let item_total = item.unit_price_in_cents * item.quantity;
@@ -283,6 +427,10 @@ impl WebhookPublisher {
                     sub_total = sub_total + item_total;
                     refunded_sub_total = refunded_sub_total + refunded_total;
                 }
+                OrderItemTypes::Products => {
+                    sub_total = sub_total + item_total;
+                    refunded_sub_total = refunded_sub_total + refunded_total;
+                }
                 OrderItemTypes::Discount => {
                     discount_total = discount_total + item_total;
                     refunded_discount_total = refunded_discount_total + refunded_total;
@@ -291,6 +439,11 @@ impl WebhookPublisher {
                     fees_total = fees_total + item_total;
                     refunded_fees_total = refunded_fees_total + refunded_total;
                 }
+                OrderItemTypes::Tax => {
+                    // Includes taxes that are part of the ticket price
+                    tax_total = tax_total + item.tax_in_cents * item.quantity;
+                    refunded_tax_total = refunded_tax_total + item.tax_in_cents * item.refunded_quantity;
+                }
             }
         }
 
@@ -302,6 +455,8 @@ impl WebhookPublisher {
         data.insert("refunded_fees_total".to_string(), json!(refunded_fees_total));
         data.insert("discount_total".to_string(), json!(discount_total));
         data.insert("refunded_discount_total".to_string(), json!(refunded_discount_total));
+        data.insert("tax_total".to_string(), json!(tax_total));
+        data.insert("refunded_tax_total".to_string(), json!(refunded_tax_total));
 
         data.insert(
             "user_id".to_string(),
@@ -386,7 +541,7 @@ impl WebhookPublisher {
         conn: &PgConnection,
     ) -> Result<(), ApiError> {
         let transferer = User::find(transfer.source_user_id, conn)?;
-        let receive_tickets_url = transfer.receive_url(&self.front_end_url, conn)?;
+        let receive_tickets_url = transfer.receive_url(&self.front_end_url, &self.wallet_encryption_keys, conn)?;
         let mut new_receive_tickets_url = None;
         data.insert(
             "user_id".to_string(),
@@ -465,3 +620,19 @@ impl WebhookPublisher {
         Ok(())
     }
 }
+
+/// Snake cased event type sent as `webhook_event_type`, e.g. `TicketTypeCreated` => `ticket_type_created`
+fn webhook_event_type(event_type: DomainEventTypes) -> String {
+    let mut result = String::new();
+    for (i, c) in event_type.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
@@ -7,6 +7,7 @@ pub enum ApplicationErrorType {
     Internal,
     BadRequest,
     ServerConfigError,
+    TooManyRequests,
 }
 
 #[derive(Debug)]
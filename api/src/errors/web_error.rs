@@ -208,6 +208,7 @@ impl ConvertToWebError for ApplicationError {
             ApplicationErrorType::Unprocessable => StatusCode::UNPROCESSABLE_ENTITY,
             ApplicationErrorType::BadRequest => StatusCode::BAD_REQUEST,
             ApplicationErrorType::ServerConfigError => StatusCode::INTERNAL_SERVER_ERROR,
+            ApplicationErrorType::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
         }
     }
     fn to_response(&self) -> HttpResponse {
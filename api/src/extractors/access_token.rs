@@ -1,14 +1,27 @@
 use crate::errors::{ApiError, ApplicationError, AuthError};
-use crate::jwt::{decode, Validation};
 use crate::server::GetAppState;
 use actix_web::HttpMessage;
-use db::models::AccessToken;
+use db::models::{AccessToken, TokenIssuer};
 
 pub(crate) struct AccessTokenExtractor;
 impl AccessTokenExtractor {
     pub fn from_request<R>(req: &R) -> Result<AccessToken, ApiError>
     where
         R: HttpMessage + GetAppState,
+    {
+        let access_token = AccessTokenExtractor::bearer_token(req)?;
+        let token = req
+            .state()
+            .config
+            .token_issuer
+            .decode(&access_token)
+            .map_err(|_| AuthError::unauthorized("Invalid auth token"))?;
+        Ok(token.claims)
+    }
+
+    pub fn bearer_token<R>(req: &R) -> Result<String, ApiError>
+    where
+        R: HttpMessage,
     {
         if let Some(auth_header) = req.headers().get("Authorization") {
             let mut parts = auth_header
@@ -20,15 +33,7 @@ impl AccessTokenExtractor {
             }
 
             match parts.next() {
-                Some(access_token) => {
-                    let token = decode::<AccessToken>(
-                        &access_token,
-                        req.state().config.token_issuer.token_secret.as_bytes(),
-                        &Validation::default(),
-                    )
-                    .map_err(|_| AuthError::unauthorized("Invalid auth token"))?;
-                    Ok(token.claims)
-                }
+                Some(access_token) => Ok(access_token.to_string()),
                 None => Err(AuthError::unauthorized("No access token provided").into()),
             }
         } else {
@@ -3,20 +3,58 @@ use crate::auth::user::User;
 use crate::errors::{ApiError, AuthError};
 use crate::middleware::RequestConnection;
 use actix_web::{dev::Payload, FromRequest, HttpRequest};
-use db::models::User as DbUser;
+use db::models::{Organization, OrganizationApiKey, Scopes, User as DbUser};
 use futures::future::{err, ready, Ready};
 
+/// Organization owned resources whose endpoints all authorize the caller through organization or
+/// event scopes, API keys are only accepted below these and the event and organization paths
+const API_KEY_RESOURCES: &[&str] = &[
+    "bundles",
+    "codes",
+    "comps",
+    "domain_event_publishers",
+    "event_report_subscribers",
+    "event_series",
+    "holds",
+    "notes",
+    "products",
+    "reports",
+    "settlement_adjustments",
+    "settlements",
+    "tax_rates",
+    "webhook_deliveries",
+];
+
+/// Event endpoints that act for the caller themselves, such as their tickets or waitlist entries
+const API_KEY_EXCLUDED_EVENT_RESOURCES: &[&str] = &["interest", "tickets", "waitlist", "websockets"];
+
 impl FromRequest for User {
     type Config = ();
     type Error = ApiError;
     type Future = Ready<Result<User, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        if let Ok(bearer_token) = AccessTokenExtractor::bearer_token(req) {
+            if OrganizationApiKey::is_api_key(&bearer_token) {
+                if !api_key_accepted_for_path(req.path()) {
+                    return err(AuthError::unauthorized("API keys can not be used for this endpoint").into());
+                }
+                return ready(api_key_user(&bearer_token, req));
+            }
+        }
+
         let token = match AccessTokenExtractor::from_request(req) {
             Ok(token) => token,
             Err(e) => return err(e),
         };
 
+        // Challenge tokens only prove the password was correct, the second factor is still outstanding
+        if let Some(ref scopes) = token.scopes {
+            if scopes.contains(&Scopes::MfaChallenge.to_string()) {
+                return err(AuthError::unauthorized("Multi-factor authentication required").into());
+            }
+        }
+
         let connection = match req.connection() {
             Ok(conn) => conn,
             Err(e) => return err(e),
@@ -42,3 +80,38 @@ impl FromRequest for User {
         }
     }
 }
+
+fn api_key_user(key: &str, req: &HttpRequest) -> Result<User, ApiError> {
+    let connection = req.connection()?;
+    let connection = connection.get();
+    let api_key =
+        OrganizationApiKey::find_by_key(key, connection).map_err(|_| AuthError::unauthorized("Invalid API key"))?;
+    if !api_key.is_active() {
+        return Err(AuthError::unauthorized("API key has expired or been revoked").into());
+    }
+
+    // Keys stop working once the user who created them no longer has access to the organization
+    let user = DbUser::find(api_key.created_by_user_id, connection)?;
+    let organization = Organization::find(api_key.organization_id, connection)?;
+    if user.deleted_at.is_some() || !organization.is_member(&user, connection)? {
+        return Err(AuthError::unauthorized("API key creator no longer has access to the organization").into());
+    }
+
+    let api_key = api_key.record_usage(connection)?;
+    Ok(User::new_for_api_key(api_key, user, req))
+}
+
+/// Whether an organization API key may authenticate a request for this path, keys are limited to
+/// organization resources so they can never act as their creator on the creator's own account
+pub fn api_key_accepted_for_path(path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["organizations", _, ..] => true,
+        ["events", "checkins"] => false,
+        ["events", _, rest @ ..] => !rest
+            .iter()
+            .any(|segment| API_KEY_EXCLUDED_EVENT_RESOURCES.contains(segment)),
+        [resource, _, ..] => API_KEY_RESOURCES.contains(resource),
+        _ => false,
+    }
+}
@@ -40,6 +40,10 @@ pub fn bad_request<T: Responder>(message: &str) -> Result<T, ApiError> {
     Err(ApplicationError::new_with_type(ApplicationErrorType::BadRequest, message.to_string()).into())
 }
 
+pub fn too_many_requests<T: Responder>(message: &str) -> Result<T, ApiError> {
+    Err(ApplicationError::new_with_type(ApplicationErrorType::TooManyRequests, message.to_string()).into())
+}
+
 pub fn internal_server_error<T: Responder>(message: &str) -> Result<T, ApiError> {
     error!("Internal Server Error: {}", message);
     Err(ApplicationError::new(message.to_string()).into())
@@ -0,0 +1,184 @@
+use crate::config::LoginThrottlingConfig;
+use actix_web::HttpRequest;
+use cache::{CacheConnection, CacheError};
+use chrono::prelude::*;
+use itertools::Itertools;
+use std::cmp;
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+// Failed attempts allowed before each further attempt has to wait, the wait doubles per failure
+const BACKOFF_AFTER_FAILED_ATTEMPTS: i64 = 3;
+const MAX_BACKOFF_SECONDS: i64 = 300;
+const KEY_PREFIX: &str = "login_throttle";
+
+#[derive(Debug, PartialEq)]
+pub enum LoginThrottleStatus {
+    Allowed,
+    BackingOff { retry_after_seconds: i64 },
+    Locked,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FailedLogin {
+    pub failed_attempts: i64,
+    /// Only present for the failure which locked the account
+    pub unlock_token: Option<String>,
+}
+
+/// Address of the client making the request for throttling purposes. Only the `X-Forwarded-For`
+/// entries appended by trusted proxies are used as anything before them is supplied by the client.
+pub fn client_ip_address(request: &HttpRequest, trusted_proxy_count: usize) -> Option<String> {
+    let forwarded_for = request
+        .headers()
+        .get_all("X-Forwarded-For")
+        .filter_map(|value| value.to_str().ok())
+        .join(",");
+    forwarded_client_ip_address(request.peer_addr(), Some(&forwarded_for), trusted_proxy_count)
+}
+
+pub fn forwarded_client_ip_address(
+    peer_address: Option<SocketAddr>,
+    forwarded_for: Option<&str>,
+    trusted_proxy_count: usize,
+) -> Option<String> {
+    if trusted_proxy_count > 0 {
+        let forwarded_addresses: Vec<&str> = forwarded_for
+            .unwrap_or("")
+            .split(',')
+            .map(|address| address.trim())
+            .filter(|address| !address.is_empty())
+            .collect();
+        // Requests which did not pass through every proxy are identified by the connecting peer
+        if forwarded_addresses.len() >= trusted_proxy_count {
+            return Some(forwarded_addresses[forwarded_addresses.len() - trusted_proxy_count].to_string());
+        }
+    }
+
+    peer_address.map(|address| address.ip().to_string())
+}
+
+/// Tracks failed password logins per account and per IP address in the cache
+pub struct LoginThrottle<C: CacheConnection> {
+    cache: C,
+    config: LoginThrottlingConfig,
+}
+
+impl<C: CacheConnection> LoginThrottle<C> {
+    pub fn new(cache: C, config: LoginThrottlingConfig) -> Self {
+        LoginThrottle { cache, config }
+    }
+
+    pub fn status(&mut self, email: &str, ip_address: Option<&str>) -> Result<LoginThrottleStatus, CacheError> {
+        let account = account(email);
+        if self.cache.get(&key("lockout", &account))?.is_some() {
+            return Ok(LoginThrottleStatus::Locked);
+        }
+
+        let mut retry_after_seconds = self.backoff_remaining(&account)?;
+        if let Some(ip_address) = ip_address {
+            retry_after_seconds = cmp::max(retry_after_seconds, self.backoff_remaining(&ip(ip_address))?);
+        }
+
+        if retry_after_seconds > 0 {
+            return Ok(LoginThrottleStatus::BackingOff { retry_after_seconds });
+        }
+        Ok(LoginThrottleStatus::Allowed)
+    }
+
+    pub fn record_failure(&mut self, email: &str, ip_address: Option<&str>) -> Result<FailedLogin, CacheError> {
+        let account = account(email);
+        let window = self.config.lockout_period.num_milliseconds() as usize;
+
+        let failed_attempts = self.cache.increment(&key("failures", &account), Some(window))?;
+        self.back_off(&account, backoff_seconds(failed_attempts))?;
+
+        if let Some(ip_address) = ip_address {
+            let ip = ip(ip_address);
+            let ip_failed_attempts = self.cache.increment(&key("failures", &ip), Some(window))?;
+            let seconds = if ip_failed_attempts >= self.config.max_failed_attempts_per_ip {
+                self.config.lockout_period.num_seconds()
+            } else {
+                backoff_seconds(ip_failed_attempts)
+            };
+            self.back_off(&ip, seconds)?;
+        }
+
+        let mut unlock_token = None;
+        if failed_attempts >= self.config.max_failed_attempts {
+            let token = Uuid::new_v4().to_string();
+            self.cache.add(&key("lockout", &account), "1", Some(window))?;
+            self.cache.add(&key("unlock", &token), &account, Some(window))?;
+            unlock_token = Some(token);
+        }
+
+        Ok(FailedLogin {
+            failed_attempts,
+            unlock_token,
+        })
+    }
+
+    pub fn record_success(&mut self, email: &str) -> Result<(), CacheError> {
+        self.reset(&account(email))
+    }
+
+    /// Lifts the lockout for the account the token was issued to, returns the account's email
+    pub fn unlock(&mut self, unlock_token: &str) -> Result<Option<String>, CacheError> {
+        let unlock_key = key("unlock", unlock_token);
+        let account = match self.cache.get(&unlock_key)? {
+            Some(account) => account,
+            None => return Ok(None),
+        };
+
+        self.reset(&account)?;
+        self.cache.delete(&key("lockout", &account))?;
+        self.cache.delete(&unlock_key)?;
+        Ok(Some(account))
+    }
+
+    fn reset(&mut self, subject: &str) -> Result<(), CacheError> {
+        self.cache.delete(&key("failures", subject))?;
+        self.cache.delete(&key("backoff", subject))
+    }
+
+    fn back_off(&mut self, subject: &str, seconds: i64) -> Result<(), CacheError> {
+        if seconds <= 0 {
+            return Ok(());
+        }
+
+        let until = Utc::now().timestamp() + seconds;
+        self.cache.add(
+            &key("backoff", subject),
+            &until.to_string(),
+            Some((seconds * 1000) as usize),
+        )
+    }
+
+    fn backoff_remaining(&mut self, subject: &str) -> Result<i64, CacheError> {
+        let until: i64 = match self.cache.get(&key("backoff", subject))? {
+            Some(until) => until.parse().unwrap_or(0),
+            None => return Ok(0),
+        };
+        Ok(cmp::max(until - Utc::now().timestamp(), 0))
+    }
+}
+
+fn backoff_seconds(failed_attempts: i64) -> i64 {
+    if failed_attempts < BACKOFF_AFTER_FAILED_ATTEMPTS {
+        return 0;
+    }
+    let exponent = cmp::min(failed_attempts - BACKOFF_AFTER_FAILED_ATTEMPTS + 1, 16) as u32;
+    cmp::min(2i64.pow(exponent), MAX_BACKOFF_SECONDS)
+}
+
+fn account(email: &str) -> String {
+    format!("account:{}", email.trim().to_lowercase())
+}
+
+fn ip(ip_address: &str) -> String {
+    format!("ip:{}", ip_address)
+}
+
+fn key(kind: &str, subject: &str) -> String {
+    format!("{}:{}:{}", KEY_PREFIX, kind, subject)
+}
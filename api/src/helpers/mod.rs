@@ -1,2 +1,3 @@
 pub mod application;
 pub mod caching;
+pub mod login_throttling;
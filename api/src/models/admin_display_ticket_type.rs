@@ -28,6 +28,9 @@ pub struct AdminDisplayTicketType {
     pub app_sales_enabled: bool,
     pub web_sales_enabled: bool,
     pub box_office_sales_enabled: bool,
+    pub reentry_policy: ReentryPolicy,
+    pub reentry_limit: Option<i32>,
+    pub access_zone_ids: Vec<Uuid>,
 }
 
 impl AdminDisplayTicketType {
@@ -56,6 +59,8 @@ impl AdminDisplayTicketType {
             None => None,
         };
 
+        let access_zone_ids = ticket_type.access_zones(conn)?.into_iter().map(|z| z.id).collect();
+
         let result = AdminDisplayTicketType {
             id: ticket_type.id,
             name: ticket_type.name.clone(),
@@ -80,6 +85,9 @@ impl AdminDisplayTicketType {
             app_sales_enabled: ticket_type.app_sales_enabled,
             web_sales_enabled: ticket_type.web_sales_enabled,
             box_office_sales_enabled: ticket_type.box_office_sales_enabled,
+            reentry_policy: ticket_type.reentry_policy,
+            reentry_limit: ticket_type.reentry_limit,
+            access_zone_ids,
         };
         Ok(result)
     }
@@ -7,6 +7,7 @@ pub use self::event_venue_entry::*;
 pub use self::event_websocket::*;
 pub use self::event_websocket_message::*;
 pub use self::facebook_web_login_token::*;
+pub use self::oidc_web_login_token::*;
 pub use self::past_or_upcoming_parameters::*;
 pub use self::path_parameters::*;
 pub use self::payload::*;
@@ -26,6 +27,7 @@ mod event_venue_entry;
 mod event_websocket;
 mod event_websocket_message;
 mod facebook_web_login_token;
+mod oidc_web_login_token;
 mod past_or_upcoming_parameters;
 mod path_parameters;
 mod payload;
@@ -0,0 +1,12 @@
+use crate::utils::serializers::default_as_false;
+
+#[derive(Deserialize, Default, Serialize)]
+pub struct OidcWebLoginToken {
+    pub id_token: String,
+    /// Links the provider account to the logged in user instead of logging in
+    #[serde(default = "default_as_false")]
+    pub link_to_user_id: bool,
+    /// Apple only shares the user's name with the app on first sign in, not in the ID token
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
@@ -27,12 +27,24 @@ pub struct MainTablePathParameters {
     pub id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct EventSeriesTicketTypePathParameters {
+    pub id: Uuid, // Event series Id
+    pub ticket_type_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct EventTicketPathParameters {
     pub event_id: Uuid,
     pub ticket_type_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct OrganizationApiKeyPathParameters {
+    pub id: Uuid, // Organization Id
+    pub api_key_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct OrganizationFanPathParameters {
     pub id: Uuid, // Organization Id
@@ -51,6 +63,18 @@ pub struct OrganizationInvitePathParameters {
     pub invite_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct OrganizationWebhookPathParameters {
+    pub id: Uuid, // Organization Id
+    pub webhook_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookDeliveryPathParameters {
+    pub id: Uuid, // Domain event publisher Id
+    pub webhook_delivery_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct CompPathParameters {
     pub hold_id: Uuid,
@@ -0,0 +1,191 @@
+use crate::payments::*;
+use ::braintree::{BraintreeClient, BraintreeError};
+use db::models::PaymentProviders;
+
+impl From<BraintreeError> for PaymentProcessorError {
+    fn from(b: BraintreeError) -> PaymentProcessorError {
+        let validation_response = match b.error_code {
+            Some(ref error_code) => match error_code.as_ref() {
+                "PROCESSOR_DECLINED" | "GATEWAY_REJECTED" => Some("Card has been declined"),
+                "81707" => Some("Invalid CVC"),
+                "81712" => Some("Invalid card expiry month"),
+                "81713" => Some("Invalid card expiry year"),
+                "81715" => Some("Invalid card number"),
+                _ => None,
+            },
+            None => None,
+        }
+        .map(|s| s.to_string());
+
+        PaymentProcessorError {
+            description: b.description.clone(),
+            cause: Some(Box::new(b)),
+            validation_response,
+        }
+    }
+}
+
+pub struct BraintreePaymentProcessor {
+    client: BraintreeClient,
+    currency: String,
+}
+
+impl BraintreePaymentProcessor {
+    /// `currency` is the currency of the merchant account, Braintree charges every transaction in it
+    pub fn new(
+        public_key: String,
+        private_key: String,
+        merchant_account_id: String,
+        currency: String,
+        base_url: String,
+    ) -> BraintreePaymentProcessor {
+        BraintreePaymentProcessor {
+            client: BraintreeClient::new(public_key, private_key, merchant_account_id, base_url),
+            currency,
+        }
+    }
+}
+
+pub struct BraintreePaymentBehavior {
+    client: BraintreeClient,
+    currency: String,
+}
+
+#[async_trait::async_trait]
+impl PaymentProcessor for BraintreePaymentProcessor {
+    fn behavior(&self) -> PaymentProcessorBehavior {
+        PaymentProcessorBehavior::AuthThenComplete(Box::new(BraintreePaymentBehavior {
+            client: self.client.clone(),
+            currency: self.currency.clone(),
+        }))
+    }
+
+    async fn update_metadata(
+        &self,
+        charge_id: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<UpdateMetadataResult, PaymentProcessorError> {
+        Ok(self
+            .client
+            .update_custom_fields(charge_id, metadata)
+            .await
+            .map(|r| UpdateMetadataResult {
+                id: r.id,
+                raw: r.raw_data,
+            })?)
+    }
+
+    async fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        Ok(self.client.reverse(auth_token).await.map(|r| ChargeAuthResult {
+            id: r.id,
+            raw: r.raw_data,
+            client_secret: None,
+        })?)
+    }
+
+    async fn partial_refund(&self, auth_token: &str, amount: i64) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        Ok(self
+            .client
+            .partial_refund(auth_token, amount)
+            .await
+            .map(|r| ChargeAuthResult {
+                id: r.id,
+                raw: r.raw_data,
+                client_secret: None,
+            })?)
+    }
+
+    fn partial_refund_blocking(
+        &self,
+        auth_token: &str,
+        amount: i64,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        Ok(self
+            .client
+            .partial_refund_blocking(auth_token, amount)
+            .map(|r| ChargeAuthResult {
+                id: r.id,
+                raw: r.raw_data,
+                client_secret: None,
+            })?)
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthThenCompletePaymentBehavior for BraintreePaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Braintree
+    }
+
+    /// Cards are vaulted with the organization's Braintree gateway, which other organizations cannot charge
+    fn repeat_tokens_per_organization(&self) -> bool {
+        true
+    }
+
+    async fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        _description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        Ok(self
+            .client
+            .vault_payment_method(token)
+            .await
+            .map(|r| RepeatChargeToken {
+                token: r.id,
+                raw: r.raw_data,
+            })?)
+    }
+
+    /// Braintree cannot replace the card behind a vaulted payment method, so the new card is
+    /// vaulted and its token replaces the stored one
+    async fn update_repeat_token(
+        &self,
+        _repeat_token: &str,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        self.create_token_for_repeat_charges(token, description).await
+    }
+
+    /// The currency is fixed by the organization's Braintree merchant account, charges in any other
+    /// currency are rejected rather than being charged in the merchant account's currency
+    async fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        _application_fee_in_cents: i64,
+        currency: &str,
+        _description: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        if !currency.eq_ignore_ascii_case(&self.currency) {
+            let message = format!(
+                "Payments in {} are not supported by this organization's Braintree merchant account",
+                currency.to_uppercase()
+            );
+            return Err(PaymentProcessorError {
+                description: message.clone(),
+                cause: None,
+                validation_response: Some(message),
+            });
+        }
+
+        Ok(self
+            .client
+            .authorize(token, amount, metadata)
+            .await
+            .map(|r| ChargeAuthResult {
+                id: r.id,
+                raw: r.raw_data,
+                client_secret: None,
+            })?)
+    }
+
+    async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        Ok(self.client.capture(auth_token).await.map(|r| ChargeResult {
+            id: r.id,
+            raw: r.raw_data,
+        })?)
+    }
+}
@@ -1,9 +1,16 @@
 pub struct ChargeAuthResult {
     pub id: String,
     pub raw: String,
+    /// Present when the customer must complete additional authentication (e.g. 3-D Secure)
+    /// before the charge can be completed
+    pub client_secret: Option<String>,
 }
 
 impl ChargeAuthResult {
+    pub fn requires_action(&self) -> bool {
+        self.client_secret.is_some()
+    }
+
     pub fn to_json(&self) -> Result<serde_json::Value, serde_json::Error> {
         serde_json::from_str(&self.raw)
     }
@@ -5,6 +5,7 @@ pub use self::payment_processor_error::*;
 pub use self::repeat_charge_token::*;
 pub use self::update_metadata_result::*;
 
+pub mod braintree;
 mod charge_auth_result;
 mod charge_result;
 pub mod globee;
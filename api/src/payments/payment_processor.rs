@@ -12,6 +12,12 @@ pub enum PaymentProcessorBehavior {
 pub trait AuthThenCompletePaymentBehavior {
     fn payment_provider(&self) -> PaymentProviders;
 
+    /// Whether repeat charge tokens are created with the organization's own processor account, such
+    /// tokens can only be charged for that organization
+    fn repeat_tokens_per_organization(&self) -> bool {
+        false
+    }
+
     async fn create_token_for_repeat_charges(
         &self,
         token: &str,
@@ -25,15 +31,31 @@ pub trait AuthThenCompletePaymentBehavior {
         description: &str,
     ) -> Result<RepeatChargeToken, PaymentProcessorError>;
 
+    /// `application_fee_in_cents` is the platform's share of `amount` for processors that split charges
     async fn auth(
         &self,
         token: &str,
         amount: i64,
+        application_fee_in_cents: i64,
         currency: &str,
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError>;
 
+    /// Reloads an auth after the customer has completed additional authentication.
+    /// Fails if the customer could not be authenticated.
+    async fn find_authed_charge(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        Err(PaymentProcessorError {
+            description: format!(
+                "{} does not support additional authentication of charge {}",
+                self.payment_provider(),
+                auth_token
+            ),
+            cause: None,
+            validation_response: None,
+        })
+    }
+
     async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError>;
 }
 
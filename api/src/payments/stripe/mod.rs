@@ -1,6 +1,40 @@
 use crate::payments::*;
-use ::stripe::{StripeClient, StripeError};
+use ::stripe::{DestinationCharge, PaymentIntent, StripeClient, StripeError};
 use db::models::PaymentProviders;
+use db::utils::hash::hmac_sha256;
+use ring::constant_time;
+
+/// Webhooks signed longer ago than this are rejected to protect against replayed requests
+pub const WEBHOOK_SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
+
+/// Verifies the `Stripe-Signature` header of a webhook, `t=<timestamp>,v1=<signature>`. Stripe signs
+/// `<timestamp>.<payload>` with the endpoint's secret and may send several `v1` signatures while
+/// the secret is being rolled.
+pub fn verify_webhook_signature(payload: &str, signature_header: &str, webhook_secret: &str, now: i64) -> bool {
+    let mut timestamp: Option<i64> = None;
+    let mut signatures = Vec::new();
+    for part in signature_header.split(',') {
+        let mut key_value = part.trim().splitn(2, '=');
+        match (key_value.next(), key_value.next()) {
+            (Some("t"), Some(value)) => timestamp = value.parse().ok(),
+            (Some("v1"), Some(value)) => signatures.push(value),
+            _ => (),
+        }
+    }
+
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => return false,
+    };
+    if (now - timestamp).abs() > WEBHOOK_SIGNATURE_TOLERANCE_SECONDS {
+        return false;
+    }
+
+    let expected_signature = hmac_sha256::sign(webhook_secret, &format!("{}.{}", timestamp, payload));
+    signatures.iter().any(|signature| {
+        constant_time::verify_slices_are_equal(signature.as_bytes(), expected_signature.as_bytes()).is_ok()
+    })
+}
 
 impl From<StripeError> for PaymentProcessorError {
     fn from(s: StripeError) -> PaymentProcessorError {
@@ -34,18 +68,22 @@ impl From<StripeError> for PaymentProcessorError {
 
 pub struct StripePaymentProcessor {
     client: StripeClient,
+    connected_account_id: Option<String>,
 }
 
 impl StripePaymentProcessor {
-    pub fn new(stripe_secret_key: String) -> StripePaymentProcessor {
+    /// When the organization has a connected account charges are made as destination charges to it
+    pub fn new(stripe_secret_key: String, connected_account_id: Option<String>) -> StripePaymentProcessor {
         StripePaymentProcessor {
             client: StripeClient::new(stripe_secret_key),
+            connected_account_id,
         }
     }
 }
 
 pub struct StripePaymentBehavior {
     client: StripeClient,
+    connected_account_id: Option<String>,
 }
 
 #[async_trait::async_trait]
@@ -53,6 +91,7 @@ impl PaymentProcessor for StripePaymentProcessor {
     fn behavior(&self) -> PaymentProcessorBehavior {
         PaymentProcessorBehavior::AuthThenComplete(Box::new(StripePaymentBehavior {
             client: self.client.clone(),
+            connected_account_id: self.connected_account_id.clone(),
         }))
     }
 
@@ -72,9 +111,26 @@ impl PaymentProcessor for StripePaymentProcessor {
     }
 
     async fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        // Uncaptured payment intents cannot be refunded, their hold is released instead
+        if auth_token.starts_with("pi_") {
+            let payment_intent = self.client.find_payment_intent(auth_token).await?;
+            if payment_intent.is_authorized() || payment_intent.requires_action() {
+                return Ok(self
+                    .client
+                    .cancel_payment_intent(auth_token)
+                    .await
+                    .map(|r| ChargeAuthResult {
+                        id: r.id,
+                        raw: r.raw_data,
+                        client_secret: None,
+                    })?);
+            }
+        }
+
         Ok(self.client.refund(auth_token).await.map(|r| ChargeAuthResult {
             id: r.id,
             raw: r.raw_data,
+            client_secret: None,
         })?)
     }
 
@@ -86,6 +142,7 @@ impl PaymentProcessor for StripePaymentProcessor {
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
+                client_secret: None,
             })?)
     }
 
@@ -100,6 +157,7 @@ impl PaymentProcessor for StripePaymentProcessor {
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
+                client_secret: None,
             })?)
     }
 }
@@ -144,24 +202,69 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         &self,
         token: &str,
         amount: i64,
+        application_fee_in_cents: i64,
         currency: &str,
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
-        Ok(self
+        let destination = self.connected_account_id.as_ref().map(|account_id| DestinationCharge {
+            account_id: account_id.clone(),
+            application_fee_amount: application_fee_in_cents,
+        });
+        let payment_intent = self
             .client
-            .auth(token, amount, currency, description, metadata)
-            .await
-            .map(|r| ChargeAuthResult {
-                id: r.id,
-                raw: r.raw_data,
-            })?)
+            .create_payment_intent(token, amount, currency, description, destination, metadata)
+            .await?;
+        StripePaymentBehavior::to_charge_auth_result(payment_intent)
     }
 
-    async fn complete_authed_charge(&self, _auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
-        Ok(self.client.complete(_auth_token).await.map(|r| ChargeResult {
+    async fn find_authed_charge(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let payment_intent = self.client.find_payment_intent(auth_token).await?;
+        StripePaymentBehavior::to_charge_auth_result(payment_intent)
+    }
+
+    async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        if auth_token.starts_with("pi_") {
+            return Ok(self
+                .client
+                .capture_payment_intent(auth_token)
+                .await
+                .map(|r| ChargeResult {
+                    id: r.id,
+                    raw: r.raw_data,
+                })?);
+        }
+
+        // Charges authorized before payment intents were introduced
+        Ok(self.client.complete(auth_token).await.map(|r| ChargeResult {
             id: r.id,
             raw: r.raw_data,
         })?)
     }
 }
+
+impl StripePaymentBehavior {
+    fn to_charge_auth_result(payment_intent: PaymentIntent) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        if payment_intent.has_failed() {
+            return Err(PaymentProcessorError {
+                description: format!(
+                    "Payment intent {} could not be authorized, status: {}",
+                    payment_intent.id, payment_intent.status
+                ),
+                cause: None,
+                validation_response: Some("Card authentication failed".to_string()),
+            });
+        }
+
+        let client_secret = if payment_intent.requires_action() {
+            payment_intent.client_secret
+        } else {
+            None
+        };
+        Ok(ChargeAuthResult {
+            id: payment_intent.id,
+            raw: payment_intent.raw_data,
+            client_secret,
+        })
+    }
+}
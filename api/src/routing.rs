@@ -7,6 +7,16 @@ pub fn routes(app: &mut web::ServiceConfig) {
     // Please try to keep in alphabetical order
 
     app.service(
+        web::resource("/.well-known/jwks.json")
+            .wrap(CacheResource::new(CacheUsersBy::None))
+            .route(web::get().to(auth::jwks)),
+    )
+    .service(
+        web::resource("/access_zones/{id}")
+            .route(web::put().to(access_zones::update))
+            .route(web::delete().to(access_zones::destroy)),
+    )
+    .service(
         web::resource("/admin/stuck_domain_actions").route(web::get().to(admin::admin::admin_stuck_domain_actions)),
     )
     .service(web::resource("/admin/ticket_count").route(web::get().to(admin::admin::admin_ticket_count)))
@@ -44,7 +54,9 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::post().to(artists::create)),
     )
     .service(web::resource("/auth/token").route(web::post().to(auth::token)))
+    .service(web::resource("/auth/token/mfa").route(web::post().to(auth::token_mfa)))
     .service(web::resource("/auth/token/refresh").route(web::post().to(auth::token_refresh)))
+    .service(web::resource("/auth/unlock").route(web::post().to(auth::unlock)))
     .service(
         web::resource("/broadcasts/{id}")
             .route(web::get().to(broadcasts::show))
@@ -52,6 +64,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::delete().to(broadcasts::delete)),
     )
     .service(web::resource("/broadcasts/{id}/tracking_count").route(web::post().to(broadcasts::tracking_count)))
+    .service(
+        web::resource("/bundles/{id}")
+            .route(web::get().to(bundles::show))
+            .route(web::put().to(bundles::update))
+            .route(web::delete().to(bundles::destroy)),
+    )
     .service(
         web::resource("/cart")
             .route(web::delete().to(cart::destroy))
@@ -62,6 +80,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/cart/{id}/duplicate").route(web::post().to(cart::duplicate)))
     .service(web::resource("/cart/clear_invalid_items").route(web::delete().to(cart::clear_invalid_items)))
     .service(web::resource("/cart/checkout").route(web::post().to(cart::checkout)))
+    .service(web::resource("/cart/{id}/confirm").route(web::post().to(cart::confirm)))
     .service(web::resource("/codes/{id}/link").route(web::get().to(codes::link)))
     .service(
         web::resource("/codes/{id}")
@@ -75,7 +94,26 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::patch().to(comps::update))
             .route(web::delete().to(comps::destroy)),
     )
+    .service(
+        web::resource("/domain_event_publishers/{id}/webhook_deliveries")
+            .route(web::get().to(webhook_deliveries::index)),
+    )
+    .service(
+        web::resource("/domain_event_publishers/{id}/webhook_deliveries/{webhook_delivery_id}/replay")
+            .route(web::post().to(webhook_deliveries::replay)),
+    )
     .service(web::resource("/event_report_subscribers/{id}").route(web::delete().to(event_report_subscribers::destroy)))
+    .service(web::resource("/event_series").route(web::post().to(event_series::create)))
+    .service(
+        web::resource("/event_series/{id}")
+            .route(web::get().to(event_series::show))
+            .route(web::put().to(event_series::update)),
+    )
+    .service(web::resource("/event_series/{id}/events").route(web::put().to(event_series::update_events)))
+    .service(
+        web::resource("/event_series/{id}/ticket_types/{ticket_type_id}")
+            .route(web::patch().to(event_series::update_ticket_type)),
+    )
     .service(
         web::resource("/events")
         // In future it may be better to cache this for every user to save the database hit
@@ -99,6 +137,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::put().to(events::update_artists)),
     )
     .service(web::resource("/events/{id}/ticket_holder_count").route(web::get().to(events::ticket_holder_count)))
+    .service(
+        web::resource("/events/{id}/access_zones")
+            .route(web::get().to(access_zones::index))
+            .route(web::post().to(access_zones::create)),
+    )
+    .service(web::resource("/events/{id}/check_out").route(web::post().to(events::check_out_ticket)))
     .service(web::resource("/events/{id}/clone").route(web::post().to(events::clone)))
     .service(
         web::resource("/events/{id}/codes")
@@ -118,6 +162,8 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::post().to(events::add_interest))
             .route(web::delete().to(events::remove_interest)),
     )
+    .service(web::resource("/events/{id}/products/redeem").route(web::post().to(products::redeem)))
+    .service(web::resource("/events/{id}/products").route(web::get().to(products::index_for_event)))
     .service(web::resource("/events/{id}/publish").route(web::post().to(events::publish)))
     .service(
         web::resource("/events/{id}/broadcasts")
@@ -127,6 +173,14 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/events/{id}/links").route(web::post().to(events::create_link)))
     .service(web::resource("/events/{id}/rarities").route(web::post().to(rarities::create)))
+    .service(web::resource("/events/{id}/occupancy").route(web::get().to(events::occupancy)))
+    .service(web::resource("/events/{id}/offline_redemptions").route(web::post().to(events::redeem_offline)))
+    .service(web::resource("/events/{id}/scanner_snapshot").route(web::get().to(events::scanner_snapshot)))
+    .service(
+        web::resource("/events/{id}/seats")
+            .route(web::get().to(seat_maps::event_seats))
+            .route(web::put().to(seat_maps::update_event_seating)),
+    )
     .service(web::resource("/events/{id}/redeem/{ticket_instance_id}").route(web::post().to(events::redeem_ticket)))
     .service(web::resource("/events/{id}/redeem").route(web::post().to(events::redeem_ticket)))
     .service(
@@ -146,6 +200,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::patch().to(ticket_types::update))
             .route(web::delete().to(ticket_types::cancel)),
     )
+    .service(
+        web::resource("/events/{event_id}/ticket_types/{ticket_type_id}/waitlist")
+            .route(web::get().to(waitlist_entries::show))
+            .route(web::post().to(waitlist_entries::create))
+            .route(web::delete().to(waitlist_entries::destroy)),
+    )
     .service(web::resource("/events/{id}/unpublish").route(web::post().to(events::unpublish)))
     .service(web::resource("/events/{id}/users").route(web::get().to(events::users)))
     .service(web::resource("/events/{id}/users/invites").route(web::post().to(organization_invites::create_for_event)))
@@ -159,6 +219,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/external/facebook/web_login").route(web::post().to(external::facebook::web_login)))
     .service(web::resource("/external/facebook/scopes").route(web::get().to(external::facebook::scopes)))
     .service(web::resource("/external/facebook").route(web::delete().to(external::facebook::disconnect)))
+    .service(web::resource("/external/oidc/{id}/web_login").route(web::post().to(external::oidc::web_login)))
     .service(
         web::resource("/genres")
             .wrap(CacheResource::new(CacheUsersBy::None))
@@ -167,6 +228,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/invitations/{id}").route(web::get().to(organization_invites::view)))
     .service(web::resource("/invitations").route(web::post().to(organization_invites::accept_request)))
     .service(web::resource("/ipns/globee").route(web::post().to(ipns::globee)))
+    .service(web::resource("/ipns/stripe").route(web::post().to(ipns::stripe)))
     .service(
         web::resource("/holds/{id}/comps")
             .route(web::get().to(comps::index))
@@ -206,6 +268,15 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(organization_venues::show))
             .route(web::delete().to(organization_venues::destroy)),
     )
+    .service(
+        web::resource("/organizations/{id}/api_keys/{api_key_id}")
+            .route(web::delete().to(organization_api_keys::destroy)),
+    )
+    .service(
+        web::resource("/organizations/{id}/api_keys")
+            .route(web::get().to(organization_api_keys::index))
+            .route(web::post().to(organization_api_keys::create)),
+    )
     .service(
         web::resource("/organizations/{id}/announcements").route(web::get().to(announcements::show_from_organization)),
     )
@@ -214,6 +285,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(artists::show_from_organizations))
             .route(web::post().to(organizations::add_artist)),
     )
+    .service(
+        web::resource("/organizations/{id}/bundles")
+            .route(web::get().to(bundles::index))
+            .route(web::post().to(bundles::create)),
+    )
+    .service(web::resource("/organizations/{id}/event_series").route(web::get().to(event_series::index)))
     .service(web::resource("/organizations/{id}/events").route(web::get().to(events::show_from_organizations)))
     .service(web::resource("/organizations/{id}/export_event_data").route(web::get().to(events::export_event_data)))
     .service(
@@ -261,11 +338,21 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(organization_venues::organizations_index))
             .route(web::post().to(organization_venues::create)),
     )
+    .service(
+        web::resource("/organizations/{id}/products")
+            .route(web::get().to(products::index))
+            .route(web::post().to(products::create)),
+    )
     .service(
         web::resource("/organizations/{id}/settlements")
             .route(web::get().to(settlements::index))
             .route(web::post().to(settlements::create)),
     )
+    .service(
+        web::resource("/organizations/{id}/tax_rates")
+            .route(web::get().to(tax_rates::index))
+            .route(web::post().to(tax_rates::create)),
+    )
     .service(
         web::resource("/organizations/{id}/invites")
             .route(web::get().to(organization_invites::index))
@@ -279,6 +366,28 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/organizations/{id}/users/{user_id}").route(web::delete().to(organizations::remove_user)))
     .service(web::resource("/organizations/{id}/venues").route(web::get().to(venues::show_from_organizations)))
+    .service(
+        web::resource("/organizations/{id}/webhooks/{webhook_id}/pause")
+            .route(web::put().to(organization_webhooks::pause)),
+    )
+    .service(
+        web::resource("/organizations/{id}/webhooks/{webhook_id}/resume")
+            .route(web::put().to(organization_webhooks::resume)),
+    )
+    .service(
+        web::resource("/organizations/{id}/webhooks/{webhook_id}/test")
+            .route(web::post().to(organization_webhooks::test)),
+    )
+    .service(
+        web::resource("/organizations/{id}/webhooks/{webhook_id}")
+            .route(web::put().to(organization_webhooks::update))
+            .route(web::delete().to(organization_webhooks::destroy)),
+    )
+    .service(
+        web::resource("/organizations/{id}/webhooks")
+            .route(web::get().to(organization_webhooks::index))
+            .route(web::post().to(organization_webhooks::create)),
+    )
     .service(
         web::resource("/organizations/{id}")
             .route(web::get().to(organizations::show))
@@ -296,6 +405,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/payments/callback/{nonce}/{id}").route(web::get().to(payments::callback)))
     .service(web::resource("/payment_methods").route(web::get().to(payment_methods::index)))
+    .service(
+        web::resource("/products/{id}")
+            .route(web::get().to(products::show))
+            .route(web::put().to(products::update))
+            .route(web::delete().to(products::destroy)),
+    )
     .service(web::resource("/redemption_codes/{code}").route(web::get().to(redemption_codes::show)))
     .service(
         web::resource("/regions/{id}")
@@ -309,7 +424,13 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(regions::index))
             .route(web::post().to(regions::create)),
     )
+    .service(web::resource("/report_exports/{id}/download").route(web::get().to(report_exports::download)))
     .service(web::resource("/reports/{id}").route(web::get().to(reports::get_report)))
+    .service(
+        web::resource("/seat_maps/{id}")
+            .route(web::get().to(seat_maps::show))
+            .route(web::delete().to(seat_maps::destroy)),
+    )
     .service(web::resource("/send_download_link").route(web::post().to(send_download_link::create)))
     .service(web::resource("/send_download_link/resend").route(web::post().to(send_download_link::resend)))
     .service(web::resource("/slugs").route(web::get().to(slugs::index)))
@@ -336,6 +457,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(settlements::show))
             .route(web::delete().to(settlements::destroy)),
     )
+    .service(
+        web::resource("/tax_rates/{id}")
+            .route(web::put().to(tax_rates::update))
+            .route(web::delete().to(tax_rates::destroy)),
+    )
     .service(web::resource("/tickets/transfer").route(web::post().to(tickets::transfer_authorization)))
     .service(web::resource("/tickets/receive").route(web::post().to(tickets::receive_transfer)))
     .service(web::resource("/tickets/send").route(web::post().to(tickets::send_via_email_or_phone)))
@@ -373,11 +499,30 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/user_invites").route(web::post().to(user_invites::create)))
     .service(web::resource("/users/{id}/organizations").route(web::get().to(users::list_organizations)))
     .service(web::resource("/users/me/marketplace_account").route(web::post().to(users::create_marketplace_account)))
+    .service(
+        web::resource("/users/me/mfa")
+            .route(web::post().to(users::begin_mfa_enrollment))
+            .route(web::delete().to(users::disable_mfa)),
+    )
+    .service(web::resource("/users/me/mfa/activate").route(web::post().to(users::activate_mfa)))
+    .service(web::resource("/users/me/sessions").route(web::get().to(users::sessions)))
+    .service(web::resource("/users/me/sessions/{id}").route(web::delete().to(users::revoke_session)))
+    .service(web::resource("/users/{id}/sessions").route(web::delete().to(users::revoke_sessions_for_user)))
+    .service(
+        web::resource("/venues/{id}/access_zones")
+            .route(web::get().to(access_zones::venue_index))
+            .route(web::post().to(access_zones::create_for_venue)),
+    )
     .service(
         web::resource("/venues/{id}/organization_venues")
             .route(web::get().to(organization_venues::venues_index))
             .route(web::post().to(organization_venues::create)),
     )
+    .service(
+        web::resource("/venues/{id}/seat_maps")
+            .route(web::get().to(seat_maps::index))
+            .route(web::post().to(seat_maps::create)),
+    )
     .service(
         web::resource("/venues/{id}/stages")
             .route(web::post().to(stages::create))
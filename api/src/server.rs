@@ -13,6 +13,7 @@ use actix_files as fs;
 use actix_web::middleware::Logger;
 use actix_web::{dev::ServiceRequest, http, HttpRequest, HttpResponse};
 use actix_web::{web, web::Data, App, HttpServer};
+use db::models::Wallet;
 use db::utils::errors::DatabaseError;
 use log::Level::{Debug, Warn};
 use std::collections::HashMap;
@@ -9,7 +9,9 @@ pub mod gen_sitemap;
 pub mod google_recaptcha;
 pub mod logging;
 pub mod marketplace_api;
+pub mod oidc;
 pub mod redis;
+pub mod report_exports;
 pub mod sendgrid;
 pub mod serializers;
 mod service_locator;
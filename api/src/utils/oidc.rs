@@ -0,0 +1,128 @@
+use crate::auth::default_token_issuer::TokenVerificationKey;
+use crate::auth::jwks::JwkSet;
+use crate::config::OidcProviderConfig;
+use crate::errors::{ApiError, AuthError};
+use jwt::{decode, decode_header, Validation};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Providers' key sets are reused for this long before being downloaded again
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+/// Tokens with an unknown key id trigger a download at most this often, so they cannot be used to
+/// hammer the provider
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    static ref JWKS_CACHE: RwLock<HashMap<String, CachedJwkSet>> = RwLock::new(HashMap::new());
+}
+
+struct CachedJwkSet {
+    jwks: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Claims of an OpenID Connect ID token used to log a user in
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    /// Either a single client id or a list of them
+    pub aud: Value,
+    pub exp: u64,
+    pub email: Option<String>,
+    /// Apple sends this as the string "true" rather than a boolean
+    pub email_verified: Option<Value>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+impl IdTokenClaims {
+    pub fn audiences(&self) -> Vec<&str> {
+        match self.aud {
+            Value::String(ref aud) => vec![aud.as_str()],
+            Value::Array(ref aud) => aud.iter().filter_map(|a| a.as_str()).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The email address, if the provider has verified that it belongs to the user
+    pub fn verified_email(&self) -> Option<&str> {
+        let verified = match self.email_verified {
+            Some(Value::Bool(verified)) => verified,
+            Some(Value::String(ref verified)) => verified == "true",
+            _ => false,
+        };
+        if verified {
+            self.email.as_ref().map(|e| e.as_str())
+        } else {
+            None
+        }
+    }
+}
+
+/// Checks the ID token signature against the provider's published keys, then its issuer,
+/// audience and expiry
+pub async fn verify_id_token(provider: &OidcProviderConfig, id_token: &str) -> Result<IdTokenClaims, ApiError> {
+    let invalid_token = || AuthError::unauthorized("Invalid ID token");
+    let header = decode_header(id_token).map_err(|_| invalid_token())?;
+    let kid = header.kid.ok_or_else(invalid_token)?;
+
+    let key = verification_key(provider, &kid).await?.ok_or_else(invalid_token)?;
+    if header.alg != key.algorithm {
+        return Err(invalid_token().into());
+    }
+
+    let claims = decode::<IdTokenClaims>(id_token, &key.public_key, &Validation::new(key.algorithm))
+        .map_err(|_| invalid_token())?
+        .claims;
+    if !provider.issuers.contains(&claims.iss) {
+        return Err(AuthError::unauthorized("ID token was not issued by the provider").into());
+    }
+    if !claims
+        .audiences()
+        .iter()
+        .any(|aud| provider.client_ids.iter().any(|client_id| client_id == aud))
+    {
+        return Err(AuthError::unauthorized("ID token was not issued for this application").into());
+    }
+
+    Ok(claims)
+}
+
+/// Finds the provider's key with the given id. The provider's key set is cached per provider and
+/// downloaded again once it expires or when a token is signed with a key it does not contain, as
+/// happens after the provider rotates its keys.
+async fn verification_key(provider: &OidcProviderConfig, kid: &str) -> Result<Option<TokenVerificationKey>, ApiError> {
+    let refresh = match JWKS_CACHE.read().unwrap().get(&provider.jwks_uri) {
+        Some(cached) => {
+            let age = cached.fetched_at.elapsed();
+            match cached.jwks.keys.iter().find(|k| k.kid == kid) {
+                Some(key) if age < JWKS_CACHE_TTL => return Ok(Some(key.to_verification_key()?)),
+                Some(_) => true,
+                None => age >= JWKS_MIN_REFRESH_INTERVAL,
+            }
+        }
+        None => true,
+    };
+    if !refresh {
+        return Ok(None);
+    }
+
+    let client = reqwest::Client::new();
+    let jwks: JwkSet = client.get(&provider.jwks_uri).send().await?.json().await?;
+    let key = match jwks.keys.iter().find(|k| k.kid == kid) {
+        Some(key) => Some(key.to_verification_key()?),
+        None => None,
+    };
+    JWKS_CACHE.write().unwrap().insert(
+        provider.jwks_uri.clone(),
+        CachedJwkSet {
+            jwks,
+            fetched_at: Instant::now(),
+        },
+    );
+
+    Ok(key)
+}
@@ -0,0 +1,760 @@
+use crate::database::Connection;
+use crate::errors::*;
+use bytes::Bytes;
+use chrono::prelude::*;
+use db::prelude::*;
+use diesel::PgConnection;
+use futures::stream::Stream;
+use futures::task::{Context, Poll};
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::Serialize;
+use serde_json::Value;
+use simple_excel_writer::{Row, Workbook};
+use std::collections::VecDeque;
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::pin::Pin;
+use uuid::Uuid;
+
+/// Rows of the paged reports loaded at a time while writing an export
+const EXPORT_PAGE_SIZE: u32 = 1000;
+/// Size of the chunks a built XLSX file is streamed in
+const XLSX_CHUNK_SIZE: usize = 64 * 1024;
+/// Size of the chunks a completed export is read from the database in while it is downloaded
+const DOWNLOAD_CHUNK_SIZE: i64 = 64 * 1024;
+/// Columns holding amounts in cents whose names do not contain `_in_cents`
+const MONEY_COLUMNS: &[&str] = &[
+    "gross",
+    "per_order_client_online_fees",
+    "per_order_company_online_fees",
+    "promo_code_discounted_ticket_price",
+    "refund_total",
+    "sales_total",
+    "total",
+];
+
+/// The report parameters an export is built from, stored with background exports
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ReportExportParameters {
+    pub report: String,
+    pub event_id: Option<Uuid>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+    pub query: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReportCell {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    /// Amount in cents, written as a decimal amount in the currency
+    Money(i64),
+}
+
+impl ReportCell {
+    fn from_value(field: &str, value: Value) -> ReportCell {
+        match value {
+            Value::Null => ReportCell::Empty,
+            Value::Number(number) => match number.as_i64() {
+                Some(cents) if is_money_column(field) => ReportCell::Money(cents),
+                Some(integer) => ReportCell::Integer(integer),
+                None => ReportCell::Decimal(number.as_f64().unwrap_or(0.0)),
+            },
+            Value::String(text) => match text.parse::<NaiveDateTime>() {
+                Ok(date) => ReportCell::Text(date.format("%Y-%m-%d %H:%M:%S").to_string()),
+                Err(_) => ReportCell::Text(text),
+            },
+            value => ReportCell::Text(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ReportCell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportCell::Empty => Ok(()),
+            ReportCell::Text(text) => f.write_str(text),
+            ReportCell::Integer(integer) => write!(f, "{}", integer),
+            ReportCell::Decimal(decimal) => write!(f, "{}", decimal),
+            ReportCell::Money(cents) => f.write_str(&format_money(*cents)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportSheet {
+    pub name: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<ReportCell>>,
+}
+
+impl ReportSheet {
+    /// Builds a sheet with a column for each serialized field of the rows, in declaration order
+    pub fn from_rows<T: Serialize>(name: &str, rows: &[T]) -> Result<ReportSheet, ApiError> {
+        let mut headers = Vec::new();
+        let mut cells = Vec::new();
+        for row in rows {
+            let fields: OrderedFields = serde_json::from_slice(&serde_json::to_vec(row)?)?;
+            if headers.is_empty() {
+                headers = fields.0.iter().map(|(field, _)| column_header(field)).collect();
+            }
+            cells.push(
+                fields
+                    .0
+                    .into_iter()
+                    .map(|(field, value)| ReportCell::from_value(&field, value))
+                    .collect(),
+            );
+        }
+
+        Ok(ReportSheet {
+            name: name.to_string(),
+            headers,
+            rows: cells,
+        })
+    }
+}
+
+/// The fields of a serialized row in the order they were written
+struct OrderedFields(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedFieldsVisitor;
+
+        impl<'de> Visitor<'de> for OrderedFieldsVisitor {
+            type Value = OrderedFields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a report row")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields = Vec::new();
+                while let Some(field) = map.next_entry::<String, Value>()? {
+                    fields.push(field);
+                }
+                Ok(OrderedFields(fields))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedFieldsVisitor)
+    }
+}
+
+/// `unit_price_in_cents` becomes `Unit Price`, the cells of money columns hold the amount rather than cents
+pub fn column_header(field: &str) -> String {
+    field
+        .replace("_in_cents", "")
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub fn is_money_column(field: &str) -> bool {
+    field.contains("_in_cents") || MONEY_COLUMNS.contains(&field)
+}
+
+pub fn format_money(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, (cents / 100).abs(), (cents % 100).abs())
+}
+
+/// Rows of a sheet loaded together, the name and headers of a sheet are written with its first page
+pub struct ReportPage {
+    pub starts_sheet: bool,
+    pub sheet: ReportSheet,
+}
+
+/// Loads the rows of an export a page at a time so the report is never held in memory as a whole.
+/// Only transaction details and scan counts grow with sales, the sheets of the other reports are
+/// totals and are loaded together.
+pub struct ReportPages {
+    organization_id: Uuid,
+    parameters: ReportExportParameters,
+    totals: Option<VecDeque<ReportSheet>>,
+    page: u32,
+    finished: bool,
+    sheet_count: usize,
+    row_count: i64,
+}
+
+impl ReportPages {
+    pub fn new(organization_id: Uuid, parameters: ReportExportParameters) -> ReportPages {
+        ReportPages {
+            organization_id,
+            parameters,
+            totals: None,
+            page: 0,
+            finished: false,
+            sheet_count: 0,
+            row_count: 0,
+        }
+    }
+
+    /// Sheets in the export, known once the first page has been loaded
+    pub fn sheet_count(&self) -> usize {
+        self.sheet_count
+    }
+
+    /// Rows loaded so far
+    pub fn row_count(&self) -> i64 {
+        self.row_count
+    }
+
+    pub fn next_page(&mut self, conn: &PgConnection) -> Result<Option<ReportPage>, ApiError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let page = match paged_sheet(self.organization_id, &self.parameters, self.page, conn)? {
+            Some(sheet) => {
+                self.page += 1;
+                self.sheet_count = 1;
+                self.finished = sheet.rows.len() < EXPORT_PAGE_SIZE as usize;
+                // The previous page held the last rows
+                if sheet.rows.is_empty() && self.page > 1 {
+                    return Ok(None);
+                }
+                ReportPage {
+                    starts_sheet: self.page == 1,
+                    sheet,
+                }
+            }
+            None => {
+                if self.totals.is_none() {
+                    let sheets = totals_sheets(self.organization_id, &self.parameters, conn)?;
+                    self.sheet_count = sheets.len();
+                    self.totals = Some(sheets.into());
+                }
+                match self.totals.as_mut().and_then(|totals| totals.pop_front()) {
+                    Some(sheet) => ReportPage {
+                        starts_sheet: true,
+                        sheet,
+                    },
+                    None => {
+                        self.finished = true;
+                        return Ok(None);
+                    }
+                }
+            }
+        };
+
+        self.row_count += page.sheet.rows.len() as i64;
+        Ok(Some(page))
+    }
+}
+
+/// Writes the whole export to `writer`, CSV rows are written as their page is loaded
+pub fn write_export<W: Write>(
+    format: ReportExportFormat,
+    pages: &mut ReportPages,
+    conn: &PgConnection,
+    writer: &mut W,
+) -> Result<(), ApiError> {
+    match format {
+        ReportExportFormat::Csv => {
+            while let Some(page) = pages.next_page(conn)? {
+                writer.write_all(&csv_page(&page, pages.sheet_count())?)?;
+            }
+        }
+        ReportExportFormat::Xlsx => {
+            io::copy(&mut XlsxFile::build(pages, conn)?, writer)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Streams an export to the response as its rows are loaded
+pub struct ReportExportStream {
+    format: ReportExportFormat,
+    pages: ReportPages,
+    connection: Connection,
+    xlsx_file: Option<XlsxFile>,
+    finished: bool,
+}
+
+impl ReportExportStream {
+    pub fn new(format: ReportExportFormat, pages: ReportPages, connection: Connection) -> ReportExportStream {
+        ReportExportStream {
+            format,
+            pages,
+            connection,
+            xlsx_file: None,
+            finished: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<Bytes>, ApiError> {
+        let conn = self.connection.get();
+        match self.format {
+            ReportExportFormat::Csv => match self.pages.next_page(conn)? {
+                Some(page) => Ok(Some(Bytes::from(csv_page(&page, self.pages.sheet_count())?))),
+                None => Ok(None),
+            },
+            ReportExportFormat::Xlsx => {
+                if self.xlsx_file.is_none() {
+                    self.xlsx_file = Some(XlsxFile::build(&mut self.pages, conn)?);
+                }
+                let mut chunk = vec![0; XLSX_CHUNK_SIZE];
+                let read = match self.xlsx_file.as_mut() {
+                    Some(xlsx_file) => xlsx_file.read(&mut chunk)?,
+                    None => 0,
+                };
+                if read == 0 {
+                    return Ok(None);
+                }
+                chunk.truncate(read);
+                Ok(Some(Bytes::from(chunk)))
+            }
+        }
+    }
+}
+
+impl Stream for ReportExportStream {
+    type Item = Result<Bytes, ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        let chunk = self.next_chunk();
+        match chunk {
+            Ok(Some(_)) => (),
+            _ => self.finished = true,
+        }
+        Poll::Ready(chunk.transpose())
+    }
+}
+
+/// Streams the file of a completed export from the database a chunk at a time
+pub struct ReportExportDownloadStream {
+    report_export: ReportExport,
+    offset: i64,
+    connection: Connection,
+    finished: bool,
+}
+
+impl ReportExportDownloadStream {
+    pub fn new(report_export: ReportExport, connection: Connection) -> ReportExportDownloadStream {
+        ReportExportDownloadStream {
+            report_export,
+            offset: 0,
+            connection,
+            finished: false,
+        }
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<Bytes>, ApiError> {
+        let chunk = self
+            .report_export
+            .content_chunk(self.offset, DOWNLOAD_CHUNK_SIZE, self.connection.get())?;
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+        self.offset += chunk.len() as i64;
+        Ok(Some(Bytes::from(chunk)))
+    }
+}
+
+impl Stream for ReportExportDownloadStream {
+    type Item = Result<Bytes, ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        let chunk = self.next_chunk();
+        match chunk {
+            Ok(Some(_)) => (),
+            _ => self.finished = true,
+        }
+        Poll::Ready(chunk.transpose())
+    }
+}
+
+/// Writes a page as CSV. Sheets are written one after another, each preceded by its name when there
+/// is more than one.
+pub fn csv_page(page: &ReportPage, sheet_count: usize) -> Result<Vec<u8>, ApiError> {
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(vec![]);
+    if page.starts_sheet {
+        if sheet_count > 1 {
+            writer.write_record(&[&page.sheet.name]).map_err(export_error)?;
+        }
+        if !page.sheet.headers.is_empty() {
+            writer.write_record(&page.sheet.headers).map_err(export_error)?;
+        }
+    }
+    for row in &page.sheet.rows {
+        writer
+            .write_record(row.iter().map(|cell| cell.to_string()))
+            .map_err(export_error)?;
+    }
+
+    writer.into_inner().map_err(export_error)
+}
+
+/// XLSX files are zip archives, the workbook is built in a temporary file that is removed once
+/// it has been read
+struct XlsxFile {
+    path: PathBuf,
+    file: File,
+}
+
+impl XlsxFile {
+    fn build(pages: &mut ReportPages, conn: &PgConnection) -> Result<XlsxFile, ApiError> {
+        let path = env::temp_dir().join(format!("report_export_{}.xlsx", Uuid::new_v4()));
+        match write_xlsx(pages, &path.to_string_lossy(), conn).and_then(|_| Ok(File::open(&path)?)) {
+            Ok(file) => Ok(XlsxFile { path, file }),
+            Err(e) => {
+                let _ = fs::remove_file(&path);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Read for XlsxFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl Drop for XlsxFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn write_xlsx(pages: &mut ReportPages, path: &str, conn: &PgConnection) -> Result<(), ApiError> {
+    let mut workbook = Workbook::create(path);
+    let mut next_page = pages.next_page(conn)?;
+    while let Some(page) = next_page.take() {
+        let mut worksheet = workbook.create_sheet(&page.sheet.name);
+        let mut sheet = page.sheet;
+        let mut error = None;
+        workbook.write_sheet(&mut worksheet, |sheet_writer| {
+            let mut header_row = Row::new();
+            for header in &sheet.headers {
+                header_row.add_cell(header.as_str());
+            }
+            sheet_writer.append_row(header_row)?;
+
+            loop {
+                for row in &sheet.rows {
+                    sheet_writer.append_row(xlsx_row(row))?;
+                }
+                match pages.next_page(conn) {
+                    Ok(Some(page)) if !page.starts_sheet => sheet = page.sheet,
+                    Ok(page) => {
+                        next_page = page;
+                        break;
+                    }
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        if let Some(e) = error {
+            return Err(e);
+        }
+    }
+
+    workbook.close()?;
+    Ok(())
+}
+
+fn xlsx_row(row: &[ReportCell]) -> Row {
+    let mut xlsx_row = Row::new();
+    for cell in row {
+        match cell {
+            ReportCell::Empty => xlsx_row.add_cell(""),
+            ReportCell::Text(text) => xlsx_row.add_cell(text.as_str()),
+            ReportCell::Integer(integer) => xlsx_row.add_cell(*integer as f64),
+            ReportCell::Decimal(decimal) => xlsx_row.add_cell(*decimal),
+            ReportCell::Money(cents) => xlsx_row.add_cell(*cents as f64 / 100.0),
+        }
+    }
+    xlsx_row
+}
+
+fn export_error<E: ToString>(error: E) -> ApiError {
+    ApplicationError::new(format!("Could not build report export: {}", error.to_string())).into()
+}
+
+fn required_event_id(parameters: &ReportExportParameters) -> Result<Uuid, ApiError> {
+    parameters.event_id.ok_or_else(|| {
+        ApplicationError::new_with_type(
+            ApplicationErrorType::BadRequest,
+            "event_id parameter is required".to_string(),
+        )
+        .into()
+    })
+}
+
+/// Rows an export will contain. Only transaction details grow with sales, the other reports
+/// are totals per event, ticket type or payment method.
+pub fn estimated_row_count(
+    organization_id: Uuid,
+    parameters: &ReportExportParameters,
+    conn: &PgConnection,
+) -> Result<i64, ApiError> {
+    match parameters.report.as_str() {
+        "transaction_details" => Ok(Report::transaction_detail_report(
+            parameters.query.clone(),
+            parameters.event_id,
+            Some(organization_id),
+            parameters.start_utc,
+            parameters.end_utc,
+            0,
+            1,
+            conn,
+        )?
+        .paging
+        .total as i64),
+        _ => Ok(0),
+    }
+}
+
+/// A page of the reports whose rows grow with sales, `None` for the other reports
+fn paged_sheet(
+    organization_id: Uuid,
+    parameters: &ReportExportParameters,
+    page: u32,
+    conn: &PgConnection,
+) -> Result<Option<ReportSheet>, ApiError> {
+    let sheet = match parameters.report.as_str() {
+        "transaction_details" => {
+            let transactions = Report::transaction_detail_report(
+                parameters.query.clone(),
+                parameters.event_id,
+                Some(organization_id),
+                parameters.start_utc,
+                parameters.end_utc,
+                page,
+                EXPORT_PAGE_SIZE,
+                conn,
+            )?;
+            ReportSheet::from_rows("Transactions", &transactions.data)?
+        }
+        "scan_count" => {
+            let scan_counts = Report::scan_count_report(required_event_id(parameters)?, page, EXPORT_PAGE_SIZE, conn)?;
+            ReportSheet::from_rows("Scan Counts", &scan_counts.data)?
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(sheet))
+}
+
+/// Loads the reports made of totals, the sheets match the sections of their JSON response
+fn totals_sheets(
+    organization_id: Uuid,
+    parameters: &ReportExportParameters,
+    conn: &PgConnection,
+) -> Result<Vec<ReportSheet>, ApiError> {
+    let sheets = match parameters.report.as_str() {
+        "box_office_sales_summary" => box_office_sales_summary_sheets(&Report::box_office_sales_summary_report(
+            organization_id,
+            parameters.start_utc,
+            parameters.end_utc,
+            conn,
+        )?)?,
+        "event_summary" => event_summary_sheets(&[Report::summary_event_report(
+            required_event_id(parameters)?,
+            parameters.start_utc,
+            parameters.end_utc,
+            conn,
+        )?])?,
+        "audit_report" => {
+            let event_id = required_event_id(parameters)?;
+            let all_sales = Report::summary_event_report(event_id, parameters.start_utc, parameters.end_utc, conn)?;
+            let end_date = parameters.end_utc.unwrap_or(Utc::now().naive_utc());
+            let end_date_sales =
+                Report::summary_event_report(event_id, Some(end_date.date().and_hms(0, 0, 0)), Some(end_date), conn)?;
+            let inventory = Report::ticket_count_report(Some(event_id), Some(organization_id), conn)?;
+            vec![
+                ReportSheet::from_rows("End Date Sales", &end_date_sales.sales)?,
+                ReportSheet::from_rows("All Sales", &all_sales.sales)?,
+                ReportSheet::from_rows("Inventory", &inventory.counts)?,
+                ReportSheet::from_rows("Inventory Sales", &inventory.sales)?,
+            ]
+        }
+        "weekly_settlement" => event_summary_sheets(&Report::organization_summary_report(
+            organization_id,
+            parameters.start_utc,
+            parameters.end_utc,
+            conn,
+        )?)?,
+        "ticket_count" => {
+            let ticket_counts = Report::ticket_count_report(parameters.event_id, Some(organization_id), conn)?;
+            vec![
+                ReportSheet::from_rows("Counts", &ticket_counts.counts)?,
+                ReportSheet::from_rows("Sales", &ticket_counts.sales)?,
+            ]
+        }
+        "reconciliation_summary" => vec![ReportSheet::from_rows(
+            "Reconciliation",
+            &Report::reconciliation_summary_report(organization_id, parameters.start_utc, parameters.end_utc, conn)?,
+        )?],
+        "reconciliation_details" => reconciliation_detail_sheets(&Report::reconciliation_detail_report(
+            organization_id,
+            parameters.start_utc,
+            parameters.end_utc,
+            conn,
+        )?)?,
+        "promo_code" => vec![ReportSheet::from_rows(
+            "Promo Codes",
+            &Report::promo_code_report(parameters.event_id, Some(organization_id), conn)?,
+        )?],
+        "event_series" => vec![ReportSheet::from_rows(
+            "Event Series",
+            &Report::event_series_report(parameters.event_id, Some(organization_id), conn)?,
+        )?],
+        "waitlist" => vec![ReportSheet::from_rows(
+            "Waitlist",
+            &Report::waitlist_report(parameters.event_id, Some(organization_id), conn)?,
+        )?],
+        "product_sales" => vec![ReportSheet::from_rows(
+            "Product Sales",
+            &Report::product_sales_report(parameters.event_id, Some(organization_id), conn)?,
+        )?],
+        _ => return Err(NotFoundError {}.into()),
+    };
+
+    Ok(sheets)
+}
+
+fn event_summary_sheets(results: &[EventSummarySalesResult]) -> Result<Vec<ReportSheet>, ApiError> {
+    let sales: Vec<&EventSummarySalesRow> = results.iter().flat_map(|r| r.sales.iter()).collect();
+    let ticket_fees: Vec<&EventSummaryFeesRow> = results.iter().flat_map(|r| r.ticket_fees.iter()).collect();
+    let other_fees: Vec<&EventSummaryOtherFees> = results.iter().flat_map(|r| r.other_fees.iter()).collect();
+
+    Ok(vec![
+        ReportSheet::from_rows("Sales", &sales)?,
+        ReportSheet::from_rows("Ticket Fees", &ticket_fees)?,
+        ReportSheet::from_rows("Other Fees", &other_fees)?,
+    ])
+}
+
+#[derive(Serialize)]
+struct BoxOfficeOperatorEventRow<'a> {
+    operator_name: &'a str,
+    event_name: &'a Option<String>,
+    event_date: Option<NaiveDateTime>,
+    number_of_tickets: u32,
+    face_value_in_cents: u32,
+    revenue_share_value_in_cents: u32,
+    total_sales_in_cents: u32,
+}
+
+#[derive(Serialize)]
+struct BoxOfficeOperatorPaymentRow<'a> {
+    operator_name: &'a str,
+    payment_type: ExternalPaymentType,
+    quantity: u32,
+    total_sales_in_cents: u32,
+}
+
+fn box_office_sales_summary_sheets(report: &BoxOfficeSalesSummaryReport) -> Result<Vec<ReportSheet>, ApiError> {
+    let mut operator_events = Vec::new();
+    let mut operator_payments = Vec::new();
+    for operator in &report.operators {
+        for event in &operator.events {
+            operator_events.push(BoxOfficeOperatorEventRow {
+                operator_name: &operator.operator_name,
+                event_name: &event.event_name,
+                event_date: event.event_date,
+                number_of_tickets: event.number_of_tickets,
+                face_value_in_cents: event.face_value_in_cents,
+                revenue_share_value_in_cents: event.revenue_share_value_in_cents,
+                total_sales_in_cents: event.total_sales_in_cents,
+            });
+        }
+        for payment in &operator.payments {
+            operator_payments.push(BoxOfficeOperatorPaymentRow {
+                operator_name: &operator.operator_name,
+                payment_type: payment.payment_type,
+                quantity: payment.quantity,
+                total_sales_in_cents: payment.total_sales_in_cents,
+            });
+        }
+    }
+
+    Ok(vec![
+        ReportSheet::from_rows("Operator Sales", &operator_events)?,
+        ReportSheet::from_rows("Operator Payments", &operator_payments)?,
+        ReportSheet::from_rows("Payments", &report.payments)?,
+    ])
+}
+
+/// Client fees are broken down per fee range in the JSON report, the export shows their total
+#[derive(Serialize)]
+struct ReconciliationDetailRow<'a> {
+    event_name: &'a str,
+    event_start: Option<NaiveDateTime>,
+    payment_method: &'a str,
+    payment_provider: &'a str,
+    quantity: i64,
+    unit_price_in_cents: i64,
+    client_fee_in_cents: i64,
+    event_fee_in_cents: i64,
+    sales_total: i64,
+    refund_quantity: i64,
+    refund_unit_price_in_cents: i64,
+    refund_client_fee_in_cents: i64,
+    refund_event_fee_in_cents: i64,
+    refund_total: i64,
+    total: i64,
+}
+
+fn reconciliation_detail_sheets(results: &[ReconciliationDetailEventResult]) -> Result<Vec<ReportSheet>, ApiError> {
+    let mut rows = Vec::new();
+    for event in results {
+        for entry in &event.entries {
+            rows.push(ReconciliationDetailRow {
+                event_name: &event.event_name,
+                event_start: event.event_start,
+                payment_method: &entry.payment_method,
+                payment_provider: &entry.payment_provider,
+                quantity: entry.quantity,
+                unit_price_in_cents: entry.unit_price_in_cents,
+                client_fee_in_cents: entry.client_fee_in_cents.iter().map(|f| f.client_fee_in_cents).sum(),
+                event_fee_in_cents: entry.event_fee_in_cents,
+                sales_total: entry.sales_total,
+                refund_quantity: entry.refund_quantity,
+                refund_unit_price_in_cents: entry.refund_unit_price_in_cents,
+                refund_client_fee_in_cents: entry
+                    .refund_client_fee_in_cents
+                    .iter()
+                    .map(|f| f.client_fee_in_cents)
+                    .sum(),
+                refund_event_fee_in_cents: entry.refund_event_fee_in_cents,
+                refund_total: entry.refund_total,
+                total: entry.total,
+            });
+        }
+    }
+
+    Ok(vec![ReportSheet::from_rows("Reconciliation", &rows)?])
+}
@@ -1,5 +1,6 @@
 use crate::config::Config;
 use crate::errors::*;
+use crate::payments::braintree::BraintreePaymentProcessor;
 use crate::payments::globee::GlobeePaymentProcessor;
 use crate::payments::stripe::StripePaymentProcessor;
 use crate::payments::PaymentProcessor;
@@ -15,6 +16,7 @@ pub struct ServiceLocator {
     stripe_secret_key: String,
     globee_api_key: String,
     globee_base_url: String,
+    braintree_base_url: String,
     branch_io_base_url: String,
     branch_io_branch_key: String,
     branch_io_timeout: u64,
@@ -38,6 +40,7 @@ impl ServiceLocator {
             stripe_secret_key: config.stripe_secret_key.clone(),
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
+            braintree_base_url: config.braintree_base_url.clone(),
             branch_io_base_url: config.branch_io_base_url.clone(),
             branch_io_branch_key: config.branch_io_branch_key.clone(),
             branch_io_timeout: config.branch_io_timeout,
@@ -63,7 +66,10 @@ impl ServiceLocator {
         organization: &Organization,
     ) -> Result<Box<dyn PaymentProcessor>, ApiError> {
         match provider {
-            PaymentProviders::Stripe => Ok(Box::new(StripePaymentProcessor::new(self.stripe_secret_key.clone()))),
+            PaymentProviders::Stripe => Ok(Box::new(StripePaymentProcessor::new(
+                self.stripe_secret_key.clone(),
+                organization.stripe_connect_account_id.clone(),
+            ))),
             PaymentProviders::Globee => {
                 let mut org = organization.clone();
                 org.decrypt(&self.api_keys_encryption_key)?;
@@ -74,6 +80,31 @@ impl ServiceLocator {
                     self.globee_base_url.clone(),
                 )))
             }
+            PaymentProviders::Braintree => {
+                let mut org = organization.clone();
+                org.decrypt(&self.api_keys_encryption_key)?;
+
+                match (
+                    org.braintree_public_key,
+                    org.braintree_private_key,
+                    org.braintree_merchant_account_id,
+                ) {
+                    (Some(public_key), Some(private_key), Some(merchant_account_id)) => {
+                        Ok(Box::new(BraintreePaymentProcessor::new(
+                            public_key,
+                            private_key,
+                            merchant_account_id,
+                            org.currency,
+                            self.braintree_base_url.clone(),
+                        )))
+                    }
+                    _ => Err(ApplicationError::new_with_type(
+                        ApplicationErrorType::Unprocessable,
+                        "Braintree has not been configured for this organization".to_string(),
+                    )
+                    .into()),
+                }
+            }
             // External is not valid for service locator
             PaymentProviders::Free | PaymentProviders::External => {
                 return Err(ApplicationError::new("Unknown payment provider".into()).into());
@@ -99,6 +130,7 @@ impl ServiceLocator {
     pub fn is_refund_supported(provider: String) -> bool {
         match provider.to_lowercase().as_str() {
             "stripe" => true,
+            "braintree" => true,
             "globee" => false,
             "external" => false,
             _ => false,
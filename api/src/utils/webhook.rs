@@ -1,12 +1,109 @@
 use crate::config::Config;
 use crate::errors::*;
 use crate::utils::webhook_adapters::{CustomerIoWebhookAdapter, NullAdapter, WebhookAdapter};
+use chrono::prelude::*;
 use db::prelude::*;
+use db::utils::hash::hmac_sha256;
 use diesel::PgConnection;
 use serde_json;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+pub const WEBHOOK_EVENT_ID_HEADER: &str = "X-BigNeon-Event-Id";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-BigNeon-Timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-BigNeon-Signature";
+
+pub struct WebhookResponse {
+    pub status: u16,
+    pub body: Option<String>,
+}
+
+impl WebhookResponse {
+    pub fn is_success(&self) -> bool {
+        self.status >= 200 && self.status < 300
+    }
+}
+
+/// Signature sent in the `X-BigNeon-Signature` header. Receivers recompute the HMAC-SHA256
+/// of `<timestamp>.<body>` with the publisher's webhook secret and reject stale timestamps
+/// to protect against replayed requests.
+pub fn webhook_signature(webhook_secret: &str, timestamp: i64, body: &str) -> String {
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hmac_sha256::sign(webhook_secret, &format!("{}.{}", timestamp, body))
+    )
+}
+
+/// Identifies and signs a logged webhook delivery, see `webhook_signature`
+pub struct WebhookSigning {
+    pub event_id: Uuid,
+    pub webhook_secret: String,
+}
+
+impl WebhookSigning {
+    /// Headers to send with `body`, which must be the exact request body received by the receiver
+    pub fn headers(&self, body: &str) -> Vec<(&'static str, String)> {
+        let timestamp = Utc::now().timestamp();
+        vec![
+            (WEBHOOK_EVENT_ID_HEADER, self.event_id.to_string()),
+            (WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string()),
+            (
+                WEBHOOK_SIGNATURE_HEADER,
+                webhook_signature(&self.webhook_secret, timestamp, body),
+            ),
+        ]
+    }
+}
+
+/// Makes a single attempt at delivering a logged webhook. The delivery id is sent as the event id
+/// so the receiver can discard duplicates from retries and replays. Publishers with an adapter sign
+/// the requests made by the adapter and record the response to its last request.
+pub fn deliver_webhook(
+    webhook_delivery: &WebhookDelivery,
+    conn: &PgConnection,
+    config: &Config,
+) -> Result<WebhookResponse, ApiError> {
+    let domain_event_publisher = webhook_delivery.domain_event_publisher(conn)?;
+    let mut payload = webhook_delivery.payload.clone();
+    payload["event_id"] = json!(webhook_delivery.id);
+    let body = payload.to_string();
+    let signing = WebhookSigning {
+        event_id: webhook_delivery.id,
+        webhook_secret: domain_event_publisher.webhook_secret.clone(),
+    };
+
+    if domain_event_publisher.adapter.is_some() {
+        return send_with_adapter(
+            &[domain_event_publisher.webhook_url.clone()],
+            &body,
+            Some(domain_event_publisher.id),
+            Some(&signing),
+            conn,
+            config,
+        )?
+        .ok_or_else(|| ApplicationError::new("Webhook adapter did not make a request".to_string()).into());
+    }
+
+    let client = reqwest::blocking::Client::new();
+    let mut request = client
+        .post(&domain_event_publisher.webhook_url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    for (name, value) in signing.headers(&body) {
+        request = request.header(name, value);
+    }
+    let response = request
+        .body(body)
+        .send()
+        .map_err(|err| ApplicationError::new(format!("Error making webhook request: {}", err)))?;
+
+    let status = response.status().as_u16();
+    Ok(WebhookResponse {
+        status,
+        body: response.text().ok(),
+    })
+}
+
 // TODO: it uses sync client under the hood, so will block executor
 pub async fn send_webhook_async(
     tokens: &[String],
@@ -15,16 +112,34 @@ pub async fn send_webhook_async(
     conn: &PgConnection,
     config: &Config,
 ) -> Result<(), ApiError> {
-    send_webhook(tokens, body, domain_event_publisher_id, conn, config)
+    send_webhook(tokens, body, domain_event_publisher_id, None, conn, config)
 }
 
 pub fn send_webhook(
     webhook_urls: &[String],
     body: &str,
     domain_event_publisher_id: Option<Uuid>,
+    signing: Option<&WebhookSigning>,
     conn: &PgConnection,
     config: &Config,
 ) -> Result<(), ApiError> {
+    match send_with_adapter(webhook_urls, body, domain_event_publisher_id, signing, conn, config)? {
+        Some(ref response) if !response.is_success() => {
+            Err(ApplicationError::new(format!("Webhook request failed with status {}", response.status)).into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Sends the webhook through the publisher's adapter, returning the receiver's response
+fn send_with_adapter(
+    webhook_urls: &[String],
+    body: &str,
+    domain_event_publisher_id: Option<Uuid>,
+    signing: Option<&WebhookSigning>,
+    conn: &PgConnection,
+    config: &Config,
+) -> Result<Option<WebhookResponse>, ApiError> {
     let adapter = match domain_event_publisher_id {
         None => Box::new(NullAdapter::new()) as Box<dyn WebhookAdapter>,
         Some(id) => {
@@ -48,5 +163,5 @@ pub fn send_webhook(
 
     let payload: HashMap<String, serde_json::Value> = serde_json::from_str(body)?;
 
-    adapter.send(webhook_urls, payload)
+    adapter.send(webhook_urls, payload, signing)
 }
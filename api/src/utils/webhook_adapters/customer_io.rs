@@ -1,6 +1,7 @@
 use crate::config::Config;
 use crate::errors::{ApiError, ApplicationError};
-use crate::utils::webhook_adapters::WebhookAdapter;
+use crate::utils::webhook::{WebhookResponse, WebhookSigning};
+use crate::utils::webhook_adapters::{json_request, WebhookAdapter};
 use db::models::*;
 use log::Level::Debug;
 use serde_json::Value;
@@ -30,7 +31,12 @@ impl WebhookAdapter for CustomerIoWebhookAdapter {
         self.api_key = config["api_key"].as_str().unwrap().to_string();
     }
 
-    fn send(&self, _webhook_urls: &[String], payload: HashMap<String, Value, RandomState>) -> Result<(), ApiError> {
+    fn send(
+        &self,
+        _webhook_urls: &[String],
+        payload: HashMap<String, Value, RandomState>,
+        signing: Option<&WebhookSigning>,
+    ) -> Result<Option<WebhookResponse>, ApiError> {
         let client = reqwest::blocking::Client::new();
         let mut payload = payload;
         payload.insert("environment".to_string(), json!(self.environment));
@@ -41,27 +47,34 @@ impl WebhookAdapter for CustomerIoWebhookAdapter {
                     // For user created messages, send a pre event to create the user in customer.io
 
                     if webhook_event_type == "temporary_user_created" || webhook_event_type == "user_created" {
-                        self.send_user_created_message(&payload, &user_id)?;
+                        let response = self.send_user_created_message(&payload, &user_id, signing)?;
+                        if !response.is_success() {
+                            return Ok(Some(response));
+                        }
                     };
 
-                    client
-                        .post(&format!(
+                    json_request(
+                        client.post(&format!(
                             "https://track.customer.io/api/v1/customers/{}/events",
                             user_id
-                        ))
-                        .json(&json!({"name": webhook_event_type, "data": payload}))
+                        )),
+                        &json!({"name": webhook_event_type, "data": payload}),
+                        signing,
+                    )?
                 } else {
                     return Err(
                         ApplicationError::new("Cannot determine event to send to Customer.io".to_string()).into(),
                     );
                 }
             }
-            None => client.post("https://track.customer.io/api/v1/events").json(&payload),
+            None => json_request(
+                client.post("https://track.customer.io/api/v1/events"),
+                &payload,
+                signing,
+            )?,
         };
 
-        self.send_request(client, &payload)?;
-
-        Ok(())
+        Ok(Some(self.send_request(client, &payload)?))
     }
 }
 
@@ -70,7 +83,7 @@ impl CustomerIoWebhookAdapter {
         &self,
         client: reqwest::blocking::RequestBuilder,
         payload: &HashMap<String, Value, RandomState>,
-    ) -> Result<(), ApiError> {
+    ) -> Result<WebhookResponse, ApiError> {
         jlog!(
             Debug,
             "bigneon::domain_actions",
@@ -82,24 +95,28 @@ impl CustomerIoWebhookAdapter {
             .send()
             .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
         let status = resp.status();
-        let error_for_status = resp.error_for_status_ref().map(|_| ());
         let text = resp
             .text()
             .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
         jlog!(Debug, "bigneon::domain_actions", "Response from customer.io", {"text": text, "status": status.to_string()});
-        error_for_status?;
-        Ok(())
+        Ok(WebhookResponse {
+            status: status.as_u16(),
+            body: Some(text),
+        })
     }
 
     fn send_user_created_message(
         &self,
         payload: &HashMap<String, Value, RandomState>,
         user_id: &str,
-    ) -> Result<(), ApiError> {
+        signing: Option<&WebhookSigning>,
+    ) -> Result<WebhookResponse, ApiError> {
         let client = reqwest::blocking::Client::new();
-        let client = client
-            .put(&format!("https://track.customer.io/api/v1/customers/{}", user_id))
-            .json(&payload);
+        let client = json_request(
+            client.put(&format!("https://track.customer.io/api/v1/customers/{}", user_id)),
+            payload,
+            signing,
+        )?;
         self.send_request(client, payload)
     }
 }
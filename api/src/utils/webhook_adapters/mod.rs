@@ -2,6 +2,9 @@ mod customer_io;
 mod null_adapter;
 
 use crate::errors::ApiError;
+use crate::utils::webhook::{WebhookResponse, WebhookSigning};
+use reqwest::blocking::RequestBuilder;
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
@@ -11,5 +14,28 @@ pub use self::null_adapter::*;
 
 pub trait WebhookAdapter {
     fn initialize(&mut self, config: Value);
-    fn send(&self, webhook_urls: &[String], payload: HashMap<String, Value, RandomState>) -> Result<(), ApiError>;
+    /// Returns the response to the last request made, or to the first request that was not
+    /// successful. `None` when no request was made.
+    fn send(
+        &self,
+        webhook_urls: &[String],
+        payload: HashMap<String, Value, RandomState>,
+        signing: Option<&WebhookSigning>,
+    ) -> Result<Option<WebhookResponse>, ApiError>;
+}
+
+/// Sets the JSON body of an adapter request, signing it when sent for a logged webhook delivery
+pub fn json_request<T: Serialize>(
+    request: RequestBuilder,
+    body: &T,
+    signing: Option<&WebhookSigning>,
+) -> Result<RequestBuilder, ApiError> {
+    let body = serde_json::to_string(body)?;
+    let mut request = request.header(reqwest::header::CONTENT_TYPE, "application/json");
+    if let Some(signing) = signing {
+        for (name, value) in signing.headers(&body) {
+            request = request.header(name, value);
+        }
+    }
+    Ok(request.body(body))
 }
@@ -1,5 +1,6 @@
 use crate::errors::{ApiError, ApplicationError};
-use crate::utils::webhook_adapters::WebhookAdapter;
+use crate::utils::webhook::{WebhookResponse, WebhookSigning};
+use crate::utils::webhook_adapters::{json_request, WebhookAdapter};
 use log::Level::Debug;
 use serde_json::Value;
 use std::collections::hash_map::RandomState;
@@ -16,24 +17,34 @@ impl NullAdapter {
 impl WebhookAdapter for NullAdapter {
     fn initialize(&mut self, _config: Value) {}
 
-    fn send(&self, webhook_urls: &[String], payload: HashMap<String, Value, RandomState>) -> Result<(), ApiError> {
+    fn send(
+        &self,
+        webhook_urls: &[String],
+        payload: HashMap<String, Value, RandomState>,
+        signing: Option<&WebhookSigning>,
+    ) -> Result<Option<WebhookResponse>, ApiError> {
         let client = reqwest::blocking::Client::new();
+        let mut response = None;
         for webhook_url in webhook_urls {
-            let resp = client
-                .post(webhook_url)
-                .json(&payload)
+            let resp = json_request(client.post(webhook_url), &payload, signing)?
                 .send()
                 .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
 
             let status = resp.status();
-            let error_for_status = resp.error_for_status_ref().map(|_| ());
             let text = resp
                 .text()
                 .map_err(|_err| ApplicationError::new("Error making webhook request".to_string()))?;
 
             jlog!(Debug, "bigneon::domain_actions", "Response from customer.io", {"text": text, "status": status.to_string()});
-            error_for_status?;
+            let webhook_response = WebhookResponse {
+                status: status.as_u16(),
+                body: Some(text),
+            };
+            if !webhook_response.is_success() {
+                return Ok(Some(webhook_response));
+            }
+            response = Some(webhook_response);
         }
-        Ok(())
+        Ok(response)
     }
 }
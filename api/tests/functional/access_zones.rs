@@ -0,0 +1,43 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::access_zones::create(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::access_zones::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::access_zones::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::access_zones::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::access_zones::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::access_zones::create(Roles::Promoter, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::access_zones::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::access_zones::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::access_zones::create(Roles::OrgBoxOffice, false).await;
+    }
+}
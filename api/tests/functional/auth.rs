@@ -3,14 +3,17 @@ use crate::support;
 use crate::support::database::TestDatabase;
 use crate::support::test_request::TestRequest;
 use actix_web::{http::StatusCode, HttpResponse};
-use api::auth::TokenResponse;
+use api::auth::jwks::JwkSet;
+use api::auth::{LoginResponse, TokenResponse};
 use api::controllers::auth;
-use api::controllers::auth::{LoginRequest, RefreshRequest};
+use api::controllers::auth::{LoginRequest, MfaTokenRequest, RefreshRequest};
 use api::extractors::*;
 use api::models::*;
+use chrono::prelude::*;
 use chrono::Duration;
 use db::models::TokenIssuer;
-use db::prelude::{AccessToken, Scopes};
+use db::prelude::{AccessToken, DomainEvent, DomainEventTypes, Scopes, Tables, UserSession};
+use db::utils::totp;
 use serde_json;
 use uuid::Uuid;
 
@@ -29,14 +32,18 @@ async fn token() {
     let state = test_request.extract_state().await;
     let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
 
-    let response: TokenResponse = auth::token((
+    let response = match auth::token((
         test_request.request,
         database.connection.into(),
         json,
         RequestInfo { user_agent: None },
     ))
     .await
-    .unwrap();
+    .unwrap()
+    {
+        LoginResponse::Token(response) => response,
+        LoginResponse::MfaChallenge(_) => panic!("Unexpected multi-factor challenge"),
+    };
 
     let access_token = state.config.token_issuer.decode(&response.access_token).unwrap();
 
@@ -48,6 +55,165 @@ async fn token() {
     assert_eq!(refresh_token.claims.get_id().unwrap(), user.id);
 }
 
+#[actix_rt::test]
+async fn token_with_mfa_enabled() {
+    let database = TestDatabase::new();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .with_mfa(&state.config.mfa_encryption_keys)
+        .finish();
+
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+
+    let challenge = match auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap()
+    {
+        LoginResponse::MfaChallenge(challenge) => challenge,
+        LoginResponse::Token(_) => panic!("Expected multi-factor challenge"),
+    };
+    assert!(challenge.mfa_required);
+
+    // Challenge token can not be used to refresh
+    let json = Json(RefreshRequest::new(&challenge.mfa_token));
+    let response: HttpResponse = auth::token_refresh((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Incorrect code
+    let json = Json(MfaTokenRequest::new(&challenge.mfa_token, "000000"));
+    let response = auth::token_mfa((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await;
+    assert!(response.is_err());
+    assert_eq!("Authentication code incorrect", response.err().unwrap().to_string());
+
+    // The code used to activate MFA can not be used again
+    let secret = user.mfa_secret(&state.config.mfa_encryption_keys).unwrap().unwrap();
+    let code = totp::code_at(&secret, Utc::now().timestamp()).unwrap();
+    let json = Json(MfaTokenRequest::new(&challenge.mfa_token, &code));
+    let response = auth::token_mfa((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await;
+    assert!(response.is_err());
+    assert_eq!("Authentication code incorrect", response.err().unwrap().to_string());
+
+    let code = totp::code_at(&secret, Utc::now().timestamp() + totp::STEP_SECONDS).unwrap();
+    let json = Json(MfaTokenRequest::new(&challenge.mfa_token, &code));
+    let response: TokenResponse = auth::token_mfa((
+        state.clone(),
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .unwrap();
+    let access_token = state.config.token_issuer.decode(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+}
+
+#[actix_rt::test]
+async fn token_mfa_with_exhausted_challenge() {
+    let database = TestDatabase::new();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let user = database
+        .create_user()
+        .with_mfa(&state.config.mfa_encryption_keys)
+        .finish();
+    let mfa_token = state
+        .config
+        .token_issuer
+        .issue_with_limited_scopes(user.id, vec![Scopes::MfaChallenge], Duration::minutes(5))
+        .unwrap();
+
+    for _ in 0..5 {
+        let json = Json(MfaTokenRequest::new(&mfa_token, "000000"));
+        let response = auth::token_mfa((
+            state.clone(),
+            database.connection.clone().into(),
+            json,
+            RequestInfo { user_agent: None },
+        ))
+        .await;
+        assert_eq!("Authentication code incorrect", response.err().unwrap().to_string());
+    }
+
+    // Correct codes are no longer accepted for the challenge
+    let code = totp::code_at(
+        &user.mfa_secret(&state.config.mfa_encryption_keys).unwrap().unwrap(),
+        Utc::now().timestamp() + totp::STEP_SECONDS,
+    )
+    .unwrap();
+    let json = Json(MfaTokenRequest::new(&mfa_token, &code));
+    let response = auth::token_mfa((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await;
+    assert_eq!(
+        "Too many incorrect authentication codes, please log in again",
+        response.err().unwrap().to_string()
+    );
+}
+
+#[actix_rt::test]
+async fn token_mfa_without_challenge_token() {
+    let database = TestDatabase::new();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let user = database
+        .create_user()
+        .with_mfa(&state.config.mfa_encryption_keys)
+        .finish();
+
+    let access_token = state.config.token_issuer.issue(user.id, Duration::minutes(5)).unwrap();
+    let code = totp::code_at(
+        &user.mfa_secret(&state.config.mfa_encryption_keys).unwrap().unwrap(),
+        Utc::now().timestamp(),
+    )
+    .unwrap();
+    let json = Json(MfaTokenRequest::new(&access_token, &code));
+
+    let response = auth::token_mfa((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await;
+    assert!(response.is_err());
+    assert_eq!(
+        "Token can not be used to complete a multi-factor login",
+        response.err().unwrap().to_string()
+    );
+}
+
 #[actix_rt::test]
 async fn token_invalid_email() {
     let database = TestDatabase::new();
@@ -78,7 +244,7 @@ async fn token_incorrect_password() {
 
     let response = auth::token((
         test_request.request,
-        database.connection.into(),
+        database.connection.clone().into(),
         json,
         RequestInfo { user_agent: None },
     ))
@@ -86,6 +252,15 @@ async fn token_incorrect_password() {
 
     assert!(response.is_err());
     assert_eq!("Email or password incorrect", response.err().unwrap().to_string());
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserLoginFailed),
+        database.connection.get(),
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
 }
 
 #[actix_rt::test]
@@ -101,9 +276,14 @@ async fn token_refresh() {
         .unwrap();
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -125,9 +305,14 @@ async fn token_refresh_invalid_refresh_token_secret() {
 
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -144,9 +329,14 @@ async fn token_refresh_invalid_refresh_token() {
     let state = test_request.extract_state().await;
     let json = Json(RefreshRequest::new(&"not.a.real.token"));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -168,9 +358,14 @@ async fn token_refresh_user_does_not_exist() {
     let refresh_token = state.config.token_issuer.encode(&refresh_token_claims).unwrap();
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
@@ -194,9 +389,14 @@ async fn token_refresh_password_reset_since_issued() {
 
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -222,9 +422,14 @@ async fn token_refreshed_after_password_change() {
     let refresh_token = token_issuer.encode(&refresh_token_claims).unwrap();
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -233,3 +438,167 @@ async fn token_refreshed_after_password_change() {
 
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
 }
+
+#[actix_rt::test]
+async fn token_refresh_rotates_session() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let connection = database.connection.get();
+    let session = UserSession::create(user.id, Some("Test Agent".to_string()))
+        .commit(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let token_issuer = state.config.token_issuer.clone();
+    let refresh_token = token_issuer
+        .issue_refresh_token(user.id, session.id, session.current_token_id, Duration::minutes(30))
+        .unwrap();
+
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse = auth::token_refresh((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let rotated_refresh_token = token_issuer.decode(&response.refresh_token).unwrap();
+    assert_eq!(rotated_refresh_token.claims.sid, Some(session.id));
+    assert_ne!(rotated_refresh_token.claims.jti, Some(session.current_token_id));
+
+    // Presenting the rotated token again revokes the session
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse = auth::token_refresh((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+
+    let json = Json(RefreshRequest::new(&response_refresh_token(&body)));
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+async fn token_refresh_disabled_user() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let connection = database.connection.get();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let refresh_token = state
+        .config
+        .token_issuer
+        .issue_refresh_token(user.id, session.id, session.current_token_id, Duration::minutes(30))
+        .unwrap();
+    user.disable(None, connection).unwrap();
+
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+}
+
+#[actix_rt::test]
+async fn token_refresh_expired() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let connection = database.connection.get();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let refresh_token = state
+        .config
+        .token_issuer
+        .issue_refresh_token(user.id, session.id, session.current_token_id, Duration::minutes(-5))
+        .unwrap();
+
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        UserSession::find(session.id, connection).unwrap().current_token_id,
+        session.current_token_id
+    );
+}
+
+#[actix_rt::test]
+async fn token_refresh_without_session_after_sessions_revoked() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let connection = database.connection.get();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+    let session = session.revoke(connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let mut refresh_token_claims =
+        AccessToken::new_limited_scope(user.id, "iss".to_string(), 30, vec![Scopes::TokenRefresh]);
+    refresh_token_claims.issued = session.revoked_at.unwrap().timestamp() as u64 - 1;
+    let refresh_token = state.config.token_issuer.encode(&refresh_token_claims).unwrap();
+
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Token no longer valid"}).to_string());
+}
+
+fn response_refresh_token(body: &str) -> String {
+    let response: TokenResponse = serde_json::from_str(body).unwrap();
+    response.refresh_token
+}
+
+#[actix_rt::test]
+async fn jwks() {
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+
+    let response: HttpResponse = auth::jwks(state).await.into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let jwks: JwkSet = serde_json::from_str(&body).unwrap();
+    assert!(jwks.keys.is_empty());
+}
@@ -0,0 +1,38 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::access_zones::{self, CreateAccessZoneRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::{AccessZone, Roles};
+use serde_json;
+
+pub async fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let json = Json(CreateAccessZoneRequest {
+        name: "Backstage".to_string(),
+        description: None,
+    });
+    let response: HttpResponse = access_zones::create((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let access_zone: AccessZone = serde_json::from_str(&body).unwrap();
+    assert_eq!(access_zone.event_id, Some(event.id));
+    assert_eq!(access_zone.venue_id, None);
+    assert_eq!(access_zone.name, "Backstage".to_string());
+}
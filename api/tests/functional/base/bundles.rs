@@ -0,0 +1,61 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::bundles::{self, *};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let mut ticket_types = Vec::new();
+    for _ in 0..2 {
+        let event = database
+            .create_event()
+            .with_organization(&organization)
+            .with_ticket_pricing()
+            .finish();
+        ticket_types.push(event.ticket_types(true, None, connection).unwrap().remove(0));
+    }
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(CreateBundleRequest {
+        name: "Weekend pass".to_string(),
+        description: None,
+        price_in_cents: 160,
+        ticket_types: ticket_types
+            .iter()
+            .map(|ticket_type| UpdateBundleTicketType {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+            })
+            .collect(),
+    });
+    let response: HttpResponse = bundles::create((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_bundle: DisplayBundle = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_bundle.name, "Weekend pass");
+    assert_eq!(display_bundle.organization_id, organization.id);
+    assert_eq!(display_bundle.ticket_types.len(), 2);
+    for (bundle_ticket_type, ticket_type) in display_bundle.ticket_types.iter().zip(ticket_types.iter()) {
+        assert_eq!(bundle_ticket_type.ticket_type_id, ticket_type.id);
+        assert_eq!(bundle_ticket_type.event_id, ticket_type.event_id);
+        assert_eq!(bundle_ticket_type.allocated_price_in_cents, 80);
+    }
+}
@@ -42,8 +42,11 @@ pub async fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
     });
 
     let response: HttpResponse = cart::update_cart((
@@ -114,6 +117,7 @@ pub async fn replace_box_office_pricing(role: Roles, should_test_succeed: bool)
             ticket_type_id: old_ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -137,8 +141,11 @@ pub async fn replace_box_office_pricing(role: Roles, should_test_succeed: bool)
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
     });
 
     let response: HttpResponse = cart::replace_cart((
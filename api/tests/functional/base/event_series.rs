@@ -0,0 +1,152 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::event_series::{self, *};
+use api::extractors::*;
+use api::models::{EventSeriesTicketTypePathParameters, PathParameters};
+use db::models::*;
+use serde_json;
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let venue = database.create_venue().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let json = Json(CreateEventSeriesRequest {
+        template_event_id: event.id,
+        name: "Weekly residency".to_string(),
+        recurrence_rule: "FREQ=WEEKLY;COUNT=3".to_string(),
+    });
+    let response: HttpResponse = event_series::create((
+        database.connection.clone().into(),
+        json,
+        auth_user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_event_series: DisplayEventSeries = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_event_series.event_series.name, "Weekly residency");
+    assert_eq!(display_event_series.event_series.template_event_id, event.id);
+    assert_eq!(display_event_series.events.len(), 3);
+    assert_eq!(display_event_series.events[0].id, event.id);
+    for occurrence in display_event_series.events.iter() {
+        assert_eq!(occurrence.event_series_id, Some(display_event_series.event_series.id));
+        assert_eq!(occurrence.name, event.name);
+    }
+}
+
+pub async fn update_events(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let venue = database.create_venue().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let event_series = EventSeries::create(
+        organization.id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    event_series.generate_occurrences(None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event_series.id;
+    let json = Json(EventEditableAttributes {
+        additional_info: Some(Some("Bring a friend".to_string())),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        event_series::update_events((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let events: Vec<Event> = serde_json::from_str(&body).unwrap();
+    assert_eq!(events.len(), 3);
+    for event in event_series.events(connection).unwrap() {
+        assert_eq!(event.additional_info, Some("Bring a friend".to_string()));
+    }
+}
+
+pub async fn update_ticket_type(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let venue = database.create_venue().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let event_series = EventSeries::create(
+        organization.id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "ticket_type_id"]);
+    let mut path = Path::<EventSeriesTicketTypePathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = event_series.id;
+    path.ticket_type_id = ticket_type.id;
+    let json = Json(TicketTypeEditableAttributes {
+        name: Some("VIP".to_string()),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        event_series::update_ticket_type((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(TicketType::find(ticket_type.id, connection).unwrap().name, "VIP");
+    for occurrence in occurrences {
+        assert_eq!(occurrence.ticket_types(true, None, connection).unwrap()[0].name, "VIP");
+    }
+}
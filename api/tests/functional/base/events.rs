@@ -97,7 +97,9 @@ pub async fn redeem_ticket(role: Roles, should_test_succeed: bool) {
     //First try when Redeem code is wrong
     let request_data = TicketRedeemRequest {
         redeem_key: "WrongKey".to_string(),
+        ticket_id: None,
         check_in_source: Some(CheckInSource::Scanned),
+        access_zone_id: None,
     };
 
     let response: HttpResponse = events::redeem_ticket((
@@ -116,7 +118,9 @@ pub async fn redeem_ticket(role: Roles, should_test_succeed: bool) {
         //Now try with redeem code being correct
         let request_data = TicketRedeemRequest {
             redeem_key: ticket.redeem_key.unwrap(),
+            ticket_id: None,
             check_in_source: Some(CheckInSource::Scanned),
+            access_zone_id: None,
         };
 
         let response: HttpResponse = events::redeem_ticket((
@@ -138,6 +142,196 @@ pub async fn redeem_ticket(role: Roles, should_test_succeed: bool) {
     }
 }
 
+pub async fn check_out_ticket(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let user = database.create_user().finish();
+    let request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user2 = database.create_user().finish();
+    let ticket_type = event.ticket_types(true, None, conn).unwrap()[0].id;
+    let ticket = database.create_purchased_tickets(&user2, ticket_type, 1).remove(0);
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        user.id,
+        CheckInSource::Scanned,
+        conn,
+    )
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let request_data = TicketRedeemRequest {
+        redeem_key: ticket.redeem_key.clone().unwrap(),
+        ticket_id: None,
+        check_in_source: None,
+        access_zone_id: None,
+    };
+    let response: HttpResponse =
+        events::check_out_ticket((database.connection.clone().into(), path, Json(request_data), auth_user))
+            .await
+            .into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let scans: Vec<TicketScan> = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            scans.iter().map(|s| s.scan_type).collect::<Vec<TicketScanType>>(),
+            vec![TicketScanType::CheckIn, TicketScanType::CheckOut]
+        );
+        assert_eq!(
+            TicketScan::occupancy_for_event(event.id, conn).unwrap(),
+            EventOccupancy {
+                entries: 1,
+                exits: 1,
+                inside: 0
+            }
+        );
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub async fn scanner_snapshot(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let user = database.create_user().finish();
+    let request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user2 = database.create_user().finish();
+    let ticket_type = event.ticket_types(true, None, conn).unwrap()[0].id;
+    let ticket = database.create_purchased_tickets(&user2, ticket_type, 1).remove(0);
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let state = request.extract_state().await;
+    let response: HttpResponse =
+        events::scanner_snapshot((database.connection.clone().into(), path, auth_user, state.clone()))
+            .await
+            .into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        // The whole snapshot is signed, tampering with the ticket list invalidates it
+        let snapshot = jwt::decode::<ScannerSnapshot>(
+            body["snapshot"].as_str().unwrap(),
+            state.config.token_issuer.token_secret.as_bytes(),
+            &jwt::Validation::default(),
+        )
+        .unwrap()
+        .claims;
+        assert_eq!(snapshot.event_id, event.id);
+        assert_eq!(
+            snapshot.upload_token,
+            ScannerSnapshot::upload_token(
+                &state.config.scanner_snapshot_secret,
+                event.id,
+                snapshot.generated_at,
+                &snapshot.salt
+            )
+        );
+        assert_eq!(
+            snapshot.exp,
+            (snapshot.generated_at + state.config.scanner_snapshot_max_age).timestamp()
+        );
+        assert_eq!(
+            snapshot.tickets,
+            vec![ScannerSnapshotTicket {
+                id: ticket.id,
+                ticket_type_id: ticket_type,
+                redeem_key_hash: Some(TicketInstance::hash_redeem_key(
+                    &snapshot.salt,
+                    &ticket.redeem_key.unwrap()
+                )),
+                redeem_secret: None,
+                status: TicketInstanceStatus::Purchased,
+                pending_transfer: false,
+            }]
+        );
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub async fn redeem_offline(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let conn = database.connection.get();
+    let user = database.create_user().finish();
+    let request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user2 = database.create_user().finish();
+    let ticket_type = event.ticket_types(true, None, conn).unwrap()[0].id;
+    let ticket = database.create_purchased_tickets(&user2, ticket_type, 1).remove(0);
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let state = request.extract_state().await;
+    let generated_at = Utc::now().naive_utc();
+    let request_data = OfflineRedeemRequest {
+        generated_at,
+        salt: "salt".to_string(),
+        upload_token: ScannerSnapshot::upload_token(
+            &state.config.scanner_snapshot_secret,
+            event.id,
+            generated_at,
+            "salt",
+        ),
+        scans: vec![OfflineScan {
+            ticket_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            scanned_at: generated_at,
+            device_id: Some("door-1".to_string()),
+            check_in_source: Some(CheckInSource::Scanned),
+            access_zone_id: None,
+        }],
+    };
+    let response: HttpResponse = events::redeem_offline((
+        database.connection.clone().into(),
+        path,
+        Json(request_data),
+        auth_user,
+        state,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let results: Vec<OfflineScanResult> = serde_json::from_str(&body).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].ticket_id, ticket.id);
+        assert_eq!(results[0].result, RedeemResults::TicketRedeemSuccess);
+        let ticket = TicketInstance::find(ticket.id, conn).unwrap();
+        assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
 pub async fn export_event_data(role: Roles, should_test_succeed: bool, past_or_upcoming: Option<PastOrUpcoming>) {
     let database = TestDatabase::new();
 
@@ -623,6 +817,7 @@ pub async fn dashboard(role: Roles, should_test_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1,25 +1,34 @@
+pub mod access_zones;
 pub mod announcements;
 pub mod artists;
+pub mod bundles;
 pub mod cart;
 pub mod codes;
 pub mod collections;
 pub mod comps;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod events;
 pub mod holds;
 pub mod notes;
 pub mod orders;
+pub mod organization_api_keys;
 pub mod organization_invites;
 pub mod organization_venues;
+pub mod organization_webhooks;
 pub mod organizations;
+pub mod products;
 pub mod regions;
 pub mod reports;
 pub mod reports_admin;
+pub mod seat_maps;
 pub mod settlement_adjustments;
 pub mod settlements;
 pub mod stages;
+pub mod tax_rates;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
 pub mod users;
 pub mod venues;
+pub mod webhook_deliveries;
@@ -126,6 +126,7 @@ pub async fn activity(role: Roles, should_test_true: bool) {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -240,6 +241,7 @@ pub async fn details(role: Roles, should_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -391,6 +393,7 @@ pub async fn refund(role: Roles, manual_override: bool, should_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -0,0 +1,119 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::organization_api_keys::{self, NewOrganizationApiKeyRequest};
+use api::extractors::*;
+use api::models::*;
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+pub async fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let creator = database.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        creator.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = organization_api_keys::index((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let api_keys: Vec<OrganizationApiKey> = serde_json::from_str(&body).unwrap();
+    assert_eq!(api_keys.len(), 1);
+    assert_eq!(api_keys[0].id, api_key.id);
+    assert!(!body.contains("key_hash"));
+}
+
+pub async fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewOrganizationApiKeyRequest {
+        name: "Integration".to_string(),
+        scopes: vec![Scopes::OrgRead.to_string(), Scopes::OrgWrite.to_string()],
+        event_ids: vec![],
+        expires_at: None,
+    });
+    let response: HttpResponse =
+        organization_api_keys::create((database.connection.clone().into(), path, json, auth_user.clone()))
+            .await
+            .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let value: Value = serde_json::from_str(&body).unwrap();
+    let key = value["key"].as_str().unwrap();
+    let api_key = OrganizationApiKey::find_by_key(key, connection).unwrap();
+    assert_eq!(value["id"], json!(api_key.id));
+    assert_eq!(api_key.organization_id, organization.id);
+    assert_eq!(api_key.created_by_user_id, auth_user.id());
+    assert_eq!(api_key.get_scopes(), vec![Scopes::OrgRead, Scopes::OrgWrite]);
+    assert!(key.starts_with(&api_key.key_prefix));
+}
+
+pub async fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let creator = database.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        creator.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "api_key_id"]);
+    let mut path = Path::<OrganizationApiKeyPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.api_key_id = api_key.id;
+    let response: HttpResponse = organization_api_keys::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(OrganizationApiKey::find(api_key.id, connection).unwrap().is_active());
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!OrganizationApiKey::find(api_key.id, connection).unwrap().is_active());
+}
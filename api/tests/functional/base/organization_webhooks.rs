@@ -0,0 +1,261 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::organization_webhooks::{self, NewOrganizationWebhookRequest, UpdateOrganizationWebhookRequest};
+use api::extractors::*;
+use api::models::*;
+use db::models::*;
+use serde_json;
+use serde_json::Value;
+
+pub async fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+    database.create_domain_event_publisher().finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = organization_webhooks::index((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let webhooks: Vec<DomainEventPublisher> = serde_json::from_str(&body).unwrap();
+    assert_eq!(webhooks, vec![webhook]);
+    // The signing secret is only returned when the webhook is created
+    assert!(!body.contains("webhook_secret"));
+}
+
+pub async fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewOrganizationWebhookRequest {
+        webhook_url: "https://example.com/webhooks".to_string(),
+        event_types: vec![
+            DomainEventTypes::EventPublished,
+            DomainEventTypes::TicketInstanceRedeemed,
+        ],
+    });
+    let response: HttpResponse =
+        organization_webhooks::create((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let value: Value = serde_json::from_str(&body).unwrap();
+    let webhook = DomainEventPublisher::find_by_organization_id(organization.id, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(value["id"], json!(webhook.id));
+    assert_eq!(value["webhook_url"], json!("https://example.com/webhooks"));
+    assert_eq!(value["webhook_secret"], json!(webhook.webhook_secret));
+    assert_eq!(
+        webhook.event_types,
+        vec![
+            DomainEventTypes::EventPublished,
+            DomainEventTypes::TicketInstanceRedeemed
+        ]
+    );
+}
+
+pub async fn update(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_id"]);
+    let mut path = Path::<OrganizationWebhookPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.webhook_id = webhook.id;
+    let json = Json(UpdateOrganizationWebhookRequest {
+        webhook_url: Some("https://example.com/updated".to_string()),
+        event_types: Some(vec![DomainEventTypes::EventCreated]),
+    });
+    let response: HttpResponse =
+        organization_webhooks::update((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let webhook: DomainEventPublisher = serde_json::from_str(&body).unwrap();
+    assert_eq!(webhook.webhook_url, "https://example.com/updated".to_string());
+    assert_eq!(webhook.event_types, vec![DomainEventTypes::EventCreated]);
+}
+
+pub async fn pause(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_id"]);
+    let mut path = Path::<OrganizationWebhookPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.webhook_id = webhook.id;
+    let response: HttpResponse = organization_webhooks::pause((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(!DomainEventPublisher::find(webhook.id, connection).unwrap().is_paused());
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(DomainEventPublisher::find(webhook.id, connection).unwrap().is_paused());
+}
+
+pub async fn resume(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish()
+        .pause(connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_id"]);
+    let mut path = Path::<OrganizationWebhookPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.webhook_id = webhook.id;
+    let response: HttpResponse = organization_webhooks::resume((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(DomainEventPublisher::find(webhook.id, connection).unwrap().is_paused());
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!DomainEventPublisher::find(webhook.id, connection).unwrap().is_paused());
+}
+
+pub async fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_id"]);
+    let mut path = Path::<OrganizationWebhookPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.webhook_id = webhook.id;
+    let response: HttpResponse = organization_webhooks::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        DomainEventPublisher::find_by_organization_id(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+pub async fn test(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_id"]);
+    let mut path = Path::<OrganizationWebhookPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.webhook_id = webhook.id;
+    let response: HttpResponse = organization_webhooks::test((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let webhook_delivery: WebhookDelivery = serde_json::from_str(&body).unwrap();
+    assert_eq!(webhook_delivery.domain_event_publisher_id, webhook.id);
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(webhook_delivery.payload["webhook_event_type"], json!("test"));
+    assert_eq!(
+        WebhookDelivery::find_by_domain_event_publisher_id(webhook.id, None, 0, 100, connection)
+            .unwrap()
+            .paging
+            .total,
+        1
+    );
+}
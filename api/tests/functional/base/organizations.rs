@@ -186,6 +186,10 @@ pub async fn create(role: Roles, should_test_succeed: bool) {
         globee_api_key: None,
         max_instances_per_ticket_type: Some(11000),
         settlement_type: None,
+        braintree_public_key: None,
+        braintree_private_key: None,
+        braintree_merchant_account_id: None,
+        stripe_connect_account_id: None,
     });
 
     let test_request = TestRequest::create_with_uri("/organizations");
@@ -285,6 +289,9 @@ pub async fn update_restricted_field(restricted_field: &str, role: Roles, should
         "max_instances_per_ticket_type" => {
             attributes.max_instances_per_ticket_type = Some(11000);
         }
+        "stripe_connect_account_id" => {
+            attributes.stripe_connect_account_id = Some(Some("acct_1GqIC8HYLQbJDcKE".to_string()));
+        }
         _ => panic!("Unexpected restricted field"),
     }
 
@@ -315,6 +322,12 @@ pub async fn update_restricted_field(restricted_field: &str, role: Roles, should
         "max_instances_per_ticket_type" => {
             assert_eq!(updated_organization.max_instances_per_ticket_type, 11000);
         }
+        "stripe_connect_account_id" => {
+            assert_eq!(
+                updated_organization.stripe_connect_account_id,
+                Some("acct_1GqIC8HYLQbJDcKE".to_string())
+            );
+        }
         _ => panic!("Unexpected restricted field"),
     }
 }
@@ -0,0 +1,123 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::products::{self, *};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(CreateProductRequest {
+        event_id: Some(event.id),
+        name: "T-shirt".to_string(),
+        description: None,
+        price_in_cents: 2000,
+        quantity: 0,
+        requires_pickup: true,
+        variants: vec![
+            UpdateProductVariant {
+                name: "M".to_string(),
+                quantity: 10,
+            },
+            UpdateProductVariant {
+                name: "L".to_string(),
+                quantity: 5,
+            },
+        ],
+    });
+    let response: HttpResponse = products::create((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_product: DisplayProduct = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_product.name, "T-shirt");
+    assert_eq!(display_product.organization_id, organization.id);
+    assert_eq!(display_product.event_id, Some(event.id));
+    assert_eq!(display_product.available, 15);
+    assert_eq!(display_product.variants.len(), 2);
+}
+
+pub async fn redeem(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let product = Product::create(
+        organization.id,
+        Some(event.id),
+        "Parking pass".to_string(),
+        None,
+        500,
+        10,
+        true,
+    )
+    .commit(None, connection)
+    .unwrap();
+    let buyer = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.update_product_quantities(
+        buyer.id,
+        &[UpdateOrderProduct {
+            product_id: product.id,
+            product_variant_id: None,
+            quantity: 2,
+            event_id: None,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let json = Json(RedeemProductRequest {
+        order_item_id: order_item.id,
+        quantity: 2,
+    });
+    let response: HttpResponse = products::redeem((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let order_item: OrderItem = serde_json::from_str(&body).unwrap();
+    assert_eq!(order_item.redeemed_quantity, 2);
+}
@@ -88,7 +88,9 @@ pub async fn scan_counts(role: Roles, should_succeed: bool) {
             total: None,
             ticket_type_name: ticket_types[0].name.clone(),
             scanned_count: 1,
-            not_scanned_count: 7
+            not_scanned_count: 7,
+            entry_count: 1,
+            exit_count: 0
         }],
         report_data.data
     );
@@ -510,5 +512,8 @@ fn build_transaction_report_row(
         check_in_source: None,
         headline_artist_alt_genres: None,
         headline_artist_main_genre: None,
+        currency: event.currency.clone(),
+        tax_in_cents: 0,
+        tax_in_cents_total: 0,
     }
 }
@@ -0,0 +1,127 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::seat_maps::{self, *};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::prelude::*;
+use serde_json;
+use std::collections::HashMap;
+
+pub async fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(CreateSeatMap {
+        name: "Main Floor".to_string(),
+        stage_id: None,
+        sections: vec![CreateSeatMapSection {
+            name: "Orchestra".to_string(),
+            rows: vec![CreateSeatMapRow {
+                label: "A".to_string(),
+                seats: vec![
+                    CreateSeat {
+                        seat_number: "101".to_string(),
+                        price_tier: "Premium".to_string(),
+                    },
+                    CreateSeat {
+                        seat_number: "102".to_string(),
+                        price_tier: "Premium".to_string(),
+                    },
+                ],
+            }],
+        }],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = venue.id;
+    let response: HttpResponse = seat_maps::create((database.connection.clone().into(), path, json, user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let seat_map: DisplaySeatMap = serde_json::from_str(&body).unwrap();
+    assert_eq!(seat_map.name, "Main Floor");
+    assert_eq!(seat_map.venue_id, venue.id);
+    assert_eq!(seat_map.sections.len(), 1);
+    let seats = &seat_map.sections[0].rows[0].seats;
+    assert_eq!(seats.len(), 2);
+    assert_eq!(seats[1].seat_number, "102");
+    assert_eq!(seats[1].seat_rank, 1);
+}
+
+pub async fn update_event_seating(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let venue = database.create_venue().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(10)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let seat_map = SeatMap::create(venue.id, None, "Main Floor".to_string())
+        .commit(connection)
+        .unwrap();
+    let section = SeatMapSection::create(seat_map.id, "Orchestra".to_string(), 0)
+        .commit(connection)
+        .unwrap();
+    Seat::create_multiple(
+        &[
+            Seat::create(
+                section.id,
+                "A".to_string(),
+                0,
+                "1".to_string(),
+                0,
+                "Premium".to_string(),
+            ),
+            Seat::create(
+                section.id,
+                "A".to_string(),
+                0,
+                "2".to_string(),
+                1,
+                "Premium".to_string(),
+            ),
+        ],
+        connection,
+    )
+    .unwrap();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("Premium".to_string(), ticket_type.id);
+    let json = Json(UpdateEventSeating {
+        seat_map_id: seat_map.id,
+        price_tiers,
+    });
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event.id;
+    let response: HttpResponse =
+        seat_maps::update_event_seating((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let seats = Seat::find_for_event(event.id, connection).unwrap();
+    assert_eq!(seats.len(), 2);
+    assert!(seats
+        .iter()
+        .all(|seat| seat.available && seat.ticket_type_id == Some(ticket_type.id)));
+}
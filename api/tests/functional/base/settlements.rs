@@ -29,6 +29,7 @@ pub async fn create(role: Roles, should_succeed: bool) {
         comment: Some(comment.clone()),
         start_time,
         end_time,
+        currency: None,
     });
 
     let test_request = TestRequest::create();
@@ -0,0 +1,117 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::tax_rates::{self, NewTaxRateRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::{Roles, TaxRate, TaxRateEditableAttributes};
+use serde_json;
+
+pub async fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    database.create_tax_rate().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = tax_rates::index((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_tax_rates: Vec<TaxRate> = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        found_tax_rates,
+        TaxRate::find_for_organization(organization.id, connection).unwrap()
+    );
+}
+
+pub async fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewTaxRateRequest {
+        venue_id: None,
+        name: "VAT".to_string(),
+        rate_percent: 20.0,
+        inclusive: true,
+        applies_to_fees: false,
+    });
+    let response: HttpResponse = tax_rates::create((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rate: TaxRate = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rate.organization_id, organization.id);
+    assert_eq!(tax_rate.name, "VAT".to_string());
+    assert_eq!(tax_rate.rate_percent, 20.0);
+    assert!(tax_rate.inclusive);
+}
+
+pub async fn update(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let tax_rate = database.create_tax_rate().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = tax_rate.id;
+    let json = Json(TaxRateEditableAttributes {
+        rate_percent: Some(7.5),
+        ..Default::default()
+    });
+    let response: HttpResponse = tax_rates::update((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_tax_rate: TaxRate = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_tax_rate.rate_percent, 7.5);
+}
+
+pub async fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let tax_rate = database.create_tax_rate().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = tax_rate.id;
+    let response: HttpResponse = tax_rates::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(TaxRate::find(tax_rate.id, connection).is_err());
+}
@@ -55,6 +55,10 @@ pub async fn show_other_user_ticket(role: Roles, should_test_succeed: bool) {
             transfer_address: None,
             check_in_source: None,
             promo_image_url: None,
+            redeem_secret: None,
+            seat_section: None,
+            seat_row: None,
+            seat_number: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -123,6 +127,10 @@ pub async fn update(role: Roles, owns_ticket: bool, should_test_succeed: bool) {
             transfer_address: None,
             check_in_source: None,
             promo_image_url: None,
+            redeem_secret: None,
+            seat_section: None,
+            seat_row: None,
+            seat_number: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -159,6 +167,7 @@ pub async fn show_redeemable_ticket(role: Roles, should_test_succeed: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -10,6 +10,7 @@ use api::controllers::transfers::{self, *};
 use api::errors::ApiError;
 use api::models::*;
 use chrono::prelude::*;
+use db::dev::TestProject;
 use db::prelude::*;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -176,6 +177,7 @@ pub async fn cancel_completed_transfer(role: Roles, should_succeed: bool) {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -112,6 +112,7 @@ pub async fn activity(role: Roles, should_test_true: bool) {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -202,6 +203,7 @@ pub async fn history(role: Roles, should_test_true: bool) {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -354,6 +356,35 @@ pub async fn show_push_notification_tokens_for_user_id(role: Roles, should_test_
     }
 }
 
+pub async fn revoke_sessions_for_user(role: Roles, should_test_true: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user2, Roles::OrgMember)
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let session = UserSession::create(user2.id, None).commit(connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = user2.id;
+    let response: HttpResponse =
+        users::revoke_sessions_for_user((database.connection.clone().into(), path, auth_user.clone()))
+            .await
+            .into();
+
+    if should_test_true {
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+    } else {
+        support::expects_unauthorized(&response);
+        assert!(UserSession::find(session.id, connection).unwrap().is_active());
+    }
+}
+
 pub async fn show_push_notification_tokens(role: Roles, should_test_true: bool) {
     let database = TestDatabase::new();
     let connection = database.connection.get();
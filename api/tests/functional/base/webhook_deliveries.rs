@@ -0,0 +1,84 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::webhook_deliveries;
+use api::models::{PathParameters, WebhookDeliveryPathParameters};
+use db::prelude::*;
+use serde_json;
+
+pub async fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+    WebhookDelivery::create(database.create_domain_event_publisher().finish().id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri("/domain_event_publishers/id/webhook_deliveries?status=Pending");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = domain_event_publisher.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).await.unwrap();
+    let response =
+        webhook_deliveries::index((database.connection.clone().into(), path, query_parameters, auth_user)).await;
+
+    if !should_succeed {
+        assert_eq!(
+            response.err().unwrap().to_string(),
+            "User does not have the required permissions"
+        );
+        return;
+    }
+
+    let response = response.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.payload().data, vec![webhook_delivery]);
+    assert_eq!(response.payload().paging.total, 1);
+}
+
+pub async fn replay(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let domain_event_publisher = database.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap()
+        .mark_failed(Some(500), None, connection)
+        .unwrap();
+    let webhook_delivery = (1..WEBHOOK_DELIVERY_MAX_ATTEMPTS).fold(webhook_delivery, |webhook_delivery, _| {
+        webhook_delivery.mark_failed(Some(500), None, connection).unwrap()
+    });
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Failed);
+
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_delivery_id"]);
+    let mut path = Path::<WebhookDeliveryPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = domain_event_publisher.id;
+    path.webhook_delivery_id = webhook_delivery.id;
+    let response: HttpResponse = webhook_deliveries::replay((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let webhook_delivery: WebhookDelivery = serde_json::from_str(&body).unwrap();
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(webhook_delivery.attempt_count, 0);
+}
@@ -0,0 +1,43 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::bundles::create(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::bundles::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::bundles::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::bundles::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::bundles::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::bundles::create(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::bundles::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::bundles::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::bundles::create(Roles::OrgBoxOffice, false).await;
+    }
+}
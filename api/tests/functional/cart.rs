@@ -315,6 +315,7 @@ async fn destroy() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -370,8 +371,11 @@ async fn update() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
     });
 
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
@@ -417,8 +421,11 @@ async fn update_with_draft_event() {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         box_office_pricing: None,
     });
 
@@ -452,16 +459,20 @@ async fn update_multiple() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![
             cart::CartItem {
                 ticket_type_id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             cart::CartItem {
                 ticket_type_id: ticket_type_id2,
                 quantity: 3,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
     });
@@ -521,10 +532,13 @@ async fn add_with_increment() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -570,10 +584,13 @@ async fn update_with_increment_failure_invalid_quantity() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -610,10 +627,13 @@ async fn update_with_existing_cart() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -653,6 +673,7 @@ async fn reduce() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -672,10 +693,13 @@ async fn reduce() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 6,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -718,6 +742,7 @@ async fn remove() {
             ticket_type_id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -738,10 +763,13 @@ async fn remove() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -786,6 +814,7 @@ async fn remove_with_increment() {
             ticket_type_id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -806,10 +835,13 @@ async fn remove_with_increment() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -857,6 +889,7 @@ async fn remove_with_increment_failure_invalid_quantity() {
             ticket_type_id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -877,10 +910,13 @@ async fn remove_with_increment_failure_invalid_quantity() {
     let input = Json(cart::UpdateCartRequest {
         box_office_pricing: None,
         tracking_data: None,
+        bundles: vec![],
+        products: vec![],
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
     });
 
@@ -1146,6 +1182,87 @@ async fn checkout_free_for_paid_items() {
     assert_eq!(order.status, OrderStatus::Draft);
 }
 
+#[actix_rt::test]
+async fn checkout_fails_for_mixed_currencies() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let eur_event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_currency("EUR")
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let eur_ticket_type = eur_event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    let user = database.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: eur_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let request = TestRequest::create();
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        method: PaymentRequest::Card {
+            token: "abc".into(),
+            provider: PaymentProviders::Stripe,
+            save_payment_method: false,
+            set_default: false,
+        },
+    });
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let expected_json = HttpResponse::UnprocessableEntity().json(json!({
+        "error": "Could not complete this checkout because it contains items in more than one currency"
+    }));
+    let expected_text = unwrap_body_to_string(&expected_json).unwrap();
+    let body = unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_text);
+
+    // Reload cart
+    let cart = Order::find(cart.id, connection).unwrap();
+    assert_eq!(cart.status, OrderStatus::Draft);
+    assert_eq!(cart.currency, None);
+}
+
 #[actix_rt::test]
 async fn clear_invalid_items() {
     let database = TestDatabase::new();
@@ -1163,6 +1280,7 @@ async fn clear_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1209,6 +1327,7 @@ async fn checkout_fails_for_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1384,3 +1503,54 @@ async fn checkout_provider_globee() {
     let order = Order::find(order.id, conn).unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 }
+
+#[actix_rt::test]
+async fn confirm_fails_for_unowned_order() {
+    let database = TestDatabase::new();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let order = database.create_cart().for_event(&event).finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = order.id;
+    let response: HttpResponse = cart::confirm((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[actix_rt::test]
+async fn confirm_fails_when_not_pending_authentication() {
+    let database = TestDatabase::new();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = order.id;
+    let response: HttpResponse = cart::confirm((
+        database.connection.clone().into(),
+        path,
+        auth_user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let expected_json = HttpResponse::UnprocessableEntity().json(json!({
+        "error": "Could not confirm this cart because it is not awaiting authentication"
+    }));
+    let expected_text = unwrap_body_to_string(&expected_json).unwrap();
+    let body = unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_text);
+}
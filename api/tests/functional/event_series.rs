@@ -0,0 +1,125 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::event_series::create(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::event_series::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::event_series::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::event_series::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::event_series::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::event_series::create(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::event_series::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::event_series::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::event_series::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_events_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_events_org_member() {
+        base::event_series::update_events(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_admin() {
+        base::event_series::update_events(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_user() {
+        base::event_series::update_events(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_org_owner() {
+        base::event_series::update_events(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_door_person() {
+        base::event_series::update_events(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_promoter() {
+        base::event_series::update_events(Roles::Promoter, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_promoter_read_only() {
+        base::event_series::update_events(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_org_admin() {
+        base::event_series::update_events(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_events_box_office() {
+        base::event_series::update_events(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_ticket_type_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_ticket_type_org_member() {
+        base::event_series::update_ticket_type(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_admin() {
+        base::event_series::update_ticket_type(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_user() {
+        base::event_series::update_ticket_type(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_org_owner() {
+        base::event_series::update_ticket_type(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_door_person() {
+        base::event_series::update_ticket_type(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_promoter() {
+        base::event_series::update_ticket_type(Roles::Promoter, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_promoter_read_only() {
+        base::event_series::update_ticket_type(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_org_admin() {
+        base::event_series::update_ticket_type(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_ticket_type_box_office() {
+        base::event_series::update_ticket_type(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -9,12 +9,14 @@ use actix_web::{
 };
 use api::controllers::events;
 use api::controllers::events::*;
+use api::database::CacheDatabase;
 use api::extractors::*;
 use api::models::*;
 use chrono::prelude::*;
 use chrono::Duration;
 use db::models::*;
 use db::utils::dates;
+use db::utils::totp;
 use diesel::PgConnection;
 use serde_json;
 use serde_json::Value;
@@ -2179,6 +2181,375 @@ mod redeem_ticket {
     }
 }
 
+#[actix_rt::test]
+async fn redeem_ticket_with_rotating_code() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_rotating_redeem_codes()
+        .finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+    let ticket = database.create_purchased_tickets(&user, ticket_type_id, 1).remove(0);
+    let auth_user = support::create_auth_user(Roles::DoorPerson, Some(&organization), &database);
+    let code = totp::code_at(&ticket.redeem_secret.clone().unwrap(), Utc::now().timestamp()).unwrap();
+
+    // Rotating codes are not unique per event so the ticket has to be identified
+    let request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let response: HttpResponse = events::redeem_ticket((
+        database.connection.clone().into(),
+        path,
+        Json(TicketRedeemRequest {
+            redeem_key: code.clone(),
+            ticket_id: None,
+            check_in_source: Some(CheckInSource::Scanned),
+            access_zone_id: None,
+        }),
+        auth_user.clone(),
+        request.extract_state().await,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let response: HttpResponse = events::redeem_ticket((
+        database.connection.clone().into(),
+        path,
+        Json(TicketRedeemRequest {
+            redeem_key: code,
+            ticket_id: Some(ticket.id),
+            check_in_source: Some(CheckInSource::Scanned),
+            access_zone_id: None,
+        }),
+        auth_user,
+        request.extract_state().await,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+}
+
+async fn redeem_ticket_request(
+    event: &Event,
+    ticket: &TicketInstance,
+    redeem_key: String,
+    check_in_source: Option<CheckInSource>,
+    auth_user: api::auth::user::User,
+    database: &TestDatabase,
+) -> HttpResponse {
+    let request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    events::redeem_ticket((
+        database.connection.clone().into(),
+        path,
+        Json(TicketRedeemRequest {
+            redeem_key,
+            ticket_id: Some(ticket.id),
+            check_in_source,
+            access_zone_id: None,
+        }),
+        auth_user,
+        request.extract_state().await,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into()
+}
+
+#[actix_rt::test]
+async fn redeem_ticket_with_rotating_code_without_check_in_source() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_rotating_redeem_codes()
+        .finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+    let mut tickets = database.create_purchased_tickets(&user, ticket_type_id, 2);
+    let ticket = tickets.remove(0);
+    let other_ticket = tickets.remove(0);
+    let door_person = support::create_auth_user(Roles::DoorPerson, Some(&organization), &database);
+    let box_office = support::create_auth_user(Roles::OrgBoxOffice, Some(&organization), &database);
+
+    // Scanner clients that omit the source can no longer use the static key
+    let response = redeem_ticket_request(
+        &event,
+        &other_ticket,
+        other_ticket.redeem_key.clone().unwrap(),
+        None,
+        door_person.clone(),
+        &database,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Only box office users can check in guests with the static key
+    let response = redeem_ticket_request(
+        &event,
+        &other_ticket,
+        other_ticket.redeem_key.clone().unwrap(),
+        Some(CheckInSource::GuestList),
+        door_person.clone(),
+        &database,
+    )
+    .await;
+    support::expects_unauthorized(&response);
+    let response = redeem_ticket_request(
+        &event,
+        &other_ticket,
+        other_ticket.redeem_key.clone().unwrap(),
+        Some(CheckInSource::GuestList),
+        box_office,
+        &database,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Rotating codes are accepted without the source
+    let code = totp::code_at(&ticket.redeem_secret.clone().unwrap(), Utc::now().timestamp()).unwrap();
+    let response = redeem_ticket_request(&event, &ticket, code, None, door_person, &database).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+}
+
+#[cfg(test)]
+mod check_out_ticket {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn check_out_ticket_org_member() {
+        base::events::check_out_ticket(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_admin() {
+        base::events::check_out_ticket(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_user() {
+        base::events::check_out_ticket(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_org_owner() {
+        base::events::check_out_ticket(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_door_person() {
+        base::events::check_out_ticket(Roles::DoorPerson, true).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_promoter() {
+        base::events::check_out_ticket(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_promoter_read_only() {
+        base::events::check_out_ticket(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_org_admin() {
+        base::events::check_out_ticket(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn check_out_ticket_box_office() {
+        base::events::check_out_ticket(Roles::OrgBoxOffice, true).await;
+    }
+}
+
+#[cfg(test)]
+mod scanner_snapshot_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn scanner_snapshot_org_member() {
+        base::events::scanner_snapshot(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_admin() {
+        base::events::scanner_snapshot(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_user() {
+        base::events::scanner_snapshot(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_org_owner() {
+        base::events::scanner_snapshot(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_door_person() {
+        base::events::scanner_snapshot(Roles::DoorPerson, true).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_promoter() {
+        base::events::scanner_snapshot(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_promoter_read_only() {
+        base::events::scanner_snapshot(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_org_admin() {
+        base::events::scanner_snapshot(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn scanner_snapshot_box_office() {
+        base::events::scanner_snapshot(Roles::OrgBoxOffice, true).await;
+    }
+}
+
+#[cfg(test)]
+mod redeem_offline_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn redeem_offline_org_member() {
+        base::events::redeem_offline(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_admin() {
+        base::events::redeem_offline(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_user() {
+        base::events::redeem_offline(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_org_owner() {
+        base::events::redeem_offline(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_door_person() {
+        base::events::redeem_offline(Roles::DoorPerson, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_promoter() {
+        base::events::redeem_offline(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_promoter_read_only() {
+        base::events::redeem_offline(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_org_admin() {
+        base::events::redeem_offline(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_box_office() {
+        base::events::redeem_offline(Roles::OrgBoxOffice, true).await;
+    }
+}
+
+#[actix_rt::test]
+async fn redeem_offline_with_invalid_upload_token() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+    let ticket = database.create_purchased_tickets(&user, ticket_type_id, 1).remove(0);
+    let auth_user = support::create_auth_user(Roles::DoorPerson, Some(&organization), &database);
+
+    let request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let state = request.extract_state().await;
+    let generated_at = Utc::now().naive_utc();
+    let response: HttpResponse = events::redeem_offline((
+        database.connection.clone().into(),
+        path,
+        Json(OfflineRedeemRequest {
+            generated_at,
+            salt: "salt".to_string(),
+            upload_token: ScannerSnapshot::upload_token("another-secret", event.id, generated_at, "salt"),
+            scans: vec![OfflineScan {
+                ticket_id: ticket.id,
+                redeem_key: ticket.redeem_key.clone().unwrap(),
+                scanned_at: generated_at,
+                device_id: None,
+                check_in_source: Some(CheckInSource::Scanned),
+                access_zone_id: None,
+            }],
+        }),
+        auth_user,
+        state,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+}
+
+#[actix_rt::test]
+async fn redeem_offline_with_expired_snapshot() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+    let ticket = database.create_purchased_tickets(&user, ticket_type_id, 1).remove(0);
+    let auth_user = support::create_auth_user(Roles::DoorPerson, Some(&organization), &database);
+
+    let request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+    path.id = event.id;
+    let state = request.extract_state().await;
+    let generated_at = Utc::now().naive_utc() - state.config.scanner_snapshot_max_age - Duration::minutes(1);
+    let upload_token =
+        ScannerSnapshot::upload_token(&state.config.scanner_snapshot_secret, event.id, generated_at, "salt");
+    let response: HttpResponse = events::redeem_offline((
+        database.connection.clone().into(),
+        path,
+        Json(OfflineRedeemRequest {
+            generated_at,
+            salt: "salt".to_string(),
+            upload_token,
+            scans: vec![OfflineScan {
+                ticket_id: ticket.id,
+                redeem_key: ticket.redeem_key.clone().unwrap(),
+                scanned_at: generated_at,
+                device_id: None,
+                check_in_source: Some(CheckInSource::Scanned),
+                access_zone_id: None,
+            }],
+        }),
+        auth_user,
+        state,
+        CacheDatabase { inner: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+}
+
 #[actix_rt::test]
 pub async fn delete_fails_has_ticket_in_cart() {
     let database = TestDatabase::new();
@@ -2202,6 +2573,7 @@ pub async fn delete_fails_has_ticket_in_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2280,6 +2652,7 @@ async fn dashboard_with_default_range() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
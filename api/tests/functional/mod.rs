@@ -1,36 +1,48 @@
+mod access_zones;
 mod announcements;
 mod artists;
 mod auth;
 mod base;
 mod broadcast;
+mod bundles;
 mod cart;
 mod codes;
 mod collection_items;
 mod collections;
 mod comps;
 mod event_report_subscribers;
+mod event_series;
 mod events;
 mod genres;
 mod holds;
 mod notes;
+mod oidc;
 mod orders;
+mod organization_api_keys;
 mod organization_invites;
 mod organization_venues;
+mod organization_webhooks;
 mod organizations;
 mod password_resets;
 mod payment_methods;
+mod products;
 mod redemption_codes;
 mod regions;
+mod report_exports;
 mod reports;
 mod reports_admin;
+mod seat_maps;
 mod settlement_adjustments;
 mod settlements;
 mod sitemap;
 mod slugs;
 mod stages;
+mod tax_rates;
 mod ticket_types;
 mod tickets;
 mod transfers;
 mod user_invites;
 mod users;
 mod venues;
+mod waitlist_entries;
+mod webhook_deliveries;
@@ -0,0 +1,303 @@
+use crate::jwt::{encode, Algorithm, Header};
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::auth::default_token_issuer::DefaultTokenIssuer;
+use api::auth::TokenResponse;
+use api::config::OidcProviderConfig;
+use api::controllers::external::oidc;
+use api::extractors::*;
+use api::models::{OidcWebLoginToken, RequestInfo, StringPathParameters};
+use chrono::{Duration, Utc};
+use db::prelude::*;
+use mockito::{mock, Mock};
+use serde_json::Value;
+use uuid::Uuid;
+
+// Keys of the local mock issuer, KEY_2 is not published in its key set
+const KEY_1_PRIVATE: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgy0WSn8yK83fZf093qryOaSw8atvgUuaVtr7Fk9ppUnuhRANCAAQS7uU5VAQHt4CnQqbTulveIsaa/DORAXARvRPjEP87yTNT/2GpfUo1xAg3rEu9j2FF7Vr/L583iDKkayapNq7j";
+const KEY_1_PUBLIC: &str = "BBLu5TlUBAe3gKdCptO6W94ixpr8M5EBcBG9E+MQ/zvJM1P/Yal9SjXECDesS72PYUXtWv8vnzeIMqRrJqk2ruM=";
+const KEY_2_PRIVATE: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgORDAniKRSA/LQA2KUOwTbGZazULQQJNiFnuqBRFh5duhRANCAARZybWm3GrCjc+ZlfCOugGkwWOgoNzTZG1RwXeJMw1ZP7mrgQq/hiEg5zEbe3Tg+UGpndWdSN5n7RypRoOeMMen";
+const ISSUER: &str = "https://issuer.example.com";
+const CLIENT_ID: &str = "bigneon-web";
+
+fn mock_issuer(name: &str) -> (OidcProviderConfig, Mock) {
+    let token_issuer = DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into())
+        .with_signing_keys(
+            format!("key-1:ES256:{}", KEY_1_PRIVATE).parse().unwrap(),
+            vec![format!("key-1:ES256:{}", KEY_1_PUBLIC).parse().unwrap()],
+        )
+        .unwrap();
+    let path = format!("/oidc/{}/certs", name);
+    let jwks = mock("GET", path.as_str())
+        .with_status(200)
+        .with_body(serde_json::to_string(&token_issuer.jwks().unwrap()).unwrap())
+        .create();
+
+    let provider = OidcProviderConfig {
+        name: "google".to_string(),
+        site: GOOGLE_SITE.to_string(),
+        issuers: vec![ISSUER.to_string()],
+        jwks_uri: format!("{}{}", mockito::server_url(), path),
+        client_ids: vec![CLIENT_ID.to_string(), "bigneon-ios".to_string()],
+    };
+    (provider, jwks)
+}
+
+fn id_token(private_key: &str, claims: Value) -> String {
+    let mut header = Header::new(Algorithm::ES256);
+    header.kid = Some("key-1".to_string());
+    encode(&header, &claims, &base64::decode(private_key).unwrap()).unwrap()
+}
+
+fn claims(sub: &str, email: &str, email_verified: bool) -> Value {
+    json!({
+        "iss": ISSUER,
+        "sub": sub,
+        "aud": CLIENT_ID,
+        "exp": (Utc::now() + Duration::minutes(5)).timestamp(),
+        "email": email,
+        "email_verified": email_verified,
+        "given_name": "Jane",
+        "family_name": "Doe",
+    })
+}
+
+async fn web_login(
+    database: &TestDatabase,
+    provider: OidcProviderConfig,
+    login_token: OidcWebLoginToken,
+    auth_user: OptionalUser,
+) -> HttpResponse {
+    let mut config = TestRequest::config();
+    config.oidc_providers = vec![provider];
+    let test_request = TestRequest::create_with_config("/", vec!["id"], config);
+    let state = test_request.extract_state().await;
+    let mut path = Path::<StringPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = "google".to_string();
+
+    oidc::web_login((
+        state,
+        database.connection.clone().into(),
+        path,
+        Json(login_token),
+        auth_user,
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into()
+}
+
+fn logged_in_user_id(response: &HttpResponse, database: &TestDatabase) -> Uuid {
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let access_token = TestRequest::config()
+        .token_issuer
+        .decode(&response.access_token)
+        .unwrap();
+    let user = User::find(access_token.claims.get_id().unwrap(), database.connection.get()).unwrap();
+    user.id
+}
+
+#[actix_rt::test]
+async fn web_login_creates_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (provider, _jwks) = mock_issuer("creates_user");
+    let login_token = OidcWebLoginToken {
+        id_token: id_token(KEY_1_PRIVATE, claims("google-1", "jane@example.com", true)),
+        ..Default::default()
+    };
+
+    let response = web_login(&database, provider, login_token, OptionalUser(None)).await;
+    let user = User::find(logged_in_user_id(&response, &database), connection).unwrap();
+    assert_eq!(user.email, Some("jane@example.com".to_string()));
+    assert_eq!(user.first_name, Some("Jane".to_string()));
+    assert_eq!(user.last_name, Some("Doe".to_string()));
+    let external_login = ExternalLogin::find_user("google-1", GOOGLE_SITE, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(external_login.user_id, user.id);
+}
+
+#[actix_rt::test]
+async fn web_login_existing_external_login() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    user.add_external_login(
+        None,
+        "google-1".to_string(),
+        GOOGLE_SITE.to_string(),
+        String::new(),
+        vec![],
+        database.connection.get(),
+    )
+    .unwrap();
+    let (provider, _jwks) = mock_issuer("existing_external_login");
+    let login_token = OidcWebLoginToken {
+        id_token: id_token(KEY_1_PRIVATE, claims("google-1", "other@example.com", true)),
+        ..Default::default()
+    };
+
+    let response = web_login(&database, provider, login_token, OptionalUser(None)).await;
+    assert_eq!(logged_in_user_id(&response, &database), user.id);
+}
+
+#[actix_rt::test]
+async fn web_login_links_user_with_verified_email() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+    let (provider, _jwks) = mock_issuer("links_verified_email");
+    let login_token = OidcWebLoginToken {
+        id_token: id_token(KEY_1_PRIVATE, claims("google-1", "Jane@Example.com", true)),
+        ..Default::default()
+    };
+
+    let response = web_login(&database, provider, login_token, OptionalUser(None)).await;
+    assert_eq!(logged_in_user_id(&response, &database), user.id);
+    let external_login = ExternalLogin::find_user("google-1", GOOGLE_SITE, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(external_login.user_id, user.id);
+}
+
+#[actix_rt::test]
+async fn web_login_does_not_link_unverified_email() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+    let (provider, _jwks) = mock_issuer("unverified_email");
+    let login_token = OidcWebLoginToken {
+        id_token: id_token(KEY_1_PRIVATE, claims("google-1", "jane@example.com", false)),
+        ..Default::default()
+    };
+
+    let response = web_login(&database, provider, login_token, OptionalUser(None)).await;
+    let new_user = User::find(logged_in_user_id(&response, &database), connection).unwrap();
+    assert_ne!(new_user.id, user.id);
+    assert_eq!(new_user.email, None);
+    assert!(ExternalLogin::find_for_site(user.id, GOOGLE_SITE, connection)
+        .optional()
+        .unwrap()
+        .is_none());
+}
+
+#[actix_rt::test]
+async fn web_login_links_to_logged_in_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+    let (provider, _jwks) = mock_issuer("links_logged_in_user");
+    let login_token = OidcWebLoginToken {
+        id_token: id_token(KEY_1_PRIVATE, claims("google-1", "jane@example.com", true)),
+        link_to_user_id: true,
+        ..Default::default()
+    };
+
+    let response = web_login(&database, provider, login_token, OptionalUser(Some(auth_user.clone()))).await;
+    assert_eq!(logged_in_user_id(&response, &database), auth_user.id());
+    let external_login = ExternalLogin::find_user("google-1", GOOGLE_SITE, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(external_login.user_id, auth_user.id());
+}
+
+#[actix_rt::test]
+async fn web_login_rejects_invalid_tokens() {
+    let database = TestDatabase::new();
+    let (provider, _jwks) = mock_issuer("invalid_tokens");
+
+    let mut wrong_audience = claims("google-1", "jane@example.com", true);
+    wrong_audience["aud"] = json!("someone-else");
+    let mut wrong_issuer = claims("google-1", "jane@example.com", true);
+    wrong_issuer["iss"] = json!("https://attacker.example.com");
+    let mut expired = claims("google-1", "jane@example.com", true);
+    expired["exp"] = json!((Utc::now() - Duration::minutes(5)).timestamp());
+
+    for id_token in vec![
+        id_token(KEY_1_PRIVATE, wrong_audience),
+        id_token(KEY_1_PRIVATE, wrong_issuer),
+        id_token(KEY_1_PRIVATE, expired),
+        // Signed with a key the issuer does not publish
+        id_token(KEY_2_PRIVATE, claims("google-1", "jane@example.com", true)),
+        "not-a-token".to_string(),
+    ] {
+        let login_token = OidcWebLoginToken {
+            id_token,
+            ..Default::default()
+        };
+        let response = web_login(&database, provider.clone(), login_token, OptionalUser(None)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    assert!(
+        ExternalLogin::find_user("google-1", GOOGLE_SITE, database.connection.get())
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[actix_rt::test]
+async fn web_login_accepts_any_configured_audience() {
+    let database = TestDatabase::new();
+    let (provider, _jwks) = mock_issuer("any_audience");
+    let mut claims = claims("google-1", "jane@example.com", true);
+    claims["aud"] = json!(["bigneon-ios"]);
+    let login_token = OidcWebLoginToken {
+        id_token: id_token(KEY_1_PRIVATE, claims),
+        ..Default::default()
+    };
+
+    let response = web_login(&database, provider, login_token, OptionalUser(None)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_rt::test]
+async fn web_login_caches_provider_keys() {
+    let database = TestDatabase::new();
+    let (provider, jwks) = mock_issuer("cached_keys");
+    let jwks = jwks.expect(1);
+
+    for sub in vec!["google-1", "google-2"] {
+        let login_token = OidcWebLoginToken {
+            id_token: id_token(KEY_1_PRIVATE, claims(sub, &format!("{}@example.com", sub), true)),
+            ..Default::default()
+        };
+        let response = web_login(&database, provider.clone(), login_token, OptionalUser(None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    jwks.assert();
+}
+
+#[actix_rt::test]
+async fn web_login_unknown_provider() {
+    let database = TestDatabase::new();
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+    let state = test_request.extract_state().await;
+    let mut path = Path::<StringPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = "myspace".to_string();
+
+    let response: HttpResponse = oidc::web_login((
+        state,
+        database.connection.clone().into(),
+        path,
+        Json(OidcWebLoginToken::default()),
+        OptionalUser(None),
+        RequestInfo { user_agent: None },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
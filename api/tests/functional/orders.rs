@@ -18,6 +18,7 @@ use crate::support::test_request::TestRequest;
 use api::controllers::orders::{self, *};
 use api::extractors::Json;
 use api::models::PathParameters;
+use db::dev::TestProject;
 use db::models::*;
 use db::schema;
 
@@ -516,6 +517,7 @@ pub async fn refund_for_non_refundable_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -549,6 +551,7 @@ pub async fn refund_for_non_refundable_tickets() {
         "example@tari.com",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -624,6 +627,7 @@ pub async fn refund_hold_ticket() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -696,6 +700,7 @@ pub async fn refund_hold_ticket() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -0,0 +1,272 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::{organization_api_keys, organization_webhooks};
+use api::extractors::*;
+use api::models::*;
+use chrono::{Duration, Utc};
+use db::models::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::organization_api_keys::index(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::organization_api_keys::index(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_super() {
+        base::organization_api_keys::index(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_user() {
+        base::organization_api_keys::index(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::organization_api_keys::index(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::organization_api_keys::index(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::organization_api_keys::index(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::organization_api_keys::create(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::organization_api_keys::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_super() {
+        base::organization_api_keys::create(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::organization_api_keys::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::organization_api_keys::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::organization_api_keys::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::organization_api_keys::create(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn destroy_org_member() {
+        base::organization_api_keys::destroy(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_admin() {
+        base::organization_api_keys::destroy(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_super() {
+        base::organization_api_keys::destroy(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_user() {
+        base::organization_api_keys::destroy(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_org_owner() {
+        base::organization_api_keys::destroy(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_org_admin() {
+        base::organization_api_keys::destroy(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_door_person() {
+        base::organization_api_keys::destroy(Roles::DoorPerson, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn create_with_scope_user_does_not_have() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgAdmin, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(organization_api_keys::NewOrganizationApiKeyRequest {
+        name: "Integration".to_string(),
+        scopes: vec![Scopes::OrgAdmin.to_string()],
+        event_ids: vec![],
+        expires_at: None,
+    });
+    let response: HttpResponse =
+        organization_api_keys::create((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(
+        OrganizationApiKey::find_for_organization(organization.id, database.connection.get())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[actix_rt::test]
+async fn create_with_unknown_scope() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(organization_api_keys::NewOrganizationApiKeyRequest {
+        name: "Integration".to_string(),
+        scopes: vec!["not:a-scope".to_string()],
+        event_ids: vec![],
+        expires_at: None,
+    });
+    let response: HttpResponse =
+        organization_api_keys::create((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_rt::test]
+async fn create_with_api_key() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let creator = database.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        creator.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead, Scopes::OrgWrite],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(database.connection.get()).unwrap();
+    let auth_user = support::create_auth_user_from_api_key(&api_key, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(organization_api_keys::NewOrganizationApiKeyRequest {
+        name: "Copy".to_string(),
+        scopes: vec![Scopes::OrgRead.to_string()],
+        event_ids: vec![],
+        expires_at: None,
+    });
+    let response: HttpResponse =
+        organization_api_keys::create((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_rt::test]
+async fn api_key_scopes() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let admin = database
+        .create_user()
+        .finish()
+        .add_role(Roles::Admin, connection)
+        .unwrap();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        admin.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgWrite],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    let auth_user = support::create_auth_user_from_api_key(&api_key, &database);
+
+    // Only the key's scopes apply, not those of the admin who created it
+    assert!(!auth_user.user.is_admin());
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)
+        .is_ok());
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::OrgRead, &organization, connection)
+        .is_err());
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::OrgWrite, &other_organization, connection)
+        .is_err());
+    assert!(auth_user.requires_scope(Scopes::OrgWrite).is_err());
+
+    // Keys pass the same checks controllers already make
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        organization_webhooks::index((database.connection.clone().into(), path, auth_user.clone()))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let api_key = api_key.revoke(None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_api_key(&api_key, &database);
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)
+        .is_err());
+}
+
+#[actix_rt::test]
+async fn api_key_scopes_limited_to_events() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let other_event = database.create_event().with_organization(&organization).finish();
+    let creator = database.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        creator.id,
+        "Scanner".to_string(),
+        vec![Scopes::RedeemTicket],
+        vec![event.id],
+        Some(Utc::now().naive_utc() + Duration::days(1)),
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    let auth_user = support::create_auth_user_from_api_key(&api_key, &database);
+
+    assert!(auth_user
+        .requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)
+        .is_ok());
+    assert!(auth_user
+        .requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &other_event, connection)
+        .is_err());
+    assert!(auth_user
+        .requires_scope_for_organization(Scopes::RedeemTicket, &organization, connection)
+        .is_err());
+}
@@ -0,0 +1,233 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::organization_webhooks::index(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::organization_webhooks::index(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_super() {
+        base::organization_webhooks::index(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_user() {
+        base::organization_webhooks::index(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::organization_webhooks::index(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::organization_webhooks::index(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::organization_webhooks::index(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::organization_webhooks::create(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::organization_webhooks::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_super() {
+        base::organization_webhooks::create(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::organization_webhooks::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::organization_webhooks::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::organization_webhooks::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::organization_webhooks::create(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_org_member() {
+        base::organization_webhooks::update(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_admin() {
+        base::organization_webhooks::update(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_super() {
+        base::organization_webhooks::update(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_user() {
+        base::organization_webhooks::update(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_owner() {
+        base::organization_webhooks::update(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_admin() {
+        base::organization_webhooks::update(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_door_person() {
+        base::organization_webhooks::update(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod pause_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn pause_org_member() {
+        base::organization_webhooks::pause(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn pause_admin() {
+        base::organization_webhooks::pause(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn pause_super() {
+        base::organization_webhooks::pause(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn pause_user() {
+        base::organization_webhooks::pause(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn pause_org_owner() {
+        base::organization_webhooks::pause(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn pause_org_admin() {
+        base::organization_webhooks::pause(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn pause_door_person() {
+        base::organization_webhooks::pause(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod resume_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn resume_org_member() {
+        base::organization_webhooks::resume(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn resume_admin() {
+        base::organization_webhooks::resume(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn resume_super() {
+        base::organization_webhooks::resume(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn resume_user() {
+        base::organization_webhooks::resume(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn resume_org_owner() {
+        base::organization_webhooks::resume(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn resume_org_admin() {
+        base::organization_webhooks::resume(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn resume_door_person() {
+        base::organization_webhooks::resume(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn destroy_org_member() {
+        base::organization_webhooks::destroy(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_admin() {
+        base::organization_webhooks::destroy(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_super() {
+        base::organization_webhooks::destroy(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_user() {
+        base::organization_webhooks::destroy(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_org_owner() {
+        base::organization_webhooks::destroy(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_org_admin() {
+        base::organization_webhooks::destroy(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_door_person() {
+        base::organization_webhooks::destroy(Roles::DoorPerson, false).await;
+    }
+}
+
+#[cfg(test)]
+mod test_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn test_org_member() {
+        base::organization_webhooks::test(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn test_admin() {
+        base::organization_webhooks::test(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn test_super() {
+        base::organization_webhooks::test(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn test_user() {
+        base::organization_webhooks::test(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn test_org_owner() {
+        base::organization_webhooks::test(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn test_org_admin() {
+        base::organization_webhooks::test(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn test_door_person() {
+        base::organization_webhooks::test(Roles::DoorPerson, false).await;
+    }
+}
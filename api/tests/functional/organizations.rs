@@ -415,6 +415,47 @@ mod update_tests_with_max_tickets_per_ticket_type {
     }
 }
 
+#[cfg(test)]
+mod update_tests_with_stripe_connect_account_id {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_org_member() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_admin() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_user() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_owner() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_door_person() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_promoter() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_promoter_read_only() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_admin() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_box_office() {
+        organizations::update_restricted_field("stripe_connect_account_id", Roles::OrgBoxOffice, false).await;
+    }
+}
+
 #[cfg(test)]
 mod list_organization_members_tests {
     use super::*;
@@ -7,6 +7,7 @@ use api::auth::TokenResponse;
 use api::controllers::password_resets::{self, CreatePasswordResetParameters, UpdatePasswordResetParameters};
 use api::database::Connection as ApiConnection;
 use api::extractors::*;
+use api::models::RequestInfo;
 use chrono::{Duration, Utc};
 use db::models::concerns::users::password_resetable::*;
 use db::models::TokenIssuer;
@@ -82,7 +83,10 @@ async fn update() {
 
     let token_issuer = state.config.token_issuer.clone();
 
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).await.into();
+    let response: HttpResponse =
+        password_resets::update((state, connection_object, json, RequestInfo { user_agent: None }))
+            .await
+            .into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert!(user.password_reset_token.is_none());
@@ -126,7 +130,10 @@ async fn update_expired_token() {
         password_reset_token: token,
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).await.into();
+    let response: HttpResponse =
+        password_resets::update((state, connection_object, json, RequestInfo { user_agent: None }))
+            .await
+            .into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -152,7 +159,10 @@ async fn update_incorrect_token() {
         password_reset_token: Uuid::new_v4(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).await.into();
+    let response: HttpResponse =
+        password_resets::update((state, connection_object, json, RequestInfo { user_agent: None }))
+            .await
+            .into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -0,0 +1,84 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::products::create(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::products::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::products::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::products::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::products::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::products::create(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::products::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::products::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::products::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod redeem_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn redeem_org_member() {
+        base::products::redeem(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_admin() {
+        base::products::redeem(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_user() {
+        base::products::redeem(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_org_owner() {
+        base::products::redeem(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_door_person() {
+        base::products::redeem(Roles::DoorPerson, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_promoter() {
+        base::products::redeem(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_promoter_read_only() {
+        base::products::redeem(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_org_admin() {
+        base::products::redeem(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_box_office() {
+        base::products::redeem(Roles::OrgBoxOffice, true).await;
+    }
+}
@@ -0,0 +1,62 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::report_exports::{self, ReportExportDownloadParameters};
+use api::models::PathParameters;
+use db::prelude::*;
+
+async fn download(report_export: &ReportExport, token: &str, database: &TestDatabase) -> HttpResponse {
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/report_exports/{}/download?token={}",
+        report_export.id, token
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = report_export.id;
+    let query = Query::<ReportExportDownloadParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    report_exports::download((database.connection.clone().into(), path, query))
+        .await
+        .into()
+}
+
+#[actix_rt::test]
+async fn download_report_export() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "transaction_details".to_string(),
+        ReportExportFormat::Csv,
+        json!({ "report": "transaction_details" }),
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Pending exports cannot be downloaded
+    let response = download(&report_export, "token", &database).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    report_export.set_content(b"Event Name\nEvent1\n", connection).unwrap();
+    let (report_export, download_token) = report_export.mark_completed(1, connection).unwrap();
+    let mut response = download(&report_export, &download_token, &database).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/csv");
+    assert_eq!(
+        response.headers().get("Content-Disposition").unwrap().to_str().unwrap(),
+        format!("attachment; filename=\"{}\"", report_export.file_name())
+    );
+    let body = support::read_streamed_body(&mut response).await;
+    assert_eq!(body, b"Event Name\nEvent1\n".to_vec());
+
+    let response = download(&report_export, "wrong-token", &database).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -1,5 +1,15 @@
 use crate::functional::base;
+use crate::support;
 use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::auth::user::User as AuthUser;
+use api::controllers::reports::{self, ReportQueryParameters};
+use api::models::PathParameters;
 use chrono::prelude::*;
 use db::dev::HoldBuilder;
 use db::prelude::*;
@@ -31,6 +41,7 @@ pub async fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         true,
         false,
@@ -98,6 +109,7 @@ pub async fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -193,6 +205,7 @@ pub async fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code,
+            seat_ids: None,
         }],
         false,
         false,
@@ -287,6 +300,7 @@ pub async fn ticket_counts_report() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code,
+            seat_ids: None,
         }],
         false,
         false,
@@ -557,3 +571,166 @@ mod transaction_detail_report_with_event_tests {
         base::reports::transaction_detail_report(Roles::OrgBoxOffice, false, true).await;
     }
 }
+
+async fn export_report(
+    test_request: TestRequest,
+    organization: &Organization,
+    auth_user: AuthUser,
+    database: &TestDatabase,
+) -> HttpResponse {
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let query = Query::<ReportQueryParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    reports::get_report((
+        test_request.extract_state().await,
+        database.connection.clone().into(),
+        query,
+        path,
+        auth_user,
+    ))
+    .await
+    .into()
+}
+
+#[actix_rt::test]
+async fn export_report_csv() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_fees().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_name("Event1".to_string())
+        .with_ticket_pricing()
+        .finish();
+    database
+        .create_order()
+        .quantity(2)
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri("/reports?report=transaction_details&format=csv");
+    let mut response = export_report(test_request, &organization, auth_user, &database).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/csv");
+    assert!(response
+        .headers()
+        .get("Content-Disposition")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("attachment; filename=\"transaction_details_"));
+    let body = String::from_utf8(support::read_streamed_body(&mut response).await).unwrap();
+    let lines: Vec<&str> = body.lines().collect();
+    assert!(lines[0].starts_with("Event Name,"));
+    assert!(lines.len() > 1);
+    assert!(body.contains("Event1"));
+}
+
+#[actix_rt::test]
+async fn export_report_xlsx() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().with_fees().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/reports?report=ticket_count&format=xlsx&event_id={}",
+        event.id
+    ));
+    let mut response = export_report(test_request, &organization, auth_user, &database).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // XLSX files are zip archives
+    assert!(support::read_streamed_body(&mut response).await.starts_with(b"PK"));
+}
+
+#[actix_rt::test]
+async fn export_report_over_row_limit() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_fees().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let mut config = TestRequest::config();
+    config.report_export_inline_row_limit = 0;
+    let test_request =
+        TestRequest::create_with_config("/reports?report=transaction_details&format=xlsx", vec!["id"], config);
+    let response = export_report(test_request, &organization, auth_user.clone(), &database).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let report_export: ReportExport = serde_json::from_str(&body).unwrap();
+    let report_export = ReportExport::find(report_export.id, connection).unwrap();
+    assert_eq!(report_export.status, ReportExportStatus::Pending);
+    assert_eq!(report_export.format, ReportExportFormat::Xlsx);
+    assert_eq!(report_export.user_id, auth_user.id());
+    assert_eq!(report_export.organization_id, organization.id);
+    assert!(!DomainAction::find_by_resource(
+        Some(Tables::ReportExports),
+        Some(report_export.id),
+        DomainActionTypes::ProcessReportExport,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap()
+    .is_empty());
+}
+
+#[actix_rt::test]
+async fn export_report_invalid_format() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri("/reports?report=transaction_details&format=pdf");
+    let response = export_report(test_request, &organization, auth_user, &database).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_rt::test]
+async fn export_report_requires_access() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let other_event = database.create_event().finish();
+
+    // Events of other organizations are not found
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/reports?report=transaction_details&format=csv&event_id={}",
+        other_event.id
+    ));
+    let response = export_report(test_request, &organization, auth_user, &database).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Box office users cannot export financial reports
+    let auth_user = support::create_auth_user(Roles::OrgBoxOffice, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri("/reports?report=reconciliation_summary&format=csv");
+    let response = export_report(test_request, &organization, auth_user, &database).await;
+    support::expects_unauthorized(&response);
+
+    // Per event reports need an event
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let test_request = TestRequest::create_with_uri("/reports?report=event_summary&format=csv");
+    let response = export_report(test_request, &organization, auth_user, &database).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
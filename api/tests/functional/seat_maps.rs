@@ -0,0 +1,84 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::seat_maps::create(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::seat_maps::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::seat_maps::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::seat_maps::create(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::seat_maps::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::seat_maps::create(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::seat_maps::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::seat_maps::create(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::seat_maps::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_event_seating_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_event_seating_org_member() {
+        base::seat_maps::update_event_seating(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_admin() {
+        base::seat_maps::update_event_seating(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_user() {
+        base::seat_maps::update_event_seating(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_org_owner() {
+        base::seat_maps::update_event_seating(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_door_person() {
+        base::seat_maps::update_event_seating(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_promoter() {
+        base::seat_maps::update_event_seating(Roles::Promoter, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_promoter_read_only() {
+        base::seat_maps::update_event_seating(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_org_admin() {
+        base::seat_maps::update_event_seating(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_event_seating_box_office() {
+        base::seat_maps::update_event_seating(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -0,0 +1,166 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::tax_rates::index(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::tax_rates::index(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_user() {
+        base::tax_rates::index(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::tax_rates::index(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::tax_rates::index(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_promoter() {
+        base::tax_rates::index(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_promoter_read_only() {
+        base::tax_rates::index(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::tax_rates::index(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_box_office() {
+        base::tax_rates::index(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::tax_rates::create(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::tax_rates::create(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_user() {
+        base::tax_rates::create(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::tax_rates::create(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::tax_rates::create(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::tax_rates::create(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::tax_rates::create(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::tax_rates::create(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::tax_rates::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_org_member() {
+        base::tax_rates::update(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_admin() {
+        base::tax_rates::update(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_user() {
+        base::tax_rates::update(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_owner() {
+        base::tax_rates::update(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_door_person() {
+        base::tax_rates::update(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_promoter() {
+        base::tax_rates::update(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_promoter_read_only() {
+        base::tax_rates::update(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_admin() {
+        base::tax_rates::update(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_box_office() {
+        base::tax_rates::update(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn destroy_org_member() {
+        base::tax_rates::destroy(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_admin() {
+        base::tax_rates::destroy(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_user() {
+        base::tax_rates::destroy(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_org_owner() {
+        base::tax_rates::destroy(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_door_person() {
+        base::tax_rates::destroy(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_promoter() {
+        base::tax_rates::destroy(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_promoter_read_only() {
+        base::tax_rates::destroy(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_org_admin() {
+        base::tax_rates::destroy(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn destroy_box_office() {
+        base::tax_rates::destroy(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -802,11 +802,13 @@ pub async fn cancel_with_sold_tickets_and_hold() {
                 ticket_type_id: created_ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: created_ticket_type.id,
                 quantity: 5,
                 redemption_code: hold.redemption_code,
+                seat_ids: None,
             },
         ],
         false,
@@ -15,6 +15,7 @@ use api::controllers::tickets::SendTicketsRequest;
 use api::controllers::tickets::{self, SearchParameters, ShowTicketResponse, TransferTicketRequest};
 use api::extractors::*;
 use api::models::{OptionalPathParameters, PathParameters};
+use db::dev::TestProject;
 use db::prelude::*;
 
 #[actix_rt::test]
@@ -57,6 +58,7 @@ pub async fn index() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -81,6 +83,7 @@ pub async fn index() {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -140,6 +143,10 @@ pub async fn index() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        redeem_secret: None,
+        seat_section: None,
+        seat_row: None,
+        seat_number: None,
     };
     assert_eq!(vec![expected_ticket.clone()], found_data.data);
     // Test without specified event
@@ -171,6 +178,10 @@ pub async fn index() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        redeem_secret: None,
+        seat_section: None,
+        seat_row: None,
+        seat_number: None,
     };
     assert_eq!(
         vec![
@@ -253,6 +264,7 @@ pub async fn show() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -294,6 +306,10 @@ pub async fn show() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        redeem_secret: None,
+        seat_section: None,
+        seat_row: None,
+        seat_number: None,
     };
 
     let expected_result = ShowTicketResponse {
@@ -468,6 +484,8 @@ mod show_redeem_key {
 #[actix_rt::test]
 async fn ticket_transfer_authorization() {
     let database = TestDatabase::new();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
     let user = database.create_user().finish();
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
     let organization = database.create_organization().finish();
@@ -492,6 +510,7 @@ async fn ticket_transfer_authorization() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -506,6 +525,7 @@ async fn ticket_transfer_authorization() {
     };
 
     let response = tickets::transfer_authorization((
+        state.clone(),
         database.connection.clone().into(),
         Json(ticket_transfer_request.clone()),
         auth_user.clone(),
@@ -526,6 +546,7 @@ async fn ticket_transfer_authorization() {
     .unwrap();
 
     let response = tickets::transfer_authorization((
+        state.clone(),
         database.connection.clone().into(),
         Json(ticket_transfer_request.clone()),
         auth_user.clone(),
@@ -542,6 +563,7 @@ async fn ticket_transfer_authorization() {
     //Now lets try add a ticket that the user doesn't own.
     ticket_transfer_request.ticket_ids.push(Uuid::new_v4());
     let response = tickets::transfer_authorization((
+        state.clone(),
         database.connection.clone().into(),
         Json(ticket_transfer_request),
         auth_user.clone(),
@@ -650,6 +672,7 @@ async fn receive_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -677,7 +700,7 @@ async fn receive_ticket_transfer() {
         conn,
     )
     .unwrap()
-    .into_authorization(conn)
+    .into_authorization(&TestProject::wallet_encryption_keys(), conn)
     .unwrap();
 
     //Try receive transfer
@@ -724,6 +747,7 @@ async fn receive_ticket_transfer_fails_cancelled_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -751,7 +775,11 @@ async fn receive_ticket_transfer_fails_cancelled_transfer() {
 
     let response: HttpResponse = tickets::receive_transfer((
         database.connection.clone().into(),
-        Json(transfer.into_authorization(conn).unwrap()),
+        Json(
+            transfer
+                .into_authorization(&TestProject::wallet_encryption_keys(), conn)
+                .unwrap(),
+        ),
         auth_user2.clone(),
         request.extract_state().await,
     ))
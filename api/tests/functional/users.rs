@@ -2,11 +2,11 @@ use crate::functional::base;
 use crate::support;
 use crate::support::database::TestDatabase;
 use crate::support::test_request::TestRequest;
-use actix_web::{http::StatusCode, HttpResponse};
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
 use api::auth::TokenResponse;
 use api::controllers::users;
 use api::extractors::*;
-use api::models::{RegisterRequest, RequestInfo, UserProfileAttributes};
+use api::models::{PathParameters, RegisterRequest, RequestInfo, UserProfileAttributes};
 use db::prelude::*;
 use serde_json;
 use std::collections::HashMap;
@@ -382,6 +382,47 @@ mod users_show_tests {
     }
 }
 
+#[cfg(test)]
+mod revoke_sessions_for_user_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_org_member() {
+        base::users::revoke_sessions_for_user(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_admin() {
+        base::users::revoke_sessions_for_user(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_user() {
+        base::users::revoke_sessions_for_user(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_org_owner() {
+        base::users::revoke_sessions_for_user(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_door_person() {
+        base::users::revoke_sessions_for_user(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_promoter() {
+        base::users::revoke_sessions_for_user(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_promoter_read_only() {
+        base::users::revoke_sessions_for_user(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_org_admin() {
+        base::users::revoke_sessions_for_user(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn revoke_sessions_for_user_box_office() {
+        base::users::revoke_sessions_for_user(Roles::OrgBoxOffice, false).await;
+    }
+}
+
 #[actix_rt::test]
 async fn register_address_exists() {
     let database = TestDatabase::new();
@@ -769,3 +810,65 @@ async fn update_current_user_address_exists() {
         "Email is already in use"
     );
 }
+
+#[actix_rt::test]
+async fn sessions() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let session = UserSession::create(user.id, Some("Test Agent".to_string()))
+        .commit(connection)
+        .unwrap();
+    let revoked_session = UserSession::create(user.id, None).commit(connection).unwrap();
+    revoked_session.revoke(connection).unwrap();
+    UserSession::create(user2.id, None).commit(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse = users::sessions((database.connection.clone().into(), auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let sessions: Vec<UserSession> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, session.id);
+    assert_eq!(sessions[0].user_agent, Some("Test Agent".to_string()));
+}
+
+#[actix_rt::test]
+async fn revoke_session() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = session.id;
+    let response: HttpResponse = users::revoke_session((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+}
+
+#[actix_rt::test]
+async fn revoke_session_for_other_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let session = UserSession::create(user2.id, None).commit(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = session.id;
+    let response: HttpResponse = users::revoke_session((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    support::expects_unauthorized(&response);
+    assert!(UserSession::find(session.id, connection).unwrap().is_active());
+}
@@ -0,0 +1,119 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::waitlist_entries::{self, CreateWaitlistEntryRequest};
+use api::extractors::*;
+use api::models::EventTicketPathParameters;
+use db::prelude::*;
+
+fn sold_out_event(database: &TestDatabase) -> (Event, TicketType) {
+    let event = database
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, database.connection.get())
+        .unwrap()
+        .remove(0);
+    database.create_order().for_event(&event).quantity(1).is_paid().finish();
+    (event, ticket_type)
+}
+
+async fn path(event: &Event, ticket_type: &TicketType) -> Path<EventTicketPathParameters> {
+    let test_request = TestRequest::create();
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    path
+}
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let (event, ticket_type) = sold_out_event(&database);
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse = waitlist_entries::create((
+        database.connection.clone().into(),
+        path(&event, &ticket_type).await,
+        Json(CreateWaitlistEntryRequest { quantity: 1 }),
+        auth_user,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let waitlist_entry: WaitlistEntry = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, user.id);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+}
+
+#[actix_rt::test]
+async fn create_when_tickets_are_available() {
+    let database = TestDatabase::new();
+    let event = database.create_event().with_ticket_pricing().finish();
+    let ticket_type = event
+        .ticket_types(true, None, database.connection.get())
+        .unwrap()
+        .remove(0);
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let response: HttpResponse = waitlist_entries::create((
+        database.connection.clone().into(),
+        path(&event, &ticket_type).await,
+        Json(CreateWaitlistEntryRequest { quantity: 1 }),
+        auth_user,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation_response = support::validation_response_from_response(&response).unwrap();
+    assert!(validation_response.fields.get("ticket_type_id").is_some());
+}
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (event, ticket_type) = sold_out_event(&database);
+    let user = database.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = waitlist_entries::show((
+        database.connection.clone().into(),
+        path(&event, &ticket_type).await,
+        auth_user.clone(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response: HttpResponse = waitlist_entries::destroy((
+        database.connection.clone().into(),
+        path(&event, &ticket_type).await,
+        auth_user.clone(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let waitlist_entry: WaitlistEntry = support::unwrap_body_to_object(&response).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+
+    // No longer on the waitlist
+    let response: HttpResponse = waitlist_entries::show((
+        database.connection.clone().into(),
+        path(&event, &ticket_type).await,
+        auth_user,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
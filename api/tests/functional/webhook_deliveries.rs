@@ -0,0 +1,90 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::webhook_deliveries;
+use api::models::WebhookDeliveryPathParameters;
+use db::models::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::webhook_deliveries::index(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::webhook_deliveries::index(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_super() {
+        base::webhook_deliveries::index(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_user() {
+        base::webhook_deliveries::index(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::webhook_deliveries::index(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::webhook_deliveries::index(Roles::OrgAdmin, false).await;
+    }
+}
+
+#[cfg(test)]
+mod replay_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn replay_org_member() {
+        base::webhook_deliveries::replay(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn replay_admin() {
+        base::webhook_deliveries::replay(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn replay_super() {
+        base::webhook_deliveries::replay(Roles::Super, true).await;
+    }
+    #[actix_rt::test]
+    async fn replay_user() {
+        base::webhook_deliveries::replay(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn replay_org_owner() {
+        base::webhook_deliveries::replay(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn replay_org_admin() {
+        base::webhook_deliveries::replay(Roles::OrgAdmin, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn replay_for_other_domain_event_publisher() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let domain_event_publisher = database.create_domain_event_publisher().finish();
+    let domain_event_publisher2 = database.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher2.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "webhook_delivery_id"]);
+    let mut path = Path::<WebhookDeliveryPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = domain_event_publisher.id;
+    path.webhook_delivery_id = webhook_delivery.id;
+    let response: HttpResponse = webhook_deliveries::replay((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -22,9 +22,12 @@ impl TestDatabase {
 
         connection.begin_test_transaction().unwrap();
 
-        TestDatabase {
-            connection: connection.into(),
-        }
+        // Handlers run within a request transaction which they may commit part way through,
+        // nesting it keeps those commits inside the test transaction
+        let connection: DbConnection = connection.into();
+        connection.begin_transaction().unwrap();
+
+        TestDatabase { connection }
     }
 
     pub fn create_organization_with_user(&self, user: &User, owner: bool) -> OrganizationBuilder {
@@ -144,6 +147,14 @@ impl TestDatabase {
         SettlementBuilder::new(self.connection.get())
     }
 
+    pub fn create_tax_rate(&self) -> TaxRateBuilder {
+        TaxRateBuilder::new(self.connection.get())
+    }
+
+    pub fn create_access_zone(&self) -> AccessZoneBuilder {
+        AccessZoneBuilder::new(self.connection.get())
+    }
+
     pub fn create_fee_schedule(&self) -> FeeScheduleBuilder {
         FeeScheduleBuilder::new(self.connection.get())
     }
@@ -156,6 +167,7 @@ impl TestDatabase {
                 ticket_type_id,
                 quantity,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -177,7 +189,7 @@ impl TestDatabase {
 }
 
 pub fn connection() -> PgConnection {
-    let config = Config::new(Environment::Test);
+    let config = Config::new(Environment::Test).unwrap();
 
     PgConnection::establish(&config.database_url).unwrap_or_else(|e| {
         panic!(
@@ -9,7 +9,8 @@ use actix_web::{
     HttpResponse,
 };
 use api::auth::user::User as AuthUser;
-use db::models::{Organization, Roles, User};
+use db::models::{Organization, OrganizationApiKey, Roles, User};
+use futures::StreamExt;
 use serde::Deserialize;
 use serde_json;
 use std::collections::HashMap;
@@ -30,6 +31,16 @@ pub fn unwrap_body_to_string(response: &HttpResponse) -> Result<&str, &'static s
     }
 }
 
+/// Reads the chunks of a streamed body
+pub async fn read_streamed_body(response: &mut HttpResponse) -> Vec<u8> {
+    let mut body = response.take_body();
+    let mut content = Vec::new();
+    while let Some(chunk) = body.next().await {
+        content.extend_from_slice(&chunk.unwrap());
+    }
+    content
+}
+
 pub fn unwrap_body_to_object<'a, T>(response: &'a HttpResponse) -> Result<T, &'static str>
 where
     T: Deserialize<'a>,
@@ -101,3 +112,9 @@ pub fn expects_forbidden(response: &HttpResponse, message: Option<&str>) {
     let body = unwrap_body_to_string(&response).unwrap();
     assert_eq!(body, expected_text);
 }
+
+pub fn create_auth_user_from_api_key(api_key: &OrganizationApiKey, database: &TestDatabase) -> AuthUser {
+    let test_request = TestRequest::create();
+    let user = User::find(api_key.created_by_user_id, database.connection.get()).unwrap();
+    AuthUser::new_for_api_key(api_key.clone(), user, &test_request.request)
+}
@@ -8,6 +8,7 @@ use api::config::Config;
 use api::database::Database;
 use api::server::AppState;
 use api::utils::spotify;
+use db::dev::TestProject;
 use db::models::Environment;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
@@ -33,10 +34,21 @@ impl TestRequest {
     }
 
     pub fn create_with_uri_custom_params(path: &str, params: Vec<&'static str>) -> TestRequest {
-        let mut config = Config::new(Environment::Test);
+        TestRequest::create_with_config(path, params, TestRequest::config())
+    }
+
+    /// Configuration used by test requests, for tests which need to adjust it
+    pub fn config() -> Config {
+        let mut config = Config::new(Environment::Test).unwrap();
         config.token_issuer = Box::new(DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into()));
         config.api_keys_encryption_key = "test_encryption_key".to_string();
         config.google_recaptcha_secret_key = None;
+        config.wallet_encryption_keys = TestProject::wallet_encryption_keys();
+        config.mfa_encryption_keys = TestProject::mfa_encryption_keys();
+        config
+    }
+
+    pub fn create_with_config(path: &str, params: Vec<&'static str>, config: Config) -> TestRequest {
         if config.spotify_auth_token.is_some() {
             spotify::SINGLETON.set_auth_token(&config.spotify_auth_token.clone().unwrap());
         }
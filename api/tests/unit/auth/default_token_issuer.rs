@@ -0,0 +1,190 @@
+use crate::jwt::{decode_header, Algorithm};
+use api::auth::default_token_issuer::{DefaultTokenIssuer, TokenSigningKey, TokenVerificationKey};
+use api::auth::jwks::Jwk;
+use chrono::Duration;
+use db::models::TokenIssuer;
+use uuid::Uuid;
+
+const KEY_1_PRIVATE: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgy0WSn8yK83fZf093qryOaSw8atvgUuaVtr7Fk9ppUnuhRANCAAQS7uU5VAQHt4CnQqbTulveIsaa/DORAXARvRPjEP87yTNT/2GpfUo1xAg3rEu9j2FF7Vr/L583iDKkayapNq7j";
+const KEY_1_PUBLIC: &str = "BBLu5TlUBAe3gKdCptO6W94ixpr8M5EBcBG9E+MQ/zvJM1P/Yal9SjXECDesS72PYUXtWv8vnzeIMqRrJqk2ruM=";
+const KEY_2_PRIVATE: &str = "MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgORDAniKRSA/LQA2KUOwTbGZazULQQJNiFnuqBRFh5duhRANCAARZybWm3GrCjc+ZlfCOugGkwWOgoNzTZG1RwXeJMw1ZP7mrgQq/hiEg5zEbe3Tg+UGpndWdSN5n7RypRoOeMMen";
+const KEY_2_PUBLIC: &str = "BFnJtabcasKNz5mV8I66AaTBY6Cg3NNkbVHBd4kzDVk/uauBCr+GISDnMRt7dOD5Qamd1Z1I3mftHKlGg54wx6c=";
+const RSA_PUBLIC: &str = "MIIBCgKCAQEA877O+q1ZX3u3Gh1FtHNXxtEG9BIHecCvgeFZPntoAciW+S9AnmIcBEO29AVfBnVe2GDrlaRMYUHKtEOYf+zs5YYBbXMq/iDyfGcmOrXPAU2zyiZF3ha7PFUiXuIK8rpCN+mSTWrOeyntoE5tcaJVhhzyo8OXtoWBCPIiKJEQxPbGvMvc6rqoIuV8IOe4wMw/hWRCggIVE/juEv9rFkc7bMg3dBm2vmGMIEmcxmTzTbmAbRpc4MOWQTCkIEiMK47835rrNfvAuWsSMHpfvnXyhTDen+tYllv5p5+ve/w/AfiyQamKQdzi7M3Z2QzMhzMgVAdkkqbnnl+0+IMMNjYhVQIDAQAB";
+
+fn token_issuer(signing_kid: &str, signing_key: &str, verification_keys: Vec<String>) -> DefaultTokenIssuer {
+    DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into())
+        .with_signing_keys(
+            format!("{}:ES256:{}", signing_kid, signing_key).parse().unwrap(),
+            verification_keys.iter().map(|k| k.parse().unwrap()).collect(),
+        )
+        .unwrap()
+}
+
+#[test]
+fn issue_signs_with_key_id() {
+    let token_issuer = token_issuer("key-1", KEY_1_PRIVATE, vec![format!("key-1:ES256:{}", KEY_1_PUBLIC)]);
+    let user_id = Uuid::new_v4();
+    let token = token_issuer.issue(user_id, Duration::minutes(15)).unwrap();
+
+    let header = decode_header(&token).unwrap();
+    assert_eq!(header.alg, Algorithm::ES256);
+    assert_eq!(header.kid, Some("key-1".to_string()));
+    assert_eq!(token_issuer.decode(&token).unwrap().claims.get_id().unwrap(), user_id);
+}
+
+#[test]
+fn decode_accepts_tokens_from_rotated_keys() {
+    let old_token_issuer = token_issuer("key-1", KEY_1_PRIVATE, vec![format!("key-1:ES256:{}", KEY_1_PUBLIC)]);
+    let user_id = Uuid::new_v4();
+    let old_token = old_token_issuer.issue(user_id, Duration::minutes(15)).unwrap();
+
+    let rotated_token_issuer = token_issuer(
+        "key-2",
+        KEY_2_PRIVATE,
+        vec![
+            format!("key-1:ES256:{}", KEY_1_PUBLIC),
+            format!("key-2:ES256:{}", KEY_2_PUBLIC),
+        ],
+    );
+    assert_eq!(
+        rotated_token_issuer
+            .decode(&old_token)
+            .unwrap()
+            .claims
+            .get_id()
+            .unwrap(),
+        user_id
+    );
+    let new_token = rotated_token_issuer.issue(user_id, Duration::minutes(15)).unwrap();
+    assert_eq!(decode_header(&new_token).unwrap().kid, Some("key-2".to_string()));
+
+    // Once key-1 is retired its tokens are no longer accepted
+    let retired_token_issuer = token_issuer("key-2", KEY_2_PRIVATE, vec![format!("key-2:ES256:{}", KEY_2_PUBLIC)]);
+    assert!(retired_token_issuer.decode(&old_token).is_err());
+    assert!(retired_token_issuer.decode(&new_token).is_ok());
+}
+
+#[test]
+fn decode_rejects_unknown_key_ids() {
+    let token_issuer = token_issuer("key-1", KEY_1_PRIVATE, vec![format!("key-1:ES256:{}", KEY_1_PUBLIC)]);
+    let legacy_token_issuer = DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into());
+    let user_id = Uuid::new_v4();
+
+    // Tokens without a key id are only accepted while no signing keys are configured
+    let legacy_token = legacy_token_issuer.issue(user_id, Duration::minutes(15)).unwrap();
+    assert!(token_issuer.decode(&legacy_token).is_err());
+    assert_eq!(
+        legacy_token_issuer
+            .decode(&legacy_token)
+            .unwrap()
+            .claims
+            .get_id()
+            .unwrap(),
+        user_id
+    );
+
+    let other_issuer = DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into())
+        .with_signing_keys(
+            format!("key-3:ES256:{}", KEY_2_PRIVATE).parse().unwrap(),
+            vec![format!("key-3:ES256:{}", KEY_2_PUBLIC).parse().unwrap()],
+        )
+        .unwrap();
+    let unknown_token = other_issuer.issue(user_id, Duration::minutes(15)).unwrap();
+    assert!(token_issuer.decode(&unknown_token).is_err());
+}
+
+#[test]
+fn with_signing_keys_requires_matching_verification_key() {
+    let result = DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into()).with_signing_keys(
+        format!("key-2:ES256:{}", KEY_2_PRIVATE).parse().unwrap(),
+        vec![format!("key-1:ES256:{}", KEY_1_PUBLIC).parse().unwrap()],
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn parse_key() {
+    assert!("key-1:HS256:c2VjcmV0".parse::<TokenVerificationKey>().is_err());
+    assert!("key-1:ES256".parse::<TokenVerificationKey>().is_err());
+    assert!("key-1:ES256:not base64!".parse::<TokenSigningKey>().is_err());
+
+    let key: TokenVerificationKey = format!("key-1:ES256:{}", KEY_1_PUBLIC).parse().unwrap();
+    assert_eq!(key.kid, "key-1");
+    assert_eq!(key.algorithm, Algorithm::ES256);
+    assert_eq!(key.public_key.len(), 65);
+}
+
+#[test]
+fn jwks() {
+    let token_issuer = DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into());
+    assert!(token_issuer.jwks().unwrap().keys.is_empty());
+
+    let token_issuer = token_issuer
+        .with_signing_keys(
+            format!("key-1:ES256:{}", KEY_1_PRIVATE).parse().unwrap(),
+            vec![
+                format!("key-0:RS256:{}", RSA_PUBLIC).parse().unwrap(),
+                format!("key-1:ES256:{}", KEY_1_PUBLIC).parse().unwrap(),
+            ],
+        )
+        .unwrap();
+    let jwks = token_issuer.jwks().unwrap();
+    assert_eq!(
+        jwks.keys,
+        vec![
+            Jwk {
+                kty: "RSA".to_string(),
+                kid: "key-0".to_string(),
+                alg: "RS256".to_string(),
+                key_use: "sig".to_string(),
+                n: Some("877O-q1ZX3u3Gh1FtHNXxtEG9BIHecCvgeFZPntoAciW-S9AnmIcBEO29AVfBnVe2GDrlaRMYUHKtEOYf-zs5YYBbXMq_iDyfGcmOrXPAU2zyiZF3ha7PFUiXuIK8rpCN-mSTWrOeyntoE5tcaJVhhzyo8OXtoWBCPIiKJEQxPbGvMvc6rqoIuV8IOe4wMw_hWRCggIVE_juEv9rFkc7bMg3dBm2vmGMIEmcxmTzTbmAbRpc4MOWQTCkIEiMK47835rrNfvAuWsSMHpfvnXyhTDen-tYllv5p5-ve_w_AfiyQamKQdzi7M3Z2QzMhzMgVAdkkqbnnl-0-IMMNjYhVQ".to_string()),
+                e: Some("AQAB".to_string()),
+                crv: None,
+                x: None,
+                y: None,
+            },
+            Jwk {
+                kty: "EC".to_string(),
+                kid: "key-1".to_string(),
+                alg: "ES256".to_string(),
+                key_use: "sig".to_string(),
+                n: None,
+                e: None,
+                crv: Some("P-256".to_string()),
+                x: Some("Eu7lOVQEB7eAp0Km07pb3iLGmvwzkQFwEb0T4xD_O8k".to_string()),
+                y: Some("M1P_Yal9SjXECDesS72PYUXtWv8vnzeIMqRrJqk2ruM".to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn jwk_to_verification_key() {
+    let token_issuer = DefaultTokenIssuer::new("test_secret".into(), "bn-api-test".into())
+        .with_signing_keys(
+            format!("key-1:ES256:{}", KEY_1_PRIVATE).parse().unwrap(),
+            vec![
+                format!("key-0:RS256:{}", RSA_PUBLIC).parse().unwrap(),
+                format!("key-1:ES256:{}", KEY_1_PUBLIC).parse().unwrap(),
+            ],
+        )
+        .unwrap();
+
+    // Published keys read back to the same verification keys
+    for (jwk, key) in token_issuer
+        .jwks()
+        .unwrap()
+        .keys
+        .iter()
+        .zip(token_issuer.verification_keys.iter())
+    {
+        let verification_key = jwk.to_verification_key().unwrap();
+        assert_eq!(verification_key.kid, key.kid);
+        assert_eq!(verification_key.algorithm, key.algorithm);
+        assert_eq!(verification_key.public_key, key.public_key);
+    }
+
+    let mut jwk = token_issuer.jwks().unwrap().keys.remove(1);
+    jwk.crv = Some("P-384".to_string());
+    assert!(jwk.to_verification_key().is_err());
+}
@@ -0,0 +1 @@
+pub mod default_token_issuer;
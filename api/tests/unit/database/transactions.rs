@@ -8,7 +8,7 @@ use diesel::{Connection as DieselConnection, PgConnection};
 
 #[actix_rt::test]
 async fn no_hanging_transaction_in_pool() {
-    let mut config = Config::new(Environment::Test);
+    let mut config = Config::new(Environment::Test).unwrap();
     config.connection_pool.min = 1;
     config.connection_pool.max = 1;
     let db = Database::from_config(&config);
@@ -50,7 +50,7 @@ async fn wipes_transaction_for_request() {
 
 #[actix_rt::test]
 async fn diesel_pool_does_not_release_transaction() {
-    let mut config = Config::new(Environment::Test);
+    let mut config = Config::new(Environment::Test).unwrap();
     config.connection_pool.min = 1;
     config.connection_pool.max = 1;
     let pool = create_connection_pool(&config);
@@ -1 +1,3 @@
+pub mod process_report_export;
+pub mod process_waitlist;
 pub mod webhook_publisher;
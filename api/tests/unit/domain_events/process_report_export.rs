@@ -0,0 +1,78 @@
+use crate::support::database::TestDatabase;
+use api::config::Config;
+use api::database::Connection;
+use api::domain_events::executors::ProcessReportExportExecutor;
+use db::prelude::*;
+
+fn process(report_export: &ReportExport, database: &TestDatabase) -> ReportExport {
+    let connection = database.connection.get();
+    let domain_action = DomainAction::find_by_resource(
+        Some(Tables::ReportExports),
+        Some(report_export.id),
+        DomainActionTypes::ProcessReportExport,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap()
+    .remove(0);
+    let conn: Connection = database.connection.clone().into();
+    ProcessReportExportExecutor::new(Config::new(Environment::Test).unwrap())
+        .perform_job(&domain_action, &conn)
+        .unwrap();
+
+    ReportExport::find(report_export.id, connection).unwrap()
+}
+
+#[test]
+fn perform_job() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "transaction_details".to_string(),
+        ReportExportFormat::Csv,
+        json!({ "report": "transaction_details" }),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let report_export = process(&report_export, &database);
+    assert_eq!(report_export.status, ReportExportStatus::Completed);
+    assert_eq!(report_export.row_count, Some(0));
+    assert!(report_export.content.is_some());
+    assert!(report_export.expires_at.is_some());
+    let domain_actions = DomainAction::find_by_resource(
+        None,
+        None,
+        DomainActionTypes::Communication,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+}
+
+#[test]
+fn perform_job_with_invalid_parameters() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "event_summary".to_string(),
+        ReportExportFormat::Xlsx,
+        json!({ "report": "event_summary" }),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let report_export = process(&report_export, &database);
+    assert_eq!(report_export.status, ReportExportStatus::Failed);
+    assert!(report_export.content.is_none());
+    assert!(report_export.error_message.is_some());
+}
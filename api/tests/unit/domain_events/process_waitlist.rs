@@ -0,0 +1,70 @@
+use crate::support::database::TestDatabase;
+use api::config::Config;
+use api::database::Connection;
+use api::domain_events::executors::ProcessWaitlistExecutor;
+use db::prelude::*;
+
+fn communication_count(database: &TestDatabase) -> usize {
+    DomainAction::find_by_resource(
+        None,
+        None,
+        DomainActionTypes::Communication,
+        DomainActionStatus::Pending,
+        database.connection.get(),
+    )
+    .unwrap()
+    .len()
+}
+
+#[test]
+fn perform_job() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(1)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let buyer = database.create_user().finish();
+    let mut order = database
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let user = database.create_user().finish();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(tickets[0].id),
+    }];
+    order.refund(&refund_items, buyer.id, None, false, connection).unwrap();
+
+    let communications = communication_count(&database);
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    let conn: Connection = database.connection.clone().into();
+    ProcessWaitlistExecutor::new(Config::new(Environment::Test).unwrap())
+        .perform_job(&domain_action, &conn)
+        .unwrap();
+
+    let waitlist_entry = WaitlistEntry::find(waitlist_entry.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Offered);
+    assert!(waitlist_entry.hold_id.is_some());
+    // The offer is sent by email and SMS
+    assert_eq!(communication_count(&database), communications + 2);
+}
@@ -4,6 +4,7 @@ use api::config::Config;
 use api::domain_events::webhook_publisher::WebhookPublisher;
 use api::utils::deep_linker::BranchDeepLinker;
 use chrono::prelude::*;
+use db::dev::TestProject;
 use db::prelude::*;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -13,7 +14,7 @@ fn webhook_payloads() {
     let project = TestDatabase::new();
     let connection = project.connection.get();
     let organization = project.create_organization().with_fees().finish();
-    let config = Config::new(Environment::Test);
+    let config = Config::new(Environment::Test).unwrap();
     let publisher = WebhookPublisher::new(
         "http://localhost:5432".to_string(),
         DefaultTokenIssuer::new("asdf".into(), "asdf".into()),
@@ -22,6 +23,7 @@ fn webhook_payloads() {
             config.branch_io_branch_key.clone(),
             500,
         )),
+        TestProject::wallet_encryption_keys(),
     );
     //let domain_event_publisher = project.create_domain_event_publisher().finish();
     let event_start = NaiveDateTime::parse_from_str("2055-06-14 16:00:00.000", "%Y-%m-%d %H:%M:%S%.f").unwrap();
@@ -340,6 +342,60 @@ fn webhook_payloads() {
         fetch_from_payload::<i64>(&push_token_payload, "last_used"),
         push_token_domain_event.created_at.timestamp()
     );
+
+    // Organization event payloads
+    let event_domain_event = DomainEvent::create(
+        DomainEventTypes::EventPublished,
+        "Event published".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let mut event_payloads = publisher
+        .create_webhook_payloads(&event_domain_event, connection)
+        .unwrap();
+    assert_eq!(event_payloads.len(), 1);
+    let event_payload = event_payloads.remove(0);
+    assert_eq!(
+        fetch_from_payload::<String>(&event_payload, "webhook_event_type"),
+        "event_published".to_string()
+    );
+    assert_eq!(
+        fetch_from_payload::<Uuid>(&event_payload, "organization_id"),
+        organization.id
+    );
+    assert_eq!(
+        fetch_from_payload::<i64>(&event_payload, "timestamp"),
+        event_domain_event.created_at.timestamp()
+    );
+
+    let redeemed_domain_event = DomainEvent::create(
+        DomainEventTypes::TicketInstanceRedeemed,
+        "Ticket redeemed".to_string(),
+        Tables::TicketInstances,
+        Some(ticket.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let mut redeemed_payloads = publisher
+        .create_webhook_payloads(&redeemed_domain_event, connection)
+        .unwrap();
+    assert_eq!(redeemed_payloads.len(), 1);
+    let redeemed_payload = redeemed_payloads.remove(0);
+    assert_eq!(
+        fetch_from_payload::<String>(&redeemed_payload, "webhook_event_type"),
+        "ticket_redeemed".to_string()
+    );
+    assert_eq!(fetch_from_payload::<Uuid>(&redeemed_payload, "ticket_id"), ticket.id);
+    assert_eq!(
+        fetch_from_payload::<Uuid>(&redeemed_payload, "organization_id"),
+        organization.id
+    );
 }
 
 fn fetch_from_payload<T>(payload: &HashMap<String, serde_json::Value>, key: &str) -> T
@@ -0,0 +1,34 @@
+use api::extractors::api_key_accepted_for_path;
+
+#[test]
+fn api_key_accepted_for_organization_resources() {
+    assert!(api_key_accepted_for_path(
+        "/organizations/0f85443e-9e70-45ba-bf28-0f59c183856f/events"
+    ));
+    assert!(api_key_accepted_for_path(
+        "/events/0f85443e-9e70-45ba-bf28-0f59c183856f/guests"
+    ));
+    assert!(api_key_accepted_for_path(
+        "/holds/0f85443e-9e70-45ba-bf28-0f59c183856f/comps"
+    ));
+    assert!(api_key_accepted_for_path(
+        "/settlements/0f85443e-9e70-45ba-bf28-0f59c183856f"
+    ));
+}
+
+#[test]
+fn api_key_rejected_for_user_resources() {
+    assert!(!api_key_accepted_for_path("/users/me"));
+    assert!(!api_key_accepted_for_path("/users/me/mfa"));
+    assert!(!api_key_accepted_for_path("/users/me/sessions"));
+    assert!(!api_key_accepted_for_path("/cart"));
+    assert!(!api_key_accepted_for_path("/cart/checkout"));
+    assert!(!api_key_accepted_for_path("/organizations"));
+    assert!(!api_key_accepted_for_path("/events/checkins"));
+    assert!(!api_key_accepted_for_path(
+        "/events/0f85443e-9e70-45ba-bf28-0f59c183856f/tickets"
+    ));
+    assert!(!api_key_accepted_for_path(
+        "/events/0f85443e-9e70-45ba-bf28-0f59c183856f/ticket_types/0f85443e-9e70-45ba-bf28-0f59c183856f/waitlist"
+    ));
+}
@@ -0,0 +1,190 @@
+use api::config::LoginThrottlingConfig;
+use api::helpers::login_throttling::{forwarded_client_ip_address, LoginThrottle, LoginThrottleStatus};
+use cache::{CacheConnection, CacheError};
+use chrono::Duration;
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct MemoryCache {
+    values: HashMap<String, String>,
+}
+
+impl CacheConnection for MemoryCache {
+    fn get(&mut self, key: &str) -> Result<Option<String>, CacheError> {
+        Ok(self.values.get(key).cloned())
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), CacheError> {
+        self.values.remove(key);
+        Ok(())
+    }
+
+    fn add(&mut self, key: &str, data: &str, _ttl: Option<usize>) -> Result<(), CacheError> {
+        self.values.insert(key.to_string(), data.to_string());
+        Ok(())
+    }
+
+    fn publish(&mut self, _channel: &str, _message: &str) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    fn delete_by_key_fragment(&mut self, key_fragment: &str) -> Result<(), CacheError> {
+        self.values.retain(|key, _| !key.contains(key_fragment));
+        Ok(())
+    }
+
+    fn increment(&mut self, key: &str, _ttl: Option<usize>) -> Result<i64, CacheError> {
+        let value = self.values.get(key).and_then(|v| v.parse::<i64>().ok()).unwrap_or(0) + 1;
+        self.values.insert(key.to_string(), value.to_string());
+        Ok(value)
+    }
+}
+
+fn login_throttle() -> LoginThrottle<MemoryCache> {
+    LoginThrottle::new(
+        MemoryCache::default(),
+        LoginThrottlingConfig {
+            max_failed_attempts: 5,
+            max_failed_attempts_per_ip: 8,
+            lockout_period: Duration::minutes(30),
+            trusted_proxy_count: 1,
+        },
+    )
+}
+
+#[test]
+fn record_failure_backs_off_progressively() {
+    let mut login_throttle = login_throttle();
+    let email = "user@localhost";
+
+    for _ in 0..2 {
+        login_throttle.record_failure(email, None).unwrap();
+        assert_eq!(
+            login_throttle.status(email, None).unwrap(),
+            LoginThrottleStatus::Allowed
+        );
+    }
+
+    let failed_login = login_throttle.record_failure(email, None).unwrap();
+    assert_eq!(failed_login.failed_attempts, 3);
+    assert!(failed_login.unlock_token.is_none());
+    match login_throttle.status(email, None).unwrap() {
+        LoginThrottleStatus::BackingOff { retry_after_seconds } => assert!(retry_after_seconds <= 2),
+        status => panic!("Unexpected status {:?}", status),
+    }
+
+    login_throttle.record_failure(email, None).unwrap();
+    match login_throttle.status(email, None).unwrap() {
+        LoginThrottleStatus::BackingOff { retry_after_seconds } => assert!(retry_after_seconds > 2),
+        status => panic!("Unexpected status {:?}", status),
+    }
+
+    // Email is matched case insensitively, other accounts are unaffected
+    assert_ne!(
+        login_throttle.status("USER@localhost", None).unwrap(),
+        LoginThrottleStatus::Allowed
+    );
+    assert_eq!(
+        login_throttle.status("other@localhost", None).unwrap(),
+        LoginThrottleStatus::Allowed
+    );
+
+    login_throttle.record_success(email).unwrap();
+    assert_eq!(
+        login_throttle.status(email, None).unwrap(),
+        LoginThrottleStatus::Allowed
+    );
+}
+
+#[test]
+fn record_failure_locks_account() {
+    let mut login_throttle = login_throttle();
+    let email = "user@localhost";
+
+    for _ in 0..4 {
+        assert!(login_throttle
+            .record_failure(email, None)
+            .unwrap()
+            .unlock_token
+            .is_none());
+    }
+    let unlock_token = login_throttle
+        .record_failure(email, None)
+        .unwrap()
+        .unlock_token
+        .unwrap();
+    assert_eq!(login_throttle.status(email, None).unwrap(), LoginThrottleStatus::Locked);
+
+    assert!(login_throttle.unlock("not-a-token").unwrap().is_none());
+    assert!(login_throttle.unlock(&unlock_token).unwrap().is_some());
+    assert_eq!(
+        login_throttle.status(email, None).unwrap(),
+        LoginThrottleStatus::Allowed
+    );
+
+    // Token can only be used once
+    assert!(login_throttle.unlock(&unlock_token).unwrap().is_none());
+}
+
+#[test]
+fn record_failure_throttles_ip_address() {
+    let mut login_throttle = login_throttle();
+    let ip_address = Some("10.0.0.1");
+
+    // Spreading attempts over many accounts still backs off the address
+    for i in 0..3 {
+        login_throttle
+            .record_failure(&format!("user{}@localhost", i), ip_address)
+            .unwrap();
+    }
+    match login_throttle.status("new@localhost", ip_address).unwrap() {
+        LoginThrottleStatus::BackingOff { .. } => (),
+        status => panic!("Unexpected status {:?}", status),
+    }
+    assert_eq!(
+        login_throttle.status("new@localhost", Some("10.0.0.2")).unwrap(),
+        LoginThrottleStatus::Allowed
+    );
+
+    for i in 3..8 {
+        login_throttle
+            .record_failure(&format!("user{}@localhost", i), ip_address)
+            .unwrap();
+    }
+    match login_throttle.status("new@localhost", ip_address).unwrap() {
+        LoginThrottleStatus::BackingOff { retry_after_seconds } => assert!(retry_after_seconds > 300),
+        status => panic!("Unexpected status {:?}", status),
+    }
+}
+
+#[test]
+fn forwarded_client_ip_address_uses_trusted_proxy_entries() {
+    let peer_address = Some("10.0.0.5:4000".parse().unwrap());
+
+    assert_eq!(
+        forwarded_client_ip_address(peer_address, None, 0),
+        Some("10.0.0.5".to_string())
+    );
+    // The client supplied the first entry, the load balancer appended the address it saw
+    assert_eq!(
+        forwarded_client_ip_address(peer_address, Some("1.1.1.1, 203.0.113.7"), 1),
+        Some("203.0.113.7".to_string())
+    );
+    assert_eq!(
+        forwarded_client_ip_address(peer_address, Some("1.1.1.1, 203.0.113.7, 10.0.0.9"), 2),
+        Some("203.0.113.7".to_string())
+    );
+    // Forwarded addresses are ignored without trusted proxies or when a proxy was bypassed
+    assert_eq!(
+        forwarded_client_ip_address(peer_address, Some("1.1.1.1"), 0),
+        Some("10.0.0.5".to_string())
+    );
+    assert_eq!(
+        forwarded_client_ip_address(peer_address, Some("1.1.1.1"), 2),
+        Some("10.0.0.5".to_string())
+    );
+    assert_eq!(
+        forwarded_client_ip_address(peer_address, Some(""), 1),
+        Some("10.0.0.5".to_string())
+    );
+}
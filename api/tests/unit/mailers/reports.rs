@@ -0,0 +1,45 @@
+use crate::support::database::TestDatabase;
+use api::communications::mailers;
+use api::config::Config;
+use db::prelude::*;
+
+#[test]
+fn report_export_ready() {
+    let config = Config::new(Environment::Test).unwrap();
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "transaction_details".to_string(),
+        ReportExportFormat::Csv,
+        json!({ "report": "transaction_details" }),
+    )
+    .commit(connection)
+    .unwrap();
+    let (report_export, download_token) = report_export.mark_completed(0, connection).unwrap();
+
+    mailers::reports::report_export_ready(&config, &user, &report_export, &download_token, connection).unwrap();
+    let domain_actions = DomainAction::find_by_resource(
+        None,
+        None,
+        DomainActionTypes::Communication,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    let communication: Communication = serde_json::from_value(domain_actions[0].payload.clone()).unwrap();
+    assert_eq!(communication.destinations, CommAddress::from(user.email.unwrap()));
+    let template_data = communication.template_data.unwrap();
+    assert_eq!(
+        template_data[0].get("download_link"),
+        Some(&format!(
+            "{}/report_exports/{}/download?token={}",
+            config.api_base_url, report_export.id, download_token
+        ))
+    );
+    assert_eq!(template_data[0].get("report"), Some(&"transaction_details".to_string()));
+}
@@ -6,7 +6,7 @@ use db::models::{CommAddress, Environment};
 
 #[test]
 fn password_reset_email() {
-    let config = Config::new(Environment::Test);
+    let config = Config::new(Environment::Test).unwrap();
     let database = TestDatabase::new();
 
     let user = database.create_user().finish();
@@ -22,3 +22,21 @@ fn password_reset_email() {
         Some(CommAddress::from("noreply@bigneon.com".to_string()))
     );
 }
+
+#[test]
+fn account_locked_email() {
+    let config = Config::new(Environment::Test).unwrap();
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+
+    let account_locked_email = mailers::user::account_locked_email(&config, &user, "unlock-token");
+    assert_eq!(
+        account_locked_email.destinations,
+        CommAddress::from(user.email.unwrap().to_string())
+    );
+    let template_data = account_locked_email.template_data.unwrap();
+    assert_eq!(
+        template_data[0].get("unlock_link"),
+        Some(&format!("{}/unlock-account?token=unlock-token", config.front_end_url))
+    );
+}
@@ -1,5 +1,9 @@
+pub mod auth;
 pub mod database;
 pub mod domain_events;
+pub mod extractors;
 pub mod helpers;
 pub mod mailers;
 pub mod models;
+pub mod payments;
+pub mod utils;
@@ -96,6 +96,7 @@ fn from_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 100,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -114,6 +115,7 @@ fn from_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 90,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -0,0 +1,2 @@
+pub mod braintree;
+pub mod stripe;
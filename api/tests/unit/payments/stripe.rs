@@ -0,0 +1,79 @@
+use api::payments::stripe::verify_webhook_signature;
+use db::utils::hash::hmac_sha256;
+
+const PAYLOAD: &str = r#"{"id":"evt_123","type":"payment_intent.amount_capturable_updated"}"#;
+const SECRET: &str = "whsec_test";
+
+fn signature_header(timestamp: i64, payload: &str, secret: &str) -> String {
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hmac_sha256::sign(secret, &format!("{}.{}", timestamp, payload))
+    )
+}
+
+#[test]
+fn verify_webhook_signature_valid() {
+    let header = signature_header(1_585_000_000, PAYLOAD, SECRET);
+    assert!(verify_webhook_signature(PAYLOAD, &header, SECRET, 1_585_000_000));
+    assert!(verify_webhook_signature(PAYLOAD, &header, SECRET, 1_585_000_100));
+}
+
+#[test]
+fn verify_webhook_signature_multiple_signatures() {
+    // Stripe sends one v1 signature per active secret while a secret is being rolled
+    let header = format!("{},v1=deadbeef", signature_header(1_585_000_000, PAYLOAD, SECRET));
+    assert!(verify_webhook_signature(PAYLOAD, &header, SECRET, 1_585_000_000));
+}
+
+#[test]
+fn verify_webhook_signature_invalid() {
+    let header = signature_header(1_585_000_000, PAYLOAD, SECRET);
+    assert!(!verify_webhook_signature(
+        r#"{"id":"evt_124"}"#,
+        &header,
+        SECRET,
+        1_585_000_000
+    ));
+    assert!(!verify_webhook_signature(
+        PAYLOAD,
+        &header,
+        "whsec_other",
+        1_585_000_000
+    ));
+    assert!(!verify_webhook_signature(PAYLOAD, "v1=deadbeef", SECRET, 1_585_000_000));
+    assert!(!verify_webhook_signature(PAYLOAD, "", SECRET, 1_585_000_000));
+}
+
+#[test]
+fn verify_webhook_signature_mismatched_length() {
+    let header = signature_header(1_585_000_000, PAYLOAD, SECRET);
+    let truncated_header = &header[..header.len() - 1];
+    assert!(!verify_webhook_signature(
+        PAYLOAD,
+        truncated_header,
+        SECRET,
+        1_585_000_000
+    ));
+    let extended_header = format!("{}0", header);
+    assert!(!verify_webhook_signature(
+        PAYLOAD,
+        &extended_header,
+        SECRET,
+        1_585_000_000
+    ));
+    let empty_signature_header = "t=1585000000,v1=";
+    assert!(!verify_webhook_signature(
+        PAYLOAD,
+        empty_signature_header,
+        SECRET,
+        1_585_000_000
+    ));
+}
+
+#[test]
+fn verify_webhook_signature_outside_tolerance() {
+    let header = signature_header(1_585_000_000, PAYLOAD, SECRET);
+    assert!(!verify_webhook_signature(PAYLOAD, &header, SECRET, 1_585_000_301));
+    assert!(!verify_webhook_signature(PAYLOAD, &header, SECRET, 1_584_999_699));
+}
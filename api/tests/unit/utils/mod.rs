@@ -0,0 +1,2 @@
+pub mod report_exports;
+pub mod webhook;
@@ -0,0 +1,118 @@
+use api::utils::report_exports::{self, ReportCell, ReportPage, ReportSheet};
+use chrono::NaiveDate;
+
+#[derive(Serialize)]
+struct ExportRow {
+    event_name: String,
+    ticket_count: i64,
+    unit_price_in_cents: i64,
+    gross: i64,
+    promo_code: Option<String>,
+    transaction_date: chrono::NaiveDateTime,
+}
+
+fn rows() -> Vec<ExportRow> {
+    vec![
+        ExportRow {
+            event_name: "Event, the first".to_string(),
+            ticket_count: 2,
+            unit_price_in_cents: 1050,
+            gross: 2100,
+            promo_code: None,
+            transaction_date: NaiveDate::from_ymd(2020, 4, 1).and_hms(19, 30, 0),
+        },
+        ExportRow {
+            event_name: "Second Event".to_string(),
+            ticket_count: 1,
+            unit_price_in_cents: -5,
+            gross: 0,
+            promo_code: Some("SPRING".to_string()),
+            transaction_date: NaiveDate::from_ymd(2020, 4, 2).and_hms_micro(8, 0, 0, 1500),
+        },
+    ]
+}
+
+#[test]
+fn column_header() {
+    assert_eq!(report_exports::column_header("unit_price_in_cents"), "Unit Price");
+    assert_eq!(
+        report_exports::column_header("client_fee_in_cents_total"),
+        "Client Fee Total"
+    );
+    assert_eq!(report_exports::column_header("qty_tickets_sold"), "Qty Tickets Sold");
+}
+
+#[test]
+fn format_money() {
+    assert_eq!(report_exports::format_money(0), "0.00");
+    assert_eq!(report_exports::format_money(1050), "10.50");
+    assert_eq!(report_exports::format_money(-5), "-0.05");
+    assert_eq!(report_exports::format_money(-123456), "-1234.56");
+}
+
+#[test]
+fn sheet_from_rows() {
+    let sheet = ReportSheet::from_rows("Transactions", &rows()).unwrap();
+    assert_eq!(
+        sheet.headers,
+        vec![
+            "Event Name",
+            "Ticket Count",
+            "Unit Price",
+            "Gross",
+            "Promo Code",
+            "Transaction Date"
+        ]
+    );
+    assert_eq!(
+        sheet.rows[0],
+        vec![
+            ReportCell::Text("Event, the first".to_string()),
+            ReportCell::Integer(2),
+            ReportCell::Money(1050),
+            ReportCell::Money(2100),
+            ReportCell::Empty,
+            ReportCell::Text("2020-04-01 19:30:00".to_string()),
+        ]
+    );
+    assert_eq!(sheet.rows[1][5], ReportCell::Text("2020-04-02 08:00:00".to_string()));
+
+    let empty_sheet = ReportSheet::from_rows::<ExportRow>("Transactions", &[]).unwrap();
+    assert!(empty_sheet.headers.is_empty());
+    assert!(empty_sheet.rows.is_empty());
+}
+
+#[test]
+fn csv_page() {
+    let sheet = ReportSheet::from_rows("Transactions", &rows()).unwrap();
+    let page = ReportPage {
+        starts_sheet: true,
+        sheet: sheet.clone(),
+    };
+    let csv = String::from_utf8(report_exports::csv_page(&page, 1).unwrap()).unwrap();
+    assert_eq!(
+        csv,
+        "Event Name,Ticket Count,Unit Price,Gross,Promo Code,Transaction Date\n\
+         \"Event, the first\",2,10.50,21.00,,2020-04-01 19:30:00\n\
+         Second Event,1,-0.05,0.00,SPRING,2020-04-02 08:00:00\n"
+    );
+
+    // Each sheet is named when there is more than one
+    let csv = String::from_utf8(report_exports::csv_page(&page, 2).unwrap()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "Transactions");
+    assert_eq!(
+        lines[1],
+        "Event Name,Ticket Count,Unit Price,Gross,Promo Code,Transaction Date"
+    );
+
+    // Later pages of a sheet only hold rows
+    let page = ReportPage {
+        starts_sheet: false,
+        sheet,
+    };
+    let csv = String::from_utf8(report_exports::csv_page(&page, 2).unwrap()).unwrap();
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.starts_with("\"Event, the first\","));
+}
@@ -0,0 +1,25 @@
+use api::utils::webhook::*;
+use uuid::Uuid;
+
+#[test]
+fn webhook_signing_headers() {
+    let event_id = Uuid::new_v4();
+    let signing = WebhookSigning {
+        event_id,
+        webhook_secret: "whsec_test".to_string(),
+    };
+    let body = r#"{"webhook_event_type":"order_completed"}"#;
+
+    let headers = signing.headers(body);
+    assert_eq!(headers.len(), 3);
+    assert_eq!(headers[0], (WEBHOOK_EVENT_ID_HEADER, event_id.to_string()));
+    assert_eq!(headers[1].0, WEBHOOK_TIMESTAMP_HEADER);
+    let timestamp: i64 = headers[1].1.parse().unwrap();
+    assert_eq!(
+        headers[2],
+        (
+            WEBHOOK_SIGNATURE_HEADER,
+            webhook_signature("whsec_test", timestamp, body)
+        )
+    );
+}
@@ -0,0 +1,102 @@
+use reqwest;
+use serde_json;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct BraintreeError {
+    pub description: String,
+    pub cause: Option<Arc<dyn Error>>,
+    /// Transaction status (e.g. `PROCESSOR_DECLINED`) or the legacy code of the first GraphQL error
+    pub error_code: Option<String>,
+}
+
+impl Error for BraintreeError {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+unsafe impl Send for BraintreeError {}
+unsafe impl Sync for BraintreeError {}
+
+impl fmt::Display for BraintreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match &self.cause {
+            Some(c) => write!(f, "{} caused by: {}", self.description, c.to_string()),
+            None => write!(f, "{}", self.description),
+        }
+    }
+}
+
+impl BraintreeError {
+    pub fn from_response_text(status: reqwest::StatusCode, response_text: &str) -> BraintreeError {
+        #[derive(Deserialize)]
+        struct Extensions {
+            #[serde(rename = "legacyCode")]
+            legacy_code: Option<String>,
+        }
+        #[derive(Deserialize)]
+        struct GraphQLError {
+            message: String,
+            extensions: Option<Extensions>,
+        }
+        #[derive(Deserialize)]
+        struct R {
+            errors: Vec<GraphQLError>,
+        }
+
+        match serde_json::from_str::<R>(response_text) {
+            Ok(ref r) if !r.errors.is_empty() => BraintreeError {
+                description: format!("Error calling Braintree: {}", r.errors[0].message),
+                cause: None,
+                error_code: r.errors[0].extensions.as_ref().and_then(|e| e.legacy_code.clone()),
+            },
+            _ => BraintreeError {
+                description: format!("Error calling Braintree: HTTP Code {}: Body:{}", status, response_text),
+                cause: None,
+                error_code: None,
+            },
+        }
+    }
+
+    pub fn unexpected_response(response_text: &str) -> BraintreeError {
+        BraintreeError {
+            description: format!("Unexpected response from Braintree: Body:{}", response_text),
+            cause: None,
+            error_code: None,
+        }
+    }
+
+    pub fn transaction_failed(status: &str, response_text: &str) -> BraintreeError {
+        BraintreeError {
+            description: format!(
+                "Braintree transaction was not successful: {}: Body:{}",
+                status, response_text
+            ),
+            cause: None,
+            error_code: Some(status.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for BraintreeError {
+    fn from(r: reqwest::Error) -> Self {
+        BraintreeError {
+            description: format!("Error calling Braintree: reqwest error {}", r),
+            cause: Some(Arc::new(r)),
+            error_code: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for BraintreeError {
+    fn from(r: serde_json::Error) -> Self {
+        BraintreeError {
+            description: format!("Error deserializing response:{}", r),
+            cause: Some(Arc::new(r)),
+            error_code: None,
+        }
+    }
+}
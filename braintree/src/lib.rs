@@ -0,0 +1,21 @@
+#![deny(unreachable_patterns)]
+#![deny(unused_variables)]
+#![deny(unused_imports)]
+// Unused results is more often than not an error
+#![deny(unused_must_use)]
+extern crate reqwest;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
+
+pub use self::braintree_client::BraintreeClient;
+pub use self::braintree_error::BraintreeError;
+pub use self::payment_method::PaymentMethod;
+pub use self::transaction_result::TransactionResult;
+
+mod braintree_client;
+mod braintree_error;
+mod payment_method;
+mod transaction_result;
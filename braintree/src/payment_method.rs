@@ -0,0 +1,11 @@
+#[derive(Deserialize, Serialize)]
+pub struct PaymentMethod {
+    pub id: String,
+    pub raw_data: String,
+}
+
+impl PaymentMethod {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+}
@@ -0,0 +1,12 @@
+#[derive(Deserialize, Serialize)]
+pub struct TransactionResult {
+    pub id: String,
+    pub status: Option<String>,
+    pub raw_data: String,
+}
+
+impl TransactionResult {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+}
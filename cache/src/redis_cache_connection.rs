@@ -1,17 +1,28 @@
 use crate::cache_error::*;
 use crate::r2d2_redis::r2d2::{Pool, PooledConnection};
 use crate::r2d2_redis::RedisConnectionManager;
-use crate::redis::Commands;
+use crate::redis::{Commands, Script};
 use std::sync::Arc;
 use std::time::Duration;
 
 type Milliseconds = usize;
 
+// Increments and sets the time to live in one step so a counter can never be left without expiry.
+// The window starts with the first increment.
+const INCREMENT_SCRIPT: &str = r"
+local value = redis.call('INCR', KEYS[1])
+if ARGV[1] and redis.call('PTTL', KEYS[1]) == -1 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[1])
+end
+return value
+";
+
 // Contract for the Cache
 pub trait CacheConnection {
     fn get(&mut self, key: &str) -> Result<Option<String>, CacheError>;
     fn delete(&mut self, key: &str) -> Result<(), CacheError>;
     fn add(&mut self, key: &str, data: &str, ttl: Option<Milliseconds>) -> Result<(), CacheError>;
+    fn increment(&mut self, key: &str, ttl: Option<Milliseconds>) -> Result<i64, CacheError>;
     fn publish(&mut self, channel: &str, message: &str) -> Result<(), CacheError>;
     fn delete_by_key_fragment(&mut self, key_fragment: &str) -> Result<(), CacheError>;
 }
@@ -83,6 +94,16 @@ impl CacheConnection for RedisCacheConnection {
         }
         Ok(())
     }
+
+    fn increment(&mut self, key: &str, ttl: Option<Milliseconds>) -> Result<i64, CacheError> {
+        let script = Script::new(INCREMENT_SCRIPT);
+        let mut invocation = script.key(key);
+        if let Some(ttl_val) = ttl {
+            invocation.arg(ttl_val);
+        }
+        let value: i64 = invocation.invoke(&mut *self.conn()?)?;
+        Ok(value)
+    }
 }
 
 #[cfg(test)]
@@ -107,4 +128,17 @@ mod tests {
             assert!(conn.get("key").unwrap().is_none());
         }
     }
+
+    #[test]
+    fn test_increment() {
+        if let Some(mut conn) = RedisCacheConnection::create_connection_pool("redis://127.0.0.1/", 10, 10, 10).ok() {
+            conn.delete("counter").unwrap();
+            assert_eq!(1, conn.increment("counter", Some(10)).unwrap());
+            assert_eq!(2, conn.increment("counter", Some(10)).unwrap());
+
+            sleep(11);
+            // counter should now be expired
+            assert!(conn.get("counter").unwrap().is_none());
+        }
+    }
 }
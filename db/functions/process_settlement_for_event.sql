@@ -49,6 +49,8 @@ INNER JOIN orders o on oi.order_id = o.id
 WHERE oi.event_id = $2
 AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
+-- Taxes are refunded alongside their parent item which is picked up here
+AND oi.item_type <> 'Tax'
 AND (start_override IS NULL OR r.created_at >= start_override)
 AND ($3 IS NULL OR r.created_at >= $3)
 AND ($4 IS NULL OR r.created_at <= $4)
@@ -57,7 +59,7 @@ AND ri.amount > 0
 AND r.settlement_id IS NULL
 AND o.box_office_pricing IS FALSE;
 
-INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type)
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type, tax_in_cents, product_id)
 SELECT -- Group result set by face price to prevent multiple records for holds that match code discounts
   entries.settlement_id,
   entries.event_id,
@@ -66,8 +68,11 @@ SELECT -- Group result set by face price to prevent multiple records for holds t
   entries.revenue_share_value_in_cents,
   SUM(online_sold_quantity),
   SUM(fee_sold_quantity),
-  SUM(online_sold_quantity) * entries.face_value_in_cents + SUM(fee_sold_quantity) * entries.revenue_share_value_in_cents,
-  entries.settlement_entry_type
+  SUM(online_sold_quantity) * (entries.face_value_in_cents + entries.tax_charged_in_cents) + SUM(fee_sold_quantity) * (entries.revenue_share_value_in_cents + entries.fee_tax_charged_in_cents),
+  entries.settlement_entry_type,
+  -- Tax collected is passed through to the organization including taxes contained in the face value
+  SUM(online_sold_quantity) * entries.tax_value_in_cents + SUM(fee_sold_quantity) * entries.fee_tax_value_in_cents,
+  entries.product_id
 FROM (
   SELECT
     $1 as settlement_id,
@@ -100,7 +105,13 @@ FROM (
           CAST(SUM(COALESCE(oi_t_fees.quantity, 0)) AS BIGINT)
         END
     END as fee_sold_quantity,
-    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' ELSE 'TicketType' END as settlement_entry_type
+    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' WHEN 'Products' THEN 'Product' ELSE 'TicketType' END as settlement_entry_type,
+    oi.product_id,
+    -- Tax per unit collected and the portion of it charged on top of the price (exclusive taxes)
+    CASE oi.item_type WHEN 'EventFees' THEN 0 ELSE CAST(COALESCE(oi_tax.tax_in_cents, 0) AS BIGINT) END as tax_value_in_cents,
+    CASE oi.item_type WHEN 'EventFees' THEN 0 ELSE CAST(COALESCE(oi_tax.unit_price_in_cents, 0) AS BIGINT) END as tax_charged_in_cents,
+    CASE oi.item_type WHEN 'EventFees' THEN CAST(COALESCE(oi_tax.tax_in_cents, 0) AS BIGINT) ELSE CAST(COALESCE(oi_t_fees_tax.tax_in_cents, 0) AS BIGINT) END as fee_tax_value_in_cents,
+    CASE oi.item_type WHEN 'EventFees' THEN CAST(COALESCE(oi_tax.unit_price_in_cents, 0) AS BIGINT) ELSE CAST(COALESCE(oi_t_fees_tax.unit_price_in_cents, 0) AS BIGINT) END as fee_tax_charged_in_cents
   FROM order_items oi
   INNER JOIN order_item_ids oi_ids ON oi.id = oi_ids.id
   INNER JOIN orders o ON oi.order_id = o.id
@@ -108,24 +119,36 @@ FROM (
   LEFT JOIN order_items oi_promo_code ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
   LEFT JOIN order_items oi_t_fees ON oi_t_fees.parent_id = oi.id AND oi_t_fees.item_type = 'PerUnitFees'
   LEFT JOIN refund_items oi_t_fees_r ON oi_t_fees_r.order_item_id = oi_t_fees.id AND oi_t_fees_r.refund_id = oi_ids.refund_id
+  LEFT JOIN order_items oi_tax ON oi_tax.parent_id = oi.id AND oi_tax.item_type = 'Tax'
+  LEFT JOIN order_items oi_t_fees_tax ON oi_t_fees_tax.parent_id = oi_t_fees.id AND oi_t_fees_tax.item_type = 'Tax'
   GROUP BY
     oi.item_type,
     oi.event_id,
     oi.ticket_type_id,
+    oi.product_id,
     oi.unit_price_in_cents,
     oi.client_fee_in_cents,
     oi_t_fees.client_fee_in_cents,
     oi_promo_code.unit_price_in_cents,
     oi_t_fees_r.quantity,
-    oi_r.quantity
+    oi_r.quantity,
+    oi_tax.tax_in_cents,
+    oi_tax.unit_price_in_cents,
+    oi_t_fees_tax.tax_in_cents,
+    oi_t_fees_tax.unit_price_in_cents
 ) entries
   GROUP BY
     entries.settlement_id,
     entries.event_id,
     entries.ticket_type_id,
+    entries.product_id,
     entries.face_value_in_cents,
     entries.revenue_share_value_in_cents,
-    entries.settlement_entry_type
+    entries.settlement_entry_type,
+    entries.tax_value_in_cents,
+    entries.tax_charged_in_cents,
+    entries.fee_tax_value_in_cents,
+    entries.fee_tax_charged_in_cents
   -- Filter out any records where the sum of their quantities is 0
   -- Negative indicates a refund settlement adjustment, positive purchases
   HAVING
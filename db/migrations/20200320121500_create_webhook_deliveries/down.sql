@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_webhook_deliveries_domain_event_id;
+DROP INDEX IF EXISTS index_webhook_deliveries_domain_event_publisher_id_status;
+DROP TABLE IF EXISTS webhook_deliveries;
+
+ALTER TABLE domain_event_publishers
+    DROP webhook_secret;
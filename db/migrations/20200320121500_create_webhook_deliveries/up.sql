@@ -0,0 +1,20 @@
+ALTER TABLE domain_event_publishers
+    ADD webhook_secret TEXT NOT NULL DEFAULT encode(gen_random_bytes(32), 'hex');
+
+CREATE TABLE webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    domain_event_publisher_id UUID NOT NULL REFERENCES domain_event_publishers (id),
+    domain_event_id UUID NULL REFERENCES domain_events (id),
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL,
+    attempt_count BIGINT NOT NULL DEFAULT 0,
+    response_status INT NULL,
+    response_body TEXT NULL,
+    last_attempted_at TIMESTAMP NULL,
+    next_attempt_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_webhook_deliveries_domain_event_publisher_id_status ON webhook_deliveries (domain_event_publisher_id, status);
+CREATE INDEX index_webhook_deliveries_domain_event_id ON webhook_deliveries (domain_event_id);
@@ -0,0 +1,2 @@
+ALTER TABLE domain_event_publishers
+    DROP paused_at;
@@ -0,0 +1,2 @@
+ALTER TABLE domain_event_publishers
+    ADD paused_at TIMESTAMP NULL;
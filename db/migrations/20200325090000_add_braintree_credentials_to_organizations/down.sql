@@ -0,0 +1,4 @@
+ALTER TABLE organizations
+    DROP COLUMN braintree_public_key,
+    DROP COLUMN braintree_private_key,
+    DROP COLUMN braintree_merchant_account_id;
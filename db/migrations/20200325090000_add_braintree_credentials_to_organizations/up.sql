@@ -0,0 +1,4 @@
+ALTER TABLE organizations
+    ADD braintree_public_key TEXT,
+    ADD braintree_private_key TEXT,
+    ADD braintree_merchant_account_id TEXT;
@@ -0,0 +1,5 @@
+ALTER TABLE settlements
+    DROP COLUMN stripe_payout_id;
+
+ALTER TABLE organizations
+    DROP COLUMN stripe_connect_account_id;
@@ -0,0 +1,5 @@
+ALTER TABLE organizations
+    ADD stripe_connect_account_id TEXT;
+
+ALTER TABLE settlements
+    ADD stripe_payout_id TEXT;
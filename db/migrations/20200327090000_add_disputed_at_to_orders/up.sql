@@ -0,0 +1,2 @@
+ALTER TABLE orders
+    ADD disputed_at TIMESTAMP NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE wallets
+    DROP COLUMN secret_key_version;
@@ -0,0 +1,3 @@
+-- Version of the master key the secret key is encrypted with, 0 when unencrypted
+ALTER TABLE wallets
+    ADD secret_key_version INTEGER NOT NULL DEFAULT 0;
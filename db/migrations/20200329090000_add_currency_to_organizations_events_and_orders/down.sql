@@ -0,0 +1,16 @@
+DROP INDEX IF EXISTS index_events_organization_id_currency;
+
+ALTER TABLE settlements
+    DROP COLUMN currency;
+
+ALTER TABLE orders
+    DROP COLUMN currency;
+
+ALTER TABLE events
+    DROP COLUMN currency;
+
+ALTER TABLE fee_schedules
+    DROP COLUMN currency;
+
+ALTER TABLE organizations
+    DROP COLUMN currency;
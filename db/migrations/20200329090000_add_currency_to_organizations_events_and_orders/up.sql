@@ -0,0 +1,18 @@
+-- ISO 4217 currency codes, amounts remain in the minor unit of the currency (*_in_cents)
+ALTER TABLE organizations
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE fee_schedules
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE events
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+-- Set when the order is checked out
+ALTER TABLE orders
+    ADD currency TEXT NULL;
+
+ALTER TABLE settlements
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+CREATE INDEX index_events_organization_id_currency ON events (organization_id, currency);
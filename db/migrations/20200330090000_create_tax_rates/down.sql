@@ -0,0 +1,10 @@
+ALTER TABLE settlement_entries
+    DROP COLUMN tax_in_cents;
+
+ALTER TABLE order_items
+    DROP COLUMN tax_in_cents;
+ALTER TABLE order_items
+    DROP COLUMN tax_rate_id;
+
+DROP INDEX IF EXISTS index_tax_rates_organization_id_venue_id;
+DROP TABLE IF EXISTS tax_rates;
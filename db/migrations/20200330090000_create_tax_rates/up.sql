@@ -0,0 +1,27 @@
+-- Tax rates are set per organization, optionally overridden per venue
+CREATE TABLE tax_rates
+(
+    id              UUID PRIMARY KEY     DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID        NOT NULL REFERENCES organizations (id),
+    venue_id        UUID        NULL REFERENCES venues (id),
+    name            TEXT        NOT NULL,
+    rate_percent    REAL        NOT NULL,
+    -- Inclusive taxes are part of the ticket price (VAT), exclusive taxes are added to it (sales tax)
+    inclusive       BOOLEAN     NOT NULL DEFAULT FALSE,
+    applies_to_fees BOOLEAN     NOT NULL DEFAULT FALSE,
+    created_at      TIMESTAMP   NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP   NOT NULL DEFAULT now(),
+    CONSTRAINT tax_rates_rate_percent_valid CHECK (rate_percent >= 0 AND rate_percent <= 100)
+);
+
+CREATE UNIQUE INDEX index_tax_rates_organization_id_venue_id ON tax_rates (organization_id, COALESCE(venue_id, '00000000-0000-0000-0000-000000000000'));
+
+-- Tax order items record the tax per unit of their parent item in tax_in_cents, their unit price is the portion
+-- added to the order total which is 0 for inclusive taxes
+ALTER TABLE order_items
+    ADD tax_rate_id UUID NULL REFERENCES tax_rates (id) ON DELETE SET NULL;
+ALTER TABLE order_items
+    ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE settlement_entries
+    ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
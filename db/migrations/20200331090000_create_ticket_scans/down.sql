@@ -0,0 +1,8 @@
+ALTER TABLE ticket_types
+    DROP COLUMN reentry_limit;
+ALTER TABLE ticket_types
+    DROP COLUMN reentry_policy;
+
+DROP INDEX IF EXISTS index_ticket_scans_event_id;
+DROP INDEX IF EXISTS index_ticket_scans_ticket_instance_id;
+DROP TABLE IF EXISTS ticket_scans;
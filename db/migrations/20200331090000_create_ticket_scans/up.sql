@@ -0,0 +1,29 @@
+-- Every entry and exit through the gates, the latest scan of a ticket tells whether the holder is inside
+CREATE TABLE ticket_scans
+(
+    id                 UUID PRIMARY KEY     DEFAULT gen_random_uuid() NOT NULL,
+    ticket_instance_id UUID        NOT NULL REFERENCES ticket_instances (id),
+    event_id           UUID        NOT NULL REFERENCES events (id),
+    scan_type          TEXT        NOT NULL,
+    scanned_by_user_id UUID        NULL REFERENCES users (id),
+    check_in_source    TEXT        NULL,
+    scanned_at         TIMESTAMP   NOT NULL DEFAULT now(),
+    created_at         TIMESTAMP   NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_ticket_scans_ticket_instance_id ON ticket_scans (ticket_instance_id);
+CREATE INDEX index_ticket_scans_event_id ON ticket_scans (event_id);
+
+-- Redeemed tickets are inside the event until they are checked out
+INSERT INTO ticket_scans (ticket_instance_id, event_id, scan_type, scanned_by_user_id, check_in_source, scanned_at)
+SELECT ti.id, tt.event_id, 'CheckIn', ti.redeemed_by_user_id, ti.check_in_source, COALESCE(ti.redeemed_at, ti.updated_at)
+FROM ticket_instances ti
+JOIN assets a ON a.id = ti.asset_id
+JOIN ticket_types tt ON tt.id = a.ticket_type_id
+WHERE ti.status = 'Redeemed';
+
+-- Number of times a ticket can re-enter after checking out, reentry_limit only applies to the Limited policy
+ALTER TABLE ticket_types
+    ADD reentry_policy TEXT NOT NULL DEFAULT 'None';
+ALTER TABLE ticket_types
+    ADD reentry_limit INTEGER NULL;
@@ -0,0 +1,10 @@
+ALTER TABLE ticket_scans
+    DROP COLUMN access_zone_id;
+
+DROP INDEX IF EXISTS index_ticket_type_access_zones_access_zone_id;
+DROP INDEX IF EXISTS index_ticket_type_access_zones_ticket_type_id_access_zone_id;
+DROP TABLE IF EXISTS ticket_type_access_zones;
+
+DROP INDEX IF EXISTS index_access_zones_event_id;
+DROP INDEX IF EXISTS index_access_zones_venue_id;
+DROP TABLE IF EXISTS access_zones;
@@ -0,0 +1,31 @@
+-- Areas or gates a ticket can open, defined for every event at a venue or for a single event
+CREATE TABLE access_zones
+(
+    id          UUID PRIMARY KEY     DEFAULT gen_random_uuid() NOT NULL,
+    venue_id    UUID        NULL REFERENCES venues (id),
+    event_id    UUID        NULL REFERENCES events (id),
+    name        TEXT        NOT NULL,
+    description TEXT        NULL,
+    created_at  TIMESTAMP   NOT NULL DEFAULT now(),
+    updated_at  TIMESTAMP   NOT NULL DEFAULT now(),
+    CONSTRAINT access_zones_venue_id_or_event_id CHECK ((venue_id IS NULL) <> (event_id IS NULL))
+);
+
+CREATE INDEX index_access_zones_venue_id ON access_zones (venue_id);
+CREATE INDEX index_access_zones_event_id ON access_zones (event_id);
+
+CREATE TABLE ticket_type_access_zones
+(
+    id             UUID PRIMARY KEY     DEFAULT gen_random_uuid() NOT NULL,
+    ticket_type_id UUID        NOT NULL REFERENCES ticket_types (id),
+    access_zone_id UUID        NOT NULL REFERENCES access_zones (id) ON DELETE CASCADE,
+    created_at     TIMESTAMP   NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP   NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_ticket_type_access_zones_ticket_type_id_access_zone_id ON ticket_type_access_zones (ticket_type_id, access_zone_id);
+CREATE INDEX index_ticket_type_access_zones_access_zone_id ON ticket_type_access_zones (access_zone_id);
+
+-- Gate the ticket was scanned at
+ALTER TABLE ticket_scans
+    ADD access_zone_id UUID NULL REFERENCES access_zones (id) ON DELETE SET NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE ticket_instances
+    DROP COLUMN redeem_secret;
+
+ALTER TABLE events
+    DROP COLUMN rotating_redeem_codes;
@@ -0,0 +1,5 @@
+ALTER TABLE events
+    ADD rotating_redeem_codes BOOLEAN NOT NULL DEFAULT 'F';
+
+ALTER TABLE ticket_instances
+    ADD redeem_secret TEXT NULL;
@@ -0,0 +1,9 @@
+ALTER TABLE organizations
+    DROP COLUMN require_mfa_for_financial_scopes;
+
+DROP INDEX IF EXISTS index_user_mfa_recovery_codes_user_id;
+DROP TABLE IF EXISTS user_mfa_recovery_codes;
+
+ALTER TABLE users
+    DROP COLUMN mfa_secret,
+    DROP COLUMN mfa_enabled_at;
@@ -0,0 +1,19 @@
+-- Time-based one-time password secret, MFA is only enforced once the secret has been confirmed
+ALTER TABLE users
+    ADD mfa_secret     TEXT      NULL,
+    ADD mfa_enabled_at TIMESTAMP NULL;
+
+CREATE TABLE user_mfa_recovery_codes
+(
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id     UUID      NOT NULL REFERENCES users (id),
+    hashed_code TEXT      NOT NULL,
+    used_at     TIMESTAMP NULL,
+    created_at  TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at  TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_user_mfa_recovery_codes_user_id ON user_mfa_recovery_codes (user_id);
+
+ALTER TABLE organizations
+    ADD require_mfa_for_financial_scopes BOOLEAN NOT NULL DEFAULT 'F';
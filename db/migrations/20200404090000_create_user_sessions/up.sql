@@ -0,0 +1,15 @@
+-- Server-side refresh token families, each refresh rotates current_token_id and presenting an
+-- already rotated token revokes the whole session
+CREATE TABLE user_sessions
+(
+    id                UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id           UUID      NOT NULL REFERENCES users (id),
+    current_token_id  UUID      NOT NULL,
+    user_agent        TEXT      NULL,
+    last_refreshed_at TIMESTAMP NOT NULL DEFAULT now(),
+    revoked_at        TIMESTAMP NULL,
+    created_at        TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at        TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_user_sessions_user_id ON user_sessions (user_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_organization_api_keys_key_hash;
+DROP INDEX IF EXISTS index_organization_api_keys_organization_id;
+DROP TABLE IF EXISTS organization_api_keys;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_report_exports_user_id;
+DROP INDEX IF EXISTS index_report_exports_organization_id;
+DROP TABLE IF EXISTS report_exports;
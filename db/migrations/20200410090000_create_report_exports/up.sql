@@ -0,0 +1,22 @@
+-- Report exports too large to build during the request, the file is kept until the download link expires
+CREATE TABLE report_exports
+(
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id     UUID      NOT NULL REFERENCES organizations (id),
+    user_id             UUID      NOT NULL REFERENCES users (id),
+    report              TEXT      NOT NULL,
+    format              TEXT      NOT NULL,
+    parameters          JSONB     NOT NULL DEFAULT '{}',
+    status              TEXT      NOT NULL DEFAULT 'Pending',
+    content             BYTEA     NULL,
+    row_count           BIGINT    NULL,
+    download_token_hash TEXT      NULL,
+    error_message       TEXT      NULL,
+    completed_at        TIMESTAMP NULL,
+    expires_at          TIMESTAMP NULL,
+    created_at          TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_report_exports_organization_id ON report_exports (organization_id);
+CREATE INDEX index_report_exports_user_id ON report_exports (user_id);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_ticket_instances_seat_id;
+ALTER TABLE ticket_instances
+    DROP seat_id;
+
+DROP TABLE IF EXISTS ticket_type_seat_tiers;
+DROP TABLE IF EXISTS seats;
+DROP TABLE IF EXISTS seat_map_sections;
+DROP TABLE IF EXISTS seat_maps;
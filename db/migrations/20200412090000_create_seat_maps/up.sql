@@ -0,0 +1,60 @@
+-- Reserved seating layouts of a venue, or of one of its stages
+CREATE TABLE seat_maps
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    venue_id   UUID      NOT NULL REFERENCES venues (id),
+    stage_id   UUID      NULL REFERENCES stages (id),
+    name       TEXT      NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_seat_maps_venue_id ON seat_maps (venue_id);
+
+-- Rank orders sections, rows and seats from best to worst for best available selection
+CREATE TABLE seat_map_sections
+(
+    id          UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    seat_map_id UUID      NOT NULL REFERENCES seat_maps (id) ON DELETE CASCADE,
+    name        TEXT      NOT NULL,
+    rank        INTEGER   NOT NULL DEFAULT 0,
+    created_at  TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at  TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_seat_map_sections_seat_map_id_name ON seat_map_sections (seat_map_id, name);
+
+CREATE TABLE seats
+(
+    id                  UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    seat_map_section_id UUID      NOT NULL REFERENCES seat_map_sections (id) ON DELETE CASCADE,
+    row_label           TEXT      NOT NULL,
+    row_rank            INTEGER   NOT NULL DEFAULT 0,
+    seat_number         TEXT      NOT NULL,
+    -- Position of the seat within its row, adjacent seats have consecutive ranks
+    seat_rank           INTEGER   NOT NULL DEFAULT 0,
+    price_tier          TEXT      NOT NULL,
+    created_at          TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at          TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_seats_seat_map_section_id_row_label_seat_number ON seats (seat_map_section_id, row_label, seat_number);
+
+-- Seats of a price tier are sold as tickets of the mapped ticket type
+CREATE TABLE ticket_type_seat_tiers
+(
+    id             UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    ticket_type_id UUID      NOT NULL REFERENCES ticket_types (id),
+    seat_map_id    UUID      NOT NULL REFERENCES seat_maps (id),
+    price_tier     TEXT      NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at     TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_ticket_type_seat_tiers_ticket_type_id ON ticket_type_seat_tiers (ticket_type_id);
+CREATE INDEX index_ticket_type_seat_tiers_seat_map_id ON ticket_type_seat_tiers (seat_map_id);
+
+ALTER TABLE ticket_instances
+    ADD seat_id UUID NULL REFERENCES seats (id);
+
+CREATE INDEX index_ticket_instances_seat_id ON ticket_instances (seat_id);
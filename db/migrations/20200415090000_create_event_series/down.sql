@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS index_ticket_types_cloned_from_ticket_type_id;
+ALTER TABLE ticket_types
+    DROP cloned_from_ticket_type_id;
+
+DROP INDEX IF EXISTS index_events_event_series_id;
+ALTER TABLE events
+    DROP event_series_id;
+
+DROP INDEX IF EXISTS index_event_series_template_event_id;
+DROP INDEX IF EXISTS index_event_series_organization_id;
+DROP TABLE IF EXISTS event_series;
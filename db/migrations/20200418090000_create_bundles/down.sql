@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS index_order_items_bundle_id;
+ALTER TABLE order_items
+    DROP bundle_id;
+
+DROP INDEX IF EXISTS index_bundle_ticket_types_ticket_type_id;
+DROP INDEX IF EXISTS index_bundle_ticket_types_bundle_id_ticket_type_id;
+DROP TABLE IF EXISTS bundle_ticket_types;
+
+DROP INDEX IF EXISTS index_bundles_organization_id;
+DROP TABLE IF EXISTS bundles;
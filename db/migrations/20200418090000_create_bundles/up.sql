@@ -0,0 +1,34 @@
+-- Products priced as one unit covering ticket types across several events, e.g. festival passes
+CREATE TABLE bundles
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    name            TEXT      NOT NULL,
+    description     TEXT      NULL,
+    price_in_cents  BIGINT    NOT NULL,
+    deleted_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_bundles_organization_id ON bundles (organization_id);
+
+-- The share of the bundle price allocated to a single ticket of the ticket type, used for settlement and refunds
+CREATE TABLE bundle_ticket_types
+(
+    id                       UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    bundle_id                UUID      NOT NULL REFERENCES bundles (id),
+    ticket_type_id           UUID      NOT NULL REFERENCES ticket_types (id),
+    quantity                 BIGINT    NOT NULL,
+    allocated_price_in_cents BIGINT    NOT NULL,
+    created_at               TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at               TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_bundle_ticket_types_bundle_id_ticket_type_id ON bundle_ticket_types (bundle_id, ticket_type_id);
+CREATE INDEX index_bundle_ticket_types_ticket_type_id ON bundle_ticket_types (ticket_type_id);
+
+ALTER TABLE order_items
+    ADD bundle_id Uuid NULL REFERENCES bundles (id);
+
+CREATE INDEX index_order_items_bundle_id ON order_items (bundle_id);
@@ -0,0 +1,16 @@
+ALTER TABLE settlement_entries
+    DROP product_id;
+
+DROP INDEX IF EXISTS index_order_items_product_variant_id;
+DROP INDEX IF EXISTS index_order_items_product_id;
+ALTER TABLE order_items
+    DROP redeemed_quantity,
+    DROP product_variant_id,
+    DROP product_id;
+
+DROP INDEX IF EXISTS index_product_variants_product_id_name;
+DROP TABLE IF EXISTS product_variants;
+
+DROP INDEX IF EXISTS index_products_event_id;
+DROP INDEX IF EXISTS index_products_organization_id;
+DROP TABLE IF EXISTS products;
@@ -0,0 +1,42 @@
+-- Non-admission products such as parking passes, drink tickets and merchandise
+CREATE TABLE products
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID      NOT NULL REFERENCES organizations (id),
+    event_id        UUID      NULL REFERENCES events (id),
+    name            TEXT      NOT NULL,
+    description     TEXT      NULL,
+    price_in_cents  BIGINT    NOT NULL,
+    -- Inventory of products without variants, products with variants track inventory per variant
+    quantity        BIGINT    NOT NULL DEFAULT 0,
+    requires_pickup BOOLEAN   NOT NULL DEFAULT 'F',
+    deleted_at      TIMESTAMP NULL,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_products_organization_id ON products (organization_id);
+CREATE INDEX index_products_event_id ON products (event_id);
+
+CREATE TABLE product_variants
+(
+    id         UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    product_id UUID      NOT NULL REFERENCES products (id),
+    name       TEXT      NOT NULL,
+    quantity   BIGINT    NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_product_variants_product_id_name ON product_variants (product_id, name);
+
+ALTER TABLE order_items
+    ADD product_id Uuid NULL REFERENCES products (id),
+    ADD product_variant_id Uuid NULL REFERENCES product_variants (id),
+    ADD redeemed_quantity BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX index_order_items_product_id ON order_items (product_id);
+CREATE INDEX index_order_items_product_variant_id ON order_items (product_variant_id);
+
+ALTER TABLE settlement_entries
+    ADD product_id Uuid NULL REFERENCES products (id);
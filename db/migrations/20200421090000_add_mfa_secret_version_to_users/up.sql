@@ -0,0 +1,3 @@
+-- Version of the master key the MFA secret is encrypted with, 0 when unencrypted
+ALTER TABLE users
+    ADD mfa_secret_version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+ALTER TABLE settlements
+    DROP COLUMN stripe_transfer_error;
+
+ALTER TABLE settlements
+    RENAME COLUMN stripe_transfer_id TO stripe_payout_id;
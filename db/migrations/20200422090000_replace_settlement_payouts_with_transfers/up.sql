@@ -0,0 +1,7 @@
+-- Settlements are paid by transferring the Stripe-collected amount to the organization's connected account
+ALTER TABLE settlements
+    RENAME COLUMN stripe_payout_id TO stripe_transfer_id;
+
+-- Error returned by Stripe for the last failed transfer attempt
+ALTER TABLE settlements
+    ADD stripe_transfer_error TEXT;
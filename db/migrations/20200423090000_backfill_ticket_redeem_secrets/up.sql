@@ -0,0 +1,11 @@
+-- Tickets issued before rotating redeem codes were added have no secret to derive codes from.
+-- Secrets are 32 base32 characters (20 bytes), each picked from a random byte.
+UPDATE ticket_instances
+SET redeem_secret = (
+    SELECT string_agg(substr('ABCDEFGHIJKLMNOPQRSTUVWXYZ234567', get_byte(r.bytes, i) % 32 + 1, 1), '' ORDER BY i)
+    -- Referencing the ticket makes the secret generated per row instead of once for the update
+    FROM (SELECT gen_random_bytes(32) AS bytes, ticket_instances.id) r,
+         generate_series(0, 31) i
+)
+WHERE redeem_key IS NOT NULL
+  AND redeem_secret IS NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE users
+    DROP COLUMN mfa_last_used_step;
+
+DROP INDEX IF EXISTS index_user_mfa_challenges_user_id;
+DROP INDEX IF EXISTS index_user_mfa_challenges_token_hash;
+DROP TABLE IF EXISTS user_mfa_challenges;
@@ -0,0 +1,18 @@
+-- Incorrect codes entered per multi-factor challenge token, kept in the database so the limit holds
+-- without the cache
+CREATE TABLE user_mfa_challenges
+(
+    id              UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id         UUID      NOT NULL REFERENCES users (id),
+    token_hash      TEXT      NOT NULL,
+    failed_attempts INTEGER   NOT NULL DEFAULT 0,
+    created_at      TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at      TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_user_mfa_challenges_token_hash ON user_mfa_challenges (token_hash);
+CREATE INDEX index_user_mfa_challenges_user_id ON user_mfa_challenges (user_id);
+
+-- Time step of the last accepted authenticator code, codes from that step or earlier are rejected
+ALTER TABLE users
+    ADD mfa_last_used_step BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+DELETE
+FROM payment_methods
+WHERE organization_id IS NOT NULL;
+
+DROP INDEX index_payment_methods_user_id_name_organization_id;
+DROP INDEX index_payment_methods_user_id_name;
+CREATE UNIQUE INDEX index_payment_methods_user_id_name ON payment_methods (user_id, name);
+
+DROP INDEX index_payment_methods_organization_id;
+ALTER TABLE payment_methods
+    DROP organization_id;
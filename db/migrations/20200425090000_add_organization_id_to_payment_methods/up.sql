@@ -0,0 +1,10 @@
+-- Braintree vaults cards with each organization's own gateway, so those payment methods are stored per
+-- organization. Payment methods of site wide processors keep a NULL organization.
+ALTER TABLE payment_methods
+    ADD organization_id UUID NULL REFERENCES organizations (id);
+
+CREATE INDEX index_payment_methods_organization_id ON payment_methods (organization_id);
+
+DROP INDEX index_payment_methods_user_id_name;
+CREATE UNIQUE INDEX index_payment_methods_user_id_name ON payment_methods (user_id, name) WHERE organization_id IS NULL;
+CREATE UNIQUE INDEX index_payment_methods_user_id_name_organization_id ON payment_methods (user_id, name, organization_id) WHERE organization_id IS NOT NULL;
@@ -21,12 +21,14 @@ extern crate diesel;
 embed_migrations!("./migrations");
 
 use bigneon_db::prelude::*;
+use bigneon_db::utils::encryption::KeyRing;
 use chrono::prelude::Utc;
 use clap::ArgMatches;
 use clap::{App, Arg, SubCommand};
 use diesel::connection::SimpleConnection;
 use diesel::pg::PgConnection;
 use diesel::Connection;
+use std::env;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::path::Path;
@@ -334,8 +336,11 @@ fn create_user(
     db_connection: &PgConnection,
 ) {
     println!("Creating user");
+    let wallet_encryption_keys = env::var("WALLET_ENCRYPTION_KEYS").expect("WALLET_ENCRYPTION_KEYS must be defined");
+    let wallet_encryption_keys = KeyRing::parse(&wallet_encryption_keys)
+        .expect("WALLET_ENCRYPTION_KEYS must be comma separated version:key pairs");
     let user = User::create(Some(first_name), Some(last_name), Some(email), Some(phone), &password)
-        .commit(None, &db_connection)
+        .commit(None, &wallet_encryption_keys, &db_connection)
         .expect("Failed to create system admin");
     let user = user
         .add_role(Roles::Admin, &db_connection)
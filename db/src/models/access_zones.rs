@@ -0,0 +1,144 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{self, exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{access_zones, ticket_type_access_zones};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "access_zones"]
+pub struct AccessZone {
+    pub id: Uuid,
+    pub venue_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "access_zones"]
+pub struct AccessZoneEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub description: Option<Option<String>>,
+}
+
+#[derive(Clone, Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "access_zones"]
+pub struct NewAccessZone {
+    pub venue_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl NewAccessZone {
+    pub fn commit(&self, conn: &PgConnection) -> Result<AccessZone, DatabaseError> {
+        diesel::insert_into(access_zones::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create access zone")
+    }
+}
+
+impl AccessZone {
+    pub fn create_for_venue(venue_id: Uuid, name: String, description: Option<String>) -> NewAccessZone {
+        NewAccessZone {
+            venue_id: Some(venue_id),
+            event_id: None,
+            name,
+            description,
+        }
+    }
+
+    pub fn create_for_event(event_id: Uuid, name: String, description: Option<String>) -> NewAccessZone {
+        NewAccessZone {
+            venue_id: None,
+            event_id: Some(event_id),
+            name,
+            description,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AccessZone, DatabaseError> {
+        access_zones::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load access zone")
+    }
+
+    pub fn find_by_venue_id(venue_id: Uuid, conn: &PgConnection) -> Result<Vec<AccessZone>, DatabaseError> {
+        access_zones::table
+            .filter(access_zones::venue_id.eq(venue_id))
+            .order_by(access_zones::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load access zones for venue")
+    }
+
+    /// Zones defined for the event along with the zones of the venue it takes place at
+    pub fn find_for_event(event: &Event, conn: &PgConnection) -> Result<Vec<AccessZone>, DatabaseError> {
+        access_zones::table
+            .filter(
+                access_zones::event_id
+                    .eq(event.id)
+                    .or(access_zones::venue_id.eq(event.venue_id)),
+            )
+            .order_by(access_zones::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load access zones for event")
+    }
+
+    pub fn find_for_ticket_type(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<AccessZone>, DatabaseError> {
+        access_zones::table
+            .inner_join(ticket_type_access_zones::table)
+            .filter(ticket_type_access_zones::ticket_type_id.eq(ticket_type_id))
+            .select(access_zones::all_columns)
+            .order_by(access_zones::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load access zones for ticket type")
+    }
+
+    pub fn is_for_event(&self, event: &Event) -> bool {
+        self.event_id == Some(event.id) || (self.venue_id.is_some() && self.venue_id == event.venue_id)
+    }
+
+    /// Tickets only open the zones explicitly assigned to their ticket type
+    pub fn grants_access(&self, ticket_type_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            ticket_type_access_zones::table
+                .filter(ticket_type_access_zones::access_zone_id.eq(self.id))
+                .filter(ticket_type_access_zones::ticket_type_id.eq(ticket_type_id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check access zone for ticket type")
+    }
+
+    pub fn update(
+        &self,
+        attributes: AccessZoneEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<AccessZone, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not update access zone",
+            diesel::update(self)
+                .set((attributes, access_zones::updated_at.eq(dsl::now)))
+                .get_result(conn),
+        )
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete access zone",
+            diesel::delete(self).execute(conn),
+        )
+    }
+}
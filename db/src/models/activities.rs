@@ -785,6 +785,12 @@ impl ActivityItem {
             pub company_fee_in_cents: i64,
             pub client_fee_in_cents: i64,
             pub refunded_quantity: i64,
+            pub tax_rate_id: Option<Uuid>,
+            pub tax_in_cents: i64,
+            pub bundle_id: Option<Uuid>,
+            pub product_id: Option<Uuid>,
+            pub product_variant_id: Option<Uuid>,
+            pub redeemed_quantity: i64,
         };
 
         let refund_ids: Vec<Uuid> = refund_data.iter().map(|r| r.refund_id).collect();
@@ -814,6 +820,12 @@ impl ActivityItem {
                 order_items::company_fee_in_cents,
                 order_items::client_fee_in_cents,
                 order_items::refunded_quantity,
+                order_items::tax_rate_id,
+                order_items::tax_in_cents,
+                order_items::bundle_id,
+                order_items::product_id,
+                order_items::product_variant_id,
+                order_items::redeemed_quantity,
             ))
             .order_by(refunds::id)
             .load(conn)
@@ -849,6 +861,12 @@ impl ActivityItem {
                     company_fee_in_cents: item.company_fee_in_cents,
                     client_fee_in_cents: item.client_fee_in_cents,
                     refunded_quantity: item.refunded_quantity,
+                    tax_rate_id: item.tax_rate_id,
+                    tax_in_cents: item.tax_in_cents,
+                    bundle_id: item.bundle_id,
+                    product_id: item.product_id,
+                    product_variant_id: item.product_variant_id,
+                    redeemed_quantity: item.redeemed_quantity,
                 };
                 refund_items.push(RefundActivityItem {
                     id: item.id,
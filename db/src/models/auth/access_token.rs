@@ -13,6 +13,12 @@ pub struct AccessToken {
     #[serde(default)]
     pub scopes: Option<Vec<String>>,
     pub issued: u64,
+    /// Session a refresh token belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Refresh token id, only the session's current token id can be used to refresh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<Uuid>,
 }
 
 impl AccessToken {
@@ -28,6 +34,8 @@ impl AccessToken {
             exp,
             scopes: None,
             issued,
+            sid: None,
+            jti: None,
         }
     }
 
@@ -42,9 +50,25 @@ impl AccessToken {
             exp,
             scopes: Some(scopes.into_iter().map(|s| s.to_string()).collect_vec()),
             issued,
+            sid: None,
+            jti: None,
         }
     }
 
+    pub fn new_refresh_token(
+        user_id: Uuid,
+        issuer: String,
+        expiry_in_minutes: i64,
+        session_id: Uuid,
+        token_id: Uuid,
+    ) -> Self {
+        let mut access_token =
+            AccessToken::new_limited_scope(user_id, issuer, expiry_in_minutes, vec![Scopes::TokenRefresh]);
+        access_token.sid = Some(session_id);
+        access_token.jti = Some(token_id);
+        access_token
+    }
+
     pub fn get_id(&self) -> Result<Uuid, ParseError> {
         Ok(Uuid::parse_str(&self.sub)?)
     }
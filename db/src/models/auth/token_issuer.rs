@@ -11,4 +11,11 @@ pub trait TokenIssuer {
     fn issue(&self, user_id: Uuid, expires: Duration) -> Result<String, Error>;
     fn issue_with_limited_scopes(&self, user_id: Uuid, scopes: Vec<Scopes>, expires: Duration)
         -> Result<String, Error>;
+    fn issue_refresh_token(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        token_id: Uuid,
+        expires: Duration,
+    ) -> Result<String, Error>;
 }
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::{Bundle, TicketType};
+use schema::bundle_ticket_types;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Bundle)]
+#[belongs_to(TicketType)]
+#[table_name = "bundle_ticket_types"]
+pub struct BundleTicketType {
+    pub id: Uuid,
+    pub bundle_id: Uuid,
+    pub ticket_type_id: Uuid,
+    /// Tickets of the ticket type included in one unit of the bundle
+    pub quantity: i64,
+    /// Share of the bundle price allocated to a single ticket of the ticket type
+    pub allocated_price_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "bundle_ticket_types"]
+pub(crate) struct NewBundleTicketType {
+    pub bundle_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub quantity: i64,
+    pub allocated_price_in_cents: i64,
+}
+
+impl NewBundleTicketType {
+    pub(crate) fn commit(&self, conn: &PgConnection) -> Result<BundleTicketType, DatabaseError> {
+        diesel::insert_into(bundle_ticket_types::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add ticket type to bundle")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct UpdateBundleTicketType {
+    pub ticket_type_id: Uuid,
+    pub quantity: u32,
+}
+
+impl BundleTicketType {
+    pub fn find_for_bundle(bundle_id: Uuid, conn: &PgConnection) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        bundle_ticket_types::table
+            .filter(bundle_ticket_types::bundle_id.eq(bundle_id))
+            .order_by(bundle_ticket_types::created_at)
+            .then_order_by(bundle_ticket_types::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types for bundle")
+    }
+
+    pub(crate) fn destroy_for_bundle(bundle_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(bundle_ticket_types::table.filter(bundle_ticket_types::bundle_id.eq(bundle_id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove ticket types from bundle")
+    }
+
+    pub(crate) fn update_allocated_price(
+        &self,
+        allocated_price_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<BundleTicketType, DatabaseError> {
+        diesel::update(self)
+            .set((
+                bundle_ticket_types::allocated_price_in_cents.eq(allocated_price_in_cents),
+                bundle_ticket_types::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update bundle ticket type")
+    }
+}
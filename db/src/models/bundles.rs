@@ -0,0 +1,448 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl::{self, exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{bundles, order_items, orders};
+use serde_with::rust::double_option;
+use std::cmp;
+use std::collections::HashSet;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::*;
+use validators::{self, *};
+
+/// A product priced as one unit that covers ticket types across several events, e.g. a festival pass
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "bundles"]
+pub struct Bundle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "bundles"]
+pub struct NewBundle {
+    pub organization_id: Uuid,
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Serialize, Validate)]
+#[table_name = "bundles"]
+pub struct BundleEditableAttributes {
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayBundle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    /// Units of the bundle that can still be bought
+    pub available: u32,
+    pub ticket_types: Vec<DisplayBundleTicketType>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayBundleTicketType {
+    pub ticket_type_id: Uuid,
+    pub ticket_type_name: String,
+    pub event_id: Uuid,
+    pub event_name: String,
+    pub event_start: Option<NaiveDateTime>,
+    pub quantity: i64,
+    pub allocated_price_in_cents: i64,
+}
+
+impl NewBundle {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Bundle, DatabaseError> {
+        let mut validation_errors = match self.validate() {
+            Ok(_) => Ok(()),
+            Err(errors) => Err(errors),
+        };
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "price_in_cents",
+            Bundle::price_valid(self.price_in_cents),
+        );
+        validation_errors?;
+
+        let bundle: Bundle = diesel::insert_into(bundles::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create bundle")?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleCreated,
+            format!("Bundle '{}' created", &bundle.name),
+            Tables::Bundles,
+            Some(bundle.id),
+            current_user_id,
+            Some(json!(&bundle)),
+        )
+        .commit(conn)?;
+
+        Ok(bundle)
+    }
+}
+
+impl Bundle {
+    pub fn create(organization_id: Uuid, name: String, description: Option<String>, price_in_cents: i64) -> NewBundle {
+        NewBundle {
+            organization_id,
+            name,
+            description,
+            price_in_cents,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Bundle, DatabaseError> {
+        bundles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundle")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<Bundle>, DatabaseError> {
+        bundles::table
+            .filter(bundles::organization_id.eq(organization_id))
+            .filter(bundles::deleted_at.is_null())
+            .order_by(bundles::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundles for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn ticket_types(&self, conn: &PgConnection) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        BundleTicketType::find_for_bundle(self.id, conn)
+    }
+
+    /// Whether the bundle is part of a paid order, its ticket types are fixed from then on so refunds
+    /// can be checked against them
+    pub fn has_been_sold(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            order_items::table
+                .inner_join(orders::table.on(orders::id.eq(order_items::order_id)))
+                .filter(order_items::bundle_id.eq(self.id))
+                .filter(orders::status.eq(OrderStatus::Paid)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if bundle has been sold")
+    }
+
+    pub fn update(
+        &self,
+        attributes: BundleEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Bundle, DatabaseError> {
+        let mut validation_errors = match attributes.validate() {
+            Ok(_) => Ok(()),
+            Err(errors) => Err(errors),
+        };
+        if let Some(price_in_cents) = attributes.price_in_cents {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "price_in_cents",
+                Bundle::price_valid(price_in_cents),
+            );
+        }
+        validation_errors?;
+        if attributes
+            .price_in_cents
+            .map(|price| price != self.price_in_cents)
+            .unwrap_or(false)
+            && self.has_been_sold(conn)?
+        {
+            return DatabaseError::business_process_error("Price can not be changed once the bundle has been sold");
+        }
+
+        let bundle: Bundle = diesel::update(self)
+            .set((&attributes, bundles::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update bundle")?;
+        if bundle.price_in_cents != self.price_in_cents {
+            bundle.allocate_prices(conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::BundleUpdated,
+            format!("Bundle '{}' updated", &bundle.name),
+            Tables::Bundles,
+            Some(bundle.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(bundle)
+    }
+
+    /// Replaces the ticket types included in the bundle and allocates the bundle price across them
+    pub fn update_ticket_types(
+        &self,
+        ticket_types: &[UpdateBundleTicketType],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        if self.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Bundle has been deleted");
+        }
+        if ticket_types.is_empty() {
+            return DatabaseError::validation_error("ticket_types", "A bundle must include at least one ticket type");
+        }
+        if self.has_been_sold(conn)? {
+            return DatabaseError::business_process_error(
+                "Ticket types can not be changed once the bundle has been sold",
+            );
+        }
+
+        let mut validation_errors: Result<(), ValidationErrors> = Ok(());
+        let mut ticket_type_ids = HashSet::new();
+        let mut currencies = HashSet::new();
+        for item in ticket_types {
+            if !ticket_type_ids.insert(item.ticket_type_id) {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "ticket_type_id",
+                    Err(create_validation_error(
+                        "duplicate",
+                        "Ticket type can only be included in a bundle once",
+                    )),
+                );
+                continue;
+            }
+
+            let ticket_type = TicketType::find(item.ticket_type_id, conn)?;
+            let event = ticket_type.event(conn)?;
+            if event.organization_id != self.organization_id {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "ticket_type_id",
+                    Err(create_validation_error(
+                        "invalid_organization",
+                        "Ticket type must belong to an event of the bundle's organization",
+                    )),
+                );
+            }
+            if ticket_type.status == TicketTypeStatus::Cancelled || ticket_type.deleted_at.is_some() {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "ticket_type_id",
+                    Err(create_validation_error(
+                        "unavailable",
+                        "Ticket type is no longer available",
+                    )),
+                );
+            }
+            if item.quantity == 0 || item.quantity as i32 % ticket_type.increment != 0 {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "quantity",
+                    Err(create_validation_error(
+                        "quantity_invalid_increment",
+                        "Quantity must be a positive multiple of the ticket type increment",
+                    )),
+                );
+            }
+            currencies.insert(event.currency);
+        }
+        validation_errors?;
+        if currencies.len() > 1 {
+            return DatabaseError::validation_error(
+                "ticket_types",
+                "Ticket types of a bundle must be sold in the same currency",
+            );
+        }
+
+        BundleTicketType::destroy_for_bundle(self.id, conn)?;
+        for item in ticket_types {
+            NewBundleTicketType {
+                bundle_id: self.id,
+                ticket_type_id: item.ticket_type_id,
+                quantity: item.quantity as i64,
+                allocated_price_in_cents: 0,
+            }
+            .commit(conn)?;
+        }
+        let bundle_ticket_types = self.allocate_prices(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleUpdated,
+            format!("Bundle '{}' ticket types updated", &self.name),
+            Tables::Bundles,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "ticket_types": ticket_types })),
+        )
+        .commit(conn)?;
+
+        Ok(bundle_ticket_types)
+    }
+
+    pub fn delete(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                bundles::deleted_at.eq(dsl::now.nullable()),
+                bundles::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete bundle")?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleDeleted,
+            format!("Bundle '{}' deleted", &self.name),
+            Tables::Bundles,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&self)),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Units of the bundle that can be bought, limited by the ticket type with the fewest tickets left
+    pub fn available(&self, conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let mut available: Option<u32> = None;
+        for bundle_ticket_type in self.ticket_types(conn)? {
+            let ticket_type = TicketType::find(bundle_ticket_type.ticket_type_id, conn)?;
+            let units = ticket_type.valid_available_ticket_count(conn)? / bundle_ticket_type.quantity as u32;
+            available = Some(available.map(|a| cmp::min(a, units)).unwrap_or(units));
+        }
+
+        Ok(available.unwrap_or(0))
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayBundle, DatabaseError> {
+        let mut ticket_types = Vec::new();
+        for bundle_ticket_type in self.ticket_types(conn)? {
+            let ticket_type = TicketType::find(bundle_ticket_type.ticket_type_id, conn)?;
+            let event = ticket_type.event(conn)?;
+            ticket_types.push(DisplayBundleTicketType {
+                ticket_type_id: ticket_type.id,
+                ticket_type_name: ticket_type.name,
+                event_id: event.id,
+                event_name: event.name,
+                event_start: event.event_start,
+                quantity: bundle_ticket_type.quantity,
+                allocated_price_in_cents: bundle_ticket_type.allocated_price_in_cents,
+            });
+        }
+
+        Ok(DisplayBundle {
+            id: self.id,
+            organization_id: self.organization_id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            price_in_cents: self.price_in_cents,
+            available: self.available(conn)?,
+            ticket_types,
+        })
+    }
+
+    /// Splits the bundle price across its tickets in proportion to the face value of each ticket type,
+    /// or evenly when the ticket types are free. `components` are the quantity and face value of each
+    /// ticket type, the price allocated to a single ticket of each is returned. Returns `None` if the
+    /// price can not be split exactly between the tickets.
+    pub fn allocate_price(price_in_cents: i64, components: &[(i64, i64)]) -> Option<Vec<i64>> {
+        let total_quantity: i64 = components.iter().map(|&(quantity, _)| quantity).sum();
+        if total_quantity <= 0 {
+            return None;
+        }
+        let total_face_value: i64 = components
+            .iter()
+            .map(|&(quantity, face_value)| quantity * face_value)
+            .sum();
+
+        let mut allocations: Vec<i64> = components
+            .iter()
+            .map(|&(_, face_value)| {
+                if total_face_value > 0 {
+                    price_in_cents * face_value / total_face_value
+                } else {
+                    price_in_cents / total_quantity
+                }
+            })
+            .collect();
+        let mut remainder = price_in_cents
+            - components
+                .iter()
+                .zip(allocations.iter())
+                .map(|(&(quantity, _), allocation)| quantity * allocation)
+                .sum::<i64>();
+
+        // Left over cents go to the components with the most tickets first so a component with a
+        // single ticket can take whatever remains
+        let mut indexes: Vec<usize> = (0..components.len()).collect();
+        indexes.sort_by_key(|&i| -components[i].0);
+        for i in indexes {
+            let quantity = components[i].0;
+            let extra = remainder / quantity;
+            allocations[i] += extra;
+            remainder -= extra * quantity;
+        }
+
+        if remainder != 0 {
+            return None;
+        }
+        Some(allocations)
+    }
+
+    fn allocate_prices(&self, conn: &PgConnection) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        let bundle_ticket_types = self.ticket_types(conn)?;
+        let mut components = Vec::new();
+        for bundle_ticket_type in &bundle_ticket_types {
+            let ticket_type = TicketType::find(bundle_ticket_type.ticket_type_id, conn)?;
+            components.push((bundle_ticket_type.quantity, ticket_type.price_in_cents));
+        }
+        if components.is_empty() {
+            return Ok(bundle_ticket_types);
+        }
+
+        let allocations = match Bundle::allocate_price(self.price_in_cents, &components) {
+            Some(allocations) => allocations,
+            None => {
+                return DatabaseError::validation_error(
+                    "price_in_cents",
+                    "Bundle price can not be split evenly across its tickets",
+                )
+            }
+        };
+        let mut result = Vec::new();
+        for (bundle_ticket_type, allocated_price_in_cents) in bundle_ticket_types.iter().zip(allocations) {
+            result.push(bundle_ticket_type.update_allocated_price(allocated_price_in_cents, conn)?);
+        }
+
+        Ok(result)
+    }
+
+    fn price_valid(price_in_cents: i64) -> Result<(), ValidationError> {
+        if price_in_cents < 0 {
+            return Err(create_validation_error(
+                "invalid_price",
+                "Price must be zero or greater",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -472,6 +472,13 @@ impl Code {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve code")
     }
 
+    pub fn find_including_deleted(id: Uuid, conn: &PgConnection) -> Result<Code, DatabaseError> {
+        codes::table
+            .filter(codes::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve code")
+    }
+
     pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<usize, DatabaseError> {
         let result = diesel::update(codes::table.filter(codes::id.eq(self.id)))
             .set((codes::deleted_at.eq(dsl::now), codes::updated_at.eq(dsl::now)))
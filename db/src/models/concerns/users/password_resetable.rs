@@ -2,7 +2,7 @@ use chrono::{NaiveDateTime, Utc};
 use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
-use models::User;
+use models::{User, UserSession};
 use schema::users;
 use utils::errors::{DatabaseError, ErrorCode};
 use utils::passwords::PasswordHash;
@@ -35,7 +35,7 @@ impl PasswordResetable for User {
             let hash = PasswordHash::generate(password, None);
             let now = Utc::now().naive_utc();
 
-            DatabaseError::wrap(
+            let user: User = DatabaseError::wrap(
                 ErrorCode::UpdateError,
                 "Could not save new password for user",
                 diesel::update(users.filter(id.eq(user.id)))
@@ -49,7 +49,11 @@ impl PasswordResetable for User {
                         },
                     ))
                     .get_result(conn),
-            )
+            )?;
+
+            // Sessions started with the old password are no longer trusted
+            UserSession::revoke_all_for_user(user.id, conn)?;
+            Ok(user)
         } else {
             Err(DatabaseError::new(
                 ErrorCode::InternalError,
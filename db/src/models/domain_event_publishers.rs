@@ -8,7 +8,8 @@ use serde_json::Value;
 use std::hash::{Hash, Hasher};
 use utils::errors::*;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{validate_url, Validate, ValidationError};
+use validators::{self, *};
 
 pub static SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTypes] = &[
     DomainEventTypes::TransferTicketStarted,
@@ -21,9 +22,42 @@ pub static SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTyp
     DomainEventTypes::OrderRetargetingEmailTriggered,
     DomainEventTypes::TemporaryUserCreated,
     DomainEventTypes::PushNotificationTokenCreated,
+    DomainEventTypes::EventCreated,
+    DomainEventTypes::EventUpdated,
+    DomainEventTypes::EventPublished,
+    DomainEventTypes::EventUnpublished,
+    DomainEventTypes::EventCancelled,
+    DomainEventTypes::EventDeleted,
+    DomainEventTypes::TicketTypeCreated,
+    DomainEventTypes::TicketTypeUpdated,
+    DomainEventTypes::TicketTypeSalesStarted,
+    DomainEventTypes::TicketTypeSoldOut,
+    DomainEventTypes::HoldCreated,
+    DomainEventTypes::HoldDeleted,
+    DomainEventTypes::HoldQuantityChanged,
+    DomainEventTypes::HoldAutomaticallyReleased,
+    DomainEventTypes::CodeCreated,
+    DomainEventTypes::CodeUpdated,
+    DomainEventTypes::CodeDeleted,
+    DomainEventTypes::TicketInstanceRedeemed,
 ];
 
-#[derive(Clone, Debug, Serialize, Identifiable, Queryable, QueryableByName)]
+/// Event types raised on users, orders and transfers. Their webhook payloads contain the customer's
+/// contact details and sign in links so they are only published to system publishers.
+pub static CUSTOMER_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTypes] = &[
+    DomainEventTypes::OrderCompleted,
+    DomainEventTypes::OrderRefund,
+    DomainEventTypes::OrderResendConfirmationTriggered,
+    DomainEventTypes::OrderRetargetingEmailTriggered,
+    DomainEventTypes::PushNotificationTokenCreated,
+    DomainEventTypes::TemporaryUserCreated,
+    DomainEventTypes::TransferTicketCancelled,
+    DomainEventTypes::TransferTicketCompleted,
+    DomainEventTypes::TransferTicketStarted,
+    DomainEventTypes::UserCreated,
+];
+
+#[derive(Clone, Debug, Deserialize, Serialize, Identifiable, Queryable, QueryableByName)]
 #[table_name = "domain_event_publishers"]
 pub struct DomainEventPublisher {
     pub id: Uuid,
@@ -38,6 +72,9 @@ pub struct DomainEventPublisher {
     pub adapter: Option<WebhookAdapters>,
     pub adapter_config: Option<Value>,
     pub blocked_until: NaiveDateTime,
+    #[serde(skip_serializing, default)]
+    pub webhook_secret: String,
+    pub paused_at: Option<NaiveDateTime>,
 }
 
 impl Eq for DomainEventPublisher {}
@@ -60,6 +97,7 @@ pub struct DomainEventPublisherEditableAttributes {
     #[validate(url(message = "Webhook URL is invalid"))]
     pub webhook_url: Option<String>,
     pub import_historic_events: Option<bool>,
+    pub event_types: Option<Vec<DomainEventTypes>>,
 }
 
 impl DomainEventPublisher {
@@ -71,6 +109,43 @@ impl DomainEventPublisher {
             .to_db_error(ErrorCode::QueryError, "Could not load Domain Event Publishers")
     }
 
+    pub fn find_by_organization_id(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DomainEventPublisher>, DatabaseError> {
+        domain_event_publishers::table
+            .filter(domain_event_publishers::organization_id.eq(organization_id))
+            .filter(domain_event_publishers::deleted_at.is_null())
+            .order_by(domain_event_publishers::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load Domain Event Publishers")
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Paused publishers keep advancing through the event stream without sending webhooks
+    pub fn pause(&self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        diesel::update(self)
+            .set((
+                domain_event_publishers::paused_at.eq(dsl::now.nullable()),
+                domain_event_publishers::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not pause domain event publisher")
+    }
+
+    pub fn resume(&self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        diesel::update(self)
+            .set((
+                domain_event_publishers::paused_at.eq(None::<NaiveDateTime>),
+                domain_event_publishers::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not resume domain event publisher")
+    }
+
     pub fn delete(self, conn: &PgConnection) -> Result<(), DatabaseError> {
         diesel::update(&self)
             .set((
@@ -177,12 +252,43 @@ impl DomainEventPublisher {
         attributes: &DomainEventPublisherEditableAttributes,
         conn: &PgConnection,
     ) -> Result<DomainEventPublisher, DatabaseError> {
+        let mut validation_errors = attributes.validate();
+        if let (Some(_), Some(event_types)) = (self.organization_id, attributes.event_types.as_ref()) {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "event_types",
+                DomainEventPublisher::organization_event_types_valid(event_types),
+            );
+        }
+        validation_errors?;
+
         diesel::update(self)
             .set((attributes, domain_event_publishers::updated_at.eq(dsl::now)))
             .get_result(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not update domain event publisher")
     }
 
+    /// Organizations can subscribe to any published event type raised on records they own, only
+    /// events recorded against the publisher's organization are sent
+    pub fn organization_event_types_valid(event_types: &[DomainEventTypes]) -> Result<(), ValidationError> {
+        if event_types.is_empty() {
+            return Err(create_validation_error(
+                "required",
+                "At least one event type must be selected",
+            ));
+        }
+        if event_types.iter().any(|event_type| {
+            !SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING.contains(event_type)
+                || CUSTOMER_DOMAIN_EVENT_TYPES_FOR_PUBLISHING.contains(event_type)
+        }) {
+            return Err(create_validation_error(
+                "event_type_not_supported",
+                "Event type is not supported for organization webhooks",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn acquire_lock(&mut self, timeout: i64, conn: &PgConnection) -> Result<(), DatabaseError> {
         let timeout = Utc::now().naive_utc() + Duration::seconds(timeout);
         let result: Option<DomainEventPublisher> = diesel::update(&*self)
@@ -266,9 +372,32 @@ pub struct NewDomainEventPublisher {
 
 impl NewDomainEventPublisher {
     pub fn commit(self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        self.validate_record()?;
         diesel::insert_into(domain_event_publishers::table)
             .values(self)
             .get_result(conn)
             .to_db_error(ErrorCode::InsertError, "Could not insert domain event publisher")
     }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        // Publishers created by the system for adapters or admins are not restricted
+        if self.organization_id.is_none() || self.adapter.is_some() {
+            return Ok(());
+        }
+
+        let mut validation_errors = validators::append_validation_error(
+            self.validate(),
+            "event_types",
+            DomainEventPublisher::organization_event_types_valid(&self.event_types),
+        );
+        if !validate_url(&self.webhook_url) {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "webhook_url",
+                Err(create_validation_error("url", "Webhook URL is invalid")),
+            );
+        }
+
+        Ok(validation_errors?)
+    }
 }
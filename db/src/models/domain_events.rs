@@ -64,6 +64,7 @@ impl DomainEvent {
             main_id,
             user_id,
             created_at: None,
+            organization_id: None,
         }
     }
 
@@ -99,6 +100,47 @@ impl DomainEvent {
             .to_db_error(ErrorCode::QueryError, "Could not load domain events")
     }
 
+    /// Organization owning the record the event is raised on, used to route the event to the
+    /// organization's webhooks. Orders and transfers can span several organizations and records
+    /// such as users belong to none, events on those are never sent to organization webhooks.
+    /// Only resolved when publishing to an organization's webhook as it can take several queries.
+    pub fn owning_organization_id(&self, conn: &PgConnection) -> Result<Option<Uuid>, DatabaseError> {
+        if self.organization_id.is_some() {
+            return Ok(self.organization_id);
+        }
+        let main_id = match self.main_id {
+            Some(main_id) => main_id,
+            None => return Ok(None),
+        };
+
+        Ok(match self.main_table {
+            Tables::Organizations => Some(main_id),
+            Tables::Bundles => Bundle::find(main_id, conn).optional()?.map(|b| b.organization_id),
+            Tables::Events => Event::find_including_deleted(main_id, conn)
+                .optional()?
+                .map(|e| e.organization_id),
+            Tables::EventSeries => EventSeries::find(main_id, conn).optional()?.map(|e| e.organization_id),
+            Tables::Products => Product::find(main_id, conn).optional()?.map(|p| p.organization_id),
+            Tables::TicketTypes => match TicketType::find(main_id, conn).optional()? {
+                Some(ticket_type) => Some(ticket_type.event(conn)?.organization_id),
+                None => None,
+            },
+            Tables::Holds => match Hold::find(main_id, conn).optional()? {
+                Some(hold) => Some(hold.organization(conn)?.id),
+                None => None,
+            },
+            Tables::Codes => match Code::find_including_deleted(main_id, conn).optional()? {
+                Some(code) => Some(code.organization(conn)?.id),
+                None => None,
+            },
+            Tables::TicketInstances => match TicketInstance::find(main_id, conn).optional()? {
+                Some(ticket) => Some(ticket.organization(conn)?.id),
+                None => None,
+            },
+            _ => None,
+        })
+    }
+
     pub fn post_processing(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         if let Some(main_id) = self.main_id {
             match self.event_type {
@@ -169,6 +211,7 @@ pub struct NewDomainEvent {
     pub main_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
     pub created_at: Option<NaiveDateTime>,
+    pub organization_id: Option<Uuid>,
 }
 
 impl NewDomainEvent {
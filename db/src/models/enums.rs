@@ -104,6 +104,9 @@ define_enum! { CommunicationType [EmailTemplate, Sms, Push, Webhook]}
 define_enum! { DomainEventTypes [
     AnnouncementCreated,
     AnnouncementDeleted,
+    BundleCreated,
+    BundleDeleted,
+    BundleUpdated,
     CodeCreated,
     CodeDeleted,
     CodeUpdated,
@@ -117,6 +120,8 @@ define_enum! { DomainEventTypes [
     EventPublished,
     EventReportSubscriberCreated,
     EventReportSubscriberDeleted,
+    EventSeriesCreated,
+    EventSeriesUpdated,
     EventUpdated,
     EventUnpublished,
     ExternalLoginCreated,
@@ -130,11 +135,16 @@ define_enum! { DomainEventTypes [
     OrderBehalfOfUserChanged,
     OrderCompleted,
     OrderCreated,
+    OrderDisputeClosed,
+    OrderDisputeCreated,
+    OrderDisputeUpdated,
     OrderRefund,
     OrderResendConfirmationTriggered,
     OrderRetargetingEmailTriggered,
     OrderStatusUpdated,
     OrderUpdated,
+    OrganizationApiKeyCreated,
+    OrganizationApiKeyRevoked,
     OrganizationCreated,
     NoteCreated,
     NoteDeleted,
@@ -146,9 +156,16 @@ define_enum! { DomainEventTypes [
     PaymentMethodCreated,
     PaymentMethodUpdated,
     PaymentUpdated,
+    ProductCreated,
+    ProductDeleted,
+    ProductRedeemed,
+    ProductUpdated,
     UserCreated,
     UserDisabled,
     UserLogin,
+    UserLoginFailed,
+    UserMfaDisabled,
+    UserMfaEnabled,
     UserRegistration,
     UserUpdated,
     LostPassword,
@@ -164,9 +181,11 @@ define_enum! { DomainEventTypes [
     TemporaryUserCreated,
     TicketInstanceAddedToHold,
     TicketInstanceAddedToListing,
+    TicketInstanceCheckedOut,
     TicketInstanceNullified,
     TicketInstancePurchased,
     TicketInstanceRedeemed,
+    TicketInstanceReentered,
     TicketInstanceReleasedFromHold,
     TicketInstanceReleasedFromListing,
     TicketInstanceUpdated,
@@ -186,13 +205,23 @@ define_enum! { DomainActionTypes [
     Communication,
     FinalizeSettlements,
     PaymentProviderIPN,
+    // Builds a report export too large to return from the request and emails a download link
+    ProcessReportExport,
     ProcessSettlementReport,
+    // Dispute, refund and payment intent notifications received on /ipns/stripe
+    ProcessStripeWebhook,
     ProcessTransferDrip,
+    // Offers inventory freed on a sold out ticket type to the next people on its waitlist
+    ProcessWaitlist,
+    // Removes the files of report exports whose download link has expired
+    PurgeExpiredReportExports,
     RegenerateDripActions,
     ReleaseHoldInventory,
     RetargetAbandonedOrders,
     SendAutomaticReportEmails,
     SendPurchaseCompletedCommunication,
+    // Signed delivery of a domain event to a publisher's webhook
+    SendWebhook,
     SubmitSitemapToSearchEngines,
     UpdateGenres
 ]}
@@ -212,37 +241,43 @@ define_enum! { HistoryType [Purchase]}
 define_enum! { HoldTypes [Discount, Comp] }
 define_enum! { ListingStatus [Pending, Published] }
 define_enum! { MarketplaceAccountStatus [ Pending, Linked ]}
-define_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-define_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees]}
+define_enum! { OrderStatus [Cancelled, Draft, Paid, PendingAuthentication, PendingPayment] }
+define_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees, Tax, Products]}
 define_enum! { OrderTypes [Cart, BackOffice] }
 define_enum! { PaymentMethods [CreditCard, External, Free, Provider] }
-define_enum! { PaymentProviders [External, Globee, Free, Stripe] }
-define_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
+define_enum! { PaymentProviders [Braintree, External, Globee, Free, Stripe] }
+define_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn, RequiresAction] }
 define_enum! { PastOrUpcoming [Past,Upcoming]}
 define_enum! { Platforms [Web, App, BoxOffice]}
+define_enum! { ReentryPolicy [None, Limited, Unlimited] }
+define_enum! { ReportExportFormat [Csv, Xlsx]}
+define_enum! { ReportExportStatus [Pending, Completed, Failed]}
 define_enum! { ReportTypes [TicketCounts]}
 define_enum! { Roles [Admin, DoorPerson, OrgAdmin, OrgBoxOffice, OrgMember, OrgOwner, PrismIntegration, Promoter, PromoterReadOnly, User, Super] }
 define_enum! { SettlementStatus[PendingSettlement, FinalizedSettlement] }
 define_enum! { SettlementTypes [Rolling, PostEvent]}
 define_enum! { SettlementAdjustmentTypes [ManualCredit, ManualDeduction, Chargeback]}
-define_enum! { SettlementEntryTypes [EventFees, TicketType]}
+define_enum! { SettlementEntryTypes [EventFees, TicketType, Product]}
 define_enum! { SlugTypes[ Event, Organization, Venue, City, Genre, CityGenre ] }
 define_enum! { SortingDir[ Asc, Desc ] }
 define_enum! { SourceOrDestination [Destination,Source]}
 define_enum! { Tables [
-    Announcements, Artists, Broadcasts, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, ExternalLogins, FeeSchedules,
-    Holds, Orders, Organizations, Notes, Payments, PaymentMethods, PushNotificationTokens, TemporaryUsers, TicketInstances, TicketTypes,
-    TicketPricing, Transfers, Users, Venues, Genres
+    Announcements, Artists, Broadcasts, Bundles, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, EventSeries, ExternalLogins, FeeSchedules,
+    Holds, Orders, Organizations, Notes, Payments, PaymentMethods, Products, PushNotificationTokens, ReportExports, TemporaryUsers, TicketInstances, TicketTypes,
+    TicketPricing, Transfers, Users, Venues, Genres, WebhookDeliveries
 ] }
 define_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 define_enum! { TicketPricingStatus [Published, Deleted, Default] }
+define_enum! { TicketScanType [CheckIn, CheckOut] }
 define_enum! { TicketTypeEndDateType [DoorTime, EventEnd, EventStart, Manual] }
 define_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, OnSaleSoon, SaleEnded, Cancelled, Deleted] }
 define_enum! { TicketTypeType [ Token, LootBox ]}
 define_enum! { TicketTypeVisibility [ Always, Hidden, WhenAvailable ]}
 define_enum! { TransferMessageType [Email, Phone] }
 define_enum! { TransferStatus [Pending, Cancelled, Completed, EventEnded] }
+define_enum! { WaitlistEntryStatus [Waiting, Offered, Purchased, Expired, Cancelled]}
 define_enum! { WebhookAdapters [CustomerIo]}
+define_enum! { WebhookDeliveryStatus [Pending, Delivered, Failed]}
 
 impl Roles {
     pub fn get_event_limited_roles() -> Vec<Roles> {
@@ -292,6 +327,22 @@ impl Tables {
     }
 }
 
+impl ReportExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ReportExportFormat::Csv => "text/csv",
+            ReportExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportExportFormat::Csv => "csv",
+            ReportExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
 impl OrderItemTypes {
     pub fn is_fee(self) -> bool {
         self == OrderItemTypes::PerUnitFees
@@ -0,0 +1,349 @@
+use chrono::prelude::*;
+use chrono_tz::Tz;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_series, events, ticket_types};
+use utils::errors::*;
+use utils::recurrence::RecurrenceRule;
+use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
+use validators::{self, *};
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "event_series"]
+pub struct EventSeries {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_rule: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "event_series"]
+pub struct NewEventSeries {
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: String,
+    pub recurrence_rule: String,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Serialize, Validate)]
+#[table_name = "event_series"]
+pub struct EventSeriesEditableAttributes {
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: Option<String>,
+    pub recurrence_rule: Option<String>,
+}
+
+impl NewEventSeries {
+    /// Creates the series and makes the template event its first occurrence. Remaining occurrences
+    /// are created by `EventSeries::generate_occurrences`.
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        self.validate()?;
+        let template = Event::find(self.template_event_id, conn)?;
+        if template.organization_id != self.organization_id {
+            return DatabaseError::validation_error(
+                "template_event_id",
+                "Template event must belong to the organization",
+            );
+        }
+        if template.event_series_id.is_some() {
+            return DatabaseError::validation_error("template_event_id", "Event is already part of a series");
+        }
+        EventSeries::expand_rule(&self.recurrence_rule, &template, conn)?;
+
+        let event_series: EventSeries = diesel::insert_into(event_series::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event series")?;
+
+        diesel::update(&template)
+            .set((
+                events::event_series_id.eq(event_series.id),
+                events::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add event to series")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesCreated,
+            format!("Event series '{}' created", &event_series.name),
+            Tables::EventSeries,
+            Some(event_series.id),
+            current_user_id,
+            Some(json!(&event_series)),
+        )
+        .commit(conn)?;
+
+        Ok(event_series)
+    }
+}
+
+impl EventSeries {
+    pub fn create(
+        organization_id: Uuid,
+        template_event_id: Uuid,
+        name: String,
+        recurrence_rule: String,
+    ) -> NewEventSeries {
+        NewEventSeries {
+            organization_id,
+            template_event_id,
+            name,
+            recurrence_rule: recurrence_rule.trim().to_uppercase(),
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        event_series::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event series")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventSeries>, DatabaseError> {
+        event_series::table
+            .filter(event_series::organization_id.eq(organization_id))
+            .order_by(event_series::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event series for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn template_event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.template_event_id, conn)
+    }
+
+    /// Occurrences of the series ordered by start, including the template event
+    pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .order_by(events::event_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for event series")
+    }
+
+    /// Occurrences that have not started or been cancelled, bulk edits are applied to these
+    pub fn upcoming_events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .filter(events::cancelled_at.is_null())
+            .filter(events::event_start.gt(Utc::now().naive_utc()))
+            .order_by(events::event_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load upcoming events for event series")
+    }
+
+    /// Start of every occurrence in the recurrence rule in UTC
+    pub fn occurrence_starts(&self, conn: &PgConnection) -> Result<Vec<NaiveDateTime>, DatabaseError> {
+        EventSeries::expand_rule(&self.recurrence_rule, &self.template_event(conn)?, conn)
+    }
+
+    /// Creates an occurrence for every upcoming start in the recurrence rule that does not have one yet.
+    /// Occurrences are never removed here, shortening a rule leaves existing occurrences in place.
+    pub fn generate_occurrences(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let template = self.template_event(conn)?;
+        let starts = EventSeries::expand_rule(&self.recurrence_rule, &template, conn)?;
+        // Deleted occurrences are included so they are not recreated
+        let existing_starts: Vec<Option<NaiveDateTime>> = events::table
+            .filter(events::event_series_id.eq(self.id))
+            .select(events::event_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for event series")?;
+
+        let now = Utc::now().naive_utc();
+        let mut occurrences = Vec::new();
+        for start in starts {
+            if start <= now || existing_starts.contains(&Some(start)) {
+                continue;
+            }
+            occurrences.push(template.clone_for_series(self, start, current_user_id, conn)?);
+        }
+
+        Ok(occurrences)
+    }
+
+    pub fn update(
+        &self,
+        current_user_id: Option<Uuid>,
+        attributes: EventSeriesEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<EventSeries, DatabaseError> {
+        attributes.validate()?;
+        let mut attributes = attributes;
+        if let Some(recurrence_rule) = attributes.recurrence_rule.take() {
+            let recurrence_rule = recurrence_rule.trim().to_uppercase();
+            EventSeries::expand_rule(&recurrence_rule, &self.template_event(conn)?, conn)?;
+            attributes.recurrence_rule = Some(recurrence_rule);
+        }
+
+        let result: EventSeries = diesel::update(self)
+            .set((&attributes, event_series::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update event series")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesUpdated,
+            format!("Event series '{}' updated", &self.name),
+            Tables::EventSeries,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Applies the changes to every upcoming occurrence, including the template until it has taken
+    /// place. Dates are set by the recurrence rule so they can only be changed on individual occurrences.
+    pub fn update_events(
+        &self,
+        current_user_id: Option<Uuid>,
+        attributes: EventEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let date_fields = [
+            ("event_start", attributes.event_start.is_some()),
+            ("event_end", attributes.event_end.is_some()),
+            ("door_time", attributes.door_time.is_some()),
+            ("publish_date", attributes.publish_date.is_some()),
+            ("redeem_date", attributes.redeem_date.is_some()),
+            ("cancelled_at", attributes.cancelled_at.is_some()),
+        ];
+        EventSeries::reject_date_fields(&date_fields)?;
+
+        let mut updated_events = Vec::new();
+        for event in self.events_for_bulk_update(conn)? {
+            updated_events.push(event.update(current_user_id, attributes.clone(), conn)?);
+        }
+
+        Ok(updated_events)
+    }
+
+    /// Applies the changes to the ticket types cloned from a ticket type of the template event on every
+    /// upcoming occurrence. The template's own ticket type is only changed while the template is upcoming.
+    pub fn update_ticket_types(
+        &self,
+        ticket_type_id: Uuid,
+        current_user_id: Option<Uuid>,
+        attributes: TicketTypeEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketType>, DatabaseError> {
+        let date_fields = [
+            ("start_date", attributes.start_date.is_some()),
+            ("end_date", attributes.end_date.is_some()),
+            ("parent_id", attributes.parent_id.is_some()),
+        ];
+        EventSeries::reject_date_fields(&date_fields)?;
+
+        let template_ticket_type = TicketType::find(ticket_type_id, conn)?;
+        if template_ticket_type.event_id != self.template_event_id {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Ticket type must belong to the template event of the series",
+            );
+        }
+
+        let event_ids: Vec<Uuid> = self.events_for_bulk_update(conn)?.iter().map(|e| e.id).collect();
+        let template_is_upcoming = event_ids.contains(&self.template_event_id);
+        let cloned_ticket_types: Vec<TicketType> = ticket_types::table
+            .filter(ticket_types::cloned_from_ticket_type_id.eq(template_ticket_type.id))
+            .filter(ticket_types::event_id.eq_any(event_ids))
+            .filter(ticket_types::deleted_at.is_null())
+            .order_by(ticket_types::event_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types for event series")?;
+
+        let mut updated_ticket_types = Vec::new();
+        if template_is_upcoming {
+            updated_ticket_types.push(template_ticket_type.update(attributes.clone(), current_user_id, conn)?);
+        }
+        for ticket_type in cloned_ticket_types {
+            updated_ticket_types.push(ticket_type.update(attributes.clone(), current_user_id, conn)?);
+        }
+
+        Ok(updated_ticket_types)
+    }
+
+    /// Past events are left untouched, including the template once it has taken place. Occurrences
+    /// generated after that are cloned from the template as it was when it ended.
+    fn events_for_bulk_update(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        self.upcoming_events(conn)
+    }
+
+    fn reject_date_fields(fields: &[(&'static str, bool)]) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        for &(field, present) in fields {
+            if present {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    field,
+                    Err(create_validation_error(
+                        "not_editable_for_series",
+                        "Dates are set by the recurrence rule and can only be changed on individual events",
+                    )),
+                );
+            }
+        }
+        Ok(validation_errors?)
+    }
+
+    /// Expands the rule from the start of the template event. Rules are expanded in the venue's time
+    /// zone so occurrences keep the same local start time across daylight saving changes.
+    fn expand_rule(
+        recurrence_rule: &str,
+        template: &Event,
+        conn: &PgConnection,
+    ) -> Result<Vec<NaiveDateTime>, DatabaseError> {
+        let event_start = match template.event_start {
+            Some(event_start) => event_start,
+            None => {
+                return DatabaseError::validation_error("template_event_id", "Template event must have a start date")
+            }
+        };
+        let rule = RecurrenceRule::parse(recurrence_rule).map_err(recurrence_rule_error)?;
+
+        let timezone: Option<Tz> = template.venue(conn)?.and_then(|venue| venue.timezone.parse().ok());
+        let occurrences = match timezone {
+            Some(timezone) => rule.occurrences(timezone.from_utc_datetime(&event_start).naive_local()),
+            None => rule.occurrences(event_start),
+        };
+        let occurrences = occurrences.map_err(recurrence_rule_error)?;
+
+        Ok(match timezone {
+            // Local times skipped by a daylight saving change have no occurrence
+            Some(timezone) => occurrences
+                .into_iter()
+                .filter_map(|occurrence| timezone.from_local_datetime(&occurrence).earliest())
+                .map(|occurrence| occurrence.naive_utc())
+                .collect(),
+            None => occurrences,
+        })
+    }
+}
+
+fn recurrence_rule_error(error: ValidationError) -> DatabaseError {
+    let mut errors = ValidationErrors::new();
+    errors.add("recurrence_rule", error);
+    errors.into()
+}
@@ -73,6 +73,9 @@ pub struct Event {
     pub facebook_event_id: Option<String>,
     pub settled_at: Option<NaiveDateTime>,
     pub cloned_from_event_id: Option<Uuid>,
+    pub currency: String,
+    pub rotating_redeem_codes: bool,
+    pub event_series_id: Option<Uuid>,
 }
 
 impl PartialOrd for Event {
@@ -127,6 +130,13 @@ pub struct NewEvent {
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub facebook_event_id: Option<String>,
     pub cloned_from_event_id: Option<Uuid>,
+    /// Defaults to the currency of the organization
+    #[validate(custom = "validators::validate_currency")]
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    /// Tickets are redeemed with a time-based code shown in the app instead of a static key
+    #[serde(default)]
+    pub rotating_redeem_codes: bool,
 }
 
 pub enum TicketHoldersCountType {
@@ -140,6 +150,16 @@ impl NewEvent {
         self.validate()?;
         let mut new_event = self.clone();
 
+        if new_event.currency.is_none() {
+            new_event.currency = Some(
+                organizations::table
+                    .filter(organizations::id.eq(new_event.organization_id))
+                    .select(organizations::currency)
+                    .first::<String>(conn)
+                    .to_db_error(ErrorCode::QueryError, "Could not load organization currency")?,
+            );
+        }
+
         match new_event.event_start {
             Some(event_start) => {
                 if new_event.event_end.is_none() {
@@ -207,7 +227,7 @@ impl NewEvent {
     }
 }
 
-#[derive(AsChangeset, Default, Deserialize, Validate, Serialize)]
+#[derive(AsChangeset, Clone, Default, Deserialize, Validate, Serialize)]
 #[table_name = "events"]
 pub struct EventEditableAttributes {
     pub name: Option<String>,
@@ -251,6 +271,10 @@ pub struct EventEditableAttributes {
     #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
     pub facebook_event_id: Option<Option<String>>,
     pub cloned_from_event_id: Option<Option<Uuid>>,
+    #[validate(custom = "validators::validate_currency")]
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub rotating_redeem_codes: Option<bool>,
 }
 
 #[derive(Debug, Default, PartialEq, Serialize)]
@@ -281,6 +305,110 @@ impl Event {
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Event, DatabaseError> {
+        let (event, _) = self.clone_with_ticket_types(clone_fields, None, current_user_id, conn)?;
+        Ok(event)
+    }
+
+    /// Creates an occurrence of `event_series` from this template event starting at `event_start`.
+    /// Ticket types, pricing periods, holds and codes are copied with their dates kept at the same
+    /// offset from the start of the event as on the template.
+    pub(crate) fn clone_for_series(
+        &self,
+        event_series: &EventSeries,
+        event_start: NaiveDateTime,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Event, DatabaseError> {
+        let offset = event_start.signed_duration_since(self.event_start.unwrap_or(event_start));
+        let clone_fields = CloneFields {
+            name: self.name.clone(),
+            event_start,
+            event_end: self
+                .event_end
+                .map(|event_end| event_end + offset)
+                .unwrap_or(event_start + Duration::days(1)),
+        };
+        let (event, ticket_types) = self.clone_with_ticket_types(&clone_fields, Some(offset), current_user_id, conn)?;
+        let mut event: Event = diesel::update(&event)
+            .set((
+                events::event_series_id.eq(event_series.id),
+                events::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add event to series")?;
+
+        for hold in Hold::find_for_event(self.id, false, conn)? {
+            // Holds for a specific person are not part of the shared configuration
+            if hold.email.is_some() || hold.phone.is_some() {
+                continue;
+            }
+            let ticket_type = match ticket_types.get(&hold.ticket_type_id) {
+                Some(ticket_type) => ticket_type,
+                None => continue,
+            };
+            let (quantity, _) = hold.quantity(conn)?;
+            let new_hold = Hold::create_hold(
+                hold.name.clone(),
+                event.id,
+                hold.redemption_code.clone(),
+                hold.discount_in_cents.map(|discount| discount as u32),
+                hold.end_at.map(|end_at| offset_date(end_at, offset)),
+                hold.max_per_user.map(|max| max as u32),
+                hold.hold_type.clone(),
+                ticket_type.id,
+            )
+            .commit(current_user_id, conn)?;
+            new_hold.set_quantity(current_user_id, quantity, conn)?;
+        }
+
+        for code in Code::find_for_event(self.id, None, conn)? {
+            let code = code.display_code;
+            let ticket_type_ids: Vec<Uuid> = code
+                .ticket_type_ids
+                .iter()
+                .filter_map(|id| ticket_types.get(id).map(|ticket_type| ticket_type.id))
+                .collect();
+            let redemption_code = match code.redemption_codes.first() {
+                Some(redemption_code) if !ticket_type_ids.is_empty() => redemption_code.clone(),
+                _ => continue,
+            };
+            let new_code = Code::create(
+                code.name,
+                event.id,
+                code.code_type,
+                redemption_code,
+                code.max_uses as u32,
+                code.discount_in_cents.map(|discount| discount as u32),
+                code.discount_as_percentage.map(|discount| discount as u32),
+                code.start_date
+                    .map(|start_date| offset_date(start_date, offset))
+                    .unwrap_or(times::zero()),
+                code.end_date
+                    .map(|end_date| offset_date(end_date, offset))
+                    .unwrap_or(times::infinity()),
+                code.max_tickets_per_user.map(|max| max as u32),
+            )
+            .commit(current_user_id, conn)?;
+            new_code.update_ticket_types(ticket_type_ids, conn)?;
+        }
+
+        if self.status == EventStatus::Published {
+            event = event.publish(current_user_id, conn)?;
+        }
+
+        Ok(event)
+    }
+
+    /// Clones the event returning the new ticket types keyed by the id of the ticket type they were
+    /// cloned from. With a `date_offset` ticket type dates and pricing periods are moved by the
+    /// offset instead of sales starting immediately.
+    fn clone_with_ticket_types(
+        &self,
+        clone_fields: &CloneFields,
+        date_offset: Option<Duration>,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(Event, HashMap<Uuid, TicketType>), DatabaseError> {
         let calculated_door_time = match self.door_time {
             Some(door_time) => match self.event_start {
                 Some(event_start) => {
@@ -313,6 +441,8 @@ impl Event {
         event.video_url = self.video_url.clone();
         event.is_external = self.is_external;
         event.external_url = self.external_url.clone();
+        event.currency = Some(self.currency.clone());
+        event.rotating_redeem_codes = self.rotating_redeem_codes;
         let event = event.commit(current_user_id, conn)?;
 
         for event_artist in EventArtist::find_all_from_event(self.id, conn)? {
@@ -328,6 +458,7 @@ impl Event {
         }
 
         let org_wallet = Wallet::find_default_for_organization(event.organization_id, conn)?;
+        let mut ticket_types = HashMap::new();
         for ticket_type in self.ticket_types(false, None, conn)? {
             // Skip any cancelled or deleted ticket type. Skip children (will be included below)
             if ticket_type.status == TicketTypeStatus::Cancelled
@@ -338,7 +469,15 @@ impl Event {
                 continue;
             }
 
-            event.clone_ticket_type(&org_wallet, None, &ticket_type, current_user_id, conn)?;
+            event.clone_ticket_type(
+                &org_wallet,
+                None,
+                &ticket_type,
+                date_offset,
+                &mut ticket_types,
+                current_user_id,
+                conn,
+            )?;
         }
 
         DomainEvent::create(
@@ -351,7 +490,7 @@ impl Event {
         )
         .commit(conn)?;
 
-        Ok(event)
+        Ok((event, ticket_types))
     }
 
     fn clone_ticket_type(
@@ -359,24 +498,34 @@ impl Event {
         org_wallet: &Wallet,
         parent_ticket_type: Option<&TicketType>,
         ticket_type: &TicketType,
+        date_offset: Option<Duration>,
+        cloned_ticket_types: &mut HashMap<Uuid, TicketType>,
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<TicketType, DatabaseError> {
+        let (start_date, end_date, end_date_type) = match date_offset {
+            Some(offset) => (
+                ticket_type.start_date.map(|start_date| offset_date(start_date, offset)),
+                ticket_type.end_date.map(|end_date| offset_date(end_date, offset)),
+                ticket_type.end_date_type,
+            ),
+            None => (
+                Some(times::zero()),
+                None,
+                if ticket_type.end_date_type == TicketTypeEndDateType::Manual {
+                    TicketTypeEndDateType::EventEnd
+                } else {
+                    ticket_type.end_date_type
+                },
+            ),
+        };
         let new_ticket_type = self.add_ticket_type(
             ticket_type.name.clone(),
             ticket_type.description.clone(),
             ticket_type.valid_ticket_count(conn)?,
-            if parent_ticket_type.is_some() {
-                None
-            } else {
-                Some(times::zero())
-            },
-            None,
-            if ticket_type.end_date_type == TicketTypeEndDateType::Manual {
-                TicketTypeEndDateType::EventEnd
-            } else {
-                ticket_type.end_date_type
-            },
+            if parent_ticket_type.is_some() { None } else { start_date },
+            end_date,
+            end_date_type,
             Some(org_wallet.id),
             Some(ticket_type.increment),
             ticket_type.limit_per_person,
@@ -395,6 +544,26 @@ impl Event {
             current_user_id,
             conn,
         )?;
+        let new_ticket_type: TicketType = diesel::update(&new_ticket_type)
+            .set(ticket_types::cloned_from_ticket_type_id.eq(ticket_type.id))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update cloned ticket type")?;
+
+        if let Some(offset) = date_offset {
+            for ticket_pricing in ticket_type.valid_ticket_pricing(false, conn)? {
+                new_ticket_type.add_ticket_pricing(
+                    ticket_pricing.name,
+                    offset_date(ticket_pricing.start_date, offset),
+                    offset_date(ticket_pricing.end_date, offset),
+                    ticket_pricing.price_in_cents,
+                    ticket_pricing.is_box_office_only,
+                    Some(ticket_pricing.status),
+                    current_user_id,
+                    conn,
+                )?;
+            }
+        }
+        cloned_ticket_types.insert(ticket_type.id, new_ticket_type.clone());
 
         for child_ticket_type in ticket_type.find_dependent_ticket_types(conn)? {
             if child_ticket_type.status == TicketTypeStatus::Cancelled
@@ -408,6 +577,8 @@ impl Event {
                 org_wallet,
                 Some(&new_ticket_type),
                 &child_ticket_type,
+                date_offset,
+                cloned_ticket_types,
                 current_user_id,
                 conn,
             )?;
@@ -693,6 +864,18 @@ impl Event {
         let associated_with_active_orders = self.associated_with_active_orders(conn)?;
 
         if associated_with_active_orders {
+            if let Some(ref currency) = attributes.currency {
+                if currency != &self.currency {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "event.currency",
+                        Err(create_validation_error(
+                            "cannot_change_currency_with_sales",
+                            "Event with sales cannot change currency.",
+                        )),
+                    );
+                }
+            }
             if attributes.event_start != self.event_start {
                 if let Some(updated_date) = attributes.event_start {
                     if updated_date < Utc::now().naive_utc() {
@@ -1128,6 +1311,13 @@ impl Event {
             .to_db_error(ErrorCode::QueryError, "Error loading event")
     }
 
+    pub fn find_including_deleted(id: Uuid, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        events::table
+            .find(id)
+            .first::<Event>(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading event")
+    }
+
     pub fn find_incl_org_venue_fees(
         id: Uuid,
         conn: &PgConnection,
@@ -1709,6 +1899,8 @@ impl Event {
             )
             .inner_join(events::table.on(events::id.eq(ticket_types::event_id)))
             .left_join(venues::table.on(venues::id.nullable().eq(events::venue_id.nullable())))
+            .left_join(seats::table.on(seats::id.nullable().eq(ticket_instances::seat_id)))
+            .left_join(seat_map_sections::table.on(seat_map_sections::id.eq(seats::seat_map_section_id)))
             .into_boxed();
         if let Some(event_id) = event_id {
             query = query.filter(ticket_types::event_id.nullable().eq(event_id))
@@ -1783,6 +1975,9 @@ impl Event {
                 , sql::<Timestamp>("ticket_instances.updated_at AS updated_at")
                 , sql::<Nullable<Text>>("CASE WHEN ticket_instances.redeemed_by_user_id IS NOT NULL THEN (SELECT CONCAT(u2.first_name, ' ', u2.last_name) FROM users u2 WHERE u2.id = ticket_instances.redeemed_by_user_id) ELSE NULL END  AS redeemed_by")
                 , sql::<Nullable<Timestamp>>("ticket_instances.redeemed_at AS redeemed_at")
+                , sql::<Nullable<Text>>("seat_map_sections.name AS seat_section")
+                , sql::<Nullable<Text>>("seats.row_label AS seat_row")
+                , sql::<Nullable<Text>>("seats.seat_number AS seat_number")
             ))
             .paginate(paging.page as i64)
             .per_page(paging.limit as i64)
@@ -2287,6 +2482,7 @@ impl Event {
             event_type: self.event_type,
             slug,
             cloned_from_event_id: self.cloned_from_event_id,
+            currency: self.currency.clone(),
         })
     }
 }
@@ -2315,6 +2511,7 @@ pub struct DisplayEvent {
     pub genres: Vec<String>,
     pub slug: String,
     pub cloned_from_event_id: Option<Uuid>,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -2419,3 +2616,12 @@ pub struct GuestListItem {
     pub providers: Vec<String>,
     pub pending_transfer: Option<PendingTransfer>,
 }
+
+/// Moves a cloned date by `offset`, open ended dates (`times::zero()` and `times::infinity()`) are kept
+fn offset_date(date: NaiveDateTime, offset: Duration) -> NaiveDateTime {
+    if date == times::zero() || date == times::infinity() {
+        date
+    } else {
+        date + offset
+    }
+}
@@ -12,6 +12,8 @@ use utils::errors::*;
 use uuid::Uuid;
 
 pub const FACEBOOK_SITE: &str = "facebook.com";
+pub const GOOGLE_SITE: &str = "google.com";
+pub const APPLE_SITE: &str = "apple.com";
 
 #[derive(Clone, Identifiable, Associations, Queryable, Serialize, Deserialize, PartialEq, Debug)]
 #[belongs_to(User, foreign_key = "user_id")]
@@ -16,6 +16,7 @@ pub struct FeeSchedule {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub organization_id: Uuid,
+    pub currency: String,
 }
 
 impl FeeSchedule {
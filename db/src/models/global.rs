@@ -16,5 +16,9 @@ pub fn schedule_domain_actions(conn: &PgConnection) -> Result<(), DatabaseError>
         Settlement::create_next_finalize_settlements_domain_action(conn)?;
     }
 
+    if DomainAction::upcoming_domain_action(None, None, DomainActionTypes::PurgeExpiredReportExports, conn)?.is_none() {
+        ReportExport::create_next_purge_expired_domain_action(conn)?;
+    }
+
     Ok(())
 }
@@ -451,6 +451,9 @@ impl Hold {
                     Some(json!({"old_quantity": count, "new_quantity": quantity})),
                 )
                 .commit(conn)?;
+
+                // Released tickets can be offered to anyone waiting on the ticket type
+                WaitlistEntry::schedule_processing(self.ticket_type_id, Utc::now().naive_utc(), conn)?;
             }
         }
 
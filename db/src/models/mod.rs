@@ -1,3 +1,4 @@
+pub use self::access_zones::*;
 pub use self::activities::*;
 pub use self::announcement_engagements::*;
 pub use self::announcements::*;
@@ -5,6 +6,8 @@ pub use self::artists::*;
 pub use self::assets::*;
 pub use self::auth::*;
 pub use self::broadcasts::*;
+pub use self::bundle_ticket_types::*;
+pub use self::bundles::*;
 pub use self::codes::*;
 pub use self::collection_items::*;
 pub use self::collections::*;
@@ -16,6 +19,7 @@ pub use self::enums::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
 pub use self::event_report_subscribers::*;
+pub use self::event_series::*;
 pub use self::event_users::*;
 pub use self::events::*;
 pub use self::external_logins::FACEBOOK_SITE;
@@ -34,6 +38,7 @@ pub use self::marketplace_accounts::*;
 pub use self::notes::*;
 pub use self::order_items::*;
 pub use self::orders::*;
+pub use self::organization_api_keys::*;
 pub use self::organization_interactions::*;
 pub use self::organization_invites::*;
 pub use self::organization_users::*;
@@ -43,6 +48,8 @@ pub use self::paging::*;
 pub use self::payment_methods::*;
 pub use self::payments::*;
 pub use self::platforms::*;
+pub use self::product_variants::*;
+pub use self::products::*;
 pub use self::push_notification_tokens::*;
 pub use self::rarities::*;
 pub use self::redeemable_ticket::*;
@@ -50,30 +57,44 @@ pub use self::refund_items::*;
 pub use self::refunded_tickets::*;
 pub use self::refunds::*;
 pub use self::regions::*;
+pub use self::report_exports::*;
 pub use self::reports::*;
 pub use self::scopes::*;
+pub use self::seat_map_sections::*;
+pub use self::seat_maps::*;
+pub use self::seats::*;
 pub use self::settlement_adjustments::*;
 pub use self::settlement_entries::*;
 pub use self::settlements::*;
 pub use self::slugs::*;
 pub use self::stages::*;
+pub use self::tax_rates::*;
 pub use self::temporary_users::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
+pub use self::ticket_scans::*;
+pub use self::ticket_type_access_zones::*;
 pub use self::ticket_type_codes::*;
+pub use self::ticket_type_seat_tiers::*;
 pub use self::ticket_types::*;
 pub use self::transfer_tickets::*;
 pub use self::transfers::*;
+pub use self::user_mfa_challenges::*;
+pub use self::user_mfa_recovery_codes::*;
+pub use self::user_sessions::*;
 pub use self::users::*;
 pub use self::venues::*;
+pub use self::waitlist_entries::*;
 pub use self::wallets::*;
+pub use self::webhook_deliveries::*;
 
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
 pub mod concerns;
 
+mod access_zones;
 mod activities;
 pub mod analytics;
 mod announcement_engagements;
@@ -82,6 +103,8 @@ mod artists;
 mod assets;
 mod auth;
 mod broadcasts;
+mod bundle_ticket_types;
+mod bundles;
 mod codes;
 mod collection_items;
 mod collections;
@@ -93,6 +116,7 @@ pub mod enums;
 mod event_artists;
 mod event_interest;
 mod event_report_subscribers;
+mod event_series;
 mod event_users;
 mod events;
 mod external_logins;
@@ -110,6 +134,7 @@ mod marketplace_accounts;
 mod notes;
 mod order_items;
 mod orders;
+mod organization_api_keys;
 mod organization_interactions;
 mod organization_invites;
 mod organization_users;
@@ -119,6 +144,8 @@ mod paging;
 mod payment_methods;
 mod payments;
 mod platforms;
+mod product_variants;
+mod products;
 mod push_notification_tokens;
 mod rarities;
 mod redeemable_ticket;
@@ -126,23 +153,36 @@ mod refund_items;
 mod refunded_tickets;
 mod refunds;
 mod regions;
+mod report_exports;
 mod reports;
 pub mod scopes;
+mod seat_map_sections;
+mod seat_maps;
+mod seats;
 mod settlement_adjustments;
 mod settlement_entries;
 mod settlements;
 mod slugs;
 mod stages;
+mod tax_rates;
 mod temporary_users;
 mod ticket_instances;
 mod ticket_pricing;
+mod ticket_scans;
+mod ticket_type_access_zones;
 mod ticket_type_codes;
+mod ticket_type_seat_tiers;
 mod ticket_types;
 mod transfer_tickets;
 mod transfers;
+mod user_mfa_challenges;
+mod user_mfa_recovery_codes;
+mod user_sessions;
 mod users;
 mod venues;
+mod waitlist_entries;
 mod wallets;
+mod webhook_deliveries;
 
 pub fn deserialize_unless_blank<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
 where
@@ -39,6 +39,13 @@ pub struct OrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub tax_rate_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub bundle_id: Option<Uuid>,
+    pub product_id: Option<Uuid>,
+    pub product_variant_id: Option<Uuid>,
+    /// Units of a product item picked up at the door
+    pub redeemed_quantity: i64,
 }
 
 impl OrderItem {
@@ -67,6 +74,15 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item discount")
     }
 
+    pub fn find_tax_item(&self, conn: &PgConnection) -> Result<Option<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item tax")
+    }
+
     pub fn description(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
         use models::OrderItemTypes::*;
         let res = match self.item_type {
@@ -80,6 +96,26 @@ impl OrderItem {
             }
             Discount => "Discount".to_string(),
             CreditCardFees => "Credit Card Fees".to_string(),
+            Tax => match self.tax_rate_id {
+                Some(tax_rate_id) => TaxRate::find(tax_rate_id, conn)?.name,
+                None => "Tax".to_string(),
+            },
+            Products => {
+                let product = self.product(conn)?;
+                let name = match product {
+                    Some(p) => match self.product_variant_id {
+                        Some(product_variant_id) => {
+                            format!("{} ({})", p.name, ProductVariant::find(product_variant_id, conn)?.name)
+                        }
+                        None => p.name,
+                    },
+                    None => "Other".to_string(),
+                };
+                match self.event_id {
+                    Some(_) => format!("{} - {}", self.event(conn)?.name, name),
+                    None => name,
+                }
+            }
             _ => {
                 let ticket_type = self.ticket_type(conn)?;
                 match ticket_type {
@@ -91,6 +127,75 @@ impl OrderItem {
         Ok(res)
     }
 
+    pub fn product(&self, conn: &PgConnection) -> Result<Option<Product>, DatabaseError> {
+        match self.product_id {
+            Some(product_id) => Ok(Some(Product::find(product_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Marks units of a paid product item as picked up at the door
+    pub fn redeem_product(
+        &self,
+        quantity: u32,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrderItem, DatabaseError> {
+        let product = match self.product(conn)? {
+            Some(product) => product,
+            None => return DatabaseError::business_process_error("Order item is not a product"),
+        };
+        if !product.requires_pickup {
+            return DatabaseError::business_process_error("Product does not require pickup");
+        }
+        if self.order(conn)?.status != OrderStatus::Paid {
+            return DatabaseError::business_process_error("Product can only be picked up for paid orders");
+        }
+        if quantity == 0 {
+            return DatabaseError::validation_error("quantity", "Quantity must be greater than zero");
+        }
+
+        // The bound is checked in the update itself so concurrent pickups can not exceed the units paid for
+        let order_item: Option<OrderItem> = diesel::update(self)
+            .filter(
+                (order_items::redeemed_quantity + quantity as i64)
+                    .le(order_items::quantity - order_items::refunded_quantity),
+            )
+            .set((
+                order_items::redeemed_quantity.eq(order_items::redeemed_quantity + quantity as i64),
+                order_items::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not redeem product")
+            .optional()?;
+        let order_item = match order_item {
+            Some(order_item) => order_item,
+            None => {
+                return DatabaseError::validation_error(
+                    "quantity",
+                    "Quantity exceeds the units remaining to be picked up",
+                )
+            }
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::ProductRedeemed,
+            format!("{} x '{}' picked up", quantity, &product.name),
+            Tables::Products,
+            Some(product.id),
+            current_user_id,
+            Some(json!({
+                "order_id": self.order_id,
+                "order_item_id": self.id,
+                "product_variant_id": self.product_variant_id,
+                "quantity": quantity
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(order_item)
+    }
+
     pub(crate) fn refund_one_unit(&mut self, refund_fees: bool, conn: &PgConnection) -> Result<i64, DatabaseError> {
         if self.order(conn)?.status != OrderStatus::Paid {
             return DatabaseError::business_process_error("Order item must have associated paid order to refund unit");
@@ -101,6 +206,11 @@ impl OrderItem {
                 "Order item refund failed as requested refund quantity exceeds remaining quantity",
             );
         }
+        if self.item_type == OrderItemTypes::Products
+            && self.refunded_quantity + self.redeemed_quantity >= self.quantity
+        {
+            return DatabaseError::business_process_error("Products that have been picked up can not be refunded");
+        }
 
         self.refunded_quantity += 1;
 
@@ -114,6 +224,10 @@ impl OrderItem {
         }
 
         let mut refund_amount_in_cents = self.unit_price_in_cents + discount_amount;
+        // Tax is refunded along with the unit it was charged on
+        if let Some(mut tax_item) = self.find_tax_item(conn)? {
+            refund_amount_in_cents += tax_item.refund_one_unit(true, conn)?;
+        }
         // Refund fees if ticket is being refunded
         if refund_fees && self.item_type == OrderItemTypes::Tickets {
             let fee_item = self.find_fee_item(conn)?;
@@ -156,6 +270,7 @@ impl OrderItem {
             || self.item_type == OrderItemTypes::EventFees
             || self.item_type == OrderItemTypes::Discount
             || self.item_type == OrderItemTypes::CreditCardFees
+            || self.item_type == OrderItemTypes::Tax
         {
             return Ok(());
         }
@@ -479,6 +594,12 @@ impl OrderItem {
             event_id: Uuid,
             #[sql_type = "dUuid"]
             order_id: Uuid,
+            #[sql_type = "Nullable<dUuid>"]
+            bundle_id: Option<Uuid>,
+            #[sql_type = "Nullable<dUuid>"]
+            product_id: Option<Uuid>,
+            #[sql_type = "Nullable<dUuid>"]
+            product_variant_id: Option<Uuid>,
         }
 
         let results: Vec<R> = diesel::sql_query(
@@ -497,6 +618,9 @@ impl OrderItem {
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
              WHEN item_type = 'Discount' THEN 'Discount'
              WHEN item_type = 'CreditCardFees' THEN 'Credit Card Fees'
+             WHEN item_type = 'Tax' THEN COALESCE(tr.name, 'Tax')
+             WHEN item_type = 'Products' THEN e.name || ' - ' || p.name || COALESCE(' (' || pv.name || ')', '')
+             WHEN b.id IS NOT NULL THEN b.name || ' - ' || e.name || ' - ' || tt.name
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
@@ -511,7 +635,10 @@ impl OrderItem {
              ELSE 'Valid'
            END AS cart_item_status,
            e.id AS event_id,
-           oi.order_id
+           oi.order_id,
+           oi.bundle_id,
+           oi.product_id,
+           oi.product_variant_id
         FROM order_items oi
            JOIN orders o ON oi.order_id = o.id
            LEFT JOIN ticket_pricing tp ON tp.id = oi.ticket_pricing_id
@@ -531,6 +658,10 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN tax_rates tr ON oi.tax_rate_id = tr.id
+           LEFT JOIN bundles b ON oi.bundle_id = b.id
+           LEFT JOIN products p ON oi.product_id = p.id
+           LEFT JOIN product_variants pv ON oi.product_variant_id = pv.id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -579,6 +710,9 @@ impl OrderItem {
                     redemption_code: item.redemption_code,
                     cart_item_status: item.cart_item_status,
                     event_id: item.event_id,
+                    bundle_id: item.bundle_id,
+                    product_id: item.product_id,
+                    product_variant_id: item.product_variant_id,
                 });
             }
             order_items.insert(order_id, display_items);
@@ -629,6 +763,7 @@ pub(crate) struct NewTicketsOrderItem {
     pub ticket_pricing_id: Uuid,
     pub hold_id: Option<Uuid>,
     pub code_id: Option<Uuid>,
+    pub bundle_id: Option<Uuid>,
 }
 
 impl NewTicketsOrderItem {
@@ -666,6 +801,27 @@ impl NewTicketsOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewProductsOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub product_id: Uuid,
+    pub product_variant_id: Option<Uuid>,
+}
+
+impl NewProductsOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "order_items"]
 pub(crate) struct NewFeesOrderItem {
@@ -711,6 +867,30 @@ impl NewDiscountOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub company_fee_in_cents: i64,
+    pub client_fee_in_cents: i64,
+    pub parent_id: Option<Uuid>,
+    pub tax_rate_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Deserialize, Queryable, QueryableByName, Serialize)]
 pub struct DisplayOrderItem {
     #[sql_type = "dUuid"]
@@ -739,4 +919,14 @@ pub struct DisplayOrderItem {
     pub cart_item_status: Option<CartItemStatus>,
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
+    /// Set on the ticket items of a bundle, the items of a bundle are priced with its allocated share
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sql_type = "Nullable<dUuid>"]
+    pub bundle_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sql_type = "Nullable<dUuid>"]
+    pub product_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sql_type = "Nullable<dUuid>"]
+    pub product_variant_id: Option<Uuid>,
 }
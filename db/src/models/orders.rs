@@ -64,6 +64,8 @@ pub struct Order {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub referrer: Option<String>,
+    pub disputed_at: Option<NaiveDateTime>,
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -370,6 +372,7 @@ impl Order {
                         quantity: item.quantity as u32,
                         ticket_type_id,
                         redemption_code: redemption_code.clone(),
+                        seat_ids: None,
                     });
                 }
             }
@@ -429,11 +432,16 @@ impl Order {
     }
 
     pub fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
-        let validation_errors = append_validation_error(
+        let mut validation_errors = append_validation_error(
             Ok(()),
             "event_id",
             Order::order_contains_items_from_only_one_event(self.id, conn)?,
         );
+        validation_errors = append_validation_error(
+            validation_errors,
+            "organization_id",
+            Order::order_contains_items_from_only_one_organization(self.id, conn)?,
+        );
 
         Ok(validation_errors?)
     }
@@ -445,6 +453,7 @@ impl Order {
         let event_count = order_items::table
             .filter(order_items::order_id.eq(id))
             .filter(order_items::event_id.is_not_null())
+            .filter(order_items::bundle_id.is_null())
             .select(sql::<BigInt>("count(distinct event_id) AS event_count"))
             .get_result::<i64>(conn)
             .to_db_error(ErrorCode::QueryError, "Could not get count of unique events in cart")?;
@@ -458,6 +467,34 @@ impl Order {
         Ok(Ok(()))
     }
 
+    /// Bundles can span events, but an order is still settled with a single organization
+    pub fn order_contains_items_from_only_one_organization(
+        id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Result<(), ValidationError>, DatabaseError> {
+        let organization_count = order_items::table
+            .inner_join(events::table.on(order_items::event_id.eq(events::id.nullable())))
+            .filter(order_items::order_id.eq(id))
+            .select(sql::<BigInt>(
+                "count(distinct events.organization_id) AS organization_count",
+            ))
+            .get_result::<i64>(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not get count of unique organizations in cart",
+            )?;
+
+        if organization_count > 1 {
+            let mut validation_error = create_validation_error(
+                "cart_organization_limit_reached",
+                "You already have tickets from another organizer in your cart. Please clear your cart first to purchase these tickets.",
+            );
+            validation_error.add_param(Cow::from("order_id"), &id);
+            return Ok(Err(validation_error.into()));
+        }
+        Ok(Ok(()))
+    }
+
     pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
         let cart_user: Option<User> = users::table
             .filter(users::last_cart_id.eq(self.id))
@@ -499,7 +536,15 @@ impl Order {
             );
         }
 
-        // delete children order items
+        // delete children order items and their taxes
+        let child_ids = order_items::table
+            .filter(order_items::parent_id.eq(item_id))
+            .select(order_items::id);
+        diesel::delete(order_items::table.filter(order_items::parent_id.eq_any(child_ids)))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
+
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
             .map(|_| ())
@@ -558,6 +603,178 @@ impl Order {
         Ok(())
     }
 
+    /// Flags the order as disputed by the purchaser's bank and records a chargeback against the
+    /// organization's pending settlement. Unredeemed tickets are nullified when `nullify_tickets` is set.
+    pub fn mark_disputed(
+        &mut self,
+        dispute_reference: &str,
+        amount_in_cents: i64,
+        nullify_tickets: bool,
+        raw_data: Value,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        // Payment providers can notify us of the same dispute more than once
+        if self.disputed_at.is_some() {
+            return Ok(None);
+        }
+
+        *self = diesel::update(&*self)
+            .set((
+                orders::disputed_at.eq(Some(Utc::now().naive_utc())),
+                orders::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not flag the order as disputed")?;
+
+        let settlement_adjustment = self.adjust_pending_settlement(
+            SettlementAdjustmentTypes::Chargeback,
+            amount_in_cents,
+            format!("Chargeback for order {} ({})", self.order_number(), dispute_reference),
+            conn,
+        )?;
+        let nullified_tickets = if nullify_tickets {
+            TicketInstance::nullify_for_order(self.id, None, conn)?
+        } else {
+            Vec::new()
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::OrderDisputeCreated,
+            "Order disputed".to_string(),
+            Tables::Orders,
+            Some(self.id),
+            None,
+            Some(json!({
+                "dispute_reference": dispute_reference,
+                "amount_in_cents": amount_in_cents,
+                "settlement_adjustment_id": settlement_adjustment.as_ref().map(|a| a.id),
+                "nullified_ticket_ids": nullified_tickets.iter().map(|t| t.id).collect::<Vec<Uuid>>(),
+                "data": raw_data
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(settlement_adjustment)
+    }
+
+    pub fn update_dispute(
+        &self,
+        dispute_reference: &str,
+        raw_data: Value,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::OrderDisputeUpdated,
+            "Order dispute updated".to_string(),
+            Tables::Orders,
+            Some(self.id),
+            None,
+            Some(json!({ "dispute_reference": dispute_reference, "data": raw_data })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Records the outcome of a dispute. A dispute decided in the organization's favour reverses the
+    /// chargeback recorded for it on the pending settlement, nullified tickets are not restored.
+    /// Payment providers can notify us of the same outcome more than once, the chargeback is only
+    /// reversed the first time.
+    pub fn close_dispute(
+        &self,
+        dispute_reference: &str,
+        won: bool,
+        raw_data: Value,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        let chargeback = match self.dispute_settlement_adjustment_id(
+            DomainEventTypes::OrderDisputeCreated,
+            dispute_reference,
+            conn,
+        )? {
+            Some(id) => SettlementAdjustment::find(id, conn).optional()?,
+            None => None,
+        };
+        let reversed = self
+            .dispute_settlement_adjustment_id(DomainEventTypes::OrderDisputeClosed, dispute_reference, conn)?
+            .is_some();
+
+        let settlement_adjustment = match chargeback {
+            Some(chargeback) if won && !reversed => self.adjust_pending_settlement(
+                SettlementAdjustmentTypes::ManualCredit,
+                chargeback.amount_in_cents,
+                format!(
+                    "Reversed chargeback for order {} ({})",
+                    self.order_number(),
+                    dispute_reference
+                ),
+                conn,
+            )?,
+            _ => None,
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::OrderDisputeClosed,
+            "Order dispute closed".to_string(),
+            Tables::Orders,
+            Some(self.id),
+            None,
+            Some(json!({
+                "dispute_reference": dispute_reference,
+                "won": won,
+                "settlement_adjustment_id": settlement_adjustment.as_ref().map(|a| a.id),
+                "data": raw_data
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(settlement_adjustment)
+    }
+
+    /// Settlement adjustment recorded by the dispute event of the given type, if any
+    fn dispute_settlement_adjustment_id(
+        &self,
+        event_type: DomainEventTypes,
+        dispute_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Uuid>, DatabaseError> {
+        Ok(
+            DomainEvent::find(Tables::Orders, Some(self.id), Some(event_type), conn)?
+                .into_iter()
+                .filter_map(|domain_event| domain_event.event_data)
+                .filter(|data| data["dispute_reference"].as_str() == Some(dispute_reference))
+                .filter_map(|data| data["settlement_adjustment_id"].as_str().and_then(|id| id.parse().ok()))
+                .next(),
+        )
+    }
+
+    /// Returns `None` when the order spans several organizations or there is no pending settlement to adjust
+    fn adjust_pending_settlement(
+        &self,
+        settlement_adjustment_type: SettlementAdjustmentTypes,
+        amount_in_cents: i64,
+        note: String,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        let organizations = self.organizations(conn)?;
+        if organizations.len() != 1 {
+            return Ok(None);
+        }
+
+        // Orders placed before currencies were recorded were charged in the organization's currency
+        let currency = self
+            .currency
+            .clone()
+            .unwrap_or_else(|| organizations[0].currency.clone());
+        match Settlement::find_pending_for_organization(organizations[0].id, &currency, conn)? {
+            Some(settlement) => Ok(Some(
+                SettlementAdjustment::create(settlement.id, settlement_adjustment_type, Some(note), amount_in_cents)
+                    .commit(conn)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     pub fn has_refunds(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
         select(exists(refunds::table.filter(refunds::order_id.eq(self.id))))
             .get_result(conn)
@@ -573,6 +790,7 @@ impl Order {
         conn: &PgConnection,
     ) -> Result<(Refund, i64), DatabaseError> {
         self.lock_version(conn)?;
+        self.validate_bundle_refund(refund_data, conn)?;
         let mut total_to_be_refunded: i64 = 0;
 
         let refund = Refund::create(self.id, user_id, reason, manual_override).commit(conn)?;
@@ -583,6 +801,10 @@ impl Order {
             let mut order_item = OrderItem::find(refund_datum.order_item_id, conn)?;
             if order_item.item_type == OrderItemTypes::Discount {
                 return DatabaseError::business_process_error("Discount order items can not be refunded");
+            } else if order_item.item_type == OrderItemTypes::Tax {
+                return DatabaseError::business_process_error(
+                    "Tax order items can not be refunded, they are refunded with their parent item",
+                );
             } else if order_item.order_id != self.id {
                 return DatabaseError::business_process_error("Order item id does not belong to this order");
             }
@@ -642,6 +864,58 @@ impl Order {
         Ok((refund, total_to_be_refunded))
     }
 
+    /// Tickets bought as part of a bundle are refunded a whole bundle unit at a time, i.e. the same number
+    /// of units for every event in the bundle. Tickets for cancelled events can be refunded on their own at
+    /// the price allocated to them.
+    fn validate_bundle_refund(
+        &self,
+        refund_data: &[RefundItemRequest],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let mut requested_quantities: HashMap<Uuid, i64> = HashMap::new();
+        for refund_datum in refund_data {
+            *requested_quantities.entry(refund_datum.order_item_id).or_insert(0) += 1;
+        }
+
+        let items = self.items(conn)?;
+        let mut bundle_ids: Vec<Uuid> = items
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets && requested_quantities.contains_key(&i.id))
+            .filter_map(|i| i.bundle_id)
+            .collect();
+        bundle_ids.sort();
+        bundle_ids.dedup();
+
+        for bundle_id in bundle_ids {
+            let bundle_ticket_types = BundleTicketType::find_for_bundle(bundle_id, conn)?;
+            let mut refunded_units: Option<i64> = None;
+            for item in items
+                .iter()
+                .filter(|i| i.item_type == OrderItemTypes::Tickets && i.bundle_id == Some(bundle_id))
+            {
+                if Event::find(item.event_id.unwrap(), conn)?.cancelled_at.is_some() {
+                    continue;
+                }
+                let component_quantity = bundle_ticket_types
+                    .iter()
+                    .find(|b| Some(b.ticket_type_id) == item.ticket_type_id)
+                    .map(|b| b.quantity)
+                    .unwrap_or(item.quantity);
+                let refunded_quantity =
+                    item.refunded_quantity + requested_quantities.get(&item.id).cloned().unwrap_or(0);
+                let units = refunded_quantity / component_quantity;
+                if refunded_quantity % component_quantity != 0 || refunded_units.unwrap_or(units) != units {
+                    return DatabaseError::business_process_error(
+                        "Bundle tickets must be refunded together, include the tickets for every event in the bundle",
+                    );
+                }
+                refunded_units = Some(units);
+            }
+        }
+
+        Ok(())
+    }
+
     fn refund_ticket_instance(
         ticket_instance: &TicketInstance,
         order_item: &mut OrderItem,
@@ -777,7 +1051,10 @@ impl Order {
         self.lock_version(conn)?;
         let new_expires_at = Utc::now().naive_utc() + Duration::minutes(CART_EXPIRY_TIME_MINUTES);
 
-        if self.status != OrderStatus::Draft && self.status != OrderStatus::PendingPayment {
+        if self.status != OrderStatus::Draft
+            && self.status != OrderStatus::PendingPayment
+            && self.status != OrderStatus::PendingAuthentication
+        {
             return DatabaseError::business_process_error(
                 "Can't refresh expired cart unless the order is in draft or pending payment statuses",
             );
@@ -786,7 +1063,16 @@ impl Order {
         }
 
         for item in self.items(conn)? {
-            if item.item_type != OrderItemTypes::Tickets {
+            if item.item_type == OrderItemTypes::Products {
+                // Products held by an expired cart are no longer reserved so they may have sold out
+                let product = Product::find(item.product_id.unwrap(), conn)?.lock(conn)?;
+                if product.deleted_at.is_some()
+                    || product.available_for_order(item.product_variant_id, Some(self.id), conn)? < item.quantity
+                {
+                    return DatabaseError::business_process_error("Product is no longer available");
+                }
+                continue;
+            } else if item.item_type != OrderItemTypes::Tickets {
                 continue;
             } else if item.ticket_type_id.is_none() {
                 // Sanity check given unwrap below
@@ -804,6 +1090,7 @@ impl Order {
                 item.ticket_type_id.unwrap(),
                 item.hold_id,
                 item.quantity as u32,
+                None,
                 conn,
             )?;
         }
@@ -1321,7 +1608,10 @@ impl Order {
         self.lock_version(conn)?;
 
         for current_line in self.items(conn)? {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            if current_line.item_type == OrderItemTypes::Products {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            } else if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
             // Use calculated quantity as reserved may have been taken in the meantime no longer pointing to this order item
@@ -1354,6 +1644,15 @@ impl Order {
         let mut check_ticket_limits: Vec<LimitCheck> = vec![];
         let mut mapped = vec![];
         for (index, item) in items.iter().enumerate() {
+            if item
+                .seat_ids
+                .as_ref()
+                .map(|seat_ids| seat_ids.len() as u32)
+                .unwrap_or(item.quantity)
+                != item.quantity
+            {
+                return DatabaseError::validation_error("seat_ids", "A seat must be selected for each ticket");
+            }
             let ticket_type = TicketType::find(item.ticket_type_id, conn)?;
             mapped.push(match &item.redemption_code {
                 Some(r) => match Hold::find_by_redemption_code(r, Some(ticket_type.event_id), conn).optional()? {
@@ -1402,7 +1701,11 @@ impl Order {
         }
 
         for mut current_line in current_items {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            if current_line.item_type == OrderItemTypes::Products && remove_others {
+                jlog!(Level::Debug, "Removing products because remove others was called.", { "order_item.id": current_line.id, "product_id": current_line.product_id});
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            } else if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
 
@@ -1410,6 +1713,7 @@ impl Order {
             {
                 let matching_result: Option<&MatchData> = mapped.iter().find(|match_data| {
                     match_data.index.is_some()
+                        && current_line.bundle_id.is_none()
                         && Some(match_data.update_order_item.ticket_type_id) == current_line.ticket_type_id
                         && match_data.hold_id == current_line.hold_id
                         && match_data.code_id == current_line.code_id
@@ -1418,7 +1722,32 @@ impl Order {
                 if let Some(match_data) = matching_result {
                     jlog!(Level::Debug, "Found an existing cart item, replacing");
                     index_to_remove = match_data.index;
-                    if current_line.quantity as u32 > match_data.update_order_item.quantity {
+                    if let Some(ref seat_ids) = match_data.update_order_item.seat_ids {
+                        jlog!(Level::Debug, "Replacing the seats of cart item");
+                        TicketInstance::release_tickets(
+                            &current_line,
+                            current_line.quantity as u32,
+                            Some(current_user_id),
+                            conn,
+                        )?;
+                        if seat_ids.is_empty() {
+                            self.destroy_item(current_line.id, conn)?;
+                        } else {
+                            let ticket_type = TicketType::find(current_line.ticket_type_id.unwrap(), conn)?;
+                            check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
+                            TicketInstance::reserve_tickets(
+                                &current_line,
+                                self.expires_at,
+                                ticket_type.id,
+                                match_data.hold_id,
+                                seat_ids.len() as u32,
+                                Some(seat_ids),
+                                conn,
+                            )?;
+                            current_line.quantity = seat_ids.len() as i64;
+                            current_line.update(conn)?;
+                        }
+                    } else if current_line.quantity as u32 > match_data.update_order_item.quantity {
                         jlog!(Level::Debug, "Reducing quantity of cart item");
                         TicketInstance::release_tickets(
                             &current_line,
@@ -1458,6 +1787,7 @@ impl Order {
                                 unit_price_in_cents: price_in_cents,
                                 hold_id: match_data.hold_id,
                                 code_id: match_data.code_id,
+                                bundle_id: None,
                             }
                             .commit(conn)?;
                             TicketInstance::reserve_tickets(
@@ -1466,6 +1796,7 @@ impl Order {
                                 ticket_type_id,
                                 match_data.hold_id,
                                 match_data.update_order_item.quantity - current_line.quantity as u32,
+                                None,
                                 conn,
                             )?;
                         } else {
@@ -1475,6 +1806,7 @@ impl Order {
                                 ticket_type_id,
                                 match_data.hold_id,
                                 match_data.update_order_item.quantity - current_line.quantity as u32,
+                                None,
                                 conn,
                             )?;
                             current_line.quantity = match_data.update_order_item.quantity as i64;
@@ -1532,6 +1864,7 @@ impl Order {
                 unit_price_in_cents: price_in_cents,
                 hold_id: match_data.hold_id,
                 code_id: match_data.code_id,
+                bundle_id: None,
             }
             .commit(conn);
 
@@ -1541,6 +1874,11 @@ impl Order {
                 match_data.update_order_item.ticket_type_id,
                 match_data.hold_id,
                 match_data.update_order_item.quantity,
+                match_data
+                    .update_order_item
+                    .seat_ids
+                    .as_ref()
+                    .map(|seat_ids| seat_ids.as_slice()),
                 conn,
             )?;
         }
@@ -1549,6 +1887,225 @@ impl Order {
         if self.items(conn)?.len() == 0 {
             self.remove_expiry(current_user_id, conn)?;
         }
+        self.validate_ticket_limits(check_ticket_limits, conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        // Beware there could be multiple orders that meet this condition
+        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
+            if remaining == 0 {
+                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the quantity of each bundle in the cart, a bundle's tickets are added and released together
+    pub fn update_bundle_quantities(
+        &mut self,
+        current_user_id: Uuid,
+        items: &[UpdateOrderBundle],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        jlog!(Debug, "Update order bundle quantities", {"items": items, "user_id": current_user_id});
+
+        let current_items = self.items(conn)?;
+        let mut check_ticket_limits: Vec<LimitCheck> = vec![];
+        for item in items {
+            for current_line in current_items
+                .iter()
+                .filter(|i| i.item_type == OrderItemTypes::Tickets && i.bundle_id == Some(item.bundle_id))
+            {
+                jlog!(Level::Debug, "Removing existing bundle cart item", { "order_item.id": current_line.id, "bundle_id": item.bundle_id});
+                TicketInstance::release_tickets(
+                    current_line,
+                    current_line.quantity as u32,
+                    Some(current_user_id),
+                    conn,
+                )?;
+                self.destroy_item(current_line.id, conn)?;
+            }
+
+            if item.quantity == 0 {
+                continue;
+            }
+
+            let bundle = Bundle::find(item.bundle_id, conn)?;
+            if bundle.deleted_at.is_some() {
+                return DatabaseError::business_process_error("Bundle is no longer available");
+            }
+            let bundle_ticket_types = bundle.ticket_types(conn)?;
+            if bundle_ticket_types.is_empty() {
+                return DatabaseError::business_process_error("Bundle does not include any tickets");
+            }
+
+            // Set cart expiration time if not currently set (empty carts have no expiration)
+            if self.expires_at.is_none() {
+                self.set_expiry(Some(current_user_id), None, false, conn)?;
+            }
+
+            jlog!(Level::Debug, "Adding bundle cart items");
+            for bundle_ticket_type in bundle_ticket_types {
+                if !TicketType::is_event_available_for_sale(&bundle_ticket_type.ticket_type_id, conn)? {
+                    return DatabaseError::business_process_error("Bundle includes tickets that are not on sale");
+                }
+                let ticket_type = TicketType::find(bundle_ticket_type.ticket_type_id, conn)?;
+                let ticket_pricing =
+                    TicketPricing::get_current_ticket_pricing(ticket_type.id, self.box_office_pricing, false, conn)?;
+                let quantity = bundle_ticket_type.quantity * item.quantity as i64;
+                check_ticket_limits.push(LimitCheck {
+                    ticket_type_id: ticket_type.id,
+                    hold_id: None,
+                    code_id: None,
+                    limit_per_person: ticket_type.limit_per_person as u32,
+                    redemption_code: None,
+                });
+
+                let order_item = NewTicketsOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::Tickets,
+                    quantity,
+                    ticket_type_id: ticket_type.id,
+                    ticket_pricing_id: ticket_pricing.id,
+                    event_id: Some(ticket_type.event_id),
+                    unit_price_in_cents: bundle_ticket_type.allocated_price_in_cents,
+                    hold_id: None,
+                    code_id: None,
+                    bundle_id: Some(bundle.id),
+                }
+                .commit(conn)?;
+
+                TicketInstance::reserve_tickets(
+                    &order_item,
+                    self.expires_at,
+                    ticket_type.id,
+                    None,
+                    quantity as u32,
+                    None,
+                    conn,
+                )?;
+            }
+        }
+
+        // if the cart is empty at this point, it is effectively a new cart, remove expiration
+        if self.items(conn)?.len() == 0 {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+        self.validate_ticket_limits(check_ticket_limits, conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        // Beware there could be multiple orders that meet this condition
+        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
+            if remaining == 0 {
+                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the quantity of each product in the cart, products are held for the cart until it expires
+    pub fn update_product_quantities(
+        &mut self,
+        current_user_id: Uuid,
+        items: &[UpdateOrderProduct],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        jlog!(Debug, "Update order product quantities", {"items": items, "user_id": current_user_id});
+
+        for item in items {
+            let product = Product::find(item.product_id, conn)?;
+            if product.deleted_at.is_some() {
+                return DatabaseError::business_process_error("Product is no longer available");
+            }
+
+            let variants = product.variants(conn)?;
+            match item.product_variant_id {
+                Some(product_variant_id) => {
+                    if !variants.iter().any(|v| v.id == product_variant_id) {
+                        return DatabaseError::validation_error(
+                            "product_variant_id",
+                            "Variant does not belong to the product",
+                        );
+                    }
+                }
+                None => {
+                    if !variants.is_empty() {
+                        return DatabaseError::validation_error(
+                            "product_variant_id",
+                            "A variant must be selected for this product",
+                        );
+                    }
+                }
+            }
+
+            let event_id = match product.event_id.or(item.event_id) {
+                Some(event_id) => event_id,
+                None => {
+                    return DatabaseError::validation_error("event_id", "An event is required for this product");
+                }
+            };
+            if product.event_id.is_some() && item.event_id.is_some() && product.event_id != item.event_id {
+                return DatabaseError::validation_error("event_id", "Product is not sold for this event");
+            }
+            if Event::find(event_id, conn)?.organization_id != product.organization_id {
+                return DatabaseError::validation_error("event_id", "Product is not sold for this event");
+            }
+
+            let product = product.lock(conn)?;
+            for current_line in self.items(conn)?.iter().filter(|i| {
+                i.item_type == OrderItemTypes::Products
+                    && i.product_id == Some(product.id)
+                    && i.product_variant_id == item.product_variant_id
+                    && i.event_id == Some(event_id)
+            }) {
+                jlog!(Level::Debug, "Removing existing product cart item", { "order_item.id": current_line.id, "product_id": product.id});
+                self.destroy_item(current_line.id, conn)?;
+            }
+
+            if item.quantity == 0 {
+                continue;
+            }
+
+            if product.available_for_order(item.product_variant_id, Some(self.id), conn)? < item.quantity as i64 {
+                return DatabaseError::validation_error("quantity", "Not enough of this product is available");
+            }
+
+            // Set cart expiration time if not currently set (empty carts have no expiration)
+            if self.expires_at.is_none() {
+                self.set_expiry(Some(current_user_id), None, false, conn)?;
+            }
+
+            jlog!(Level::Debug, "Adding product cart item");
+            NewProductsOrderItem {
+                order_id: self.id,
+                item_type: OrderItemTypes::Products,
+                event_id: Some(event_id),
+                quantity: item.quantity as i64,
+                unit_price_in_cents: product.price_in_cents,
+                product_id: product.id,
+                product_variant_id: item.product_variant_id,
+            }
+            .commit(conn)?;
+        }
+
+        // if the cart is empty at this point, it is effectively a new cart, remove expiration
+        if self.items(conn)?.len() == 0 {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)
+    }
+
+    fn validate_ticket_limits(
+        &self,
+        check_ticket_limits: Vec<LimitCheck>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         for limit_check in check_ticket_limits {
             let ordered_quantity = Order::quantity_for_user_for_ticket_type(
                 self.user_id,
@@ -1585,15 +2142,6 @@ impl Order {
                 return Err(errors.into());
             }
         }
-        self.update_fees_and_discounts(conn)?;
-        self.validate_record(conn)?;
-        // Beware there could be multiple orders that meet this condition
-        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
-            if remaining == 0 {
-                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
-            }
-        }
-
         Ok(())
     }
 
@@ -1640,13 +2188,14 @@ impl Order {
             match o.item_type {
                 OrderItemTypes::EventFees => self.destroy_item(o.id, conn)?,
                 OrderItemTypes::CreditCardFees => self.destroy_item(o.id, conn)?,
+                OrderItemTypes::Tax => self.destroy_item(o.id, conn)?,
                 _ => {}
             }
         }
 
         // Box office purchased tickets do not have fees at this time
         if self.box_office_pricing {
-            return Ok(());
+            return self.update_taxes(conn);
         }
 
         let mut per_event_fees_included: HashMap<Uuid, bool> = HashMap::new();
@@ -1690,6 +2239,11 @@ impl Order {
                             all_zero_price = false;
                         }
                     }
+                    OrderItemTypes::Products => {
+                        if o.unit_price_in_cents > 0 {
+                            all_zero_price = false;
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -1742,6 +2296,63 @@ impl Order {
             }
         }
 
+        self.update_taxes(conn)
+    }
+
+    /// Adds a tax item to each taxable item in the order using the tax rate of its event. Must be
+    /// run after fees and discounts are in place as the tax is charged on the discounted price.
+    fn update_taxes(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let items = self.items(conn)?;
+        let mut tax_rates: HashMap<Uuid, Option<TaxRate>> = HashMap::new();
+
+        for item in &items {
+            let event_id = match item.event_id {
+                Some(event_id) => event_id,
+                None => continue,
+            };
+            if !tax_rates.contains_key(&event_id) {
+                let event = Event::find(event_id, conn)?;
+                tax_rates.insert(event_id, TaxRate::find_for_event(&event, conn)?);
+            }
+            let tax_rate = match tax_rates.get(&event_id) {
+                Some(Some(tax_rate)) => tax_rate,
+                _ => continue,
+            };
+
+            let taxable_amount = match item.item_type {
+                OrderItemTypes::Tickets => {
+                    let discount: i64 = items
+                        .iter()
+                        .filter(|i| i.parent_id == Some(item.id) && i.item_type == OrderItemTypes::Discount)
+                        .map(|i| i.unit_price_in_cents)
+                        .sum();
+                    item.unit_price_in_cents + discount
+                }
+                OrderItemTypes::Products => item.unit_price_in_cents,
+                item_type if item_type.is_fee() && tax_rate.applies_to_fees => item.unit_price_in_cents,
+                _ => continue,
+            };
+
+            let (unit_price_in_cents, tax_in_cents) = tax_rate.calculate(taxable_amount);
+            if tax_in_cents == 0 {
+                continue;
+            }
+
+            NewTaxOrderItem {
+                order_id: self.id,
+                item_type: OrderItemTypes::Tax,
+                event_id: item.event_id,
+                quantity: item.quantity,
+                unit_price_in_cents,
+                company_fee_in_cents: 0,
+                client_fee_in_cents: 0,
+                parent_id: Some(item.id),
+                tax_rate_id: Some(tax_rate.id),
+                tax_in_cents,
+            }
+            .commit(conn)?;
+        }
+
         Ok(())
     }
 
@@ -1853,6 +2464,39 @@ impl Order {
         Ok(result)
     }
 
+    /// Distinct currencies of the events the order has items for
+    pub fn currencies(&self, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        order_items::table
+            .inner_join(events::table.on(order_items::event_id.eq(events::id.nullable())))
+            .filter(order_items::order_id.eq(self.id))
+            .select(events::currency)
+            .distinct()
+            .order_by(events::currency)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load currencies for order")
+    }
+
+    /// Records the currency the order is charged in, refusing orders with items in more than one currency
+    pub fn set_currency(&mut self, conn: &PgConnection) -> Result<String, DatabaseError> {
+        let mut currencies = self.currencies(conn)?;
+        if currencies.len() > 1 {
+            return DatabaseError::business_process_error(
+                "Could not complete this checkout because it contains items in more than one currency",
+            );
+        }
+        if currencies.is_empty() {
+            return DatabaseError::business_process_error("Could not determine the currency of an empty order");
+        }
+        let currency = currencies.remove(0);
+
+        *self = diesel::update(&*self)
+            .set((orders::currency.eq(&currency), orders::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not set the currency of the order")?;
+
+        Ok(currency)
+    }
+
     pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
         let mut unique_events: Vec<Uuid> = self.items(conn)?.iter().filter_map(|i| i.event_id).collect();
         unique_events.sort();
@@ -2049,6 +2693,8 @@ impl Order {
             organization_ids: Option<Vec<Uuid>>,
             #[sql_type = "Nullable<Array<dUuid>>"]
             event_ids: Option<Vec<Uuid>>,
+            #[sql_type = "Nullable<Text>"]
+            currency: Option<String>,
         }
 
         let mut query = sql_query(
@@ -2071,7 +2717,8 @@ impl Order {
                 CAST(COALESCE(SUM(oi.unit_price_in_cents * oi.refunded_quantity), 0) as BigInt) as total_refunded_in_cents,
                 ARRAY_AGG(DISTINCT SUBSTRING(orgs.allowed_payment_providers::text from 2 for char_length(orgs.allowed_payment_providers::text) - 2)) FILTER (WHERE orgs.allowed_payment_providers IS NOT NULL) as allowed_payment_providers,
                 ARRAY_AGG(DISTINCT e.organization_id) FILTER (WHERE e.organization_id IS NOT NULL) as organization_ids,
-                ARRAY_AGG(DISTINCT e.id) FILTER (WHERE e.id IS NOT NULL) as event_ids
+                ARRAY_AGG(DISTINCT e.id) FILTER (WHERE e.id IS NOT NULL) as event_ids,
+                COALESCE(o.currency, MIN(e.currency)) as currency
             FROM orders o
             LEFT JOIN order_items oi ON oi.order_id = o.id
             LEFT JOIN events e ON oi.event_id = e.id
@@ -2105,6 +2752,7 @@ impl Order {
                 o.expires_at,
                 o.checkout_url_expires,
                 o.checkout_url,
+                o.currency,
                 p.payment_methods,
                 p.providers
             ORDER BY o.order_date desc
@@ -2220,6 +2868,11 @@ impl Order {
             let allowed_payment_methods: Vec<AllowedPaymentMethod> = intersect_set(&available_payment_providers)
                 .into_iter()
                 .filter_map(|p| match p {
+                    PaymentProviders::Braintree => Some(AllowedPaymentMethod {
+                        method: "Card".to_string(),
+                        provider: PaymentProviders::Braintree,
+                        display_name: "Card".to_string(),
+                    }),
                     PaymentProviders::Stripe => Some(AllowedPaymentMethod {
                         method: "Card".to_string(),
                         provider: PaymentProviders::Stripe,
@@ -2274,6 +2927,7 @@ impl Order {
                 limited_tickets_remaining,
                 total_in_cents: result.total_in_cents,
                 total_refunded_in_cents: result.total_refunded_in_cents,
+                currency: result.currency,
                 seconds_until_expiry,
                 user_id: result.user_id,
                 user,
@@ -2550,7 +3204,9 @@ impl Order {
             }
 
             OrderStatus::Draft => Ok(()),
-            OrderStatus::PendingPayment => self.update_status(current_user_id, OrderStatus::Draft, conn),
+            OrderStatus::PendingPayment | OrderStatus::PendingAuthentication => {
+                self.update_status(current_user_id, OrderStatus::Draft, conn)
+            }
         }
     }
 
@@ -2566,7 +3222,8 @@ impl Order {
         }
 
         let p = payment.commit(current_user_id, conn)?;
-        if p.status != PaymentStatus::Requested {
+        // The cart is kept until the customer has completed the payment
+        if p.status != PaymentStatus::Requested && p.status != PaymentStatus::RequiresAction {
             self.clear_user_cart(conn)?;
         }
 
@@ -2595,6 +3252,9 @@ impl Order {
                 .collect_vec()
             {
                 TicketInstance::mark_as_purchased(item, self.on_behalf_of_user_id.unwrap_or(self.user_id), conn)?;
+                if let Some(hold_id) = item.hold_id {
+                    WaitlistEntry::mark_purchased(hold_id, self.id, conn)?;
+                }
             }
 
             let ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
@@ -2765,6 +3425,15 @@ impl Order {
         Ok(self.calculate_total_and_refunded_total(conn)?.0)
     }
 
+    /// Portion of the order total retained by the platform per the fee schedule
+    pub fn calculate_company_fee_total(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self
+            .items(conn)?
+            .iter()
+            .map(|item| item.company_fee_in_cents * (item.quantity - item.refunded_quantity))
+            .sum())
+    }
+
     pub fn calculate_total_and_refunded_total(&self, conn: &PgConnection) -> Result<(i64, i64), DatabaseError> {
         let order_items = self.items(conn)?;
         let mut total = 0;
@@ -2865,6 +3534,7 @@ pub struct DisplayOrder {
     pub limited_tickets_remaining: Vec<TicketsRemaining>,
     pub total_in_cents: i64,
     pub total_refunded_in_cents: i64,
+    pub currency: Option<String>,
     pub user_id: Uuid,
     pub user: DisplayUser,
     pub order_number: String,
@@ -2909,6 +3579,25 @@ pub struct UpdateOrderItem {
     pub ticket_type_id: Uuid,
     pub quantity: u32,
     pub redemption_code: Option<String>,
+    /// Seats to reserve for ticket types with reserved seating, the best available seats are reserved if not set
+    #[serde(default)]
+    pub seat_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct UpdateOrderBundle {
+    pub bundle_id: Uuid,
+    pub quantity: u32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct UpdateOrderProduct {
+    pub product_id: Uuid,
+    /// Required for products with variants
+    pub product_variant_id: Option<Uuid>,
+    pub quantity: u32,
+    /// Event the product is sold for, required for products not tied to an event
+    pub event_id: Option<Uuid>,
 }
 
 #[test]
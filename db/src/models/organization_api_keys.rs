@@ -0,0 +1,215 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::organization_api_keys;
+use std::str::FromStr;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validator::ValidationErrors;
+use validators::{self, *};
+
+/// Keys are sent as bearer tokens, the prefix tells them apart from JWTs
+pub const API_KEY_PREFIX: &str = "bnk_";
+const API_KEY_LENGTH: usize = 40;
+// Characters of the key kept in the clear so keys can be told apart when listed
+const DISPLAYED_KEY_LENGTH: usize = 8;
+// Scopes tied to the login flow which make no sense for a key
+const UNGRANTABLE_SCOPES: [Scopes; 3] = [Scopes::MfaChallenge, Scopes::TemporaryUserPromote, Scopes::TokenRefresh];
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "organization_api_keys"]
+pub struct OrganizationApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub created_by_user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub event_ids: Vec<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_keys"]
+pub struct NewOrganizationApiKey {
+    pub organization_id: Uuid,
+    pub created_by_user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub event_ids: Vec<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl NewOrganizationApiKey {
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors: Result<(), ValidationErrors> = Ok(());
+        if self.scopes.is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "scopes",
+                Err(create_validation_error("required", "At least one scope is required")),
+            );
+        }
+        if UNGRANTABLE_SCOPES
+            .iter()
+            .any(|scope| self.scopes.contains(&scope.to_string()))
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "scopes",
+                Err(create_validation_error(
+                    "invalid",
+                    "Scopes can not be granted to an API key",
+                )),
+            );
+        }
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "event_ids",
+            event_ids_belong_to_organization_validation(true, self.organization_id, &self.event_ids, conn)?,
+        );
+
+        Ok(validation_errors?)
+    }
+
+    pub fn commit(&self, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        self.validate_record(conn)?;
+        let api_key: OrganizationApiKey = diesel::insert_into(organization_api_keys::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create organization API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationApiKeyCreated,
+            "Organization API key created".to_string(),
+            Tables::Organizations,
+            Some(api_key.organization_id),
+            Some(api_key.created_by_user_id),
+            Some(json!({ "organization_api_key_id": api_key.id, "scopes": api_key.scopes })),
+        )
+        .commit(conn)?;
+
+        Ok(api_key)
+    }
+}
+
+impl OrganizationApiKey {
+    /// Also returns the generated key, only its hash is stored so it can not be shown again
+    pub fn create(
+        organization_id: Uuid,
+        created_by_user_id: Uuid,
+        name: String,
+        scopes: Vec<Scopes>,
+        event_ids: Vec<Uuid>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> (NewOrganizationApiKey, String) {
+        let key = format!("{}{}", API_KEY_PREFIX, random_alpha_string(API_KEY_LENGTH));
+        let mut scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        scopes.sort();
+        scopes.dedup();
+
+        (
+            NewOrganizationApiKey {
+                organization_id,
+                created_by_user_id,
+                name,
+                key_prefix: key[..API_KEY_PREFIX.len() + DISPLAYED_KEY_LENGTH].to_string(),
+                key_hash: sha256::digest(&key),
+                scopes,
+                event_ids,
+                expires_at,
+            },
+            key,
+        )
+    }
+
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(API_KEY_PREFIX)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        organization_api_keys::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization API key")
+    }
+
+    pub fn find_by_key(key: &str, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        organization_api_keys::table
+            .filter(organization_api_keys::key_hash.eq(sha256::digest(key)))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization API key")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationApiKey>, DatabaseError> {
+        organization_api_keys::table
+            .filter(organization_api_keys::organization_id.eq(organization_id))
+            .order_by(organization_api_keys::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization API keys")
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map(|e| e > Utc::now().naive_utc()).unwrap_or(true)
+    }
+
+    pub fn get_scopes(&self) -> Vec<Scopes> {
+        self.scopes.iter().filter_map(|s| Scopes::from_str(s).ok()).collect()
+    }
+
+    /// Keys only act within their organization, keys restricted to events only pass event checks
+    pub fn allows(&self, scope: Scopes, organization_id: Uuid, event_id: Option<Uuid>) -> bool {
+        if !self.is_active() || self.organization_id != organization_id || !self.get_scopes().contains(&scope) {
+            return false;
+        }
+        if self.event_ids.is_empty() {
+            return true;
+        }
+        event_id.map(|id| self.event_ids.contains(&id)).unwrap_or(false)
+    }
+
+    pub fn record_usage(&self, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        diesel::update(self)
+            .set(organization_api_keys::last_used_at.eq(dsl::now.nullable()))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not record organization API key usage")
+    }
+
+    pub fn revoke(&self, revoked_by: Option<&User>, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        let api_key: OrganizationApiKey = diesel::update(self)
+            .set((
+                organization_api_keys::revoked_at.eq(dsl::now.nullable()),
+                organization_api_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke organization API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationApiKeyRevoked,
+            "Organization API key revoked".to_string(),
+            Tables::Organizations,
+            Some(api_key.organization_id),
+            revoked_by.map(|u| u.id),
+            Some(json!({ "organization_api_key_id": api_key.id })),
+        )
+        .commit(conn)?;
+
+        Ok(api_key)
+    }
+}
@@ -19,6 +19,7 @@ use utils::errors::*;
 use utils::pagination::Paginate;
 use utils::text;
 use uuid::Uuid;
+use validators;
 
 const DEFAULT_SETTLEMENT_TIMEZONE: &str = "America/Los_Angeles";
 
@@ -54,6 +55,12 @@ pub struct Organization {
     pub slug_id: Option<Uuid>,
     pub google_ads_conversion_id: Option<String>,
     pub google_ads_conversion_labels: Vec<String>,
+    pub braintree_public_key: Option<String>,
+    pub braintree_private_key: Option<String>,
+    pub braintree_merchant_account_id: Option<String>,
+    pub stripe_connect_account_id: Option<String>,
+    pub currency: String,
+    pub require_mfa_for_financial_scopes: bool,
 }
 
 #[derive(Serialize)]
@@ -90,6 +97,16 @@ pub struct NewOrganization {
     pub globee_api_key: Option<String>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub braintree_public_key: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub braintree_private_key: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub braintree_merchant_account_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub stripe_connect_account_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Serialize, Clone, Deserialize, Debug, PartialEq)]
@@ -108,6 +125,10 @@ impl NewOrganization {
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        if let Some(ref currency) = self.currency {
+            validators::append_validation_error(Ok(()), "currency", validators::validate_currency(currency))?;
+        }
+
         let mut updated_organisation = self;
         if encryption_key.len() > 0 {
             if let Some(key) = updated_organisation.sendgrid_api_key.clone() {
@@ -122,6 +143,9 @@ impl NewOrganization {
             if let Some(key) = updated_organisation.globee_api_key.clone() {
                 updated_organisation.globee_api_key = Some(encrypt(&key, encryption_key)?);
             }
+            if let Some(key) = updated_organisation.braintree_private_key.clone() {
+                updated_organisation.braintree_private_key = Some(encrypt(&key, encryption_key)?);
+            }
         }
 
         let org: Organization = diesel::insert_into(organizations::table)
@@ -152,6 +176,7 @@ impl NewOrganization {
         diesel::update(fee_schedules::table.filter(fee_schedules::id.eq(org.fee_schedule_id)))
             .set((
                 fee_schedules::organization_id.eq(org.id),
+                fee_schedules::currency.eq(&org.currency),
                 fee_schedules::updated_at.eq(dsl::now),
             ))
             .execute(conn)
@@ -214,6 +239,18 @@ pub struct OrganizationEditableAttributes {
     pub google_ads_conversion_id: Option<Option<String>>,
     #[serde(default)]
     pub google_ads_conversion_labels: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub braintree_public_key: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub braintree_private_key: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub braintree_merchant_account_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub stripe_connect_account_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub require_mfa_for_financial_scopes: Option<bool>,
 }
 
 impl Organization {
@@ -358,6 +395,10 @@ impl Organization {
         encryption_key: &String,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        if let Some(ref currency) = attributes.currency {
+            validators::append_validation_error(Ok(()), "currency", validators::validate_currency(currency))?;
+        }
+
         if encryption_key.len() > 0 {
             if let Some(Some(key)) = attributes.sendgrid_api_key {
                 attributes.sendgrid_api_key = Some(Some(encrypt(&key, encryption_key)?));
@@ -371,6 +412,9 @@ impl Organization {
             if let Some(Some(key)) = attributes.globee_api_key {
                 attributes.globee_api_key = Some(Some(encrypt(&key, encryption_key)?));
             }
+            if let Some(Some(key)) = attributes.braintree_private_key {
+                attributes.braintree_private_key = Some(Some(encrypt(&key, encryption_key)?));
+            }
 
             if let Some(Some(key)) = attributes.google_ads_conversion_id {
                 attributes.google_ads_conversion_id = Some(Some(encrypt(&key, encryption_key)?));
@@ -405,6 +449,15 @@ impl Organization {
             ))
             .get_result::<Organization>(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not update organization")?;
+        if organization.currency != self.currency {
+            diesel::update(fee_schedules::table.filter(fee_schedules::id.eq(organization.fee_schedule_id)))
+                .set((
+                    fee_schedules::currency.eq(&organization.currency),
+                    fee_schedules::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update the fee schedule currency")?;
+        }
         organization.schedule_domain_actions(settlement_period_in_days, conn)?;
 
         Ok(organization)
@@ -550,11 +603,20 @@ impl Organization {
 
     pub fn get_scopes_for_user(&self, user: &User, conn: &PgConnection) -> Result<Vec<Scopes>, DatabaseError> {
         let (roles, additional_scopes) = self.get_roles_for_user(user, conn)?;
-        let user_scopes = scopes::get_scopes(roles, additional_scopes);
+        let mut user_scopes = scopes::get_scopes(roles, additional_scopes);
+        self.apply_mfa_policy(user, &mut user_scopes);
 
         Ok(user_scopes)
     }
 
+    /// Members only receive financial scopes once they have set up MFA when the organization
+    /// requires it
+    pub fn apply_mfa_policy(&self, user: &User, scopes: &mut Vec<Scopes>) {
+        if self.require_mfa_for_financial_scopes && !user.mfa_enabled() {
+            scopes.retain(|scope| !scope.is_financial());
+        }
+    }
+
     pub fn get_roles_for_user(
         &self,
         user: &User,
@@ -761,6 +823,7 @@ impl Organization {
         diesel::update(fee_schedule)
             .set((
                 fee_schedules::organization_id.eq(self.id),
+                fee_schedules::currency.eq(&self.currency),
                 fee_schedules::updated_at.eq(dsl::now),
             ))
             .execute(conn)
@@ -1160,6 +1223,9 @@ impl Organization {
             if let Some(key) = self.globee_api_key.clone() {
                 self.globee_api_key = Some(decrypt(&key, &encryption_key)?);
             }
+            if let Some(key) = self.braintree_private_key.clone() {
+                self.braintree_private_key = Some(decrypt(&key, &encryption_key)?);
+            }
         }
 
         Ok(())
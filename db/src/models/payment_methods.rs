@@ -19,23 +19,28 @@ pub struct PaymentMethod {
     pub provider_data: serde_json::Value,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub organization_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
 #[table_name = "payment_methods"]
 pub struct PaymentMethodEditableAttributes {
+    pub provider: Option<String>,
     pub provider_data: Option<serde_json::Value>,
 }
 
 sql_function!(fn lower(x: Text) -> Text);
 
 impl PaymentMethod {
+    /// `organization_id` is set for processors that vault payment methods with the organization's own
+    /// account, such methods can only be charged for that organization's events
     pub fn create(
         user_id: Uuid,
         name: PaymentProviders,
         is_default: bool,
         provider: String,
         data: serde_json::Value,
+        organization_id: Option<Uuid>,
     ) -> NewPaymentMethod {
         NewPaymentMethod {
             user_id,
@@ -43,6 +48,7 @@ impl PaymentMethod {
             is_default,
             provider,
             provider_data: data,
+            organization_id,
         }
     }
 
@@ -73,6 +79,29 @@ impl PaymentMethod {
             .to_db_error(ErrorCode::QueryError, "Could not load payment methods for user")
     }
 
+    /// Finds the user's payment method for the processor, `organization_id` is `None` for payment methods
+    /// that can be charged for any organization
+    pub fn find_for_user_and_organization(
+        user_id: Uuid,
+        name: PaymentProviders,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PaymentMethod, DatabaseError> {
+        let mut query = payment_methods::table
+            .filter(payment_methods::user_id.eq(user_id))
+            .filter(lower(payment_methods::name).eq(lower(name)))
+            .into_boxed();
+
+        query = match organization_id {
+            Some(organization_id) => query.filter(payment_methods::organization_id.eq(organization_id)),
+            None => query.filter(payment_methods::organization_id.is_null()),
+        };
+
+        query
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment method for user")
+    }
+
     pub fn update(
         &self,
         attributes: &PaymentMethodEditableAttributes,
@@ -113,6 +142,7 @@ pub struct NewPaymentMethod {
     is_default: bool,
     provider: String,
     provider_data: serde_json::Value,
+    organization_id: Option<Uuid>,
 }
 
 impl NewPaymentMethod {
@@ -11,6 +11,9 @@ use serde_json;
 use utils::errors::*;
 use uuid::Uuid;
 
+/// Time a customer has to complete 3-D Secure or similar authentication before the order expires
+pub const PAYMENT_AUTHENTICATION_EXPIRY_MINUTES: i64 = 30;
+
 #[allow(dead_code)]
 #[derive(Debug, Identifiable, PartialEq, Queryable)]
 pub struct Payment {
@@ -96,6 +99,19 @@ impl Payment {
             .expect_single()
     }
 
+    pub fn find_by_external_reference(
+        provider: PaymentProviders,
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        payments::table
+            .filter(payments::provider.eq(provider))
+            .filter(payments::external_reference.eq(external_reference))
+            .filter(payments::refund_id.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment")
+    }
+
     pub fn log_refund(
         &self,
         current_user_id: Uuid,
@@ -138,6 +154,16 @@ impl Payment {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         self.update_status(new_status, current_user_id, conn)?;
+        self.record_ipn(raw_data, current_user_id, conn)
+    }
+
+    /// Records a provider notification that does not change the payment's status
+    pub fn record_ipn(
+        &self,
+        raw_data: serde_json::Value,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         DomainEvent::create(
             DomainEventTypes::PaymentProviderIPN,
             "Payment IPN received".to_string(),
@@ -240,6 +266,42 @@ impl Payment {
         Ok(())
     }
 
+    /// Holds the order while the customer completes additional authentication with their card issuer
+    pub fn mark_requires_action(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status != PaymentStatus::RequiresAction {
+            self.update_status(PaymentStatus::RequiresAction, current_user_id, conn)?;
+        }
+        let mut order = self.order(conn)?;
+        order.update_status(current_user_id, OrderStatus::PendingAuthentication, conn)?;
+        order.set_expiry(
+            current_user_id,
+            Some(Utc::now().naive_utc() + Duration::minutes(PAYMENT_AUTHENTICATION_EXPIRY_MINUTES)),
+            false,
+            conn,
+        )?;
+        Ok(())
+    }
+
+    /// Cancels a payment the customer could not authenticate, returning the order to the cart
+    pub fn mark_authentication_failed(
+        &self,
+        raw_data: serde_json::Value,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status != PaymentStatus::RequiresAction {
+            return DatabaseError::business_process_error(
+                "Could not mark payment authentication as failed because it is not awaiting authentication",
+            );
+        }
+        self.mark_cancelled(raw_data, current_user_id, conn)?;
+        self.order(conn)?.reset_to_draft(current_user_id, conn)
+    }
+
     pub fn mark_cancelled(
         &self,
         raw_data: serde_json::Value,
@@ -251,7 +313,7 @@ impl Payment {
             Completed | Authorized | Refunded | PendingConfirmation => DatabaseError::business_process_error(
                 "Could not mark payment as cancelled because it is in a status that doesn't allow cancelling",
             ),
-            Requested | Unpaid | Draft | Unknown | PendingIpn => {
+            Requested | Unpaid | Draft | Unknown | PendingIpn | RequiresAction => {
                 DomainEvent::create(
                     DomainEventTypes::PaymentCancelled,
                     "Payment was cancelled".to_string(),
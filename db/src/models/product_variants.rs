@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::prelude::*;
+use models::Product;
+use schema::{order_items, product_variants};
+use utils::errors::*;
+use uuid::Uuid;
+
+/// An option of a product with its own inventory, e.g. the size of a T-shirt
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Product)]
+#[table_name = "product_variants"]
+pub struct ProductVariant {
+    pub id: Uuid,
+    pub product_id: Uuid,
+    pub name: String,
+    pub quantity: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "product_variants"]
+pub(crate) struct NewProductVariant {
+    pub product_id: Uuid,
+    pub name: String,
+    pub quantity: i64,
+}
+
+impl NewProductVariant {
+    pub(crate) fn commit(&self, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        diesel::insert_into(product_variants::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add variant to product")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct UpdateProductVariant {
+    pub name: String,
+    pub quantity: u32,
+}
+
+impl ProductVariant {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        product_variants::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load product variant")
+    }
+
+    pub fn find_for_product(product_id: Uuid, conn: &PgConnection) -> Result<Vec<ProductVariant>, DatabaseError> {
+        product_variants::table
+            .filter(product_variants::product_id.eq(product_id))
+            .order_by(product_variants::created_at)
+            .then_order_by(product_variants::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load variants for product")
+    }
+
+    /// Whether the variant has been added to any order, such variants are kept for reporting
+    pub fn has_order_items(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            order_items::table.filter(order_items::product_variant_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if product variant has been ordered",
+        )
+    }
+
+    pub(crate) fn update_quantity(&self, quantity: i64, conn: &PgConnection) -> Result<ProductVariant, DatabaseError> {
+        diesel::update(self)
+            .set((
+                product_variants::quantity.eq(quantity),
+                product_variants::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update product variant")
+    }
+
+    pub(crate) fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove variant from product")
+    }
+}
@@ -0,0 +1,442 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Uuid as dUuid};
+use models::*;
+use schema::products;
+use serde_with::rust::double_option;
+use std::cmp;
+use std::collections::HashSet;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::*;
+use validators::{self, *};
+
+/// A non-admission item sold alongside tickets, e.g. a parking pass, drink ticket or T-shirt. Products
+/// without an event can be added to the cart for any event of the organization.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "products"]
+pub struct Product {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    /// Inventory of the product, products with variants track inventory per variant instead
+    pub quantity: i64,
+    /// Whether the product is handed out at the door and has to be marked as picked up
+    pub requires_pickup: bool,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "products"]
+pub struct NewProduct {
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub quantity: i64,
+    pub requires_pickup: bool,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Serialize, Validate)]
+#[table_name = "products"]
+pub struct ProductEditableAttributes {
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub description: Option<Option<String>>,
+    pub price_in_cents: Option<i64>,
+    pub quantity: Option<i64>,
+    pub requires_pickup: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayProduct {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub price_in_cents: i64,
+    pub quantity: i64,
+    pub requires_pickup: bool,
+    /// Units that can still be bought, across all variants for products with variants
+    pub available: i64,
+    pub variants: Vec<DisplayProductVariant>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayProductVariant {
+    pub id: Uuid,
+    pub name: String,
+    pub quantity: i64,
+    pub available: i64,
+}
+
+impl NewProduct {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        let mut validation_errors = match self.validate() {
+            Ok(_) => Ok(()),
+            Err(errors) => Err(errors),
+        };
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "price_in_cents",
+            Product::price_valid(self.price_in_cents),
+        );
+        validation_errors =
+            validators::append_validation_error(validation_errors, "quantity", Product::quantity_valid(self.quantity));
+        if let Some(event_id) = self.event_id {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "event_id",
+                Product::event_valid(self.organization_id, event_id, conn)?,
+            );
+        }
+        validation_errors?;
+
+        let product: Product = diesel::insert_into(products::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductCreated,
+            format!("Product '{}' created", &product.name),
+            Tables::Products,
+            Some(product.id),
+            current_user_id,
+            Some(json!(&product)),
+        )
+        .commit(conn)?;
+
+        Ok(product)
+    }
+}
+
+impl Product {
+    pub fn create(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        name: String,
+        description: Option<String>,
+        price_in_cents: i64,
+        quantity: i64,
+        requires_pickup: bool,
+    ) -> NewProduct {
+        NewProduct {
+            organization_id,
+            event_id,
+            name,
+            description,
+            price_in_cents,
+            quantity,
+            requires_pickup,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        products::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load product")
+    }
+
+    /// Products of the organization, when an event is given only the products that can be sold for it
+    /// are returned: the event's own products and those not tied to an event
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Product>, DatabaseError> {
+        let mut query = products::table
+            .filter(products::organization_id.eq(organization_id))
+            .filter(products::deleted_at.is_null())
+            .into_boxed();
+        if let Some(event_id) = event_id {
+            query = query.filter(products::event_id.eq(event_id).or(products::event_id.is_null()));
+        }
+
+        query
+            .order_by(products::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load products for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn variants(&self, conn: &PgConnection) -> Result<Vec<ProductVariant>, DatabaseError> {
+        ProductVariant::find_for_product(self.id, conn)
+    }
+
+    pub fn update(
+        &self,
+        attributes: ProductEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Product, DatabaseError> {
+        let mut validation_errors = match attributes.validate() {
+            Ok(_) => Ok(()),
+            Err(errors) => Err(errors),
+        };
+        if let Some(price_in_cents) = attributes.price_in_cents {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "price_in_cents",
+                Product::price_valid(price_in_cents),
+            );
+        }
+        if let Some(quantity) = attributes.quantity {
+            validation_errors =
+                validators::append_validation_error(validation_errors, "quantity", Product::quantity_valid(quantity));
+        }
+        validation_errors?;
+
+        let product: Product = diesel::update(self)
+            .set((&attributes, products::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductUpdated,
+            format!("Product '{}' updated", &product.name),
+            Tables::Products,
+            Some(product.id),
+            current_user_id,
+            Some(json!(&attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(product)
+    }
+
+    /// Replaces the variants of the product, variants are matched by name so their inventory can be
+    /// adjusted without losing the orders placed for them
+    pub fn update_variants(
+        &self,
+        variants: &[UpdateProductVariant],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<ProductVariant>, DatabaseError> {
+        if self.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Product has been deleted");
+        }
+
+        let mut validation_errors: Result<(), ValidationErrors> = Ok(());
+        let mut names = HashSet::new();
+        for variant in variants {
+            if variant.name.is_empty() {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "name",
+                    Err(create_validation_error("length", "Name is required")),
+                );
+            } else if !names.insert(variant.name.as_str()) {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "name",
+                    Err(create_validation_error(
+                        "duplicate",
+                        "Variant names must be unique within a product",
+                    )),
+                );
+            }
+        }
+        validation_errors?;
+
+        let existing = self.variants(conn)?;
+        for variant in existing.iter().filter(|v| !names.contains(v.name.as_str())) {
+            if variant.has_order_items(conn)? {
+                return DatabaseError::business_process_error(&format!(
+                    "Variant '{}' has been ordered and can not be removed",
+                    variant.name
+                ));
+            }
+            variant.destroy(conn)?;
+        }
+        for variant in variants {
+            match existing.iter().find(|v| v.name == variant.name) {
+                Some(existing_variant) => {
+                    existing_variant.update_quantity(variant.quantity as i64, conn)?;
+                }
+                None => {
+                    NewProductVariant {
+                        product_id: self.id,
+                        name: variant.name.clone(),
+                        quantity: variant.quantity as i64,
+                    }
+                    .commit(conn)?;
+                }
+            }
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::ProductUpdated,
+            format!("Product '{}' variants updated", &self.name),
+            Tables::Products,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "variants": variants })),
+        )
+        .commit(conn)?;
+
+        self.variants(conn)
+    }
+
+    pub fn delete(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                products::deleted_at.eq(dsl::now.nullable()),
+                products::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete product")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ProductDeleted,
+            format!("Product '{}' deleted", &self.name),
+            Tables::Products,
+            Some(self.id),
+            current_user_id,
+            Some(json!(&self)),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    /// Units of the product, or of the given variant, that can still be bought
+    pub fn available(&self, product_variant_id: Option<Uuid>, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        self.available_for_order(product_variant_id, None, conn)
+    }
+
+    /// Units that can still be bought ignoring the units held by `order_id`, so an order can change the
+    /// quantity it already holds
+    pub(crate) fn available_for_order(
+        &self,
+        product_variant_id: Option<Uuid>,
+        order_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        let inventory = match product_variant_id {
+            Some(product_variant_id) => ProductVariant::find(product_variant_id, conn)?.quantity,
+            None => {
+                let variants = self.variants(conn)?;
+                if variants.is_empty() {
+                    self.quantity
+                } else {
+                    variants.iter().map(|v| v.quantity).sum()
+                }
+            }
+        };
+
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "BigInt"]
+            quantity: i64,
+        }
+
+        // Paid units and units held by carts that have not expired are unavailable
+        let result: R = diesel::sql_query(
+            r#"
+            SELECT CAST(COALESCE(SUM(oi.quantity - oi.refunded_quantity), 0) AS BIGINT) AS quantity
+            FROM order_items oi
+            JOIN orders o ON oi.order_id = o.id
+            WHERE oi.product_id = $1
+            AND oi.item_type = 'Products'
+            AND ($2 IS NULL OR oi.product_variant_id = $2)
+            AND ($3 IS NULL OR o.id <> $3)
+            AND (
+                o.status = 'Paid'
+                OR (o.status IN ('Draft', 'PendingPayment', 'PendingAuthentication') AND o.expires_at > now())
+            )
+        "#,
+        )
+        .bind::<dUuid, _>(self.id)
+        .bind::<Nullable<dUuid>, _>(product_variant_id)
+        .bind::<Nullable<dUuid>, _>(order_id)
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load sold quantity for product")?;
+
+        Ok(cmp::max(inventory - result.quantity, 0))
+    }
+
+    /// Locks the product row so concurrent carts check its inventory one at a time
+    pub(crate) fn lock(&self, conn: &PgConnection) -> Result<Product, DatabaseError> {
+        products::table
+            .find(self.id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock product")
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayProduct, DatabaseError> {
+        let mut variants = Vec::new();
+        for variant in self.variants(conn)? {
+            variants.push(DisplayProductVariant {
+                available: self.available(Some(variant.id), conn)?,
+                id: variant.id,
+                name: variant.name,
+                quantity: variant.quantity,
+            });
+        }
+
+        Ok(DisplayProduct {
+            id: self.id,
+            organization_id: self.organization_id,
+            event_id: self.event_id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            price_in_cents: self.price_in_cents,
+            quantity: self.quantity,
+            requires_pickup: self.requires_pickup,
+            available: self.available(None, conn)?,
+            variants,
+        })
+    }
+
+    fn event_valid(
+        organization_id: Uuid,
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Result<(), ValidationError>, DatabaseError> {
+        if Event::find(event_id, conn)?.organization_id != organization_id {
+            return Ok(Err(create_validation_error(
+                "invalid_organization",
+                "Event must belong to the product's organization",
+            )));
+        }
+        Ok(Ok(()))
+    }
+
+    fn price_valid(price_in_cents: i64) -> Result<(), ValidationError> {
+        if price_in_cents < 0 {
+            return Err(create_validation_error(
+                "invalid_price",
+                "Price must be zero or greater",
+            ));
+        }
+        Ok(())
+    }
+
+    fn quantity_valid(quantity: i64) -> Result<(), ValidationError> {
+        if quantity < 0 {
+            return Err(create_validation_error(
+                "invalid_quantity",
+                "Quantity must be zero or greater",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -49,4 +49,10 @@ pub struct RedeemableTicket {
     pub redeemed_by: Option<String>,
     #[sql_type = "Nullable<Timestamp>"]
     pub redeemed_at: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_section: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_row: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_number: Option<String>,
 }
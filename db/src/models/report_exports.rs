@@ -0,0 +1,259 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bytea, Nullable, Uuid as dUuid};
+use models::*;
+use schema::report_exports;
+use serde_json::Value;
+use utils::errors::*;
+use utils::hash::sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// Days the download link of a completed export stays valid
+pub const REPORT_EXPORT_DOWNLOAD_EXPIRY_DAYS: i64 = 7;
+const DOWNLOAD_TOKEN_LENGTH: usize = 40;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "report_exports"]
+pub struct ReportExport {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub report: String,
+    pub format: ReportExportFormat,
+    pub parameters: Value,
+    pub status: ReportExportStatus,
+    #[serde(skip_serializing)]
+    pub content: Option<Vec<u8>>,
+    pub row_count: Option<i64>,
+    #[serde(skip_serializing)]
+    pub download_token_hash: Option<String>,
+    pub error_message: Option<String>,
+    pub completed_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ReportExport {
+    pub fn create(
+        organization_id: Uuid,
+        user_id: Uuid,
+        report: String,
+        format: ReportExportFormat,
+        parameters: Value,
+    ) -> NewReportExport {
+        NewReportExport {
+            organization_id,
+            user_id,
+            report,
+            format,
+            parameters,
+            status: ReportExportStatus::Pending,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ReportExport, DatabaseError> {
+        report_exports::table
+            .filter(report_exports::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report export")
+    }
+
+    /// Finds a completed export the download token was issued for, as long as it has not expired.
+    /// The file is not loaded, it is read with `content_chunk` as it is downloaded.
+    pub fn find_for_download(
+        id: Uuid,
+        download_token: &str,
+        conn: &PgConnection,
+    ) -> Result<ReportExport, DatabaseError> {
+        report_exports::table
+            .filter(report_exports::id.eq(id))
+            .filter(report_exports::status.eq(ReportExportStatus::Completed))
+            .filter(report_exports::download_token_hash.eq(sha256::digest(download_token)))
+            .filter(report_exports::expires_at.gt(dsl::now))
+            .select((
+                report_exports::id,
+                report_exports::organization_id,
+                report_exports::user_id,
+                report_exports::report,
+                report_exports::format,
+                report_exports::parameters,
+                report_exports::status,
+                None::<Vec<u8>>.into_sql::<Nullable<Bytea>>(),
+                report_exports::row_count,
+                report_exports::download_token_hash,
+                report_exports::error_message,
+                report_exports::completed_at,
+                report_exports::expires_at,
+                report_exports::created_at,
+                report_exports::updated_at,
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report export")
+    }
+
+    /// Reads up to `length` bytes of the stored file starting at `offset`, empty once the end of
+    /// the file has been reached
+    pub fn content_chunk(&self, offset: i64, length: i64, conn: &PgConnection) -> Result<Vec<u8>, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "Nullable<Bytea>"]
+            chunk: Option<Vec<u8>>,
+        }
+
+        let result: R = diesel::sql_query(
+            "SELECT substring(content FROM ($2 + 1)::INTEGER FOR $3::INTEGER) AS chunk FROM report_exports WHERE id = $1",
+        )
+        .bind::<dUuid, _>(self.id)
+        .bind::<BigInt, _>(offset)
+        .bind::<BigInt, _>(length)
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load report export content")?;
+
+        Ok(result.chunk.unwrap_or_default())
+    }
+
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}_{}.{}",
+            self.report,
+            self.created_at.format("%Y%m%d"),
+            self.format.extension()
+        )
+    }
+
+    /// Starts the stored file over with the first chunk written by the export
+    pub fn set_content(&self, chunk: &[u8], conn: &PgConnection) -> Result<ReportExport, DatabaseError> {
+        diesel::update(self)
+            .set((
+                report_exports::content.eq(Some(chunk)),
+                report_exports::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update report export")
+    }
+
+    /// Adds the next chunk written by the export so the file is never held in memory as a whole
+    pub fn append_content(&self, chunk: &[u8], conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::sql_query(
+            "UPDATE report_exports SET content = COALESCE(content, ''::BYTEA) || $2, updated_at = now() WHERE id = $1",
+        )
+        .bind::<dUuid, _>(self.id)
+        .bind::<Bytea, _>(chunk)
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update report export")?;
+
+        Ok(())
+    }
+
+    /// Completes an export whose file has been written and returns it along with the token for its
+    /// download link, only the hash of the token is kept
+    pub fn mark_completed(&self, row_count: i64, conn: &PgConnection) -> Result<(ReportExport, String), DatabaseError> {
+        let download_token = random_alpha_string(DOWNLOAD_TOKEN_LENGTH);
+        let report_export = diesel::update(self)
+            .set((
+                report_exports::status.eq(ReportExportStatus::Completed),
+                report_exports::row_count.eq(Some(row_count)),
+                report_exports::download_token_hash.eq(Some(sha256::digest(&download_token))),
+                report_exports::completed_at.eq(dsl::now.nullable()),
+                report_exports::expires_at.eq(Some(
+                    Utc::now().naive_utc() + Duration::days(REPORT_EXPORT_DOWNLOAD_EXPIRY_DAYS),
+                )),
+                report_exports::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update report export")?;
+
+        Ok((report_export, download_token))
+    }
+
+    pub fn mark_failed(&self, error_message: String, conn: &PgConnection) -> Result<ReportExport, DatabaseError> {
+        diesel::update(self)
+            .set((
+                report_exports::status.eq(ReportExportStatus::Failed),
+                report_exports::content.eq(None::<Vec<u8>>),
+                report_exports::error_message.eq(Some(error_message)),
+                report_exports::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update report export")
+    }
+
+    /// Removes the files of exports whose download link has expired, the exports themselves are kept
+    pub fn purge_expired(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(
+            report_exports::table
+                .filter(report_exports::expires_at.lt(dsl::now))
+                .filter(report_exports::content.is_not_null()),
+        )
+        .set((
+            report_exports::content.eq(None::<Vec<u8>>),
+            report_exports::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not purge expired report exports")
+    }
+
+    pub fn create_next_purge_expired_domain_action(conn: &PgConnection) -> Result<(), DatabaseError> {
+        let now = Utc::now().naive_utc();
+        if let Some(upcoming_domain_action) =
+            DomainAction::upcoming_domain_action(None, None, DomainActionTypes::PurgeExpiredReportExports, conn)?
+        {
+            if upcoming_domain_action.scheduled_at > now {
+                return DatabaseError::business_process_error(
+                    "Purge expired report exports domain action is already pending",
+                );
+            }
+        }
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::PurgeExpiredReportExports,
+            None,
+            json!({}),
+            None,
+            None,
+        );
+        action.schedule_at(now.date().and_hms(0, 0, 0) + Duration::days(1));
+        action.commit(conn)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "report_exports"]
+pub struct NewReportExport {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub report: String,
+    pub format: ReportExportFormat,
+    pub parameters: Value,
+    pub status: ReportExportStatus,
+}
+
+impl NewReportExport {
+    /// Saves the export and queues the domain action that builds it
+    pub fn commit(self, conn: &PgConnection) -> Result<ReportExport, DatabaseError> {
+        let report_export: ReportExport = diesel::insert_into(report_exports::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not insert report export")?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::ProcessReportExport,
+            None,
+            json!({}),
+            Some(Tables::ReportExports),
+            Some(report_export.id),
+        )
+        .commit(conn)?;
+
+        Ok(report_export)
+    }
+}
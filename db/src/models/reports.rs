@@ -3,7 +3,7 @@ use chrono::Duration;
 use chrono_tz::Tz;
 use diesel;
 use diesel::prelude::*;
-use diesel::sql_types::{BigInt, Bool, Nullable, Text, Time, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Double, Nullable, Text, Time, Timestamp, Uuid as dUuid};
 use itertools::Itertools;
 use models::*;
 use std::collections::HashMap;
@@ -323,6 +323,12 @@ pub struct TransactionReportRow {
     pub headline_artist_alt_genres: Option<String>,
     #[sql_type = "Nullable<Text>"]
     pub headline_artist_main_genre: Option<String>,
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents_total: i64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -429,6 +435,8 @@ pub struct EventSummarySalesRow {
     pub total_gross_income_in_cents: i64,
     #[sql_type = "dUuid"]
     pub ticket_type_id: Uuid,
+    #[sql_type = "BigInt"]
+    pub total_tax_in_cents: i64,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -473,6 +481,8 @@ pub struct EventSummaryOtherFees {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub total_tax_in_cents: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -486,6 +496,86 @@ pub struct ScanCountReportRow {
     pub scanned_count: i64,
     #[sql_type = "BigInt"]
     pub not_scanned_count: i64,
+    #[sql_type = "BigInt"]
+    pub entry_count: i64,
+    #[sql_type = "BigInt"]
+    pub exit_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, QueryableByName)]
+pub struct WaitlistReportRow {
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "Text"]
+    pub ticket_type_name: String,
+    #[sql_type = "BigInt"]
+    pub joined_count: i64,
+    #[sql_type = "BigInt"]
+    pub waiting_count: i64,
+    #[sql_type = "BigInt"]
+    pub offered_count: i64,
+    #[sql_type = "BigInt"]
+    pub purchased_count: i64,
+    #[sql_type = "BigInt"]
+    pub expired_count: i64,
+    #[sql_type = "BigInt"]
+    pub cancelled_count: i64,
+    #[sql_type = "BigInt"]
+    pub tickets_sold: i64,
+    #[sql_type = "BigInt"]
+    pub sales_in_cents: i64,
+    #[sql_type = "Double"]
+    pub conversion_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, QueryableByName)]
+pub struct EventSeriesReportRow {
+    #[sql_type = "dUuid"]
+    pub event_series_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_series_name: String,
+    #[sql_type = "Text"]
+    pub recurrence_rule: String,
+    #[sql_type = "BigInt"]
+    pub occurrence_count: i64,
+    #[sql_type = "BigInt"]
+    pub upcoming_occurrence_count: i64,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub first_event_start: Option<NaiveDateTime>,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub last_event_start: Option<NaiveDateTime>,
+    #[sql_type = "BigInt"]
+    pub tickets_sold: i64,
+    #[sql_type = "BigInt"]
+    pub tickets_refunded: i64,
+    #[sql_type = "BigInt"]
+    pub sales_in_cents: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, QueryableByName)]
+pub struct ProductSalesReportRow {
+    #[sql_type = "dUuid"]
+    pub product_id: Uuid,
+    #[sql_type = "Text"]
+    pub product_name: String,
+    #[sql_type = "Nullable<Text>"]
+    pub product_variant_name: Option<String>,
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "BigInt"]
+    pub quantity_sold: i64,
+    #[sql_type = "BigInt"]
+    pub quantity_refunded: i64,
+    #[sql_type = "BigInt"]
+    pub quantity_redeemed: i64,
+    #[sql_type = "BigInt"]
+    pub sales_in_cents: i64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -1036,6 +1126,49 @@ impl Report {
         TicketSalesRow::fetch(None, None, true, true, true, false, event_id, organization_id, conn)
     }
 
+    /// Waitlist sign ups, offers and how many offers were converted to purchases per ticket type
+    pub fn waitlist_report(
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistReportRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_waitlist.sql");
+        diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(event_id)
+            .bind::<Nullable<dUuid>, _>(organization_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
+    /// Occurrences and ticket sales rolled up per event series. With an `event_id` only the series
+    /// containing that event is returned.
+    pub fn event_series_report(
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventSeriesReportRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_event_series.sql");
+        diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(event_id)
+            .bind::<Nullable<dUuid>, _>(organization_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
+    /// Paid product units per event and variant, including how many were refunded or picked up
+    pub fn product_sales_report(
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<ProductSalesReportRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_product_sales.sql");
+        diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(event_id)
+            .bind::<Nullable<dUuid>, _>(organization_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
     /// Fetches the generic ticket sales and counts data
     pub fn ticket_sales_and_counts(
         event_id: Option<Uuid>,
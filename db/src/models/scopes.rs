@@ -39,6 +39,7 @@ pub enum Scopes {
     HoldWrite,
     ListingWrite,
     LootBoxWrite,
+    MfaChallenge,
     NoteDelete,
     NoteRead,
     NoteWrite,
@@ -135,6 +136,7 @@ impl fmt::Display for Scopes {
             Scopes::HoldWrite => "hold:write",
             Scopes::ListingWrite => "listing:write",
             Scopes::LootBoxWrite => "loot-box:write",
+            Scopes::MfaChallenge => "mfa:challenge",
             Scopes::NoteDelete => "note:delete",
             Scopes::NoteRead => "note:read",
             Scopes::NoteWrite => "note:write",
@@ -227,6 +229,7 @@ impl FromStr for Scopes {
             "hold:write" => Scopes::HoldWrite,
             "listing:write" => Scopes::ListingWrite,
             "loot-box:write" => Scopes::LootBoxWrite,
+            "mfa:challenge" => Scopes::MfaChallenge,
             "note:delete" => Scopes::NoteDelete,
             "note:read" => Scopes::NoteRead,
             "note:write" => Scopes::NoteWrite,
@@ -289,6 +292,22 @@ impl FromStr for Scopes {
     }
 }
 
+impl Scopes {
+    /// Scopes that move money, organizations can require multi-factor authentication for them
+    pub fn is_financial(&self) -> bool {
+        match self {
+            Scopes::OrderMakeExternalPayment
+            | Scopes::OrderRefund
+            | Scopes::OrderRefundOverride
+            | Scopes::OrgModifySettlementType
+            | Scopes::SettlementAdjustmentDelete
+            | Scopes::SettlementAdjustmentWrite
+            | Scopes::SettlementWrite => true,
+            _ => false,
+        }
+    }
+}
+
 pub fn get_scopes(roles: Vec<Roles>, additional_scopes: Option<AdditionalOrgMemberScopes>) -> Vec<Scopes> {
     let mut scopes: Vec<Scopes> = roles.into_iter().flat_map(|r| get_scopes_for_role(r)).collect();
     if let Some(extra_scopes) = additional_scopes {
@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::seat_map_sections;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(SeatMap)]
+#[table_name = "seat_map_sections"]
+pub struct SeatMapSection {
+    pub id: Uuid,
+    pub seat_map_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seat_map_sections"]
+pub struct NewSeatMapSection {
+    pub seat_map_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+}
+
+impl NewSeatMapSection {
+    pub fn commit(&self, conn: &PgConnection) -> Result<SeatMapSection, DatabaseError> {
+        diesel::insert_into(seat_map_sections::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seat map section")
+    }
+}
+
+impl SeatMapSection {
+    pub fn create(seat_map_id: Uuid, name: String, rank: i32) -> NewSeatMapSection {
+        NewSeatMapSection {
+            seat_map_id,
+            name,
+            rank,
+        }
+    }
+
+    pub fn find_by_seat_map_id(seat_map_id: Uuid, conn: &PgConnection) -> Result<Vec<SeatMapSection>, DatabaseError> {
+        seat_map_sections::table
+            .filter(seat_map_sections::seat_map_id.eq(seat_map_id))
+            .order_by(seat_map_sections::rank)
+            .then_order_by(seat_map_sections::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seat map sections")
+    }
+
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        Seat::find_by_section_id(self.id, conn)
+    }
+}
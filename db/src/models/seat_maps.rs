@@ -0,0 +1,183 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::prelude::*;
+use itertools::Itertools;
+use models::*;
+use schema::{seat_maps, ticket_type_seat_tiers};
+use std::collections::HashMap;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Venue)]
+#[table_name = "seat_maps"]
+pub struct SeatMap {
+    pub id: Uuid,
+    pub venue_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seat_maps"]
+pub struct NewSeatMap {
+    pub venue_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+}
+
+impl NewSeatMap {
+    pub fn commit(&self, conn: &PgConnection) -> Result<SeatMap, DatabaseError> {
+        if let Some(stage_id) = self.stage_id {
+            if Stage::find(stage_id, conn)?.venue_id != self.venue_id {
+                return DatabaseError::validation_error("stage_id", "Stage must belong to the seat map's venue");
+            }
+        }
+
+        diesel::insert_into(seat_maps::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seat map")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeatMap {
+    pub id: Uuid,
+    pub venue_id: Uuid,
+    pub stage_id: Option<Uuid>,
+    pub name: String,
+    pub sections: Vec<DisplaySeatMapSection>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeatMapSection {
+    pub id: Uuid,
+    pub name: String,
+    pub rank: i32,
+    pub rows: Vec<DisplaySeatMapRow>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySeatMapRow {
+    pub label: String,
+    pub seats: Vec<Seat>,
+}
+
+impl SeatMap {
+    pub fn create(venue_id: Uuid, stage_id: Option<Uuid>, name: String) -> NewSeatMap {
+        NewSeatMap {
+            venue_id,
+            stage_id,
+            name,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SeatMap, DatabaseError> {
+        seat_maps::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seat map")
+    }
+
+    pub fn find_by_venue_id(venue_id: Uuid, conn: &PgConnection) -> Result<Vec<SeatMap>, DatabaseError> {
+        seat_maps::table
+            .filter(seat_maps::venue_id.eq(venue_id))
+            .order_by(seat_maps::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seat maps")
+    }
+
+    pub fn sections(&self, conn: &PgConnection) -> Result<Vec<SeatMapSection>, DatabaseError> {
+        SeatMapSection::find_by_seat_map_id(self.id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplaySeatMap, DatabaseError> {
+        let mut sections = Vec::new();
+        for section in self.sections(conn)? {
+            let mut rows: Vec<DisplaySeatMapRow> = Vec::new();
+            for seat in section.seats(conn)? {
+                if rows.last().map(|row| row.label != seat.row_label).unwrap_or(true) {
+                    rows.push(DisplaySeatMapRow {
+                        label: seat.row_label.clone(),
+                        seats: Vec::new(),
+                    });
+                }
+                if let Some(row) = rows.last_mut() {
+                    row.seats.push(seat);
+                }
+            }
+            sections.push(DisplaySeatMapSection {
+                id: section.id,
+                name: section.name,
+                rank: section.rank,
+                rows,
+            });
+        }
+
+        Ok(DisplaySeatMap {
+            id: self.id,
+            venue_id: self.venue_id,
+            stage_id: self.stage_id,
+            name: self.name.clone(),
+            sections,
+        })
+    }
+
+    pub fn is_used(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            ticket_type_seat_tiers::table.filter(ticket_type_seat_tiers::seat_map_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if seat map is used")
+    }
+
+    /// Seat maps can only be removed while no event sells their seats
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        if self.is_used(conn)? {
+            return DatabaseError::business_process_error("Seat map is used by an event and cannot be deleted");
+        }
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete seat map")
+    }
+
+    /// Sells the seats of each price tier as tickets of the mapped ticket type of the event
+    pub fn assign_to_event(
+        &self,
+        event: &Event,
+        price_tiers: &HashMap<String, Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketTypeSeatTier>, DatabaseError> {
+        if event.venue_id != Some(self.venue_id) {
+            return DatabaseError::validation_error("seat_map_id", "Seat map must belong to the event's venue");
+        }
+
+        let mut seat_map_price_tiers = Vec::new();
+        for section in self.sections(conn)? {
+            for seat in section.seats(conn)? {
+                seat_map_price_tiers.push(seat.price_tier);
+            }
+        }
+
+        let mut tiers = Vec::new();
+        for (price_tier, ticket_type_id) in price_tiers.iter().sorted() {
+            if !seat_map_price_tiers.contains(price_tier) {
+                return DatabaseError::validation_error("price_tiers", "Seat map has no seats in the price tier");
+            }
+            if TicketType::find(*ticket_type_id, conn)?.event_id != event.id {
+                return DatabaseError::validation_error("price_tiers", "Ticket type must belong to the event");
+            }
+
+            let tier = TicketTypeSeatTier::create(*ticket_type_id, self.id, price_tier.clone()).commit(conn)?;
+            tier.assign_seats(conn)?;
+            tiers.push(tier);
+        }
+
+        Ok(tiers)
+    }
+}
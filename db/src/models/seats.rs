@@ -0,0 +1,194 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Integer, Nullable, Text, Uuid as dUuid};
+use itertools::Itertools;
+use models::*;
+use schema::seats;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(SeatMapSection)]
+#[table_name = "seats"]
+pub struct Seat {
+    pub id: Uuid,
+    pub seat_map_section_id: Uuid,
+    pub row_label: String,
+    pub row_rank: i32,
+    pub seat_number: String,
+    pub seat_rank: i32,
+    pub price_tier: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "seats"]
+pub struct NewSeat {
+    pub seat_map_section_id: Uuid,
+    pub row_label: String,
+    pub row_rank: i32,
+    pub seat_number: String,
+    pub seat_rank: i32,
+    pub price_tier: String,
+}
+
+/// A seat whose ticket can currently be reserved, in best to worst order
+#[derive(Clone, Debug, PartialEq, QueryableByName)]
+pub struct AvailableSeat {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub seat_map_section_id: Uuid,
+    #[sql_type = "Text"]
+    pub row_label: String,
+    #[sql_type = "Integer"]
+    pub seat_rank: i32,
+}
+
+/// A seat of an event's seat map with the ticket type it is sold as
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct DisplayEventSeat {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "dUuid"]
+    pub seat_map_section_id: Uuid,
+    #[sql_type = "Text"]
+    pub section_name: String,
+    #[sql_type = "Text"]
+    pub row_label: String,
+    #[sql_type = "Text"]
+    pub seat_number: String,
+    #[sql_type = "Text"]
+    pub price_tier: String,
+    #[sql_type = "Nullable<dUuid>"]
+    pub ticket_type_id: Option<Uuid>,
+    #[sql_type = "Bool"]
+    pub available: bool,
+}
+
+impl Seat {
+    pub fn create(
+        seat_map_section_id: Uuid,
+        row_label: String,
+        row_rank: i32,
+        seat_number: String,
+        seat_rank: i32,
+        price_tier: String,
+    ) -> NewSeat {
+        NewSeat {
+            seat_map_section_id,
+            row_label,
+            row_rank,
+            seat_number,
+            seat_rank,
+            price_tier,
+        }
+    }
+
+    pub fn create_multiple(new_seats: &[NewSeat], conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        diesel::insert_into(seats::table)
+            .values(new_seats)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create seats")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Seat, DatabaseError> {
+        seats::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seat")
+    }
+
+    pub fn find_by_section_id(seat_map_section_id: Uuid, conn: &PgConnection) -> Result<Vec<Seat>, DatabaseError> {
+        seats::table
+            .filter(seats::seat_map_section_id.eq(seat_map_section_id))
+            .order_by(seats::row_rank)
+            .then_order_by(seats::row_label)
+            .then_order_by(seats::seat_rank)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seats")
+    }
+
+    /// Seats with a ticket of the ticket type that is available to reserve from the given hold,
+    /// or from general inventory when no hold is given
+    pub fn find_available(
+        ticket_type_id: Uuid,
+        hold_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<AvailableSeat>, DatabaseError> {
+        diesel::sql_query(
+            r#"
+            SELECT s.id, s.seat_map_section_id, s.row_label, s.seat_rank
+            FROM ticket_instances t
+            JOIN assets a ON t.asset_id = a.id
+            JOIN seats s ON t.seat_id = s.id
+            JOIN seat_map_sections sms ON s.seat_map_section_id = sms.id
+            WHERE ((t.reserved_until < now() AND t.status = 'Reserved') OR t.status = 'Available')
+            AND a.ticket_type_id = $1
+            AND t.parent_id IS NULL
+            AND t.hold_id IS NOT DISTINCT FROM $2
+            ORDER BY sms.rank, sms.name, s.row_rank, s.row_label, s.seat_rank
+        "#,
+        )
+        .bind::<dUuid, _>(ticket_type_id)
+        .bind::<Nullable<dUuid>, _>(hold_id)
+        .load(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load available seats")
+    }
+
+    /// Picks the best `quantity` seats, preferring the best row with enough adjacent seats
+    /// and falling back to the best seats wherever they are
+    pub fn best_available(seats: &[AvailableSeat], quantity: usize) -> Vec<Uuid> {
+        if quantity == 0 || seats.len() < quantity {
+            return Vec::new();
+        }
+
+        for (_, row) in &seats
+            .iter()
+            .group_by(|seat| (seat.seat_map_section_id, seat.row_label.clone()))
+        {
+            let row: Vec<&AvailableSeat> = row.collect();
+            for window in row.windows(quantity) {
+                if window[quantity - 1].seat_rank - window[0].seat_rank == quantity as i32 - 1 {
+                    return window.iter().map(|seat| seat.id).collect();
+                }
+            }
+        }
+
+        seats.iter().take(quantity).map(|seat| seat.id).collect()
+    }
+
+    /// All seats of the seat map assigned to the event's ticket types
+    pub fn find_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<DisplayEventSeat>, DatabaseError> {
+        diesel::sql_query(
+            r#"
+            SELECT s.id, s.seat_map_section_id, sms.name AS section_name, s.row_label, s.seat_number, s.price_tier,
+                   a.ticket_type_id,
+                   COALESCE((t.reserved_until < now() AND t.status = 'Reserved') OR t.status = 'Available', false)
+                       AND t.hold_id IS NULL AS available
+            FROM seats s
+            JOIN seat_map_sections sms ON s.seat_map_section_id = sms.id
+            LEFT JOIN (
+                SELECT ti.*
+                FROM ticket_instances ti
+                JOIN assets a2 ON ti.asset_id = a2.id
+                JOIN ticket_types tt ON a2.ticket_type_id = tt.id
+                WHERE tt.event_id = $1
+            ) t ON t.seat_id = s.id
+            LEFT JOIN assets a ON t.asset_id = a.id
+            WHERE sms.seat_map_id IN (
+                SELECT ttst.seat_map_id
+                FROM ticket_type_seat_tiers ttst
+                JOIN ticket_types tt ON ttst.ticket_type_id = tt.id
+                WHERE tt.event_id = $1
+            )
+            ORDER BY sms.rank, sms.name, s.row_rank, s.row_label, s.seat_rank
+        "#,
+        )
+        .bind::<dUuid, _>(event_id)
+        .load(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not load event seats")
+    }
+}
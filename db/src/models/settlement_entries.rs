@@ -5,7 +5,7 @@ use diesel::prelude::*;
 use diesel::sql_types::{Nullable, Text};
 use itertools::Itertools;
 use models::*;
-use schema::{events, settlement_entries, ticket_types};
+use schema::{events, products, settlement_entries, ticket_types};
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -26,6 +26,8 @@ pub struct SettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub tax_in_cents: i64,
+    pub product_id: Option<Uuid>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -43,6 +45,9 @@ pub struct DisplaySettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub tax_in_cents: i64,
+    pub product_id: Option<Uuid>,
+    pub product_name: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -58,6 +63,7 @@ impl SettlementEntry {
     ) -> Result<Vec<EventGroupedSettlementEntry>, DatabaseError> {
         let entries: Vec<DisplaySettlementEntry> = settlement_entries::table
             .left_join(ticket_types::table.on(settlement_entries::ticket_type_id.eq(ticket_types::id.nullable())))
+            .left_join(products::table.on(settlement_entries::product_id.eq(products::id.nullable())))
             .inner_join(events::table.on(events::id.eq(settlement_entries::event_id)))
             .filter(settlement_entries::settlement_id.eq(settlement.id))
             .select((
@@ -74,11 +80,15 @@ impl SettlementEntry {
                 settlement_entries::settlement_entry_type,
                 settlement_entries::created_at,
                 settlement_entries::updated_at,
+                settlement_entries::tax_in_cents,
+                settlement_entries::product_id,
+                sql::<Nullable<Text>>("products.name AS product_name"),
             ))
             .order_by(events::event_start)
             .then_order_by(settlement_entries::event_id)
             .then_order_by(settlement_entries::settlement_entry_type.nullable().desc())
             .then_order_by(ticket_types::rank)
+            .then_order_by(products::name)
             .then_order_by(settlement_entries::face_value_in_cents)
             .load(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load Settlement Entries")?;
@@ -119,6 +129,7 @@ impl SettlementEntry {
             settlement_entry_type,
             fee_sold_quantity,
             total_sales_in_cents,
+            product_id: None,
         }
     }
 }
@@ -135,6 +146,7 @@ pub struct NewSettlementEntry {
     pub fee_sold_quantity: i64,
     pub total_sales_in_cents: i64,
     pub settlement_entry_type: SettlementEntryTypes,
+    pub product_id: Option<Uuid>,
 }
 impl NewSettlementEntry {
     pub fn commit(&self, conn: &PgConnection) -> Result<SettlementEntry, DatabaseError> {
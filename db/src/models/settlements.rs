@@ -3,7 +3,7 @@ use chrono_tz::Tz;
 use dev::times;
 use diesel::dsl::select;
 use diesel::prelude::*;
-use diesel::sql_types::{Nullable, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Nullable, Timestamp, Uuid as dUuid};
 use diesel::{self, dsl};
 use models::*;
 use schema::{settlement_adjustments, settlements};
@@ -30,9 +30,12 @@ pub struct Settlement {
     pub only_finished_events: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub stripe_transfer_id: Option<String>,
+    pub currency: String,
+    pub stripe_transfer_error: Option<String>,
 }
 
-#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[table_name = "settlements"]
 pub struct NewSettlement {
     pub organization_id: Uuid,
@@ -41,6 +44,8 @@ pub struct NewSettlement {
     pub status: SettlementStatus,
     pub comment: Option<String>,
     pub only_finished_events: bool,
+    /// Only events sold in this currency are settled, defaults to the organization's currency
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -54,11 +59,16 @@ impl NewSettlement {
     pub fn commit(&self, user: Option<User>, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
         self.validate_record()?;
 
+        let mut new_settlement = self.clone();
+        if new_settlement.currency.is_none() {
+            new_settlement.currency = Some(Organization::find(self.organization_id, conn)?.currency);
+        }
+
         let settlement = DatabaseError::wrap(
             ErrorCode::InsertError,
             "Could not create new settlement",
             diesel::insert_into(settlements::table)
-                .values(self)
+                .values(&new_settlement)
                 .get_result::<Settlement>(conn),
         )?;
 
@@ -78,7 +88,7 @@ impl NewSettlement {
     }
 
     fn validate_record(&self) -> Result<(), DatabaseError> {
-        let validation_errors = validators::append_validation_error(
+        let mut validation_errors = validators::append_validation_error(
             Ok(()),
             "start_time",
             validators::n_date_valid(
@@ -90,6 +100,13 @@ impl NewSettlement {
                 "end_time",
             ),
         );
+        if let Some(ref currency) = self.currency {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "currency",
+                validators::validate_currency(currency),
+            );
+        }
 
         Ok(validation_errors?)
     }
@@ -111,6 +128,7 @@ impl Settlement {
             status,
             comment,
             only_finished_events,
+            currency: None,
         }
     }
 
@@ -185,11 +203,28 @@ impl Settlement {
             .to_db_error(ErrorCode::QueryError, "Could not load settlement")
     }
 
+    /// Latest settlement for the organization in the given currency that has not yet been finalized
+    pub fn find_pending_for_organization(
+        organization_id: Uuid,
+        currency: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization_id))
+            .filter(settlements::currency.eq(currency))
+            .filter(settlements::status.eq(SettlementStatus::PendingSettlement))
+            .order_by(settlements::end_time.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load pending settlement")
+    }
+
+    /// Processes the organization's settlements for the last period, one per currency sold in
     pub fn process_settlement_for_organization(
         organization: &Organization,
         settlement_period_in_days: Option<u32>,
         conn: &PgConnection,
-    ) -> Result<Settlement, DatabaseError> {
+    ) -> Result<Vec<Settlement>, DatabaseError> {
         let last_processed_settlement = Settlement::find_last_settlement_for_organization(organization, conn)?;
 
         let end_time = organization.next_settlement_date(settlement_period_in_days)?
@@ -211,17 +246,32 @@ impl Settlement {
                 + Duration::seconds(1)
         };
 
-        let settlement = Settlement::create(
-            organization.id,
-            start_time,
-            end_time,
-            SettlementStatus::PendingSettlement,
-            None,
-            organization.settlement_type == SettlementTypes::PostEvent,
-        )
-        .commit(None, conn)?;
+        let mut currencies = vec![organization.currency.clone()];
+        let ending_events =
+            Event::get_all_events_ending_between(organization.id, start_time, end_time, EventStatus::Published, conn)?;
+        let events_with_transactions =
+            Event::get_all_events_with_transactions_between(organization.id, start_time, end_time, conn)?;
+        for event in ending_events.into_iter().chain(events_with_transactions) {
+            if !currencies.contains(&event.currency) {
+                currencies.push(event.currency);
+            }
+        }
 
-        Ok(settlement)
+        let mut settlements = Vec::new();
+        for currency in currencies {
+            let mut new_settlement = Settlement::create(
+                organization.id,
+                start_time,
+                end_time,
+                SettlementStatus::PendingSettlement,
+                None,
+                organization.settlement_type == SettlementTypes::PostEvent,
+            );
+            new_settlement.currency = Some(currency);
+            settlements.push(new_settlement.commit(None, conn)?);
+        }
+
+        Ok(settlements)
     }
 
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
@@ -266,7 +316,7 @@ impl Settlement {
             conn,
         )?;
 
-        for event in ending_events {
+        for event in ending_events.into_iter().filter(|e| e.currency == self.currency) {
             if self.only_finished_events {
                 self.create_entries_from_event_transactions(&event, true, conn)?;
             }
@@ -279,6 +329,9 @@ impl Settlement {
         for event in
             Event::get_all_events_with_transactions_between(self.organization_id, self.start_time, self.end_time, conn)?
         {
+            if event.currency != self.currency {
+                continue;
+            }
             if !self.only_finished_events || event.event_end.unwrap_or(times::infinity()) < self.end_time {
                 self.create_entries_from_event_transactions(&event, false, conn)?;
             }
@@ -313,16 +366,69 @@ impl Settlement {
         Ok(())
     }
 
-    pub fn finalize_settlements(conn: &PgConnection) -> Result<(), DatabaseError> {
+    /// Finalizes all pending settlements, returning the settlements that were finalized
+    pub fn finalize_settlements(conn: &PgConnection) -> Result<Vec<Settlement>, DatabaseError> {
         diesel::update(settlements::table.filter(settlements::status.eq(SettlementStatus::PendingSettlement)))
             .set((
                 settlements::status.eq(SettlementStatus::FinalizedSettlement),
                 settlements::updated_at.eq(dsl::now),
             ))
-            .execute(conn)
-            .to_db_error(ErrorCode::UpdateError, "Could not finalize settlements")?;
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not finalize settlements")
+    }
 
-        Ok(())
+    /// Amount to transfer to the organization's connected account: Stripe payments held on the platform
+    /// for orders and refunds in this settlement, less platform fees, chargebacks and other adjustments
+    pub fn stripe_transfer_amount_in_cents(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "BigInt"]
+            amount_in_cents: i64,
+        }
+
+        let query = include_str!("../queries/settlement_stripe_transfer_amount.sql");
+        let result: R = diesel::sql_query(query)
+            .bind::<dUuid, _>(self.id)
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not calculate settlement transfer amount")?;
+        Ok(result.amount_in_cents)
+    }
+
+    pub fn set_stripe_transfer_id(
+        &self,
+        stripe_transfer_id: String,
+        conn: &PgConnection,
+    ) -> Result<Settlement, DatabaseError> {
+        if self.status != SettlementStatus::FinalizedSettlement {
+            return DatabaseError::business_process_error("Only finalized settlements can be transferred");
+        }
+        if self.stripe_transfer_id.is_some() {
+            return DatabaseError::business_process_error("Settlement has already been transferred");
+        }
+
+        diesel::update(self)
+            .set((
+                settlements::stripe_transfer_id.eq(Some(stripe_transfer_id)),
+                settlements::stripe_transfer_error.eq(None::<String>),
+                settlements::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update settlement transfer")
+    }
+
+    /// Records why the transfer failed so the settlement can be paid manually
+    pub fn set_stripe_transfer_error(
+        &self,
+        stripe_transfer_error: String,
+        conn: &PgConnection,
+    ) -> Result<Settlement, DatabaseError> {
+        diesel::update(self)
+            .set((
+                settlements::stripe_transfer_error.eq(Some(stripe_transfer_error)),
+                settlements::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update settlement transfer")
     }
 
     pub fn find_for_organization(
@@ -0,0 +1,167 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::tax_rates;
+use std::borrow::Cow;
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+use validator::ValidationError;
+use validators::{self, *};
+
+#[derive(AsChangeset, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, QueryableByName, Serialize)]
+#[table_name = "tax_rates"]
+pub struct TaxRate {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub venue_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f32,
+    pub inclusive: bool,
+    pub applies_to_fees: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "tax_rates"]
+pub struct TaxRateEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+    pub rate_percent: Option<f32>,
+    pub inclusive: Option<bool>,
+    pub applies_to_fees: Option<bool>,
+}
+
+#[derive(Clone, Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "tax_rates"]
+pub struct NewTaxRate {
+    pub organization_id: Uuid,
+    pub venue_id: Option<Uuid>,
+    pub name: String,
+    pub rate_percent: f32,
+    pub inclusive: bool,
+    pub applies_to_fees: bool,
+}
+
+impl NewTaxRate {
+    pub fn commit(&self, conn: &PgConnection) -> Result<TaxRate, DatabaseError> {
+        validators::append_validation_error(
+            Ok(()),
+            "rate_percent",
+            TaxRate::validate_rate_percent(self.rate_percent),
+        )?;
+
+        diesel::insert_into(tax_rates::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rate")
+    }
+}
+
+impl TaxRate {
+    pub fn create(
+        organization_id: Uuid,
+        venue_id: Option<Uuid>,
+        name: String,
+        rate_percent: f32,
+        inclusive: bool,
+        applies_to_fees: bool,
+    ) -> NewTaxRate {
+        NewTaxRate {
+            organization_id,
+            venue_id,
+            name,
+            rate_percent,
+            inclusive,
+            applies_to_fees,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRate, DatabaseError> {
+        tax_rates::table
+            .filter(tax_rates::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rate")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<TaxRate>, DatabaseError> {
+        tax_rates::table
+            .filter(tax_rates::organization_id.eq(organization_id))
+            .order_by(tax_rates::venue_id.is_not_null())
+            .then_order_by(tax_rates::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rates for organization")
+    }
+
+    /// Tax rate charged for an event, a rate set for the event's venue takes precedence over
+    /// the organization's default rate
+    pub fn find_for_event(event: &Event, conn: &PgConnection) -> Result<Option<TaxRate>, DatabaseError> {
+        tax_rates::table
+            .filter(tax_rates::organization_id.eq(event.organization_id))
+            .filter(tax_rates::venue_id.is_null().or(tax_rates::venue_id.eq(event.venue_id)))
+            .order_by(tax_rates::venue_id.is_null())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rate for event")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn update(&self, attributes: TaxRateEditableAttributes, conn: &PgConnection) -> Result<TaxRate, DatabaseError> {
+        if let Some(rate_percent) = attributes.rate_percent {
+            validators::append_validation_error(Ok(()), "rate_percent", TaxRate::validate_rate_percent(rate_percent))?;
+        }
+
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not update tax rate",
+            diesel::update(self)
+                .set((attributes, tax_rates::updated_at.eq(dsl::now)))
+                .get_result(conn),
+        )
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete tax rate",
+            diesel::delete(self).execute(conn),
+        )
+    }
+
+    /// Returns the amount added to the price and the tax contained in the resulting total for a
+    /// single unit priced at `amount_in_cents`. Inclusive taxes are already part of the price so
+    /// nothing is added.
+    pub fn calculate(&self, amount_in_cents: i64) -> (i64, i64) {
+        if amount_in_cents <= 0 {
+            return (0, 0);
+        }
+
+        let rate = self.rate_percent / 100f32;
+        if self.inclusive {
+            let tax = amount_in_cents - (amount_in_cents as f32 / (1f32 + rate)).round() as i64;
+            (0, tax)
+        } else {
+            let tax = (amount_in_cents as f32 * rate).round() as i64;
+            (tax, tax)
+        }
+    }
+
+    fn validate_rate_percent(rate_percent: f32) -> Result<(), ValidationError> {
+        if rate_percent < 0f32 || rate_percent > 100f32 {
+            let mut validation_error = create_validation_error(
+                "rate_percent_out_of_range",
+                "Tax rate must be between 0 and 100 percent",
+            );
+            validation_error.add_param(Cow::from("rate_percent"), &rate_percent);
+            return Err(validation_error);
+        }
+        Ok(())
+    }
+}
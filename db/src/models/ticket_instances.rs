@@ -13,15 +13,21 @@ use models::*;
 use rand;
 use rand::Rng;
 use schema::{
-    assets, events, order_items, orders, organizations, ticket_instances, ticket_types, transfers, users, wallets,
+    assets, events, order_items, orders, organizations, seat_map_sections, seats, ticket_instances, ticket_types,
+    transfers, users, wallets,
 };
 use std::cmp;
 use tari_client::*;
+use utils::encryption::KeyRing;
 use utils::errors::*;
+use utils::hash::hmac_sha256;
+use utils::totp;
 use uuid::Uuid;
 use validators::*;
 
 const TICKET_NUMBER_LENGTH: usize = 8;
+// Rotating redeem codes from the previous or next time step are still accepted
+const REDEEM_CODE_SKEW_STEPS: i64 = 1;
 
 #[derive(Clone, Debug, Identifiable, PartialEq, Deserialize, Serialize, Queryable, QueryableByName)]
 #[table_name = "ticket_instances"]
@@ -44,6 +50,8 @@ pub struct TicketInstance {
     pub check_in_source: Option<CheckInSource>,
     parent_id: Option<Uuid>,
     pub listing_id: Option<Uuid>,
+    pub redeem_secret: Option<String>,
+    pub seat_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Clone, Deserialize, Serialize)]
@@ -147,7 +155,8 @@ impl TicketInstance {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         let query = include_str!("../queries/release_tickets.sql");
-        let new_status = if self.ticket_type(conn)?.status == TicketTypeStatus::Cancelled {
+        let ticket_type = self.ticket_type(conn)?;
+        let new_status = if ticket_type.status == TicketTypeStatus::Cancelled {
             TicketInstanceStatus::Nullified
         } else {
             TicketInstanceStatus::Available
@@ -168,6 +177,8 @@ impl TicketInstance {
 
         if new_status == TicketInstanceStatus::Nullified {
             tickets[0].create_nullified_domain_event(Some(user_id), conn)?;
+        } else if tickets[0].hold_id.is_none() {
+            WaitlistEntry::schedule_processing(ticket_type.id, Utc::now().naive_utc(), conn)?;
         }
 
         Ok(())
@@ -210,6 +221,8 @@ impl TicketInstance {
                     WHERE tt.ticket_instance_id = ticket_instances.id
                     AND t.status = 'Pending'
                 )")))
+            .left_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+            .left_join(seat_map_sections::table.on(seats::seat_map_section_id.eq(seat_map_sections::id)))
             .filter(ticket_instances::id.eq(id))
             .select((
                 ticket_instances::id,
@@ -229,7 +242,9 @@ impl TicketInstance {
                 events::id,
                 events::venue_id,
                 ticket_instances::status,
-                ticket_instances::redeem_key,
+                sql::<Nullable<Text>>(
+                    "CASE WHEN events.rotating_redeem_codes THEN NULL ELSE ticket_instances.redeem_key END AS redeem_key",
+                ),
                 events::redeem_date,
                 events::event_start,
                 sql::<Bool>("transfers.id is not null AS pending_transfer"),
@@ -240,6 +255,12 @@ impl TicketInstance {
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
                 ticket_types::promo_image_url,
+                sql::<Nullable<Text>>(
+                    "CASE WHEN events.rotating_redeem_codes THEN ticket_instances.redeem_secret ELSE NULL END AS redeem_secret",
+                ),
+                seat_map_sections::name.nullable(),
+                seats::row_label.nullable(),
+                seats::seat_number.nullable(),
             ))
             .first::<DisplayTicketIntermediary>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -267,6 +288,75 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")
     }
 
+    /// Tickets that can be redeemed at the door for an event, used by scanners that may lose
+    /// connectivity. Redeem keys are hashed with `salt` so the snapshot does not contain them.
+    /// Events using rotating codes include the ticket secrets instead so scanners can check the
+    /// time-based codes.
+    pub fn find_for_scanner_snapshot(
+        event_id: Uuid,
+        salt: &str,
+        conn: &PgConnection,
+    ) -> Result<Vec<ScannerSnapshotTicket>, DatabaseError> {
+        let event = Event::find(event_id, conn)?;
+        let tickets: Vec<(Uuid, Uuid, Option<String>, Option<String>, TicketInstanceStatus, bool)> =
+            ticket_instances::table
+                .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+                .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+                .filter(ticket_types::event_id.eq(event_id))
+                .filter(ticket_instances::redeem_key.is_not_null())
+                .filter(
+                    ticket_instances::status
+                        .eq(TicketInstanceStatus::Purchased)
+                        .or(ticket_instances::status.eq(TicketInstanceStatus::Redeemed)),
+                )
+                .select((
+                    ticket_instances::id,
+                    ticket_types::id,
+                    ticket_instances::redeem_key,
+                    ticket_instances::redeem_secret,
+                    ticket_instances::status,
+                    sql::<Bool>(
+                        "EXISTS (
+                        SELECT 1 FROM transfer_tickets tt
+                        JOIN transfers t ON t.id = tt.transfer_id
+                        WHERE tt.ticket_instance_id = ticket_instances.id
+                        AND t.status = 'Pending'
+                    )",
+                    ),
+                ))
+                .order_by(ticket_instances::id)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Unable to load tickets for scanner snapshot")?;
+
+        Ok(tickets
+            .into_iter()
+            .map(
+                |(id, ticket_type_id, redeem_key, redeem_secret, status, pending_transfer)| {
+                    let (redeem_key_hash, redeem_secret) = if event.rotating_redeem_codes {
+                        (None, redeem_secret)
+                    } else {
+                        (
+                            Some(TicketInstance::hash_redeem_key(salt, &redeem_key.unwrap_or_default())),
+                            None,
+                        )
+                    };
+                    ScannerSnapshotTicket {
+                        id,
+                        ticket_type_id,
+                        redeem_key_hash,
+                        redeem_secret,
+                        status,
+                        pending_transfer,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    pub fn hash_redeem_key(salt: &str, redeem_key: &str) -> String {
+        hmac_sha256::sign(salt, redeem_key)
+    }
+
     pub fn find_for_processing(
         id: Uuid,
         event_id: Uuid,
@@ -317,6 +407,8 @@ impl TicketInstance {
                         WHERE tt.ticket_instance_id = ticket_instances.id
                         AND t.status = 'Pending'
                     )")))
+            .left_join(seats::table.on(ticket_instances::seat_id.eq(seats::id.nullable())))
+            .left_join(seat_map_sections::table.on(seats::seat_map_section_id.eq(seat_map_sections::id)))
             .filter(
                 events::event_end.ge(start_time.unwrap_or_else(|| NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0))),
             )
@@ -347,7 +439,9 @@ impl TicketInstance {
                 events::id,
                 events::venue_id,
                 ticket_instances::status,
-                ticket_instances::redeem_key,
+                sql::<Nullable<Text>>(
+                    "CASE WHEN events.rotating_redeem_codes THEN NULL ELSE ticket_instances.redeem_key END AS redeem_key",
+                ),
                 events::redeem_date,
                 events::event_start,
                 sql::<Bool>("transfers.id is not null AS pending_transfer"),
@@ -358,6 +452,12 @@ impl TicketInstance {
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
                 ticket_types::promo_image_url,
+                sql::<Nullable<Text>>(
+                    "CASE WHEN events.rotating_redeem_codes THEN ticket_instances.redeem_secret ELSE NULL END AS redeem_secret",
+                ),
+                seat_map_sections::name.nullable(),
+                seats::row_label.nullable(),
+                seats::seat_number.nullable(),
             ))
             .order_by(events::event_start.asc())
             .then_order_by(events::name.asc())
@@ -446,12 +546,15 @@ impl TicketInstance {
         Ok(())
     }
 
+    /// Reserves the tickets of the given seats, or the best available seats when none are given
+    /// for ticket types with reserved seating
     pub fn reserve_tickets(
         order_item: &OrderItem,
         expires_at: Option<NaiveDateTime>,
         ticket_type_id: Uuid,
         ticket_holding_id: Option<Uuid>,
         quantity: u32,
+        seat_ids: Option<&[Uuid]>,
         conn: &PgConnection,
     ) -> Result<Vec<TicketInstance>, DatabaseError> {
         let order_expires_at = expires_at.ok_or(DatabaseError::new(
@@ -459,16 +562,42 @@ impl TicketInstance {
             Some("Expiration date was not set on cart prior to reserving tickets".to_string()),
         ))?;
 
-        let query = include_str!("../queries/reserve_tickets.sql");
-        let q = diesel::sql_query(query)
-            .bind::<sql_types::Uuid, _>(order_item.id)
-            .bind::<sql_types::Timestamp, _>(order_expires_at)
-            .bind::<sql_types::Uuid, _>(ticket_type_id)
-            .bind::<sql_types::Nullable<sql_types::Uuid>, _>(ticket_holding_id)
-            .bind::<BigInt, _>(quantity as i64);
-        let tickets: Vec<TicketInstance> = q
-            .get_results(conn)
-            .to_db_error(ErrorCode::UpdateError, "Could not reserve tickets")?;
+        let seats_selected = seat_ids.is_some();
+        let seated = TicketTypeSeatTier::find_for_ticket_type(ticket_type_id, conn)?.is_some();
+        let seat_ids = match seat_ids {
+            Some(seat_ids) => {
+                if !seated {
+                    return DatabaseError::validation_error("seat_ids", "Ticket type does not have reserved seating");
+                }
+                if seat_ids.len() as u32 != quantity {
+                    return DatabaseError::validation_error("seat_ids", "A seat must be selected for each ticket");
+                }
+                Some(seat_ids.to_vec())
+            }
+            None if seated => Some(Seat::best_available(
+                &Seat::find_available(ticket_type_id, ticket_holding_id, conn)?,
+                quantity as usize,
+            )),
+            None => None,
+        };
+
+        let tickets: Vec<TicketInstance> = match seat_ids {
+            Some(ref seat_ids) => diesel::sql_query(include_str!("../queries/reserve_seats.sql"))
+                .bind::<sql_types::Uuid, _>(order_item.id)
+                .bind::<sql_types::Timestamp, _>(order_expires_at)
+                .bind::<sql_types::Uuid, _>(ticket_type_id)
+                .bind::<sql_types::Nullable<sql_types::Uuid>, _>(ticket_holding_id)
+                .bind::<Array<dUuid>, _>(seat_ids)
+                .get_results(conn),
+            None => diesel::sql_query(include_str!("../queries/reserve_tickets.sql"))
+                .bind::<sql_types::Uuid, _>(order_item.id)
+                .bind::<sql_types::Timestamp, _>(order_expires_at)
+                .bind::<sql_types::Uuid, _>(ticket_type_id)
+                .bind::<sql_types::Nullable<sql_types::Uuid>, _>(ticket_holding_id)
+                .bind::<BigInt, _>(quantity as i64)
+                .get_results(conn),
+        }
+        .to_db_error(ErrorCode::UpdateError, "Could not reserve tickets")?;
 
         if tickets.len() as u32 != quantity {
             if (tickets.len() as u32) < quantity {
@@ -481,6 +610,12 @@ impl TicketInstance {
                     )
                 );
 
+                if seats_selected {
+                    return DatabaseError::validation_error(
+                        "seat_ids",
+                        "Could not reserve tickets, the selected seats are not available",
+                    );
+                }
                 return DatabaseError::validation_error(
                     "quantity",
                     "Could not reserve tickets, not enough tickets are available",
@@ -1014,14 +1149,38 @@ impl TicketInstance {
             key = generate_redeem_key(9);
         }
 
+        // The secret is replaced along with the key so a previous holder's app stops producing
+        // valid rotating codes
         diesel::update(self)
-            .set(ticket_instances::redeem_key.eq(key.clone()))
+            .set((
+                ticket_instances::redeem_key.eq(key.clone()),
+                ticket_instances::redeem_secret.eq(totp::generate_secret()),
+            ))
             .execute(conn)
             .to_db_error(ErrorCode::InternalError, "Could not write redeem key")?;
 
         Ok(key)
     }
 
+    /// Scans for events using rotating codes only accept the time-based code derived from the
+    /// ticket's secret so a screenshot of the code stops working. The static redeem key is still
+    /// accepted for guest list check-ins made by the box office.
+    pub fn redeem_key_matches(
+        &self,
+        redeem_key: &str,
+        event: &Event,
+        check_in_source: CheckInSource,
+        scanned_at: NaiveDateTime,
+    ) -> bool {
+        if !event.rotating_redeem_codes || check_in_source == CheckInSource::GuestList {
+            return self.redeem_key.as_ref().map(|key| key.as_str()) == Some(redeem_key);
+        }
+        match self.redeem_secret {
+            Some(ref secret) => totp::verify(secret, redeem_key, scanned_at.timestamp(), REDEEM_CODE_SKEW_STEPS),
+            None => false,
+        }
+    }
+
     pub fn has_pending_transfer(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
         Ok(TransferTicket::pending_transfer(self.id, conn)?.is_some())
     }
@@ -1032,6 +1191,98 @@ impl TicketInstance {
         user_id: Uuid,
         check_in_source: CheckInSource,
         conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        TicketInstance::redeem_ticket_for_access_zone(ticket_id, redeem_key, user_id, check_in_source, None, conn)
+    }
+
+    /// Redeems a ticket scanned at the gate of an access zone, tickets whose ticket type was not
+    /// given access to the zone are rejected with `TicketNotValidForAccessZone`
+    pub fn redeem_ticket_for_access_zone(
+        ticket_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        check_in_source: CheckInSource,
+        access_zone_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        TicketInstance::redeem_ticket_at(
+            ticket_id,
+            redeem_key,
+            user_id,
+            check_in_source,
+            access_zone_id,
+            Utc::now().naive_utc(),
+            conn,
+        )
+    }
+
+    /// Applies scans made while a scanner was offline. Scans are replayed in the order they were
+    /// made so when a ticket was scanned on more than one device the earliest scan redeems it and
+    /// later scans are reported as `TicketAlreadyRedeemed`.
+    pub fn redeem_offline_scans(
+        event_id: Uuid,
+        scans: &[OfflineScan],
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OfflineScanResult>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let mut results = Vec::new();
+        for scan in scans.iter().sorted_by_key(|s| s.scanned_at) {
+            let ticket: Option<TicketInstance> = ticket_instances::table
+                .inner_join(assets::table.on(ticket_instances::asset_id.eq(assets::id)))
+                .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
+                .filter(ticket_types::event_id.eq(event_id))
+                .filter(ticket_instances::id.eq(scan.ticket_id))
+                .select(ticket_instances::all_columns)
+                .first(conn)
+                .optional()
+                .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
+
+            let result = match ticket {
+                Some(ref ticket) => TicketInstance::redeem_ticket_at(
+                    ticket.id,
+                    scan.redeem_key.clone(),
+                    user_id,
+                    scan.check_in_source.unwrap_or(CheckInSource::Scanned),
+                    scan.access_zone_id,
+                    // Device clocks can drift ahead of the server
+                    cmp::min(scan.scanned_at, now),
+                    conn,
+                )?,
+                None => RedeemResults::TicketInvalid,
+            };
+
+            let (redeemed_by_user_id, redeemed_at) = match (&result, ticket) {
+                (RedeemResults::TicketRedeemSuccess, Some(ticket))
+                | (RedeemResults::TicketReentrySuccess, Some(ticket))
+                | (RedeemResults::TicketAlreadyRedeemed, Some(ticket)) => {
+                    let ticket = TicketInstance::find(ticket.id, conn)?;
+                    (ticket.redeemed_by_user_id, ticket.redeemed_at)
+                }
+                _ => (None, None),
+            };
+
+            results.push(OfflineScanResult {
+                ticket_id: scan.ticket_id,
+                device_id: scan.device_id.clone(),
+                scanned_at: scan.scanned_at,
+                result,
+                redeemed_by_user_id,
+                redeemed_at,
+            });
+        }
+
+        Ok(results)
+    }
+
+    fn redeem_ticket_at(
+        ticket_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        check_in_source: CheckInSource,
+        access_zone_id: Option<Uuid>,
+        redeemed_at: NaiveDateTime,
+        conn: &PgConnection,
     ) -> Result<RedeemResults, DatabaseError> {
         let ticket: TicketInstance = ticket_instances::table
             .find(ticket_id)
@@ -1039,20 +1290,38 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
         if ticket.has_pending_transfer(conn)? {
             return Ok(RedeemResults::TicketTransferInProcess);
-        } else if ticket.status == TicketInstanceStatus::Purchased
-            && ticket.redeem_key.is_some()
-            && ticket.redeem_key.clone().unwrap() == redeem_key
-        {
+        }
+
+        let ticket_type = ticket.ticket_type(conn)?;
+        if let Some(access_zone_id) = access_zone_id {
+            if !AccessZone::find(access_zone_id, conn)?.grants_access(ticket_type.id, conn)? {
+                return Ok(RedeemResults::TicketNotValidForAccessZone);
+            }
+        }
+
+        let event = Event::find(ticket_type.event_id, conn)?;
+        let redeem_key_matches = ticket.redeem_key_matches(&redeem_key, &event, check_in_source, redeemed_at);
+        if ticket.status == TicketInstanceStatus::Purchased && redeem_key_matches {
             diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(ticket_id)))
                 .set((
                     ticket_instances::status.eq(TicketInstanceStatus::Redeemed),
                     ticket_instances::redeemed_by_user_id.eq(user_id),
-                    ticket_instances::redeemed_at.eq(dsl::now),
+                    ticket_instances::redeemed_at.eq(redeemed_at),
                     ticket_instances::check_in_source.eq(check_in_source),
                     ticket_instances::updated_at.eq(dsl::now),
                 ))
                 .execute(conn)
                 .to_db_error(ErrorCode::UpdateError, "Could not set ticket to Redeemed")?;
+            TicketScan::create(
+                ticket.id,
+                ticket_type.event_id,
+                TicketScanType::CheckIn,
+                Some(user_id),
+                Some(check_in_source),
+                access_zone_id,
+                redeemed_at,
+            )
+            .commit(conn)?;
 
             DomainEvent::create(
                 DomainEventTypes::TicketInstanceRedeemed,
@@ -1064,6 +1333,16 @@ impl TicketInstance {
             )
             .commit(conn)?;
         } else if ticket.status == TicketInstanceStatus::Redeemed {
+            if redeem_key_matches {
+                return ticket.reenter(
+                    &ticket_type,
+                    user_id,
+                    check_in_source,
+                    access_zone_id,
+                    redeemed_at,
+                    conn,
+                );
+            }
             return Ok(RedeemResults::TicketAlreadyRedeemed);
         } else {
             return Ok(RedeemResults::TicketInvalid);
@@ -1071,6 +1350,110 @@ impl TicketInstance {
         Ok(RedeemResults::TicketRedeemSuccess)
     }
 
+    /// Lets a redeemed ticket back in after it was checked out, as often as its ticket type's
+    /// re-entry policy allows. Scanning a ticket that is still inside is a duplicate scan.
+    fn reenter(
+        &self,
+        ticket_type: &TicketType,
+        user_id: Uuid,
+        check_in_source: CheckInSource,
+        access_zone_id: Option<Uuid>,
+        scanned_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        let scans = TicketScan::find_for_ticket_instance(self.id, conn)?;
+        match scans.last() {
+            Some(scan) if scan.scan_type == TicketScanType::CheckOut => (),
+            _ => return Ok(RedeemResults::TicketAlreadyRedeemed),
+        }
+
+        // The first check-in redeemed the ticket, every check-in after it is a re-entry
+        let reentries = scans
+            .iter()
+            .filter(|s| s.scan_type == TicketScanType::CheckIn)
+            .count()
+            .saturating_sub(1) as i64;
+        if let Some(reentries_allowed) = ticket_type.reentries_allowed() {
+            if reentries >= reentries_allowed {
+                return Ok(RedeemResults::TicketReentryNotAllowed);
+            }
+        }
+
+        TicketScan::create(
+            self.id,
+            ticket_type.event_id,
+            TicketScanType::CheckIn,
+            Some(user_id),
+            Some(check_in_source),
+            access_zone_id,
+            scanned_at,
+        )
+        .commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketInstanceReentered,
+            "Ticket re-entered".to_string(),
+            Tables::TicketInstances,
+            Some(self.id),
+            Some(user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(RedeemResults::TicketReentrySuccess)
+    }
+
+    /// Records a redeemed ticket leaving the event, whether it can come back in depends on its
+    /// ticket type's re-entry policy
+    pub fn check_out_ticket(
+        ticket_id: Uuid,
+        redeem_key: String,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<RedeemResults, DatabaseError> {
+        let ticket = TicketInstance::find(ticket_id, conn)?;
+        if !ticket.redeem_key_matches(
+            &redeem_key,
+            &ticket.event(conn)?,
+            CheckInSource::Scanned,
+            Utc::now().naive_utc(),
+        ) {
+            return Ok(RedeemResults::TicketInvalid);
+        }
+        if ticket.status != TicketInstanceStatus::Redeemed {
+            return Ok(RedeemResults::TicketNotCheckedIn);
+        }
+
+        let scans = TicketScan::find_for_ticket_instance(ticket.id, conn)?;
+        let event_id = match scans.last() {
+            Some(scan) if scan.scan_type == TicketScanType::CheckIn => scan.event_id,
+            _ => return Ok(RedeemResults::TicketNotCheckedIn),
+        };
+
+        TicketScan::create(
+            ticket.id,
+            event_id,
+            TicketScanType::CheckOut,
+            Some(user_id),
+            None,
+            None,
+            Utc::now().naive_utc(),
+        )
+        .commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketInstanceCheckedOut,
+            "Ticket checked out".to_string(),
+            Tables::TicketInstances,
+            Some(ticket.id),
+            Some(user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(RedeemResults::TicketCheckedOut)
+    }
+
     pub fn show_redeemable_ticket(ticket_id: Uuid, conn: &PgConnection) -> Result<RedeemableTicket, DatabaseError> {
         let tickets_and_counts = Event::guest_list_tickets(None, Some(ticket_id), None, &None, None, conn)?;
 
@@ -1093,6 +1476,7 @@ impl TicketInstance {
         address: &str,
         sent_via: TransferMessageType,
         to_user_id: Uuid,
+        key_ring: &KeyRing,
         conn: &PgConnection,
     ) -> Result<Transfer, DatabaseError> {
         let transfer =
@@ -1100,7 +1484,7 @@ impl TicketInstance {
         let wallet = Wallet::find_default_for_user(from_user.id, conn)?;
         let receiver_wallet = Wallet::find_default_for_user(to_user_id, conn)?;
         TicketInstance::receive_ticket_transfer(
-            transfer.into_authorization(conn)?,
+            transfer.into_authorization(key_ring, conn)?,
             &wallet,
             to_user_id,
             receiver_wallet.id,
@@ -1355,6 +1739,34 @@ impl TicketInstance {
         Ok(())
     }
 
+    /// Nullifies the purchased, unredeemed tickets of an order, e.g. when the payment has been charged back
+    pub fn nullify_for_order(
+        order_id: Uuid,
+        user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let order_item_ids = order_items::table
+            .filter(order_items::order_id.eq(order_id))
+            .select(order_items::id.nullable());
+        let tickets: Vec<TicketInstance> = diesel::update(
+            ticket_instances::table
+                .filter(ticket_instances::order_item_id.eq_any(order_item_ids))
+                .filter(ticket_instances::status.eq(TicketInstanceStatus::Purchased)),
+        )
+        .set((
+            ticket_instances::status.eq(TicketInstanceStatus::Nullified),
+            ticket_instances::updated_at.eq(dsl::now),
+        ))
+        .get_results(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not nullify tickets for order")?;
+
+        for ticket in &tickets {
+            ticket.create_nullified_domain_event(user_id, conn)?;
+        }
+
+        Ok(tickets)
+    }
+
     pub fn nullify_tickets(
         asset_id: Uuid,
         quantity: u32,
@@ -1401,6 +1813,11 @@ pub struct DisplayTicket {
     pub transfer_address: Option<String>,
     pub check_in_source: Option<CheckInSource>,
     pub promo_image_url: Option<String>,
+    /// Secret the app derives rotating redeem codes from, only set for events using them
+    pub redeem_secret: Option<String>,
+    pub seat_section: Option<String>,
+    pub seat_row: Option<String>,
+    pub seat_number: Option<String>,
 }
 
 #[derive(Queryable, QueryableByName)]
@@ -1445,6 +1862,14 @@ pub struct DisplayTicketIntermediary {
     pub check_in_source: Option<CheckInSource>,
     #[sql_type = "Nullable<Text>"]
     pub promo_image_url: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub redeem_secret: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_section: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_row: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_number: Option<String>,
 }
 
 impl From<DisplayTicketIntermediary> for DisplayTicket {
@@ -1466,10 +1891,13 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             .or(day_before_event_start)
             .unwrap();
 
-        let redeem_key = if Utc::now().naive_utc() > redemption_allowed_after_date {
-            ticket_intermediary.redeem_key.clone()
+        let (redeem_key, redeem_secret) = if Utc::now().naive_utc() > redemption_allowed_after_date {
+            (
+                ticket_intermediary.redeem_key.clone(),
+                ticket_intermediary.redeem_secret.clone(),
+            )
         } else {
-            None
+            (None, None)
         };
 
         DisplayTicket {
@@ -1488,6 +1916,10 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             transfer_address: ticket_intermediary.transfer_address,
             check_in_source: ticket_intermediary.check_in_source,
             promo_image_url: ticket_intermediary.promo_image_url,
+            redeem_secret,
+            seat_section: ticket_intermediary.seat_section,
+            seat_row: ticket_intermediary.seat_row,
+            seat_number: ticket_intermediary.seat_number,
         }
     }
 }
@@ -1541,12 +1973,49 @@ struct NewTicketInstance {
     wallet_id: Uuid,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum RedeemResults {
     TicketRedeemSuccess,
     TicketAlreadyRedeemed,
     TicketInvalid,
     TicketTransferInProcess,
+    TicketReentrySuccess,
+    TicketReentryNotAllowed,
+    TicketCheckedOut,
+    TicketNotCheckedIn,
+    TicketNotValidForAccessZone,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScannerSnapshotTicket {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub redeem_key_hash: Option<String>,
+    /// Secret rotating redeem codes are derived from, only set for events using them
+    pub redeem_secret: Option<String>,
+    pub status: TicketInstanceStatus,
+    pub pending_transfer: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineScan {
+    pub ticket_id: Uuid,
+    pub redeem_key: String,
+    pub scanned_at: NaiveDateTime,
+    pub device_id: Option<String>,
+    pub check_in_source: Option<CheckInSource>,
+    #[serde(default)]
+    pub access_zone_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineScanResult {
+    pub ticket_id: Uuid,
+    pub device_id: Option<String>,
+    pub scanned_at: NaiveDateTime,
+    pub result: RedeemResults,
+    pub redeemed_by_user_id: Option<Uuid>,
+    pub redeemed_at: Option<NaiveDateTime>,
 }
 
 fn generate_redeem_key(len: u32) -> String {
@@ -0,0 +1,117 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Uuid as dUuid};
+use models::*;
+use schema::ticket_scans;
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, QueryableByName, Serialize)]
+#[table_name = "ticket_scans"]
+pub struct TicketScan {
+    pub id: Uuid,
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub scan_type: TicketScanType,
+    pub scanned_by_user_id: Option<Uuid>,
+    pub check_in_source: Option<CheckInSource>,
+    pub scanned_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub access_zone_id: Option<Uuid>,
+}
+
+#[derive(Clone, Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "ticket_scans"]
+pub struct NewTicketScan {
+    pub ticket_instance_id: Uuid,
+    pub event_id: Uuid,
+    pub scan_type: TicketScanType,
+    pub scanned_by_user_id: Option<Uuid>,
+    pub check_in_source: Option<CheckInSource>,
+    pub access_zone_id: Option<Uuid>,
+    pub scanned_at: NaiveDateTime,
+}
+
+impl NewTicketScan {
+    pub fn commit(&self, conn: &PgConnection) -> Result<TicketScan, DatabaseError> {
+        diesel::insert_into(ticket_scans::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ticket scan")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct EventOccupancy {
+    #[sql_type = "BigInt"]
+    pub entries: i64,
+    #[sql_type = "BigInt"]
+    pub exits: i64,
+    #[sql_type = "BigInt"]
+    pub inside: i64,
+}
+
+impl TicketScan {
+    pub fn create(
+        ticket_instance_id: Uuid,
+        event_id: Uuid,
+        scan_type: TicketScanType,
+        scanned_by_user_id: Option<Uuid>,
+        check_in_source: Option<CheckInSource>,
+        access_zone_id: Option<Uuid>,
+        scanned_at: NaiveDateTime,
+    ) -> NewTicketScan {
+        NewTicketScan {
+            ticket_instance_id,
+            event_id,
+            scan_type,
+            scanned_by_user_id,
+            check_in_source,
+            access_zone_id,
+            scanned_at,
+        }
+    }
+
+    /// Check-in and check-out log for a ticket, oldest scan first
+    pub fn find_for_ticket_instance(
+        ticket_instance_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketScan>, DatabaseError> {
+        ticket_scans::table
+            .filter(ticket_scans::ticket_instance_id.eq(ticket_instance_id))
+            .order_by(ticket_scans::scanned_at)
+            .then_order_by(ticket_scans::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load scans for ticket")
+    }
+
+    /// Entries and exits recorded for an event, `inside` counts the tickets whose latest scan
+    /// was a check-in
+    pub fn occupancy_for_event(event_id: Uuid, conn: &PgConnection) -> Result<EventOccupancy, DatabaseError> {
+        let query = r#"
+            SELECT
+                CAST(COUNT(*) FILTER (WHERE ts.scan_type = 'CheckIn') AS BIGINT)  AS entries,
+                CAST(COUNT(*) FILTER (WHERE ts.scan_type = 'CheckOut') AS BIGINT) AS exits,
+                (
+                    SELECT CAST(COUNT(*) AS BIGINT)
+                    FROM (
+                        SELECT DISTINCT ON (ticket_instance_id) scan_type
+                        FROM ticket_scans
+                        WHERE event_id = $1
+                        ORDER BY ticket_instance_id, scanned_at DESC, created_at DESC
+                    ) latest
+                    WHERE latest.scan_type = 'CheckIn'
+                )                                                                 AS inside
+            FROM ticket_scans ts
+            WHERE ts.event_id = $1
+        "#;
+
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(event_id)
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load occupancy for event")
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::{AccessZone, TicketType};
+use schema::ticket_type_access_zones;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(TicketType)]
+#[belongs_to(AccessZone)]
+#[table_name = "ticket_type_access_zones"]
+pub struct TicketTypeAccessZone {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub access_zone_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "ticket_type_access_zones"]
+pub struct NewTicketTypeAccessZone {
+    pub ticket_type_id: Uuid,
+    pub access_zone_id: Uuid,
+}
+
+impl NewTicketTypeAccessZone {
+    pub fn commit(&self, conn: &PgConnection) -> Result<TicketTypeAccessZone, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::InsertError,
+            "Could not add access zone to ticket_type",
+            diesel::insert_into(ticket_type_access_zones::table)
+                .values(self)
+                .get_result(conn),
+        )
+    }
+}
+
+impl TicketTypeAccessZone {
+    pub fn create(ticket_type_id: Uuid, access_zone_id: Uuid) -> NewTicketTypeAccessZone {
+        NewTicketTypeAccessZone {
+            ticket_type_id,
+            access_zone_id,
+        }
+    }
+
+    pub fn destroy_multiple(
+        ticket_type_id: Uuid,
+        access_zone_ids: Vec<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Could not remove ticket type access zones",
+            diesel::delete(
+                ticket_type_access_zones::table
+                    .filter(ticket_type_access_zones::ticket_type_id.eq(ticket_type_id))
+                    .filter(ticket_type_access_zones::access_zone_id.eq_any(access_zone_ids)),
+            )
+            .execute(conn),
+        )
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text, Uuid as dUuid};
+use models::*;
+use schema::ticket_type_seat_tiers;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(TicketType)]
+#[belongs_to(SeatMap)]
+#[table_name = "ticket_type_seat_tiers"]
+pub struct TicketTypeSeatTier {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub seat_map_id: Uuid,
+    pub price_tier: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "ticket_type_seat_tiers"]
+pub struct NewTicketTypeSeatTier {
+    pub ticket_type_id: Uuid,
+    pub seat_map_id: Uuid,
+    pub price_tier: String,
+}
+
+impl NewTicketTypeSeatTier {
+    /// A ticket type keeps the price tier it was first mapped to as its tickets hold those seats
+    pub fn commit(self, conn: &PgConnection) -> Result<TicketTypeSeatTier, DatabaseError> {
+        if let Some(existing) = TicketTypeSeatTier::find_for_ticket_type(self.ticket_type_id, conn)? {
+            if existing.seat_map_id != self.seat_map_id || existing.price_tier != self.price_tier {
+                return DatabaseError::validation_error(
+                    "price_tiers",
+                    "Ticket type is already mapped to a different price tier",
+                );
+            }
+            return Ok(existing);
+        }
+
+        diesel::insert_into(ticket_type_seat_tiers::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not map ticket type to price tier")
+    }
+}
+
+impl TicketTypeSeatTier {
+    pub fn create(ticket_type_id: Uuid, seat_map_id: Uuid, price_tier: String) -> NewTicketTypeSeatTier {
+        NewTicketTypeSeatTier {
+            ticket_type_id,
+            seat_map_id,
+            price_tier,
+        }
+    }
+
+    pub fn find_for_ticket_type(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<TicketTypeSeatTier>, DatabaseError> {
+        ticket_type_seat_tiers::table
+            .filter(ticket_type_seat_tiers::ticket_type_id.eq(ticket_type_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket type price tier")
+    }
+
+    /// Assigns the seats of the price tier to the ticket type's available tickets, the ticket type
+    /// needs an unseated ticket for every seat. Tickets left without a seat are not sold.
+    pub fn assign_seats(&self, conn: &PgConnection) -> Result<Vec<TicketInstance>, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "BigInt"]
+            seats: i64,
+            #[sql_type = "BigInt"]
+            tickets: i64,
+        }
+
+        let counts: R = diesel::sql_query(
+            r#"
+            SELECT
+                (SELECT count(*)
+                 FROM seats s
+                 JOIN seat_map_sections sms ON s.seat_map_section_id = sms.id
+                 WHERE sms.seat_map_id = $2
+                 AND s.price_tier = $3
+                 AND NOT EXISTS(
+                     SELECT 1
+                     FROM ticket_instances ti
+                     JOIN assets a ON ti.asset_id = a.id
+                     JOIN ticket_types tt ON a.ticket_type_id = tt.id
+                     WHERE ti.seat_id = s.id
+                     AND tt.event_id = (SELECT event_id FROM ticket_types WHERE id = $1)
+                 )) AS seats,
+                (SELECT count(*)
+                 FROM ticket_instances t
+                 JOIN assets a ON t.asset_id = a.id
+                 WHERE a.ticket_type_id = $1
+                 AND t.seat_id IS NULL
+                 AND t.status = 'Available'
+                 AND t.parent_id IS NULL) AS tickets
+        "#,
+        )
+        .bind::<dUuid, _>(self.ticket_type_id)
+        .bind::<dUuid, _>(self.seat_map_id)
+        .bind::<Text, _>(&self.price_tier)
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not count seats to assign")?;
+
+        if counts.tickets < counts.seats {
+            return DatabaseError::validation_error(
+                "price_tiers",
+                "Ticket type does not have enough available tickets for the seats in the price tier",
+            );
+        }
+
+        diesel::sql_query(include_str!("../queries/assign_seats.sql"))
+            .bind::<dUuid, _>(self.ticket_type_id)
+            .bind::<dUuid, _>(self.seat_map_id)
+            .bind::<Text, _>(&self.price_tier)
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign seats")
+    }
+}
@@ -11,6 +11,7 @@ use schema::{
     assets, events, fee_schedules, organizations, ticket_instances, ticket_pricing, ticket_type_codes, ticket_types,
 };
 use serde_with::rust::double_option;
+use std::borrow::Cow;
 use std::cmp;
 use std::cmp::Ordering;
 use utils::errors::*;
@@ -48,6 +49,9 @@ pub struct TicketType {
     pub ticket_type_type: TicketTypeType,
     pub promo_image_url: Option<String>,
     pub content_url: Option<String>,
+    pub reentry_policy: ReentryPolicy,
+    pub reentry_limit: Option<i32>,
+    pub cloned_from_ticket_type_id: Option<Uuid>,
 }
 
 impl PartialOrd for TicketType {
@@ -56,7 +60,7 @@ impl PartialOrd for TicketType {
     }
 }
 
-#[derive(AsChangeset, Default, Deserialize, Serialize)]
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
 #[table_name = "ticket_types"]
 pub struct TicketTypeEditableAttributes {
     pub name: Option<String>,
@@ -79,6 +83,9 @@ pub struct TicketTypeEditableAttributes {
     pub box_office_sales_enabled: Option<bool>,
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    pub reentry_policy: Option<ReentryPolicy>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub reentry_limit: Option<Option<i32>>,
 }
 
 impl TicketType {
@@ -261,6 +268,68 @@ impl TicketType {
         Ok(result)
     }
 
+    pub fn update_reentry_policy(
+        self,
+        reentry_policy: ReentryPolicy,
+        reentry_limit: Option<i32>,
+        conn: &PgConnection,
+    ) -> Result<TicketType, DatabaseError> {
+        validators::append_validation_error(
+            Ok(()),
+            "reentry_limit",
+            TicketType::validate_reentry_limit(reentry_policy, reentry_limit),
+        )?;
+
+        diesel::update(&self)
+            .set((
+                ticket_types::reentry_policy.eq(reentry_policy),
+                ticket_types::reentry_limit.eq(reentry_limit),
+                ticket_types::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket type re-entry policy")
+    }
+
+    pub fn access_zones(&self, conn: &PgConnection) -> Result<Vec<AccessZone>, DatabaseError> {
+        AccessZone::find_for_ticket_type(self.id, conn)
+    }
+
+    pub fn update_access_zones(&self, access_zone_ids: Vec<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let event = self.event(conn)?;
+        let mut validation_errors = Ok(());
+        for access_zone_id in &access_zone_ids {
+            if !AccessZone::find(*access_zone_id, conn)?.is_for_event(&event) {
+                let mut validation_error =
+                    create_validation_error("invalid", "Access zone does not belong to the event or its venue");
+                validation_error.add_param(Cow::from("access_zone_id"), access_zone_id);
+                validation_errors =
+                    validators::append_validation_error(validation_errors, "access_zone_ids", Err(validation_error));
+            }
+        }
+        validation_errors?;
+
+        let existing_access_zone_ids = self
+            .access_zones(conn)?
+            .into_iter()
+            .map(|z| z.id)
+            .collect::<Vec<Uuid>>();
+        let pending_deletion = existing_access_zone_ids
+            .clone()
+            .into_iter()
+            .filter(|id| !access_zone_ids.contains(id))
+            .collect::<Vec<Uuid>>();
+        let pending_addition = access_zone_ids
+            .into_iter()
+            .filter(|id| !existing_access_zone_ids.contains(id))
+            .collect::<Vec<Uuid>>();
+        TicketTypeAccessZone::destroy_multiple(self.id, pending_deletion, conn)?;
+
+        for access_zone_id in pending_addition {
+            TicketTypeAccessZone::create(self.id, access_zone_id).commit(conn)?;
+        }
+        Ok(())
+    }
+
     pub fn update_rank_only(self, new_rank: i32, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
         let result: TicketType = diesel::update(&self)
             .set((ticket_types::rank.eq(new_rank), ticket_types::updated_at.eq(dsl::now)))
@@ -369,6 +438,15 @@ impl TicketType {
         attributes: &mut TicketTypeEditableAttributes,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
+        validators::append_validation_error(
+            Ok(()),
+            "reentry_limit",
+            TicketType::validate_reentry_limit(
+                attributes.reentry_policy.unwrap_or(self.reentry_policy),
+                attributes.reentry_limit.unwrap_or(self.reentry_limit),
+            ),
+        )?;
+
         if attributes.end_date_type.unwrap_or(self.end_date_type) == TicketTypeEndDateType::Manual
             && (attributes.end_date == Some(None) || (attributes.end_date.is_none() && self.end_date.is_none()))
         {
@@ -425,6 +503,29 @@ impl TicketType {
         Ok(())
     }
 
+    fn validate_reentry_limit(
+        reentry_policy: ReentryPolicy,
+        reentry_limit: Option<i32>,
+    ) -> Result<(), ValidationError> {
+        if reentry_policy == ReentryPolicy::Limited && reentry_limit.unwrap_or(0) <= 0 {
+            return Err(create_validation_error(
+                "required",
+                "Re-entry limit must be greater than 0 for limited re-entry",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of times a ticket of this type may enter again after checking out, `None` when
+    /// re-entry is unlimited
+    pub fn reentries_allowed(&self) -> Option<i64> {
+        match self.reentry_policy {
+            ReentryPolicy::None => Some(0),
+            ReentryPolicy::Limited => Some(self.reentry_limit.unwrap_or(0) as i64),
+            ReentryPolicy::Unlimited => None,
+        }
+    }
+
     pub fn validate_ticket_pricing(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         let mut validation_errors: Result<(), ValidationErrors> = Ok(());
 
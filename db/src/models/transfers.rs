@@ -11,6 +11,7 @@ use schema::{
 use serde_json::Value;
 use std::cmp::Ordering;
 use tari_client::*;
+use utils::encryption::KeyRing;
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -151,24 +152,33 @@ impl Transfer {
         })
     }
 
-    pub fn receive_url(&self, front_end_url: &str, conn: &PgConnection) -> Result<String, DatabaseError> {
+    pub fn receive_url(
+        &self,
+        front_end_url: &str,
+        key_ring: &KeyRing,
+        conn: &PgConnection,
+    ) -> Result<String, DatabaseError> {
         Ok(format!(
             "{}/tickets/transfers/receive?sender_user_id={}&transfer_key={}&num_tickets={}&signature={}",
             front_end_url,
             self.source_user_id,
             self.transfer_key,
             self.transfer_tickets(conn)?.len(),
-            self.signature(conn)?
+            self.signature(key_ring, conn)?
         )
         .to_string())
     }
 
-    pub fn into_authorization(&self, conn: &PgConnection) -> Result<TransferAuthorization, DatabaseError> {
+    pub fn into_authorization(
+        &self,
+        key_ring: &KeyRing,
+        conn: &PgConnection,
+    ) -> Result<TransferAuthorization, DatabaseError> {
         Ok(TransferAuthorization {
             transfer_key: self.transfer_key,
             sender_user_id: self.source_user_id,
             num_tickets: self.transfer_tickets(conn)?.len() as u32,
-            signature: self.signature(conn)?,
+            signature: self.signature(key_ring, conn)?,
         })
     }
 
@@ -219,11 +229,12 @@ impl Transfer {
             .to_db_error(ErrorCode::QueryError, "Could not check transfer ticket count")
     }
 
-    pub fn signature(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
+    /// Signed with the source user's wallet, `key_ring` decrypts the wallet's secret key
+    pub fn signature(&self, key_ring: &KeyRing, conn: &PgConnection) -> Result<String, DatabaseError> {
         let mut message: String = self.transfer_key.to_string();
         message.push_str(self.source_user_id.to_string().as_str());
         message.push_str(self.transfer_ticket_count(conn)?.to_string().as_str());
-        let secret_key = Wallet::find_default_for_user(self.source_user_id, conn)?.secret_key;
+        let secret_key = Wallet::find_default_for_user(self.source_user_id, conn)?.secret_key(key_ring)?;
         Ok(convert_bytes_to_hexstring(&cryptographic_signature(
             &message,
             &convert_hexstring_to_bytes(&secret_key),
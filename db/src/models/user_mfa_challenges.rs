@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::user_mfa_challenges;
+use utils::errors::*;
+use utils::hash::sha256;
+use uuid::Uuid;
+
+// Incorrect codes accepted for a multi-factor challenge before the user has to log in again
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+#[derive(Associations, Identifiable, Queryable, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "user_mfa_challenges"]
+pub struct UserMfaChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    token_hash: String,
+    pub failed_attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_mfa_challenges"]
+struct NewUserMfaChallenge {
+    user_id: Uuid,
+    token_hash: String,
+    failed_attempts: i32,
+}
+
+impl UserMfaChallenge {
+    /// Whether too many incorrect codes have been entered for the challenge token
+    pub fn exhausted(user_id: Uuid, mfa_token: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let failed_attempts: Option<i32> = user_mfa_challenges::table
+            .filter(user_mfa_challenges::user_id.eq(user_id))
+            .filter(user_mfa_challenges::token_hash.eq(sha256::digest(mfa_token)))
+            .select(user_mfa_challenges::failed_attempts)
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load multi-factor challenge")?;
+
+        Ok(failed_attempts.unwrap_or(0) >= MAX_FAILED_ATTEMPTS)
+    }
+
+    /// Counts an incorrect code against the challenge token, returning the failures so far
+    pub fn record_failure(user_id: Uuid, mfa_token: &str, conn: &PgConnection) -> Result<i32, DatabaseError> {
+        diesel::insert_into(user_mfa_challenges::table)
+            .values(&NewUserMfaChallenge {
+                user_id,
+                token_hash: sha256::digest(mfa_token),
+                failed_attempts: 1,
+            })
+            .on_conflict(user_mfa_challenges::token_hash)
+            .do_update()
+            .set((
+                user_mfa_challenges::failed_attempts.eq(user_mfa_challenges::failed_attempts + 1),
+                user_mfa_challenges::updated_at.eq(dsl::now),
+            ))
+            .returning(user_mfa_challenges::failed_attempts)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not record failed multi-factor attempt")
+    }
+
+    /// Removes the user's challenges once they have completed a multi-factor login
+    pub fn destroy_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Could not remove multi-factor challenges",
+            diesel::delete(user_mfa_challenges::table.filter(user_mfa_challenges::user_id.eq(user_id))).execute(conn),
+        )
+    }
+}
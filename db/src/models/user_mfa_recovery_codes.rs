@@ -0,0 +1,101 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::user_mfa_recovery_codes;
+use utils::errors::*;
+use utils::hash::hmac_sha256;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+#[derive(Associations, Identifiable, Queryable, PartialEq, Debug)]
+#[belongs_to(User)]
+#[table_name = "user_mfa_recovery_codes"]
+pub struct UserMfaRecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    hashed_code: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_mfa_recovery_codes"]
+struct NewUserMfaRecoveryCode {
+    user_id: Uuid,
+    hashed_code: String,
+}
+
+impl UserMfaRecoveryCode {
+    /// Replaces the user's recovery codes, the plain codes are only available from the result
+    pub fn generate_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        UserMfaRecoveryCode::destroy_for_user(user_id, conn)?;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| random_alpha_string(RECOVERY_CODE_LENGTH).to_uppercase())
+            .collect();
+        let new_codes: Vec<NewUserMfaRecoveryCode> = codes
+            .iter()
+            .map(|code| NewUserMfaRecoveryCode {
+                user_id,
+                hashed_code: UserMfaRecoveryCode::hash_code(user_id, code),
+            })
+            .collect();
+        diesel::insert_into(user_mfa_recovery_codes::table)
+            .values(&new_codes)
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create recovery codes")?;
+
+        Ok(codes)
+    }
+
+    pub fn find_unused_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<UserMfaRecoveryCode>, DatabaseError> {
+        user_mfa_recovery_codes::table
+            .filter(user_mfa_recovery_codes::user_id.eq(user_id))
+            .filter(user_mfa_recovery_codes::used_at.is_null())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load recovery codes")
+    }
+
+    /// Marks the code as used, returns false if it does not exist or was already used
+    pub fn redeem(user_id: Uuid, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let updated = diesel::update(
+            user_mfa_recovery_codes::table
+                .filter(user_mfa_recovery_codes::user_id.eq(user_id))
+                .filter(user_mfa_recovery_codes::hashed_code.eq(UserMfaRecoveryCode::hash_code(user_id, code)))
+                .filter(user_mfa_recovery_codes::used_at.is_null()),
+        )
+        .set((
+            user_mfa_recovery_codes::used_at.eq(dsl::now.nullable()),
+            user_mfa_recovery_codes::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not use recovery code")?;
+
+        Ok(updated == 1)
+    }
+
+    pub fn destroy_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Could not remove recovery codes",
+            diesel::delete(user_mfa_recovery_codes::table.filter(user_mfa_recovery_codes::user_id.eq(user_id)))
+                .execute(conn),
+        )
+    }
+
+    // Codes are random so a keyed hash is enough, dashes and spaces users add are ignored
+    fn hash_code(user_id: Uuid, code: &str) -> String {
+        let normalized_code: String = code
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_uppercase();
+        hmac_sha256::sign(&user_id.to_string(), &normalized_code)
+    }
+}
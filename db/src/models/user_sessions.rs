@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::user_sessions;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "user_sessions"]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub current_token_id: Uuid,
+    pub user_agent: Option<String>,
+    pub last_refreshed_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_sessions"]
+pub struct NewUserSession {
+    pub user_id: Uuid,
+    pub current_token_id: Uuid,
+    pub user_agent: Option<String>,
+}
+
+impl NewUserSession {
+    pub fn commit(&self, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        diesel::insert_into(user_sessions::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create user session")
+    }
+}
+
+impl UserSession {
+    pub fn create(user_id: Uuid, user_agent: Option<String>) -> NewUserSession {
+        NewUserSession {
+            user_id,
+            current_token_id: Uuid::new_v4(),
+            user_agent,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        user_sessions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load user session")
+    }
+
+    /// Sessions which have not been revoked, most recently used first
+    pub fn find_active_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<UserSession>, DatabaseError> {
+        user_sessions::table
+            .filter(user_sessions::user_id.eq(user_id))
+            .filter(user_sessions::revoked_at.is_null())
+            .order_by(user_sessions::last_refreshed_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sessions for user")
+    }
+
+    /// When any of the user's sessions was last revoked, refresh tokens issued without a session
+    /// before then are no longer accepted
+    pub fn last_revoked_at_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<NaiveDateTime>, DatabaseError> {
+        user_sessions::table
+            .filter(user_sessions::user_id.eq(user_id))
+            .select(dsl::max(user_sessions::revoked_at))
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load session revocations for user")
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    /// Exchanges the presented refresh token id for a new one. A token id which is no longer
+    /// current means the token was copied and used elsewhere, so the session is revoked and
+    /// `None` is returned.
+    pub fn rotate(&self, token_id: Uuid, conn: &PgConnection) -> Result<Option<UserSession>, DatabaseError> {
+        if !self.is_active() {
+            return Ok(None);
+        }
+
+        let session: Option<UserSession> = diesel::update(
+            user_sessions::table
+                .filter(user_sessions::id.eq(self.id))
+                .filter(user_sessions::current_token_id.eq(token_id))
+                .filter(user_sessions::revoked_at.is_null()),
+        )
+        .set((
+            user_sessions::current_token_id.eq(Uuid::new_v4()),
+            user_sessions::last_refreshed_at.eq(dsl::now),
+            user_sessions::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not rotate user session")?;
+
+        if session.is_none() {
+            self.revoke(conn)?;
+        }
+        Ok(session)
+    }
+
+    pub fn revoke(&self, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not revoke user session",
+            diesel::update(self)
+                .set((
+                    user_sessions::revoked_at.eq(dsl::now.nullable()),
+                    user_sessions::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn),
+        )
+    }
+
+    pub fn revoke_all_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(
+            user_sessions::table
+                .filter(user_sessions::user_id.eq(user_id))
+                .filter(user_sessions::revoked_at.is_null()),
+        )
+        .set((
+            user_sessions::revoked_at.eq(dsl::now.nullable()),
+            user_sessions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not revoke sessions for user")
+    }
+}
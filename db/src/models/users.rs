@@ -15,15 +15,20 @@ use schema::{event_users, events, genres, organization_users, organizations, use
 use serde_json::Value;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use utils::encryption::KeyRing;
 use utils::errors::Optional;
 use utils::errors::{ConvertToDatabaseError, DatabaseError, ErrorCode};
 use utils::pagination::Paginate;
 use utils::passwords::PasswordHash;
 use utils::rand::random_alpha_string;
+use utils::totp;
 use uuid::Uuid;
 use validator::*;
 use validators::{self, *};
 
+// Codes from the previous or next time step are accepted to allow for clock drift
+const MFA_CODE_SKEW_STEPS: i64 = 1;
+
 #[derive(Insertable, PartialEq, Debug, Validate)]
 #[table_name = "users"]
 pub struct NewUser {
@@ -60,6 +65,10 @@ pub struct User {
     pub accepted_terms_date: Option<NaiveDateTime>,
     pub invited_at: Option<NaiveDateTime>,
     pub deleted_at: Option<NaiveDateTime>,
+    mfa_secret: Option<String>,
+    pub mfa_enabled_at: Option<NaiveDateTime>,
+    mfa_secret_version: i32,
+    mfa_last_used_step: i64,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -133,7 +142,13 @@ impl PartialOrd for User {
 }
 
 impl NewUser {
-    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<User, DatabaseError> {
+    /// Creates the user along with their default wallet, the wallet's secret key is encrypted with `key_ring`
+    pub fn commit(
+        &self,
+        current_user_id: Option<Uuid>,
+        key_ring: &KeyRing,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError> {
         self.validate()?;
         let user: User = diesel::insert_into(users::table)
             .values(self)
@@ -141,7 +156,7 @@ impl NewUser {
             .to_db_error(ErrorCode::InsertError, "Could not create new user")?;
 
         DomainEvent::create(DomainEventTypes::UserCreated, "User created".to_string(), Tables::Users, Some(user.id), current_user_id, Some(json!({"first_name": self.first_name, "last_name": self.last_name, "email": self.email, "phone": self.phone}))).commit(conn)?;
-        Wallet::create_for_user(user.id, "Default".to_string(), true, conn)?;
+        Wallet::create_for_user(user.id, "Default".to_string(), true, key_ring, conn)?;
 
         Ok(user)
     }
@@ -327,6 +342,7 @@ impl User {
         access_token: String,
         scopes: Vec<String>,
         current_user_id: Option<Uuid>,
+        key_ring: &KeyRing,
         conn: &PgConnection,
     ) -> Result<User, DatabaseError> {
         let rand_password = random_alpha_string(16);
@@ -340,7 +356,7 @@ impl User {
             hashed_pw: hash.to_string(),
             role: vec![Roles::User],
         };
-        new_user.commit(current_user_id, conn).and_then(|user| {
+        new_user.commit(current_user_id, key_ring, conn).and_then(|user| {
             user.add_external_login(current_user_id, external_user_id, site, access_token, scopes, conn)?;
             Ok(user)
         })
@@ -363,15 +379,34 @@ impl User {
         Ok(())
     }
 
+    /// Failed password login, the user is unknown when the email does not match an account
+    pub fn login_failed_domain_event(
+        user_id: Option<Uuid>,
+        json: Value,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::UserLoginFailed,
+            "User login failed".to_string(),
+            Tables::Users,
+            user_id,
+            user_id,
+            Some(json),
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+
     pub fn create_stub(
         first_name: String,
         last_name: String,
         email: Option<String>,
         phone: Option<String>,
         current_user_id: Option<Uuid>,
+        key_ring: &KeyRing,
         conn: &PgConnection,
     ) -> Result<User, DatabaseError> {
-        Self::new_stub(Some(first_name), Some(last_name), email, phone).commit(current_user_id, conn)
+        Self::new_stub(Some(first_name), Some(last_name), email, phone).commit(current_user_id, key_ring, conn)
     }
 
     pub fn transfer_activity_by_event_tickets(
@@ -542,6 +577,12 @@ impl User {
             facebook_event_id: Option<String>,
             #[sql_type = "Nullable<dUuid>"]
             cloned_from_event_id: Option<Uuid>,
+            #[sql_type = "Text"]
+            currency: String,
+            #[sql_type = "Bool"]
+            rotating_redeem_codes: bool,
+            #[sql_type = "Nullable<dUuid>"]
+            event_series_id: Option<Uuid>,
         }
 
         let mut query = sql_query(
@@ -632,6 +673,9 @@ impl User {
             slug_id: Some(event.slug_id),
             facebook_event_id: event.facebook_event_id,
             cloned_from_event_id: event.cloned_from_event_id,
+            currency: event.currency,
+            rotating_redeem_codes: event.rotating_redeem_codes,
+            event_series_id: event.event_series_id,
         });
 
         let mut result: Vec<ActivitySummary> = Vec::new();
@@ -975,6 +1019,192 @@ impl User {
         hash.verify(password)
     }
 
+    pub fn mfa_enabled(&self) -> bool {
+        self.mfa_enabled_at.is_some()
+    }
+
+    /// Base32 secret for the user's authenticator app, stored encrypted with the MFA master keys
+    pub fn mfa_secret(&self, key_ring: &KeyRing) -> Result<Option<String>, DatabaseError> {
+        match self.mfa_secret {
+            Some(ref secret) => Ok(Some(key_ring.decrypt(secret, self.mfa_secret_version)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Re-encrypts MFA secrets that are unencrypted or encrypted with an older master key version,
+    /// returning the number of users updated
+    pub fn reencrypt_mfa_secrets(key_ring: &KeyRing, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let current_version = match key_ring.current_version() {
+            Some(current_version) => current_version,
+            None => return DatabaseError::business_process_error("No encryption keys have been configured"),
+        };
+
+        let users: Vec<User> = users::table
+            .filter(users::mfa_secret.is_not_null())
+            .filter(users::mfa_secret_version.ne(current_version))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load users with MFA secrets")?;
+        for user in &users {
+            let secret = match user.mfa_secret {
+                Some(ref secret) => key_ring.decrypt(secret, user.mfa_secret_version)?,
+                None => continue,
+            };
+            let (mfa_secret, mfa_secret_version) = key_ring.encrypt(&secret)?;
+            diesel::update(users::table.filter(users::id.eq(user.id)))
+                .set((
+                    users::mfa_secret.eq(mfa_secret),
+                    users::mfa_secret_version.eq(mfa_secret_version),
+                    users::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not re-encrypt MFA secret")?;
+        }
+
+        Ok(users.len())
+    }
+
+    /// Generates a new secret for the user to add to their authenticator app, MFA is only enforced
+    /// once a code from the app has been confirmed with `activate_mfa`
+    pub fn begin_mfa_enrollment(&self, key_ring: &KeyRing, conn: &PgConnection) -> Result<User, DatabaseError> {
+        if self.mfa_enabled() {
+            return DatabaseError::business_process_error("Multi-factor authentication is already enabled");
+        }
+
+        let (mfa_secret, mfa_secret_version) = key_ring.encrypt(&totp::generate_secret())?;
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not update multi-factor authentication for user",
+            diesel::update(self)
+                .set((
+                    users::mfa_secret.eq(mfa_secret),
+                    users::mfa_secret_version.eq(mfa_secret_version),
+                    users::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn),
+        )
+    }
+
+    /// Enables MFA once the user proves their authenticator app is set up, returns the recovery
+    /// codes which are not retrievable afterwards
+    pub fn activate_mfa(
+        &self,
+        code: &str,
+        key_ring: &KeyRing,
+        conn: &PgConnection,
+    ) -> Result<(User, Vec<String>), DatabaseError> {
+        let secret = match (self.mfa_secret(key_ring)?, self.mfa_enabled()) {
+            (Some(secret), false) => secret,
+            (_, true) => {
+                return DatabaseError::business_process_error("Multi-factor authentication is already enabled")
+            }
+            (None, false) => {
+                return DatabaseError::business_process_error("Multi-factor authentication enrollment has not started")
+            }
+        };
+        let step = totp::verified_step(&secret, code, Utc::now().timestamp(), MFA_CODE_SKEW_STEPS);
+        if step.is_none() {
+            validators::append_validation_error(
+                Ok(()),
+                "code",
+                Err(create_validation_error("invalid", "Code is invalid or has expired")),
+            )?;
+        }
+
+        let user: User = DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not update multi-factor authentication for user",
+            diesel::update(self)
+                .set((
+                    users::mfa_enabled_at.eq(dsl::now.nullable()),
+                    users::mfa_last_used_step.eq(step.unwrap_or_default()),
+                    users::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn),
+        )?;
+        let recovery_codes = UserMfaRecoveryCode::generate_for_user(self.id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserMfaEnabled,
+            "Multi-factor authentication enabled".to_string(),
+            Tables::Users,
+            Some(self.id),
+            Some(self.id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok((user, recovery_codes))
+    }
+
+    /// Accepts a code from the user's authenticator app or one of their unused recovery codes, each
+    /// authenticator code is only accepted once
+    pub fn verify_mfa_code(&self, code: &str, key_ring: &KeyRing, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let secret = match self.mfa_secret(key_ring)? {
+            Some(secret) if self.mfa_enabled() => secret,
+            _ => return Ok(false),
+        };
+        if let Some(step) = totp::verified_step(&secret, code, Utc::now().timestamp(), MFA_CODE_SKEW_STEPS) {
+            return self.use_mfa_step(step, conn);
+        }
+
+        UserMfaRecoveryCode::redeem(self.id, code, conn)
+    }
+
+    // Records the time step of an accepted authenticator code, returns false if a code from this or
+    // a later step was already used so codes can not be replayed within the skew window
+    fn use_mfa_step(&self, step: i64, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let updated = diesel::update(
+            users::table
+                .filter(users::id.eq(self.id))
+                .filter(users::mfa_last_used_step.lt(step)),
+        )
+        .set((users::mfa_last_used_step.eq(step), users::updated_at.eq(dsl::now)))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not update multi-factor authentication for user",
+        )?;
+
+        Ok(updated == 1)
+    }
+
+    pub fn disable_mfa(&self, code: &str, key_ring: &KeyRing, conn: &PgConnection) -> Result<User, DatabaseError> {
+        if !self.verify_mfa_code(code, key_ring, conn)? {
+            validators::append_validation_error(
+                Ok(()),
+                "code",
+                Err(create_validation_error("invalid", "Code is invalid or has expired")),
+            )?;
+        }
+
+        let user: User = DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not update multi-factor authentication for user",
+            diesel::update(self)
+                .set((
+                    users::mfa_secret.eq(None::<String>),
+                    users::mfa_secret_version.eq(0),
+                    users::mfa_enabled_at.eq(None::<NaiveDateTime>),
+                    users::mfa_last_used_step.eq(0),
+                    users::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn),
+        )?;
+        UserMfaRecoveryCode::destroy_for_user(self.id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserMfaDisabled,
+            "Multi-factor authentication disabled".to_string(),
+            Tables::Users,
+            Some(self.id),
+            Some(self.id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(user)
+    }
+
     pub fn add_role(&self, r: Roles, conn: &PgConnection) -> Result<User, DatabaseError> {
         let mut new_roles = self.role.clone();
         if !new_roles.contains(&r) {
@@ -1103,16 +1333,14 @@ impl User {
         PaymentMethod::find_default_for_user(self.id, conn)
     }
 
-    pub fn payment_method(&self, name: PaymentProviders, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
-        let mut payment_methods = PaymentMethod::find_for_user(self.id, Some(name), conn)?;
-        if payment_methods.is_empty() {
-            Err(DatabaseError::new(
-                ErrorCode::NoResults,
-                Some("No payment method found for user".to_string()),
-            ))
-        } else {
-            Ok(payment_methods.remove(0))
-        }
+    /// `organization_id` selects a payment method vaulted with that organization's own processor account
+    pub fn payment_method(
+        &self,
+        name: PaymentProviders,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PaymentMethod, DatabaseError> {
+        PaymentMethod::find_for_user_and_organization(self.id, name, organization_id, conn)
     }
 
     fn update_role(&self, new_roles: Vec<Roles>, conn: &PgConnection) -> Result<User, DatabaseError> {
@@ -1271,6 +1499,8 @@ impl User {
             external_login.delete(current_user.map(|u| u.id), conn)?
         }
 
+        UserSession::revoke_all_for_user(self.id, conn)?;
+
         Ok(result)
     }
 }
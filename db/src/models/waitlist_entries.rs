@@ -0,0 +1,306 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::waitlist_entries;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// Hours an offer holds the freed tickets before they are offered to the next person in line
+pub const WAITLIST_OFFER_EXPIRY_HOURS: i64 = 24;
+/// Minutes between checks for inventory freed without a release event, e.g. expired carts
+pub const WAITLIST_CHECK_INTERVAL_MINUTES: i64 = 15;
+const OFFER_REDEMPTION_CODE_LENGTH: usize = 8;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i64,
+    pub status: WaitlistEntryStatus,
+    pub hold_id: Option<Uuid>,
+    pub offered_at: Option<NaiveDateTime>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub order_id: Option<Uuid>,
+    pub purchased_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl WaitlistEntry {
+    pub fn create(ticket_type_id: Uuid, user_id: Uuid, quantity: u32) -> NewWaitlistEntry {
+        NewWaitlistEntry {
+            ticket_type_id,
+            user_id,
+            quantity: quantity as i64,
+            status: WaitlistEntryStatus::Waiting,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry")
+    }
+
+    /// Finds the entry the user is still waiting on or has an open offer for
+    pub fn find_active(
+        ticket_type_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .filter(waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry")
+            .optional()
+    }
+
+    /// Entries still waiting for an offer, first in line first
+    pub fn find_waiting(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Waiting))
+            .order_by(waitlist_entries::created_at.asc())
+            .then_order_by(waitlist_entries::id.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entries")
+    }
+
+    pub fn hold(&self, conn: &PgConnection) -> Result<Option<Hold>, DatabaseError> {
+        match self.hold_id {
+            Some(hold_id) => Ok(Some(Hold::find(hold_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Takes the user off the waitlist, tickets held for an open offer are released to the next person in line
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting && self.status != WaitlistEntryStatus::Offered {
+            return DatabaseError::business_process_error("Waitlist entry is no longer active");
+        }
+
+        let entry: WaitlistEntry = diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(WaitlistEntryStatus::Cancelled),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")?;
+
+        if let Some(hold) = self.hold(conn)? {
+            hold.remove_available_quantity(current_user_id, conn)?;
+        }
+
+        Ok(entry)
+    }
+
+    /// Expires lapsed offers and offers the ticket type's unreserved inventory to the people waiting, in the order
+    /// they joined. Someone is never skipped for a later, smaller request. Returns the entries that received an offer.
+    pub fn process(ticket_type_id: Uuid, conn: &PgConnection) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        diesel::update(
+            waitlist_entries::table
+                .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+                .filter(waitlist_entries::status.eq(WaitlistEntryStatus::Offered))
+                .filter(waitlist_entries::offer_expires_at.le(dsl::now.nullable())),
+        )
+        .set((
+            waitlist_entries::status.eq(WaitlistEntryStatus::Expired),
+            waitlist_entries::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not expire waitlist offers")?;
+
+        let mut offers = Vec::new();
+        if !TicketType::is_event_available_for_sale(&ticket_type_id, conn)? {
+            return Ok(offers);
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let mut available = ticket_type.valid_available_ticket_count(conn)? as i64;
+        for entry in WaitlistEntry::find_waiting(ticket_type_id, conn)? {
+            if entry.quantity > available {
+                break;
+            }
+            available -= entry.quantity;
+            offers.push(entry.offer(&ticket_type, conn)?);
+        }
+
+        WaitlistEntry::schedule_processing(
+            ticket_type_id,
+            Utc::now().naive_utc() + Duration::minutes(WAITLIST_CHECK_INTERVAL_MINUTES),
+            conn,
+        )?;
+
+        Ok(offers)
+    }
+
+    /// Moves the tickets into a hold only the entry's redemption code can purchase from. Tickets left in the
+    /// hold when it ends are released back to the ticket type by `ReleaseHoldInventory`.
+    fn offer(&self, ticket_type: &TicketType, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        let user = User::find(self.user_id, conn)?;
+        let offer_expires_at = Utc::now().naive_utc() + Duration::hours(WAITLIST_OFFER_EXPIRY_HOURS);
+        let redemption_code = format!("WL{}", random_alpha_string(OFFER_REDEMPTION_CODE_LENGTH)).to_uppercase();
+
+        let mut new_hold = Hold::create_hold(
+            format!("Waitlist {}", redemption_code),
+            ticket_type.event_id,
+            Some(redemption_code),
+            Some(0),
+            Some(offer_expires_at),
+            Some(self.quantity as u32),
+            HoldTypes::Discount,
+            ticket_type.id,
+        );
+        new_hold.email = user.email;
+        new_hold.phone = user.phone;
+        let hold = new_hold.commit(None, conn)?;
+        hold.set_quantity(None, self.quantity as u32, conn)?;
+
+        diesel::update(self)
+            .set((
+                waitlist_entries::status.eq(WaitlistEntryStatus::Offered),
+                waitlist_entries::hold_id.eq(Some(hold.id)),
+                waitlist_entries::offered_at.eq(dsl::now.nullable()),
+                waitlist_entries::offer_expires_at.eq(Some(offer_expires_at)),
+                waitlist_entries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")
+    }
+
+    /// Records the purchase against the offer made through the hold, called once the order is paid. Payment can
+    /// complete after the offer has lapsed so expired offers are converted as well.
+    pub(crate) fn mark_purchased(hold_id: Uuid, order_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(
+            waitlist_entries::table
+                .filter(waitlist_entries::hold_id.eq(hold_id))
+                .filter(
+                    waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Offered, WaitlistEntryStatus::Expired]),
+                ),
+        )
+        .set((
+            waitlist_entries::status.eq(WaitlistEntryStatus::Purchased),
+            waitlist_entries::order_id.eq(Some(order_id)),
+            waitlist_entries::purchased_at.eq(dsl::now.nullable()),
+            waitlist_entries::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")?;
+
+        Ok(())
+    }
+
+    /// Makes sure the waitlist is processed by `run_at` when people are waiting on the ticket type. A pending check
+    /// scheduled later is moved up rather than adding another.
+    pub fn schedule_processing(
+        ticket_type_id: Uuid,
+        run_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if WaitlistEntry::find_waiting(ticket_type_id, conn)?.is_empty() {
+            return Ok(());
+        }
+
+        // A blocked action is the one currently being executed, it completes without checking the waitlist again
+        let now = Utc::now().naive_utc();
+        match DomainAction::find_by_resource(
+            Some(Tables::TicketTypes),
+            Some(ticket_type_id),
+            DomainActionTypes::ProcessWaitlist,
+            DomainActionStatus::Pending,
+            conn,
+        )?
+        .into_iter()
+        .find(|action| action.blocked_until <= now)
+        {
+            Some(action) => {
+                if action.scheduled_at > run_at {
+                    action.set_scheduled_at(run_at, conn)?;
+                }
+            }
+            None => WaitlistEntry::create_processing_domain_action(ticket_type_id, run_at, conn)?,
+        }
+
+        Ok(())
+    }
+
+    fn create_processing_domain_action(
+        ticket_type_id: Uuid,
+        run_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ProcessWaitlist,
+            None,
+            json!({}),
+            Some(Tables::TicketTypes),
+            Some(ticket_type_id),
+        );
+        action.schedule_at(run_at);
+        action.commit(conn)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "waitlist_entries"]
+pub struct NewWaitlistEntry {
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i64,
+    pub status: WaitlistEntryStatus,
+}
+
+impl NewWaitlistEntry {
+    pub fn commit(self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        self.validate_record(conn)?;
+
+        let entry: WaitlistEntry = diesel::insert_into(waitlist_entries::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not insert waitlist entry")?;
+
+        // Sold out ticket types can free up without a release, e.g. when carts expire
+        WaitlistEntry::schedule_processing(
+            entry.ticket_type_id,
+            Utc::now().naive_utc() + Duration::minutes(WAITLIST_CHECK_INTERVAL_MINUTES),
+            conn,
+        )?;
+
+        Ok(entry)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if self.quantity < 1 {
+            return DatabaseError::validation_error("quantity", "Quantity must be at least 1");
+        }
+        if ticket_type.limit_per_person > 0 && self.quantity > ticket_type.limit_per_person as i64 {
+            return DatabaseError::validation_error("quantity", "Quantity exceeds the limit per person");
+        }
+        if !TicketType::is_event_available_for_sale(&ticket_type.id, conn)?
+            || ticket_type.status(false, conn)? != TicketTypeStatus::SoldOut
+        {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Waitlist is only open for sold out ticket types",
+            );
+        }
+        if WaitlistEntry::find_active(self.ticket_type_id, self.user_id, conn)?.is_some() {
+            return DatabaseError::validation_error("ticket_type_id", "Already on the waitlist for this ticket type");
+        }
+
+        Ok(())
+    }
+}
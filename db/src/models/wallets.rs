@@ -1,27 +1,74 @@
 use chrono::NaiveDateTime;
 use diesel;
+use diesel::expression::dsl;
 use diesel::prelude::*;
 use schema::wallets;
 use std::default::Default;
 use tari_client::{convert_bytes_to_hexstring, cryptographic_keypair};
+use utils::encryption::KeyRing;
 use utils::errors;
 use utils::errors::*;
 use uuid::Uuid;
 
+const REENCRYPT_BATCH_SIZE: i64 = 1000;
+
 #[derive(Identifiable, Queryable, Clone)]
 pub struct Wallet {
     pub id: Uuid,
     pub user_id: Option<Uuid>,
     organization_id: Option<Uuid>,
     name: String,
-    pub secret_key: String,
+    secret_key: String,
     pub public_key: String,
     default_flag: bool,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    pub secret_key_version: i32,
 }
 
 impl Wallet {
+    /// Hex encoded secret key used to sign on behalf of the wallet's owner
+    pub fn secret_key(&self, key_ring: &KeyRing) -> Result<String, DatabaseError> {
+        key_ring.decrypt(&self.secret_key, self.secret_key_version)
+    }
+
+    /// Re-encrypts secret keys that are unencrypted or encrypted with an older master key version,
+    /// returning the number of wallets updated
+    pub fn reencrypt_secret_keys(key_ring: &KeyRing, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        let current_version = match key_ring.current_version() {
+            Some(current_version) => current_version,
+            None => return DatabaseError::business_process_error("No wallet encryption keys have been configured"),
+        };
+
+        let mut updated = 0;
+        loop {
+            let wallets: Vec<Wallet> = wallets::table
+                .filter(wallets::secret_key_version.ne(current_version))
+                .order_by(wallets::id)
+                .limit(REENCRYPT_BATCH_SIZE)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not load wallets")?;
+            if wallets.is_empty() {
+                break;
+            }
+
+            for wallet in &wallets {
+                let (secret_key, secret_key_version) = key_ring.encrypt(&wallet.secret_key(key_ring)?)?;
+                diesel::update(wallets::table.filter(wallets::id.eq(wallet.id)))
+                    .set((
+                        wallets::secret_key.eq(secret_key),
+                        wallets::secret_key_version.eq(secret_key_version),
+                        wallets::updated_at.eq(dsl::now),
+                    ))
+                    .execute(conn)
+                    .to_db_error(ErrorCode::UpdateError, "Could not re-encrypt wallet secret key")?;
+            }
+            updated += wallets.len();
+        }
+
+        Ok(updated)
+    }
+
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<Wallet, DatabaseError> {
         wallets::table
             .filter(wallets::id.eq(id))
@@ -33,6 +80,7 @@ impl Wallet {
         user_id: Uuid,
         name: String,
         force: bool,
+        key_ring: &KeyRing,
         conn: &PgConnection,
     ) -> Result<Wallet, DatabaseError> {
         let (secret_key, public_key) = cryptographic_keypair();
@@ -55,12 +103,13 @@ impl Wallet {
             default_flag,
             ..Default::default()
         }
-        .commit(conn)
+        .commit(key_ring, conn)
     }
 
     pub fn create_for_organization(
         organization_id: Uuid,
         name: String,
+        key_ring: &KeyRing,
         conn: &PgConnection,
     ) -> Result<Wallet, DatabaseError> {
         let (secret_key, public_key) = cryptographic_keypair();
@@ -79,7 +128,7 @@ impl Wallet {
             default_flag,
             ..Default::default()
         }
-        .commit(conn)
+        .commit(key_ring, conn)
     }
 
     pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<Wallet>, DatabaseError> {
@@ -108,8 +157,8 @@ impl Wallet {
                 }
             }
         } else {
-            //Create default wallet for user
-            result_wallet = Wallet::create_for_user(user_id, "Default".to_string(), true, conn)?;
+            // Wallets are created with the user as creating one needs the master keys
+            return DatabaseError::no_results("Could not find default wallet for user");
         }
         Ok(result_wallet)
     }
@@ -126,8 +175,8 @@ impl Wallet {
                 }
             }
         } else {
-            //Create default wallet for org
-            result_wallet = Wallet::create_for_organization(organization_id, "Default".to_string(), conn)?;
+            // Wallets are created with the organization as creating one needs the master keys
+            return DatabaseError::no_results("Could not find default wallet for organization");
         }
         Ok(result_wallet)
     }
@@ -142,10 +191,15 @@ pub struct NewWallet {
     secret_key: String,
     public_key: String,
     default_flag: bool,
+    secret_key_version: i32,
 }
 
 impl NewWallet {
-    pub fn commit(self, conn: &PgConnection) -> Result<Wallet, DatabaseError> {
+    /// Stores the wallet with its secret key encrypted by the current master key
+    pub fn commit(mut self, key_ring: &KeyRing, conn: &PgConnection) -> Result<Wallet, DatabaseError> {
+        let (secret_key, secret_key_version) = key_ring.encrypt(&self.secret_key)?;
+        self.secret_key = secret_key;
+        self.secret_key_version = secret_key_version;
         diesel::insert_into(wallets::table)
             .values(self)
             .get_result(conn)
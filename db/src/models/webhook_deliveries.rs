@@ -0,0 +1,207 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::webhook_deliveries;
+use serde_json::Value;
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use utils::pagination::*;
+use uuid::Uuid;
+
+/// Number of attempts made before a delivery is marked as failed
+pub const WEBHOOK_DELIVERY_MAX_ATTEMPTS: i64 = 8;
+/// Longest response body kept in the delivery log
+const MAX_RESPONSE_BODY_LENGTH: usize = 2000;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, QueryableByName, Serialize)]
+#[table_name = "webhook_deliveries"]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Option<Uuid>,
+    pub payload: Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempt_count: i64,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub last_attempted_at: Option<NaiveDateTime>,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl WebhookDelivery {
+    pub fn create(
+        domain_event_publisher_id: Uuid,
+        domain_event_id: Option<Uuid>,
+        payload: Value,
+    ) -> NewWebhookDelivery {
+        NewWebhookDelivery {
+            domain_event_publisher_id,
+            domain_event_id,
+            payload,
+            status: WebhookDeliveryStatus::Pending,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        webhook_deliveries::table
+            .filter(webhook_deliveries::id.eq(id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook delivery")
+    }
+
+    pub fn find_by_domain_event_publisher_id(
+        domain_event_publisher_id: Uuid,
+        status: Option<WebhookDeliveryStatus>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<WebhookDelivery>, DatabaseError> {
+        let mut query = webhook_deliveries::table
+            .filter(webhook_deliveries::domain_event_publisher_id.eq(domain_event_publisher_id))
+            .into_boxed();
+
+        if let Some(status) = status {
+            query = query.filter(webhook_deliveries::status.eq(status));
+        }
+
+        let (deliveries, record_count): (Vec<WebhookDelivery>, i64) = query
+            .order_by(webhook_deliveries::created_at.desc())
+            .then_order_by(webhook_deliveries::id.desc())
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading webhook deliveries")?;
+
+        Ok(Payload::from_data(deliveries, page, limit, Some(record_count as u64)))
+    }
+
+    pub fn domain_event_publisher(&self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        DomainEventPublisher::find(self.domain_event_publisher_id, conn)
+    }
+
+    /// Queues a domain action to send this delivery, honouring any scheduled retry time
+    pub fn queue(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        let mut action = DomainAction::create(
+            self.domain_event_id,
+            DomainActionTypes::SendWebhook,
+            Some(CommunicationChannelType::Webhook),
+            json!({}),
+            Some(Tables::WebhookDeliveries),
+            Some(self.id),
+        );
+        if let Some(next_attempt_at) = self.next_attempt_at {
+            action.schedule_at(next_attempt_at);
+        }
+        action.commit(conn)
+    }
+
+    pub fn mark_delivered(
+        &self,
+        response_status: i32,
+        response_body: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<WebhookDelivery, DatabaseError> {
+        diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(WebhookDeliveryStatus::Delivered),
+                webhook_deliveries::attempt_count.eq(self.attempt_count + 1),
+                webhook_deliveries::response_status.eq(Some(response_status)),
+                webhook_deliveries::response_body.eq(response_body.map(truncate_response_body)),
+                webhook_deliveries::last_attempted_at.eq(dsl::now.nullable()),
+                webhook_deliveries::next_attempt_at.eq(None::<NaiveDateTime>),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")
+    }
+
+    /// Records a failed attempt. Until the maximum number of attempts is reached another
+    /// attempt is scheduled, backing off exponentially from one minute.
+    pub fn mark_failed(
+        &self,
+        response_status: Option<i32>,
+        response_body: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<WebhookDelivery, DatabaseError> {
+        let attempt_count = self.attempt_count + 1;
+        let (status, next_attempt_at) = if attempt_count >= WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+            (WebhookDeliveryStatus::Failed, None)
+        } else {
+            (
+                WebhookDeliveryStatus::Pending,
+                Some(Utc::now().naive_utc() + WebhookDelivery::retry_delay(attempt_count)),
+            )
+        };
+
+        let delivery: WebhookDelivery = diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(status),
+                webhook_deliveries::attempt_count.eq(attempt_count),
+                webhook_deliveries::response_status.eq(response_status),
+                webhook_deliveries::response_body.eq(response_body.map(truncate_response_body)),
+                webhook_deliveries::last_attempted_at.eq(dsl::now.nullable()),
+                webhook_deliveries::next_attempt_at.eq(next_attempt_at),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")?;
+
+        if delivery.status == WebhookDeliveryStatus::Pending {
+            delivery.queue(conn)?;
+        }
+
+        Ok(delivery)
+    }
+
+    /// Sends the delivery again with the same event id so receivers can de-duplicate it
+    pub fn replay(&self, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        if self.status == WebhookDeliveryStatus::Pending {
+            return DatabaseError::business_process_error("Webhook delivery is already pending");
+        }
+
+        let delivery: WebhookDelivery = diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(WebhookDeliveryStatus::Pending),
+                webhook_deliveries::attempt_count.eq(0),
+                webhook_deliveries::next_attempt_at.eq(None::<NaiveDateTime>),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")?;
+        delivery.queue(conn)?;
+
+        Ok(delivery)
+    }
+
+    pub fn retry_delay(attempt_count: i64) -> Duration {
+        Duration::minutes(2i64.pow((attempt_count.max(1) - 1) as u32))
+    }
+}
+
+fn truncate_response_body(body: String) -> String {
+    body.chars().take(MAX_RESPONSE_BODY_LENGTH).collect()
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, PartialEq, Serialize)]
+#[table_name = "webhook_deliveries"]
+pub struct NewWebhookDelivery {
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Option<Uuid>,
+    pub payload: Value,
+    pub status: WebhookDeliveryStatus,
+}
+
+impl NewWebhookDelivery {
+    pub fn commit(self, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        diesel::insert_into(webhook_deliveries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not insert webhook delivery")
+    }
+}
@@ -0,0 +1,29 @@
+-- Gives each seat of the price tier not yet sold for the event one of the ticket type's unseated available tickets
+WITH tier_seats AS (SELECT s.id,
+                           row_number() OVER (ORDER BY sms.rank, sms.name, s.row_rank, s.row_label, s.seat_rank) AS position
+                    FROM seats AS s
+                             INNER JOIN seat_map_sections AS sms ON s.seat_map_section_id = sms.id
+                    WHERE sms.seat_map_id = $2
+                      AND s.price_tier = $3
+                      AND NOT EXISTS(SELECT 1
+                                     FROM ticket_instances AS ti
+                                              INNER JOIN assets AS a ON ti.asset_id = a.id
+                                              INNER JOIN ticket_types AS tt ON a.ticket_type_id = tt.id
+                                     WHERE ti.seat_id = s.id
+                                       AND tt.event_id = (SELECT event_id FROM ticket_types WHERE id = $1))),
+     tickets AS (SELECT t.id,
+                        row_number() OVER (ORDER BY t.id) AS position
+                 FROM ticket_instances AS t
+                          INNER JOIN assets AS a ON t.asset_id = a.id
+                 WHERE a.ticket_type_id = $1
+                   AND t.seat_id IS NULL
+                   AND t.status = 'Available'
+                   AND t.parent_id IS NULL)
+
+UPDATE ticket_instances
+
+SET seat_id    = tier_seats.id,
+    updated_at = now()
+FROM tickets
+         INNER JOIN tier_seats ON tickets.position = tier_seats.position
+WHERE ticket_instances.id = tickets.id RETURNING ticket_instances.*;
@@ -9,6 +9,7 @@ UPDATE ticket_instances
 SET order_item_id  = NULL,
     reserved_until = NULL,
     redeem_key     = NULL,
+    redeem_secret  = NULL,
     status         = $5,
     updated_at     = now()
 FROM cte
@@ -0,0 +1,29 @@
+SELECT
+  es.id                                                                     AS event_series_id,
+  es.name                                                                   AS event_series_name,
+  es.recurrence_rule                                                        AS recurrence_rule,
+  CAST(COUNT(e.id) AS BIGINT)                                               AS occurrence_count,
+  CAST(COUNT(e.id) FILTER (WHERE e.event_start > now()) AS BIGINT)          AS upcoming_occurrence_count,
+  MIN(e.event_start)                                                        AS first_event_start,
+  MAX(e.event_start)                                                        AS last_event_start,
+  CAST(COALESCE(SUM(s.tickets_sold), 0) AS BIGINT)                          AS tickets_sold,
+  CAST(COALESCE(SUM(s.tickets_refunded), 0) AS BIGINT)                      AS tickets_refunded,
+  CAST(COALESCE(SUM(s.sales_in_cents), 0) AS BIGINT)                        AS sales_in_cents
+FROM event_series es
+JOIN events e ON e.event_series_id = es.id AND e.deleted_at IS NULL
+LEFT JOIN (
+  SELECT
+    oi.event_id,
+    SUM(oi.quantity - oi.refunded_quantity)                          AS tickets_sold,
+    SUM(oi.refunded_quantity)                                        AS tickets_refunded,
+    SUM((oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents) AS sales_in_cents
+  FROM order_items oi
+  JOIN orders o ON o.id = oi.order_id
+  WHERE o.status = 'Paid'
+  AND oi.item_type = 'Tickets'
+  GROUP BY oi.event_id
+) s ON s.event_id = e.id
+WHERE ($1 IS NULL OR es.id = (SELECT event_series_id FROM events WHERE id = $1))
+AND ($2 IS NULL OR es.organization_id = $2)
+GROUP BY es.id, es.name, es.recurrence_rule
+ORDER BY es.name;
@@ -3,9 +3,11 @@ SELECT oi.event_id,
        CAST(COALESCE(SUM(oi.company_fee_in_cents), 0) AS BIGINT) AS total_company_fee_in_cents,
        CAST(COALESCE(oi.company_fee_in_cents, 0) AS BIGINT) AS company_fee_in_cents,
        CAST(COALESCE(SUM(oi.client_fee_in_cents), 0) AS BIGINT)  AS total_client_fee_in_cents,
-       CAST(COALESCE(oi.client_fee_in_cents, 0) AS BIGINT)  AS client_fee_in_cents
+       CAST(COALESCE(oi.client_fee_in_cents, 0) AS BIGINT)  AS client_fee_in_cents,
+       CAST(COALESCE(SUM(oi_tax.tax_in_cents), 0) AS BIGINT)     AS total_tax_in_cents
 FROM orders
        LEFT JOIN order_items oi on orders.id = oi.order_id
+       LEFT JOIN order_items oi_tax on (oi_tax.item_type = 'Tax' AND oi.id = oi_tax.parent_id)
        LEFT JOIN events e on oi.event_id = e.id
 WHERE orders.status = 'Paid'
   AND ($1 is null or oi.event_id = $1)
@@ -12,6 +12,7 @@ SELECT event_id,
        total_client_fee_in_cents,
        pricing_name,
        ticket_name,
+       total_tax_in_cents,
        CAST(total_net_income + total_company_fee_in_cents +
             total_client_fee_in_cents AS BIGINT) AS total_gross_income_in_cents
 FROM (
@@ -44,6 +45,10 @@ FROM (
                     ((COALESCE(oi_promo_code.quantity, 0) - COALESCE(oi_promo_code.refunded_quantity, 0)) *
                      COALESCE(oi_promo_code.unit_price_in_cents, 0))),
                               0) AS BIGINT)            AS total_net_income,
+                -- tax collected on the tickets and their per unit fees
+                CAST(COALESCE(SUM((oi_tax.quantity - oi_tax.refunded_quantity) * oi_tax.tax_in_cents), 0) +
+                     COALESCE(SUM((oi_fees_tax.quantity - oi_fees_tax.refunded_quantity) * oi_fees_tax.tax_in_cents),
+                              0) AS BIGINT)            AS total_tax_in_cents,
                 tp.name                                AS pricing_name,
                 CASE WHEN tt.status = 'Cancelled' THEN concat(tt.name, ' (Cancelled)') ELSE tt.name END AS ticket_name
          FROM orders
@@ -51,6 +56,9 @@ FROM (
                   LEFT JOIN order_items oi_fees ON (oi_fees.item_type = 'PerUnitFees' AND oi.id = oi_fees.parent_id)
                   LEFT JOIN order_items oi_promo_code
                             ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
+                  LEFT JOIN order_items oi_tax ON (oi_tax.item_type = 'Tax' AND oi.id = oi_tax.parent_id)
+                  LEFT JOIN order_items oi_fees_tax
+                            ON (oi_fees_tax.item_type = 'Tax' AND oi_fees.id = oi_fees_tax.parent_id)
                   LEFT JOIN codes c ON oi.code_id = c.id
                   LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
                   LEFT JOIN ticket_pricing tp ON (oi.ticket_pricing_id = tp.id)
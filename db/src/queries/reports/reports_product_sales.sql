@@ -0,0 +1,21 @@
+SELECT
+  p.id                                                                                  AS product_id,
+  p.name                                                                                AS product_name,
+  pv.name                                                                               AS product_variant_name,
+  e.id                                                                                  AS event_id,
+  e.name                                                                                AS event_name,
+  CAST(SUM(oi.quantity) AS BIGINT)                                                      AS quantity_sold,
+  CAST(SUM(oi.refunded_quantity) AS BIGINT)                                             AS quantity_refunded,
+  CAST(SUM(oi.redeemed_quantity) AS BIGINT)                                             AS quantity_redeemed,
+  CAST(SUM((oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents) AS BIGINT)    AS sales_in_cents
+FROM order_items oi
+JOIN orders o ON o.id = oi.order_id
+JOIN products p ON p.id = oi.product_id
+JOIN events e ON e.id = oi.event_id
+LEFT JOIN product_variants pv ON pv.id = oi.product_variant_id
+WHERE o.status = 'Paid'
+AND oi.item_type = 'Products'
+AND ($1 IS NULL OR e.id = $1)
+AND ($2 IS NULL OR p.organization_id = $2)
+GROUP BY p.id, p.name, pv.id, pv.name, e.id, e.name, e.event_start
+ORDER BY e.event_start, e.name, p.name, pv.name;
@@ -50,7 +50,14 @@ SELECT COUNT(*) OVER ()
     o.platform,
     ti_agg.check_in_source,
     g.headline_artist_alt_genres,
-    g.headline_artist_main_genre
+    g.headline_artist_main_genre,
+    COALESCE(o.currency, e.currency)                                                                   AS currency,
+    -- tax collected on the ticket and its per unit fees
+    CAST(COALESCE(oi_tax.tax_in_cents, 0) + COALESCE(oi_fees_tax.tax_in_cents, 0) AS BIGINT)           AS tax_in_cents,
+    CAST(COALESCE(oi_tax.tax_in_cents, 0) *
+           (COALESCE(oi_tax.quantity, 0) - COALESCE(oi_tax.refunded_quantity, 0))
+    + COALESCE(oi_fees_tax.tax_in_cents, 0) *
+           (COALESCE(oi_fees_tax.quantity, 0) - COALESCE(oi_fees_tax.refunded_quantity, 0)) AS BIGINT) AS tax_in_cents_total
 FROM orders o
     LEFT JOIN order_items oi ON (o.id = oi.order_id AND oi.item_type = 'Tickets')
     LEFT JOIN order_items oi_fees ON (oi_fees.item_type = 'PerUnitFees' AND oi.id = oi_fees.parent_id)
@@ -58,6 +65,8 @@ FROM orders o
         ON (oi_event_fees.item_type = 'EventFees' AND o.id = oi_event_fees.order_id)
     LEFT JOIN order_items oi_promo_code
         ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
+    LEFT JOIN order_items oi_tax ON (oi_tax.item_type = 'Tax' AND oi.id = oi_tax.parent_id)
+    LEFT JOIN order_items oi_fees_tax ON (oi_fees_tax.item_type = 'Tax' AND oi_fees.id = oi_fees_tax.parent_id)
     LEFT JOIN codes c ON oi.code_id = c.id
     LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
     LEFT JOIN (SELECT order_id,
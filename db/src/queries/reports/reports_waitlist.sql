@@ -0,0 +1,35 @@
+SELECT
+  e.id                                                                                          AS event_id,
+  e.name                                                                                        AS event_name,
+  tt.id                                                                                         AS ticket_type_id,
+  tt.name                                                                                       AS ticket_type_name,
+  CAST(COUNT(w.id) AS BIGINT)                                                                   AS joined_count,
+  CAST(COUNT(w.id) FILTER (WHERE w.status = 'Waiting') AS BIGINT)                               AS waiting_count,
+  CAST(COUNT(w.id) FILTER (WHERE w.offered_at IS NOT NULL) AS BIGINT)                           AS offered_count,
+  CAST(COUNT(w.id) FILTER (WHERE w.status = 'Purchased') AS BIGINT)                             AS purchased_count,
+  CAST(COUNT(w.id) FILTER (WHERE w.status = 'Expired') AS BIGINT)                               AS expired_count,
+  CAST(COUNT(w.id) FILTER (WHERE w.status = 'Cancelled') AS BIGINT)                             AS cancelled_count,
+  CAST(COALESCE(SUM(s.quantity), 0) AS BIGINT)                                                  AS tickets_sold,
+  CAST(COALESCE(SUM(s.sales_in_cents), 0) AS BIGINT)                                            AS sales_in_cents,
+  -- Percentage of offers that were purchased
+  CAST(COALESCE(ROUND(COUNT(w.id) FILTER (WHERE w.status = 'Purchased') * 100.0
+    / NULLIF(COUNT(w.id) FILTER (WHERE w.offered_at IS NOT NULL), 0), 2), 0) AS DOUBLE PRECISION) AS conversion_rate
+FROM waitlist_entries w
+JOIN ticket_types tt ON tt.id = w.ticket_type_id
+JOIN events e ON e.id = tt.event_id
+LEFT JOIN (
+  SELECT
+    oi.hold_id,
+    SUM(oi.quantity - oi.refunded_quantity)                          AS quantity,
+    SUM((oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents) AS sales_in_cents
+  FROM order_items oi
+  JOIN orders o ON o.id = oi.order_id
+  WHERE o.status = 'Paid'
+  AND oi.item_type = 'Tickets'
+  AND oi.hold_id IS NOT NULL
+  GROUP BY oi.hold_id
+) s ON s.hold_id = w.hold_id AND w.status = 'Purchased'
+WHERE ($1 IS NULL OR e.id = $1)
+AND ($2 IS NULL OR e.organization_id = $2)
+GROUP BY e.id, e.name, e.event_start, tt.id, tt.name, tt.rank
+ORDER BY e.event_start, e.name, tt.rank;
@@ -0,0 +1,56 @@
+-- Stripe funds held on the platform balance for a settlement, less the platform's fees.
+-- Destination charges are excluded as those funds were moved to the connected account when charged,
+-- their refunds reverse the transfer. Chargebacks are paid from the platform balance for both kinds of
+-- charges so settlement adjustments are always applied.
+WITH platform_orders AS (
+    SELECT DISTINCT p.order_id
+    FROM payments p
+    WHERE p.provider = 'Stripe'
+      AND p.status = 'Completed'
+      AND p.refund_id IS NULL
+      AND (p.raw_data IS NULL OR json_typeof(p.raw_data -> 'transfer_data') IS DISTINCT FROM 'object')
+)
+SELECT CAST(COALESCE(SUM(amount_in_cents), 0) AS BIGINT) AS amount_in_cents
+FROM (
+         -- Collected for orders settled in this settlement
+         SELECT p.amount AS amount_in_cents
+         FROM payments p
+                  INNER JOIN orders o ON o.id = p.order_id
+                  INNER JOIN platform_orders po ON po.order_id = o.id
+         WHERE o.settlement_id = $1
+           AND p.provider = 'Stripe'
+           AND p.status = 'Completed'
+           AND p.refund_id IS NULL
+         UNION ALL
+         -- Platform fees on those orders
+         SELECT -oi.company_fee_in_cents * oi.quantity
+         FROM order_items oi
+                  INNER JOIN orders o ON o.id = oi.order_id
+                  INNER JOIN platform_orders po ON po.order_id = o.id
+         WHERE o.settlement_id = $1
+         UNION ALL
+         -- Refunded in this settlement, recorded as negative payments
+         SELECT p.amount
+         FROM payments p
+                  INNER JOIN refunds r ON r.id = p.refund_id
+                  INNER JOIN platform_orders po ON po.order_id = p.order_id
+         WHERE r.settlement_id = $1
+           AND p.provider = 'Stripe'
+           AND p.status = 'Refunded'
+         UNION ALL
+         -- Platform fees returned with those refunds
+         SELECT oi.company_fee_in_cents * ri.quantity
+         FROM refund_items ri
+                  INNER JOIN refunds r ON r.id = ri.refund_id
+                  INNER JOIN order_items oi ON oi.id = ri.order_item_id
+                  INNER JOIN platform_orders po ON po.order_id = oi.order_id
+         WHERE r.settlement_id = $1
+         UNION ALL
+         -- Chargebacks and manual adjustments
+         SELECT CASE
+                    WHEN sa.settlement_adjustment_type = 'ManualCredit' THEN sa.amount_in_cents
+                    ELSE -sa.amount_in_cents
+                    END
+         FROM settlement_adjustments sa
+         WHERE sa.settlement_id = $1
+     ) collected;
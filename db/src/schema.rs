@@ -1,3 +1,15 @@
+table! {
+    access_zones (id) {
+        id -> Uuid,
+        venue_id -> Nullable<Uuid>,
+        event_id -> Nullable<Uuid>,
+        name -> Text,
+        description -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     analytics_page_views (id) {
         id -> Uuid,
@@ -111,6 +123,31 @@ table! {
     }
 }
 
+table! {
+    bundle_ticket_types (id) {
+        id -> Uuid,
+        bundle_id -> Uuid,
+        ticket_type_id -> Uuid,
+        quantity -> Int8,
+        allocated_price_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    bundles (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        price_in_cents -> Int8,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     codes (id) {
         id -> Uuid,
@@ -197,6 +234,8 @@ table! {
         adapter -> Nullable<Varchar>,
         adapter_config -> Nullable<Jsonb>,
         blocked_until -> Timestamp,
+        webhook_secret -> Text,
+        paused_at -> Nullable<Timestamp>,
     }
 }
 
@@ -261,6 +300,18 @@ table! {
     }
 }
 
+table! {
+    event_series (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        template_event_id -> Uuid,
+        name -> Text,
+        recurrence_rule -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     event_users (id) {
         id -> Uuid,
@@ -309,6 +360,9 @@ table! {
         facebook_event_id -> Nullable<Text>,
         settled_at -> Nullable<Timestamp>,
         cloned_from_event_id -> Nullable<Uuid>,
+        currency -> Text,
+        rotating_redeem_codes -> Bool,
+        event_series_id -> Nullable<Uuid>,
     }
 }
 
@@ -347,6 +401,8 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         organization_id -> Uuid,
+
+        currency -> Text,
     }
 }
 
@@ -454,6 +510,12 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        tax_rate_id -> Nullable<Uuid>,
+        tax_in_cents -> Int8,
+        bundle_id -> Nullable<Uuid>,
+        product_id -> Nullable<Uuid>,
+        product_variant_id -> Nullable<Uuid>,
+        redeemed_quantity -> Int8,
     }
 }
 
@@ -495,6 +557,26 @@ table! {
         platform -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         referrer -> Nullable<Text>,
+        disputed_at -> Nullable<Timestamp>,
+        currency -> Nullable<Text>,
+    }
+}
+
+table! {
+    organization_api_keys (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        created_by_user_id -> Uuid,
+        name -> Text,
+        key_prefix -> Text,
+        key_hash -> Text,
+        scopes -> Array<Text>,
+        event_ids -> Array<Uuid>,
+        expires_at -> Nullable<Timestamp>,
+        last_used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -579,6 +661,12 @@ table! {
         slug_id -> Nullable<Uuid>,
         google_ads_conversion_id -> Nullable<Text>,
         google_ads_conversion_labels -> Array<Text>,
+        braintree_public_key -> Nullable<Text>,
+        braintree_private_key -> Nullable<Text>,
+        braintree_merchant_account_id -> Nullable<Text>,
+        stripe_connect_account_id -> Nullable<Text>,
+        currency -> Text,
+        require_mfa_for_financial_scopes -> Bool,
     }
 }
 
@@ -592,6 +680,7 @@ table! {
         provider_data -> Json,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        organization_id -> Nullable<Uuid>,
     }
 }
 
@@ -613,6 +702,33 @@ table! {
     }
 }
 
+table! {
+    product_variants (id) {
+        id -> Uuid,
+        product_id -> Uuid,
+        name -> Text,
+        quantity -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    products (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        name -> Text,
+        description -> Nullable<Text>,
+        price_in_cents -> Int8,
+        quantity -> Int8,
+        requires_pickup -> Bool,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     push_notification_tokens (id) {
         id -> Uuid,
@@ -682,6 +798,62 @@ table! {
     }
 }
 
+table! {
+    report_exports (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        report -> Text,
+        format -> Text,
+        parameters -> Jsonb,
+        status -> Text,
+        content -> Nullable<Bytea>,
+        row_count -> Nullable<Int8>,
+        download_token_hash -> Nullable<Text>,
+        error_message -> Nullable<Text>,
+        completed_at -> Nullable<Timestamp>,
+        expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seat_map_sections (id) {
+        id -> Uuid,
+        seat_map_id -> Uuid,
+        name -> Text,
+        rank -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seat_maps (id) {
+        id -> Uuid,
+        venue_id -> Uuid,
+        stage_id -> Nullable<Uuid>,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    seats (id) {
+        id -> Uuid,
+        seat_map_section_id -> Uuid,
+        row_label -> Text,
+        row_rank -> Int4,
+        seat_number -> Text,
+        seat_rank -> Int4,
+        price_tier -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     settlement_adjustments (id) {
         id -> Uuid,
@@ -708,6 +880,8 @@ table! {
         settlement_entry_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        tax_in_cents -> Int8,
+        product_id -> Nullable<Uuid>,
     }
 }
 
@@ -722,6 +896,9 @@ table! {
         only_finished_events -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        stripe_transfer_id -> Nullable<Text>,
+        currency -> Text,
+        stripe_transfer_error -> Nullable<Text>,
     }
 }
 
@@ -761,6 +938,20 @@ table! {
     }
 }
 
+table! {
+    tax_rates (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        venue_id -> Nullable<Uuid>,
+        name -> Text,
+        rate_percent -> Float4,
+        inclusive -> Bool,
+        applies_to_fees -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     temporary_user_links (temporary_user_id, user_id) {
         temporary_user_id -> Uuid,
@@ -800,6 +991,8 @@ table! {
         check_in_source -> Nullable<Text>,
         parent_id -> Nullable<Uuid>,
         listing_id -> Nullable<Uuid>,
+        redeem_secret -> Nullable<Text>,
+        seat_id -> Nullable<Uuid>,
     }
 }
 
@@ -819,6 +1012,30 @@ table! {
     }
 }
 
+table! {
+    ticket_scans (id) {
+        id -> Uuid,
+        ticket_instance_id -> Uuid,
+        event_id -> Uuid,
+        scan_type -> Text,
+        scanned_by_user_id -> Nullable<Uuid>,
+        check_in_source -> Nullable<Text>,
+        scanned_at -> Timestamp,
+        created_at -> Timestamp,
+        access_zone_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    ticket_type_access_zones (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        access_zone_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     ticket_type_codes (id) {
         id -> Uuid,
@@ -829,6 +1046,17 @@ table! {
     }
 }
 
+table! {
+    ticket_type_seat_tiers (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        seat_map_id -> Uuid,
+        price_tier -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     ticket_types (id) {
         id -> Uuid,
@@ -857,6 +1085,9 @@ table! {
         ticket_type_type -> Varchar,
         promo_image_url -> Nullable<Text>,
         content_url -> Nullable<Text>,
+        reentry_policy -> Text,
+        reentry_limit -> Nullable<Int4>,
+        cloned_from_ticket_type_id -> Nullable<Uuid>,
     }
 }
 
@@ -897,6 +1128,41 @@ table! {
     }
 }
 
+table! {
+    user_mfa_challenges (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Text,
+        failed_attempts -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    user_mfa_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        hashed_code -> Text,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    user_sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        current_token_id -> Uuid,
+        user_agent -> Nullable<Text>,
+        last_refreshed_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     users (id) {
         id -> Uuid,
@@ -920,6 +1186,10 @@ table! {
         accepted_terms_date -> Nullable<Timestamp>,
         invited_at -> Nullable<Timestamp>,
         deleted_at -> Nullable<Timestamp>,
+        mfa_secret -> Nullable<Text>,
+        mfa_enabled_at -> Nullable<Timestamp>,
+        mfa_secret_version -> Int4,
+        mfa_last_used_step -> Int8,
     }
 }
 
@@ -946,6 +1216,23 @@ table! {
     }
 }
 
+table! {
+    waitlist_entries (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        user_id -> Uuid,
+        quantity -> Int8,
+        status -> Text,
+        hold_id -> Nullable<Uuid>,
+        offered_at -> Nullable<Timestamp>,
+        offer_expires_at -> Nullable<Timestamp>,
+        order_id -> Nullable<Uuid>,
+        purchased_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     wallets (id) {
         id -> Uuid,
@@ -957,9 +1244,29 @@ table! {
         default_flag -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        secret_key_version -> Int4,
+    }
+}
+
+table! {
+    webhook_deliveries (id) {
+        id -> Uuid,
+        domain_event_publisher_id -> Uuid,
+        domain_event_id -> Nullable<Uuid>,
+        payload -> Jsonb,
+        status -> Text,
+        attempt_count -> Int8,
+        response_status -> Nullable<Int4>,
+        response_body -> Nullable<Text>,
+        last_attempted_at -> Nullable<Timestamp>,
+        next_attempt_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
+joinable!(access_zones -> events (event_id));
+joinable!(access_zones -> venues (venue_id));
 joinable!(announcement_engagements -> announcements (announcement_id));
 joinable!(announcement_engagements -> users (user_id));
 joinable!(announcements -> organizations (organization_id));
@@ -969,6 +1276,9 @@ joinable!(artists -> genres (main_genre_id));
 joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
 joinable!(broadcasts -> events (event_id));
+joinable!(bundle_ticket_types -> bundles (bundle_id));
+joinable!(bundle_ticket_types -> ticket_types (ticket_type_id));
+joinable!(bundles -> organizations (organization_id));
 joinable!(codes -> events (event_id));
 joinable!(collection_items -> collections (collection_id));
 joinable!(collection_items -> ticket_types (collectible_id));
@@ -988,8 +1298,10 @@ joinable!(event_genres -> genres (genre_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
 joinable!(event_report_subscribers -> events (event_id));
+joinable!(event_series -> organizations (organization_id));
 joinable!(event_users -> events (event_id));
 joinable!(event_users -> users (user_id));
+joinable!(events -> event_series (event_series_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
@@ -999,16 +1311,22 @@ joinable!(holds -> ticket_types (ticket_type_id));
 joinable!(listings -> users (user_id));
 joinable!(loot_box_contents -> events (content_event_id));
 joinable!(marketplace_accounts -> users (user_id));
+joinable!(order_items -> bundles (bundle_id));
 joinable!(order_items -> codes (code_id));
 joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> product_variants (product_variant_id));
+joinable!(order_items -> products (product_id));
+joinable!(order_items -> tax_rates (tax_rate_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
 joinable!(order_transfers -> orders (order_id));
 joinable!(order_transfers -> transfers (transfer_id));
 joinable!(orders -> settlements (settlement_id));
+joinable!(organization_api_keys -> organizations (organization_id));
+joinable!(organization_api_keys -> users (created_by_user_id));
 joinable!(organization_interactions -> organizations (organization_id));
 joinable!(organization_interactions -> users (user_id));
 joinable!(organization_invites -> organizations (organization_id));
@@ -1017,10 +1335,14 @@ joinable!(organization_users -> users (user_id));
 joinable!(organization_venues -> organizations (organization_id));
 joinable!(organization_venues -> venues (venue_id));
 joinable!(organizations -> fee_schedules (fee_schedule_id));
+joinable!(payment_methods -> organizations (organization_id));
 joinable!(payment_methods -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> refunds (refund_id));
 joinable!(payments -> users (created_by));
+joinable!(product_variants -> products (product_id));
+joinable!(products -> events (event_id));
+joinable!(products -> organizations (organization_id));
 joinable!(push_notification_tokens -> users (user_id));
 joinable!(rarities -> events (event_id));
 joinable!(refund_items -> order_items (order_item_id));
@@ -1030,32 +1352,60 @@ joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
 joinable!(refunds -> orders (order_id));
 joinable!(refunds -> settlements (settlement_id));
 joinable!(refunds -> users (user_id));
+joinable!(report_exports -> organizations (organization_id));
+joinable!(report_exports -> users (user_id));
+joinable!(seat_map_sections -> seat_maps (seat_map_id));
+joinable!(seat_maps -> stages (stage_id));
+joinable!(seat_maps -> venues (venue_id));
+joinable!(seats -> seat_map_sections (seat_map_section_id));
 joinable!(settlement_adjustments -> settlements (settlement_id));
 joinable!(settlement_entries -> events (event_id));
+joinable!(settlement_entries -> products (product_id));
 joinable!(settlement_entries -> settlements (settlement_id));
 joinable!(settlement_entries -> ticket_types (ticket_type_id));
 joinable!(settlements -> organizations (organization_id));
+joinable!(tax_rates -> organizations (organization_id));
+joinable!(tax_rates -> venues (venue_id));
 joinable!(temporary_user_links -> temporary_users (temporary_user_id));
 joinable!(temporary_user_links -> users (user_id));
 joinable!(ticket_instances -> assets (asset_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> listings (listing_id));
 joinable!(ticket_instances -> order_items (order_item_id));
+joinable!(ticket_instances -> seats (seat_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
+joinable!(ticket_scans -> access_zones (access_zone_id));
+joinable!(ticket_scans -> events (event_id));
+joinable!(ticket_scans -> ticket_instances (ticket_instance_id));
+joinable!(ticket_scans -> users (scanned_by_user_id));
+joinable!(ticket_type_access_zones -> access_zones (access_zone_id));
+joinable!(ticket_type_access_zones -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
 joinable!(ticket_type_codes -> ticket_types (ticket_type_id));
+joinable!(ticket_type_seat_tiers -> seat_maps (seat_map_id));
+joinable!(ticket_type_seat_tiers -> ticket_types (ticket_type_id));
 joinable!(ticket_types -> events (event_id));
 joinable!(ticket_types -> rarities (rarity_id));
 joinable!(transfer_tickets -> ticket_instances (ticket_instance_id));
 joinable!(transfer_tickets -> transfers (transfer_id));
 joinable!(user_genres -> genres (genre_id));
 joinable!(user_genres -> users (user_id));
+joinable!(user_mfa_challenges -> users (user_id));
+joinable!(user_mfa_recovery_codes -> users (user_id));
+joinable!(user_sessions -> users (user_id));
 joinable!(venues -> regions (region_id));
+joinable!(waitlist_entries -> holds (hold_id));
+joinable!(waitlist_entries -> orders (order_id));
+joinable!(waitlist_entries -> ticket_types (ticket_type_id));
+joinable!(waitlist_entries -> users (user_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
+joinable!(webhook_deliveries -> domain_event_publishers (domain_event_publisher_id));
+joinable!(webhook_deliveries -> domain_events (domain_event_id));
 
 allow_tables_to_appear_in_same_query!(
+    access_zones,
     analytics_page_views,
     announcement_engagements,
     announcements,
@@ -1063,6 +1413,8 @@ allow_tables_to_appear_in_same_query!(
     artists,
     assets,
     broadcasts,
+    bundle_ticket_types,
+    bundles,
     codes,
     collection_items,
     collections,
@@ -1074,6 +1426,7 @@ allow_tables_to_appear_in_same_query!(
     event_genres,
     event_interest,
     event_report_subscribers,
+    event_series,
     event_users,
     events,
     external_logins,
@@ -1088,6 +1441,7 @@ allow_tables_to_appear_in_same_query!(
     order_items,
     order_transfers,
     orders,
+    organization_api_keys,
     organization_interactions,
     organization_invites,
     organization_users,
@@ -1095,28 +1449,43 @@ allow_tables_to_appear_in_same_query!(
     organizations,
     payment_methods,
     payments,
+    product_variants,
+    products,
     push_notification_tokens,
     rarities,
     refund_items,
     refunded_tickets,
     refunds,
     regions,
+    report_exports,
+    seat_map_sections,
+    seat_maps,
+    seats,
     settlement_adjustments,
     settlement_entries,
     settlements,
     slugs,
     source_aliases,
     stages,
+    tax_rates,
     temporary_user_links,
     temporary_users,
     ticket_instances,
     ticket_pricing,
+    ticket_scans,
+    ticket_type_access_zones,
     ticket_type_codes,
+    ticket_type_seat_tiers,
     ticket_types,
     transfer_tickets,
     transfers,
     user_genres,
+    user_mfa_challenges,
+    user_mfa_recovery_codes,
+    user_sessions,
     users,
     venues,
+    waitlist_entries,
     wallets,
+    webhook_deliveries,
 );
@@ -0,0 +1,66 @@
+use diesel::prelude::*;
+use models::*;
+use test::builders::*;
+use uuid::Uuid;
+
+pub struct AccessZoneBuilder<'a> {
+    event_id: Option<Uuid>,
+    venue_id: Option<Uuid>,
+    name: String,
+    ticket_type_ids: Vec<Uuid>,
+    connection: &'a PgConnection,
+}
+
+impl<'a> AccessZoneBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> AccessZoneBuilder {
+        AccessZoneBuilder {
+            event_id: None,
+            venue_id: None,
+            name: "VIP Area".to_string(),
+            ticket_type_ids: Vec::new(),
+            connection,
+        }
+    }
+
+    pub fn with_event(mut self, event: &Event) -> Self {
+        self.event_id = Some(event.id);
+        self
+    }
+
+    pub fn with_venue(mut self, venue: &Venue) -> Self {
+        self.venue_id = Some(venue.id);
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_ticket_type(mut self, ticket_type: &TicketType) -> Self {
+        self.ticket_type_ids.push(ticket_type.id);
+        self
+    }
+
+    pub fn finish(&mut self) -> AccessZone {
+        let access_zone = match self.venue_id {
+            Some(venue_id) => AccessZone::create_for_venue(venue_id, self.name.clone(), None),
+            None => {
+                let event_id = self
+                    .event_id
+                    .or_else(|| Some(EventBuilder::new(self.connection).finish().id))
+                    .unwrap();
+                AccessZone::create_for_event(event_id, self.name.clone(), None)
+            }
+        }
+        .commit(self.connection)
+        .unwrap();
+
+        for ticket_type_id in &self.ticket_type_ids {
+            TicketTypeAccessZone::create(*ticket_type_id, access_zone.id)
+                .commit(self.connection)
+                .unwrap();
+        }
+        access_zone
+    }
+}
@@ -29,6 +29,8 @@ pub struct EventBuilder<'a> {
     private_access_code: Option<String>,
     event_type: Option<EventTypes>,
     additional_info: Option<String>,
+    currency: Option<String>,
+    rotating_redeem_codes: bool,
 }
 
 impl<'a> EventBuilder<'a> {
@@ -53,6 +55,8 @@ impl<'a> EventBuilder<'a> {
             sales_end: None,
             event_type: None,
             additional_info: None,
+            currency: None,
+            rotating_redeem_codes: false,
         }
     }
 
@@ -76,6 +80,16 @@ impl<'a> EventBuilder<'a> {
         self
     }
 
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    pub fn with_rotating_redeem_codes(mut self) -> Self {
+        self.rotating_redeem_codes = true;
+        self
+    }
+
     pub fn with_name(mut self, name: String) -> Self {
         self.name = name;
         self
@@ -165,7 +179,7 @@ impl<'a> EventBuilder<'a> {
             .event_end
             .unwrap_or(event_start.into_builder().add_days(2).finish());
 
-        let event = Event::create(
+        let mut event = Event::create(
             &self.name,
             self.status,
             organization_id,
@@ -175,9 +189,10 @@ impl<'a> EventBuilder<'a> {
                 .or_else(|| Some(event_start.into_builder().add_hours(-1).finish())),
             self.publish_date,
             Some(event_end),
-        )
-        .commit(None, self.connection)
-        .unwrap();
+        );
+        event.currency = self.currency.clone();
+        event.rotating_redeem_codes = self.rotating_redeem_codes;
+        let event = event.commit(None, self.connection).unwrap();
 
         let mut attributes = EventEditableAttributes {
             promo_image_url: Some(Some("http://localhost".to_string())),
@@ -1,3 +1,4 @@
+pub use self::access_zone_builder::*;
 pub use self::announcement_builder::*;
 pub use self::announcement_engagement_builder::*;
 pub use self::artist_builder::*;
@@ -26,10 +27,12 @@ pub use self::settlement_builder::*;
 pub use self::settlement_entry_builder::*;
 pub use self::slug_builder::*;
 pub use self::stage_builder::*;
+pub use self::tax_rate_builder::*;
 pub use self::ticket_type_builder::*;
 pub use self::user_builder::*;
 pub use self::venue_builder::*;
 
+mod access_zone_builder;
 mod announcement_builder;
 mod announcement_engagement_builder;
 mod artist_builder;
@@ -58,6 +61,7 @@ mod settlement_builder;
 mod settlement_entry_builder;
 mod slug_builder;
 mod stage_builder;
+mod tax_rate_builder;
 mod ticket_type_builder;
 mod user_builder;
 mod venue_builder;
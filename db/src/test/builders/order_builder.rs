@@ -110,6 +110,7 @@ impl<'a> OrderBuilder<'a> {
                 ticket_type_id: self.ticket_type_id.unwrap(),
                 quantity: self.quantity,
                 redemption_code: self.redemption_code,
+                seat_ids: None,
             }],
             self.on_behalf_of_user.is_some(),
             self.is_box_office,
@@ -3,6 +3,7 @@ use models::*;
 use rand::prelude::*;
 use std::collections::HashMap;
 use test::builders::fee_schedule_builder::FeeScheduleBuilder;
+use test::TestProject;
 use uuid::Uuid;
 
 pub struct OrganizationBuilder<'a> {
@@ -18,6 +19,8 @@ pub struct OrganizationBuilder<'a> {
     additional_fee: i64,
     timezone: Option<String>,
     settlement_type: Option<SettlementTypes>,
+    currency: Option<String>,
+    require_mfa_for_financial_scopes: bool,
 }
 
 impl<'a> OrganizationBuilder<'a> {
@@ -36,6 +39,8 @@ impl<'a> OrganizationBuilder<'a> {
             additional_fee: 0,
             timezone: None,
             settlement_type: None,
+            currency: None,
+            require_mfa_for_financial_scopes: false,
         }
     }
 
@@ -59,6 +64,16 @@ impl<'a> OrganizationBuilder<'a> {
         self
     }
 
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    pub fn requiring_mfa_for_financial_scopes(mut self) -> Self {
+        self.require_mfa_for_financial_scopes = true;
+        self
+    }
+
     pub fn with_name(mut self, name: String) -> Self {
         self.name = name;
         self
@@ -107,6 +122,7 @@ impl<'a> OrganizationBuilder<'a> {
 
         let mut organization = Organization::create(&self.name, self.fee_schedule.unwrap().id);
         organization.settlement_type = self.settlement_type;
+        organization.currency = self.currency;
         let mut organization = organization
             .commit(None, "encryption_key", None, self.connection)
             .unwrap();
@@ -117,6 +133,7 @@ impl<'a> OrganizationBuilder<'a> {
             cc_fee_percent: self.cc_fee_percent,
             max_additional_fee_in_cents: Some(self.additional_fee),
             timezone: self.timezone,
+            require_mfa_for_financial_scopes: Some(self.require_mfa_for_financial_scopes),
             ..Default::default()
         };
 
@@ -130,7 +147,13 @@ impl<'a> OrganizationBuilder<'a> {
                 .unwrap();
         }
 
-        Wallet::create_for_organization(organization.id, String::from("Default wallet"), self.connection).unwrap();
+        Wallet::create_for_organization(
+            organization.id,
+            String::from("Default wallet"),
+            &TestProject::wallet_encryption_keys(),
+            self.connection,
+        )
+        .unwrap();
 
         if self.use_address {
             let mut attrs: OrganizationEditableAttributes = Default::default();
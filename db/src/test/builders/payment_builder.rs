@@ -48,6 +48,7 @@ impl<'a> PaymentBuilder<'a> {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -6,6 +6,7 @@ use uuid::Uuid;
 pub struct PaymentMethodBuilder<'a> {
     name: PaymentProviders,
     user_id: Option<Uuid>,
+    organization_id: Option<Uuid>,
     is_default: bool,
     connection: &'a PgConnection,
 }
@@ -15,6 +16,7 @@ impl<'a> PaymentMethodBuilder<'a> {
         PaymentMethodBuilder {
             name: PaymentProviders::Stripe,
             user_id: None,
+            organization_id: None,
             is_default: false,
             connection,
         }
@@ -25,6 +27,11 @@ impl<'a> PaymentMethodBuilder<'a> {
         self
     }
 
+    pub fn with_organization(mut self, organization: &Organization) -> PaymentMethodBuilder<'a> {
+        self.organization_id = Some(organization.id);
+        self
+    }
+
     pub fn make_default(mut self) -> Self {
         self.is_default = true;
         self
@@ -43,8 +50,15 @@ impl<'a> PaymentMethodBuilder<'a> {
 
         let user_id = self.user_id.unwrap();
 
-        PaymentMethod::create(user_id, self.name, self.is_default, "cus_example".into(), "abc".into())
-            .commit(user_id, self.connection)
-            .unwrap()
+        PaymentMethod::create(
+            user_id,
+            self.name,
+            self.is_default,
+            "cus_example".into(),
+            "abc".into(),
+            self.organization_id,
+        )
+        .commit(user_id, self.connection)
+        .unwrap()
     }
 }
@@ -7,6 +7,7 @@ pub struct SettlementAdjustmentBuilder<'a> {
     settlement_id: Option<Uuid>,
     amount_in_cents: i64,
     note: Option<String>,
+    settlement_adjustment_type: SettlementAdjustmentTypes,
     connection: &'a PgConnection,
 }
 
@@ -16,6 +17,7 @@ impl<'a> SettlementAdjustmentBuilder<'a> {
             settlement_id: None,
             note: None,
             amount_in_cents: 100,
+            settlement_adjustment_type: SettlementAdjustmentTypes::ManualDeduction,
             connection,
         }
     }
@@ -30,6 +32,11 @@ impl<'a> SettlementAdjustmentBuilder<'a> {
         self
     }
 
+    pub fn with_settlement_adjustment_type(mut self, settlement_adjustment_type: SettlementAdjustmentTypes) -> Self {
+        self.settlement_adjustment_type = settlement_adjustment_type;
+        self
+    }
+
     pub fn with_settlement(mut self, settlement: &Settlement) -> Self {
         self.settlement_id = Some(settlement.id);
         self
@@ -43,7 +50,7 @@ impl<'a> SettlementAdjustmentBuilder<'a> {
 
         SettlementAdjustment::create(
             settlement_id,
-            SettlementAdjustmentTypes::ManualDeduction,
+            self.settlement_adjustment_type,
             self.note.clone(),
             self.amount_in_cents,
         )
@@ -0,0 +1,76 @@
+use diesel::prelude::*;
+use models::*;
+use test::builders::*;
+use uuid::Uuid;
+
+pub struct TaxRateBuilder<'a> {
+    organization_id: Option<Uuid>,
+    venue_id: Option<Uuid>,
+    name: String,
+    rate_percent: f32,
+    inclusive: bool,
+    applies_to_fees: bool,
+    connection: &'a PgConnection,
+}
+
+impl<'a> TaxRateBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> TaxRateBuilder {
+        TaxRateBuilder {
+            organization_id: None,
+            venue_id: None,
+            name: "Sales Tax".to_string(),
+            rate_percent: 10f32,
+            inclusive: false,
+            applies_to_fees: false,
+            connection,
+        }
+    }
+
+    pub fn with_organization(mut self, organization: &Organization) -> Self {
+        self.organization_id = Some(organization.id);
+        self
+    }
+
+    pub fn with_venue(mut self, venue: &Venue) -> Self {
+        self.venue_id = Some(venue.id);
+        self
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_rate_percent(mut self, rate_percent: f32) -> Self {
+        self.rate_percent = rate_percent;
+        self
+    }
+
+    pub fn inclusive(mut self) -> Self {
+        self.inclusive = true;
+        self
+    }
+
+    pub fn applies_to_fees(mut self) -> Self {
+        self.applies_to_fees = true;
+        self
+    }
+
+    pub fn finish(&mut self) -> TaxRate {
+        let organization_id = self
+            .organization_id
+            .or_else(|| Some(OrganizationBuilder::new(self.connection).finish().id))
+            .unwrap();
+
+        TaxRate::create(
+            organization_id,
+            self.venue_id,
+            self.name.clone(),
+            self.rate_percent,
+            self.inclusive,
+            self.applies_to_fees,
+        )
+        .commit(self.connection)
+        .unwrap()
+    }
+}
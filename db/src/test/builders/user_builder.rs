@@ -1,5 +1,9 @@
+use chrono::prelude::*;
 use diesel::prelude::*;
 use models::User;
+use test::TestProject;
+use utils::encryption::KeyRing;
+use utils::totp;
 use uuid::Uuid;
 
 pub struct UserBuilder<'a> {
@@ -8,6 +12,7 @@ pub struct UserBuilder<'a> {
     email: Option<String>,
     phone: Option<String>,
     password: String,
+    mfa_encryption_keys: Option<KeyRing>,
     connection: &'a PgConnection,
 }
 
@@ -20,6 +25,7 @@ impl<'a> UserBuilder<'a> {
             email: Some(format!("jeff{}@tari.com", x).into()),
             phone: Some("555-555-5555".into()),
             password: "examplePassword".into(),
+            mfa_encryption_keys: None,
             connection,
         }
     }
@@ -59,15 +65,28 @@ impl<'a> UserBuilder<'a> {
         self
     }
 
+    pub fn with_mfa(mut self, key_ring: &KeyRing) -> Self {
+        self.mfa_encryption_keys = Some(key_ring.clone());
+        self
+    }
+
     pub fn finish(&self) -> User {
-        User::create(
+        let user = User::create(
             Some(self.first_name.to_string()),
             Some(self.last_name.to_string()),
             self.email.clone(),
             self.phone.clone(),
             &self.password,
         )
-        .commit(None, self.connection)
-        .unwrap()
+        .commit(None, &TestProject::wallet_encryption_keys(), self.connection)
+        .unwrap();
+
+        let key_ring = match self.mfa_encryption_keys {
+            Some(ref key_ring) => key_ring,
+            None => return user,
+        };
+        let user = user.begin_mfa_enrollment(key_ring, self.connection).unwrap();
+        let code = totp::code_at(&user.mfa_secret(key_ring).unwrap().unwrap(), Utc::now().timestamp()).unwrap();
+        user.activate_mfa(&code, key_ring, self.connection).unwrap().0
     }
 }
@@ -4,6 +4,10 @@ use diesel::{select, Connection, PgConnection, RunQueryDsl};
 use std::env;
 use test::builders::*;
 use test::dotenv::dotenv;
+use utils::encryption::KeyRing;
+
+const WALLET_ENCRYPTION_KEYS: &str = "1:test-wallet-key";
+const MFA_ENCRYPTION_KEYS: &str = "1:test-mfa-key";
 
 pub struct TestProject {
     pub connection: PgConnection,
@@ -30,10 +34,19 @@ impl TestProject {
         let admin_str = env::var("TEST_DATABASE_ADMIN_URL").expect("TEST_DATABASE_ADMIN_URL must be defined.");
         let connection = PgConnection::establish(&conn_str).expect("Could not get access to test database");
         let admin = PgConnection::establish(&admin_str).expect("Could not get admin access to admin test database");
-
         TestProject { connection, admin }
     }
 
+    /// Master keys wallets created by the builders are encrypted with
+    pub fn wallet_encryption_keys() -> KeyRing {
+        KeyRing::parse(WALLET_ENCRYPTION_KEYS).unwrap()
+    }
+
+    /// Master keys MFA secrets created in tests are encrypted with
+    pub fn mfa_encryption_keys() -> KeyRing {
+        KeyRing::parse(MFA_ENCRYPTION_KEYS).unwrap()
+    }
+
     pub fn db_exists(&self, name: &str) -> bool {
         select(sql::<Bool>(&format!(
             "EXISTS(SELECT 1 FROM pg_database WHERE datname='{}')",
@@ -175,6 +188,14 @@ impl TestProject {
         SettlementBuilder::new(&self.connection)
     }
 
+    pub fn create_tax_rate(&self) -> TaxRateBuilder {
+        TaxRateBuilder::new(&self.connection)
+    }
+
+    pub fn create_access_zone(&self) -> AccessZoneBuilder {
+        AccessZoneBuilder::new(&self.connection)
+    }
+
     pub fn get_connection(&self) -> &PgConnection {
         &self.connection
     }
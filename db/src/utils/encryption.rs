@@ -1,8 +1,70 @@
 use hex;
 use ring::aead::*;
 use ring::rand::{SecureRandom, SystemRandom};
+use std::collections::BTreeMap;
 use utils::errors::*;
 
+/// Versioned master keys. Data is encrypted with the newest key and records the key version so that
+/// it can still be decrypted after a newer key has been added. Version 0 denotes data stored before
+/// encryption was introduced, it can be read but is never written.
+#[derive(Clone, Debug, Default)]
+pub struct KeyRing {
+    keys: BTreeMap<i32, String>,
+}
+
+impl KeyRing {
+    /// Parses comma separated `version:key` pairs, e.g. `1:old-key,2:new-key`
+    pub fn parse(value: &str) -> Result<KeyRing, DatabaseError> {
+        let mut keys = BTreeMap::new();
+        for pair in value.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let mut parts = pair.splitn(2, ':');
+            let version = parts.next().and_then(|v| v.parse::<i32>().ok()).filter(|v| *v > 0);
+            match (version, parts.next()) {
+                (Some(version), Some(key)) if !key.is_empty() => {
+                    keys.insert(version, key.to_string());
+                }
+                _ => {
+                    return Err(DatabaseError::new(
+                        ErrorCode::InternalError,
+                        Some("Encryption keys must be comma separated version:key pairs".to_string()),
+                    ))
+                }
+            }
+        }
+
+        Ok(KeyRing { keys })
+    }
+
+    pub fn current_version(&self) -> Option<i32> {
+        self.keys.keys().next_back().cloned()
+    }
+
+    /// Encrypts with the current key, returning the ciphertext and the key version used
+    pub fn encrypt(&self, plaintext: &str) -> Result<(String, i32), DatabaseError> {
+        match self.keys.iter().next_back() {
+            Some((version, key)) => Ok((encrypt(plaintext, key)?, *version)),
+            None => Err(DatabaseError::new(
+                ErrorCode::InternalError,
+                Some("No encryption keys have been configured".to_string()),
+            )),
+        }
+    }
+
+    pub fn decrypt(&self, ciphertext: &str, version: i32) -> Result<String, DatabaseError> {
+        if version == 0 {
+            return Ok(ciphertext.to_string());
+        }
+
+        match self.keys.get(&version) {
+            Some(key) => decrypt(ciphertext, key),
+            None => Err(DatabaseError::new(
+                ErrorCode::InternalError,
+                Some(format!("Encryption key version {} is not configured", version)),
+            )),
+        }
+    }
+}
+
 pub fn encrypt(plaintext: &str, encryption_key: &str) -> Result<String, DatabaseError> {
     let mut key = encryption_key.to_string().into_bytes();
     if key.len() > 32 {
@@ -22,7 +84,13 @@ pub fn encrypt(plaintext: &str, encryption_key: &str) -> Result<String, Database
     for _ in 0..CHACHA20_POLY1305.tag_len() {
         in_out.push(0);
     }
-    let _ = seal_in_place(&sealing_key, &nonce, &[], &mut in_out, CHACHA20_POLY1305.tag_len())?;
+    let _ = seal_in_place(
+        &sealing_key,
+        Nonce::try_assume_unique_for_key(&nonce)?,
+        Aad::empty(),
+        &mut in_out,
+        CHACHA20_POLY1305.tag_len(),
+    )?;
 
     let data = hex::encode(&in_out);
     let mut nonce_data = hex::encode(nonce);
@@ -62,7 +130,8 @@ pub fn decrypt(ciphertext: &str, encryption_key: &str) -> Result<String, Databas
     }
 
     let mut in_out = new_data.unwrap();
-    let decrypted_data = open_in_place(&opening_key, &new_nonce.unwrap(), &[], 0, &mut in_out).unwrap();
+    let nonce = Nonce::try_assume_unique_for_key(&new_nonce.unwrap())?;
+    let decrypted_data = open_in_place(&opening_key, nonce, Aad::empty(), 0, &mut in_out)?;
 
     let plaintext = String::from_utf8(decrypted_data.to_vec());
     //Doing this rather that implement a From for just this once instance
@@ -8,7 +8,9 @@ pub mod migration;
 pub mod pagination;
 pub mod passwords;
 pub mod rand;
+pub mod recurrence;
 pub mod regexes;
 pub mod text;
+pub mod totp;
 pub use self::math::*;
 pub mod boxed_query;
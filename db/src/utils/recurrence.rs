@@ -0,0 +1,242 @@
+//! The subset of iCalendar (RFC 5545) recurrence rules supported for event series, e.g.
+//! `FREQ=WEEKLY;INTERVAL=1;BYDAY=TH,FR;COUNT=20`. `FREQ` and one of `COUNT` or `UNTIL` are
+//! required, `BYDAY` is only supported for weekly rules.
+use chrono::prelude::*;
+use chrono::Duration;
+use validator::ValidationError;
+use validators::create_validation_error;
+
+/// Upper bound on the occurrences a single rule may produce
+pub const MAX_OCCURRENCES: usize = 366;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDateTime>,
+    pub by_day: Vec<Weekday>,
+}
+
+impl RecurrenceRule {
+    pub fn parse(rule: &str) -> Result<RecurrenceRule, ValidationError> {
+        let rule = rule.trim();
+        let rule = if rule.to_uppercase().starts_with("RRULE:") {
+            &rule[6..]
+        } else {
+            rule
+        };
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+
+        for part in rule.split(';').filter(|p| !p.trim().is_empty()) {
+            let mut pair = part.splitn(2, '=');
+            let key = pair.next().unwrap_or("").trim().to_uppercase();
+            let value = pair.next().unwrap_or("").trim().to_uppercase();
+            match key.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => RecurrenceFrequency::Daily,
+                        "WEEKLY" => RecurrenceFrequency::Weekly,
+                        "MONTHLY" => RecurrenceFrequency::Monthly,
+                        _ => {
+                            return Err(create_validation_error(
+                                "unsupported_frequency",
+                                "FREQ must be DAILY, WEEKLY or MONTHLY",
+                            ))
+                        }
+                    })
+                }
+                "INTERVAL" => {
+                    interval = match value.parse::<u32>() {
+                        Ok(i) if i > 0 => i,
+                        _ => {
+                            return Err(create_validation_error(
+                                "invalid_interval",
+                                "INTERVAL must be a positive number",
+                            ))
+                        }
+                    }
+                }
+                "COUNT" => {
+                    count = match value.parse::<u32>() {
+                        Ok(c) if c > 0 => Some(c),
+                        _ => {
+                            return Err(create_validation_error(
+                                "invalid_count",
+                                "COUNT must be a positive number",
+                            ))
+                        }
+                    }
+                }
+                "UNTIL" => {
+                    until = match parse_until(&value) {
+                        Some(u) => Some(u),
+                        None => {
+                            return Err(create_validation_error(
+                                "invalid_until",
+                                "UNTIL must be a date in the format YYYYMMDD or YYYYMMDDTHHMMSS",
+                            ))
+                        }
+                    }
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        match parse_weekday(day.trim()) {
+                            Some(weekday) => {
+                                if !by_day.contains(&weekday) {
+                                    by_day.push(weekday);
+                                }
+                            }
+                            None => {
+                                return Err(create_validation_error(
+                                    "invalid_by_day",
+                                    "BYDAY must be a list of MO, TU, WE, TH, FR, SA or SU",
+                                ))
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    return Err(create_validation_error(
+                        "unsupported_rule_part",
+                        "Only FREQ, INTERVAL, COUNT, UNTIL and BYDAY are supported",
+                    ))
+                }
+            }
+        }
+
+        let frequency = match frequency {
+            Some(f) => f,
+            None => return Err(create_validation_error("required", "FREQ is required")),
+        };
+        if count.is_none() == until.is_none() {
+            return Err(create_validation_error(
+                "count_or_until_required",
+                "Exactly one of COUNT or UNTIL is required",
+            ));
+        }
+        if !by_day.is_empty() && frequency != RecurrenceFrequency::Weekly {
+            return Err(create_validation_error(
+                "unsupported_by_day",
+                "BYDAY is only supported for weekly rules",
+            ));
+        }
+        by_day.sort_by_key(|d| d.num_days_from_monday());
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+        })
+    }
+
+    /// Occurrences of the rule starting at `start`, which is always the first occurrence. Times are
+    /// kept as given so the caller should expand in the local time of the venue.
+    pub fn occurrences(&self, start: NaiveDateTime) -> Result<Vec<NaiveDateTime>, ValidationError> {
+        let mut result = vec![start];
+        let mut period = 1;
+        // Guards against rules such as a weekly rule with an UNTIL before the next matching day
+        let max_periods = (MAX_OCCURRENCES as i64 + 1) * 31;
+
+        while !self.is_finished(&result) && period < max_periods {
+            for candidate in self.period_candidates(start, period) {
+                if candidate <= start || self.is_finished(&result) {
+                    continue;
+                }
+                if self.until.map(|until| candidate > until).unwrap_or(false) {
+                    return Ok(result);
+                }
+                result.push(candidate);
+            }
+            if result.len() > MAX_OCCURRENCES {
+                return Err(create_validation_error(
+                    "too_many_occurrences",
+                    "Recurrence rule produces more than 366 occurrences",
+                ));
+            }
+            period += 1;
+        }
+
+        Ok(result)
+    }
+
+    fn is_finished(&self, occurrences: &[NaiveDateTime]) -> bool {
+        self.count.map(|c| occurrences.len() >= c as usize).unwrap_or(false)
+    }
+
+    /// Candidate dates within the `period`th period after the one containing `start`. For weekly
+    /// rules the period containing `start` is also checked for later days in the same week.
+    fn period_candidates(&self, start: NaiveDateTime, period: i64) -> Vec<NaiveDateTime> {
+        let steps = period * self.interval as i64;
+        match self.frequency {
+            RecurrenceFrequency::Daily => vec![start + Duration::days(steps)],
+            RecurrenceFrequency::Weekly => {
+                let week_start = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+                let days = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.clone()
+                };
+                let mut candidates = Vec::new();
+                // Remaining days in the first week
+                if period == 1 {
+                    for day in days.iter() {
+                        candidates.push(week_start + Duration::days(day.num_days_from_monday() as i64));
+                    }
+                }
+                for day in days.iter() {
+                    candidates
+                        .push(week_start + Duration::weeks(steps) + Duration::days(day.num_days_from_monday() as i64));
+                }
+                candidates
+            }
+            RecurrenceFrequency::Monthly => {
+                let months = start.month0() as i64 + steps;
+                let year = start.year() + (months / 12) as i32;
+                let month = (months % 12) as u32 + 1;
+                // Months without the day of the month (e.g. the 31st) are skipped
+                match NaiveDate::from_ymd_opt(year, month, start.day()) {
+                    Some(date) => vec![date.and_time(start.time())],
+                    None => vec![],
+                }
+            }
+        }
+    }
+}
+
+fn parse_until(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim_end_matches('Z');
+    if let Ok(until) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some(until);
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .map(|date| date.and_hms(23, 59, 59))
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
@@ -0,0 +1,153 @@
+//! Time-based one-time passwords (RFC 6238) using base32 encoded secrets, the format
+//! authenticator apps expect.
+use rand::{thread_rng, Rng};
+use ring::{constant_time, digest, hmac};
+use url::Url;
+
+pub const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+const SECRET_LENGTH: usize = 20;
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    thread_rng().fill(&mut secret);
+    base32_encode(&secret)
+}
+
+/// Code for the time step containing `timestamp`, `None` if the secret is not valid base32
+pub fn code_at(secret: &str, timestamp: i64) -> Option<String> {
+    let key = base32_decode(secret)?;
+    if timestamp < 0 {
+        return None;
+    }
+    Some(code_for_counter(&key, (timestamp / STEP_SECONDS) as u64))
+}
+
+/// Checks the code against every time step within `skew_steps` of `timestamp` so small clock
+/// differences between devices are tolerated
+pub fn verify(secret: &str, code: &str, timestamp: i64, skew_steps: i64) -> bool {
+    verified_step(secret, code, timestamp, skew_steps).is_some()
+}
+
+/// Time step the code belongs to when it is valid within `skew_steps` of `timestamp`, callers
+/// record it to reject the code being used again
+pub fn verified_step(secret: &str, code: &str, timestamp: i64, skew_steps: i64) -> Option<i64> {
+    let key = base32_decode(secret)?;
+    let counter = timestamp / STEP_SECONDS;
+    (-skew_steps..=skew_steps)
+        .map(|offset| counter + offset)
+        .filter(|counter| *counter >= 0)
+        .find(|counter| {
+            constant_time::verify_slices_are_equal(code_for_counter(&key, *counter as u64).as_bytes(), code.as_bytes())
+                .is_ok()
+        })
+}
+
+/// `otpauth://` URI authenticator apps can import, usually shown as a QR code
+pub fn provisioning_uri(secret: &str, issuer: &str, account_name: &str) -> String {
+    let mut uri = Url::parse("otpauth://totp/").unwrap();
+    uri.set_path(&format!("{}:{}", issuer, account_name));
+    uri.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", issuer)
+        .append_pair("digits", &DIGITS.to_string())
+        .append_pair("period", &STEP_SECONDS.to_string());
+    uri.to_string()
+}
+
+fn code_for_counter(key: &[u8], counter: u64) -> String {
+    let signing_key = hmac::SigningKey::new(&digest::SHA1, key);
+    let signature = hmac::sign(&signing_key, &counter.to_be_bytes());
+    let hash = signature.as_ref();
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = (u32::from(hash[offset]) & 0x7f) << 24
+        | u32::from(hash[offset + 1]) << 16
+        | u32::from(hash[offset + 2]) << 8
+        | u32::from(hash[offset + 3]);
+    format!("{:0width$}", binary % 10u32.pow(DIGITS), width = DIGITS as usize)
+}
+
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            encoded.push(BASE32_ALPHABET[((buffer >> (bits - 5)) & 0x1f) as usize] as char);
+            bits -= 5;
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            decoded.push((buffer >> (bits - 8)) as u8);
+            bits -= 8;
+        }
+    }
+    Some(decoded)
+}
+
+#[test]
+fn base32_round_trip() {
+    let encoded = base32_encode(b"12345678901234567890");
+    assert_eq!(encoded, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    assert_eq!(base32_decode(&encoded).unwrap(), b"12345678901234567890".to_vec());
+    assert_eq!(base32_decode("gezdgnbv").unwrap(), b"12345".to_vec());
+    assert!(base32_decode("not base32!").is_none());
+}
+
+#[test]
+fn code_at_rfc_6238_vectors() {
+    let secret = base32_encode(b"12345678901234567890");
+    assert_eq!(code_at(&secret, 59).unwrap(), "287082");
+    assert_eq!(code_at(&secret, 1_111_111_109).unwrap(), "081804");
+    assert_eq!(code_at(&secret, 1_234_567_890).unwrap(), "005924");
+    assert_eq!(code_at(&secret, 2_000_000_000).unwrap(), "279037");
+}
+
+#[test]
+fn provisioning_uri_includes_secret() {
+    let uri = provisioning_uri("GEZDGNBV", "Big Neon", "user@localhost");
+    assert!(uri.starts_with("otpauth://totp/Big%20Neon:user@localhost?"));
+    assert!(uri.contains("secret=GEZDGNBV"));
+    assert!(uri.contains("issuer=Big+Neon"));
+}
+
+#[test]
+fn verify_within_skew() {
+    let secret = generate_secret();
+    assert_eq!(base32_decode(&secret).unwrap().len(), SECRET_LENGTH);
+    let code = code_at(&secret, 1_000_000).unwrap();
+    assert!(verify(&secret, &code, 1_000_000, 0));
+    assert!(verify(&secret, &code, 1_000_000 + STEP_SECONDS, 1));
+    assert!(verify(&secret, &code, 1_000_000 - STEP_SECONDS, 1));
+    assert!(!verify(&secret, &code, 1_000_000 + 2 * STEP_SECONDS, 1));
+    assert!(!verify(&secret, "000000x", 1_000_000, 1));
+}
+
+#[test]
+fn verified_step_of_code() {
+    let secret = generate_secret();
+    let code = code_at(&secret, 1_000_000).unwrap();
+    let step = 1_000_000 / STEP_SECONDS;
+    assert_eq!(verified_step(&secret, &code, 1_000_000, 1), Some(step));
+    assert_eq!(verified_step(&secret, &code, 1_000_000 + STEP_SECONDS, 1), Some(step));
+    assert_eq!(verified_step(&secret, &code, 1_000_000 + 2 * STEP_SECONDS, 1), None);
+}
@@ -0,0 +1,13 @@
+use std::borrow::Cow;
+use validator::ValidationError;
+use validators::*;
+
+/// Currencies are stored as uppercase ISO 4217 codes, e.g. `USD`
+pub fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        let mut validation_error = create_validation_error("currency", "Currency must be a three letter ISO 4217 code");
+        validation_error.add_param(Cow::from("currency"), &currency);
+        return Err(validation_error);
+    }
+    Ok(())
+}
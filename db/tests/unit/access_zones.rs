@@ -0,0 +1,158 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let venue = project.create_venue().finish();
+
+    let access_zone = AccessZone::create_for_event(event.id, "Backstage".to_string(), Some("Crew only".to_string()))
+        .commit(connection)
+        .unwrap();
+    assert_eq!(access_zone.event_id, Some(event.id));
+    assert_eq!(access_zone.venue_id, None);
+    assert_eq!(access_zone.name, "Backstage".to_string());
+    assert_eq!(access_zone.description, Some("Crew only".to_string()));
+
+    let access_zone = AccessZone::create_for_venue(venue.id, "Balcony".to_string(), None)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(access_zone.event_id, None);
+    assert_eq!(access_zone.venue_id, Some(venue.id));
+}
+
+#[test]
+fn find() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let access_zone = project.create_access_zone().finish();
+    assert_eq!(AccessZone::find(access_zone.id, connection).unwrap(), access_zone);
+}
+
+#[test]
+fn find_by_venue_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let balcony = project
+        .create_access_zone()
+        .with_venue(&venue)
+        .with_name("Balcony")
+        .finish();
+    let floor = project
+        .create_access_zone()
+        .with_venue(&venue)
+        .with_name("Floor")
+        .finish();
+    project.create_access_zone().finish();
+
+    assert_eq!(
+        AccessZone::find_by_venue_id(venue.id, connection).unwrap(),
+        vec![balcony, floor]
+    );
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    let other_event = project.create_event().with_venue(&venue).finish();
+    let balcony = project
+        .create_access_zone()
+        .with_venue(&venue)
+        .with_name("Balcony")
+        .finish();
+    let backstage = project
+        .create_access_zone()
+        .with_event(&event)
+        .with_name("Backstage")
+        .finish();
+    let other_backstage = project.create_access_zone().with_event(&other_event).finish();
+    project.create_access_zone().finish();
+
+    assert_eq!(
+        AccessZone::find_for_event(&event, connection).unwrap(),
+        vec![backstage.clone(), balcony.clone()]
+    );
+    assert!(balcony.is_for_event(&event));
+    assert!(backstage.is_for_event(&event));
+    assert!(!other_backstage.is_for_event(&event));
+}
+
+#[test]
+fn find_for_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let access_zone = project
+        .create_access_zone()
+        .with_event(&event)
+        .with_ticket_type(ticket_type)
+        .finish();
+    project.create_access_zone().with_event(&event).finish();
+
+    assert_eq!(
+        AccessZone::find_for_ticket_type(ticket_type.id, connection).unwrap(),
+        vec![access_zone]
+    );
+}
+
+#[test]
+fn grants_access() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_type_count(2)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let access_zone = project
+        .create_access_zone()
+        .with_event(&event)
+        .with_ticket_type(&ticket_types[0])
+        .finish();
+
+    assert!(access_zone.grants_access(ticket_types[0].id, connection).unwrap());
+    assert!(!access_zone.grants_access(ticket_types[1].id, connection).unwrap());
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let access_zone = project.create_access_zone().finish();
+    let access_zone = access_zone
+        .update(
+            AccessZoneEditableAttributes {
+                name: Some("Green Room".to_string()),
+                description: Some(Some("Artists only".to_string())),
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(access_zone.name, "Green Room".to_string());
+    assert_eq!(access_zone.description, Some("Artists only".to_string()));
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let access_zone = project
+        .create_access_zone()
+        .with_event(&event)
+        .with_ticket_type(ticket_type)
+        .finish();
+
+    assert_eq!(access_zone.destroy(connection).unwrap(), 1);
+    assert!(AccessZone::find(access_zone.id, connection).is_err());
+    assert!(ticket_type.access_zones(connection).unwrap().is_empty());
+}
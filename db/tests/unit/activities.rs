@@ -43,6 +43,7 @@ fn transfer_eligible_for_cancelling() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -53,6 +54,7 @@ fn transfer_eligible_for_cancelling() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -89,6 +91,7 @@ fn transfer_eligible_for_cancelling() {
         "nowhere",
         TransferMessageType::Email,
         user3.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -305,6 +308,7 @@ fn load_for_event() {
         "nowhere",
         TransferMessageType::Email,
         user4.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -536,6 +540,7 @@ fn load_for_order() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
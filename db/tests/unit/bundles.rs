@@ -0,0 +1,551 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use db::utils::errors::*;
+
+fn create_bundle(project: &TestProject, price_in_cents: i64) -> (Bundle, Vec<TicketType>) {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let mut ticket_types = Vec::new();
+    for _ in 0..3 {
+        let event = project
+            .create_event()
+            .with_organization(&organization)
+            .with_ticket_pricing()
+            .finish();
+        ticket_types.push(event.ticket_types(true, None, connection).unwrap().remove(0));
+    }
+    let bundle = Bundle::create(organization.id, "3-show pass".to_string(), None, price_in_cents)
+        .commit(None, connection)
+        .unwrap();
+    bundle
+        .update_ticket_types(
+            &ticket_types
+                .iter()
+                .map(|tt| UpdateBundleTicketType {
+                    ticket_type_id: tt.id,
+                    quantity: 1,
+                })
+                .collect::<Vec<UpdateBundleTicketType>>(),
+            None,
+            connection,
+        )
+        .unwrap();
+    (bundle, ticket_types)
+}
+
+fn purchase(project: &TestProject, bundle: &Bundle, quantity: u32) -> (User, Order) {
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_bundle_quantities(
+        user.id,
+        &[UpdateOrderBundle {
+            bundle_id: bundle.id,
+            quantity,
+        }],
+        connection,
+    )
+    .unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    (user, Order::find(cart.id, connection).unwrap())
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let bundle = Bundle::create(
+        organization.id,
+        "Festival pass".to_string(),
+        Some("All three nights".to_string()),
+        25000,
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(bundle.organization_id, organization.id);
+    assert_eq!(bundle.price_in_cents, 25000);
+    assert_eq!(
+        Bundle::find_for_organization(organization.id, connection).unwrap(),
+        vec![bundle.clone()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Bundles,
+        Some(bundle.id),
+        Some(DomainEventTypes::BundleCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].user_id, Some(user.id));
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = Bundle::create(organization.id, "".to_string(), None, -1).commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+                assert!(errors.contains_key("price_in_cents"));
+                assert_eq!(errors["price_in_cents"][0].code, "invalid_price");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+
+    let bundle_ticket_types = bundle.ticket_types(connection).unwrap();
+    assert_eq!(bundle_ticket_types.len(), 3);
+    for (bundle_ticket_type, ticket_type) in bundle_ticket_types.iter().zip(ticket_types.iter()) {
+        assert_eq!(bundle_ticket_type.ticket_type_id, ticket_type.id);
+        assert_eq!(bundle_ticket_type.quantity, 1);
+        assert_eq!(bundle_ticket_type.allocated_price_in_cents, 80);
+    }
+
+    // Replacing the ticket types reallocates the price
+    let bundle_ticket_types = bundle
+        .update_ticket_types(
+            &[
+                UpdateBundleTicketType {
+                    ticket_type_id: ticket_types[0].id,
+                    quantity: 2,
+                },
+                UpdateBundleTicketType {
+                    ticket_type_id: ticket_types[1].id,
+                    quantity: 1,
+                },
+            ],
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(bundle_ticket_types.len(), 2);
+    assert_eq!(bundle_ticket_types[0].allocated_price_in_cents, 80);
+    assert_eq!(bundle_ticket_types[1].allocated_price_in_cents, 80);
+
+    // Changing the price reallocates it
+    let bundle = bundle
+        .update(
+            BundleEditableAttributes {
+                price_in_cents: Some(300),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let bundle_ticket_types = bundle.ticket_types(connection).unwrap();
+    assert_eq!(bundle_ticket_types[0].allocated_price_in_cents, 100);
+    assert_eq!(bundle_ticket_types[1].allocated_price_in_cents, 100);
+}
+
+#[test]
+fn update_ticket_types_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let other_ticket_type = other_event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    let result = bundle.update_ticket_types(&[], None, connection);
+    assert_eq!(
+        result,
+        DatabaseError::validation_error("ticket_types", "A bundle must include at least one ticket type")
+    );
+
+    let result = bundle.update_ticket_types(
+        &[
+            UpdateBundleTicketType {
+                ticket_type_id: ticket_types[0].id,
+                quantity: 0,
+            },
+            UpdateBundleTicketType {
+                ticket_type_id: ticket_types[0].id,
+                quantity: 1,
+            },
+            UpdateBundleTicketType {
+                ticket_type_id: other_ticket_type.id,
+                quantity: 1,
+            },
+        ],
+        None,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["quantity"][0].code, "quantity_invalid_increment");
+                assert_eq!(errors["ticket_type_id"][0].code, "duplicate");
+                assert_eq!(errors["ticket_type_id"][1].code, "invalid_organization");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Ticket types are unchanged
+    assert_eq!(bundle.ticket_types(connection).unwrap().len(), 3);
+}
+
+#[test]
+fn update_ticket_types_after_sale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+    purchase(&project, &bundle, 1);
+
+    let result = bundle.update_ticket_types(
+        &[UpdateBundleTicketType {
+            ticket_type_id: ticket_types[0].id,
+            quantity: 1,
+        }],
+        None,
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Ticket types can not be changed once the bundle has been sold",)
+    );
+}
+
+#[test]
+fn update_after_sale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = create_bundle(&project, 240);
+    purchase(&project, &bundle, 1);
+
+    let result = bundle.update(
+        BundleEditableAttributes {
+            price_in_cents: Some(300),
+            ..Default::default()
+        },
+        None,
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Price can not be changed once the bundle has been sold",)
+    );
+
+    // Other attributes can still be changed
+    let bundle = bundle
+        .update(
+            BundleEditableAttributes {
+                name: Some("Season pass".to_string()),
+                price_in_cents: Some(240),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(bundle.name, "Season pass".to_string());
+    assert_eq!(bundle.price_in_cents, 240);
+}
+
+#[test]
+fn allocate_price() {
+    // Proportional to face value
+    assert_eq!(Bundle::allocate_price(200, &[(1, 100), (1, 300)]), Some(vec![50, 150]));
+    // Remainder goes to the component with the most tickets first
+    assert_eq!(
+        Bundle::allocate_price(100, &[(1, 100), (1, 100), (1, 100)]),
+        Some(vec![34, 33, 33])
+    );
+    assert_eq!(Bundle::allocate_price(101, &[(2, 100), (1, 100)]), Some(vec![34, 33]));
+    // Free ticket types split evenly
+    assert_eq!(Bundle::allocate_price(90, &[(2, 0), (1, 0)]), Some(vec![30, 30]));
+    // Can not be split exactly
+    assert_eq!(Bundle::allocate_price(101, &[(2, 100)]), None);
+    assert_eq!(Bundle::allocate_price(100, &[]), None);
+}
+
+#[test]
+fn delete() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = create_bundle(&project, 240);
+
+    bundle.delete(None, connection).unwrap();
+    let bundle = Bundle::find(bundle.id, connection).unwrap();
+    assert!(bundle.deleted_at.is_some());
+    assert!(Bundle::find_for_organization(bundle.organization_id, connection)
+        .unwrap()
+        .is_empty());
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let result = cart.update_bundle_quantities(
+        user.id,
+        &[UpdateOrderBundle {
+            bundle_id: bundle.id,
+            quantity: 1,
+        }],
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Bundle is no longer available")
+    );
+}
+
+#[test]
+fn available() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+    let available = ticket_types[0].valid_available_ticket_count(connection).unwrap();
+    assert_eq!(bundle.available(connection).unwrap(), available);
+
+    bundle
+        .update_ticket_types(
+            &[UpdateBundleTicketType {
+                ticket_type_id: ticket_types[0].id,
+                quantity: 2,
+            }],
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(bundle.available(connection).unwrap(), available / 2);
+
+    let display_bundle = bundle.for_display(connection).unwrap();
+    assert_eq!(display_bundle.available, available / 2);
+    assert_eq!(display_bundle.ticket_types.len(), 1);
+    assert_eq!(display_bundle.ticket_types[0].event_id, ticket_types[0].event_id);
+    assert_eq!(display_bundle.ticket_types[0].allocated_price_in_cents, 120);
+}
+
+#[test]
+fn update_bundle_quantities() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    cart.update_bundle_quantities(
+        user.id,
+        &[UpdateOrderBundle {
+            bundle_id: bundle.id,
+            quantity: 2,
+        }],
+        connection,
+    )
+    .unwrap();
+    assert!(cart.expires_at.is_some());
+    let items: Vec<OrderItem> = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    // Tickets for several events are allowed in the same cart
+    assert_eq!(items.len(), 3);
+    for ticket_type in &ticket_types {
+        let item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+        assert_eq!(item.bundle_id, Some(bundle.id));
+        assert_eq!(item.event_id, Some(ticket_type.event_id));
+        assert_eq!(item.quantity, 2);
+        assert_eq!(item.unit_price_in_cents, 80);
+        assert_eq!(
+            TicketInstance::find_for_order_item(item.id, connection).unwrap().len(),
+            2
+        );
+    }
+
+    // Updating cart tickets leaves the bundle alone
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_types[0].id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let ticket_items = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .count();
+    assert_eq!(ticket_items, 4);
+
+    // Removing the bundle releases its tickets
+    cart.update_bundle_quantities(
+        user.id,
+        &[UpdateOrderBundle {
+            bundle_id: bundle.id,
+            quantity: 0,
+        }],
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    assert!(items.iter().all(|i| i.bundle_id.is_none()));
+    assert_eq!(
+        items.iter().filter(|i| i.item_type == OrderItemTypes::Tickets).count(),
+        1
+    );
+}
+
+#[test]
+fn update_bundle_quantities_exceeding_limit_per_person() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+    ticket_types[0]
+        .clone()
+        .update(
+            TicketTypeEditableAttributes {
+                limit_per_person: Some(1),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    let result = cart.update_bundle_quantities(
+        user.id,
+        &[UpdateOrderBundle {
+            bundle_id: bundle.id,
+            quantity: 2,
+        }],
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["quantity"][0].code, "limit_per_person_exceeded");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update_bundle_quantities_with_tickets_from_another_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, _) = create_bundle(&project, 240);
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let other_ticket_type = other_event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: other_ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // The bundle belongs to another organization
+    let result = cart.update_bundle_quantities(
+        user.id,
+        &[UpdateOrderBundle {
+            bundle_id: bundle.id,
+            quantity: 1,
+        }],
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["organization_id"][0].code, "cart_organization_limit_reached");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (bundle, ticket_types) = create_bundle(&project, 240);
+    let (user, mut order) = purchase(&project, &bundle, 2);
+    let items: Vec<OrderItem> = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    let tickets: Vec<Vec<TicketInstance>> = items
+        .iter()
+        .map(|i| TicketInstance::find_for_order_item(i.id, connection).unwrap())
+        .collect();
+    let refund_item = |index: usize, ticket_index: usize| RefundItemRequest {
+        order_item_id: items[index].id,
+        ticket_instance_id: Some(tickets[index][ticket_index].id),
+    };
+
+    // A single ticket of the bundle can not be refunded on its own
+    let result = order.refund(&[refund_item(0, 0)], user.id, None, false, connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error(
+            "Bundle tickets must be refunded together, include the tickets for every event in the bundle",
+        )
+    );
+
+    // A whole unit of the bundle can
+    let refund_items: Vec<RefundItemRequest> = (0..items.len()).map(|i| refund_item(i, 0)).collect();
+    let (_, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(amount, 240);
+
+    // Tickets for a cancelled event can be refunded on their own at their allocated price
+    let cancelled_index = items
+        .iter()
+        .position(|i| i.ticket_type_id == Some(ticket_types[0].id))
+        .unwrap();
+    Event::find(ticket_types[0].event_id, connection)
+        .unwrap()
+        .cancel(None, connection)
+        .unwrap();
+    let (_, amount) = order
+        .refund(&[refund_item(cancelled_index, 1)], user.id, None, false, connection)
+        .unwrap();
+    assert_eq!(amount, 80);
+}
@@ -618,11 +618,13 @@ pub fn find_number_of_uses() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -660,11 +662,13 @@ pub fn find_number_of_uses() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1,7 +1,7 @@
 use chrono::{Duration, Utc};
 use db::dev::TestProject;
 use db::models::concerns::users::password_resetable::{PasswordReset, PasswordResetable};
-use db::models::User;
+use db::models::{User, UserSession};
 use diesel;
 use diesel::prelude::*;
 use uuid::Uuid;
@@ -43,6 +43,9 @@ fn consume_password_reset_token() {
         .into();
     let password = "newPassword";
     assert!(!user.check_password(&password));
+    let session = UserSession::create(user.id, None)
+        .commit(project.get_connection())
+        .unwrap();
 
     // Consumes password reset as token was not expired and valid
     let user =
@@ -52,6 +55,9 @@ fn consume_password_reset_token() {
     assert!(user.password_reset_token.is_none());
     assert!(user.password_reset_requested_at.is_none());
     assert_ne!(user.password_modified_at, pw_modified_at);
+    assert!(!UserSession::find(session.id, project.get_connection())
+        .unwrap()
+        .is_active());
 
     // Does not consume password reset as token was expired although valid
     let user: User = diesel::update(users.filter(id.eq(user.id)))
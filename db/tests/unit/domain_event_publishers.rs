@@ -1,5 +1,6 @@
 use db::dev::TestProject;
 use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
 
 #[test]
 fn find_all() {
@@ -146,6 +147,7 @@ fn update() {
     let parameters = DomainEventPublisherEditableAttributes {
         webhook_url: Some(new_webhook_url.clone()),
         import_historic_events: Some(false),
+        event_types: None,
     };
     let domain_event_publisher = domain_event_publisher.update(&parameters, connection).unwrap();
 
@@ -216,3 +218,148 @@ fn renew_lock() {
 
     assert!(domain_event_publisher.renew_lock(60, connection).is_ok());
 }
+
+#[test]
+fn create_with_invalid_organization_data() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = DomainEventPublisher::create(
+        Some(organization.id),
+        vec![DomainEventTypes::UserCreated],
+        "http://localhost:7644/webhook".to_string(),
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_types"));
+                assert_eq!(errors["event_types"].len(), 1);
+                assert_eq!(errors["event_types"][0].code, "event_type_not_supported");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result =
+        DomainEventPublisher::create(Some(organization.id), vec![], "not a url".to_string()).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_types"));
+                assert_eq!(errors["event_types"][0].code, "required");
+                assert!(errors.contains_key("webhook_url"));
+                assert_eq!(errors["webhook_url"][0].code, "url");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // System publishers are not limited to organization event types
+    assert!(DomainEventPublisher::create(
+        None,
+        vec![DomainEventTypes::UserCreated],
+        "http://localhost:7644/webhook".to_string(),
+    )
+    .commit(connection)
+    .is_ok());
+}
+
+#[test]
+fn update_with_invalid_event_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let domain_event_publisher = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+
+    let parameters = DomainEventPublisherEditableAttributes {
+        webhook_url: None,
+        import_historic_events: None,
+        event_types: Some(vec![DomainEventTypes::OrderCompleted]),
+    };
+    let result = domain_event_publisher.update(&parameters, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event_types"));
+                assert_eq!(errors["event_types"][0].code, "event_type_not_supported");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let parameters = DomainEventPublisherEditableAttributes {
+        webhook_url: None,
+        import_historic_events: None,
+        event_types: Some(vec![
+            DomainEventTypes::EventCreated,
+            DomainEventTypes::TicketInstanceRedeemed,
+        ]),
+    };
+    let domain_event_publisher = domain_event_publisher.update(&parameters, connection).unwrap();
+    assert_eq!(
+        domain_event_publisher.event_types,
+        vec![DomainEventTypes::EventCreated, DomainEventTypes::TicketInstanceRedeemed]
+    );
+}
+
+#[test]
+fn find_by_organization_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let domain_event_publisher = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+    let domain_event_publisher2 = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::EventCreated])
+        .finish();
+    project
+        .create_domain_event_publisher()
+        .with_organization(&organization2)
+        .with_event_types(vec![DomainEventTypes::EventPublished])
+        .finish();
+    project.create_domain_event_publisher().finish();
+
+    assert_eq!(
+        DomainEventPublisher::find_by_organization_id(organization.id, connection).unwrap(),
+        vec![domain_event_publisher.clone(), domain_event_publisher2.clone()]
+    );
+
+    domain_event_publisher2.delete(connection).unwrap();
+    assert_eq!(
+        DomainEventPublisher::find_by_organization_id(organization.id, connection).unwrap(),
+        vec![domain_event_publisher]
+    );
+}
+
+#[test]
+fn pause_and_resume() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    assert!(!domain_event_publisher.is_paused());
+
+    let domain_event_publisher = domain_event_publisher.pause(connection).unwrap();
+    assert!(domain_event_publisher.is_paused());
+    assert!(DomainEventPublisher::find(domain_event_publisher.id, connection)
+        .unwrap()
+        .is_paused());
+
+    let domain_event_publisher = domain_event_publisher.resume(connection).unwrap();
+    assert!(!domain_event_publisher.is_paused());
+    assert_eq!(domain_event_publisher.paused_at, None);
+}
@@ -254,3 +254,70 @@ fn find() {
         [domain_event2]
     );
 }
+
+#[test]
+fn owning_organization_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+
+    let event_domain_event = DomainEvent::create(
+        DomainEventTypes::EventCreated,
+        "Event created".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    // Resolved when publishing rather than stored with the event
+    assert_eq!(event_domain_event.organization_id, None);
+    assert_eq!(
+        event_domain_event.owning_organization_id(connection).unwrap(),
+        Some(organization.id)
+    );
+
+    let ticket_type_domain_event = DomainEvent::create(
+        DomainEventTypes::TicketTypeCreated,
+        "Ticket type created".to_string(),
+        Tables::TicketTypes,
+        Some(ticket_type.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(
+        ticket_type_domain_event.owning_organization_id(connection).unwrap(),
+        Some(organization.id)
+    );
+
+    // Orders can span organizations and users belong to none, neither is routed to organization publishers
+    let order_domain_event = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::OrderCompleted),
+        connection,
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(order_domain_event.owning_organization_id(connection).unwrap(), None);
+    let user_domain_event = DomainEvent::find(Tables::Users, Some(user.id), None, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(user_domain_event.owning_organization_id(connection).unwrap(), None);
+}
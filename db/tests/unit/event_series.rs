@@ -0,0 +1,634 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::models::*;
+use db::schema::events;
+use db::utils::errors::ErrorCode::ValidationError;
+use db::utils::recurrence::{RecurrenceFrequency, RecurrenceRule};
+use diesel;
+use diesel::prelude::*;
+
+fn create_template(project: &TestProject) -> Event {
+    let venue = project.create_venue().with_timezone("UTC".to_string()).finish();
+    project
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = create_template(&project);
+
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        " freq=weekly;count=4 ".to_string(),
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(event_series.recurrence_rule, "FREQ=WEEKLY;COUNT=4");
+    assert_eq!(event_series.template_event_id, event.id);
+
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.event_series_id, Some(event_series.id));
+    assert_eq!(event_series.events(connection).unwrap(), vec![event]);
+
+    let domain_events = DomainEvent::find(
+        Tables::EventSeries,
+        Some(event_series.id),
+        Some(DomainEventTypes::EventSeriesCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].user_id, Some(user.id));
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_template(&project);
+
+    // Invalid recurrence rule
+    let result = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=YEARLY;COUNT=4".to_string(),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("recurrence_rule"));
+                assert_eq!(errors["recurrence_rule"][0].code, "unsupported_frequency");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Template from another organization
+    let organization = project.create_organization().finish();
+    let result = EventSeries::create(
+        organization.id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=4".to_string(),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("template_event_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Template already part of a series
+    EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=4".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let result = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Daily residency".to_string(),
+        "FREQ=DAILY;COUNT=4".to_string(),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("template_event_id"));
+                assert_eq!(
+                    errors["template_event_id"][0].message.clone().unwrap().into_owned(),
+                    "Event is already part of a series"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_template(&project);
+    let event2 = create_template(&project);
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=2".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    EventSeries::create(
+        event2.organization_id,
+        event2.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=2".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    assert_eq!(
+        EventSeries::find_for_organization(event.organization_id, connection).unwrap(),
+        vec![event_series.clone()]
+    );
+    assert_eq!(EventSeries::find(event_series.id, connection).unwrap(), event_series);
+}
+
+#[test]
+fn generate_occurrences() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = create_template(&project);
+    let template_ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+
+    let occurrences = event_series.generate_occurrences(Some(user.id), connection).unwrap();
+    assert_eq!(occurrences.len(), 2);
+    for (i, occurrence) in occurrences.iter().enumerate() {
+        let offset = Duration::weeks(i as i64 + 1);
+        assert_eq!(occurrence.name, event.name);
+        assert_eq!(occurrence.event_series_id, Some(event_series.id));
+        assert_eq!(occurrence.status, EventStatus::Published);
+        assert_eq!(occurrence.event_start, event.event_start.map(|d| d + offset));
+        assert_eq!(occurrence.event_end, event.event_end.map(|d| d + offset));
+
+        let ticket_types = occurrence.ticket_types(true, None, connection).unwrap();
+        assert_eq!(ticket_types.len(), 1);
+        let ticket_type = &ticket_types[0];
+        assert_eq!(ticket_type.cloned_from_ticket_type_id, Some(template_ticket_type.id));
+        assert_eq!(ticket_type.end_date_type, template_ticket_type.end_date_type);
+        assert_eq!(
+            ticket_type.start_date,
+            template_ticket_type.start_date.map(|d| d + offset)
+        );
+        assert_eq!(ticket_type.end_date, template_ticket_type.end_date.map(|d| d + offset));
+
+        let template_pricing = template_ticket_type.valid_ticket_pricing(false, connection).unwrap();
+        let pricing = ticket_type.valid_ticket_pricing(false, connection).unwrap();
+        assert_eq!(
+            pricing
+                .iter()
+                .map(|p| (p.name.clone(), p.start_date, p.end_date, p.price_in_cents))
+                .collect::<Vec<_>>(),
+            template_pricing
+                .iter()
+                .map(|p| (
+                    p.name.clone(),
+                    p.start_date + offset,
+                    p.end_date + offset,
+                    p.price_in_cents
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(event_series.events(connection).unwrap().len(), 3);
+
+    // Existing occurrences are not recreated
+    assert!(event_series
+        .generate_occurrences(Some(user.id), connection)
+        .unwrap()
+        .is_empty());
+
+    // Extending the rule adds the new dates only
+    let event_series = event_series
+        .update(
+            Some(user.id),
+            EventSeriesEditableAttributes {
+                recurrence_rule: Some("FREQ=WEEKLY;COUNT=4".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let occurrences = event_series.generate_occurrences(Some(user.id), connection).unwrap();
+    assert_eq!(occurrences.len(), 1);
+    assert_eq!(
+        occurrences[0].event_start,
+        event.event_start.map(|d| d + Duration::weeks(3))
+    );
+
+    // Deleted occurrences are not recreated
+    occurrences[0].clone().delete(user.id, connection).unwrap();
+    assert!(event_series
+        .generate_occurrences(Some(user.id), connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(event_series.events(connection).unwrap().len(), 3);
+}
+
+#[test]
+fn generate_occurrences_copies_holds_and_codes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_template(&project);
+    let template_ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let hold = project
+        .create_hold()
+        .with_event(&event)
+        .with_ticket_type_id(template_ticket_type.id)
+        .with_quantity(5)
+        .finish();
+    let code = project
+        .create_code()
+        .with_event(&event)
+        .for_ticket_type(&template_ticket_type)
+        .finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=2".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    assert_eq!(occurrences.len(), 1);
+    let occurrence = &occurrences[0];
+    let ticket_type = &occurrence.ticket_types(true, None, connection).unwrap()[0];
+
+    let holds = Hold::find_for_event(occurrence.id, false, connection).unwrap();
+    assert_eq!(holds.len(), 1);
+    assert_eq!(holds[0].name, hold.name);
+    assert_eq!(holds[0].redemption_code, hold.redemption_code);
+    assert_eq!(holds[0].ticket_type_id, ticket_type.id);
+    assert_eq!(holds[0].quantity(connection).unwrap(), (5, 5));
+
+    let codes = Code::find_for_event(occurrence.id, None, connection).unwrap();
+    assert_eq!(codes.len(), 1);
+    let new_code = &codes[0].display_code;
+    assert_eq!(new_code.name, code.name);
+    assert_eq!(new_code.redemption_codes, vec![code.redemption_code.clone()]);
+    assert_eq!(new_code.ticket_type_ids, vec![ticket_type.id]);
+    assert_eq!(new_code.start_date, Some(code.start_date + Duration::weeks(1)));
+    assert_eq!(new_code.end_date, Some(code.end_date + Duration::weeks(1)));
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = create_template(&project);
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=2".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let event_series = event_series
+        .update(
+            Some(user.id),
+            EventSeriesEditableAttributes {
+                name: Some("Daily residency".to_string()),
+                recurrence_rule: Some("freq=daily;count=5".to_string()),
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(event_series.name, "Daily residency");
+    assert_eq!(event_series.recurrence_rule, "FREQ=DAILY;COUNT=5");
+    assert_eq!(event_series.occurrence_starts(connection).unwrap().len(), 5);
+    assert_eq!(
+        DomainEvent::find(
+            Tables::EventSeries,
+            Some(event_series.id),
+            Some(DomainEventTypes::EventSeriesUpdated),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+
+    let result = event_series.update(
+        Some(user.id),
+        EventSeriesEditableAttributes {
+            recurrence_rule: Some("FREQ=DAILY".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("recurrence_rule"));
+                assert_eq!(errors["recurrence_rule"][0].code, "count_or_until_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update_events() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = create_template(&project);
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+
+    // Cancelled occurrences are left alone
+    let cancelled = occurrences[1].clone().cancel(None, connection).unwrap();
+
+    let updated_events = event_series
+        .update_events(
+            Some(user.id),
+            EventEditableAttributes {
+                additional_info: Some(Some("Bring a friend".to_string())),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        updated_events.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![event.id, occurrences[0].id]
+    );
+    for updated_event in updated_events {
+        assert_eq!(updated_event.additional_info, Some("Bring a friend".to_string()));
+    }
+    assert_eq!(
+        Event::find(cancelled.id, connection).unwrap().additional_info,
+        cancelled.additional_info
+    );
+
+    let result = event_series.update_events(
+        Some(user.id),
+        EventEditableAttributes {
+            event_start: Some(event.event_start.unwrap() + Duration::hours(1)),
+            door_time: Some(event.event_start.unwrap()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["event_start"][0].code, "not_editable_for_series");
+                assert_eq!(errors["door_time"][0].code, "not_editable_for_series");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update_events_after_template_has_taken_place() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = create_template(&project);
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    let template_ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let event: Event = diesel::update(events::table.filter(events::id.eq(event.id)))
+        .set(events::event_start.eq(Utc::now().naive_utc() - Duration::days(1)))
+        .get_result(connection)
+        .unwrap();
+
+    let updated_events = event_series
+        .update_events(
+            None,
+            EventEditableAttributes {
+                additional_info: Some(Some("Bring a friend".to_string())),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        updated_events.iter().map(|e| e.id).collect::<Vec<_>>(),
+        occurrences.iter().map(|e| e.id).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Event::find(event.id, connection).unwrap().additional_info,
+        event.additional_info
+    );
+
+    let ticket_types = event_series
+        .update_ticket_types(
+            template_ticket_type.id,
+            None,
+            TicketTypeEditableAttributes {
+                name: Some("VIP".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(ticket_types.len(), 2);
+    assert!(!ticket_types.iter().any(|t| t.id == template_ticket_type.id));
+    assert_eq!(
+        TicketType::find(template_ticket_type.id, connection).unwrap().name,
+        template_ticket_type.name
+    );
+}
+
+#[test]
+fn update_ticket_types() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = create_template(&project);
+    let template_ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+
+    let ticket_types = event_series
+        .update_ticket_types(
+            template_ticket_type.id,
+            Some(user.id),
+            TicketTypeEditableAttributes {
+                name: Some("VIP".to_string()),
+                limit_per_person: Some(4),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(ticket_types.len(), 3);
+    assert_eq!(ticket_types[0].id, template_ticket_type.id);
+    for occurrence in occurrences {
+        let ticket_type = &occurrence.ticket_types(true, None, connection).unwrap()[0];
+        assert_eq!(ticket_type.name, "VIP");
+        assert_eq!(ticket_type.limit_per_person, 4);
+    }
+
+    // Only ticket types of the template can be bulk edited
+    let occurrence_ticket_type = &ticket_types[1];
+    let result = event_series.update_ticket_types(
+        occurrence_ticket_type.id,
+        Some(user.id),
+        TicketTypeEditableAttributes {
+            name: Some("GA".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = event_series.update_ticket_types(
+        template_ticket_type.id,
+        Some(user.id),
+        TicketTypeEditableAttributes {
+            end_date: Some(Some(event.event_start.unwrap())),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["end_date"][0].code, "not_editable_for_series");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn recurrence_rule_parse() {
+    let rule = RecurrenceRule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=FR,TH;COUNT=6").unwrap();
+    assert_eq!(rule.frequency, RecurrenceFrequency::Weekly);
+    assert_eq!(rule.interval, 2);
+    assert_eq!(rule.count, Some(6));
+    assert_eq!(rule.until, None);
+    assert_eq!(rule.by_day, vec![Weekday::Thu, Weekday::Fri]);
+
+    let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20200501").unwrap();
+    assert_eq!(rule.until, Some(NaiveDate::from_ymd(2020, 5, 1).and_hms(23, 59, 59)));
+    let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20200501T100000Z").unwrap();
+    assert_eq!(rule.until, Some(NaiveDate::from_ymd(2020, 5, 1).and_hms(10, 0, 0)));
+
+    let invalid_rules = vec![
+        ("COUNT=2", "required"),
+        ("FREQ=YEARLY;COUNT=2", "unsupported_frequency"),
+        ("FREQ=DAILY", "count_or_until_required"),
+        ("FREQ=DAILY;COUNT=2;UNTIL=20200501", "count_or_until_required"),
+        ("FREQ=DAILY;COUNT=0", "invalid_count"),
+        ("FREQ=DAILY;INTERVAL=0;COUNT=2", "invalid_interval"),
+        ("FREQ=DAILY;UNTIL=tomorrow", "invalid_until"),
+        ("FREQ=WEEKLY;BYDAY=XX;COUNT=2", "invalid_by_day"),
+        ("FREQ=MONTHLY;BYDAY=MO;COUNT=2", "unsupported_by_day"),
+        ("FREQ=DAILY;BYHOUR=10;COUNT=2", "unsupported_rule_part"),
+    ];
+    for (rule, code) in invalid_rules {
+        assert_eq!(RecurrenceRule::parse(rule).unwrap_err().code, code, "{}", rule);
+    }
+}
+
+#[test]
+fn recurrence_rule_occurrences() {
+    // Thursday
+    let start = NaiveDate::from_ymd(2020, 4, 16).and_hms(20, 0, 0);
+
+    let rule = RecurrenceRule::parse("FREQ=DAILY;INTERVAL=2;COUNT=3").unwrap();
+    assert_eq!(
+        rule.occurrences(start).unwrap(),
+        vec![start, start + Duration::days(2), start + Duration::days(4)]
+    );
+
+    let rule = RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=TH,SA;COUNT=4").unwrap();
+    assert_eq!(
+        rule.occurrences(start).unwrap(),
+        vec![
+            start,
+            start + Duration::days(2),
+            start + Duration::days(7),
+            start + Duration::days(9),
+        ]
+    );
+
+    let rule = RecurrenceRule::parse("FREQ=WEEKLY;UNTIL=20200507").unwrap();
+    assert_eq!(
+        rule.occurrences(start).unwrap(),
+        vec![
+            start,
+            start + Duration::weeks(1),
+            start + Duration::weeks(2),
+            start + Duration::weeks(3)
+        ]
+    );
+
+    // Months without the 31st are skipped
+    let start = NaiveDate::from_ymd(2020, 1, 31).and_hms(20, 0, 0);
+    let rule = RecurrenceRule::parse("FREQ=MONTHLY;COUNT=3").unwrap();
+    assert_eq!(
+        rule.occurrences(start).unwrap(),
+        vec![
+            start,
+            NaiveDate::from_ymd(2020, 3, 31).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 5, 31).and_hms(20, 0, 0),
+        ]
+    );
+
+    let rule = RecurrenceRule::parse("FREQ=DAILY;COUNT=400").unwrap();
+    assert_eq!(rule.occurrences(start).unwrap_err().code, "too_many_occurrences");
+}
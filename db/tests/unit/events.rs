@@ -599,6 +599,7 @@ fn eligible_for_deletion() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1103,6 +1104,7 @@ fn delete() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1840,6 +1842,7 @@ fn guest_list() {
             "nowhere",
             TransferMessageType::Email,
             new_user.id,
+            &TestProject::wallet_encryption_keys(),
             connection,
         )
         .unwrap();
@@ -2314,6 +2317,7 @@ fn get_sales_by_date_range() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2340,6 +2344,7 @@ fn get_sales_by_date_range() {
                 ticket_type_id: ticket_type.id,
                 quantity: 5,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -2493,6 +2498,7 @@ fn find_by_order_item_ids() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2517,6 +2523,7 @@ fn find_by_order_item_ids() {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
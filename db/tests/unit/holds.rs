@@ -200,6 +200,7 @@ fn quantity_and_children_quantity() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -568,6 +569,7 @@ fn update_with_validation_errors() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: comp.redemption_code,
+            seat_ids: None,
         }],
         false,
         false,
@@ -619,11 +621,13 @@ fn remove_available_quantity() {
                 ticket_type_id: ticket_type.id,
                 quantity: 4,
                 redemption_code: hold.redemption_code.clone(),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: child_hold.redemption_code.clone(),
+                seat_ids: None,
             },
         ],
         false,
@@ -649,6 +653,7 @@ fn remove_available_quantity() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -1,9 +1,11 @@
+pub mod access_zones;
 pub mod activities;
 pub mod announcement_engagements;
 pub mod announcements;
 pub mod artists;
 pub mod assets;
 pub mod broadcasts;
+pub mod bundles;
 pub mod codes;
 pub mod collection_items;
 pub mod collections;
@@ -16,6 +18,7 @@ pub mod domain_events;
 pub mod event_artists;
 pub mod event_interest;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod event_users;
 pub mod events;
 pub mod external_logins;
@@ -27,6 +30,7 @@ pub mod holds;
 pub mod notes;
 pub mod order_items;
 pub mod orders;
+pub mod organization_api_keys;
 pub mod organization_interactions;
 pub mod organization_invites;
 pub mod organization_users;
@@ -35,24 +39,34 @@ pub mod organizations;
 pub mod paging;
 pub mod payment_methods;
 pub mod payments;
+pub mod products;
 pub mod push_notification_tokens;
 pub mod refund_items;
 pub mod refunded_tickets;
 pub mod refunds;
 pub mod regions;
+pub mod report_exports;
 pub mod reports;
+pub mod seat_maps;
 pub mod services;
 pub mod settlement_adjustments;
 pub mod settlement_entries;
 pub mod settlements;
 pub mod slugs;
 pub mod stages;
+pub mod tax_rates;
 pub mod temporary_users;
 pub mod ticket_instances;
 pub mod ticket_pricing;
+pub mod ticket_scans;
 pub mod ticket_type_codes;
 pub mod ticket_types;
 pub mod transfer_tickets;
 pub mod transfers;
+pub mod user_mfa_challenges;
+pub mod user_sessions;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
+pub mod wallets;
+pub mod webhook_deliveries;
@@ -847,6 +847,7 @@ fn main_event_id() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             true,
@@ -900,6 +901,7 @@ fn try_refresh_expired_cart() {
                 ticket_type_id: ticket_type.id,
                 quantity: 99,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -967,6 +969,7 @@ fn try_refresh_expired_cart() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1011,6 +1014,7 @@ fn try_refresh_expired_cart() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1252,6 +1256,7 @@ fn find_or_create_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1363,6 +1368,7 @@ fn set_remove_expiry() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1411,6 +1417,7 @@ fn update_fees() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1487,6 +1494,7 @@ fn update_fees() {
             ticket_type_id: comp.ticket_type_id,
             quantity: 2,
             redemption_code: comp.redemption_code,
+            seat_ids: None,
         }],
         false,
         true,
@@ -1505,6 +1513,226 @@ fn update_fees() {
         .is_none());
 }
 
+#[test]
+fn update_fees_and_discounts_with_taxes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let tax_rate = project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_rate_percent(10.0)
+        .applies_to_fees()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let event_fee_item = items.iter().find(|i| i.item_type == OrderItemTypes::EventFees).unwrap();
+
+    // Ticket, per unit fee and event fee are each taxed
+    let tax_items: Vec<&OrderItem> = items.iter().filter(|i| i.item_type == OrderItemTypes::Tax).collect();
+    assert_eq!(tax_items.len(), 3);
+    for parent in &[order_item, &fee_item, event_fee_item] {
+        let tax_item = parent.find_tax_item(connection).unwrap().unwrap();
+        let (unit_price_in_cents, tax_in_cents) = tax_rate.calculate(parent.unit_price_in_cents);
+        assert_eq!(tax_item.quantity, parent.quantity);
+        assert_eq!(tax_item.unit_price_in_cents, unit_price_in_cents);
+        assert_eq!(tax_item.tax_in_cents, tax_in_cents);
+        assert_eq!(tax_item.tax_rate_id, Some(tax_rate.id));
+        assert_eq!(tax_item.event_id, Some(event.id));
+    }
+    let expected_total = (order_item.unit_price_in_cents + tax_rate.calculate(order_item.unit_price_in_cents).0) * 2
+        + (fee_item.unit_price_in_cents + tax_rate.calculate(fee_item.unit_price_in_cents).0) * 2
+        + event_fee_item.unit_price_in_cents
+        + tax_rate.calculate(event_fee_item.unit_price_in_cents).0;
+    assert_eq!(cart.calculate_total(connection).unwrap(), expected_total);
+
+    // Regenerating does not duplicate tax items
+    cart.update_fees_and_discounts(connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    assert_eq!(items.iter().filter(|i| i.item_type == OrderItemTypes::Tax).count(), 3);
+
+    // Fees are no longer taxed
+    tax_rate
+        .update(
+            TaxRateEditableAttributes {
+                applies_to_fees: Some(false),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    cart.update_fees_and_discounts(connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    let tax_items: Vec<&OrderItem> = items.iter().filter(|i| i.item_type == OrderItemTypes::Tax).collect();
+    assert_eq!(tax_items.len(), 1);
+    assert_eq!(tax_items[0].parent_id, Some(order_item.id));
+
+    // Removing the tickets removes their taxes
+    cart.update_quantities(user.id, &[], false, true, connection).unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tax)
+        .is_none());
+}
+
+#[test]
+fn update_fees_and_discounts_with_inclusive_taxes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_rate_percent(10.0)
+        .finish();
+    let venue_tax_rate = project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_name("VAT")
+        .with_rate_percent(20.0)
+        .inclusive()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+
+    // Venue rate is used and nothing is added to the total
+    assert_eq!(tax_item.tax_rate_id, Some(venue_tax_rate.id));
+    assert_eq!(tax_item.unit_price_in_cents, 0);
+    assert_eq!(
+        tax_item.tax_in_cents,
+        venue_tax_rate.calculate(order_item.unit_price_in_cents).1
+    );
+    assert!(tax_item.tax_in_cents > 0);
+    assert_eq!(tax_item.description(connection).unwrap(), "VAT".to_string());
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        order_item.unit_price_in_cents * 2
+    );
+}
+
+#[test]
+fn refund_with_taxes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_rate_percent(10.0)
+        .applies_to_fees()
+        .finish();
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let items = order.items(&connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tax_item = order_item.find_tax_item(connection).unwrap().unwrap();
+    let fee_tax_item = fee_item.find_tax_item(connection).unwrap().unwrap();
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+
+    // Tax items are not refunded directly
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: tax_item.id,
+        ticket_instance_id: None,
+    }];
+    assert_eq!(
+        DatabaseError::business_process_error(
+            "Tax order items can not be refunded, they are refunded with their parent item",
+        ),
+        order.refund(&refund_items, user.id, None, false, connection)
+    );
+
+    // Refunding one ticket refunds one unit of tax on the ticket and its fee
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (refund, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(
+        amount,
+        order_item.unit_price_in_cents
+            + fee_item.unit_price_in_cents
+            + tax_item.unit_price_in_cents
+            + fee_tax_item.unit_price_in_cents
+    );
+    let refund_items = refund.items(connection).unwrap();
+    assert_eq!(refund_items.len(), 4);
+    let found_tax_item = refund_items.iter().find(|ri| ri.order_item_id == tax_item.id).unwrap();
+    assert_eq!(found_tax_item.quantity, 1);
+    assert_eq!(found_tax_item.amount, tax_item.unit_price_in_cents);
+
+    let tax_item = OrderItem::find(tax_item.id, connection).unwrap();
+    assert_eq!(tax_item.refunded_quantity, 1);
+    let fee_tax_item = OrderItem::find(fee_tax_item.id, connection).unwrap();
+    assert_eq!(fee_tax_item.refunded_quantity, 1);
+}
+
 #[test]
 fn refund_can_refund_previously_refunded_and_repurchased_tickets() {
     let project = TestProject::new();
@@ -1530,6 +1758,7 @@ fn refund_can_refund_previously_refunded_and_repurchased_tickets() {
                 ticket_type_id: ticket_types[0].id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1597,11 +1826,13 @@ fn quantity_for_user_for_event() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1643,6 +1874,7 @@ fn quantity_for_user_for_event() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1716,11 +1948,13 @@ fn items() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1779,11 +2013,13 @@ fn tickets() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1852,11 +2088,13 @@ fn items_for_display() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1900,11 +2138,13 @@ fn find_item_by_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -1959,11 +2199,13 @@ fn add_provider_payment() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -2033,11 +2275,13 @@ fn add_credit_card_payment() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -2154,6 +2398,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2182,6 +2427,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2211,6 +2457,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -2244,6 +2491,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -2276,6 +2524,7 @@ fn order_items_in_invalid_state() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2369,11 +2618,13 @@ fn total_paid() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -2445,11 +2696,13 @@ fn clear_invalid_items() {
                 ticket_type_id: ticket_type.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 2,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -2518,6 +2771,7 @@ fn calculate_total() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2532,6 +2786,215 @@ fn calculate_total() {
     assert_eq!(total, cart.calculate_total(connection).unwrap());
 }
 
+#[test]
+fn calculate_company_fee_total() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection).unwrap();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert_eq!(0, cart.calculate_company_fee_total(connection).unwrap());
+
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let per_ticket_company_fee = fee_schedule.get_range(150, connection).unwrap().company_fee_in_cents;
+    // Company share of the per ticket fees and of the event fee
+    assert_eq!(
+        2 * per_ticket_company_fee + 100,
+        cart.calculate_company_fee_total(connection).unwrap()
+    );
+}
+
+#[test]
+fn mark_disputed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+
+    let settlement_adjustment = order
+        .mark_disputed("du_1", 1500, true, json!({"reason": "fraudulent"}), connection)
+        .unwrap()
+        .unwrap();
+    assert!(order.disputed_at.is_some());
+    assert_eq!(settlement_adjustment.settlement_id, settlement.id);
+    assert_eq!(settlement_adjustment.amount_in_cents, 1500);
+    assert_eq!(
+        settlement_adjustment.settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    let tickets = order.tickets(None, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    assert!(tickets.iter().all(|t| t.status == TicketInstanceStatus::Nullified));
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::OrderDisputeCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Repeated notifications for the same dispute are ignored
+    assert!(order
+        .mark_disputed("du_1", 1500, true, json!({"reason": "fraudulent"}), connection)
+        .unwrap()
+        .is_none());
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 1);
+}
+
+#[test]
+fn mark_disputed_without_pending_settlement() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+
+    assert!(order
+        .mark_disputed("du_1", 1500, false, json!(null), connection)
+        .unwrap()
+        .is_none());
+    assert!(order.disputed_at.is_some());
+    let tickets = order.tickets(None, connection).unwrap();
+    assert!(tickets.iter().all(|t| t.status == TicketInstanceStatus::Purchased));
+}
+
+#[test]
+fn close_dispute() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .is_paid()
+        .finish();
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    order
+        .mark_disputed("du_1", 1500, false, json!(null), connection)
+        .unwrap();
+
+    // Lost disputes keep the chargeback
+    assert!(order
+        .close_dispute("du_1", false, json!({"status": "lost"}), connection)
+        .unwrap()
+        .is_none());
+
+    let settlement_adjustment = order
+        .close_dispute("du_1", true, json!({"status": "won"}), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(settlement_adjustment.settlement_id, settlement.id);
+    assert_eq!(
+        settlement_adjustment.settlement_adjustment_type,
+        SettlementAdjustmentTypes::ManualCredit
+    );
+    assert_eq!(settlement_adjustment.amount_in_cents, 1500);
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 2);
+
+    // Repeated notifications do not credit the chargeback again
+    assert!(order
+        .close_dispute("du_1", true, json!({"status": "won"}), connection)
+        .unwrap()
+        .is_none());
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 2);
+    let domain_events = DomainEvent::find(
+        Tables::Orders,
+        Some(order.id),
+        Some(DomainEventTypes::OrderDisputeClosed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 3);
+}
+
+#[test]
+fn close_dispute_without_chargeback() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let mut order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .is_paid()
+        .finish();
+    order
+        .mark_disputed("du_1", 1500, false, json!(null), connection)
+        .unwrap();
+
+    // The chargeback was not recorded as there was no pending settlement at the time
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    assert!(order
+        .close_dispute("du_1", true, json!({"status": "won"}), connection)
+        .unwrap()
+        .is_none());
+    assert!(settlement.adjustments(connection).unwrap().is_empty());
+}
+
 #[test]
 fn lock_version() {
     let project = TestProject::new();
@@ -2689,6 +3152,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2715,6 +3179,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 4,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2727,6 +3192,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -2757,6 +3223,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2778,6 +3245,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code,
+                seat_ids: None,
             }],
             false,
             true,
@@ -2799,6 +3267,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code.clone(),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2811,6 +3280,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         true,
@@ -2845,6 +3315,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: hold.redemption_code.clone(),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2868,6 +3339,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 3,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         true,
@@ -2913,6 +3385,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2935,6 +3408,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2957,6 +3431,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             }],
             false,
             true,
@@ -2969,6 +3444,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -3004,6 +3480,7 @@ fn update_quantities_check_limits() {
                 ticket_type_id: ticket_type.id,
                 quantity: 3,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             }],
             false,
             true,
@@ -3027,6 +3504,7 @@ fn update_quantities_check_limits() {
             ticket_type_id: ticket_type.id,
             quantity: 3,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -3068,6 +3546,7 @@ fn add_tickets() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3093,6 +3572,7 @@ fn add_tickets() {
             ticket_type_id: ticket.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3151,6 +3631,7 @@ fn add_tickets_below_min_fee() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3178,6 +3659,7 @@ fn add_tickets_below_min_fee() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3216,6 +3698,7 @@ fn events() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3226,6 +3709,128 @@ fn events() {
     assert_eq!(vec![event], cart.events(connection).unwrap());
 }
 
+#[test]
+fn currencies() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let eur_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_currency("EUR")
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let eur_ticket_type = &eur_event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(vec!["USD".to_string()], cart.currencies(connection).unwrap());
+
+    cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: eur_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(
+        vec!["EUR".to_string(), "USD".to_string()],
+        cart.currencies(connection).unwrap()
+    );
+}
+
+#[test]
+fn set_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("EUR").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let usd_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_currency("USD")
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    assert_eq!(event.currency, "EUR");
+    let user = project.create_user().finish();
+    let mut cart = project.create_order().for_user(&user).for_event(&event).finish();
+    assert_eq!(cart.currency, None);
+
+    assert_eq!(cart.set_currency(connection).unwrap(), "EUR");
+    assert_eq!(cart.currency, Some("EUR".to_string()));
+
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let usd_ticket_type = &usd_event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: usd_ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+                seat_ids: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let result = cart.set_currency(connection);
+    assert_eq!(
+        result.unwrap_err().cause,
+        Some("Could not complete this checkout because it contains items in more than one currency".to_string())
+    );
+}
+
 #[test]
 fn purchase_metadata() {
     let project = TestProject::new();
@@ -3251,6 +3856,7 @@ fn purchase_metadata() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3315,6 +3921,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3365,6 +3972,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -3393,6 +4001,7 @@ fn items_valid_for_purchase() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -3433,6 +4042,7 @@ fn partially_visible_order() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3493,6 +4103,7 @@ fn details() {
             ticket_type_id: ticket_type.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3953,6 +4564,7 @@ fn details() {
         "nowhere",
         TransferMessageType::Email,
         user3.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -4095,6 +4707,7 @@ fn refund() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -4114,6 +4727,7 @@ fn refund() {
         "nowhere",
         TransferMessageType::Email,
         user.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -4278,6 +4892,7 @@ fn organizations() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4302,6 +4917,7 @@ fn payments() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4361,6 +4977,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4387,6 +5004,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4400,6 +5018,7 @@ fn add_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 12,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4432,6 +5051,7 @@ fn clear_cart() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4481,6 +5101,7 @@ fn replace_tickets_for_box_office() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4509,6 +5130,7 @@ fn replace_tickets_for_box_office() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         true,
         true,
@@ -4550,6 +5172,7 @@ fn replace_tickets() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4575,6 +5198,7 @@ fn replace_tickets() {
             ticket_type_id: ticket.id,
             quantity: 15,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -4617,6 +5241,7 @@ fn replace_tickets_with_code_pricing() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -4646,6 +5271,7 @@ fn replace_tickets_with_code_pricing() {
             ticket_type_id: ticket_type.id,
             quantity: 15,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         true,
@@ -4677,6 +5303,7 @@ fn deleted_hold_not_applied() {
             ticket_type_id,
             quantity: 1,
             redemption_code,
+            seat_ids: None,
         }],
         false,
         true,
@@ -4700,6 +5327,7 @@ fn remove_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4726,6 +5354,7 @@ fn remove_tickets() {
                 ticket_type_id: ticket_type.id,
                 quantity: 6,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4746,6 +5375,7 @@ fn remove_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 0,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4770,6 +5400,7 @@ fn clear_tickets() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4803,6 +5434,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 8,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4819,6 +5451,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 4,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4836,6 +5469,7 @@ fn remove_tickets_with_increment() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -4873,6 +5507,7 @@ fn find_item() {
                 ticket_type_id: ticket_type.id,
                 quantity: 5,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4887,6 +5522,7 @@ fn find_item() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -4968,6 +5604,7 @@ fn has_items() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5011,6 +5648,7 @@ fn calculate_cart_total() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5027,6 +5665,7 @@ fn calculate_cart_total() {
             ticket_type_id: ticket.id,
             quantity: 30,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5063,6 +5702,7 @@ fn add_external_payment() {
             ticket_type_id: ticket.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5133,6 +5773,7 @@ fn add_external_payment_for_expired_code() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -5429,6 +6070,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5443,6 +6085,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type2.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5481,6 +6124,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type3.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5516,6 +6160,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type4.id,
             quantity: 2,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5543,6 +6188,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type5.id,
             quantity: 1,
             redemption_code: Some(code.redemption_code.clone()),
+            seat_ids: None,
         }],
         false,
         false,
@@ -5566,6 +6212,7 @@ fn for_display_with_invalid_items() {
             ticket_type_id: ticket_type5.id,
             quantity: 1,
             redemption_code: hold.redemption_code.clone(),
+            seat_ids: None,
         }],
         false,
         false,
@@ -5729,11 +6376,13 @@ fn validate_record() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         false,
@@ -5788,6 +6437,7 @@ fn for_display_with_organization_id_and_event_id_filters() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5880,6 +6530,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket1.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -5904,6 +6555,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket2.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -5927,6 +6579,7 @@ fn adding_event_fees() {
             ticket_type_id: ticket3.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         true,
@@ -0,0 +1,236 @@
+use chrono::{Duration, Utc};
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use uuid::Uuid;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let (new_api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgWrite, Scopes::OrgRead, Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+
+    assert!(OrganizationApiKey::is_api_key(&key));
+    assert!(key.starts_with(&api_key.key_prefix));
+    assert_ne!(api_key.key_hash, key);
+    assert_eq!(api_key.get_scopes(), vec![Scopes::OrgRead, Scopes::OrgWrite]);
+    assert!(api_key.is_active());
+
+    let domain_events = DomainEvent::find(
+        Tables::Organizations,
+        Some(organization.id),
+        Some(DomainEventTypes::OrganizationApiKeyCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+    let user = project.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::TokenRefresh],
+        vec![other_event.id],
+        None,
+    );
+
+    match new_api_key.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("scopes"));
+                assert_eq!(errors["scopes"][0].code, "invalid");
+                assert!(errors.contains_key("event_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![],
+        vec![],
+        None,
+    );
+    match new_api_key.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(errors["scopes"][0].code, "required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_by_key() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let (new_api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+
+    assert_eq!(OrganizationApiKey::find_by_key(&key, connection).unwrap(), api_key);
+    assert!(OrganizationApiKey::find_by_key(&api_key.key_prefix, connection).is_err());
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        other_organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    new_api_key.commit(connection).unwrap();
+
+    assert_eq!(
+        OrganizationApiKey::find_for_organization(organization.id, connection).unwrap(),
+        vec![api_key]
+    );
+}
+
+#[test]
+fn allows() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let user = project.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::EventWrite],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    assert!(api_key.allows(Scopes::EventWrite, organization.id, None));
+    assert!(api_key.allows(Scopes::EventWrite, organization.id, Some(event.id)));
+    assert!(!api_key.allows(Scopes::EventDelete, organization.id, None));
+    assert!(!api_key.allows(Scopes::EventWrite, Uuid::new_v4(), None));
+
+    // Event restricted keys only pass checks made for those events
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::EventWrite],
+        vec![event.id],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    assert!(api_key.allows(Scopes::EventWrite, organization.id, Some(event.id)));
+    assert!(!api_key.allows(Scopes::EventWrite, organization.id, Some(Uuid::new_v4())));
+    assert!(!api_key.allows(Scopes::EventWrite, organization.id, None));
+
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::EventWrite],
+        vec![],
+        Some(Utc::now().naive_utc() - Duration::minutes(1)),
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    assert!(!api_key.is_active());
+    assert!(!api_key.allows(Scopes::EventWrite, organization.id, None));
+}
+
+#[test]
+fn record_usage() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+    assert!(api_key.last_used_at.is_none());
+
+    let api_key = api_key.record_usage(connection).unwrap();
+    assert!(api_key.last_used_at.is_some());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let (new_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        user.id,
+        "Integration".to_string(),
+        vec![Scopes::OrgRead],
+        vec![],
+        None,
+    );
+    let api_key = new_api_key.commit(connection).unwrap();
+
+    let api_key = api_key.revoke(Some(&user), connection).unwrap();
+    assert!(!api_key.is_active());
+    assert!(!api_key.allows(Scopes::OrgRead, organization.id, None));
+
+    let domain_events = DomainEvent::find(
+        Tables::Organizations,
+        Some(organization.id),
+        Some(DomainEventTypes::OrganizationApiKeyRevoked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].user_id, Some(user.id));
+}
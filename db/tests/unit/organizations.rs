@@ -112,6 +112,55 @@ fn timezone() {
     assert_eq!(organization.timezone().unwrap(), pt_timezone);
 }
 
+#[test]
+fn currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("CAD").finish();
+    assert_eq!(organization.currency, "CAD");
+    let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection).unwrap();
+    assert_eq!(fee_schedule.currency, "CAD");
+
+    let result = organization.update(
+        OrganizationEditableAttributes {
+            currency: Some("euro".to_string()),
+            ..Default::default()
+        },
+        None,
+        &"encryption_key".to_string(),
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"][0].code, "currency");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                currency: Some("EUR".to_string()),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization.currency, "EUR");
+    let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection).unwrap();
+    assert_eq!(fee_schedule.currency, "EUR");
+
+    // Events default to the currency of their organization
+    let event = project.create_event().with_organization(&organization).finish();
+    assert_eq!(event.currency, "EUR");
+}
+
 #[test]
 fn can_process_settlements() {
     let project = TestProject::new();
@@ -169,6 +218,7 @@ fn create() {
 
     let mut organization = Organization::create("Organization", fee_schedule.id);
     organization.sendgrid_api_key = Some("A_Test_Key".to_string());
+    organization.braintree_private_key = Some("A_Braintree_Key".to_string());
 
     let mut organization = organization
         .commit(None, &"encryption_key".to_string(), None, connection)
@@ -181,8 +231,10 @@ fn create() {
     assert_eq!(slug.slug_type, SlugTypes::Organization);
 
     assert_ne!(organization.sendgrid_api_key, Some("A_Test_Key".to_string()));
+    assert_ne!(organization.braintree_private_key, Some("A_Braintree_Key".to_string()));
     organization.decrypt(&"encryption_key".to_string()).unwrap();
     assert_eq!(organization.sendgrid_api_key, Some("A_Test_Key".to_string()));
+    assert_eq!(organization.braintree_private_key, Some("A_Braintree_Key".to_string()));
 
     let updated_fee_schedule = FeeSchedule::find(fee_schedule.id, connection).unwrap();
     assert_eq!(updated_fee_schedule.organization_id, organization.id);
@@ -504,6 +556,7 @@ fn find_by_order_item_ids() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -517,6 +570,7 @@ fn find_by_order_item_ids() {
                 ticket_type_id: ticket_type2.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -575,6 +629,7 @@ fn has_fan() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -2400,6 +2455,7 @@ fn regenerate_interaction_data() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -2412,6 +2468,7 @@ fn regenerate_interaction_data() {
         "nowhere",
         TransferMessageType::Email,
         user.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -2748,6 +2805,7 @@ fn search_fans() {
         "nowhere",
         TransferMessageType::Email,
         order_user.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -2758,6 +2816,7 @@ fn search_fans() {
         "nowhere",
         TransferMessageType::Email,
         previous_transfer_user.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -2767,6 +2826,7 @@ fn search_fans() {
         "nowhere",
         TransferMessageType::Email,
         transfer_user.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -3131,6 +3191,7 @@ fn credit_card_fees() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3159,6 +3220,7 @@ fn credit_card_fees() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -3178,3 +3240,40 @@ fn credit_card_fees() {
         pre_cc_fee_total + (pre_cc_fee_total as f32 * (5f32 / 100f32)).round() as i64
     );
 }
+
+#[test]
+pub fn get_scopes_for_user_requiring_mfa() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let owner = project.create_user().finish();
+    let owner_with_mfa = project
+        .create_user()
+        .with_mfa(&TestProject::mfa_encryption_keys())
+        .finish();
+    let organization = project
+        .create_organization()
+        .with_member(&owner, Roles::OrgOwner)
+        .with_member(&owner_with_mfa, Roles::OrgOwner)
+        .finish();
+
+    let scopes = organization.get_scopes_for_user(&owner, connection).unwrap();
+    assert!(scopes.contains(&Scopes::OrderRefund));
+    assert!(scopes.contains(&Scopes::OrderMakeExternalPayment));
+
+    let organization = project
+        .create_organization()
+        .with_member(&owner, Roles::OrgOwner)
+        .with_member(&owner_with_mfa, Roles::OrgOwner)
+        .requiring_mfa_for_financial_scopes()
+        .finish();
+    assert!(organization.require_mfa_for_financial_scopes);
+
+    let scopes = organization.get_scopes_for_user(&owner, connection).unwrap();
+    assert!(!scopes.contains(&Scopes::OrderRefund));
+    assert!(!scopes.contains(&Scopes::OrderMakeExternalPayment));
+    assert!(scopes.contains(&Scopes::OrgWrite));
+
+    let scopes = organization.get_scopes_for_user(&owner_with_mfa, connection).unwrap();
+    assert!(scopes.contains(&Scopes::OrderRefund));
+    assert!(scopes.contains(&Scopes::OrderMakeExternalPayment));
+}
@@ -13,6 +13,7 @@ fn for_display() {
         true,
         "cus_example".into(),
         "abc".into(),
+        None,
     )
     .commit(user.id, connection)
     .unwrap();
@@ -38,6 +39,7 @@ fn create() {
         true,
         "cus_example".into(),
         "abc".into(),
+        None,
     )
     .commit(user.id, connection)
     .unwrap();
@@ -73,6 +75,7 @@ fn update() {
     .is_empty());
 
     let payment_method_parameters = PaymentMethodEditableAttributes {
+        provider: None,
         provider_data: Some("test".into()),
     };
     let updated_payment_method = payment_method
@@ -1,3 +1,5 @@
+use chrono::prelude::*;
+use chrono::Duration;
 use db::dev::TestProject;
 use db::models::*;
 
@@ -208,3 +210,94 @@ fn mark_cancelled() {
         true
     )
 }
+
+#[test]
+fn find_by_external_reference() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+    let found_payment = Payment::find_by_external_reference(PaymentProviders::External, "Test", connection).unwrap();
+    assert_eq!(payment, found_payment);
+    assert!(Payment::find_by_external_reference(PaymentProviders::Stripe, "Test", connection).is_err());
+}
+
+#[test]
+fn mark_requires_action() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .with_status(PaymentStatus::RequiresAction)
+        .finish();
+    payment.mark_requires_action(Some(user.id), connection).unwrap();
+
+    let order = Order::find(payment.order_id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::PendingAuthentication);
+    // The cart is kept until the payment is authenticated
+    assert_eq!(
+        Order::find_cart_for_user(user.id, connection).unwrap().map(|c| c.id),
+        Some(order.id)
+    );
+    let expected_expiry = Utc::now().naive_utc() + Duration::minutes(PAYMENT_AUTHENTICATION_EXPIRY_MINUTES);
+    assert!(order.expires_at.unwrap() <= expected_expiry);
+    assert!(order.expires_at.unwrap() > expected_expiry - Duration::minutes(1));
+}
+
+#[test]
+fn mark_authentication_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .with_status(PaymentStatus::RequiresAction)
+        .finish();
+    payment.mark_requires_action(Some(user.id), connection).unwrap();
+    payment
+        .mark_authentication_failed(json!({"status": "requires_payment_method"}), Some(user.id), connection)
+        .unwrap();
+
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Cancelled);
+    let order = Order::find(payment.order_id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+
+    // Only payments awaiting authentication can fail it
+    assert!(payment
+        .mark_authentication_failed(json!(null), Some(user.id), connection)
+        .is_err());
+}
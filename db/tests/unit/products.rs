@@ -0,0 +1,559 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::dates;
+use db::utils::errors::ErrorCode::ValidationError;
+use db::utils::errors::*;
+use uuid::Uuid;
+
+fn create_product(project: &TestProject, event: &Event, requires_pickup: bool) -> Product {
+    Product::create(
+        event.organization_id,
+        Some(event.id),
+        "Parking pass".to_string(),
+        None,
+        500,
+        10,
+        requires_pickup,
+    )
+    .commit(None, project.get_connection())
+    .unwrap()
+}
+
+fn purchase(project: &TestProject, items: &[UpdateOrderProduct]) -> (User, Order) {
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_product_quantities(user.id, items, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    (user, Order::find(cart.id, connection).unwrap())
+}
+
+fn product_item(product: &Product, product_variant_id: Option<Uuid>, quantity: u32) -> UpdateOrderProduct {
+    UpdateOrderProduct {
+        product_id: product.id,
+        product_variant_id,
+        quantity,
+        event_id: None,
+    }
+}
+
+fn assert_validation_error(result: Result<(), DatabaseError>, field: &str) {
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key(field)),
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let product = Product::create(
+        organization.id,
+        None,
+        "Drink ticket".to_string(),
+        Some("Redeemable at any bar".to_string()),
+        800,
+        100,
+        true,
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(product.organization_id, organization.id);
+    assert_eq!(product.event_id, None);
+    assert_eq!(product.quantity, 100);
+    assert!(product.requires_pickup);
+
+    let domain_events = DomainEvent::find(
+        Tables::Products,
+        Some(product.id),
+        Some(DomainEventTypes::ProductCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].user_id, Some(user.id));
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+
+    let result = Product::create(
+        organization.id,
+        Some(other_event.id),
+        "".to_string(),
+        None,
+        -1,
+        -1,
+        false,
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+                assert_eq!(errors["price_in_cents"][0].code, "invalid_price");
+                assert_eq!(errors["quantity"][0].code, "invalid_quantity");
+                assert_eq!(errors["event_id"][0].code, "invalid_organization");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let event2 = project.create_event().with_organization(&organization).finish();
+    let product = create_product(&project, &event, false);
+    let product2 = create_product(&project, &event2, false);
+    let organization_product = Product::create(organization.id, None, "T-shirt".to_string(), None, 2000, 5, true)
+        .commit(None, connection)
+        .unwrap();
+
+    let found = Product::find_for_organization(organization.id, None, connection).unwrap();
+    assert_eq!(found.len(), 3);
+    let found = Product::find_for_organization(organization.id, Some(event.id), connection).unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(found.contains(&product));
+    assert!(found.contains(&organization_product));
+    assert!(!found.contains(&product2));
+
+    product.delete(None, connection).unwrap();
+    let found = Product::find_for_organization(organization.id, Some(event.id), connection).unwrap();
+    assert_eq!(found, vec![organization_product]);
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let product = create_product(&project, &event, false);
+
+    let product = product
+        .update(
+            ProductEditableAttributes {
+                price_in_cents: Some(750),
+                requires_pickup: Some(true),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(product.price_in_cents, 750);
+    assert!(product.requires_pickup);
+    assert_eq!(product.name, "Parking pass".to_string());
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Products,
+            Some(product.id),
+            Some(DomainEventTypes::ProductUpdated),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn update_variants() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let product = create_product(&project, &event, true);
+
+    let variants = product
+        .update_variants(
+            &[
+                UpdateProductVariant {
+                    name: "S".to_string(),
+                    quantity: 5,
+                },
+                UpdateProductVariant {
+                    name: "M".to_string(),
+                    quantity: 8,
+                },
+            ],
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(variants.len(), 2);
+    let small = variants.iter().find(|v| v.name == "S").unwrap().clone();
+    assert_eq!(product.available(Some(small.id), connection).unwrap(), 5);
+    assert_eq!(product.available(None, connection).unwrap(), 13);
+
+    // Variants are matched by name so existing variants keep their id
+    let variants = product
+        .update_variants(
+            &[UpdateProductVariant {
+                name: "S".to_string(),
+                quantity: 3,
+            }],
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(variants.len(), 1);
+    assert_eq!(variants[0].id, small.id);
+    assert_eq!(variants[0].quantity, 3);
+
+    // Variants that have been ordered can not be removed
+    purchase(&project, &[product_item(&product, Some(small.id), 1)]);
+    let result = product.update_variants(
+        &[UpdateProductVariant {
+            name: "L".to_string(),
+            quantity: 3,
+        }],
+        None,
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Variant 'S' has been ordered and can not be removed")
+    );
+
+    let result = product.update_variants(
+        &[
+            UpdateProductVariant {
+                name: "S".to_string(),
+                quantity: 3,
+            },
+            UpdateProductVariant {
+                name: "S".to_string(),
+                quantity: 1,
+            },
+        ],
+        None,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert_eq!(errors["name"][0].code, "duplicate"),
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn available() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let product = create_product(&project, &event, false);
+    assert_eq!(product.available(None, connection).unwrap(), 10);
+
+    // Units held by a cart are unavailable until the cart expires
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_product_quantities(user.id, &[product_item(&product, None, 3)], connection)
+        .unwrap();
+    assert_eq!(product.available(None, connection).unwrap(), 7);
+
+    cart.set_expiry(None, Some(dates::now().add_seconds(-10).finish()), true, connection)
+        .unwrap();
+    assert_eq!(product.available(None, connection).unwrap(), 10);
+
+    purchase(&project, &[product_item(&product, None, 4)]);
+    assert_eq!(product.available(None, connection).unwrap(), 6);
+    assert_eq!(product.for_display(connection).unwrap().available, 6);
+}
+
+#[test]
+fn update_product_quantities() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let product = create_product(&project, &event, false);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    cart.update_product_quantities(user.id, &[product_item(&product, None, 2)], connection)
+        .unwrap();
+    let items: Vec<OrderItem> = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Products)
+        .collect();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].quantity, 2);
+    assert_eq!(items[0].unit_price_in_cents, 500);
+    assert_eq!(items[0].event_id, Some(event.id));
+    assert!(cart.expires_at.is_some());
+    assert_eq!(cart.calculate_total(connection).unwrap(), 1000);
+
+    // Setting the quantity again replaces the existing item
+    cart.update_product_quantities(user.id, &[product_item(&product, None, 4)], connection)
+        .unwrap();
+    let items: Vec<OrderItem> = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Products)
+        .collect();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].quantity, 4);
+
+    // Products live alongside tickets in the cart
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(
+        cart.items(connection)
+            .unwrap()
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets || i.item_type == OrderItemTypes::Products)
+            .count(),
+        2
+    );
+
+    // Inventory can not be exceeded
+    let result = cart.update_product_quantities(user.id, &[product_item(&product, None, 11)], connection);
+    assert_validation_error(result, "quantity");
+
+    // Replacing the cart removes products
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .all(|i| i.item_type != OrderItemTypes::Products));
+}
+
+#[test]
+fn update_product_quantities_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let other_event = project.create_event().finish();
+    let product = create_product(&project, &event, true);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Products not tied to an event need one in the cart
+    let organization_product = Product::create(organization.id, None, "T-shirt".to_string(), None, 2000, 5, true)
+        .commit(None, connection)
+        .unwrap();
+    let result = cart.update_product_quantities(user.id, &[product_item(&organization_product, None, 1)], connection);
+    assert_validation_error(result, "event_id");
+    let result = cart.update_product_quantities(
+        user.id,
+        &[UpdateOrderProduct {
+            event_id: Some(other_event.id),
+            ..product_item(&organization_product, None, 1)
+        }],
+        connection,
+    );
+    assert_validation_error(result, "event_id");
+    cart.update_product_quantities(
+        user.id,
+        &[UpdateOrderProduct {
+            event_id: Some(event.id),
+            ..product_item(&organization_product, None, 1)
+        }],
+        connection,
+    )
+    .unwrap();
+
+    // Products with variants need a variant selected
+    let variants = product
+        .update_variants(
+            &[UpdateProductVariant {
+                name: "XL".to_string(),
+                quantity: 2,
+            }],
+            None,
+            connection,
+        )
+        .unwrap();
+    let result = cart.update_product_quantities(user.id, &[product_item(&product, None, 1)], connection);
+    assert_validation_error(result, "product_variant_id");
+    let other_variants = organization_product
+        .update_variants(
+            &[UpdateProductVariant {
+                name: "XL".to_string(),
+                quantity: 2,
+            }],
+            None,
+            connection,
+        )
+        .unwrap();
+    let result = cart.update_product_quantities(
+        user.id,
+        &[product_item(&product, Some(other_variants[0].id), 1)],
+        connection,
+    );
+    assert_validation_error(result, "product_variant_id");
+    cart.update_product_quantities(user.id, &[product_item(&product, Some(variants[0].id), 2)], connection)
+        .unwrap();
+    assert_eq!(product.available(Some(variants[0].id), connection).unwrap(), 0);
+
+    product.delete(None, connection).unwrap();
+    let result =
+        cart.update_product_quantities(user.id, &[product_item(&product, Some(variants[0].id), 1)], connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Product is no longer available")
+    );
+}
+
+#[test]
+fn redeem_and_refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let product = create_product(&project, &event, true);
+    let (user, mut order) = purchase(&project, &[product_item(&product, None, 2)]);
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+
+    let order_item = order_item.redeem_product(1, Some(user.id), connection).unwrap();
+    assert_eq!(order_item.redeemed_quantity, 1);
+    assert_eq!(
+        DomainEvent::find(
+            Tables::Products,
+            Some(product.id),
+            Some(DomainEventTypes::ProductRedeemed),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+
+    // The unit that has not been picked up can be refunded, the one that has can not
+    let refund_item = RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: None,
+    };
+    let (_, amount) = order
+        .refund(&[refund_item.clone()], user.id, None, false, connection)
+        .unwrap();
+    assert_eq!(amount, 500);
+    let result = order.refund(&[refund_item], user.id, None, false, connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Products that have been picked up can not be refunded")
+    );
+
+    let order_item = OrderItem::find(order_item.id, connection).unwrap();
+    let result = order_item.redeem_product(1, Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("quantity")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let report = Report::product_sales_report(Some(event.id), None, connection).unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].product_id, product.id);
+    assert_eq!(report[0].quantity_sold, 2);
+    assert_eq!(report[0].quantity_refunded, 1);
+    assert_eq!(report[0].quantity_redeemed, 1);
+    assert_eq!(report[0].sales_in_cents, 500);
+}
+
+#[test]
+fn redeem_product_with_stale_order_item() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let product = create_product(&project, &event, true);
+    let (user, order) = purchase(&project, &[product_item(&product, None, 1)]);
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+
+    // A second scanner holding the same order item can not pick up the unit again
+    order_item.redeem_product(1, Some(user.id), connection).unwrap();
+    let result = order_item.redeem_product(1, Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("quantity")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+    assert_eq!(OrderItem::find(order_item.id, connection).unwrap().redeemed_quantity, 1);
+}
+
+#[test]
+fn redeem_product_not_requiring_pickup() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let product = create_product(&project, &event, false);
+    let (user, order) = purchase(&project, &[product_item(&product, None, 1)]);
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Products)
+        .unwrap();
+
+    let result = order_item.redeem_product(1, Some(user.id), connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Product does not require pickup")
+    );
+}
@@ -0,0 +1,237 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::schema::report_exports;
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "transaction_details".to_string(),
+        ReportExportFormat::Csv,
+        json!({"report": "transaction_details"}),
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(report_export.organization_id, organization.id);
+    assert_eq!(report_export.user_id, user.id);
+    assert_eq!(report_export.status, ReportExportStatus::Pending);
+    assert_eq!(report_export.parameters, json!({"report": "transaction_details"}));
+    assert!(report_export.content.is_none());
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::ReportExports),
+        Some(report_export.id),
+        DomainActionTypes::ProcessReportExport,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+}
+
+#[test]
+fn mark_completed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "scan_count".to_string(),
+        ReportExportFormat::Xlsx,
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let report_export = report_export.set_content(&[1, 2], connection).unwrap();
+    report_export.append_content(&[3], connection).unwrap();
+    let (report_export, download_token) = report_export.mark_completed(3, connection).unwrap();
+    assert_eq!(report_export.status, ReportExportStatus::Completed);
+    assert_eq!(report_export.content, Some(vec![1, 2, 3]));
+    assert_eq!(report_export.row_count, Some(3));
+    assert!(report_export.completed_at.is_some());
+    assert!(report_export.expires_at.unwrap() > Utc::now().naive_utc() + Duration::days(6));
+    assert_ne!(report_export.download_token_hash, Some(download_token));
+}
+
+#[test]
+fn mark_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "scan_count".to_string(),
+        ReportExportFormat::Csv,
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Partially written files are removed
+    let report_export = report_export.set_content(&[1, 2, 3], connection).unwrap();
+    let report_export = report_export
+        .mark_failed("event_id parameter is required".to_string(), connection)
+        .unwrap();
+    assert_eq!(report_export.status, ReportExportStatus::Failed);
+    assert!(report_export.content.is_none());
+    assert_eq!(
+        report_export.error_message,
+        Some("event_id parameter is required".to_string())
+    );
+}
+
+#[test]
+fn content_chunk() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "promo_code".to_string(),
+        ReportExportFormat::Csv,
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap();
+    assert!(report_export.content_chunk(0, 2, connection).unwrap().is_empty());
+
+    let report_export = report_export.set_content(&[1, 2, 3, 4, 5], connection).unwrap();
+    assert_eq!(report_export.content_chunk(0, 2, connection).unwrap(), vec![1, 2]);
+    assert_eq!(report_export.content_chunk(2, 2, connection).unwrap(), vec![3, 4]);
+    assert_eq!(report_export.content_chunk(4, 2, connection).unwrap(), vec![5]);
+    assert!(report_export.content_chunk(5, 2, connection).unwrap().is_empty());
+}
+
+#[test]
+fn find_for_download() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "promo_code".to_string(),
+        ReportExportFormat::Csv,
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Not available until the export has been built
+    assert!(ReportExport::find_for_download(report_export.id, "token", connection).is_err());
+
+    report_export.set_content(b"Event Name\n", connection).unwrap();
+    let (report_export, download_token) = report_export.mark_completed(0, connection).unwrap();
+    let found = ReportExport::find_for_download(report_export.id, &download_token, connection).unwrap();
+    assert_eq!(found.id, report_export.id);
+    // The file is read in chunks as it is downloaded
+    assert!(found.content.is_none());
+    assert!(ReportExport::find_for_download(report_export.id, "wrong-token", connection).is_err());
+
+    diesel::update(&report_export)
+        .set(report_exports::expires_at.eq(Some(Utc::now().naive_utc() - Duration::minutes(1))))
+        .execute(connection)
+        .unwrap();
+    assert!(ReportExport::find_for_download(report_export.id, &download_token, connection).is_err());
+}
+
+#[test]
+fn file_name() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let report_export = ReportExport::create(
+        organization.id,
+        user.id,
+        "transaction_details".to_string(),
+        ReportExportFormat::Xlsx,
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        report_export.file_name(),
+        format!("transaction_details_{}.xlsx", report_export.created_at.format("%Y%m%d"))
+    );
+}
+
+#[test]
+fn purge_expired() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let mut exports = Vec::new();
+    for _ in 0..2 {
+        let report_export = ReportExport::create(
+            organization.id,
+            user.id,
+            "promo_code".to_string(),
+            ReportExportFormat::Csv,
+            json!({}),
+        )
+        .commit(connection)
+        .unwrap();
+        report_export.set_content(b"Event Name\n", connection).unwrap();
+        let (report_export, _) = report_export.mark_completed(0, connection).unwrap();
+        exports.push(report_export);
+    }
+    diesel::update(&exports[0])
+        .set(report_exports::expires_at.eq(Some(Utc::now().naive_utc() - Duration::minutes(1))))
+        .execute(connection)
+        .unwrap();
+
+    assert_eq!(ReportExport::purge_expired(connection).unwrap(), 1);
+    let expired = ReportExport::find(exports[0].id, connection).unwrap();
+    assert!(expired.content.is_none());
+    assert_eq!(expired.status, ReportExportStatus::Completed);
+    let current = ReportExport::find(exports[1].id, connection).unwrap();
+    assert_eq!(current.content, Some(b"Event Name\n".to_vec()));
+
+    // Already purged exports are skipped
+    assert_eq!(ReportExport::purge_expired(connection).unwrap(), 0);
+}
+
+#[test]
+fn create_next_purge_expired_domain_action() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    assert!(
+        DomainAction::upcoming_domain_action(None, None, DomainActionTypes::PurgeExpiredReportExports, connection)
+            .unwrap()
+            .is_none()
+    );
+
+    ReportExport::create_next_purge_expired_domain_action(connection).unwrap();
+    let domain_action =
+        DomainAction::upcoming_domain_action(None, None, DomainActionTypes::PurgeExpiredReportExports, connection)
+            .unwrap()
+            .unwrap();
+    assert_eq!(
+        domain_action.scheduled_at,
+        Utc::now().naive_utc().date().and_hms(0, 0, 0) + Duration::days(1)
+    );
+
+    assert_eq!(domain_action.status, DomainActionStatus::Pending);
+    assert!(ReportExport::create_next_purge_expired_domain_action(connection).is_err());
+}
@@ -73,9 +73,20 @@ fn scan_count_report() {
     assert_eq!(&report_rows.data[0].ticket_type_name, &ticket_types[0].name);
     assert_eq!(report_rows.data[0].scanned_count, 2);
     assert_eq!(report_rows.data[0].not_scanned_count, 8);
+    assert_eq!(report_rows.data[0].entry_count, 2);
+    assert_eq!(report_rows.data[0].exit_count, 0);
     assert_eq!(&report_rows.data[1].ticket_type_name, &ticket_types[1].name);
     assert_eq!(report_rows.data[1].scanned_count, 0);
     assert_eq!(report_rows.data[1].not_scanned_count, 5);
+    assert_eq!(report_rows.data[1].entry_count, 0);
+    assert_eq!(report_rows.data[1].exit_count, 0);
+
+    // Check out one of the redeemed tickets
+    TicketInstance::check_out_ticket(ticket2.id, ticket2.redeem_key.clone().unwrap(), user.id, connection).unwrap();
+    let report_rows = Report::scan_count_report(event.id, 0, 100, connection).unwrap();
+    assert_eq!(report_rows.data[0].scanned_count, 2);
+    assert_eq!(report_rows.data[0].entry_count, 2);
+    assert_eq!(report_rows.data[0].exit_count, 1);
 
     // Refund one of the tickets that was previously redeemed
     let refund_items = vec![RefundItemRequest {
@@ -96,6 +107,8 @@ fn scan_count_report() {
     assert_eq!(&report_rows.data[0].ticket_type_name, &ticket_types[0].name);
     assert_eq!(report_rows.data[0].scanned_count, 1);
     assert_eq!(report_rows.data[0].not_scanned_count, 7);
+    assert_eq!(report_rows.data[0].entry_count, 1);
+    assert_eq!(report_rows.data[0].exit_count, 1);
     assert_eq!(&report_rows.data[1].ticket_type_name, &ticket_types[1].name);
     assert_eq!(report_rows.data[1].scanned_count, 0);
     assert_eq!(report_rows.data[1].not_scanned_count, 5);
@@ -1851,9 +1864,49 @@ fn build_transaction_report_row(
         check_in_source: None,
         headline_artist_alt_genres: None,
         headline_artist_main_genre: None,
+        currency: event.currency.clone(),
+        tax_in_cents: 0,
+        tax_in_cents_total: 0,
     }
 }
 
+#[test]
+fn summary_event_report_taxes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_rate_percent(10.0)
+        .applies_to_fees()
+        .finish();
+    let order = project.create_order().for_event(&event).quantity(2).is_paid().finish();
+
+    let items = order.items(connection).unwrap();
+    let ticket_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let fee_item = ticket_item.find_fee_item(connection).unwrap().unwrap();
+    let event_fee_item = items.iter().find(|i| i.item_type == OrderItemTypes::EventFees).unwrap();
+    let ticket_tax = ticket_item.find_tax_item(connection).unwrap().unwrap();
+    let fee_tax = fee_item.find_tax_item(connection).unwrap().unwrap();
+    let event_fee_tax = event_fee_item.find_tax_item(connection).unwrap().unwrap();
+
+    let result = Report::summary_event_report(event.id, None, None, connection).unwrap();
+    assert_eq!(result.sales.len(), 1);
+    assert_eq!(
+        result.sales[0].total_tax_in_cents,
+        ticket_tax.tax_in_cents * ticket_tax.quantity + fee_tax.tax_in_cents * fee_tax.quantity
+    );
+    assert_eq!(result.other_fees.len(), 1);
+    assert_eq!(result.other_fees[0].total_tax_in_cents, event_fee_tax.tax_in_cents);
+}
+
 #[test]
 fn promo_code_report() {
     let project = TestProject::new();
@@ -1934,11 +1987,13 @@ fn promo_code_report() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             },
         ],
         true,
@@ -1968,11 +2023,13 @@ fn promo_code_report() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
             UpdateOrderItem {
                 ticket_type_id: ticket_type2.id,
                 quantity: 1,
                 redemption_code: Some(code.redemption_code.clone()),
+                seat_ids: None,
             },
         ],
         true,
@@ -2002,6 +2059,7 @@ fn promo_code_report() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: Some(code2.redemption_code.clone()),
+            seat_ids: None,
         }],
         true,
         false,
@@ -2048,3 +2106,152 @@ fn promo_code_report() {
 
     assert_eq!(test_pass_count, 5);
 }
+
+#[test]
+fn waitlist_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(2)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let buyer = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    WaitlistEntry::create(ticket_type.id, user2.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    // One ticket is refunded and offered to the first in line, who buys it
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(tickets[0].id),
+    }];
+    order.refund(&refund_items, buyer.id, None, false, connection).unwrap();
+    let offer = WaitlistEntry::process(ticket_type.id, connection).unwrap().remove(0);
+    let hold = offer.hold(connection).unwrap().unwrap();
+    project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(1)
+        .with_redemption_code(hold.redemption_code.clone().unwrap())
+        .is_paid()
+        .finish();
+
+    let result = Report::waitlist_report(Some(event.id), Some(organization.id), connection).unwrap();
+    assert_eq!(result.len(), 1);
+    let row = &result[0];
+    assert_eq!(row.event_id, event.id);
+    assert_eq!(row.ticket_type_id, ticket_type.id);
+    assert_eq!(row.joined_count, 2);
+    assert_eq!(row.waiting_count, 1);
+    assert_eq!(row.offered_count, 1);
+    assert_eq!(row.purchased_count, 1);
+    assert_eq!(row.expired_count, 0);
+    assert_eq!(row.cancelled_count, 0);
+    assert_eq!(row.tickets_sold, 1);
+    assert_eq!(row.sales_in_cents, order_item.unit_price_in_cents);
+    assert_eq!(row.conversion_rate, 100.0);
+
+    // Other organizations do not see the waitlist
+    let other_organization = project.create_organization().finish();
+    assert!(Report::waitlist_report(None, Some(other_organization.id), connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn event_series_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().with_timezone("UTC".to_string()).finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .finish();
+    let event_series = EventSeries::create(
+        organization.id,
+        event.id,
+        "Weekly residency".to_string(),
+        "FREQ=WEEKLY;COUNT=3".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    let mut order = project.create_order().for_event(&event).quantity(2).is_paid().finish();
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+
+    // Events outside the series are not included
+    let other_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .quantity(5)
+        .is_paid()
+        .finish();
+
+    let result = Report::event_series_report(Some(occurrences[1].id), Some(organization.id), connection).unwrap();
+    assert_eq!(result.len(), 1);
+    let row = &result[0];
+    assert_eq!(row.event_series_id, event_series.id);
+    assert_eq!(row.event_series_name, "Weekly residency");
+    assert_eq!(row.recurrence_rule, "FREQ=WEEKLY;COUNT=3");
+    assert_eq!(row.occurrence_count, 3);
+    assert_eq!(row.upcoming_occurrence_count, 3);
+    assert_eq!(row.first_event_start, event.event_start);
+    assert_eq!(row.last_event_start, occurrences[1].event_start);
+    assert_eq!(row.tickets_sold, 2);
+    assert_eq!(row.tickets_refunded, 0);
+    assert_eq!(row.sales_in_cents, 2 * order_item.unit_price_in_cents);
+
+    // Refunds are rolled up per series
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(tickets[0].id),
+    }];
+    let user = project.create_user().finish();
+    order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    let result = Report::event_series_report(None, Some(organization.id), connection).unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].tickets_sold, 1);
+    assert_eq!(result[0].tickets_refunded, 1);
+    assert_eq!(result[0].sales_in_cents, order_item.unit_price_in_cents);
+
+    // Event not part of a series
+    assert!(
+        Report::event_series_report(Some(other_event.id), Some(organization.id), connection)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,395 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Two rows of four seats in the front section, rows of three in the back section
+fn create_seat_map(venue: &Venue, connection: &PgConnection) -> SeatMap {
+    let seat_map = SeatMap::create(venue.id, None, "Main Floor".to_string())
+        .commit(connection)
+        .unwrap();
+    for (section_rank, (section_name, seats_per_row, price_tier)) in
+        vec![("Front", 4, "A"), ("Back", 3, "B")].into_iter().enumerate()
+    {
+        let section = SeatMapSection::create(seat_map.id, section_name.to_string(), section_rank as i32)
+            .commit(connection)
+            .unwrap();
+        let mut seats = Vec::new();
+        for (row_rank, row_label) in vec!["A", "B"].into_iter().enumerate() {
+            for seat_rank in 0..seats_per_row {
+                seats.push(Seat::create(
+                    section.id,
+                    row_label.to_string(),
+                    row_rank as i32,
+                    (seat_rank + 1).to_string(),
+                    seat_rank,
+                    price_tier.to_string(),
+                ));
+            }
+        }
+        Seat::create_multiple(&seats, connection).unwrap();
+    }
+    seat_map
+}
+
+fn seated_event(project: &TestProject, tickets: u32) -> (Event, TicketType, SeatMap) {
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(tickets)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let seat_map = create_seat_map(&venue, connection);
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("A".to_string(), ticket_type.id);
+    seat_map.assign_to_event(&event, &price_tiers, connection).unwrap();
+    (event, ticket_type, seat_map)
+}
+
+fn available_seat(id: Uuid, seat_map_section_id: Uuid, row_label: &str, seat_rank: i32) -> AvailableSeat {
+    AvailableSeat {
+        id,
+        seat_map_section_id,
+        row_label: row_label.to_string(),
+        seat_rank,
+    }
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let other_venue = project.create_venue().finish();
+    let stage = Stage::create(other_venue.id, "Stage".to_string(), None, None)
+        .commit(connection)
+        .unwrap();
+
+    let seat_map = SeatMap::create(venue.id, None, "Main Floor".to_string())
+        .commit(connection)
+        .unwrap();
+    assert_eq!(seat_map.venue_id, venue.id);
+    assert_eq!(SeatMap::find_by_venue_id(venue.id, connection).unwrap(), vec![seat_map]);
+
+    // Stage must be at the venue
+    let result = SeatMap::create(venue.id, Some(stage.id), "Stage".to_string()).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("stage_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn for_display() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let seat_map = create_seat_map(&venue, connection);
+
+    let display_seat_map = seat_map.for_display(connection).unwrap();
+    assert_eq!(display_seat_map.sections.len(), 2);
+    let front = &display_seat_map.sections[0];
+    assert_eq!(front.name, "Front");
+    assert_eq!(
+        front.rows.iter().map(|row| row.label.as_str()).collect::<Vec<&str>>(),
+        vec!["A", "B"]
+    );
+    assert_eq!(
+        front.rows[0]
+            .seats
+            .iter()
+            .map(|seat| seat.seat_number.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["1", "2", "3", "4"]
+    );
+    assert_eq!(display_seat_map.sections[1].rows[1].seats.len(), 3);
+}
+
+#[test]
+fn assign_to_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (event, ticket_type, seat_map) = seated_event(&project, 10);
+
+    let tier = TicketTypeSeatTier::find_for_ticket_type(ticket_type.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(tier.seat_map_id, seat_map.id);
+    assert_eq!(tier.price_tier, "A");
+
+    // Only the eight seats in price tier A are sold
+    let seats = Seat::find_for_event(event.id, connection).unwrap();
+    assert_eq!(seats.len(), 14);
+    let sold_seats: Vec<&DisplayEventSeat> = seats.iter().filter(|seat| seat.ticket_type_id.is_some()).collect();
+    assert_eq!(sold_seats.len(), 8);
+    assert!(sold_seats
+        .iter()
+        .all(|seat| seat.price_tier == "A" && seat.available && seat.ticket_type_id == Some(ticket_type.id)));
+
+    // Assigning again leaves the seats alone
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("A".to_string(), ticket_type.id);
+    seat_map.assign_to_event(&event, &price_tiers, connection).unwrap();
+    assert_eq!(Seat::find_for_event(event.id, connection).unwrap(), seats);
+
+    // A ticket type keeps its price tier
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("B".to_string(), ticket_type.id);
+    assert!(seat_map.assign_to_event(&event, &price_tiers, connection).is_err());
+}
+
+#[test]
+fn assign_to_event_validations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let seat_map = create_seat_map(&venue, connection);
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(5)
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let other_ticket_type = other_event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    let assert_validation_error = |field: &str, price_tiers: HashMap<String, Uuid>, event: &Event| match seat_map
+        .assign_to_event(event, &price_tiers, connection)
+    {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key(field)),
+            _ => panic!("Expected validation error"),
+        },
+    };
+
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("A".to_string(), other_ticket_type.id);
+    assert_validation_error("seat_map_id", price_tiers.clone(), &other_event);
+    assert_validation_error("price_tiers", price_tiers, &event);
+
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("Z".to_string(), ticket_type.id);
+    assert_validation_error("price_tiers", price_tiers, &event);
+
+    // Five tickets are not enough for the eight seats of price tier A
+    let mut price_tiers = HashMap::new();
+    price_tiers.insert("A".to_string(), ticket_type.id);
+    assert_validation_error("price_tiers", price_tiers, &event);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let seat_map = create_seat_map(&venue, connection);
+    assert!(!seat_map.is_used(connection).unwrap());
+    seat_map.destroy(connection).unwrap();
+    assert!(SeatMap::find(seat_map.id, connection).is_err());
+
+    let (_, _, seat_map) = seated_event(&project, 10);
+    assert!(seat_map.is_used(connection).unwrap());
+    assert!(seat_map.destroy(connection).is_err());
+}
+
+#[test]
+fn best_available() {
+    let front = Uuid::new_v4();
+    let back = Uuid::new_v4();
+    let seats = vec![
+        available_seat(Uuid::new_v4(), front, "A", 0),
+        available_seat(Uuid::new_v4(), front, "A", 2),
+        available_seat(Uuid::new_v4(), front, "B", 0),
+        available_seat(Uuid::new_v4(), front, "B", 1),
+        available_seat(Uuid::new_v4(), front, "B", 2),
+        available_seat(Uuid::new_v4(), back, "A", 0),
+    ];
+
+    // Best seat first
+    assert_eq!(Seat::best_available(&seats, 1), vec![seats[0].id]);
+    // Front row has a gap so the adjacent seats of the second row are picked
+    assert_eq!(
+        Seat::best_available(&seats, 3),
+        vec![seats[2].id, seats[3].id, seats[4].id]
+    );
+    // Without enough adjacent seats the best seats are picked
+    assert_eq!(
+        Seat::best_available(&seats, 4),
+        vec![seats[0].id, seats[1].id, seats[2].id, seats[3].id]
+    );
+    assert!(Seat::best_available(&seats, 7).is_empty());
+}
+
+#[test]
+fn reserve_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (event, ticket_type, _) = seated_event(&project, 10);
+    let user = project.create_user().finish();
+    let seats = Seat::find_for_event(event.id, connection).unwrap();
+
+    // Best available seats are reserved without a selection
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    let mut reserved_seat_ids: Vec<Option<Uuid>> = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .iter()
+        .map(|ticket| ticket.seat_id)
+        .collect();
+    reserved_seat_ids.sort();
+    let mut expected_seat_ids = vec![Some(seats[0].id), Some(seats[1].id)];
+    expected_seat_ids.sort();
+    assert_eq!(reserved_seat_ids, expected_seat_ids);
+
+    // Selecting seats replaces the reserved seats
+    let selected_seat_ids = vec![seats[5].id, seats[6].id, seats[7].id];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 3,
+            redemption_code: None,
+            seat_ids: Some(selected_seat_ids.clone()),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let order_item = cart.items(connection).unwrap().remove(0);
+    assert_eq!(order_item.quantity, 3);
+    let mut reserved_seat_ids: Vec<Uuid> = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .iter()
+        .filter_map(|ticket| ticket.seat_id)
+        .collect();
+    reserved_seat_ids.sort();
+    let mut expected_seat_ids = selected_seat_ids.clone();
+    expected_seat_ids.sort();
+    assert_eq!(reserved_seat_ids, expected_seat_ids);
+    let seats = Seat::find_for_event(event.id, connection).unwrap();
+    assert!(seats[0].available);
+    assert!(!seats[5].available);
+
+    // Reserved seats can't be selected by someone else
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    let result = cart2.update_quantities(
+        user2.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: Some(vec![seats[5].id]),
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("seat_ids")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // A seat is needed for each ticket
+    let result = cart2.update_quantities(
+        user2.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+            seat_ids: Some(vec![seats[0].id]),
+        }],
+        false,
+        false,
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn reserve_seats_without_reserved_seating() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    let result = cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+            seat_ids: Some(vec![Uuid::new_v4()]),
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("seat_ids")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn seat_on_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (event, _, _) = seated_event(&project, 10);
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+
+    let (_, _, display_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
+    assert_eq!(display_ticket.seat_section, Some("Front".to_string()));
+    assert_eq!(display_ticket.seat_row, Some("A".to_string()));
+    assert_eq!(display_ticket.seat_number, Some("1".to_string()));
+
+    let redeemable_ticket = TicketInstance::show_redeemable_ticket(ticket.id, connection).unwrap();
+    assert_eq!(redeemable_ticket.seat_section, Some("Front".to_string()));
+    assert_eq!(redeemable_ticket.seat_row, Some("A".to_string()));
+    assert_eq!(redeemable_ticket.seat_number, Some("1".to_string()));
+
+    let (guests, _) = event.guest_list(None, &None, None, connection).unwrap();
+    assert_eq!(guests[0].ticket.seat_number, Some("1".to_string()));
+}
@@ -10,6 +10,7 @@ use diesel;
 use diesel::prelude::*;
 use diesel::sql_types;
 use diesel::RunQueryDsl;
+use uuid::Uuid;
 
 #[test]
 fn finalize_settlements() {
@@ -17,7 +18,13 @@ fn finalize_settlements() {
     let connection = project.get_connection();
     let settlement = project.create_settlement().finish();
     let settlement2 = project.create_settlement().finish();
-    Settlement::finalize_settlements(connection).unwrap();
+    let already_finalized_settlement = project.create_settlement().finalized().finish();
+    let finalized_settlements = Settlement::finalize_settlements(connection).unwrap();
+    let finalized_settlement_ids: Vec<Uuid> = finalized_settlements.iter().map(|s| s.id).collect();
+    assert_eq!(finalized_settlement_ids.len(), 2);
+    assert!(finalized_settlement_ids.contains(&settlement.id));
+    assert!(finalized_settlement_ids.contains(&settlement2.id));
+    assert!(!finalized_settlement_ids.contains(&already_finalized_settlement.id));
 
     let settlement = Settlement::find(settlement.id, connection).unwrap();
     let settlement2 = Settlement::find(settlement2.id, connection).unwrap();
@@ -25,6 +32,132 @@ fn finalize_settlements() {
     assert_eq!(SettlementStatus::FinalizedSettlement, settlement2.status);
 }
 
+#[test]
+fn find_pending_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    assert!(
+        Settlement::find_pending_for_organization(organization.id, "USD", connection)
+            .unwrap()
+            .is_none()
+    );
+
+    project
+        .create_settlement()
+        .with_organization(&organization)
+        .finalized()
+        .finish();
+    assert!(
+        Settlement::find_pending_for_organization(organization.id, "USD", connection)
+            .unwrap()
+            .is_none()
+    );
+
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    assert_eq!(
+        Settlement::find_pending_for_organization(organization.id, "USD", connection).unwrap(),
+        Some(settlement)
+    );
+    assert!(
+        Settlement::find_pending_for_organization(organization.id, "EUR", connection)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn stripe_transfer_amount_in_cents() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let settlement = project.create_settlement().finish();
+    assert_eq!(settlement.stripe_transfer_amount_in_cents(connection).unwrap(), 0);
+
+    // Charged on the platform account
+    let stripe_order = project.create_order().quantity(2).is_paid().finish();
+    // Paid at the box office
+    let box_office_order = project.create_order().quantity(2).box_office_order().is_paid().finish();
+    // Destination charge, already moved to the connected account
+    let user = project.create_user().finish();
+    let mut destination_order = project.create_order().for_user(&user).quantity(2).finish();
+    let total = destination_order.calculate_total(connection).unwrap();
+    destination_order
+        .add_credit_card_payment(
+            user.id,
+            total,
+            PaymentProviders::Stripe,
+            "ch_1GqIC8HYLQbJDcKE".to_string(),
+            PaymentStatus::Completed,
+            json!({"transfer_data": {"destination": "acct_1GqIC8HYLQbJDcKE"}}),
+            connection,
+        )
+        .unwrap();
+
+    for order in &[&stripe_order, &box_office_order, &destination_order] {
+        diesel::update(orders::table.filter(orders::id.eq(order.id)))
+            .set(orders::settlement_id.eq(settlement.id))
+            .execute(connection)
+            .unwrap();
+    }
+
+    let expected = stripe_order.calculate_total(connection).unwrap()
+        - stripe_order.calculate_company_fee_total(connection).unwrap();
+    assert!(expected > 0);
+    assert_eq!(
+        settlement.stripe_transfer_amount_in_cents(connection).unwrap(),
+        expected
+    );
+
+    // Chargebacks are recovered from the transfer, including those on destination charges
+    project
+        .create_settlement_adjustment()
+        .with_settlement(&settlement)
+        .with_settlement_adjustment_type(SettlementAdjustmentTypes::Chargeback)
+        .with_amount_in_cents(150)
+        .finish();
+    project
+        .create_settlement_adjustment()
+        .with_settlement(&settlement)
+        .with_settlement_adjustment_type(SettlementAdjustmentTypes::ManualCredit)
+        .with_amount_in_cents(50)
+        .finish();
+    assert_eq!(
+        settlement.stripe_transfer_amount_in_cents(connection).unwrap(),
+        expected - 100
+    );
+}
+
+#[test]
+fn set_stripe_transfer_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let settlement = project.create_settlement().finish();
+    assert!(settlement
+        .set_stripe_transfer_id("tr_1GqIC8HYLQbJDcKE".to_string(), connection)
+        .is_err());
+
+    let settlement = Settlement::finalize_settlements(connection).unwrap().remove(0);
+    let settlement = settlement
+        .set_stripe_transfer_error("Insufficient funds".to_string(), connection)
+        .unwrap();
+    assert_eq!(settlement.stripe_transfer_error, Some("Insufficient funds".to_string()));
+
+    let settlement = settlement
+        .set_stripe_transfer_id("tr_1GqIC8HYLQbJDcKE".to_string(), connection)
+        .unwrap();
+    assert_eq!(settlement.stripe_transfer_id, Some("tr_1GqIC8HYLQbJDcKE".to_string()));
+    assert_eq!(settlement.stripe_transfer_error, None);
+    assert_eq!(
+        Settlement::find(settlement.id, connection).unwrap().stripe_transfer_id,
+        Some("tr_1GqIC8HYLQbJDcKE".to_string())
+    );
+
+    // A settlement is only transferred once
+    assert!(settlement
+        .set_stripe_transfer_id("tr_2GqIC8HYLQbJDcKE".to_string(), connection)
+        .is_err());
+}
+
 #[test]
 fn create_next_finalize_settlements_domain_action() {
     let project = TestProject::new();
@@ -124,7 +257,9 @@ fn process_settlement_for_organization() {
     .unwrap();
     assert_eq!(0, domain_events.len());
 
-    let settlement = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    let mut settlements = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    assert_eq!(settlements.len(), 1);
+    let settlement = settlements.remove(0);
     let domain_events = DomainEvent::find(
         Tables::Organizations,
         Some(organization.id),
@@ -159,7 +294,9 @@ fn process_settlement_for_organization() {
     .execute(connection)
     .unwrap();
 
-    let settlement = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    let mut settlements = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    assert_eq!(settlements.len(), 1);
+    let settlement = settlements.remove(0);
     assert_eq!(
         settlement.start_time.timestamp(),
         (old_end_time + Duration::seconds(1)).timestamp()
@@ -168,6 +305,43 @@ fn process_settlement_for_organization() {
     assert_eq!(settlement.end_time.timestamp(), end_time.timestamp());
 }
 
+#[test]
+fn process_settlement_for_organization_in_multiple_currencies() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event_end = organization.next_settlement_date(None).unwrap() - Duration::days(8);
+    let usd_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(event_end - Duration::days(1))
+        .with_event_end(event_end)
+        .finish();
+    let eur_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_currency("EUR")
+        .with_event_start(event_end - Duration::days(1))
+        .with_event_end(event_end)
+        .finish();
+
+    let settlements = Settlement::process_settlement_for_organization(&organization, None, connection).unwrap();
+    assert_eq!(
+        settlements.iter().map(|s| s.currency.as_str()).collect::<Vec<&str>>(),
+        vec!["USD", "EUR"]
+    );
+    assert_eq!(settlements[0].start_time, settlements[1].start_time);
+    assert_eq!(settlements[0].end_time, settlements[1].end_time);
+
+    // Each event is settled by the settlement of its own currency
+    assert!(Event::find(usd_event.id, connection).unwrap().settled_at.is_some());
+    assert!(Event::find(eur_event.id, connection).unwrap().settled_at.is_some());
+    assert_eq!(
+        Settlement::find_pending_for_organization(organization.id, "EUR", connection).unwrap(),
+        Some(settlements[1].clone())
+    );
+}
+
 #[test]
 fn create_post_event_entries() {
     let project = TestProject::new();
@@ -0,0 +1,157 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let tax_rate = TaxRate::create(organization.id, None, "Sales Tax".to_string(), 8.5, false, true)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(tax_rate.organization_id, organization.id);
+    assert_eq!(tax_rate.venue_id, None);
+    assert_eq!(tax_rate.name, "Sales Tax".to_string());
+    assert_eq!(tax_rate.rate_percent, 8.5);
+    assert!(!tax_rate.inclusive);
+    assert!(tax_rate.applies_to_fees);
+
+    // Rate must be a valid percentage
+    let result =
+        TaxRate::create(organization.id, None, "Sales Tax".to_string(), 101.0, false, false).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("rate_percent"));
+                assert_eq!(errors["rate_percent"][0].code, "rate_percent_out_of_range");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rate = project.create_tax_rate().finish();
+    assert_eq!(TaxRate::find(tax_rate.id, connection).unwrap(), tax_rate);
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let venue = project.create_venue().finish();
+    let venue_tax_rate = project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .finish();
+    let tax_rate = project.create_tax_rate().with_organization(&organization).finish();
+    project.create_tax_rate().finish();
+
+    assert_eq!(
+        TaxRate::find_for_organization(organization.id, connection).unwrap(),
+        vec![tax_rate, venue_tax_rate]
+    );
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let venue = project.create_venue().finish();
+    let other_venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .finish();
+    assert_eq!(TaxRate::find_for_event(&event, connection).unwrap(), None);
+
+    // Organization default rate
+    let tax_rate = project.create_tax_rate().with_organization(&organization).finish();
+    project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_venue(&other_venue)
+        .finish();
+    assert_eq!(TaxRate::find_for_event(&event, connection).unwrap(), Some(tax_rate));
+
+    // Venue rate takes precedence
+    let venue_tax_rate = project
+        .create_tax_rate()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .finish();
+    assert_eq!(
+        TaxRate::find_for_event(&event, connection).unwrap(),
+        Some(venue_tax_rate)
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rate = project.create_tax_rate().finish();
+
+    let result = tax_rate.update(
+        TaxRateEditableAttributes {
+            rate_percent: Some(-1.0),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("rate_percent"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let tax_rate = tax_rate
+        .update(
+            TaxRateEditableAttributes {
+                name: Some("VAT".to_string()),
+                rate_percent: Some(20.0),
+                inclusive: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(tax_rate.name, "VAT".to_string());
+    assert_eq!(tax_rate.rate_percent, 20.0);
+    assert!(tax_rate.inclusive);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let tax_rate = project.create_tax_rate().finish();
+    assert_eq!(tax_rate.destroy(connection).unwrap(), 1);
+    assert!(TaxRate::find(tax_rate.id, connection).is_err());
+}
+
+#[test]
+fn calculate() {
+    let project = TestProject::new();
+    let tax_rate = project.create_tax_rate().with_rate_percent(10.0).finish();
+    assert_eq!(tax_rate.calculate(1500), (150, 150));
+    assert_eq!(tax_rate.calculate(1234), (123, 123));
+    assert_eq!(tax_rate.calculate(0), (0, 0));
+
+    let tax_rate = project.create_tax_rate().with_rate_percent(20.0).inclusive().finish();
+    assert_eq!(tax_rate.calculate(1200), (0, 200));
+    assert_eq!(tax_rate.calculate(-100), (0, 0));
+}
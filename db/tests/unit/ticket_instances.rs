@@ -12,6 +12,7 @@ use db::dev::times;
 use db::dev::TestProject;
 use db::prelude::*;
 use db::utils::errors::ErrorCode::ValidationError;
+use db::utils::totp;
 
 #[test]
 fn event() {
@@ -168,10 +169,13 @@ fn associate_redeem_key() {
         .pop()
         .unwrap();
     let redeem_key = ticket.redeem_key.clone();
+    let redeem_secret = ticket.redeem_secret.clone();
+    assert!(redeem_secret.is_some());
     ticket.associate_redeem_key(connection).unwrap();
     let ticket = TicketInstance::find(ticket.id, connection).unwrap();
     let new_redeem_key = ticket.redeem_key.clone();
     assert_ne!(redeem_key, new_redeem_key);
+    assert_ne!(redeem_secret, ticket.redeem_secret);
 
     ticket.associate_redeem_key(connection).unwrap();
     let ticket = TicketInstance::find(ticket.id, connection).unwrap();
@@ -626,6 +630,7 @@ fn cant_reserve_more_than_tickets_available() {
         order_item.ticket_type_id.unwrap(),
         None,
         1,
+        None,
         connection,
     );
 
@@ -668,6 +673,7 @@ fn cant_reserve_nullifed_tickets() {
         order_item.ticket_type_id.unwrap(),
         None,
         1,
+        None,
         connection,
     );
 
@@ -715,6 +721,7 @@ fn release() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -838,7 +845,9 @@ fn was_transferred() {
     let receiver_wallet = Wallet::find_default_for_user(user2.id, connection).unwrap();
     let transfer = TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection).unwrap();
     TicketInstance::receive_ticket_transfer(
-        transfer.into_authorization(connection).unwrap(),
+        transfer
+            .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+            .unwrap(),
         &sender_wallet,
         user2.id,
         receiver_wallet.id,
@@ -874,6 +883,7 @@ fn find() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -906,6 +916,10 @@ fn find() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        redeem_secret: None,
+        seat_section: None,
+        seat_row: None,
+        seat_number: None,
     };
     assert_eq!(
         (display_event, None, expected_ticket),
@@ -938,6 +952,7 @@ fn find_show_no_token() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -977,6 +992,10 @@ fn find_show_no_token() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        redeem_secret: None,
+        seat_section: None,
+        seat_row: None,
+        seat_number: None,
     };
     let (found_event, found_user, found_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
     assert_eq!(
@@ -1018,6 +1037,7 @@ fn find_for_user() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1056,6 +1076,7 @@ fn release_tickets() {
                 ticket_type_id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1108,6 +1129,7 @@ fn release_tickets_cancelled_ticket_type() {
                 ticket_type_id: ticket_type.id,
                 quantity: 10,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1174,6 +1196,7 @@ fn mark_as_purchased() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1318,6 +1341,473 @@ fn redeem_ticket() {
     assert_eq!(result, RedeemResults::TicketRedeemSuccess);
 }
 
+#[test]
+fn find_for_scanner_snapshot() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&user2)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let mut tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    tickets.sort_by_key(|t| t.id);
+    let ticket = &tickets[0];
+    let ticket2 = &tickets[1];
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        ticket.redeem_key.clone().unwrap(),
+        user.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    TicketInstance::create_transfer(&user, &[ticket2.id], None, None, false, connection).unwrap();
+
+    let snapshot = TicketInstance::find_for_scanner_snapshot(event.id, "salt", connection).unwrap();
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot[0].id, ticket.id);
+    assert_eq!(snapshot[0].status, TicketInstanceStatus::Redeemed);
+    assert!(!snapshot[0].pending_transfer);
+    assert_eq!(
+        snapshot[0].redeem_key_hash,
+        Some(TicketInstance::hash_redeem_key(
+            "salt",
+            &ticket.redeem_key.clone().unwrap()
+        ))
+    );
+    assert_ne!(snapshot[0].redeem_key_hash, ticket.redeem_key);
+    assert!(snapshot[0].redeem_secret.is_none());
+    assert_eq!(snapshot[1].id, ticket2.id);
+    assert_eq!(snapshot[1].status, TicketInstanceStatus::Purchased);
+    assert!(snapshot[1].pending_transfer);
+
+    // Hashes depend on the salt
+    let snapshot2 = TicketInstance::find_for_scanner_snapshot(event.id, "other salt", connection).unwrap();
+    assert_ne!(snapshot[0].redeem_key_hash, snapshot2[0].redeem_key_hash);
+}
+
+#[test]
+fn find_for_scanner_snapshot_with_rotating_redeem_codes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_rotating_redeem_codes()
+        .finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+
+    // Scanners check rotating codes against the secret, the static key is not accepted
+    let snapshot = TicketInstance::find_for_scanner_snapshot(event.id, "salt", connection).unwrap();
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!(snapshot[0].id, ticket.id);
+    assert!(snapshot[0].redeem_key_hash.is_none());
+    assert_eq!(snapshot[0].redeem_secret, ticket.redeem_secret);
+}
+
+#[test]
+fn redeem_offline_scans() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let door_person = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&user2)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let mut tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = tickets.remove(0);
+    let ticket2 = tickets.remove(0);
+    let other_event_ticket = TicketInstance::find_for_user(user2.id, connection).unwrap().remove(0);
+
+    let now = NaiveDateTime::from_timestamp(Utc::now().timestamp(), 0);
+    let first_scan = now - Duration::minutes(10);
+    let second_scan = now - Duration::minutes(5);
+    let scans = vec![
+        // Same ticket scanned on two devices, uploaded out of order
+        OfflineScan {
+            ticket_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            scanned_at: second_scan,
+            device_id: Some("device-2".to_string()),
+            check_in_source: None,
+            access_zone_id: None,
+        },
+        OfflineScan {
+            ticket_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            scanned_at: first_scan,
+            device_id: Some("device-1".to_string()),
+            check_in_source: None,
+            access_zone_id: None,
+        },
+        OfflineScan {
+            ticket_id: ticket2.id,
+            redeem_key: "WrongKey".to_string(),
+            scanned_at: first_scan,
+            device_id: Some("device-1".to_string()),
+            check_in_source: None,
+            access_zone_id: None,
+        },
+        OfflineScan {
+            ticket_id: other_event_ticket.id,
+            redeem_key: other_event_ticket.redeem_key.clone().unwrap(),
+            scanned_at: first_scan,
+            device_id: Some("device-1".to_string()),
+            check_in_source: None,
+            access_zone_id: None,
+        },
+    ];
+
+    let results = TicketInstance::redeem_offline_scans(event.id, &scans, door_person.id, connection).unwrap();
+    assert_eq!(results.len(), 4);
+    let first = results
+        .iter()
+        .find(|r| r.ticket_id == ticket.id && r.device_id == Some("device-1".to_string()))
+        .unwrap();
+    assert_eq!(first.result, RedeemResults::TicketRedeemSuccess);
+    assert_eq!(first.redeemed_at, Some(first_scan));
+    assert_eq!(first.redeemed_by_user_id, Some(door_person.id));
+    let conflict = results
+        .iter()
+        .find(|r| r.ticket_id == ticket.id && r.device_id == Some("device-2".to_string()))
+        .unwrap();
+    assert_eq!(conflict.result, RedeemResults::TicketAlreadyRedeemed);
+    assert_eq!(conflict.redeemed_at, Some(first_scan));
+    let invalid = results.iter().find(|r| r.ticket_id == ticket2.id).unwrap();
+    assert_eq!(invalid.result, RedeemResults::TicketInvalid);
+    let wrong_event = results.iter().find(|r| r.ticket_id == other_event_ticket.id).unwrap();
+    assert_eq!(wrong_event.result, RedeemResults::TicketInvalid);
+
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(ticket.redeemed_at, Some(first_scan));
+    assert_eq!(ticket.check_in_source, Some(CheckInSource::Scanned));
+    let other_event_ticket = TicketInstance::find(other_event_ticket.id, connection).unwrap();
+    assert_eq!(other_event_ticket.status, TicketInstanceStatus::Purchased);
+
+    // Scans already applied online are reported as conflicts
+    let results = TicketInstance::redeem_offline_scans(event.id, &scans[1..2], door_person.id, connection).unwrap();
+    assert_eq!(results[0].result, RedeemResults::TicketAlreadyRedeemed);
+}
+
+#[test]
+fn redeem_ticket_with_reentry() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let redeem_key = ticket.redeem_key.clone().unwrap();
+    let redeem = |ticket_id| {
+        TicketInstance::redeem_ticket(
+            ticket_id,
+            redeem_key.clone(),
+            user.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap()
+    };
+    let check_out =
+        |ticket_id| TicketInstance::check_out_ticket(ticket_id, redeem_key.clone(), user.id, connection).unwrap();
+
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketRedeemSuccess);
+    // Scanned again while still inside
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketAlreadyRedeemed);
+
+    // No re-entry by default
+    assert_eq!(check_out(ticket.id), RedeemResults::TicketCheckedOut);
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketReentryNotAllowed);
+
+    // Limited re-entry
+    ticket
+        .ticket_type(connection)
+        .unwrap()
+        .update_reentry_policy(ReentryPolicy::Limited, Some(1), connection)
+        .unwrap();
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketReentrySuccess);
+    assert_eq!(check_out(ticket.id), RedeemResults::TicketCheckedOut);
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketReentryNotAllowed);
+
+    // Unlimited re-entry
+    ticket
+        .ticket_type(connection)
+        .unwrap()
+        .update_reentry_policy(ReentryPolicy::Unlimited, None, connection)
+        .unwrap();
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketReentrySuccess);
+    assert_eq!(check_out(ticket.id), RedeemResults::TicketCheckedOut);
+    assert_eq!(redeem(ticket.id), RedeemResults::TicketReentrySuccess);
+
+    let scans = TicketScan::find_for_ticket_instance(ticket.id, connection).unwrap();
+    assert_eq!(
+        scans.iter().map(|s| s.scan_type).collect::<Vec<TicketScanType>>(),
+        vec![
+            TicketScanType::CheckIn,
+            TicketScanType::CheckOut,
+            TicketScanType::CheckIn,
+            TicketScanType::CheckOut,
+            TicketScanType::CheckIn,
+            TicketScanType::CheckOut,
+            TicketScanType::CheckIn,
+        ]
+    );
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+}
+
+#[test]
+fn check_out_ticket() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let redeem_key = ticket.redeem_key.clone().unwrap();
+
+    assert_eq!(
+        TicketInstance::check_out_ticket(ticket.id, "WrongKey".to_string(), user.id, connection).unwrap(),
+        RedeemResults::TicketInvalid
+    );
+    assert_eq!(
+        TicketInstance::check_out_ticket(ticket.id, redeem_key.clone(), user.id, connection).unwrap(),
+        RedeemResults::TicketNotCheckedIn
+    );
+
+    TicketInstance::redeem_ticket(
+        ticket.id,
+        redeem_key.clone(),
+        user.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(
+        TicketInstance::check_out_ticket(ticket.id, redeem_key.clone(), user.id, connection).unwrap(),
+        RedeemResults::TicketCheckedOut
+    );
+    // Already outside
+    assert_eq!(
+        TicketInstance::check_out_ticket(ticket.id, redeem_key.clone(), user.id, connection).unwrap(),
+        RedeemResults::TicketNotCheckedIn
+    );
+
+    let scans = TicketScan::find_for_ticket_instance(ticket.id, connection).unwrap();
+    assert_eq!(scans.len(), 2);
+    assert_eq!(scans[1].scan_type, TicketScanType::CheckOut);
+    assert_eq!(scans[1].event_id, event.id);
+    assert_eq!(scans[1].scanned_by_user_id, Some(user.id));
+    let domain_events = DomainEvent::find(
+        Tables::TicketInstances,
+        Some(ticket.id),
+        Some(DomainEventTypes::TicketInstanceCheckedOut),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn find_for_display_with_rotating_redeem_codes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_rotating_redeem_codes()
+        .finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    // Redeem details are only shown once the event is about to start
+    diesel::sql_query(
+        r#"
+        UPDATE events
+        SET event_start = NOW() + INTERVAL '1 hour'
+        WHERE id = $1;
+        "#,
+    )
+    .bind::<sql_types::Uuid, _>(event.id)
+    .execute(connection)
+    .unwrap();
+
+    // The app is given the secret for rotating codes, never the static key
+    let display_ticket = TicketInstance::find_for_display(ticket.id, connection).unwrap().2;
+    assert!(display_ticket.redeem_key.is_none());
+    assert_eq!(display_ticket.redeem_secret, ticket.redeem_secret);
+    let found_tickets =
+        TicketInstance::find_for_user_for_display(user.id, Some(event.id), None, None, connection).unwrap();
+    assert!(found_tickets[0].1[0].redeem_key.is_none());
+    assert_eq!(found_tickets[0].1[0].redeem_secret, ticket.redeem_secret);
+}
+
+#[test]
+fn redeem_ticket_with_rotating_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_rotating_redeem_codes()
+        .finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = tickets
+        .iter()
+        .find(|t| t.event(connection).unwrap().id == event.id)
+        .unwrap();
+    let other_ticket = tickets
+        .iter()
+        .find(|t| t.event(connection).unwrap().id == other_event.id)
+        .unwrap();
+    let now = Utc::now().timestamp();
+    let code_for = |ticket: &TicketInstance, timestamp: i64| {
+        totp::code_at(ticket.redeem_secret.as_ref().unwrap(), timestamp).unwrap()
+    };
+    let redeem = |ticket: &TicketInstance, code: String| {
+        TicketInstance::redeem_ticket(ticket.id, code, user.id, CheckInSource::Scanned, connection).unwrap()
+    };
+
+    // Scanned static keys are not accepted, the box office can still check in from the guest list
+    let redeem_key = ticket.redeem_key.clone().unwrap();
+    assert!(!ticket.redeem_key_matches(&redeem_key, &event, CheckInSource::Scanned, Utc::now().naive_utc()));
+    assert!(ticket.redeem_key_matches(&redeem_key, &event, CheckInSource::GuestList, Utc::now().naive_utc()));
+    assert_eq!(redeem(ticket, redeem_key.clone()), RedeemResults::TicketInvalid);
+    // Codes outside of the clock skew window have expired
+    assert_eq!(
+        redeem(ticket, code_for(ticket, now - 5 * totp::STEP_SECONDS)),
+        RedeemResults::TicketInvalid
+    );
+    // Events without rotating codes only accept the static key
+    assert_eq!(
+        redeem(other_ticket, code_for(other_ticket, now)),
+        RedeemResults::TicketInvalid
+    );
+
+    assert_eq!(
+        redeem(ticket, code_for(ticket, now)),
+        RedeemResults::TicketRedeemSuccess
+    );
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+}
+
+#[test]
+fn redeem_ticket_for_access_zone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let access_zone = project.create_access_zone().with_event(&event).finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let redeem = || {
+        TicketInstance::redeem_ticket_for_access_zone(
+            ticket.id,
+            ticket.redeem_key.clone().unwrap(),
+            user.id,
+            CheckInSource::Scanned,
+            Some(access_zone.id),
+            connection,
+        )
+        .unwrap()
+    };
+
+    // Ticket type has not been given access to the zone
+    assert_eq!(redeem(), RedeemResults::TicketNotValidForAccessZone);
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Purchased);
+    assert!(TicketScan::find_for_ticket_instance(ticket.id, connection)
+        .unwrap()
+        .is_empty());
+
+    ticket_type
+        .update_access_zones(vec![access_zone.id], connection)
+        .unwrap();
+    assert_eq!(redeem(), RedeemResults::TicketRedeemSuccess);
+    let scans = TicketScan::find_for_ticket_instance(ticket.id, connection).unwrap();
+    assert_eq!(scans.len(), 1);
+    assert_eq!(scans[0].access_zone_id, Some(access_zone.id));
+}
+
 #[test]
 fn organization() {
     let project = TestProject::new();
@@ -1370,6 +1860,7 @@ fn owner() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1500,6 +1991,7 @@ fn create_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1623,6 +2115,7 @@ fn has_pending_transfer() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1664,6 +2157,7 @@ fn receive_ticket_transfer() {
             ticket_type_id: ticket_type.id,
             quantity: 5,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1706,7 +2200,10 @@ fn receive_ticket_transfer() {
     //try receive the wrong number of tickets (too few)
     let transfer = TicketInstance::create_transfer(&user, &ticket_ids, None, None, false, connection).unwrap();
 
-    let mut wrong_auth: TransferAuthorization = transfer.clone().into_authorization(connection).unwrap();
+    let mut wrong_auth: TransferAuthorization = transfer
+        .clone()
+        .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+        .unwrap();
     wrong_auth.num_tickets = 4;
     let receive_auth =
         TicketInstance::receive_ticket_transfer(wrong_auth, &sender_wallet, user2.id, receiver_wallet.id, connection);
@@ -1724,7 +2221,9 @@ fn receive_ticket_transfer() {
 
     //legit receive tickets
     TicketInstance::receive_ticket_transfer(
-        transfer.into_authorization(connection).unwrap(),
+        transfer
+            .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+            .unwrap(),
         &sender_wallet,
         user2.id,
         receiver_wallet.id,
@@ -1767,7 +2266,9 @@ fn receive_ticket_transfer() {
     .execute(connection)
     .unwrap();
     let result = TicketInstance::receive_ticket_transfer(
-        transfer.into_authorization(connection).unwrap(),
+        transfer
+            .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+            .unwrap(),
         &sender_wallet,
         user.id,
         receiver_wallet.id,
@@ -1834,6 +2335,7 @@ fn transfer_to_existing_user() {
         "nowhere",
         TransferMessageType::Email,
         receiver.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
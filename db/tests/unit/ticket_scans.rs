@@ -0,0 +1,154 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let scanned_at = NaiveDateTime::from_timestamp(Utc::now().timestamp(), 0);
+    let scan = TicketScan::create(
+        ticket.id,
+        event.id,
+        TicketScanType::CheckIn,
+        Some(user.id),
+        Some(CheckInSource::Scanned),
+        None,
+        scanned_at,
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(scan.ticket_instance_id, ticket.id);
+    assert_eq!(scan.event_id, event.id);
+    assert_eq!(scan.scan_type, TicketScanType::CheckIn);
+    assert_eq!(scan.scanned_by_user_id, Some(user.id));
+    assert_eq!(scan.check_in_source, Some(CheckInSource::Scanned));
+    assert_eq!(scan.scanned_at, scanned_at);
+}
+
+#[test]
+fn find_for_ticket_instance() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let now = NaiveDateTime::from_timestamp(Utc::now().timestamp(), 0);
+    let check_out = TicketScan::create(
+        ticket.id,
+        event.id,
+        TicketScanType::CheckOut,
+        Some(user.id),
+        None,
+        None,
+        now,
+    )
+    .commit(connection)
+    .unwrap();
+    let check_in = TicketScan::create(
+        ticket.id,
+        event.id,
+        TicketScanType::CheckIn,
+        Some(user.id),
+        Some(CheckInSource::Scanned),
+        None,
+        now - Duration::minutes(30),
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        TicketScan::find_for_ticket_instance(ticket.id, connection).unwrap(),
+        vec![check_in, check_out]
+    );
+}
+
+#[test]
+fn occupancy_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(3)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    assert_eq!(
+        TicketScan::occupancy_for_event(event.id, connection).unwrap(),
+        EventOccupancy {
+            entries: 0,
+            exits: 0,
+            inside: 0
+        }
+    );
+
+    for ticket in &tickets {
+        TicketInstance::redeem_ticket(
+            ticket.id,
+            ticket.redeem_key.clone().unwrap(),
+            user.id,
+            CheckInSource::Scanned,
+            connection,
+        )
+        .unwrap();
+    }
+    TicketInstance::check_out_ticket(
+        tickets[0].id,
+        tickets[0].redeem_key.clone().unwrap(),
+        user.id,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(
+        TicketScan::occupancy_for_event(event.id, connection).unwrap(),
+        EventOccupancy {
+            entries: 3,
+            exits: 1,
+            inside: 2
+        }
+    );
+
+    // Scans for other events are not counted
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let user2 = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&user2)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let other_ticket = TicketInstance::find_for_user(user2.id, connection).unwrap().remove(0);
+    TicketInstance::redeem_ticket(
+        other_ticket.id,
+        other_ticket.redeem_key.clone().unwrap(),
+        user.id,
+        CheckInSource::Scanned,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(TicketScan::occupancy_for_event(event.id, connection).unwrap().inside, 2);
+}
@@ -405,6 +405,7 @@ fn valid_unsold_ticket_count() {
             ticket_type_id: ticket_type.id,
             quantity: 50,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -436,6 +437,7 @@ fn valid_unsold_ticket_count() {
             ticket_type_id: ticket_type.id,
             quantity: 1,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -803,6 +805,7 @@ pub fn remaining_ticket_count() {
                 ticket_type_id: ticket_type.id,
                 quantity: 20,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -818,6 +821,7 @@ pub fn remaining_ticket_count() {
                 ticket_type_id: ticket_type.id,
                 quantity: 16,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -872,6 +876,108 @@ fn update() {
     assert_eq!(updated_ticket_type.end_date_type, TicketTypeEndDateType::Manual);
 }
 
+#[test]
+fn update_reentry_policy() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let event = db.create_event().with_tickets().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    assert_eq!(ticket_type.reentry_policy, ReentryPolicy::None);
+    assert_eq!(ticket_type.reentries_allowed(), Some(0));
+
+    // Limited re-entry requires a limit
+    let result = ticket_type
+        .clone()
+        .update_reentry_policy(ReentryPolicy::Limited, None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("reentry_limit"));
+                assert_eq!(errors["reentry_limit"][0].code, "required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let ticket_type = ticket_type
+        .update_reentry_policy(ReentryPolicy::Limited, Some(2), connection)
+        .unwrap();
+    assert_eq!(ticket_type.reentry_policy, ReentryPolicy::Limited);
+    assert_eq!(ticket_type.reentry_limit, Some(2));
+    assert_eq!(ticket_type.reentries_allowed(), Some(2));
+
+    // Also validated when updated with the other editable attributes
+    let result = ticket_type.clone().update(
+        TicketTypeEditableAttributes {
+            reentry_limit: Some(None),
+            ..Default::default()
+        },
+        None,
+        connection,
+    );
+    assert!(result.is_err());
+
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                reentry_policy: Some(ReentryPolicy::Unlimited),
+                reentry_limit: Some(None),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(ticket_type.reentry_policy, ReentryPolicy::Unlimited);
+    assert_eq!(ticket_type.reentries_allowed(), None);
+}
+
+#[test]
+fn update_access_zones() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let venue = db.create_venue().finish();
+    let event = db.create_event().with_venue(&venue).with_tickets().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let balcony = db.create_access_zone().with_venue(&venue).with_name("Balcony").finish();
+    let backstage = db
+        .create_access_zone()
+        .with_event(&event)
+        .with_name("Backstage")
+        .finish();
+    let other_event_zone = db.create_access_zone().finish();
+    assert!(ticket_type.access_zones(connection).unwrap().is_empty());
+
+    // Zones must belong to the event or its venue
+    let result = ticket_type.update_access_zones(vec![balcony.id, other_event_zone.id], connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("access_zone_ids"));
+                assert_eq!(errors["access_zone_ids"][0].code, "invalid");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+    assert!(ticket_type.access_zones(connection).unwrap().is_empty());
+
+    ticket_type
+        .update_access_zones(vec![balcony.id, backstage.id], connection)
+        .unwrap();
+    assert_eq!(
+        ticket_type.access_zones(connection).unwrap(),
+        vec![backstage.clone(), balcony.clone()]
+    );
+
+    ticket_type.update_access_zones(vec![backstage.id], connection).unwrap();
+    assert_eq!(ticket_type.access_zones(connection).unwrap(), vec![backstage]);
+
+    ticket_type.update_access_zones(Vec::new(), connection).unwrap();
+    assert!(ticket_type.access_zones(connection).unwrap().is_empty());
+}
+
 #[test]
 fn update_rank() {
     let db = TestProject::new();
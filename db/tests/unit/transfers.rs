@@ -28,6 +28,7 @@ fn regenerate_redeem_keys() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -61,6 +62,7 @@ fn contains_redeemed_tickets() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -124,6 +126,7 @@ fn was_retransferred() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -154,6 +157,7 @@ fn was_retransferred() {
         "nowhere",
         TransferMessageType::Email,
         user3.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -235,12 +239,16 @@ fn receive_url() {
     transfer.add_transfer_ticket(ticket.id, connection).unwrap();
 
     assert_eq!(
-        transfer.receive_url("http://example.com", connection).unwrap(),
+        transfer
+            .receive_url("http://example.com", &TestProject::wallet_encryption_keys(), connection)
+            .unwrap(),
         format!(
             "http://example.com/tickets/transfers/receive?sender_user_id={}&transfer_key={}&num_tickets=1&signature={}",
             transfer.source_user_id,
             transfer.transfer_key,
-            transfer.signature(connection).unwrap()
+            transfer
+                .signature(&TestProject::wallet_encryption_keys(), connection)
+                .unwrap()
         )
         .to_string()
     );
@@ -269,9 +277,13 @@ fn into_authorization() {
             transfer_key: transfer.transfer_key,
             sender_user_id: transfer.source_user_id,
             num_tickets: 1,
-            signature: transfer.signature(connection).unwrap(),
+            signature: transfer
+                .signature(&TestProject::wallet_encryption_keys(), connection)
+                .unwrap(),
         },
-        transfer.into_authorization(connection).unwrap()
+        transfer
+            .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+            .unwrap()
     );
 }
 
@@ -810,11 +822,13 @@ fn signature() {
             .as_str(),
     );
     let wallet = Wallet::find_default_for_user(transfer.source_user_id, connection).unwrap();
-    let secret_key = wallet.secret_key;
+    let secret_key = wallet.secret_key(&TestProject::wallet_encryption_keys()).unwrap();
     let expected_signature = convert_bytes_to_hexstring(
         &cryptographic_signature(&message, &convert_hexstring_to_bytes(&secret_key)).unwrap(),
     );
-    let found_signature = transfer.signature(connection).unwrap();
+    let found_signature = transfer
+        .signature(&TestProject::wallet_encryption_keys(), connection)
+        .unwrap();
     assert_eq!(expected_signature, found_signature);
 
     let mut header: String = transfer.transfer_key.to_string();
@@ -1727,6 +1741,7 @@ fn cancel() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1737,6 +1752,7 @@ fn cancel() {
         "nowhere",
         TransferMessageType::Email,
         user3.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1820,6 +1836,7 @@ fn cancel() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -0,0 +1,45 @@
+use db::dev::TestProject;
+use db::models::*;
+
+#[test]
+fn record_failure() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    for failed_attempts in 1..5 {
+        assert_eq!(
+            UserMfaChallenge::record_failure(user.id, "challenge-token", connection).unwrap(),
+            failed_attempts
+        );
+        assert!(!UserMfaChallenge::exhausted(user.id, "challenge-token", connection).unwrap());
+    }
+
+    assert_eq!(
+        UserMfaChallenge::record_failure(user.id, "challenge-token", connection).unwrap(),
+        5
+    );
+    assert!(UserMfaChallenge::exhausted(user.id, "challenge-token", connection).unwrap());
+    // Other challenges are unaffected
+    assert!(!UserMfaChallenge::exhausted(user.id, "other-challenge-token", connection).unwrap());
+}
+
+#[test]
+fn destroy_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    UserMfaChallenge::record_failure(user.id, "challenge-token", connection).unwrap();
+    UserMfaChallenge::record_failure(user2.id, "other-challenge-token", connection).unwrap();
+
+    assert_eq!(UserMfaChallenge::destroy_for_user(user.id, connection).unwrap(), 1);
+    assert_eq!(
+        UserMfaChallenge::record_failure(user.id, "challenge-token", connection).unwrap(),
+        1
+    );
+    assert_eq!(
+        UserMfaChallenge::record_failure(user2.id, "other-challenge-token", connection).unwrap(),
+        2
+    );
+}
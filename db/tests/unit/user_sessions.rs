@@ -0,0 +1,105 @@
+use db::dev::TestProject;
+use db::models::*;
+use uuid::Uuid;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, Some("Test Agent".to_string()))
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(session.user_id, user.id);
+    assert_eq!(session.user_agent, Some("Test Agent".to_string()));
+    assert!(session.is_active());
+    assert_eq!(UserSession::find(session.id, connection).unwrap(), session);
+}
+
+#[test]
+fn find_active_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+    let session2 = UserSession::create(user.id, None).commit(connection).unwrap();
+    UserSession::create(user2.id, None).commit(connection).unwrap();
+
+    let sessions = UserSession::find_active_for_user(user.id, connection).unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert!(sessions.contains(&session));
+    assert!(sessions.contains(&session2));
+
+    session.revoke(connection).unwrap();
+    assert_eq!(
+        UserSession::find_active_for_user(user.id, connection).unwrap(),
+        vec![session2]
+    );
+}
+
+#[test]
+fn rotate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+
+    let rotated = session.rotate(session.current_token_id, connection).unwrap().unwrap();
+    assert_eq!(rotated.id, session.id);
+    assert_ne!(rotated.current_token_id, session.current_token_id);
+    assert!(rotated.is_active());
+
+    let rotated_again = rotated.rotate(rotated.current_token_id, connection).unwrap().unwrap();
+    assert_ne!(rotated_again.current_token_id, rotated.current_token_id);
+}
+
+#[test]
+fn rotate_with_reused_token() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+    let rotated = session.rotate(session.current_token_id, connection).unwrap().unwrap();
+
+    // The original token has already been exchanged so the whole session is revoked
+    assert!(session.rotate(session.current_token_id, connection).unwrap().is_none());
+    assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(session.rotate(rotated.current_token_id, connection).unwrap().is_none());
+
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+    assert!(session.rotate(Uuid::new_v4(), connection).unwrap().is_none());
+    assert!(!UserSession::find(session.id, connection).unwrap().is_active());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None).commit(connection).unwrap();
+
+    let session = session.revoke(connection).unwrap();
+    assert!(!session.is_active());
+    assert!(session.revoked_at.is_some());
+}
+
+#[test]
+fn revoke_all_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    UserSession::create(user.id, None).commit(connection).unwrap();
+    UserSession::create(user.id, None).commit(connection).unwrap();
+    let session = UserSession::create(user2.id, None).commit(connection).unwrap();
+
+    assert_eq!(UserSession::revoke_all_for_user(user.id, connection).unwrap(), 2);
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(UserSession::find(session.id, connection).unwrap().is_active());
+}
@@ -10,11 +10,13 @@ use validator::Validate;
 
 use db::dev::TestProject;
 use db::prelude::*;
-use db::schema::{orders, user_genres};
+use db::schema::{orders, user_genres, users};
 use db::utils::dates;
+use db::utils::encryption::KeyRing;
 use db::utils::errors;
 use db::utils::errors::ErrorCode;
 use db::utils::errors::ErrorCode::ValidationError;
+use db::utils::totp;
 
 #[test]
 fn find_for_authentication() {
@@ -78,6 +80,7 @@ fn is_attending_event() {
         "nowhere",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -140,6 +143,7 @@ fn create_stub() {
         Some(email.clone()),
         Some(phone.clone()),
         None,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -248,6 +252,7 @@ fn transfer_activity_by_event_tickets() {
         "nowhere",
         TransferMessageType::Email,
         user3.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -277,7 +282,9 @@ fn transfer_activity_by_event_tickets() {
     let sender_wallet = Wallet::find_default_for_user(user.id, connection).unwrap();
     let receiver_wallet = Wallet::find_default_for_user(user2.id, connection).unwrap();
     TicketInstance::receive_ticket_transfer(
-        transfer6.into_authorization(connection).unwrap(),
+        transfer6
+            .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+            .unwrap(),
         &sender_wallet,
         user2.id,
         receiver_wallet.id,
@@ -289,7 +296,9 @@ fn transfer_activity_by_event_tickets() {
     let sender_wallet = Wallet::find_default_for_user(user2.id, connection).unwrap();
     let receiver_wallet = Wallet::find_default_for_user(user3.id, connection).unwrap();
     TicketInstance::receive_ticket_transfer(
-        transfer7.into_authorization(connection).unwrap(),
+        transfer7
+            .into_authorization(&TestProject::wallet_encryption_keys(), connection)
+            .unwrap(),
         &sender_wallet,
         user3.id,
         receiver_wallet.id,
@@ -839,7 +848,7 @@ fn commit() {
         phone_number.clone(),
         password,
     )
-    .commit(None, project.get_connection())
+    .commit(None, &TestProject::wallet_encryption_keys(), project.get_connection())
     .unwrap();
 
     assert_eq!(user.first_name, first_name);
@@ -863,8 +872,11 @@ fn commit_duplicate_email() {
     let email = user1.email;
     let phone_number = Some("555-555-5555".to_string());
     let password = "examplePassword";
-    let result =
-        User::create(first_name, last_name, email, phone_number, password).commit(None, project.get_connection());
+    let result = User::create(first_name, last_name, email, phone_number, password).commit(
+        None,
+        &TestProject::wallet_encryption_keys(),
+        project.get_connection(),
+    );
 
     assert_eq!(result.is_err(), true);
     assert_eq!(
@@ -977,6 +989,7 @@ fn get_profile_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1187,6 +1200,7 @@ fn get_profile_for_organization() {
         "example@tari.com",
         TransferMessageType::Email,
         user2.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1322,6 +1336,7 @@ fn get_profile_for_organization() {
         "example@tari.com",
         TransferMessageType::Email,
         user4.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1351,6 +1366,7 @@ fn get_profile_for_organization() {
         "example@tari.com",
         TransferMessageType::Email,
         user5.id,
+        &TestProject::wallet_encryption_keys(),
         connection,
     )
     .unwrap();
@@ -1491,6 +1507,7 @@ fn get_history_for_organization() {
             ticket_type_id: ticket_type.id,
             quantity: 10,
             redemption_code: None,
+            seat_ids: None,
         }],
         false,
         false,
@@ -1542,6 +1559,7 @@ fn get_history_for_organization() {
                 ticket_type_id: ticket_type.id,
                 quantity: 1,
                 redemption_code: None,
+                seat_ids: None,
             }],
             false,
             false,
@@ -1696,7 +1714,7 @@ fn payment_method() {
     let project = TestProject::new();
     let user = project.create_user().finish();
     assert!(user
-        .payment_method(PaymentProviders::External, project.get_connection())
+        .payment_method(PaymentProviders::External, None, project.get_connection())
         .is_err());
 
     let payment_method = project
@@ -1706,7 +1724,47 @@ fn payment_method() {
         .finish();
     assert_eq!(
         payment_method,
-        user.payment_method(payment_method.name.clone(), project.get_connection())
+        user.payment_method(payment_method.name.clone(), None, project.get_connection())
+            .unwrap(),
+    );
+}
+
+#[test]
+fn payment_method_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+
+    let payment_method = project
+        .create_payment_method()
+        .with_name(PaymentProviders::Braintree)
+        .with_user(&user)
+        .with_organization(&organization)
+        .finish();
+    assert_eq!(
+        payment_method,
+        user.payment_method(PaymentProviders::Braintree, Some(organization.id), connection)
+            .unwrap(),
+    );
+    assert!(user
+        .payment_method(PaymentProviders::Braintree, Some(organization2.id), connection)
+        .is_err());
+    assert!(user
+        .payment_method(PaymentProviders::Braintree, None, connection)
+        .is_err());
+
+    // Each organization keeps its own payment method for the processor
+    let payment_method2 = project
+        .create_payment_method()
+        .with_name(PaymentProviders::Braintree)
+        .with_user(&user)
+        .with_organization(&organization2)
+        .finish();
+    assert_eq!(
+        payment_method2,
+        user.payment_method(PaymentProviders::Braintree, Some(organization2.id), connection)
             .unwrap(),
     );
 }
@@ -1924,6 +1982,7 @@ fn create_from_external_login() {
         access_token.to_string(),
         vec!["email".to_string()],
         None,
+        &TestProject::wallet_encryption_keys(),
         project.get_connection(),
     )
     .unwrap();
@@ -2439,3 +2498,154 @@ fn add_role() {
     let user2 = User::find(user.id, project.get_connection()).unwrap();
     assert_eq!(user2.role, vec![Roles::User, Roles::Admin]);
 }
+
+#[test]
+fn activate_mfa() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let key_ring = TestProject::mfa_encryption_keys();
+    let user = project.create_user().finish();
+    assert!(!user.mfa_enabled());
+
+    // Enrollment must be started first
+    let result = user.activate_mfa("123456", &key_ring, connection);
+    assert_eq!(result.unwrap_err().error_code, ErrorCode::BusinessProcessError);
+
+    let user = user.begin_mfa_enrollment(&key_ring, connection).unwrap();
+    assert!(user.mfa_secret(&key_ring).unwrap().is_some());
+    assert!(!user.mfa_enabled());
+
+    let result = user.activate_mfa("000000", &key_ring, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("code"));
+                assert_eq!(errors["code"].len(), 1);
+                assert_eq!(errors["code"][0].code, "invalid");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let code = totp::code_at(&user.mfa_secret(&key_ring).unwrap().unwrap(), Utc::now().timestamp()).unwrap();
+    let (user, recovery_codes) = user.activate_mfa(&code, &key_ring, connection).unwrap();
+    assert!(user.mfa_enabled());
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(
+        UserMfaRecoveryCode::find_unused_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        10
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserMfaEnabled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Enrollment can not be restarted while enabled
+    assert!(user.begin_mfa_enrollment(&key_ring, connection).is_err());
+}
+
+#[test]
+fn verify_mfa_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let key_ring = TestProject::mfa_encryption_keys();
+    let user = project.create_user().finish();
+    assert!(!user.verify_mfa_code("123456", &key_ring, connection).unwrap());
+
+    let user = user.begin_mfa_enrollment(&key_ring, connection).unwrap();
+    let secret = user.mfa_secret(&key_ring).unwrap().unwrap();
+    let code = totp::code_at(&secret, Utc::now().timestamp()).unwrap();
+    // Not enabled until activated
+    assert!(!user.verify_mfa_code(&code, &key_ring, connection).unwrap());
+
+    let (user, recovery_codes) = user.activate_mfa(&code, &key_ring, connection).unwrap();
+    // The code used for activation can not be used again
+    assert!(!user.verify_mfa_code(&code, &key_ring, connection).unwrap());
+    assert!(!user.verify_mfa_code("000000", &key_ring, connection).unwrap());
+
+    // Codes are only accepted once within the skew window
+    let code = totp::code_at(&secret, Utc::now().timestamp() + totp::STEP_SECONDS).unwrap();
+    assert!(user.verify_mfa_code(&code, &key_ring, connection).unwrap());
+    assert!(!user.verify_mfa_code(&code, &key_ring, connection).unwrap());
+
+    // Recovery codes are single use and accepted regardless of case
+    let recovery_code = recovery_codes[0].to_lowercase();
+    assert!(user.verify_mfa_code(&recovery_code, &key_ring, connection).unwrap());
+    assert!(!user.verify_mfa_code(&recovery_code, &key_ring, connection).unwrap());
+    assert_eq!(
+        UserMfaRecoveryCode::find_unused_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        9
+    );
+}
+
+#[test]
+fn disable_mfa() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let key_ring = TestProject::mfa_encryption_keys();
+    let user = project.create_user().with_mfa(&key_ring).finish();
+    assert!(user.mfa_enabled());
+
+    assert!(user.disable_mfa("000000", &key_ring, connection).is_err());
+
+    let code = totp::code_at(
+        &user.mfa_secret(&key_ring).unwrap().unwrap(),
+        Utc::now().timestamp() + totp::STEP_SECONDS,
+    )
+    .unwrap();
+    let user = user.disable_mfa(&code, &key_ring, connection).unwrap();
+    assert!(!user.mfa_enabled());
+    assert!(user.mfa_secret(&key_ring).unwrap().is_none());
+    assert!(UserMfaRecoveryCode::find_unused_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserMfaDisabled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn reencrypt_mfa_secrets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let key_ring = TestProject::mfa_encryption_keys();
+    let user = project.create_user().with_mfa(&key_ring).finish();
+    let secret = user.mfa_secret(&key_ring).unwrap().unwrap();
+    let (stored_secret, stored_version): (Option<String>, i32) = users::table
+        .find(user.id)
+        .select((users::mfa_secret, users::mfa_secret_version))
+        .first(connection)
+        .unwrap();
+    assert_ne!(stored_secret, Some(secret.clone()));
+    assert_eq!(stored_version, 1);
+
+    assert_eq!(User::reencrypt_mfa_secrets(&key_ring, connection).unwrap(), 0);
+
+    let key_ring = KeyRing::parse("1:test-mfa-key,2:second-key").unwrap();
+    assert!(User::reencrypt_mfa_secrets(&key_ring, connection).unwrap() > 0);
+    let (stored_secret, stored_version): (Option<String>, i32) = users::table
+        .find(user.id)
+        .select((users::mfa_secret, users::mfa_secret_version))
+        .first(connection)
+        .unwrap();
+    assert_eq!(stored_version, 2);
+    assert_eq!(key_ring.decrypt(&stored_secret.unwrap(), 2).unwrap(), secret);
+}
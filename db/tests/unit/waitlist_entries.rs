@@ -0,0 +1,268 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::schema::waitlist_entries;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel;
+use diesel::prelude::*;
+
+fn sold_out_event(project: &TestProject) -> (Event, TicketType, Order, User) {
+    let event = project
+        .create_event()
+        .with_ticket_pricing()
+        .with_a_specific_number_of_tickets(2)
+        .finish();
+    let ticket_type = event
+        .ticket_types(true, None, project.get_connection())
+        .unwrap()
+        .remove(0);
+    let buyer = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_user(&buyer)
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    (event, ticket_type, order, buyer)
+}
+
+fn refund_tickets(order: &mut Order, user: &User, quantity: usize, project: &TestProject) {
+    let connection = project.get_connection();
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let refund_items: Vec<RefundItemRequest> = TicketInstance::find_for_order_item(order_item.id, connection)
+        .unwrap()
+        .iter()
+        .filter(|t| t.status == TicketInstanceStatus::Purchased)
+        .take(quantity)
+        .map(|t| RefundItemRequest {
+            order_item_id: order_item.id,
+            ticket_instance_id: Some(t.id),
+        })
+        .collect();
+    order.refund(&refund_items, user.id, None, false, connection).unwrap();
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_event, ticket_type, _order, _buyer) = sold_out_event(&project);
+    let user = project.create_user().finish();
+
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 2)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(waitlist_entry.ticket_type_id, ticket_type.id);
+    assert_eq!(waitlist_entry.user_id, user.id);
+    assert_eq!(waitlist_entry.quantity, 2);
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Waiting);
+    assert!(waitlist_entry.hold_id.is_none());
+
+    // Checks for tickets freed by expired carts
+    let action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(action.scheduled_at > Utc::now().naive_utc());
+}
+
+#[test]
+fn commit_validations() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+
+    // Tickets are still available
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 1).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("ticket_type_id")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let (_event, ticket_type, _order, _buyer) = sold_out_event(&project);
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 0).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("quantity")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Already waiting
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 1).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => assert!(errors.contains_key("ticket_type_id")),
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn refund_schedules_processing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_event, ticket_type, mut order, buyer) = sold_out_event(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    refund_tickets(&mut order, &buyer, 1, &project);
+    let action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(action.scheduled_at <= Utc::now().naive_utc());
+}
+
+#[test]
+fn process() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_event, ticket_type, mut order, buyer) = sold_out_event(&project);
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let waitlist_entry = WaitlistEntry::create(ticket_type.id, user.id, 2)
+        .commit(connection)
+        .unwrap();
+    let waitlist_entry2 = WaitlistEntry::create(ticket_type.id, user2.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    // The first in line needs two tickets and is not skipped for the smaller request
+    refund_tickets(&mut order, &buyer, 1, &project);
+    assert!(WaitlistEntry::process(ticket_type.id, connection).unwrap().is_empty());
+
+    refund_tickets(&mut order, &buyer, 1, &project);
+    let offers = WaitlistEntry::process(ticket_type.id, connection).unwrap();
+    assert_eq!(offers.len(), 1);
+    let offer = &offers[0];
+    assert_eq!(offer.id, waitlist_entry.id);
+    assert_eq!(offer.status, WaitlistEntryStatus::Offered);
+    assert!(offer.offered_at.is_some());
+
+    let hold = offer.hold(connection).unwrap().unwrap();
+    assert_eq!(hold.ticket_type_id, ticket_type.id);
+    assert_eq!(hold.hold_type, HoldTypes::Discount);
+    assert_eq!(hold.discount_in_cents, Some(0));
+    assert_eq!(hold.max_per_user, Some(2));
+    assert_eq!(hold.email, user.email);
+    assert_eq!(hold.end_at, offer.offer_expires_at);
+    assert!(hold.redemption_code.is_some());
+    assert_eq!(hold.quantity(connection).unwrap(), (2, 2));
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 0);
+
+    assert_eq!(
+        WaitlistEntry::find(waitlist_entry2.id, connection).unwrap().status,
+        WaitlistEntryStatus::Waiting
+    );
+    // Someone is still waiting so the next check is scheduled
+    let action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap();
+    assert!(action.is_some());
+}
+
+#[test]
+fn process_expires_offers() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_event, ticket_type, mut order, buyer) = sold_out_event(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    refund_tickets(&mut order, &buyer, 1, &project);
+    let offer = WaitlistEntry::process(ticket_type.id, connection).unwrap().remove(0);
+
+    diesel::update(waitlist_entries::table.filter(waitlist_entries::id.eq(offer.id)))
+        .set(waitlist_entries::offer_expires_at.eq(Some(Utc::now().naive_utc() - Duration::minutes(1))))
+        .execute(connection)
+        .unwrap();
+    WaitlistEntry::process(ticket_type.id, connection).unwrap();
+    assert_eq!(
+        WaitlistEntry::find(offer.id, connection).unwrap().status,
+        WaitlistEntryStatus::Expired
+    );
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (_event, ticket_type, mut order, buyer) = sold_out_event(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    refund_tickets(&mut order, &buyer, 1, &project);
+    let offer = WaitlistEntry::process(ticket_type.id, connection).unwrap().remove(0);
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 0);
+
+    // Held tickets are released
+    let waitlist_entry = offer.cancel(Some(user.id), connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Cancelled);
+    assert_eq!(
+        offer.hold(connection).unwrap().unwrap().quantity(connection).unwrap(),
+        (0, 0)
+    );
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+    assert!(WaitlistEntry::find_active(ticket_type.id, user.id, connection)
+        .unwrap()
+        .is_none());
+
+    assert!(waitlist_entry.cancel(Some(user.id), connection).is_err());
+}
+
+#[test]
+fn mark_purchased() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (event, ticket_type, mut order, buyer) = sold_out_event(&project);
+    let user = project.create_user().finish();
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    refund_tickets(&mut order, &buyer, 1, &project);
+    let offer = WaitlistEntry::process(ticket_type.id, connection).unwrap().remove(0);
+    let hold = offer.hold(connection).unwrap().unwrap();
+
+    let waitlist_order = project
+        .create_order()
+        .for_user(&user)
+        .for_event(&event)
+        .quantity(1)
+        .with_redemption_code(hold.redemption_code.clone().unwrap())
+        .is_paid()
+        .finish();
+    let waitlist_entry = WaitlistEntry::find(offer.id, connection).unwrap();
+    assert_eq!(waitlist_entry.status, WaitlistEntryStatus::Purchased);
+    assert_eq!(waitlist_entry.order_id, Some(waitlist_order.id));
+    assert!(waitlist_entry.purchased_at.is_some());
+}
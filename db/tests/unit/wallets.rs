@@ -0,0 +1,48 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::encryption::KeyRing;
+
+#[test]
+fn reencrypt_secret_keys() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let wallet = Wallet::find_default_for_user(user.id, connection).unwrap();
+    let key_ring = TestProject::wallet_encryption_keys();
+    let secret_key = wallet.secret_key(&key_ring).unwrap();
+    assert_eq!(wallet.secret_key_version, 1);
+    assert!(wallet.secret_key(&KeyRing::default()).is_err());
+
+    assert!(Wallet::reencrypt_secret_keys(&KeyRing::default(), connection).is_err());
+    Wallet::reencrypt_secret_keys(&key_ring, connection).unwrap();
+    // Already encrypted with the current key
+    assert_eq!(Wallet::reencrypt_secret_keys(&key_ring, connection).unwrap(), 0);
+
+    let key_ring = KeyRing::parse("1:test-wallet-key,2:second-key").unwrap();
+    assert!(Wallet::reencrypt_secret_keys(&key_ring, connection).unwrap() > 0);
+    let wallet = Wallet::find(wallet.id, connection).unwrap();
+    assert_eq!(wallet.secret_key_version, 2);
+    assert_eq!(wallet.secret_key(&key_ring).unwrap(), secret_key);
+    assert!(wallet.secret_key(&TestProject::wallet_encryption_keys()).is_err());
+}
+
+#[test]
+fn key_ring() {
+    let key_ring = KeyRing::parse("2:second-key, 1:first-key").unwrap();
+    assert_eq!(key_ring.current_version(), Some(2));
+    let (ciphertext, version) = key_ring.encrypt("secret").unwrap();
+    assert_eq!(version, 2);
+    assert_ne!(ciphertext, "secret");
+    assert_eq!(key_ring.decrypt(&ciphertext, version).unwrap(), "secret");
+    assert!(key_ring.decrypt(&ciphertext, 1).is_err());
+    assert!(key_ring.decrypt(&ciphertext, 3).is_err());
+
+    // Nothing is stored unencrypted when no keys are configured
+    let key_ring = KeyRing::parse("").unwrap();
+    assert_eq!(key_ring.current_version(), None);
+    assert!(key_ring.encrypt("secret").is_err());
+
+    assert!(KeyRing::parse("first-key").is_err());
+    assert!(KeyRing::parse("0:first-key").is_err());
+    assert!(KeyRing::parse("1:").is_err());
+}
@@ -0,0 +1,170 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(
+        domain_event_publisher.id,
+        None,
+        json!({"webhook_event_type": "user_created"}),
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(webhook_delivery.domain_event_publisher_id, domain_event_publisher.id);
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(webhook_delivery.attempt_count, 0);
+    assert_eq!(webhook_delivery.payload, json!({"webhook_event_type": "user_created"}));
+    assert!(!domain_event_publisher.webhook_secret.is_empty());
+}
+
+#[test]
+fn queue() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    let domain_action = webhook_delivery.queue(connection).unwrap();
+    assert_eq!(domain_action.domain_action_type, DomainActionTypes::SendWebhook);
+    assert_eq!(domain_action.main_table, Some(Tables::WebhookDeliveries));
+    assert_eq!(domain_action.main_table_id, Some(webhook_delivery.id));
+}
+
+#[test]
+fn mark_delivered() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    let webhook_delivery = webhook_delivery
+        .mark_delivered(200, Some("OK".to_string()), connection)
+        .unwrap();
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Delivered);
+    assert_eq!(webhook_delivery.attempt_count, 1);
+    assert_eq!(webhook_delivery.response_status, Some(200));
+    assert_eq!(webhook_delivery.response_body, Some("OK".to_string()));
+    assert!(webhook_delivery.last_attempted_at.is_some());
+    assert!(webhook_delivery.next_attempt_at.is_none());
+}
+
+#[test]
+fn mark_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let mut webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    let now = Utc::now().naive_utc();
+    webhook_delivery = webhook_delivery
+        .mark_failed(Some(500), Some("Internal Server Error".to_string()), connection)
+        .unwrap();
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(webhook_delivery.attempt_count, 1);
+    assert_eq!(webhook_delivery.response_status, Some(500));
+    let next_attempt_at = webhook_delivery.next_attempt_at.unwrap();
+    assert!(next_attempt_at >= now + Duration::minutes(1));
+    assert!(next_attempt_at < now + Duration::minutes(2));
+
+    // Retry is scheduled for the next attempt
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::WebhookDeliveries),
+        Some(webhook_delivery.id),
+        DomainActionTypes::SendWebhook,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert_eq!(domain_actions[0].scheduled_at, next_attempt_at);
+
+    for _ in 1..WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+        webhook_delivery = webhook_delivery.mark_failed(None, None, connection).unwrap();
+    }
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Failed);
+    assert_eq!(webhook_delivery.attempt_count, WEBHOOK_DELIVERY_MAX_ATTEMPTS);
+    assert!(webhook_delivery.next_attempt_at.is_none());
+}
+
+#[test]
+fn retry_delay() {
+    assert_eq!(WebhookDelivery::retry_delay(1), Duration::minutes(1));
+    assert_eq!(WebhookDelivery::retry_delay(2), Duration::minutes(2));
+    assert_eq!(WebhookDelivery::retry_delay(3), Duration::minutes(4));
+    assert_eq!(WebhookDelivery::retry_delay(7), Duration::minutes(64));
+}
+
+#[test]
+fn replay() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    // Pending deliveries are already queued
+    assert!(webhook_delivery.replay(connection).is_err());
+
+    let webhook_delivery = webhook_delivery.mark_delivered(200, None, connection).unwrap();
+    let webhook_delivery = webhook_delivery.replay(connection).unwrap();
+    assert_eq!(webhook_delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(webhook_delivery.attempt_count, 0);
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::WebhookDeliveries),
+        Some(webhook_delivery.id),
+        DomainActionTypes::SendWebhook,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+}
+
+#[test]
+fn find_by_domain_event_publisher_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    let domain_event_publisher2 = project.create_domain_event_publisher().finish();
+    let webhook_delivery = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+    let webhook_delivery2 = WebhookDelivery::create(domain_event_publisher.id, None, json!({}))
+        .commit(connection)
+        .unwrap()
+        .mark_delivered(200, None, connection)
+        .unwrap();
+    WebhookDelivery::create(domain_event_publisher2.id, None, json!({}))
+        .commit(connection)
+        .unwrap();
+
+    let result =
+        WebhookDelivery::find_by_domain_event_publisher_id(domain_event_publisher.id, None, 0, 100, connection)
+            .unwrap();
+    assert_eq!(result.paging.total, 2);
+    assert!(result.data.contains(&webhook_delivery));
+    assert!(result.data.contains(&webhook_delivery2));
+
+    let result = WebhookDelivery::find_by_domain_event_publisher_id(
+        domain_event_publisher.id,
+        Some(WebhookDeliveryStatus::Delivered),
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result.data, vec![webhook_delivery2]);
+}
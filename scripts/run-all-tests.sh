@@ -25,6 +25,8 @@ export BLOCK_EXTERNAL_COMMS=1
 export TWILIO_ACCOUNT_ID=" "
 export TWILIO_API_KEY=" "
 export API_KEYS_ENCRYPTION_KEY="test_key"
+export WALLET_ENCRYPTION_KEYS="1:test_key"
+export MFA_ENCRYPTION_KEYS="1:test_mfa_key"
 export GLOBEE_API_KEY="GDFOzMkPAw79a8TCAHKkiknJB6bEYgbb"
 export GLOBEE_BASE_URL="https://test.globee.com/payment-api/v1/"
 export IPN_BASE_URL="TEST"
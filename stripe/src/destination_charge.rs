@@ -0,0 +1,6 @@
+/// Routes a charge to a connected account, keeping `application_fee_amount` on the platform
+#[derive(Clone, Debug, PartialEq)]
+pub struct DestinationCharge {
+    pub account_id: String,
+    pub application_fee_amount: i64,
+}
@@ -0,0 +1,17 @@
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Dispute {
+    pub id: String,
+    pub amount: i64,
+    pub currency: String,
+    pub charge: String,
+    pub payment_intent: Option<String>,
+    pub reason: String,
+    pub status: String,
+}
+
+impl Dispute {
+    /// The dispute was closed in the merchant's favour and the funds were returned
+    pub fn is_won(&self) -> bool {
+        self.status == "won"
+    }
+}
@@ -11,12 +11,22 @@ extern crate serde_derive;
 
 pub use self::charge_result::ChargeResult;
 pub use self::customer::*;
+pub use self::destination_charge::DestinationCharge;
+pub use self::dispute::Dispute;
+pub use self::payment_intent::PaymentIntent;
 pub use self::refund_result::RefundResult;
 pub use self::stripe_client::StripeClient;
 pub use self::stripe_error::StripeError;
+pub use self::transfer_result::TransferResult;
+pub use self::webhook_event::*;
 
 mod charge_result;
 mod customer;
+mod destination_charge;
+mod dispute;
+mod payment_intent;
 mod refund_result;
 mod stripe_client;
 mod stripe_error;
+mod transfer_result;
+mod webhook_event;
@@ -0,0 +1,49 @@
+use crate::StripeError;
+use reqwest;
+use serde_json;
+
+pub struct PaymentIntent {
+    pub id: String,
+    pub status: String,
+    /// Used by the client to complete any additional authentication (e.g. 3-D Secure)
+    pub client_secret: Option<String>,
+    pub raw_data: String,
+}
+
+impl PaymentIntent {
+    pub fn to_json(&self) -> String {
+        self.raw_data.clone()
+    }
+
+    /// The issuer has asked the customer to authenticate the payment
+    pub fn requires_action(&self) -> bool {
+        self.status == "requires_action" || self.status == "requires_source_action"
+    }
+
+    /// Funds are held and the payment intent can be captured
+    pub fn is_authorized(&self) -> bool {
+        self.status == "requires_capture"
+    }
+
+    /// Authentication or authorization failed and the payment intent can no longer be captured
+    pub fn has_failed(&self) -> bool {
+        self.status == "requires_payment_method" || self.status == "canceled"
+    }
+
+    pub async fn from_response(resp: reqwest::Response) -> Result<PaymentIntent, StripeError> {
+        let raw_data: String = resp.text().await?;
+        #[derive(Deserialize)]
+        struct R {
+            id: String,
+            status: String,
+            client_secret: Option<String>,
+        }
+        let result: R = serde_json::from_str(&raw_data)?;
+        Ok(PaymentIntent {
+            id: result.id,
+            status: result.status,
+            client_secret: result.client_secret,
+            raw_data,
+        })
+    }
+}
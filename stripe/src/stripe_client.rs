@@ -1,7 +1,10 @@
 use crate::ChargeResult;
 use crate::Customer;
+use crate::DestinationCharge;
+use crate::PaymentIntent;
 use crate::RefundResult;
 use crate::StripeError;
+use crate::TransferResult;
 use reqwest;
 
 #[derive(Clone)]
@@ -22,7 +25,7 @@ impl StripeClient {
         description: &str,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeResult, StripeError> {
-        self.create_charge(token, amount, currency, description, true, metadata)
+        self.create_charge(token, amount, currency, description, true, None, metadata)
             .await
     }
 
@@ -32,12 +35,14 @@ impl StripeClient {
         amount: i64,
         currency: &str,
         description: &str,
+        destination: Option<DestinationCharge>,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeResult, StripeError> {
-        self.create_charge(token, amount, currency, description, false, metadata)
+        self.create_charge(token, amount, currency, description, false, destination, metadata)
             .await
     }
 
+    /// Accepts either a charge or a payment intent id
     pub async fn update_metadata(
         &self,
         charge_id: &str,
@@ -50,7 +55,15 @@ impl StripeClient {
 
         let client = reqwest::Client::new();
         let resp = client
-            .post(&format!("https://api.stripe.com/v1/charges/{}", charge_id))
+            .post(&format!(
+                "https://api.stripe.com/v1/{}/{}",
+                if is_payment_intent(charge_id) {
+                    "payment_intents"
+                } else {
+                    "charges"
+                },
+                charge_id
+            ))
             .basic_auth(&self.api_key, Some(""))
             .form(&params)
             .send()
@@ -70,10 +83,11 @@ impl StripeClient {
         currency: &str,
         description: &str,
         capture: bool,
+        destination: Option<DestinationCharge>,
         metadata: Vec<(String, String)>,
     ) -> Result<ChargeResult, StripeError> {
         let mut params = vec![
-            ("currency".to_string(), currency.to_string()),
+            ("currency".to_string(), currency.to_lowercase()),
             ("amount".to_string(), amount.to_string()),
             ("description".to_string(), description.to_string()),
             (
@@ -87,6 +101,14 @@ impl StripeClient {
             ("capture".to_string(), capture.to_string()),
         ];
 
+        if let Some(destination) = destination {
+            params.push(("transfer_data[destination]".to_string(), destination.account_id));
+            params.push((
+                "application_fee_amount".to_string(),
+                destination.application_fee_amount.to_string(),
+            ));
+        }
+
         for key_value in metadata {
             params.push((format!("metadata[{}]", key_value.0), key_value.1));
         }
@@ -105,8 +127,119 @@ impl StripeClient {
         }
     }
 
+    /// Creates and confirms a payment intent that is captured later with `capture_payment_intent`.
+    /// `token` may be a card token, a payment method or a customer whose default source is charged.
+    pub async fn create_payment_intent(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        destination: Option<DestinationCharge>,
+        metadata: Vec<(String, String)>,
+    ) -> Result<PaymentIntent, StripeError> {
+        let mut params = vec![
+            ("currency".to_string(), currency.to_lowercase()),
+            ("amount".to_string(), amount.to_string()),
+            ("description".to_string(), description.to_string()),
+            ("capture_method".to_string(), "manual".to_string()),
+            ("confirm".to_string(), "true".to_string()),
+        ];
+
+        if token.starts_with("cus_") {
+            params.push(("customer".to_string(), token.to_string()));
+        } else if token.starts_with("pm_") {
+            params.push(("payment_method".to_string(), token.to_string()));
+        } else {
+            params.push(("payment_method_data[type]".to_string(), "card".to_string()));
+            params.push(("payment_method_data[card][token]".to_string(), token.to_string()));
+        }
+
+        if let Some(destination) = destination {
+            params.push(("transfer_data[destination]".to_string(), destination.account_id));
+            params.push((
+                "application_fee_amount".to_string(),
+                destination.application_fee_amount.to_string(),
+            ));
+        }
+
+        for key_value in metadata {
+            params.push((format!("metadata[{}]", key_value.0), key_value.1));
+        }
+        let client = reqwest::Client::new();
+        let resp = client
+            .post("https://api.stripe.com/v1/payment_intents")
+            .basic_auth(&self.api_key, Some(""))
+            .form(&params)
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    pub async fn find_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    pub async fn capture_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}/capture",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    /// Releases the funds held by a payment intent that has not been captured
+    pub async fn cancel_payment_intent(&self, payment_intent_id: &str) -> Result<PaymentIntent, StripeError> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .post(&format!(
+                "https://api.stripe.com/v1/payment_intents/{}/cancel",
+                payment_intent_id
+            ))
+            .basic_auth(&self.api_key, Some(""))
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return PaymentIntent::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    /// Refunds the whole charge. Destination charges also reverse the transfer to the connected
+    /// account and refund the application fee so the platform does not fund the refund.
     pub async fn refund(&self, charge_id: &str) -> Result<RefundResult, StripeError> {
-        let params = vec![("charge".to_string(), charge_id.to_string())];
+        let params = refund_params(charge_id, None, self.is_destination_charge(charge_id).await?);
 
         let client = reqwest::Client::new();
         let resp = client
@@ -124,10 +257,7 @@ impl StripeClient {
     }
 
     pub async fn partial_refund(&self, charge_id: &str, amount: i64) -> Result<RefundResult, StripeError> {
-        let params = vec![
-            ("charge".to_string(), charge_id.to_string()),
-            ("amount".to_string(), amount.to_string()),
-        ];
+        let params = refund_params(charge_id, Some(amount), self.is_destination_charge(charge_id).await?);
 
         let client = reqwest::Client::new();
         let resp = client
@@ -145,10 +275,7 @@ impl StripeClient {
     }
 
     pub fn partial_refund_blocking(&self, charge_id: &str, amount: i64) -> Result<RefundResult, StripeError> {
-        let params = vec![
-            ("charge".to_string(), charge_id.to_string()),
-            ("amount".to_string(), amount.to_string()),
-        ];
+        let params = refund_params(charge_id, Some(amount), self.is_destination_charge_blocking(charge_id)?);
 
         let client = reqwest::blocking::Client::new();
         let resp = client
@@ -164,6 +291,32 @@ impl StripeClient {
         }
     }
 
+    /// Whether the charge or payment intent sent its funds to a connected account
+    async fn is_destination_charge(&self, charge_id: &str) -> Result<bool, StripeError> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(&charge_url(charge_id))
+            .basic_auth(&self.api_key, Some(""))
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => has_transfer_destination(&resp.text().await?),
+            _ => Err(StripeError::from_response(resp).await),
+        }
+    }
+
+    fn is_destination_charge_blocking(&self, charge_id: &str) -> Result<bool, StripeError> {
+        let client = reqwest::blocking::Client::new();
+        let resp = client
+            .get(&charge_url(charge_id))
+            .basic_auth(&self.api_key, Some(""))
+            .send()?;
+        match resp.status() {
+            reqwest::StatusCode::OK => has_transfer_destination(&resp.text()?),
+            _ => Err(StripeError::from_response_blocking(resp)),
+        }
+    }
+
     pub async fn complete(&self, charge_id: &str) -> Result<ChargeResult, StripeError> {
         let client = reqwest::Client::new();
 
@@ -238,4 +391,140 @@ impl StripeClient {
             _ => return Err(StripeError::from_response(resp).await),
         }
     }
+
+    /// Transfers funds from the platform balance to a connected account.
+    /// Requests sharing an `idempotency_key` create at most one transfer.
+    pub async fn create_transfer(
+        &self,
+        destination: &str,
+        idempotency_key: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        transfer_group: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<TransferResult, StripeError> {
+        let mut params = vec![
+            ("amount".to_string(), amount.to_string()),
+            ("currency".to_string(), currency.to_lowercase()),
+            ("destination".to_string(), destination.to_string()),
+            ("description".to_string(), description.to_string()),
+            ("transfer_group".to_string(), transfer_group.to_string()),
+        ];
+
+        for key_value in metadata {
+            params.push((format!("metadata[{}]", key_value.0), key_value.1));
+        }
+        let client = reqwest::Client::new();
+        let resp = client
+            .post("https://api.stripe.com/v1/transfers")
+            .basic_auth(&self.api_key, Some(""))
+            .header("Idempotency-Key", idempotency_key)
+            .form(&params)
+            .send()
+            .await?;
+        match resp.status() {
+            reqwest::StatusCode::OK => {
+                return TransferResult::from_response(resp).await;
+            }
+            _ => return Err(StripeError::from_response(resp).await),
+        }
+    }
+}
+
+fn is_payment_intent(id: &str) -> bool {
+    id.starts_with("pi_")
+}
+
+fn charge_url(id: &str) -> String {
+    format!(
+        "https://api.stripe.com/v1/{}/{}",
+        if is_payment_intent(id) {
+            "payment_intents"
+        } else {
+            "charges"
+        },
+        id
+    )
+}
+
+fn has_transfer_destination(raw_data: &str) -> Result<bool, StripeError> {
+    #[derive(Deserialize)]
+    struct TransferData {
+        destination: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct R {
+        transfer_data: Option<TransferData>,
+    }
+    let result: R = serde_json::from_str(raw_data)?;
+    Ok(result.transfer_data.and_then(|t| t.destination).is_some())
+}
+
+/// Refunds are made against either a payment intent or a legacy charge. Stripe reverses the transfer
+/// and refunds the application fee of destination charges in proportion to the amount refunded.
+fn refund_params(id: &str, amount: Option<i64>, destination_charge: bool) -> Vec<(String, String)> {
+    let mut params = vec![if is_payment_intent(id) {
+        ("payment_intent".to_string(), id.to_string())
+    } else {
+        ("charge".to_string(), id.to_string())
+    }];
+    if let Some(amount) = amount {
+        params.push(("amount".to_string(), amount.to_string()));
+    }
+    if destination_charge {
+        params.push(("reverse_transfer".to_string(), "true".to_string()));
+        params.push(("refund_application_fee".to_string(), "true".to_string()));
+    }
+    params
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn refund_params_for_platform_charge() {
+        assert_eq!(
+            refund_params("ch_123", None, false),
+            vec![("charge".to_string(), "ch_123".to_string())]
+        );
+        assert_eq!(
+            refund_params("pi_123", Some(500), false),
+            vec![
+                ("payment_intent".to_string(), "pi_123".to_string()),
+                ("amount".to_string(), "500".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn refund_params_for_destination_charge() {
+        assert_eq!(
+            refund_params("pi_123", None, true),
+            vec![
+                ("payment_intent".to_string(), "pi_123".to_string()),
+                ("reverse_transfer".to_string(), "true".to_string()),
+                ("refund_application_fee".to_string(), "true".to_string()),
+            ]
+        );
+        assert_eq!(
+            refund_params("ch_123", Some(500), true),
+            vec![
+                ("charge".to_string(), "ch_123".to_string()),
+                ("amount".to_string(), "500".to_string()),
+                ("reverse_transfer".to_string(), "true".to_string()),
+                ("refund_application_fee".to_string(), "true".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn has_transfer_destination() {
+        assert!(
+            super::has_transfer_destination(r#"{"id":"pi_123","transfer_data":{"destination":"acct_123"}}"#).unwrap()
+        );
+        assert!(!super::has_transfer_destination(r#"{"id":"pi_123","transfer_data":null}"#).unwrap());
+        assert!(!super::has_transfer_destination(r#"{"id":"ch_123"}"#).unwrap());
+    }
 }
@@ -0,0 +1,38 @@
+use crate::StripeError;
+use serde::de::DeserializeOwned;
+use serde_json;
+use serde_json::Value;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub data: WebhookEventData,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookEventData {
+    pub object: Value,
+}
+
+impl WebhookEvent {
+    pub fn from_str(payload: &str) -> Result<WebhookEvent, StripeError> {
+        Ok(serde_json::from_str(payload)?)
+    }
+
+    /// Id of the object the event is about, e.g. the payment intent id for `payment_intent.*` events
+    pub fn object_id(&self) -> Option<&str> {
+        self.object_field("id")
+    }
+
+    /// String field of the object the event is about, e.g. the `payment_intent` of a refunded charge
+    pub fn object_field(&self, field: &str) -> Option<&str> {
+        self.data.object.get(field).and_then(|value| value.as_str())
+    }
+
+    /// Deserializes the object the event is about, e.g. a `Dispute` for `charge.dispute.*` events
+    pub fn object<T: DeserializeOwned>(&self) -> Result<T, StripeError> {
+        Ok(serde_json::from_value(self.data.object.clone())?)
+    }
+}